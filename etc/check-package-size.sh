@@ -27,6 +27,7 @@ indent cargo diet -n --package-size-limit 25KB
 (enter git-traverse && indent cargo diet -n --package-size-limit 5KB)
 (enter git-url && indent cargo diet -n --package-size-limit 7KB)
 (enter git-validate && indent cargo diet -n --package-size-limit 5KB)
+(enter git-refspec && indent cargo diet -n --package-size-limit 5KB)
 (enter git-object && indent cargo diet -n --package-size-limit 20KB)
 (enter git-commitgraph && indent cargo diet -n --package-size-limit 15KB)
 (enter git-pack && indent cargo diet -n --package-size-limit 65KB)
@@ -0,0 +1,10 @@
+#![no_main]
+
+use git_actor::{immutable, Signature};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(signature) = immutable::Signature::from_bytes::<()>(data) {
+        let _a = Signature::from(signature).write_to(Vec::new());
+    }
+});
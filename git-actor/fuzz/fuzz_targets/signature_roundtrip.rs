@@ -0,0 +1,12 @@
+#![no_main]
+
+use git_actor::{immutable, Signature};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|signature: Signature| {
+    let mut buf = Vec::new();
+    if signature.write_to(&mut buf).is_ok() {
+        let parsed = immutable::Signature::from_bytes::<()>(&buf).expect("serialized signatures can be parsed");
+        assert_eq!(Signature::from(parsed), signature);
+    }
+});
@@ -0,0 +1,167 @@
+//! Resolve the identity of authors and committers from the environment and git configuration.
+use crate::{Sign, Signature, Time};
+use bstr::{BString, ByteSlice};
+use git_config::file::GitConfig;
+use quick_error::quick_error;
+use std::{convert::TryFrom, ffi::OsString, fmt};
+
+/// The role an actor has when creating an object, determining which environment variables and configuration keys are consulted.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Role {
+    /// The actor who wrote the change, as configured by `GIT_AUTHOR_*` or `author.*`.
+    Author,
+    /// The actor who committed or tagged the change, as configured by `GIT_COMMITTER_*` or `committer.*`.
+    Committer,
+}
+
+impl Role {
+    fn env_prefix(&self) -> &'static str {
+        match self {
+            Role::Author => "GIT_AUTHOR",
+            Role::Committer => "GIT_COMMITTER",
+        }
+    }
+
+    fn config_section(&self) -> &'static str {
+        match self {
+            Role::Author => "author",
+            Role::Committer => "committer",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.config_section())
+    }
+}
+
+quick_error! {
+    /// The error returned by [`Signature::from_env_or_config()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        MissingName(role: Role) {
+            display("The {} name is unknown. Set {}_NAME, or configure 'user.name' or '{}.name'", role, role.env_prefix(), role)
+        }
+        MissingEmail(role: Role) {
+            display("The {} email is unknown. Set {}_EMAIL or EMAIL, or configure 'user.email' or '{}.email'", role, role.env_prefix(), role)
+        }
+        InvalidDate(role: Role, date: BString) {
+            display("The {} date {:?} in {}_DATE must be formatted as '[@]<seconds since epoch> <+|-><HHMM>' or 'YYYY-MM-DD HH:MM:SS <+|-><HHMM>'", role, date, role.env_prefix())
+        }
+    }
+}
+
+impl Signature {
+    /// Resolve the signature of an actor in the given `role` from the environment, falling back to `config`.
+    ///
+    /// The name is taken from `GIT_<ROLE>_NAME`, `<role>.name` or `user.name`, and the email from `GIT_<ROLE>_EMAIL`,
+    /// `<role>.email`, `user.email` or `EMAIL`, in that order. The time is parsed from `GIT_<ROLE>_DATE` in git's
+    /// internal format or as `YYYY-MM-DD HH:MM:SS <+|-><HHMM>`, and defaults to now in UTC.
+    pub fn from_env_or_config(role: Role, config: &GitConfig<'_>) -> Result<Self, Error> {
+        let var = |suffix: &str| non_empty(std::env::var_os(format!("{}_{}", role.env_prefix(), suffix)));
+        let config_value = |section: &str, key: &str| {
+            config
+                .get_raw_value(section, None, key)
+                .ok()
+                .map(|v| BString::from(git_config::values::normalize_cow(v).into_owned()))
+                .filter(|v| !v.is_empty())
+        };
+
+        let name = var("NAME")
+            .or_else(|| config_value(role.config_section(), "name"))
+            .or_else(|| config_value("user", "name"))
+            .ok_or(Error::MissingName(role))?;
+        let email = var("EMAIL")
+            .or_else(|| config_value(role.config_section(), "email"))
+            .or_else(|| config_value("user", "email"))
+            .or_else(|| non_empty(std::env::var_os("EMAIL")))
+            .ok_or(Error::MissingEmail(role))?;
+        let time = match var("DATE") {
+            Some(date) => parse_time(date.as_ref()).ok_or(Error::InvalidDate(role, date))?,
+            None => now(),
+        };
+        Ok(Signature { name, email, time })
+    }
+}
+
+fn non_empty(value: Option<OsString>) -> Option<BString> {
+    value
+        .and_then(|v| v.into_string().ok())
+        .map(|v| v.trim().to_owned())
+        .filter(|v| !v.is_empty())
+        .map(Into::into)
+}
+
+fn now() -> Time {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    Time {
+        time: seconds as u32,
+        offset: 0,
+        sign: Sign::Plus,
+    }
+}
+
+/// Parse `[@]<seconds> <+|-><HHMM>` as used in git's own serialization of dates, or `YYYY-MM-DD[T ]HH:MM:SS <+|-><HHMM>`
+/// as the ISO 8601 like format also accepted by git, with the time being local to the given offset.
+fn parse_time(date: &[u8]) -> Option<Time> {
+    let tokens: Vec<_> = date.fields_with(|c| c.is_ascii_whitespace()).collect();
+    let (local_time, tz) = match tokens.as_slice() {
+        [date_time, tz] => match date_time.find_byte(b'T') {
+            Some(pos) => (parse_date_time(&date_time[..pos], &date_time[pos + 1..])?, *tz),
+            None => {
+                let seconds = date_time.strip_prefix(b"@").unwrap_or(date_time);
+                let time = btoi::btoi::<u32>(seconds).ok()?;
+                return parse_offset(tz).map(|(offset, sign)| Time { time, offset, sign });
+            }
+        },
+        [date, time, tz] => (parse_date_time(date, time)?, *tz),
+        _ => return None,
+    };
+    let (offset, sign) = parse_offset(tz)?;
+    let time = u32::try_from(local_time - offset as i64).ok()?;
+    Some(Time { time, offset, sign })
+}
+
+/// Parse `<+|-><HHMM>` into the offset in seconds and its sign.
+fn parse_offset(tz: &[u8]) -> Option<(i32, Sign)> {
+    if tz.len() != 5 || !tz[1..].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let sign = match tz[0] {
+        b'+' => Sign::Plus,
+        b'-' => Sign::Minus,
+        _ => return None,
+    };
+    let hours = btoi::btoi::<i32>(&tz[1..3]).ok()?;
+    let minutes = btoi::btoi::<i32>(&tz[3..]).ok()?;
+    let offset = (hours * 3600 + minutes * 60) * if sign == Sign::Minus { -1 } else { 1 };
+    Some((offset, sign))
+}
+
+/// Parse `YYYY-MM-DD` and `HH:MM:SS` into the seconds since the unix epoch, ignoring time zones.
+fn parse_date_time(date: &[u8], time: &[u8]) -> Option<i64> {
+    fn numbers<'a>(input: &'a [u8], separator: u8) -> impl Iterator<Item = Option<i64>> + 'a {
+        input.split(move |b| *b == separator).map(|n| btoi::btoi::<i64>(n).ok())
+    }
+    let (year, month, day) = match numbers(date, b'-').collect::<Option<Vec<_>>>()?.as_slice() {
+        [year, month, day] if (1..=12).contains(month) && (1..=31).contains(day) => (*year, *month, *day),
+        _ => return None,
+    };
+    let (hours, minutes, seconds) = match numbers(time, b':').collect::<Option<Vec<_>>>()?.as_slice() {
+        [hours, minutes, seconds] if *hours < 24 && *minutes < 60 && *seconds <= 60 => (*hours, *minutes, *seconds),
+        _ => return None,
+    };
+    // Days since the epoch in the proleptic gregorian calendar, see http://howardhinnant.github.io/date_algorithms.html
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Some(days * 86_400 + hours * 3600 + minutes * 60 + seconds)
+}
@@ -3,6 +3,8 @@
 #![deny(rust_2018_idioms, missing_docs)]
 use bstr::BString;
 
+#[cfg(feature = "identity")]
+pub mod identity;
 pub mod immutable;
 mod signature;
 
@@ -25,6 +27,7 @@ pub struct Signature {
 /// Indicates if a number is positive or negative for use in [`Time`].
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[allow(missing_docs)]
 pub enum Sign {
     Plus,
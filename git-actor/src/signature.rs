@@ -13,6 +13,22 @@ mod convert {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary {
+    use crate::{Signature, Time};
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    impl<'a> Arbitrary<'a> for Signature {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(Signature {
+                name: Vec::<u8>::arbitrary(u)?.into(),
+                email: Vec::<u8>::arbitrary(u)?.into(),
+                time: Time::arbitrary(u)?,
+            })
+        }
+    }
+}
+
 mod write {
     use crate::{Signature, SPACE};
     use bstr::{BStr, ByteSlice};
@@ -3,7 +3,20 @@ use std::io;
 
 impl Time {
     /// Serialize this instance to `out` in a format suitable for use in header fields of serialized git commits or tags.
+    ///
+    /// Fails if the `offset` can't be represented as `HHMM`, i.e. if it is 100 hours or more.
     pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
+        const SECONDS_PER_HOUR: u32 = 60 * 60;
+        let offset = self.offset.unsigned_abs();
+        let hours = offset / SECONDS_PER_HOUR;
+        if hours > 99 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("offset of {} hours can't be represented with two digits", hours),
+            ));
+        }
+        let minutes = (offset - (hours * SECONDS_PER_HOUR)) / 60;
+
         itoa::write(&mut out, self.time)?;
         out.write_all(SPACE)?;
         out.write_all(&[match self.sign {
@@ -12,13 +25,6 @@ impl Time {
         }])?;
 
         const ZERO: &[u8; 1] = b"0";
-
-        const SECONDS_PER_HOUR: i32 = 60 * 60;
-        let offset = self.offset.abs();
-        let hours = offset / SECONDS_PER_HOUR;
-        assert!(hours < 25, "offset is more than a day: {}", hours);
-        let minutes = (offset - (hours * SECONDS_PER_HOUR)) / 60;
-
         if hours < 10 {
             out.write_all(ZERO)?;
         }
@@ -30,3 +36,21 @@ impl Time {
         itoa::write(&mut out, minutes).map(|_| ())
     }
 }
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Time {
+    /// Produce times with offsets that can be serialized, which are whole minutes of less than 100 hours in either direction.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        const MAX_OFFSET_IN_MINUTES: i32 = 99 * 60 + 59;
+        let offset = u.int_in_range(-MAX_OFFSET_IN_MINUTES..=MAX_OFFSET_IN_MINUTES)? * 60;
+        Ok(Time {
+            time: u.arbitrary()?,
+            offset,
+            sign: match offset {
+                0 => u.arbitrary()?,
+                offset if offset < 0 => Sign::Minus,
+                _ => Sign::Plus,
+            },
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use git_actor::{
+    identity::{Error, Role},
+    Sign, Signature, Time,
+};
+use git_config::file::GitConfig;
+use std::convert::TryFrom;
+
+const VARS: &[&str] = &[
+    "GIT_AUTHOR_NAME",
+    "GIT_AUTHOR_EMAIL",
+    "GIT_AUTHOR_DATE",
+    "GIT_COMMITTER_NAME",
+    "GIT_COMMITTER_EMAIL",
+    "GIT_COMMITTER_DATE",
+    "EMAIL",
+];
+
+// All assertions live in one test as they depend on the process environment.
+#[test]
+fn from_env_or_config() -> Result<(), Box<dyn std::error::Error>> {
+    for var in VARS {
+        std::env::remove_var(var);
+    }
+
+    let empty = GitConfig::try_from("")?;
+    assert!(matches!(
+        Signature::from_env_or_config(Role::Author, &empty),
+        Err(Error::MissingName(Role::Author))
+    ));
+
+    let config = GitConfig::try_from(
+        "[user]\n\tname = User Name\n\temail = user@example.com\n[committer]\n\tname = Committer Name\n",
+    )?;
+    let author = Signature::from_env_or_config(Role::Author, &config)?;
+    assert_eq!(author.name, "User Name");
+    assert_eq!(author.email, "user@example.com");
+    let committer = Signature::from_env_or_config(Role::Committer, &config)?;
+    assert_eq!(committer.name, "Committer Name", "role specific configuration wins");
+    assert_eq!(committer.email, "user@example.com");
+
+    let only_name = GitConfig::try_from("[user]\n\tname = User Name\n")?;
+    assert!(matches!(
+        Signature::from_env_or_config(Role::Committer, &only_name),
+        Err(Error::MissingEmail(Role::Committer))
+    ));
+    std::env::set_var("EMAIL", "fallback@example.com");
+    assert_eq!(
+        Signature::from_env_or_config(Role::Committer, &only_name)?.email,
+        "fallback@example.com"
+    );
+
+    std::env::set_var("GIT_AUTHOR_NAME", "Env Name");
+    std::env::set_var("GIT_AUTHOR_EMAIL", "env@example.com");
+    std::env::set_var("GIT_AUTHOR_DATE", "@1528473343 -0230");
+    assert_eq!(
+        Signature::from_env_or_config(Role::Author, &config)?,
+        Signature {
+            name: "Env Name".into(),
+            email: "env@example.com".into(),
+            time: Time {
+                time: 1528473343,
+                offset: -9000,
+                sign: Sign::Minus,
+            }
+        },
+        "the environment takes precedence"
+    );
+
+    let iso_time = Time {
+        time: 1599613563,
+        offset: 8 * 3600,
+        sign: Sign::Plus,
+    };
+    for date in &["2020-09-09 09:06:03 +0800", "2020-09-09T09:06:03 +0800"] {
+        std::env::set_var("GIT_AUTHOR_DATE", date);
+        assert_eq!(
+            Signature::from_env_or_config(Role::Author, &config)?.time,
+            iso_time,
+            "ISO 8601 like dates are local to their offset"
+        );
+    }
+
+    for date in &["yesterday", "2020-13-09 09:06:03 +0800", "2020-09-09 09:06 +0800"] {
+        std::env::set_var("GIT_AUTHOR_DATE", date);
+        assert!(matches!(
+            Signature::from_env_or_config(Role::Author, &config),
+            Err(Error::InvalidDate(Role::Author, _))
+        ));
+    }
+
+    for var in VARS {
+        std::env::remove_var(var);
+    }
+    Ok(())
+}
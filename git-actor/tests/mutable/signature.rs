@@ -36,6 +36,30 @@ mod time {
         }
         Ok(())
     }
+
+    #[test]
+    fn write_to_fails_on_offsets_which_do_not_fit_two_digit_hours() {
+        for offset in &[100 * 3600, -100 * 3600, i32::MIN] {
+            let time = Time {
+                time: 0,
+                offset: *offset,
+                sign: Sign::Plus,
+            };
+            assert_eq!(
+                time.write_to(Vec::new()).unwrap_err().kind(),
+                std::io::ErrorKind::InvalidInput
+            );
+        }
+        let mut output = Vec::new();
+        Time {
+            time: 0,
+            offset: 99 * 3600 + 59 * 60,
+            sign: Sign::Plus,
+        }
+        .write_to(&mut output)
+        .expect("largest possible offset");
+        assert_eq!(output.as_bstr(), "0 +9959");
+    }
 }
 
 mod signature {
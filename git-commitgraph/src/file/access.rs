@@ -1,4 +1,8 @@
-use crate::file::{self, commit::Commit, File, COMMIT_DATA_ENTRY_SIZE};
+use crate::file::{
+    self,
+    commit::{self, Commit, ParentIterator},
+    File, COMMIT_DATA_ENTRY_SIZE,
+};
 use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::{
     convert::TryInto,
@@ -24,6 +28,25 @@ impl File {
         Commit::new(self, pos)
     }
 
+    /// Returns the generation number of the commit at the given lexigraphical position, without decoding any other commit data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn generation_at(&self, pos: file::Position) -> u32 {
+        commit::generation_from_bytes(self.commit_data_bytes(pos))
+    }
+
+    /// Returns a non-allocating iterator over the parent positions of the commit at the given lexigraphical position,
+    /// without decoding any other commit data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn iter_parents_at(&self, pos: file::Position) -> ParentIterator<'_> {
+        ParentIterator::new(self, pos)
+    }
+
     /// The kind of hash used in this File.
     ///
     /// Note that it is always conforming to the hash used in the owning repository.
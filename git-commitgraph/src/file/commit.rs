@@ -27,10 +27,11 @@ pub enum Error {
 
 // Note that git's commit-graph-format.txt as of v2.28.0 gives an incorrect value 0x0700_0000 for
 // NO_PARENT. Fixed in https://github.com/git/git/commit/4d515253afcef985e94400adbfed7044959f9121 .
-const NO_PARENT: u32 = 0x7000_0000;
-const EXTENDED_EDGES_MASK: u32 = 0x8000_0000;
+pub(crate) const NO_PARENT: u32 = 0x7000_0000;
+pub(crate) const EXTENDED_EDGES_MASK: u32 = 0x8000_0000;
 
 /// A commit as stored in a [`File`].
+#[derive(Clone, Copy)]
 pub struct Commit<'a> {
     file: &'a File,
     pos: file::Position,
@@ -51,7 +52,7 @@ impl<'a> Commit<'a> {
             root_tree_id: git_hash::oid::try_from(&bytes[..SHA1_SIZE]).expect("20 bytes SHA1 to be alright"),
             parent1: ParentEdge::from_raw(BigEndian::read_u32(&bytes[SHA1_SIZE..SHA1_SIZE + 4])),
             parent2: ParentEdge::from_raw(BigEndian::read_u32(&bytes[SHA1_SIZE + 4..SHA1_SIZE + 8])),
-            generation: BigEndian::read_u32(&bytes[SHA1_SIZE + 8..SHA1_SIZE + 12]) >> 2,
+            generation: generation_from_bytes(bytes),
             commit_timestamp: BigEndian::read_u64(&bytes[SHA1_SIZE + 8..SHA1_SIZE + 16]) & 0x0003_ffff_ffff,
         }
     }
@@ -72,12 +73,17 @@ impl<'a> Commit<'a> {
     }
 
     /// Returns an iterator over the parent positions for lookup in the owning [Graph][crate::Graph].
-    pub fn iter_parents(&'a self) -> impl Iterator<Item = Result<graph::Position, Error>> + 'a {
+    ///
+    /// The iterator doesn't allocate and doesn't borrow `self`, so it can outlive this instance.
+    pub fn iter_parents(&self) -> ParentIterator<'a> {
         // I didn't find a combinator approach that a) was as strict as ParentIterator, b) supported
         // fuse-after-first-error behavior, and b) was significantly shorter or more understandable
         // than ParentIterator. So here we are.
         ParentIterator {
-            commit_data: self,
+            file: self.file,
+            pos: self.pos,
+            parent1: self.parent1,
+            parent2: self.parent2,
             state: ParentIteratorState::First,
         }
     }
@@ -126,36 +132,59 @@ impl<'a> PartialEq for Commit<'a> {
     }
 }
 
+/// Returns the generation number stored in the commit data `bytes` of a single commit.
+pub(crate) fn generation_from_bytes(bytes: &[u8]) -> u32 {
+    BigEndian::read_u32(&bytes[SHA1_SIZE + 8..SHA1_SIZE + 12]) >> 2
+}
+
 /// An iterator over parents of a [`Commit`].
 pub struct ParentIterator<'a> {
-    commit_data: &'a Commit<'a>,
+    file: &'a File,
+    pos: file::Position,
+    parent1: ParentEdge,
+    parent2: ParentEdge,
     state: ParentIteratorState<'a>,
 }
 
+impl<'a> ParentIterator<'a> {
+    pub(crate) fn new(file: &'a File, pos: file::Position) -> Self {
+        let bytes = file.commit_data_bytes(pos);
+        ParentIterator {
+            file,
+            pos,
+            parent1: ParentEdge::from_raw(BigEndian::read_u32(&bytes[SHA1_SIZE..SHA1_SIZE + 4])),
+            parent2: ParentEdge::from_raw(BigEndian::read_u32(&bytes[SHA1_SIZE + 4..SHA1_SIZE + 8])),
+            state: ParentIteratorState::First,
+        }
+    }
+
+    fn id(&self) -> git_hash::ObjectId {
+        self.file.id_at(self.pos).into()
+    }
+}
+
 impl<'a> Iterator for ParentIterator<'a> {
     type Item = Result<graph::Position, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let state = std::mem::replace(&mut self.state, ParentIteratorState::Exhausted);
         match state {
-            ParentIteratorState::First => match self.commit_data.parent1 {
-                ParentEdge::None => match self.commit_data.parent2 {
+            ParentIteratorState::First => match self.parent1 {
+                ParentEdge::None => match self.parent2 {
                     ParentEdge::None => None,
-                    _ => Some(Err(Error::SecondParentWithoutFirstParent(self.commit_data.id().into()))),
+                    _ => Some(Err(Error::SecondParentWithoutFirstParent(self.id()))),
                 },
                 ParentEdge::GraphPosition(pos) => {
                     self.state = ParentIteratorState::Second;
                     Some(Ok(pos))
                 }
-                ParentEdge::ExtraEdgeIndex(_) => {
-                    Some(Err(Error::FirstParentIsExtraEdgeIndex(self.commit_data.id().into())))
-                }
+                ParentEdge::ExtraEdgeIndex(_) => Some(Err(Error::FirstParentIsExtraEdgeIndex(self.id()))),
             },
-            ParentIteratorState::Second => match self.commit_data.parent2 {
+            ParentIteratorState::Second => match self.parent2 {
                 ParentEdge::None => None,
                 ParentEdge::GraphPosition(pos) => Some(Ok(pos)),
                 ParentEdge::ExtraEdgeIndex(extra_edge_index) => {
-                    if let Some(extra_edges_list) = self.commit_data.file.extra_edges_data() {
+                    if let Some(extra_edges_list) = self.file.extra_edges_data() {
                         let start_offset: usize = extra_edge_index
                             .try_into()
                             .expect("an architecture able to hold 32 bits of integer");
@@ -168,10 +197,10 @@ impl<'a> Iterator for ParentIterator<'a> {
                             // with a std::iter::from_fn closure.
                             self.next()
                         } else {
-                            Some(Err(Error::ExtraEdgesListOverflow(self.commit_data.id().into())))
+                            Some(Err(Error::ExtraEdgesListOverflow(self.id())))
                         }
                     } else {
-                        Some(Err(Error::MissingExtraEdgesList(self.commit_data.id().into())))
+                        Some(Err(Error::MissingExtraEdgesList(self.id())))
                     }
                 }
             },
@@ -186,7 +215,7 @@ impl<'a> Iterator for ParentIterator<'a> {
                         ExtraEdge::Last(pos) => Some(Ok(pos)),
                     }
                 } else {
-                    Some(Err(Error::ExtraEdgesListOverflow(self.commit_data.id().into())))
+                    Some(Err(Error::ExtraEdgesListOverflow(self.id())))
                 }
             }
             ParentIteratorState::Exhausted => None,
@@ -194,7 +223,7 @@ impl<'a> Iterator for ParentIterator<'a> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        match (&self.state, self.commit_data.parent1, self.commit_data.parent2) {
+        match (&self.state, self.parent1, self.parent2) {
             (ParentIteratorState::First, ParentEdge::None, ParentEdge::None) => (0, Some(0)),
             (ParentIteratorState::First, ParentEdge::None, _) => (1, Some(1)),
             (ParentIteratorState::First, ParentEdge::GraphPosition(_), ParentEdge::None) => (1, Some(1)),
@@ -238,7 +267,7 @@ impl ParentEdge {
     }
 }
 
-const LAST_EXTENDED_EDGE_MASK: u32 = 0x8000_0000;
+pub(crate) const LAST_EXTENDED_EDGE_MASK: u32 = 0x8000_0000;
 
 enum ExtraEdge {
     Internal(graph::Position),
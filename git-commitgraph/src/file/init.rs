@@ -1,4 +1,4 @@
-use crate::file::{File, COMMIT_DATA_ENTRY_SIZE, FAN_LEN, SIGNATURE};
+use crate::file::{Data, File, COMMIT_DATA_ENTRY_SIZE, FAN_LEN, SIGNATURE};
 use bstr::ByteSlice;
 use byteorder::{BigEndian, ByteOrder};
 use filebuffer::FileBuffer;
@@ -6,10 +6,10 @@ use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::{
     convert::{TryFrom, TryInto},
     ops::Range,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-type ChunkId = [u8; 4];
+pub(crate) type ChunkId = [u8; 4];
 
 /// The error used in [`File::at()`].
 #[derive(thiserror::Error, Debug)]
@@ -49,8 +49,8 @@ pub enum Error {
     UnsupportedVersion(u8),
 }
 
-const CHUNK_LOOKUP_SIZE: usize = 12;
-const HEADER_LEN: usize = 8;
+pub(crate) const CHUNK_LOOKUP_SIZE: usize = 12;
+pub(crate) const HEADER_LEN: usize = 8;
 const TRAILER_LEN: usize = SHA1_SIZE;
 const MIN_FILE_SIZE: usize = HEADER_LEN + ((MIN_CHUNKS + 1) * CHUNK_LOOKUP_SIZE) + TRAILER_LEN;
 const OID_LOOKUP_ENTRY_SIZE: usize = SHA1_SIZE;
@@ -58,11 +58,11 @@ const OID_LOOKUP_ENTRY_SIZE: usize = SHA1_SIZE;
 // Required chunks: OIDF, OIDL, CDAT
 const MIN_CHUNKS: usize = 3;
 const BASE_GRAPHS_LIST_CHUNK_ID: ChunkId = *b"BASE";
-const COMMIT_DATA_CHUNK_ID: ChunkId = *b"CDAT";
-const EXTENDED_EDGES_LIST_CHUNK_ID: ChunkId = *b"EDGE";
-const OID_FAN_CHUNK_ID: ChunkId = *b"OIDF";
-const OID_LOOKUP_CHUNK_ID: ChunkId = *b"OIDL";
-const SENTINEL_CHUNK_ID: ChunkId = [0u8; 4];
+pub(crate) const COMMIT_DATA_CHUNK_ID: ChunkId = *b"CDAT";
+pub(crate) const EXTENDED_EDGES_LIST_CHUNK_ID: ChunkId = *b"EDGE";
+pub(crate) const OID_FAN_CHUNK_ID: ChunkId = *b"OIDF";
+pub(crate) const OID_LOOKUP_CHUNK_ID: ChunkId = *b"OIDL";
+pub(crate) const SENTINEL_CHUNK_ID: ChunkId = [0u8; 4];
 
 impl File {
     /// Try to parse the commit graph file at `path`.
@@ -79,6 +79,13 @@ impl TryFrom<&Path> for File {
             err: e,
             path: path.to_owned(),
         })?;
+        File::from_data(Data::Mapped(data), path.to_owned())
+    }
+}
+
+impl File {
+    /// Parse a commit graph from `data`, remembering `path` as its origin.
+    pub(crate) fn from_data(data: Data, path: PathBuf) -> Result<File, Error> {
         let data_size = data.len();
         if data_size < MIN_FILE_SIZE {
             return Err(Error::Corrupt(
@@ -314,7 +321,7 @@ impl TryFrom<&Path> for File {
             extra_edges_list_range,
             fan,
             oid_lookup_offset,
-            path: path.to_owned(),
+            path,
         })
     }
 }
@@ -7,6 +7,7 @@ pub use commit::Commit;
 
 mod init;
 pub mod verify;
+pub(crate) mod write;
 
 pub use init::Error;
 
@@ -14,7 +15,7 @@ use filebuffer::FileBuffer;
 use git_hash::SIZE_OF_SHA1_DIGEST as SHA1_SIZE;
 use std::{
     fmt::{Display, Formatter},
-    ops::Range,
+    ops::{Deref, Range},
     path::PathBuf,
 };
 
@@ -30,13 +31,30 @@ pub struct File {
     base_graph_count: u8,
     base_graphs_list_offset: Option<usize>,
     commit_data_offset: usize,
-    data: FileBuffer,
+    data: Data,
     extra_edges_list_range: Option<Range<usize>>,
     fan: [u32; FAN_LEN],
     oid_lookup_offset: usize,
     path: PathBuf,
 }
 
+/// The bytes of a [`File`], either memory-mapped from disk or held in memory if the graph was created on the fly.
+pub(crate) enum Data {
+    Mapped(FileBuffer),
+    InMemory(Vec<u8>),
+}
+
+impl Deref for Data {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Data::Mapped(buf) => buf,
+            Data::InMemory(buf) => buf,
+        }
+    }
+}
+
 /// The position of a given commit within a graph file, starting at 0.
 ///
 /// Commits within a graph file are sorted in lexicographical order by OID; a commit's lexigraphical position
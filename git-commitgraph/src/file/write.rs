@@ -0,0 +1,104 @@
+use crate::file::{
+    commit::{EXTENDED_EDGES_MASK, LAST_EXTENDED_EDGE_MASK, NO_PARENT},
+    init::{
+        ChunkId, CHUNK_LOOKUP_SIZE, COMMIT_DATA_CHUNK_ID, EXTENDED_EDGES_LIST_CHUNK_ID, HEADER_LEN, OID_FAN_CHUNK_ID,
+        OID_LOOKUP_CHUNK_ID, SENTINEL_CHUNK_ID,
+    },
+    COMMIT_DATA_ENTRY_SIZE, FAN_LEN, SIGNATURE,
+};
+use byteorder::{BigEndian, ByteOrder};
+use git_hash::{ObjectId, SIZE_OF_SHA1_DIGEST as SHA1_SIZE};
+use std::convert::TryInto;
+
+/// A commit to be written into a commit-graph file.
+pub(crate) struct Entry {
+    pub id: ObjectId,
+    pub root_tree_id: ObjectId,
+    /// The lexigraphical positions of all parents in the order they appear in the commit.
+    pub parents: Vec<u32>,
+    pub generation: u32,
+    pub commit_timestamp: u64,
+}
+
+/// Serialize `entries`, which must be sorted by id, into a single commit-graph file without base graphs.
+pub(crate) fn to_bytes(entries: &[Entry]) -> Vec<u8> {
+    let mut extra_edges = Vec::new();
+    let mut commit_data = Vec::with_capacity(entries.len() * COMMIT_DATA_ENTRY_SIZE);
+    for entry in entries {
+        commit_data.extend_from_slice(entry.root_tree_id.as_slice());
+        let (parent1, parent2) = match entry.parents.as_slice() {
+            [] => (NO_PARENT, NO_PARENT),
+            [p1] => (*p1, NO_PARENT),
+            [p1, p2] => (*p1, *p2),
+            [p1, rest @ ..] => {
+                let index: u32 = extra_edges
+                    .len()
+                    .try_into()
+                    .expect("extra edge index to fit in 31 bits");
+                extra_edges.extend_from_slice(rest);
+                *extra_edges.last_mut().expect("at least two more parents") |= LAST_EXTENDED_EDGE_MASK;
+                (*p1, EXTENDED_EDGES_MASK | index)
+            }
+        };
+        write_u32(&mut commit_data, parent1);
+        write_u32(&mut commit_data, parent2);
+        write_u32(
+            &mut commit_data,
+            (entry.generation << 2) | ((entry.commit_timestamp >> 32) & 0x3) as u32,
+        );
+        write_u32(&mut commit_data, entry.commit_timestamp as u32);
+    }
+
+    let mut fan = [0u32; FAN_LEN];
+    for entry in entries {
+        fan[usize::from(entry.id.as_slice()[0])] += 1;
+    }
+    for idx in 1..FAN_LEN {
+        fan[idx] += fan[idx - 1];
+    }
+
+    let mut chunks: Vec<(ChunkId, usize)> = vec![
+        (OID_FAN_CHUNK_ID, 4 * FAN_LEN),
+        (OID_LOOKUP_CHUNK_ID, entries.len() * SHA1_SIZE),
+        (COMMIT_DATA_CHUNK_ID, commit_data.len()),
+    ];
+    if !extra_edges.is_empty() {
+        chunks.push((EXTENDED_EDGES_LIST_CHUNK_ID, extra_edges.len() * 4));
+    }
+
+    let chunk_data_len: usize = chunks.iter().map(|(_, size)| size).sum();
+    let mut out = Vec::with_capacity(HEADER_LEN + (chunks.len() + 1) * CHUNK_LOOKUP_SIZE + chunk_data_len + SHA1_SIZE);
+    out.extend_from_slice(SIGNATURE);
+    out.extend_from_slice(&[1, 1, chunks.len().try_into().expect("few chunks"), 0]);
+
+    let mut chunk_offset = HEADER_LEN + (chunks.len() + 1) * CHUNK_LOOKUP_SIZE;
+    for (id, size) in chunks.iter().copied().chain(std::iter::once((SENTINEL_CHUNK_ID, 0))) {
+        out.extend_from_slice(&id);
+        let mut offset = [0u8; 8];
+        BigEndian::write_u64(&mut offset, chunk_offset as u64);
+        out.extend_from_slice(&offset);
+        chunk_offset += size;
+    }
+
+    for count in fan.iter() {
+        write_u32(&mut out, *count);
+    }
+    for entry in entries {
+        out.extend_from_slice(entry.id.as_slice());
+    }
+    out.extend_from_slice(&commit_data);
+    for edge in extra_edges {
+        write_u32(&mut out, edge);
+    }
+
+    let mut hasher = git_features::hash::Sha1::default();
+    hasher.update(&out);
+    out.extend_from_slice(&hasher.digest());
+    out
+}
+
+fn write_u32(out: &mut Vec<u8>, value: u32) {
+    let mut buf = [0u8; 4];
+    BigEndian::write_u32(&mut buf, value);
+    out.extend_from_slice(&buf);
+}
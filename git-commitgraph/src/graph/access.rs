@@ -1,5 +1,5 @@
 use crate::{
-    file::{self, Commit, File},
+    file::{self, commit::ParentIterator, Commit, File},
     graph::{self, Graph},
 };
 
@@ -20,6 +20,24 @@ impl Graph {
         Some(r.file.commit_at(r.file_pos))
     }
 
+    /// Returns the generation number of the commit at the given position `pos`, which is faster than decoding the entire commit.
+    ///
+    /// # Panics
+    /// If `pos` is greater or equal to [`num_commits()`][Graph::num_commits()].
+    pub fn generation_at(&self, pos: graph::Position) -> u32 {
+        let r = self.lookup_by_pos(pos);
+        r.file.generation_at(r.pos)
+    }
+
+    /// Returns a non-allocating iterator over the parent positions of the commit at the given position `pos`.
+    ///
+    /// # Panics
+    /// If `pos` is greater or equal to [`num_commits()`][Graph::num_commits()].
+    pub fn iter_parents_at(&self, pos: graph::Position) -> ParentIterator<'_> {
+        let r = self.lookup_by_pos(pos);
+        r.file.iter_parents_at(r.pos)
+    }
+
     /// Returns the `hash` at the given position `pos`.
     ///
     /// # Panics
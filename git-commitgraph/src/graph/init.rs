@@ -1,9 +1,12 @@
 use crate::{
     file::{self, File},
-    Graph, MAX_COMMITS,
+    Graph, GENERATION_NUMBER_MAX, MAX_COMMITS,
 };
+use git_hash::{oid, ObjectId};
+use git_object::immutable;
 use std::{
-    convert::TryFrom,
+    collections::BTreeMap,
+    convert::{TryFrom, TryInto},
     io::{BufRead, BufReader},
     path::{Path, PathBuf},
 };
@@ -12,6 +15,16 @@ use std::{
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
 pub enum Error {
+    #[error("The commit {id} could not be found")]
+    CommitNotFound { id: ObjectId },
+    #[error("The commit {id} could not be decoded")]
+    CommitDecode {
+        #[source]
+        err: immutable::object::decode::Error,
+        id: ObjectId,
+    },
+    #[error("The commit {id} could not be decoded as it doesn't refer to a tree")]
+    CommitWithoutTree { id: ObjectId },
     #[error("{}", .path.display())]
     File {
         #[source]
@@ -87,6 +100,115 @@ impl Graph {
             .or_else(|_| Self::from_commit_graphs_dir(info_dir.as_ref().join("commit-graphs")))
     }
 
+    /// Build a commit graph in memory from all commits reachable from `tips`, for use in repositories without a commit-graph file.
+    ///
+    /// `find` looks up a commit by id, writing its data into the given buffer and returning an iterator over its tokens.
+    /// Not finding a commit is an error as all ancestors of `tips` have to be present. This is considerably slower than
+    /// reading a graph from disk, as each reachable commit has to be decoded, but allows algorithms written against
+    /// the [`Graph`] to work in any repository.
+    pub fn from_odb_traversal<Find>(
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        mut find: Find,
+    ) -> Result<Self, Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::CommitIter<'a>>,
+    {
+        struct Commit {
+            root_tree_id: ObjectId,
+            parents: Vec<ObjectId>,
+            commit_timestamp: u64,
+        }
+
+        let mut buf = Vec::new();
+        let mut commits = BTreeMap::<ObjectId, Commit>::new();
+        let mut next: Vec<ObjectId> = tips.into_iter().map(Into::into).collect();
+        while let Some(id) = next.pop() {
+            if commits.contains_key(&id) {
+                continue;
+            }
+            let iter = find(&id, &mut buf).ok_or(Error::CommitNotFound { id })?;
+            let mut root_tree_id = None;
+            let mut parents = Vec::new();
+            let mut commit_timestamp = 0;
+            for token in iter {
+                match token.map_err(|err| Error::CommitDecode { err, id })? {
+                    immutable::commit::iter::Token::Tree { id } => root_tree_id = Some(id),
+                    immutable::commit::iter::Token::Parent { id } => parents.push(id),
+                    immutable::commit::iter::Token::Committer { signature } => {
+                        commit_timestamp = u64::from(signature.time.time);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            next.extend(parents.iter().filter(|id| !commits.contains_key(*id)).cloned());
+            commits.insert(
+                id,
+                Commit {
+                    root_tree_id: root_tree_id.ok_or(Error::CommitWithoutTree { id })?,
+                    parents,
+                    commit_timestamp,
+                },
+            );
+        }
+        if commits.len() > MAX_COMMITS as usize {
+            return Err(Error::TooManyCommits(commits.len() as u64));
+        }
+
+        let ids: Vec<_> = commits.keys().collect();
+        let position = |id: &ObjectId| -> u32 {
+            ids.binary_search(&id)
+                .expect("all parents to be traversed")
+                .try_into()
+                .expect("number of commits to fit in 32 bits")
+        };
+        let mut entries: Vec<_> = commits
+            .iter()
+            .map(|(id, commit)| file::write::Entry {
+                id: *id,
+                root_tree_id: commit.root_tree_id,
+                parents: commit.parents.iter().map(position).collect(),
+                generation: 0,
+                commit_timestamp: commit.commit_timestamp,
+            })
+            .collect();
+
+        let mut stack = Vec::new();
+        for start in 0..entries.len() {
+            stack.push(start);
+            while let Some(&pos) = stack.last() {
+                if entries[pos].generation != 0 {
+                    stack.pop();
+                    continue;
+                }
+                let mut generation = 0;
+                let mut has_unknown_parents = false;
+                for parent in entries[pos].parents.iter().map(|p| *p as usize) {
+                    match entries[parent].generation {
+                        0 => {
+                            stack.push(parent);
+                            has_unknown_parents = true;
+                        }
+                        parent_generation => generation = generation.max(parent_generation),
+                    }
+                }
+                if !has_unknown_parents {
+                    entries[pos].generation = (generation + 1).min(GENERATION_NUMBER_MAX);
+                    stack.pop();
+                }
+            }
+        }
+
+        let file =
+            File::from_data(file::Data::InMemory(file::write::to_bytes(&entries)), PathBuf::new()).map_err(|err| {
+                Error::File {
+                    err,
+                    path: PathBuf::new(),
+                }
+            })?;
+        Self::new(vec![file])
+    }
+
     /// Create a new commit graph from a list of `files`.
     pub fn new(files: Vec<File>) -> Result<Self, Error> {
         let num_commits: u64 = files.iter().map(|f| u64::from(f.num_commits())).sum();
@@ -1,6 +1,7 @@
 //! Operations on a complete commit graph.
 mod access;
 mod init;
+pub use init::Error;
 pub mod verify;
 
 use crate::file::File;
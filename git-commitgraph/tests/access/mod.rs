@@ -68,3 +68,93 @@ fn two_parents() -> crate::Result {
 
     Ok(())
 }
+
+mod from_odb_traversal {
+    use crate::{check_common, inspect_refs, make_readonly_repo};
+    use git_commitgraph::Graph;
+    use git_odb::{linked::Store, pack, FindExt};
+    use std::path::Path;
+
+    fn in_memory_graph(
+        repo_dir: &Path,
+        tips: impl IntoIterator<Item = git_hash::ObjectId>,
+    ) -> Result<Graph, Box<dyn std::error::Error>> {
+        let db = Store::at(repo_dir.join(".git").join("objects"))?;
+        Ok(Graph::from_odb_traversal(tips, |oid, buf| {
+            db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+        })?)
+    }
+
+    #[test]
+    fn octopus_merges_match_the_graph_written_by_git() -> crate::Result {
+        let repo_dir = make_readonly_repo("octopus_merges.sh");
+        let refs = inspect_refs(
+            &repo_dir,
+            &[
+                "root",
+                "parent1",
+                "parent2",
+                "parent3",
+                "parent4",
+                "three_parents",
+                "four_parents",
+            ],
+        );
+        let cg = in_memory_graph(
+            &repo_dir,
+            vec![
+                refs["three_parents"].id().to_owned(),
+                refs["four_parents"].id().to_owned(),
+            ],
+        )?;
+        check_common(&cg, &refs);
+
+        let expected = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+        for (actual, expected) in cg.iter_commits().zip(expected.iter_commits()) {
+            assert_eq!(actual.id(), expected.id());
+            assert_eq!(actual.generation(), expected.generation());
+            assert_eq!(actual.committer_timestamp(), expected.committer_timestamp());
+        }
+        assert_eq!(cg.commit_at(refs["four_parents"].pos()).generation(), 3);
+
+        cg.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+        Ok(())
+    }
+
+    #[test]
+    fn only_commits_reachable_from_tips_are_included() -> crate::Result {
+        let repo_dir = make_readonly_repo("two_parents.sh");
+        let refs = inspect_refs(&repo_dir, &["parent1"]);
+        let cg = in_memory_graph(&repo_dir, Some(refs["parent1"].id().to_owned()))?;
+        check_common(&cg, &refs);
+        assert_eq!(cg.commit_at(refs["parent1"].pos()).generation(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_commits_are_an_error() {
+        let repo_dir = make_readonly_repo("single_commit.sh");
+        let missing = git_testtools::hex_to_id("0123456789012345678901234567890123456789");
+        assert!(matches!(
+            in_memory_graph(&repo_dir, Some(missing))
+                .err()
+                .expect("commit does not exist")
+                .downcast_ref::<git_commitgraph::graph::Error>(),
+            Some(git_commitgraph::graph::Error::CommitNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn commits_without_tree_are_a_decode_error() {
+        let id = git_testtools::hex_to_id("0123456789012345678901234567890123456789");
+        let err = Graph::from_odb_traversal(Some(id), |_oid, _buf| {
+            Some(git_object::immutable::CommitIter::from_bytes(b""))
+        })
+        .err()
+        .expect("a commit without tree can't be put into the graph");
+        assert!(
+            matches!(err, git_commitgraph::graph::Error::CommitWithoutTree { id: actual } if actual == id),
+            "the commit is named in the error"
+        );
+    }
+}
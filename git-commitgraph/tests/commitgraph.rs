@@ -51,6 +51,14 @@ pub fn check_common(cg: &Graph, expected: &HashMap<String, RefInfo, impl BuildHa
                 .expect("failed to access commit's parents"),
             expected_parents.iter().map(|x| x.pos()).collect::<Vec<_>>()
         );
+        assert_eq!(
+            cg.iter_parents_at(ref_info.pos())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .expect("failed to access commit's parents"),
+            expected_parents.iter().map(|x| x.pos()).collect::<Vec<_>>(),
+            "parents can be obtained without decoding the whole commit"
+        );
+        assert_eq!(cg.generation_at(ref_info.pos()), commit.generation());
     }
 
     assert_eq!(
@@ -59,15 +67,11 @@ pub fn check_common(cg: &Graph, expected: &HashMap<String, RefInfo, impl BuildHa
     );
 }
 
-use git_testtools::scripted_fixture_repo_read_only;
+use git_testtools::{hex_to_id, scripted_fixture_repo_read_only};
 pub fn make_readonly_repo(script_path: &str) -> std::path::PathBuf {
     scripted_fixture_repo_read_only(script_path).expect("script succeeds all the time")
 }
 
-pub fn hex_to_id(hex: &[u8]) -> git_hash::ObjectId {
-    git_hash::ObjectId::from_hex(hex).expect("40 bytes hex")
-}
-
 pub struct RefInfo {
     id: git_hash::ObjectId,
     parent_ids: Vec<git_hash::ObjectId>,
@@ -113,12 +117,9 @@ pub fn inspect_refs(repo_dir: impl AsRef<Path>, refs: &[&'static str]) -> HashMa
             let parts = x.trim_end().split(' ').collect::<Vec<_>>();
             (
                 parts[0].to_string(),
-                git_hash::ObjectId::from_hex(parts[1].as_bytes()).expect("40 bytes hex"),
-                git_hash::ObjectId::from_hex(parts[2].as_bytes()).expect("40 bytes hex"),
-                parts[3..]
-                    .iter()
-                    .map(|x| git_hash::ObjectId::from_hex(x.as_bytes()).expect("40 bytes hex"))
-                    .collect(),
+                hex_to_id(parts[1]),
+                hex_to_id(parts[2]),
+                parts[3..].iter().map(|x| hex_to_id(x)).collect(),
             )
         })
         .collect();
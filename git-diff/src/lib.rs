@@ -4,3 +4,6 @@
 
 ///
 pub mod tree;
+
+///
+pub mod merge;
@@ -0,0 +1,285 @@
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BString, ByteVec},
+    immutable, mutable,
+    tree::EntryMode,
+};
+use std::collections::BTreeMap;
+
+/// The error returned by [`trees()`], [`cherry_pick()`] and [`revert()`].
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum Error<E> {
+    NotFound { oid: ObjectId, kind: git_object::Kind },
+    Write(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NotFound { oid, kind } => write!(f, "The {} {} could not be found", kind, oid),
+            Error::Write(err) => write!(f, "A merged tree could not be written: {}", err),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::NotFound { .. } => None,
+            Error::Write(err) => Some(err),
+        }
+    }
+}
+
+/// The mode and object of a tree entry as seen by one side of a merge.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub struct Version {
+    /// The kind of object `oid` is pointing to.
+    pub mode: EntryMode,
+    /// The id of the object.
+    pub oid: ObjectId,
+}
+
+/// A path that was changed differently by both sides, or which was changed by one side and deleted by the other.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Conflict {
+    /// The path of the entry relative to the root of the merged trees, with `/` as separator.
+    pub path: BString,
+    /// The entry in the merge base, or `None` if it didn't exist there.
+    pub base: Option<Version>,
+    /// The entry on our side, or `None` if it was deleted or never existed.
+    pub ours: Option<Version>,
+    /// The entry on their side, or `None` if it was deleted or never existed.
+    pub theirs: Option<Version>,
+}
+
+/// The outcome of [`trees()`], [`cherry_pick()`] and [`revert()`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Outcome {
+    /// The id of the merged tree, which is fully written.
+    ///
+    /// Conflicting paths are represented by the version on our side if present, or by their version otherwise.
+    pub tree: ObjectId,
+    /// All paths that could not be merged, in the order they were encountered. The merge is clean if this is empty.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Outcome {
+    /// Returns true if no path conflicted and the resulting tree is the complete merge result.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merge the changes from `base` to `theirs` into `ours`, returning the merged tree along with all conflicts.
+///
+/// * `base` is the tree both sides are derived from, or `None` to use the empty tree.
+/// * `find` is a function `f(object_id, &mut buffer) -> Option<Object>` to obtain trees (and commits in case of
+///   [`cherry_pick()`] and [`revert()`]) backed by the given buffer.
+/// * `write` stores the given tree and returns its id. It is called for each tree that didn't exist in any of the inputs.
+///
+/// # Notes
+///
+/// * The merge is performed on tree entries only and never looks into blobs, hence a path changed by both sides is a
+///   conflict even if the changes don't overlap, unless both sides made the very same change.
+/// * No rename tracking is performed.
+/// * Nothing is written to a worktree or an index, which makes it suitable for use in bare repositories.
+pub fn trees<Find, Write, E>(
+    base: Option<&oid>,
+    ours: &oid,
+    theirs: &oid,
+    mut find: Find,
+    mut write: Write,
+) -> Result<Outcome, Error<E>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::Object<'a>>,
+    Write: FnMut(&mutable::Tree) -> Result<ObjectId, E>,
+{
+    let mut state = State {
+        buf: Vec::new(),
+        path: BString::default(),
+        conflicts: Vec::new(),
+    };
+    let tree = match state.merge(
+        base.map(ToOwned::to_owned),
+        Some(ours.to_owned()),
+        Some(theirs.to_owned()),
+        &mut find,
+        &mut write,
+    )? {
+        Some(tree) => tree,
+        None => write(&mutable::Tree { entries: Vec::new() }).map_err(Error::Write)?,
+    };
+    Ok(Outcome {
+        tree,
+        conflicts: state.conflicts,
+    })
+}
+
+/// Apply the changes introduced by `commit` onto the tree of the commit `onto`, similar to `git cherry-pick`.
+///
+/// The changes are those between the first parent of `commit` and `commit` itself. Root commits are considered to add all their files.
+/// See [`trees()`] for details on `find` and `write`.
+pub fn cherry_pick<Find, Write, E>(commit: &oid, onto: &oid, mut find: Find, write: Write) -> Result<Outcome, Error<E>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::Object<'a>>,
+    Write: FnMut(&mutable::Tree) -> Result<ObjectId, E>,
+{
+    let (tree, parent_tree, onto_tree) = lookup(commit, onto, &mut find)?;
+    trees(parent_tree.as_deref(), &onto_tree, &tree, find, write)
+}
+
+/// Undo the changes introduced by `commit` in the tree of the commit `onto`, similar to `git revert`.
+///
+/// The changes are those between the first parent of `commit` and `commit` itself. Reverting a root commit removes all of its files.
+/// See [`trees()`] for details on `find` and `write`.
+pub fn revert<Find, Write, E>(commit: &oid, onto: &oid, mut find: Find, mut write: Write) -> Result<Outcome, Error<E>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::Object<'a>>,
+    Write: FnMut(&mutable::Tree) -> Result<ObjectId, E>,
+{
+    let (tree, parent_tree, onto_tree) = lookup(commit, onto, &mut find)?;
+    let parent_tree = match parent_tree {
+        Some(tree) => tree,
+        None => write(&mutable::Tree { entries: Vec::new() }).map_err(Error::Write)?,
+    };
+    trees(Some(&tree), &onto_tree, &parent_tree, find, write)
+}
+
+/// Return the tree of `commit`, the tree of its first parent if there is one, and the tree of `onto`.
+fn lookup<Find, E>(
+    commit: &oid,
+    onto: &oid,
+    find: &mut Find,
+) -> Result<(ObjectId, Option<ObjectId>, ObjectId), Error<E>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::Object<'a>>,
+{
+    let mut buf = Vec::new();
+    let mut commit_by_id = |id: &oid| {
+        find(id, &mut buf)
+            .and_then(|obj| obj.into_commit())
+            .map(|c| (c.tree(), c.parents().next()))
+            .ok_or_else(|| Error::NotFound {
+                oid: id.to_owned(),
+                kind: git_object::Kind::Commit,
+            })
+    };
+    let (tree, parent) = commit_by_id(commit)?;
+    let parent_tree = parent.map(|id| commit_by_id(&id).map(|(tree, _)| tree)).transpose()?;
+    let (onto_tree, _) = commit_by_id(onto)?;
+    Ok((tree, parent_tree, onto_tree))
+}
+
+struct State {
+    buf: Vec<u8>,
+    path: BString,
+    conflicts: Vec<Conflict>,
+}
+
+impl State {
+    /// Merge the given trees and return the id of the result, or `None` if it is empty.
+    fn merge<Find, Write, E>(
+        &mut self,
+        base: Option<ObjectId>,
+        ours: Option<ObjectId>,
+        theirs: Option<ObjectId>,
+        find: &mut Find,
+        write: &mut Write,
+    ) -> Result<Option<ObjectId>, Error<E>>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::Object<'a>>,
+        Write: FnMut(&mutable::Tree) -> Result<ObjectId, E>,
+    {
+        if ours == theirs || base == theirs {
+            return Ok(ours);
+        }
+        if base == ours {
+            return Ok(theirs);
+        }
+
+        let mut entries_by_name = BTreeMap::<BString, [Option<Version>; 3]>::new();
+        for (side, tree) in [base, ours, theirs].iter().enumerate() {
+            let tree = match tree {
+                Some(tree) => tree,
+                None => continue,
+            };
+            let entries = find(tree, &mut self.buf)
+                .and_then(|obj| obj.into_tree())
+                .ok_or(Error::NotFound {
+                    oid: *tree,
+                    kind: git_object::Kind::Tree,
+                })?
+                .entries;
+            for entry in entries {
+                entries_by_name.entry(entry.filename.to_owned()).or_default()[side] = Some(Version {
+                    mode: entry.mode,
+                    oid: entry.oid.to_owned(),
+                });
+            }
+        }
+
+        let mut entries = Vec::with_capacity(entries_by_name.len());
+        for (filename, [base, ours, theirs]) in entries_by_name {
+            let merged = if ours == theirs || base == theirs {
+                ours
+            } else if base == ours {
+                theirs
+            } else {
+                match (base, ours, theirs) {
+                    (
+                        None
+                        | Some(Version {
+                            mode: EntryMode::Tree, ..
+                        }),
+                        Some(Version {
+                            mode: EntryMode::Tree,
+                            oid: ours,
+                        }),
+                        Some(Version {
+                            mode: EntryMode::Tree,
+                            oid: theirs,
+                        }),
+                    ) => {
+                        let previous_len = self.path.len();
+                        if previous_len != 0 {
+                            self.path.push(b'/');
+                        }
+                        self.path.push_str(&filename);
+                        let tree = self.merge(base.map(|b| b.oid), Some(ours), Some(theirs), find, write);
+                        self.path.truncate(previous_len);
+                        tree?.map(|oid| Version {
+                            mode: EntryMode::Tree,
+                            oid,
+                        })
+                    }
+                    _ => {
+                        let mut path = self.path.clone();
+                        if !path.is_empty() {
+                            path.push(b'/');
+                        }
+                        path.push_str(&filename);
+                        self.conflicts.push(Conflict {
+                            path,
+                            base,
+                            ours,
+                            theirs,
+                        });
+                        ours.or(theirs)
+                    }
+                }
+            };
+            if let Some(Version { mode, oid }) = merged {
+                entries.push(mutable::tree::Entry { mode, filename, oid });
+            }
+        }
+
+        if entries.is_empty() {
+            return Ok(None);
+        }
+        entries.sort();
+        let tree = mutable::Tree { entries };
+        write(&tree).map(Some).map_err(Error::Write)
+    }
+}
@@ -98,7 +98,7 @@ impl<'a> tree::Changes<'a> {
                 (Some(lhs), Some(rhs)) => {
                     use std::cmp::Ordering::*;
                     let (lhs, rhs) = (lhs?, rhs?);
-                    match lhs.filename.cmp(rhs.filename) {
+                    match compare_entries(&lhs, &rhs) {
                         Equal => handle_lhs_and_rhs_with_equal_filenames(lhs, rhs, &mut state.trees, delegate)?,
                         Less => catchup_lhs_with_rhs(&mut lhs_entries, lhs, rhs, &mut state.trees, delegate)?,
                         Greater => catchup_rhs_with_lhs(&mut rhs_entries, lhs, rhs, &mut state.trees, delegate)?,
@@ -173,7 +173,7 @@ fn catchup_rhs_with_lhs<R: tree::Visit>(
     add_entry_schedule_recursion(rhs, queue, delegate)?;
     loop {
         match rhs_entries.peek() {
-            Some(Ok(rhs)) => match lhs.filename.cmp(rhs.filename) {
+            Some(Ok(rhs)) => match compare_entries(&lhs, &rhs) {
                 Equal => {
                     let rhs = rhs_entries.next().transpose()?.expect("the peeked item tobe present");
                     delegate.pop_path_component();
@@ -213,7 +213,7 @@ fn catchup_lhs_with_rhs<R: tree::Visit>(
     delete_entry_schedule_recursion(lhs, queue, delegate)?;
     loop {
         match lhs_entries.peek() {
-            Some(Ok(lhs)) => match lhs.filename.cmp(rhs.filename) {
+            Some(Ok(lhs)) => match compare_entries(&lhs, &rhs) {
                 Equal => {
                     let lhs = lhs_entries.next().expect("the peeked item to be present")?;
                     delegate.pop_path_component();
@@ -335,3 +335,11 @@ type IteratorType<I> = std::mem::ManuallyDrop<std::iter::Peekable<I>>;
 fn peekable<I: Iterator>(iter: I) -> IteratorType<I> {
     std::mem::ManuallyDrop::new(iter.peekable())
 }
+
+/// Compare entries in tree order, but pair up entries of the same name even if only one of them is a tree to handle them together.
+fn compare_entries(lhs: &immutable::tree::Entry<'_>, rhs: &immutable::tree::Entry<'_>) -> std::cmp::Ordering {
+    if lhs.filename == rhs.filename {
+        return std::cmp::Ordering::Equal;
+    }
+    git_object::tree::compare_entries(lhs.filename, lhs.mode, rhs.filename, rhs.mode)
+}
@@ -0,0 +1,39 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+git config core.autocrlf false
+
+git checkout -q -b main
+echo a > a
+echo b > b
+mkdir dir
+echo c > dir/c
+git add .
+git commit -qm base
+
+git checkout -q -b topic
+echo a2 > a
+git commit -qam 'change a'
+git rev-parse HEAD > change-a.commit
+echo b-theirs > b
+git commit -qam 'change b'
+git rev-parse HEAD > change-b.commit
+
+git checkout -q main
+echo b-ours > b
+git commit -qam 'change b differently'
+echo d > dir/d
+git add dir/d
+git commit -qm 'add dir/d'
+git rev-parse HEAD > add-d.commit
+
+git checkout -q -b expected-pick main
+git cherry-pick "$(cat change-a.commit)" >/dev/null
+git rev-parse HEAD^{tree} > expected-pick.tree
+
+git checkout -q -b expected-revert main
+git revert --no-edit "$(cat add-d.commit)" >/dev/null
+git rev-parse HEAD^{tree} > expected-revert.tree
+git checkout -q main
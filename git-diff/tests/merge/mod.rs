@@ -0,0 +1,201 @@
+use git_diff::merge;
+use git_hash::{oid, ObjectId};
+use git_object::{bstr::ByteSlice, immutable, mutable, tree::EntryMode};
+use git_odb::{linked, pack, FindExt, Write};
+use std::path::{Path, PathBuf};
+
+fn fixture() -> crate::Result<(PathBuf, linked::Store)> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_merge_repo.sh")?;
+    let db = linked::Store::at(dir.join(".git").join("objects"))?;
+    Ok((dir, db))
+}
+
+fn id_from(dir: &Path, file: &str) -> crate::Result<ObjectId> {
+    Ok(ObjectId::from_hex(std::fs::read(dir.join(file))?.trim())?)
+}
+
+fn find<'a>(db: &linked::Store, id: &oid, buf: &'a mut Vec<u8>) -> Option<immutable::Object<'a>> {
+    db.find_existing(id, buf, &mut pack::cache::Never)
+        .ok()
+        .and_then(|obj| obj.decode().ok())
+}
+
+fn tree_of(db: &linked::Store, commit: &oid) -> ObjectId {
+    let mut buf = Vec::new();
+    let tree = find(db, commit, &mut buf)
+        .and_then(|obj| obj.into_commit())
+        .expect("commit")
+        .tree();
+    tree
+}
+
+fn write(tree: &mutable::Tree) -> Result<ObjectId, std::io::Error> {
+    git_odb::sink().write(&mutable::Object::Tree(tree.clone()), git_hash::Kind::Sha1)
+}
+
+#[test]
+fn cherry_pick_without_conflicts_matches_git() -> crate::Result {
+    let (dir, db) = fixture()?;
+    let outcome = merge::cherry_pick(
+        &id_from(&dir, "change-a.commit")?,
+        &id_from(&dir, "add-d.commit")?,
+        |id, buf| find(&db, id, buf),
+        write,
+    )?;
+    assert!(outcome.is_clean());
+    assert_eq!(outcome.tree, id_from(&dir, "expected-pick.tree")?);
+    Ok(())
+}
+
+#[test]
+fn revert_without_conflicts_matches_git() -> crate::Result {
+    let (dir, db) = fixture()?;
+    let outcome = merge::revert(
+        &id_from(&dir, "add-d.commit")?,
+        &id_from(&dir, "add-d.commit")?,
+        |id, buf| find(&db, id, buf),
+        write,
+    )?;
+    assert!(outcome.is_clean());
+    assert_eq!(outcome.tree, id_from(&dir, "expected-revert.tree")?);
+    Ok(())
+}
+
+#[test]
+fn cherry_pick_with_conflict_keeps_our_version_and_reports_all_sides() -> crate::Result {
+    let (dir, db) = fixture()?;
+    let onto = id_from(&dir, "add-d.commit")?;
+    let outcome = merge::cherry_pick(
+        &id_from(&dir, "change-b.commit")?,
+        &onto,
+        |id, buf| find(&db, id, buf),
+        write,
+    )?;
+    assert!(!outcome.is_clean());
+    assert_eq!(outcome.conflicts.len(), 1);
+    let conflict = &outcome.conflicts[0];
+    assert_eq!(conflict.path.as_bstr(), "b");
+    for version in &[conflict.base, conflict.ours, conflict.theirs] {
+        assert_eq!(version.expect("present on all sides").mode, EntryMode::Blob);
+    }
+    assert_ne!(conflict.ours, conflict.theirs);
+    assert_eq!(
+        outcome.tree,
+        tree_of(&db, &onto),
+        "our side wins in conflicts, and 'a' was changed in the base already"
+    );
+    Ok(())
+}
+
+mod nested_trees {
+    use git_diff::merge;
+    use git_hash::ObjectId;
+    use git_object::{bstr::ByteSlice, mutable, tree::EntryMode};
+    use git_odb::{linked, Write};
+
+    use crate::merge::find;
+
+    fn tree(db: &linked::Store, entries: &[(&str, EntryMode, ObjectId)]) -> crate::Result<ObjectId> {
+        let mut entries: Vec<_> = entries
+            .iter()
+            .map(|(name, mode, oid)| mutable::tree::Entry {
+                mode: *mode,
+                filename: (*name).into(),
+                oid: *oid,
+            })
+            .collect();
+        entries.sort();
+        Ok(db.write(&mutable::Object::Tree(mutable::Tree { entries }), git_hash::Kind::Sha1)?)
+    }
+
+    fn blob(db: &linked::Store, content: &str) -> crate::Result<ObjectId> {
+        Ok(db.write_buf(git_object::Kind::Blob, content.as_bytes(), git_hash::Kind::Sha1)?)
+    }
+
+    fn writable_db() -> crate::Result<(tempfile::TempDir, linked::Store)> {
+        let dir = git_testtools::scripted_fixture_repo_writable("make_merge_repo.sh")?;
+        let db = linked::Store::at(dir.path().join(".git").join("objects"))?;
+        Ok((dir, db))
+    }
+
+    #[test]
+    fn trees_changed_on_both_sides_are_merged_recursively_and_dropped_if_empty() -> crate::Result {
+        let (_keep, db) = writable_db()?;
+        let (a, b, keep) = (blob(&db, "a")?, blob(&db, "b")?, blob(&db, "keep")?);
+        let sub = |entries: &[(&str, EntryMode, ObjectId)]| -> crate::Result<(&'static str, EntryMode, ObjectId)> {
+            Ok(("sub", EntryMode::Tree, tree(&db, entries)?))
+        };
+        let base = tree(
+            &db,
+            &[
+                sub(&[("a", EntryMode::Blob, a), ("b", EntryMode::Blob, b)])?,
+                ("keep", EntryMode::Blob, keep),
+            ],
+        )?;
+        let ours = tree(
+            &db,
+            &[sub(&[("b", EntryMode::Blob, b)])?, ("keep", EntryMode::Blob, keep)],
+        )?;
+        let theirs = tree(
+            &db,
+            &[sub(&[("a", EntryMode::Blob, a)])?, ("keep", EntryMode::Blob, keep)],
+        )?;
+
+        let outcome = merge::trees(
+            Some(&base),
+            &ours,
+            &theirs,
+            |id, buf| find(&db, id, buf),
+            |tree| db.write(&mutable::Object::Tree(tree.clone()), git_hash::Kind::Sha1),
+        )?;
+        assert!(outcome.is_clean());
+        assert_eq!(
+            outcome.tree,
+            tree(&db, &[("keep", EntryMode::Blob, keep)])?,
+            "each side deleted one file of 'sub', leaving it empty"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts_in_nested_trees_have_their_full_path() -> crate::Result {
+        let (_keep, db) = writable_db()?;
+        let (a, b, c) = (blob(&db, "a")?, blob(&db, "b")?, blob(&db, "c")?);
+        let base = tree(
+            &db,
+            &[("sub", EntryMode::Tree, tree(&db, &[("f", EntryMode::Blob, a)])?)],
+        )?;
+        let ours = tree(
+            &db,
+            &[("sub", EntryMode::Tree, tree(&db, &[("f", EntryMode::Blob, b)])?)],
+        )?;
+        let theirs = tree(
+            &db,
+            &[(
+                "sub",
+                EntryMode::Tree,
+                tree(&db, &[("f", EntryMode::BlobExecutable, c)])?,
+            )],
+        )?;
+
+        let outcome = merge::trees(
+            Some(&base),
+            &ours,
+            &theirs,
+            |id, buf| find(&db, id, buf),
+            |tree| db.write(&mutable::Object::Tree(tree.clone()), git_hash::Kind::Sha1),
+        )?;
+        assert_eq!(outcome.conflicts.len(), 1);
+        let conflict = &outcome.conflicts[0];
+        assert_eq!(conflict.path.as_bstr(), "sub/f");
+        assert_eq!(
+            conflict.theirs,
+            Some(merge::Version {
+                mode: EntryMode::BlobExecutable,
+                oid: c
+            })
+        );
+        assert_eq!(outcome.tree, ours, "our side is chosen in case of conflict");
+        Ok(())
+    }
+}
@@ -41,3 +41,141 @@ pub mod walkdir {
 
 #[cfg(any(feature = "walkdir", feature = "jwalk"))]
 pub use self::walkdir::{walkdir_new, WalkDir};
+
+///
+#[cfg(any(feature = "walkdir", feature = "jwalk"))]
+pub mod walk {
+    use std::path::PathBuf;
+
+    /// How to handle symbolic links encountered during a [walk][super::walk()].
+    #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+    pub enum Symlinks {
+        /// Follow symbolic links and yield what they point to, descending into linked directories.
+        ///
+        /// Links that form a loop are reported as [errors][Iter::errors()].
+        Follow,
+        /// Yield symbolic links as entries without following them.
+        Yield,
+        /// Neither follow nor yield symbolic links.
+        Skip,
+    }
+
+    /// Configuration for a [walk][super::walk()].
+    #[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+    pub struct Options {
+        /// How to handle symbolic links.
+        pub symlinks: Symlinks,
+        /// If true, the entries of each directory are yielded sorted by file name, and the walk is deterministic.
+        /// Otherwise the order is unspecified and may change between runs if the walk is performed in parallel.
+        pub sorted: bool,
+        /// If true and the `parallel` feature is enabled, directories are read in parallel using a thread pool.
+        pub parallel: bool,
+        /// Entries closer to the root than this are not yielded, with the root itself being at depth 0.
+        pub min_depth: usize,
+        /// If set, entries further away from the root than this are neither yielded nor descended into.
+        pub max_depth: Option<usize>,
+    }
+
+    impl Default for Options {
+        /// Yield all entries including the root in parallel and unsorted, without following symbolic links.
+        fn default() -> Self {
+            Options {
+                symlinks: Symlinks::Yield,
+                sorted: false,
+                parallel: true,
+                min_depth: 0,
+                max_depth: None,
+            }
+        }
+    }
+
+    /// An entry yielded by [`Iter`].
+    #[derive(Debug, Clone)]
+    pub struct Entry {
+        /// The path to the entry, with the root of the walk as prefix.
+        pub path: PathBuf,
+        /// The distance to the root of the walk, which is at depth 0.
+        pub depth: usize,
+        /// The type of the entry, which is the type of what a symbolic link points to if links are followed.
+        pub file_type: std::fs::FileType,
+    }
+
+    /// An iterator over the entries of a [walk][super::walk()], which collects errors instead of yielding them.
+    pub struct Iter {
+        pub(crate) inner: super::walkdir::DirEntryIter,
+        pub(crate) symlinks: Symlinks,
+        pub(crate) errors: Vec<super::walkdir::Error>,
+    }
+
+    impl Iter {
+        /// Return all errors encountered so far, like directories that couldn't be read.
+        pub fn errors(&self) -> &[super::walkdir::Error] {
+            &self.errors
+        }
+
+        /// Consume this iterator and return all errors encountered during the walk.
+        pub fn into_errors(self) -> Vec<super::walkdir::Error> {
+            self.errors
+        }
+    }
+
+    impl Iterator for Iter {
+        type Item = Entry;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                match self.inner.next()? {
+                    Ok(entry) => {
+                        if self.symlinks == Symlinks::Skip && entry.path_is_symlink() {
+                            continue;
+                        }
+                        return Some(Entry {
+                            path: entry.path().to_path_buf(),
+                            depth: entry.depth(),
+                            file_type: entry.file_type(),
+                        });
+                    }
+                    Err(err) => self.errors.push(err),
+                }
+            }
+        }
+    }
+}
+
+/// Walk the directory tree at `root` as configured by `options`, yielding all entries in it while collecting errors.
+///
+/// The directories are read in parallel if the `parallel` feature is enabled and if [configured][walk::Options::parallel].
+#[cfg(any(feature = "walkdir", feature = "jwalk"))]
+pub fn walk(root: impl AsRef<std::path::Path>, options: walk::Options) -> walk::Iter {
+    let walk::Options {
+        symlinks,
+        sorted,
+        parallel,
+        min_depth,
+        max_depth,
+    } = options;
+    let walk = walkdir_new(root)
+        .follow_links(symlinks == walk::Symlinks::Follow)
+        .min_depth(min_depth)
+        .max_depth(max_depth.unwrap_or(usize::MAX));
+    #[cfg(feature = "parallel")]
+    let walk = walk.sort(sorted).parallelism(if parallel {
+        jwalk::Parallelism::RayonDefaultPool
+    } else {
+        jwalk::Parallelism::Serial
+    });
+    #[cfg(not(feature = "parallel"))]
+    let walk = {
+        let _ = parallel;
+        if sorted {
+            walk.sort_by(|a, b| a.file_name().cmp(b.file_name()))
+        } else {
+            walk
+        }
+    };
+    walk::Iter {
+        inner: walk.into_iter(),
+        symlinks,
+        errors: Vec::new(),
+    }
+}
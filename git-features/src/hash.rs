@@ -163,3 +163,44 @@ mod write {
 }
 #[cfg(any(feature = "sha1", feature = "fast-sha1"))]
 pub use write::Write;
+
+#[cfg(any(feature = "sha1", feature = "fast-sha1"))]
+mod read {
+    use crate::hash::Sha1;
+
+    /// A utility to automatically generate a hash while reading from an inner reader.
+    pub struct Read<T> {
+        /// The hash implementation.
+        pub hash: Sha1,
+        /// The inner reader.
+        pub inner: T,
+    }
+
+    impl<T> std::io::Read for Read<T>
+    where
+        T: std::io::Read,
+    {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let bytes_read = self.inner.read(buf)?;
+            self.hash.update(&buf[..bytes_read]);
+            Ok(bytes_read)
+        }
+    }
+
+    impl<T> Read<T>
+    where
+        T: std::io::Read,
+    {
+        /// Create a new hash reader which hashes all bytes read from `inner` with a hash of `kind`.
+        pub fn new(inner: T, kind: git_hash::Kind) -> Self {
+            match kind {
+                git_hash::Kind::Sha1 => Read {
+                    inner,
+                    hash: Sha1::default(),
+                },
+            }
+        }
+    }
+}
+#[cfg(any(feature = "sha1", feature = "fast-sha1"))]
+pub use read::Read;
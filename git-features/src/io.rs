@@ -13,9 +13,24 @@ pub mod pipe {
         /// The channel through which bytes are transferred. Useful for sending [`std::io::Error`]s instead.
         pub channel: std::sync::mpsc::SyncSender<io::Result<BytesMut>>,
         buf: BytesMut,
+        max_write_len: Option<usize>,
+    }
+
+    impl Writer {
+        /// Send `err` to the [`Reader`] which will return it from its next read once all previously written bytes were
+        /// consumed, or fail with [`BrokenPipe`][io::ErrorKind::BrokenPipe] if the reader was dropped.
+        pub fn send_error(&self, err: io::Error) -> io::Result<()> {
+            self.channel.send(Err(err)).map_err(broken_pipe)
+        }
+    }
+
+    fn broken_pipe<T>(err: std::sync::mpsc::SendError<T>) -> io::Error {
+        io::Error::new(io::ErrorKind::BrokenPipe, err.to_string())
     }
 
     /// The read-end of the pipe, implementing the [`std::io::Read`] trait.
+    ///
+    /// Reads only block if no bytes are available, and return what's available otherwise.
     pub struct Reader {
         channel: std::sync::mpsc::Receiver<io::Result<BytesMut>>,
         buf: BytesMut,
@@ -43,6 +58,9 @@ pub mod pipe {
             let mut written = 0;
             while !out.is_empty() {
                 if self.buf.is_empty() {
+                    if written != 0 {
+                        break;
+                    }
                     match self.channel.recv() {
                         Ok(Ok(buf)) => self.buf = buf,
                         Ok(Err(err)) => return Err(err),
@@ -61,10 +79,12 @@ pub mod pipe {
 
     impl io::Write for Writer {
         fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let buf = match self.max_write_len {
+                Some(max) => &buf[..buf.len().min(max)],
+                None => buf,
+            };
             self.buf.put_slice(buf);
-            self.channel
-                .send(Ok(self.buf.split()))
-                .map_err(|err| io::Error::new(io::ErrorKind::BrokenPipe, err))?;
+            self.channel.send(Ok(self.buf.split())).map_err(broken_pipe)?;
             Ok(buf.len())
         }
 
@@ -83,6 +103,7 @@ pub mod pipe {
             Writer {
                 channel: tx,
                 buf: BytesMut::with_capacity(4096),
+                max_write_len: None,
             },
             Reader {
                 channel: rx,
@@ -90,4 +111,76 @@ pub mod pipe {
             },
         )
     }
+
+    /// Like [`unidirectional()`], but the amount of bytes in flight is bounded by a fixed `capacity` instead of the
+    /// amount of writes.
+    ///
+    /// Each write transfers at most `capacity` bytes, and blocks while the previously written chunk wasn't yet received by the
+    /// [`Reader`], which applies backpressure to the writer. This way, no more than `capacity` bytes are queued, with the reader
+    /// holding at most another `capacity` bytes it didn't consume yet. A `capacity` of `0` is treated as `1`.
+    pub fn bounded(capacity: usize) -> (Writer, Reader) {
+        let capacity = capacity.max(1);
+        let (mut writer, reader) = unidirectional(1);
+        writer.buf = BytesMut::with_capacity(capacity);
+        writer.max_write_len = Some(capacity);
+        (writer, reader)
+    }
+
+    /// One end of a [`bidirectional()`] pipe, writing to and reading from the other end.
+    ///
+    /// The fields can be moved into different threads to read and write concurrently.
+    pub struct End {
+        /// The write-end, with bytes being received by the `reader` of the other end.
+        pub writer: Writer,
+        /// The read-end, receiving the bytes written by the `writer` of the other end.
+        pub reader: Reader,
+    }
+
+    impl io::Read for End {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reader.read(buf)
+        }
+    }
+
+    impl io::BufRead for End {
+        fn fill_buf(&mut self) -> io::Result<&[u8]> {
+            self.reader.fill_buf()
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.reader.consume(amt)
+        }
+    }
+
+    impl io::Write for End {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writer.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.writer.flush()
+        }
+    }
+
+    /// Returns two connected [ends][End] of a pipe whose directions are each [`bounded()`] by `capacity` bytes, similar
+    /// to a socket pair.
+    ///
+    /// Each end may write up to `capacity` bytes without blocking once the other end received what was written before.
+    /// Hence peers taking turns with messages of at most `capacity` bytes, like a client sending a request and a server
+    /// responding to it, don't deadlock even if each end is used by a single thread. If more than that is to be written
+    /// at once, split an end into its `reader` and `writer` and move them into separate threads.
+    pub fn bidirectional(capacity: usize) -> (End, End) {
+        let (a_writer, b_reader) = bounded(capacity);
+        let (b_writer, a_reader) = bounded(capacity);
+        (
+            End {
+                writer: a_writer,
+                reader: a_reader,
+            },
+            End {
+                writer: b_writer,
+                reader: b_reader,
+            },
+        )
+    }
 }
@@ -1,4 +1,8 @@
 use crate::parallel::{num_threads, Reduce};
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Mutex,
+};
 
 /// Runs `left` and `right` in parallel, returning their output when both are done.
 pub fn join<O1: Send, O2: Send>(left: impl FnOnce() -> O1 + Send, right: impl FnOnce() -> O2 + Send) -> (O1, O2) {
@@ -69,3 +73,92 @@ where
     })
     .unwrap()
 }
+
+/// Process `tasks` along with all tasks they spawn in multiple threads, and return the state of each thread once there are
+/// no more tasks.
+///
+/// Unlike with [`in_parallel()`], tasks can produce more tasks, which are picked up by whichever thread is idle first.
+/// This keeps all threads busy even if the work is very unevenly distributed among the initial tasks, as is the case when
+/// traversing trees.
+/// If only a single thread is to be used, tasks are processed on the current thread in the order they were spawned instead.
+///
+/// * if `thread_limit` is `Some`, the given amount of threads will be used. If `None`, all logical cores will be used.
+/// * `new_thread_state(thread_number) -> State` produces thread-local state once per thread to be based to `process`
+/// * `process(Task, &mut State, &mut spawn) -> Result<(), Error>` handles a task, calling `spawn(Task)` to schedule more tasks.
+///   The first error stops all threads and is returned.
+pub fn in_parallel_with_tasks<T, S, E>(
+    tasks: impl IntoIterator<Item = T>,
+    thread_limit: Option<usize>,
+    new_thread_state: impl Fn(usize) -> S + Send + Sync,
+    process: impl Fn(T, &mut S, &mut dyn FnMut(T)) -> Result<(), E> + Send + Sync,
+) -> Result<Vec<S>, E>
+where
+    T: Send,
+    S: Send,
+    E: Send,
+{
+    let num_threads = num_threads(thread_limit);
+    if num_threads == 1 {
+        return super::serial::in_parallel_with_tasks(tasks, thread_limit, new_thread_state, process);
+    }
+    // `None` tells a thread to stop, and is sent to each thread once there are no more tasks.
+    let (send_task, receive_task) = crossbeam_channel::unbounded::<Option<T>>();
+    // The amount of tasks which were spawned but not yet processed, with 0 indicating we are done.
+    let pending = AtomicUsize::new(0);
+    for task in tasks {
+        pending.fetch_add(1, Ordering::SeqCst);
+        send_task.send(Some(task)).expect("receiver to be alive");
+    }
+    if pending.load(Ordering::SeqCst) == 0 {
+        for _ in 0..num_threads {
+            send_task.send(None).expect("receiver to be alive");
+        }
+    }
+    let should_stop = AtomicBool::new(false);
+    let first_error = Mutex::new(None);
+    let new_thread_state = &new_thread_state;
+    let process = &process;
+    let (pending, should_stop, first_error_ref) = (&pending, &should_stop, &first_error);
+    let states = crossbeam_utils::thread::scope(move |s| {
+        let threads: Vec<_> = (0..num_threads)
+            .map(|thread_id| {
+                let send_task = send_task.clone();
+                let receive_task = receive_task.clone();
+                s.spawn(move |_| {
+                    let mut state = new_thread_state(thread_id);
+                    while let Ok(Some(task)) = receive_task.recv() {
+                        // Remaining tasks are skipped after an error, but still counted to know when we are done.
+                        if !should_stop.load(Ordering::Relaxed) {
+                            let res = process(task, &mut state, &mut |task| {
+                                pending.fetch_add(1, Ordering::SeqCst);
+                                send_task.send(Some(task)).expect("receiver to be alive");
+                            });
+                            if let Err(err) = res {
+                                first_error_ref
+                                    .lock()
+                                    .expect("no panic while holding the lock")
+                                    .get_or_insert(err);
+                                should_stop.store(true, Ordering::Relaxed);
+                            }
+                        }
+                        if pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+                            for _ in 0..num_threads {
+                                send_task.send(None).expect("receiver to be alive");
+                            }
+                        }
+                    }
+                    state
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>()
+    })
+    .unwrap();
+    match first_error.into_inner().expect("no panic while holding the lock") {
+        Some(err) => Err(err),
+        None => Ok(states),
+    }
+}
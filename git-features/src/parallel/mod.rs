@@ -44,6 +44,9 @@ pub use serial::*;
 mod eager_iter;
 pub use eager_iter::{EagerIter, EagerIterIf};
 
+mod sharded;
+pub use sharded::{ShardKey, ShardedMap, ShardedSet};
+
 /// A no-op returning the input _(`desired_chunk_size`, `Some(thread_limit)`, `thread_limit)_ used
 /// when the `parallel` feature toggle is not set.
 #[cfg(not(feature = "parallel"))]
@@ -111,15 +114,15 @@ pub fn optimize_chunk_size_and_thread_limit(
 
 /// Always returns 1, available when the `parallel` feature toggle is unset.
 #[cfg(not(feature = "parallel"))]
-fn num_threads(_thread_limit: Option<usize>) -> usize {
+pub fn num_threads(_thread_limit: Option<usize>) -> usize {
     1
 }
 
-/// Returns the amount of threads the system can effectively use as the amount of its logical cores.
+/// Returns the amount of threads to use for the given `thread_limit`, which is the amount of logical cores if there is no limit.
 ///
 /// Only available with the `parallel` feature toggle set.
 #[cfg(feature = "parallel")]
-fn num_threads(thread_limit: Option<usize>) -> usize {
+pub fn num_threads(thread_limit: Option<usize>) -> usize {
     let logical_cores = || num_cpus::get();
     thread_limit
         .map(|l| if l == 0 { logical_cores() } else { l })
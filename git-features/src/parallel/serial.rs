@@ -34,3 +34,32 @@ where
     }
     reducer.finalize()
 }
+
+/// Process `tasks` along with all tasks they spawn in a single thread, and return the thread's state once there are no more tasks.
+/// Tasks are processed in the order they were spawned.
+///
+/// * `new_thread_state(thread_number) -> State` produces thread-local state once per thread to be based to `process`
+/// * `process(Task, &mut State, &mut spawn) -> Result<(), Error>` handles a task, calling `spawn(Task)` to schedule more tasks.
+///   The first error stops processing and is returned.
+/// * if `thread_limit` has no effect as everything is run on the main thread, but is present to keep the signature
+///   similar to the parallel version.
+///
+/// **This serial version performing all calculations on the current thread.**
+pub fn in_parallel_with_tasks<T, S, E>(
+    tasks: impl IntoIterator<Item = T>,
+    _thread_limit: Option<usize>,
+    new_thread_state: impl Fn(usize) -> S + Send + Sync,
+    process: impl Fn(T, &mut S, &mut dyn FnMut(T)) -> Result<(), E> + Send + Sync,
+) -> Result<Vec<S>, E>
+where
+    T: Send,
+    S: Send,
+    E: Send,
+{
+    let mut state = new_thread_state(0);
+    let mut tasks: std::collections::VecDeque<T> = tasks.into_iter().collect();
+    while let Some(task) = tasks.pop_front() {
+        process(task, &mut state, &mut |task| tasks.push_back(task))?;
+    }
+    Ok(vec![state])
+}
@@ -0,0 +1,188 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::Hash,
+    sync::{Mutex, MutexGuard},
+};
+
+/// A key which knows which shard it belongs to without hashing, used by [`ShardedMap`] and [`ShardedSet`].
+pub trait ShardKey {
+    /// Return the index of the shard out of `num_shards` this key belongs to, which must be smaller than `num_shards`.
+    fn shard_index(&self, num_shards: usize) -> usize;
+}
+
+impl ShardKey for git_hash::oid {
+    /// Object ids are uniformly distributed, so their first two bytes are used directly.
+    fn shard_index(&self, num_shards: usize) -> usize {
+        let bytes = self.as_bytes();
+        u16::from_be_bytes([bytes[0], bytes[1]]) as usize % num_shards
+    }
+}
+
+impl ShardKey for git_hash::ObjectId {
+    fn shard_index(&self, num_shards: usize) -> usize {
+        self.as_ref().shard_index(num_shards)
+    }
+}
+
+/// A map to be used by multiple threads at once, which distributes its items across shards that are locked individually
+/// to reduce contention.
+///
+/// Without the `parallel` feature toggle, a single shard is used by default as there is no contention.
+pub struct ShardedMap<K, V> {
+    shards: Vec<Mutex<HashMap<K, V>>>,
+}
+
+impl<K, V> Default for ShardedMap<K, V>
+where
+    K: ShardKey + Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> ShardedMap<K, V>
+where
+    K: ShardKey + Hash + Eq,
+{
+    /// Create a new instance with enough shards to keep contention low when used by all logical cores,
+    /// or a single shard if the `parallel` feature toggle isn't set.
+    pub fn new() -> Self {
+        Self::with_shards(if cfg!(feature = "parallel") {
+            (super::num_threads(None) * 4).next_power_of_two()
+        } else {
+            1
+        })
+    }
+
+    /// Create a new instance with the given amount of `shards`, which must not be 0.
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards > 0, "need at least one shard");
+        ShardedMap {
+            shards: (0..shards).map(|_| Default::default()).collect(),
+        }
+    }
+
+    /// Insert `value` for `key` and return the previous value, if there was one.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.shard(&key).insert(key, value)
+    }
+
+    /// Remove the value for `key` and return it, if there was one.
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ShardKey + Hash + Eq + ?Sized,
+    {
+        self.shard(key).remove(key)
+    }
+
+    /// Return a copy of the value for `key`, if there is one.
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ShardKey + Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.shard(key).get(key).cloned()
+    }
+
+    /// Return `true` if there is a value for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ShardKey + Hash + Eq + ?Sized,
+    {
+        self.shard(key).contains_key(key)
+    }
+
+    /// Return the amount of items in this map.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().expect("no panic while holding the lock").len())
+            .sum()
+    }
+
+    /// Return `true` if there is no item in this map.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Turn this instance into a single `HashMap` with all items.
+    pub fn into_inner(self) -> HashMap<K, V> {
+        let mut shards = self
+            .shards
+            .into_iter()
+            .map(|shard| shard.into_inner().expect("no panic while holding the lock"));
+        let mut out = shards.next().unwrap_or_default();
+        for shard in shards {
+            out.extend(shard);
+        }
+        out
+    }
+
+    fn shard<Q>(&self, key: &Q) -> MutexGuard<'_, HashMap<K, V>>
+    where
+        Q: ShardKey + ?Sized,
+    {
+        self.shards[key.shard_index(self.shards.len())]
+            .lock()
+            .expect("no panic while holding the lock")
+    }
+}
+
+/// A set to be used by multiple threads at once, for example to keep track of objects that were already seen.
+///
+/// It's a [`ShardedMap`] without values.
+pub struct ShardedSet<T>(ShardedMap<T, ()>);
+
+impl<T> Default for ShardedSet<T>
+where
+    T: ShardKey + Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ShardedSet<T>
+where
+    T: ShardKey + Hash + Eq,
+{
+    /// Create a new instance with enough shards to keep contention low when used by all logical cores,
+    /// or a single shard if the `parallel` feature toggle isn't set.
+    pub fn new() -> Self {
+        ShardedSet(ShardedMap::new())
+    }
+
+    /// Create a new instance with the given amount of `shards`, which must not be 0.
+    pub fn with_shards(shards: usize) -> Self {
+        ShardedSet(ShardedMap::with_shards(shards))
+    }
+
+    /// Insert `item` and return `true` if it wasn't present yet.
+    pub fn insert(&self, item: T) -> bool {
+        self.0.insert(item, ()).is_none()
+    }
+
+    /// Return `true` if `item` is contained in this set.
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ShardKey + Hash + Eq + ?Sized,
+    {
+        self.0.contains_key(item)
+    }
+
+    /// Return the amount of items in this set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Return `true` if there is no item in this set.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -2,27 +2,27 @@
 use std::io;
 
 pub use prodash::progress::{Discard, DoOrDiscard, Either, ThroughputOnDrop};
+#[cfg(feature = "progress-tree")]
+pub use prodash::Tree;
 pub use prodash::{unit, Progress, Unit};
 
-/// A unit for displaying bytes with throughput and progress percentage.
+/// A unit for displaying bytes with binary prefixes, like `1.5 MiB`, along with throughput and progress percentage.
+///
+/// Values are formatted using [`format::bytes()`].
 pub fn bytes() -> Option<Unit> {
     Some(unit::dynamic_and_mode(
-        unit::Bytes,
+        display::Bytes,
         unit::display::Mode::with_throughput().and_percentage(),
     ))
 }
 
-/// A unit for displaying human readable numbers with throughput and progress percentage.
+/// A unit for displaying counts of `name` with thousands separators, like `1,234,567 objects`, along with throughput
+/// and progress percentage.
+///
+/// Values are formatted using [`format::count()`].
 pub fn count(name: &'static str) -> Option<Unit> {
     Some(unit::dynamic_and_mode(
-        unit::Human::new(
-            {
-                let mut f = unit::human::Formatter::new();
-                f.with_decimals(1);
-                f
-            },
-            name,
-        ),
+        display::Count { name },
         unit::display::Mode::with_throughput().and_percentage(),
     ))
 }
@@ -40,6 +40,198 @@ pub struct Read<R, P> {
     pub progress: P,
 }
 
+/// Human readable formatting of values, shared by the units used in progress renderers and by textual output of the
+/// values themselves.
+///
+/// Machine readable output should use the plain values instead.
+pub mod format {
+    /// Format `bytes` using binary prefixes with one decimal, like `512 B`, `1.5 KiB` or `42.0 MiB`.
+    pub fn bytes(bytes: u64) -> String {
+        const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+        if bytes < 1024 {
+            return format!("{} B", bytes);
+        }
+        let mut value = bytes as f64 / 1024.0;
+        let mut unit = UNITS[0];
+        for next_unit in &UNITS[1..] {
+            if value < 1024.0 {
+                break;
+            }
+            value /= 1024.0;
+            unit = next_unit;
+        }
+        format!("{:.1} {}", value, unit)
+    }
+
+    /// Format `count` with a comma as thousands separator, like `1,234,567`.
+    pub fn count(count: u64) -> String {
+        let digits = count.to_string();
+        let (leading_digits, groups) = digits.split_at(digits.len() % 3);
+        let mut out = String::from(leading_digits);
+        for group in groups.as_bytes().chunks(3) {
+            if !out.is_empty() {
+                out.push(',');
+            }
+            out.push_str(std::str::from_utf8(group).expect("ascii digits"));
+        }
+        out
+    }
+
+    /// Format the fraction of `value` in `total` as percentage with one decimal, like `42.1%`,
+    /// or return `None` if `total` is zero.
+    pub fn percentage(value: u64, total: u64) -> Option<String> {
+        (total != 0).then(|| percent(value as f64 / total as f64 * 100.0))
+    }
+
+    pub(crate) fn percent(percent: f64) -> String {
+        format!("{:.1}%", percent)
+    }
+}
+
+/// Plain data snapshots of all tasks in a [progress tree][prodash::Tree], for embedders who render progress themselves,
+/// like GUIs or web dashboards.
+#[cfg(feature = "progress-tree")]
+pub mod snapshot {
+    pub use prodash::progress::State;
+    use prodash::progress::{Key, Step};
+
+    /// A task in the progress tree at the time of the snapshot.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Task {
+        /// The nesting level of the task, with `1` for tasks added to the root of the tree.
+        pub level: u8,
+        /// The name of the task.
+        pub name: String,
+        /// The progress of the task, or `None` if it only serves to organize its child tasks.
+        pub progress: Option<Value>,
+    }
+
+    /// The progress of a [`Task`].
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Value {
+        /// The amount of progress made so far.
+        pub step: Step,
+        /// The step at which the task is done, or `None` if progress is unbounded.
+        pub done_at: Option<Step>,
+        /// The name of the unit of `step`, like `objects`, or `None` if there is no unit or it has no name.
+        pub unit: Option<String>,
+        /// The amount of steps made per second, available once the task was observed long enough to compute it.
+        pub throughput_per_second: Option<Step>,
+        /// All of the above as displayed by progress renderers, like `1,234/5,000 objects [24.0%]`.
+        pub display: String,
+        /// Whether the task is running or blocked.
+        pub state: State,
+    }
+
+    /// Take snapshots of the tasks in a progress tree and compute their throughput across snapshots.
+    pub struct Observer {
+        tree: prodash::Tree,
+        throughput: prodash::Throughput,
+        sorted: Vec<(Key, prodash::progress::Task)>,
+    }
+
+    impl Observer {
+        /// Create a new instance to observe `tree`.
+        pub fn new(tree: prodash::Tree) -> Self {
+            Observer {
+                tree,
+                throughput: Default::default(),
+                sorted: Vec::new(),
+            }
+        }
+
+        /// Return all tasks currently in the tree in order of hierarchy, with each task followed by its children.
+        ///
+        /// Throughput is computed from the difference to previous snapshots, hence this method should be called periodically,
+        /// for instance whenever the embedder wants to redraw.
+        pub fn snapshot(&mut self) -> Vec<Task> {
+            self.tree.sorted_snapshot(&mut self.sorted);
+            self.throughput.update_elapsed();
+            let throughput = &mut self.throughput;
+            let tasks = self
+                .sorted
+                .iter()
+                .map(|(key, task)| Task {
+                    level: key.level(),
+                    name: task.name.clone(),
+                    progress: task.progress.as_ref().map(|value| {
+                        let throughput = throughput.update_and_get(key, Some(value));
+                        Value {
+                            step: value.step,
+                            done_at: value.done_at,
+                            unit: value.unit.as_ref().and_then(|unit| {
+                                let mut name = String::new();
+                                unit.as_display_value().display_unit(&mut name, value.step).ok()?;
+                                if name.is_empty() {
+                                    None
+                                } else {
+                                    Some(name)
+                                }
+                            }),
+                            throughput_per_second: throughput.and_then(|throughput| {
+                                let secs = throughput.timespan.as_secs_f64();
+                                (secs > 0.0).then(|| (throughput.value_change_in_timespan as f64 / secs) as Step)
+                            }),
+                            display: match &value.unit {
+                                Some(unit) => unit.display(value.step, value.done_at, throughput).to_string(),
+                                None => match value.done_at {
+                                    Some(done_at) => format!("{}/{}", value.step, done_at),
+                                    None => value.step.to_string(),
+                                },
+                            },
+                            state: value.state,
+                        }
+                    }),
+                })
+                .collect();
+            self.throughput.reconcile(&self.sorted);
+            tasks
+        }
+    }
+}
+
+mod display {
+    use super::{format, unit::DisplayValue};
+    use prodash::progress::Step;
+    use std::fmt;
+
+    pub struct Bytes;
+
+    impl DisplayValue for Bytes {
+        fn display_current_value(&self, w: &mut dyn fmt::Write, value: Step, _upper: Option<Step>) -> fmt::Result {
+            w.write_str(&format::bytes(value as u64))
+        }
+        fn display_upper_bound(&self, w: &mut dyn fmt::Write, upper_bound: Step, _value: Step) -> fmt::Result {
+            w.write_str(&format::bytes(upper_bound as u64))
+        }
+        fn display_unit(&self, _w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
+            Ok(())
+        }
+        fn display_percentage(&self, w: &mut dyn fmt::Write, percentage: f64) -> fmt::Result {
+            write!(w, "[{}]", format::percent(percentage))
+        }
+    }
+
+    pub struct Count {
+        pub name: &'static str,
+    }
+
+    impl DisplayValue for Count {
+        fn display_current_value(&self, w: &mut dyn fmt::Write, value: Step, _upper: Option<Step>) -> fmt::Result {
+            w.write_str(&format::count(value as u64))
+        }
+        fn display_upper_bound(&self, w: &mut dyn fmt::Write, upper_bound: Step, _value: Step) -> fmt::Result {
+            w.write_str(&format::count(upper_bound as u64))
+        }
+        fn display_unit(&self, w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
+            w.write_str(self.name)
+        }
+        fn display_percentage(&self, w: &mut dyn fmt::Write, percentage: f64) -> fmt::Result {
+            write!(w, "[{}]", format::percent(percentage))
+        }
+    }
+}
+
 impl<R, P> io::Read for Read<R, P>
 where
     R: io::Read,
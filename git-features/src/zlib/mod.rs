@@ -18,6 +18,28 @@ pub mod inflate {
             }
         }
     }
+
+    /// The state of the decompressor after a call to [`Inflate::chunk()`][super::Inflate::chunk()].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum State {
+        /// The end of the zlib stream was reached and all of its data was written.
+        Done,
+        /// The output buffer is full, call again with more space to obtain the remaining data.
+        NeedsOutput,
+        /// All input was consumed without reaching the end of the stream, call again with more input.
+        NeedsInput,
+    }
+
+    /// The outcome of a call to [`Inflate::chunk()`][super::Inflate::chunk()].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct Chunk {
+        /// Whether the stream is done or what it needs to make progress.
+        pub state: State,
+        /// The amount of bytes consumed from the input.
+        pub consumed_in: usize,
+        /// The amount of bytes written to the output.
+        pub written_out: usize,
+    }
 }
 
 /// Decompress a few bytes of a zlib stream without allocation
@@ -46,6 +68,30 @@ impl Inflate {
             (self.state.total_out() - before_out) as usize,
         ))
     }
+
+    /// Decompress as much of `input` as fits into `out`, and call this method repeatedly to decompress a stream
+    /// in chunks of bounded size. The returned [`Chunk`][inflate::Chunk] tells whether the stream is done or
+    /// needs more input or output space, whereas an error indicates a corrupt stream.
+    ///
+    /// After an error or to decompress another stream, call [`reset()`][Inflate::reset()].
+    pub fn chunk(&mut self, input: &[u8], out: &mut [u8]) -> Result<inflate::Chunk, inflate::Error> {
+        let (status, consumed_in, written_out) = self.once(input, out)?;
+        let state = match status {
+            Status::StreamEnd => inflate::State::Done,
+            Status::Ok | Status::BufError if written_out == out.len() => inflate::State::NeedsOutput,
+            Status::Ok | Status::BufError => inflate::State::NeedsInput,
+        };
+        Ok(inflate::Chunk {
+            state,
+            consumed_in,
+            written_out,
+        })
+    }
+
+    /// Prepare the decompressor for a new stream, which also recovers it from errors.
+    pub fn reset(&mut self) {
+        self.state.reset(true);
+    }
 }
 
 ///
@@ -0,0 +1,134 @@
+mod walk {
+    use git_features::fs::{self, walk};
+    use std::path::{Path, PathBuf};
+
+    type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+    fn fixture() -> std::io::Result<tempfile::TempDir> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(dir.path().join("b").join("c"))?;
+        std::fs::write(dir.path().join("a"), b"")?;
+        std::fs::write(dir.path().join("b").join("d"), b"")?;
+        std::fs::write(dir.path().join("b").join("c").join("e"), b"")?;
+        Ok(dir)
+    }
+
+    fn relative_paths(root: &Path, iter: impl Iterator<Item = walk::Entry>) -> Vec<PathBuf> {
+        iter.map(|e| e.path.strip_prefix(root).expect("root is prefix").to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn sorted_yields_entries_depth_first_in_name_order() -> Result {
+        let dir = fixture()?;
+        let mut iter = fs::walk(
+            dir.path(),
+            walk::Options {
+                sorted: true,
+                min_depth: 1,
+                ..Default::default()
+            },
+        );
+        let paths = relative_paths(dir.path(), iter.by_ref());
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("a"),
+                "b".into(),
+                Path::new("b").join("c"),
+                Path::new("b").join("c").join("e"),
+                Path::new("b").join("d"),
+            ]
+        );
+        assert!(iter.errors().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn unsorted_yields_the_same_entries_and_respects_max_depth() -> Result {
+        let dir = fixture()?;
+        let mut paths = relative_paths(
+            dir.path(),
+            fs::walk(
+                dir.path(),
+                walk::Options {
+                    min_depth: 1,
+                    max_depth: Some(1),
+                    ..Default::default()
+                },
+            ),
+        );
+        paths.sort();
+        assert_eq!(paths, vec![PathBuf::from("a"), "b".into()]);
+        Ok(())
+    }
+
+    #[test]
+    fn the_root_is_at_depth_zero() -> Result {
+        let dir = fixture()?;
+        let root = fs::walk(dir.path(), Default::default()).next().expect("root");
+        assert_eq!(root.depth, 0);
+        assert_eq!(root.path, dir.path());
+        assert!(root.file_type.is_dir());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_roots_are_collected_as_errors() {
+        let mut iter = fs::walk("this-path-does-not-exist", Default::default());
+        assert_eq!(iter.by_ref().count(), 0);
+        assert_eq!(iter.into_errors().len(), 1);
+    }
+
+    #[cfg(unix)]
+    mod symlinks {
+        use super::{fixture, relative_paths, Result};
+        use git_features::fs::{self, walk};
+        use std::path::{Path, PathBuf};
+
+        fn walk_with(
+            symlinks: walk::Symlinks,
+        ) -> std::result::Result<(Vec<PathBuf>, usize), Box<dyn std::error::Error>> {
+            let dir = fixture()?;
+            std::os::unix::fs::symlink(dir.path().join("b").join("c"), dir.path().join("link"))?;
+            let mut iter = fs::walk(
+                dir.path(),
+                walk::Options {
+                    symlinks,
+                    sorted: true,
+                    min_depth: 1,
+                    ..Default::default()
+                },
+            );
+            let paths = relative_paths(dir.path(), iter.by_ref());
+            Ok((paths, iter.errors().len()))
+        }
+
+        #[test]
+        fn follow_descends_into_linked_directories() -> Result {
+            let (paths, num_errors) = walk_with(walk::Symlinks::Follow)?;
+            assert_eq!(num_errors, 0);
+            assert!(paths.contains(&PathBuf::from("link")));
+            assert!(paths.contains(&Path::new("link").join("e")));
+            Ok(())
+        }
+
+        #[test]
+        fn yield_returns_links_without_following_them() -> Result {
+            let (paths, num_errors) = walk_with(walk::Symlinks::Yield)?;
+            assert_eq!(num_errors, 0);
+            assert!(paths.contains(&PathBuf::from("link")));
+            assert!(!paths.contains(&Path::new("link").join("e")));
+            Ok(())
+        }
+
+        #[test]
+        fn skip_ignores_links_entirely() -> Result {
+            let (paths, num_errors) = walk_with(walk::Symlinks::Skip)?;
+            assert_eq!(num_errors, 0);
+            assert!(!paths.iter().any(|p| p.starts_with("link")));
+            assert_eq!(paths.len(), 5);
+            Ok(())
+        }
+    }
+}
@@ -11,3 +11,32 @@ fn size_of_sha1() {
 fn size_of_sha1() {
     assert_eq!(std::mem::size_of::<Sha1>(), 104)
 }
+
+mod tee {
+    use git_features::hash;
+    use std::io::{Read, Write};
+
+    const DATA: &[u8] = b"hello world";
+    const DATA_SHA1: &str = "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed";
+
+    #[test]
+    fn write_hashes_what_passes_through() -> std::io::Result<()> {
+        let mut out = hash::Write::new(Vec::new(), git_hash::Kind::Sha1);
+        out.write_all(&DATA[..5])?;
+        out.write_all(&DATA[5..])?;
+        assert_eq!(out.inner, DATA);
+        assert_eq!(git_hash::ObjectId::from(out.hash.digest()).to_string(), DATA_SHA1);
+        Ok(())
+    }
+
+    #[test]
+    fn read_hashes_what_passes_through() -> std::io::Result<()> {
+        let mut input = hash::Read::new(DATA, git_hash::Kind::Sha1);
+        let mut buf = Vec::new();
+        std::io::copy(&mut (&mut input).take(5), &mut buf)?;
+        input.read_to_end(&mut buf)?;
+        assert_eq!(buf, DATA);
+        assert_eq!(git_hash::ObjectId::from(input.hash.digest()).to_string(), DATA_SHA1);
+        Ok(())
+    }
+}
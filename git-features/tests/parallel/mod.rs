@@ -99,3 +99,115 @@ fn stepped_reduce_finalize() {
 
     assert_eq!(iter.finalize().expect("success"), 100);
 }
+
+fn id(n: usize) -> git_hash::ObjectId {
+    let mut bytes = [0u8; 20];
+    bytes[..8].copy_from_slice(&(n as u64).to_be_bytes());
+    bytes.into()
+}
+
+#[test]
+fn in_parallel_with_tasks_processes_spawned_tasks() {
+    let seen = parallel::ShardedSet::default();
+    let states = parallel::in_parallel_with_tasks(
+        vec![1usize],
+        None,
+        |_n| 0usize,
+        |task, count, spawn| {
+            assert!(seen.insert(id(task)), "each task is processed once");
+            *count += 1;
+            if task < 512 {
+                spawn(task * 2);
+                spawn(task * 2 + 1);
+            }
+            Ok::<_, ()>(())
+        },
+    )
+    .expect("successful computation");
+    assert_eq!(
+        states.iter().sum::<usize>(),
+        1023,
+        "a full binary tree of depth 10 was traversed"
+    );
+    assert_eq!(seen.len(), 1023);
+    assert!(seen.contains(&id(1023)) && !seen.contains(&id(1024)));
+}
+
+#[test]
+fn in_parallel_with_tasks_stops_at_first_error() {
+    let res = parallel::in_parallel_with_tasks(
+        0..100usize,
+        None,
+        |_n| (),
+        |task, _state, _spawn| if task == 50 { Err(task) } else { Ok(()) },
+    );
+    assert_eq!(res.map(|_| ()), Err(50));
+}
+
+#[test]
+fn in_parallel_with_tasks_finishes_without_tasks() {
+    let states = parallel::in_parallel_with_tasks(
+        None::<usize>,
+        Some(4),
+        |_n| 0usize,
+        |_task, _count, _spawn| Ok::<_, ()>(()),
+    )
+    .expect("successful computation");
+    assert_eq!(states.iter().sum::<usize>(), 0, "there was nothing to do");
+}
+
+#[test]
+fn in_parallel_with_tasks_on_a_single_thread_processes_tasks_in_spawn_order() {
+    let states = parallel::in_parallel_with_tasks(
+        vec![1usize],
+        Some(1),
+        |_n| Vec::new(),
+        |task, order, spawn| {
+            order.push(task);
+            if task < 4 {
+                spawn(task * 2);
+                spawn(task * 2 + 1);
+            }
+            Ok::<_, ()>(())
+        },
+    )
+    .expect("successful computation");
+    assert_eq!(states, vec![vec![1, 2, 3, 4, 5, 6, 7]], "breadth-first order");
+}
+
+mod sharded {
+    use super::id;
+    use git_features::parallel::{ShardKey, ShardedMap, ShardedSet};
+
+    #[test]
+    fn set_insert_and_contains() {
+        let set = ShardedSet::with_shards(3);
+        assert!(set.is_empty());
+        assert!(set.insert(id(1)));
+        assert!(!set.insert(id(1)), "items are only inserted once");
+        assert!(set.insert(id(2)));
+        assert!(set.contains(&id(1)) && set.contains(id(2).as_ref()) && !set.contains(&id(3)));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn map_insert_get_and_remove() {
+        let map = ShardedMap::default();
+        assert_eq!(map.insert(id(1), "a"), None);
+        assert_eq!(map.insert(id(1), "b"), Some("a"), "previous values are returned");
+        assert_eq!(map.insert(id(2), "c"), None);
+        assert_eq!(map.get(id(1).as_ref()), Some("b"));
+        assert_eq!(map.remove(&id(2)), Some("c"));
+        assert!(!map.contains_key(&id(2)));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.into_inner().into_iter().collect::<Vec<_>>(), vec![(id(1), "b")]);
+    }
+
+    #[test]
+    fn object_ids_are_sharded_by_their_leading_bytes() {
+        let id = git_hash::ObjectId::from_hex(b"0102ffffffffffffffffffffffffffffffffffff").expect("valid hex");
+        assert_eq!(id.shard_index(1), 0);
+        assert_eq!(id.shard_index(256), 2);
+        assert_eq!(id.shard_index(1 << 16), 0x0102);
+    }
+}
@@ -114,3 +114,110 @@ mod io {
         assert_eq!(block_count * BLOCK_SIZE, bytes_read);
     }
 }
+
+mod bounded {
+    use git_features::io;
+    use std::io::{ErrorKind, Read, Write};
+
+    #[test]
+    fn writes_are_limited_to_the_capacity() {
+        let (mut writer, mut reader) = io::pipe::bounded(4);
+        assert_eq!(
+            writer.write(b"hello").expect("space for one chunk"),
+            4,
+            "partial writes"
+        );
+        let mut buf = [0u8; 8];
+        assert_eq!(reader.read(&mut buf[..4]).expect("data available"), 4);
+        assert_eq!(&buf[..4], b"hell");
+        assert_eq!(writer.write(b"o").expect("space again"), 1);
+    }
+
+    #[test]
+    fn write_all_applies_backpressure_until_everything_is_read() {
+        let (mut writer, mut reader) = io::pipe::bounded(3);
+        let message = b"a message larger than the capacity";
+        let handle = std::thread::spawn(move || writer.write_all(message));
+
+        let mut received = Vec::new();
+        reader.read_to_end(&mut received).expect("reading works");
+        assert_eq!(received, &message[..]);
+        handle.join().expect("no panic").expect("writes succeed");
+    }
+
+    #[test]
+    fn errors_are_received_after_data_and_lack_of_reader_is_a_broken_pipe() {
+        let (mut writer, mut reader) = io::pipe::bounded(8);
+        let handle = std::thread::spawn(move || -> std::io::Result<_> {
+            writer.write_all(b"data")?;
+            writer.send_error(std::io::Error::new(ErrorKind::Other, "the error"))?;
+            Ok(writer)
+        });
+
+        let mut buf = [0u8; 8];
+        assert_eq!(reader.read(&mut buf).expect("data is read first"), 4);
+        assert_eq!(reader.read(&mut buf).unwrap_err().to_string(), "the error");
+        let mut writer = handle.join().expect("no panic").expect("reader is present");
+
+        drop(reader);
+        assert_eq!(
+            writer
+                .send_error(std::io::Error::new(ErrorKind::Other, "the error"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::BrokenPipe
+        );
+        assert_eq!(writer.write(b"more").unwrap_err().kind(), ErrorKind::BrokenPipe);
+    }
+}
+
+mod bidirectional {
+    use git_features::io;
+    use std::io::{BufRead, Read, Write};
+
+    #[test]
+    fn peers_taking_turns_from_a_single_thread_each() {
+        let (mut client, mut server) = io::pipe::bidirectional(64);
+        let server = std::thread::spawn(move || -> std::io::Result<()> {
+            let mut line = String::new();
+            while server.read_line(&mut line)? != 0 {
+                server.write_all(format!("ack {}", line).as_bytes())?;
+                line.clear();
+            }
+            Ok(())
+        });
+
+        let mut buf = [0u8; 10];
+        for request in &["one\n", "two\n"] {
+            client.write_all(request.as_bytes()).expect("server is present");
+            let n = client.read(&mut buf).expect("a response");
+            assert_eq!(&buf[..n], format!("ack {}", request).as_bytes());
+        }
+        drop(client);
+        server.join().expect("no panic").expect("no error");
+    }
+
+    #[test]
+    fn ends_can_be_split_to_write_concurrently() {
+        let (a, b) = io::pipe::bidirectional(2);
+        let payload = vec![42u8; 100];
+        let threads = vec![a, b]
+            .into_iter()
+            .map(|end| {
+                let io::pipe::End { mut writer, mut reader } = end;
+                let payload = payload.clone();
+                (
+                    std::thread::spawn(move || writer.write_all(&payload)),
+                    std::thread::spawn(move || {
+                        let mut received = Vec::new();
+                        reader.read_to_end(&mut received).map(|_| received)
+                    }),
+                )
+            })
+            .collect::<Vec<_>>();
+        for (write, read) in threads {
+            write.join().expect("no panic").expect("write success");
+            assert_eq!(read.join().expect("no panic").expect("read success"), payload);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+mod format {
+    use git_features::progress::format;
+
+    #[test]
+    fn bytes_use_binary_prefixes() {
+        assert_eq!(format::bytes(0), "0 B");
+        assert_eq!(format::bytes(1023), "1023 B");
+        assert_eq!(format::bytes(1024), "1.0 KiB");
+        assert_eq!(format::bytes(1536), "1.5 KiB");
+        assert_eq!(format::bytes(42 * 1024 * 1024), "42.0 MiB");
+        assert_eq!(format::bytes(3 * 1024 * 1024 * 1024 * 1024), "3.0 TiB");
+        assert_eq!(format::bytes(u64::MAX), "16.0 EiB");
+    }
+
+    #[test]
+    fn counts_use_thousands_separators() {
+        assert_eq!(format::count(0), "0");
+        assert_eq!(format::count(999), "999");
+        assert_eq!(format::count(1000), "1,000");
+        assert_eq!(format::count(12_345), "12,345");
+        assert_eq!(format::count(1_234_567), "1,234,567");
+        assert_eq!(format::count(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn percentages_have_one_decimal_and_need_a_total() {
+        assert_eq!(format::percentage(0, 10).as_deref(), Some("0.0%"));
+        assert_eq!(format::percentage(421, 1000).as_deref(), Some("42.1%"));
+        assert_eq!(format::percentage(1, 1).as_deref(), Some("100.0%"));
+        assert_eq!(format::percentage(1, 0), None);
+    }
+}
+
+#[cfg(feature = "progress-tree")]
+mod snapshot {
+    use git_features::progress::{self, snapshot};
+
+    #[test]
+    fn tasks_are_listed_in_order_of_hierarchy_with_their_progress() {
+        let tree = progress::Tree::new();
+        let mut observer = snapshot::Observer::new(tree.clone());
+        assert!(observer.snapshot().is_empty(), "there are no tasks yet");
+
+        let mut parent = tree.add_child("pack");
+        let mut child = parent.add_child("counting");
+        child.init(Some(10), progress::count("objects"));
+        child.set(3);
+        let mut unbounded = parent.add_child("reading");
+        unbounded.init(None, None);
+        unbounded.set(42);
+
+        assert_eq!(
+            observer.snapshot(),
+            vec![
+                snapshot::Task {
+                    level: 1,
+                    name: "pack".into(),
+                    progress: None,
+                },
+                snapshot::Task {
+                    level: 2,
+                    name: "counting".into(),
+                    progress: Some(snapshot::Value {
+                        step: 3,
+                        done_at: Some(10),
+                        unit: Some("objects".into()),
+                        throughput_per_second: None,
+                        display: "3/10 objects [30.0%]".into(),
+                        state: snapshot::State::Running,
+                    }),
+                },
+                snapshot::Task {
+                    level: 2,
+                    name: "reading".into(),
+                    progress: Some(snapshot::Value {
+                        step: 42,
+                        done_at: None,
+                        unit: None,
+                        throughput_per_second: None,
+                        display: "42".into(),
+                        state: snapshot::State::Running,
+                    }),
+                },
+            ]
+        );
+
+        drop(child);
+        assert_eq!(
+            observer.snapshot().len(),
+            2,
+            "finished tasks are removed from the tree and thus from snapshots"
+        );
+    }
+}
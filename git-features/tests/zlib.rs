@@ -0,0 +1,66 @@
+mod inflate_chunk {
+    use git_features::zlib::{inflate, stream::deflate, Inflate};
+    use std::io::Write;
+
+    fn compressed(data: &[u8]) -> Vec<u8> {
+        let mut w = deflate::Write::new(Vec::new());
+        w.write_all(data).expect("write to memory");
+        w.flush().expect("flush to memory");
+        w.into_inner()
+    }
+
+    fn data() -> Vec<u8> {
+        (0..10_000u32).flat_map(|n| n.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn bounded_output_in_fixed_size_chunks() -> Result<(), Box<dyn std::error::Error>> {
+        let data = data();
+        let input = compressed(&data);
+        let mut inflate = Inflate::default();
+        let (mut input_ofs, mut out, mut buf) = (0, Vec::new(), [0u8; 512]);
+        loop {
+            let chunk = inflate.chunk(&input[input_ofs..], &mut buf)?;
+            input_ofs += chunk.consumed_in;
+            out.extend_from_slice(&buf[..chunk.written_out]);
+            match chunk.state {
+                inflate::State::NeedsOutput => continue,
+                inflate::State::Done => break,
+                inflate::State::NeedsInput => unreachable!("all input is available"),
+            }
+        }
+        assert_eq!(out, data);
+        assert_eq!(input_ofs, input.len(), "the whole stream was consumed");
+        Ok(())
+    }
+
+    #[test]
+    fn needs_input_is_distinguished_from_corruption() -> Result<(), Box<dyn std::error::Error>> {
+        let data = data();
+        let input = compressed(&data);
+        let mut out = vec![0; data.len()];
+        let mut inflate = Inflate::default();
+
+        let half = input.len() / 2;
+        let chunk = inflate.chunk(&input[..half], &mut out)?;
+        assert_eq!(chunk.state, inflate::State::NeedsInput);
+        assert_eq!(chunk.consumed_in, half);
+        let written = chunk.written_out;
+        let chunk = inflate.chunk(&input[half..], &mut out[written..])?;
+        assert_eq!(chunk.state, inflate::State::Done);
+        assert_eq!(out, data);
+
+        inflate.reset();
+        let mut corrupt = input.clone();
+        corrupt[0] ^= 0xff;
+        assert!(
+            inflate.chunk(&corrupt, &mut out).is_err(),
+            "an invalid header is a corrupt stream"
+        );
+
+        inflate.reset();
+        let chunk = inflate.chunk(&input, &mut out)?;
+        assert_eq!(chunk.state, inflate::State::Done, "reset recovers from errors");
+        Ok(())
+    }
+}
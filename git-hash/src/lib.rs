@@ -25,6 +25,18 @@ pub mod decode {
             InvalidHexEncodingLength(length: usize) {
                 display("A hash sized {} hexadecimal characters is invalid", length)
             }
+            Invalid(c: char, index: usize) {
+                display("Invalid character '{}' at position {}", c, index)
+            }
+        }
+    }
+
+    fn to_error(err: hex::FromHexError, length: usize) -> Error {
+        match err {
+            hex::FromHexError::InvalidHexCharacter { c, index } => Error::Invalid(c, index),
+            hex::FromHexError::OddLength | hex::FromHexError::InvalidStringLength => {
+                Error::InvalidHexEncodingLength(length)
+            }
         }
     }
 
@@ -37,7 +49,7 @@ pub mod decode {
             use hex::FromHex;
             match buffer.len() {
                 40 => Ok(ObjectId::Sha1(
-                    <[u8; 20]>::from_hex(buffer).expect("our length check is correct thus we can decode hex"),
+                    <[u8; 20]>::from_hex(buffer).map_err(|err| to_error(err, 40))?,
                 )),
                 len => Err(Error::InvalidHexEncodingLength(len)),
             }
@@ -51,7 +63,7 @@ pub mod decode {
             use hex::FromHex;
             match s.len() {
                 40 => Ok(ObjectId::Sha1(
-                    <[u8; 20]>::from_hex(s).expect("our length check is correct thus we can decode hex"),
+                    <[u8; 20]>::from_hex(s).map_err(|err| to_error(err, 40))?,
                 )),
                 len => Err(Error::InvalidHexEncodingLength(len)),
             }
@@ -4,6 +4,7 @@ use std::{borrow::Borrow, fmt, io, ops::Deref};
 /// An owned hash identifying objects, most commonly Sha1
 #[derive(PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ObjectId {
     Sha1([u8; SIZE_OF_SHA1_DIGEST]),
 }
@@ -1,8 +1,9 @@
-use crate::{backoff, File, Marker, DOT_LOCK_SUFFIX};
+use crate::{backoff, stale, File, Marker, DOT_LOCK_SUFFIX};
 use git_tempfile::{AutoRemove, ContainingDirectory};
 use quick_error::quick_error;
 use std::{
     fmt,
+    io::Write,
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -34,6 +35,31 @@ impl fmt::Display for Fail {
     }
 }
 
+/// Describe how to acquire a lock, as passed to [`File::acquire_to_update_resource()`] and
+/// [`Marker::acquire_to_hold_resource()`].
+///
+/// It can be created from [`Fail`] to use the default for everything else.
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Options {
+    /// What to do if the lock is held elsewhere.
+    pub fail: Fail,
+    /// If true, locks which are [known to be stale][stale::Status::Dead] are removed and the acquisition is retried.
+    ///
+    /// This is inherently racy if multiple processes try to break the same stale lock at the same time, and
+    /// each of them may end up believing it holds the lock. Only enable it if this can't happen, for example
+    /// because lock acquisitions are serialized by other means.
+    pub break_stale_locks: bool,
+}
+
+impl From<Fail> for Options {
+    fn from(fail: Fail) -> Self {
+        Options {
+            fail,
+            ..Default::default()
+        }
+    }
+}
+
 quick_error! {
     /// The error returned when acquiring a [`File`] or [`Marker`].
     #[derive(Debug)]
@@ -52,15 +78,16 @@ quick_error! {
 
 impl File {
     /// Create a writable lock file with failure `mode` whose content will eventually overwrite the given resource `at_path`.
+    /// `mode` may also be [`Options`] to break stale locks.
     ///
     /// If `boundary_directory` is given, non-existing directories will be created automatically and removed in the case of
     /// a rollback. Otherwise the containing directory is expected to exist, even though the resource doesn't have to.
     pub fn acquire_to_update_resource(
         at_path: impl AsRef<Path>,
-        mode: Fail,
+        mode: impl Into<Options>,
         boundary_directory: Option<PathBuf>,
     ) -> Result<File, Error> {
-        let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode, boundary_directory, |p, d, c| {
+        let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode.into(), boundary_directory, |p, d, c| {
             git_tempfile::writable_at(p, d, c)
         })?;
         Ok(File {
@@ -76,13 +103,17 @@ impl Marker {
     ///
     /// If `boundary_directory` is given, non-existing directories will be created automatically and removed in the case of
     /// a rollback.
+    ///
+    /// The marker records the [owner][stale::Owner] of the lock to allow others to detect if it's stale.
     pub fn acquire_to_hold_resource(
         at_path: impl AsRef<Path>,
-        mode: Fail,
+        mode: impl Into<Options>,
         boundary_directory: Option<PathBuf>,
     ) -> Result<Marker, Error> {
-        let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode, boundary_directory, |p, d, c| {
-            git_tempfile::mark_at(p, d, c)
+        let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode.into(), boundary_directory, |p, d, c| {
+            let mut handle = git_tempfile::writable_at(p, d, c)?;
+            handle.with_mut(|f| f.write_all(&stale::Owner::current().to_bytes()))??;
+            handle.close()
         })?;
         Ok(Marker {
             created_from_file: false,
@@ -104,13 +135,26 @@ fn dir_cleanup(boundary: Option<PathBuf>) -> (ContainingDirectory, AutoRemove) {
 
 fn lock_with_mode<T>(
     resource: &Path,
-    mode: Fail,
+    Options {
+        fail: mode,
+        break_stale_locks,
+    }: Options,
     boundary_directory: Option<PathBuf>,
     try_lock: impl Fn(&Path, ContainingDirectory, AutoRemove) -> std::io::Result<T>,
 ) -> Result<(PathBuf, T), Error> {
     use std::io::ErrorKind::*;
     let (directory, cleanup) = dir_cleanup(boundary_directory);
     let lock_path = add_lock_suffix(resource);
+    let try_lock = |lock_path: &Path, directory: ContainingDirectory, cleanup: AutoRemove| match try_lock(
+        lock_path,
+        directory,
+        cleanup.clone(),
+    ) {
+        Err(err) if err.kind() == AlreadyExists && break_stale_locks && remove_stale_lock(lock_path)? => {
+            try_lock(lock_path, directory, cleanup)
+        }
+        res => res,
+    };
     let mut attempts = 1;
     match mode {
         Fail::Immediately => try_lock(&lock_path, directory, cleanup),
@@ -140,6 +184,21 @@ fn lock_with_mode<T>(
     })
 }
 
+/// Remove the lock at `lock_path` if it's stale and return true, or return false if it's not known to be stale.
+fn remove_stale_lock(lock_path: &Path) -> std::io::Result<bool> {
+    match stale::status(lock_path) {
+        Ok(status) if status.is_stale() => match std::fs::remove_file(lock_path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(true),
+            Err(err) => Err(err),
+        },
+        Ok(_) => Ok(false),
+        // the lock was released in the meantime, so let's try again
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(true),
+        Err(err) => Err(err),
+    }
+}
+
 fn add_lock_suffix(resource_path: &Path) -> PathBuf {
     resource_path.with_extension(resource_path.extension().map_or_else(
         || DOT_LOCK_SUFFIX.chars().skip(1).collect(),
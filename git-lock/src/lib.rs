@@ -8,6 +8,7 @@
 //! * consistent naming of lock files
 //! * block the thread (with timeout) or fail immediately if a lock cannot be obtained right away
 //! * commit lock files to atomically put them into the location of the originally locked file
+//! * detect and optionally break [stale] locks left behind by processes that died
 //!
 //! # Limitations
 //!
@@ -25,6 +26,7 @@ pub mod acquire;
 mod backoff;
 ///
 pub mod commit;
+pub mod stale;
 
 /// Locks a resource to eventually be overwritten with the content of this file.
 ///
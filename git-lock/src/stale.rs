@@ -0,0 +1,204 @@
+//! Detect locks left behind by processes that died without cleaning up after themselves.
+//!
+//! [Markers][crate::Marker] record their owner, that is the id of the process holding them along with the time at which
+//! the system was booted, which allows to tell if the owning process is still alive. Lock [files][crate::File] contain
+//! the future content of their resource and thus can't carry this information, nor can locks created by other implementations.
+//!
+//! # Limitations
+//!
+//! * Whether or not a process is alive can currently only be determined on Linux, everywhere else the [status][Status]
+//!   of locks is [unknown][Status::Unknown].
+//! * Processes in other PID namespaces, like other containers, appear to be dead even if they are alive. Only break stale
+//!   locks if the repository isn't shared that way.
+use std::{io, path::Path};
+
+/// The prefix of the owner information written into markers, distinguishing it from content written into lock files.
+const OWNER_PREFIX: &[u8] = b"git-lock pid ";
+/// The boot time as reported by the system may vary by a few seconds, which shouldn't be mistaken for a reboot.
+const BOOT_TIME_TOLERANCE_IN_SECONDS: u64 = 5;
+
+/// The process which created a lock.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Owner {
+    /// The id of the process holding the lock.
+    pub pid: u32,
+    /// The time at which the system was booted in seconds since UNIX epoch, or `None` if it can't be determined.
+    ///
+    /// It's used to detect locks created before the last reboot, whose `pid` may have been reused since.
+    pub boot_time: Option<u64>,
+}
+
+/// The status of a lock as determined by [`status()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Status {
+    /// The owner of the lock is still alive, or at least a process with the same id.
+    Alive(Owner),
+    /// The owner of the lock is dead, which makes the lock stale and safe to remove.
+    Dead(Owner),
+    /// It's unknown if the lock is held by a live process as it doesn't record its owner, or because process liveness
+    /// can't be determined on this platform.
+    Unknown,
+}
+
+impl Status {
+    /// Returns true if the lock is known to be stale.
+    pub fn is_stale(&self) -> bool {
+        matches!(self, Status::Dead(_))
+    }
+}
+
+impl Owner {
+    /// Return the owner information for the current process.
+    pub fn current() -> Self {
+        Owner {
+            pid: std::process::id(),
+            boot_time: boot_time(),
+        }
+    }
+
+    /// Parse the owner information previously written by [`to_bytes()`][Owner::to_bytes()] from `data`, or return `None`
+    /// if it isn't in the expected format.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let data = data.strip_prefix(OWNER_PREFIX)?.strip_suffix(b"\n")?;
+        let data = std::str::from_utf8(data).ok()?;
+        let mut tokens = data.split(' ');
+        let pid = tokens.next()?.parse().ok()?;
+        let boot_time = match (tokens.next()?, tokens.next()?) {
+            ("boot-time", "unknown") => None,
+            ("boot-time", time) => Some(time.parse().ok()?),
+            _ => return None,
+        };
+        if tokens.next().is_some() {
+            return None;
+        }
+        Some(Owner { pid, boot_time })
+    }
+
+    /// Serialize this instance as written into markers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = OWNER_PREFIX.to_vec();
+        out.extend_from_slice(
+            format!(
+                "{} boot-time {}\n",
+                self.pid,
+                self.boot_time.map_or_else(|| "unknown".into(), |t| t.to_string())
+            )
+            .as_bytes(),
+        );
+        out
+    }
+
+    /// Read the owner of the lock at `lock_path`, or return `None` if it doesn't record one.
+    pub fn from_lock(lock_path: impl AsRef<Path>) -> io::Result<Option<Self>> {
+        let lock_path = lock_path.as_ref();
+        // Larger files can't be markers and may be big lock files we don't want to read.
+        if std::fs::metadata(lock_path)?.len() > 64 {
+            return Ok(None);
+        }
+        Ok(Self::from_bytes(&std::fs::read(lock_path)?))
+    }
+}
+
+/// Determine if the lock at `lock_path` is held by a process which is still alive.
+pub fn status(lock_path: impl AsRef<Path>) -> io::Result<Status> {
+    Ok(match Owner::from_lock(lock_path)? {
+        Some(owner) => owner_status(owner, Owner::current()),
+        None => Status::Unknown,
+    })
+}
+
+fn owner_status(owner: Owner, current: Owner) -> Status {
+    if let (Some(owner_boot_time), Some(boot_time)) = (owner.boot_time, current.boot_time) {
+        if owner_boot_time.max(boot_time) - owner_boot_time.min(boot_time) > BOOT_TIME_TOLERANCE_IN_SECONDS {
+            return Status::Dead(owner);
+        }
+    }
+    if owner.pid == current.pid {
+        return Status::Alive(owner);
+    }
+    match is_alive(owner.pid) {
+        Some(true) => Status::Alive(owner),
+        Some(false) => Status::Dead(owner),
+        None => Status::Unknown,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_alive(pid: u32) -> Option<bool> {
+    let proc = Path::new("/proc");
+    proc.is_dir().then(|| proc.join(pid.to_string()).exists())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_alive(_pid: u32) -> Option<bool> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn boot_time() -> Option<u64> {
+    let stat = std::fs::read_to_string("/proc/stat").ok()?;
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|time| time.trim().parse().ok())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn boot_time() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("time after epoch")
+            .as_secs()
+    }
+
+    #[test]
+    fn owner_roundtrips_through_bytes() {
+        for boot_time in &[None, Some(now())] {
+            let owner = Owner {
+                pid: 42,
+                boot_time: *boot_time,
+            };
+            assert_eq!(Owner::from_bytes(&owner.to_bytes()), Some(owner));
+        }
+    }
+
+    #[test]
+    fn lock_file_content_is_not_mistaken_for_an_owner() {
+        assert_eq!(Owner::from_bytes(b"4b825dc642cb6eb9a060e54bf8d69288fbee4904\n"), None);
+        assert_eq!(Owner::from_bytes(b""), None);
+        assert_eq!(Owner::from_bytes(b"git-lock pid 42 boot-time 10 extra\n"), None);
+    }
+
+    #[test]
+    fn owners_from_before_a_reboot_are_dead() {
+        let current = Owner {
+            pid: 1,
+            boot_time: Some(now()),
+        };
+        let owner = Owner {
+            pid: 1,
+            boot_time: Some(now() - 3600),
+        };
+        assert_eq!(owner_status(owner, current), Status::Dead(owner));
+    }
+
+    #[test]
+    fn the_current_process_is_alive_even_if_the_boot_time_varies_slightly() {
+        let current = Owner {
+            pid: 1,
+            boot_time: Some(now()),
+        };
+        let owner = Owner {
+            pid: 1,
+            boot_time: current.boot_time.map(|t| t - 1),
+        };
+        assert_eq!(owner_status(owner, current), Status::Alive(owner));
+    }
+}
@@ -41,6 +41,73 @@ mod acquire {
         assert!(err_str.contains("the-resource.lock"), "it mentions the lockfile itself");
         Ok(())
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn markers_record_their_owner_to_be_recognized_as_alive() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("the-resource");
+        let guard = git_lock::Marker::acquire_to_hold_resource(&resource, Fail::Immediately, None)?;
+        let status = git_lock::stale::status(guard.lock_path())?;
+        assert_eq!(
+            status,
+            git_lock::stale::Status::Alive(git_lock::stale::Owner::current())
+        );
+
+        let options = git_lock::acquire::Options {
+            fail: Fail::Immediately,
+            break_stale_locks: true,
+        };
+        assert!(
+            git_lock::Marker::acquire_to_hold_resource(&resource, options, None).is_err(),
+            "locks of live processes are never broken"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn stale_locks_are_broken_only_if_configured() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("the-resource");
+        let lock_path = dir.path().join("the-resource.lock");
+        let owner_before_reboot = git_lock::stale::Owner {
+            pid: std::process::id(),
+            boot_time: Some(1),
+        };
+        std::fs::write(&lock_path, owner_before_reboot.to_bytes())?;
+        assert!(git_lock::stale::status(&lock_path)?.is_stale());
+
+        assert!(git_lock::Marker::acquire_to_hold_resource(&resource, Fail::Immediately, None).is_err());
+        let guard = git_lock::Marker::acquire_to_hold_resource(
+            &resource,
+            git_lock::acquire::Options {
+                fail: Fail::Immediately,
+                break_stale_locks: true,
+            },
+            None,
+        )?;
+        assert_eq!(guard.lock_path(), lock_path);
+        assert!(!git_lock::stale::status(&lock_path)?.is_stale(), "the lock is ours now");
+        Ok(())
+    }
+
+    #[test]
+    fn locks_without_owner_are_never_broken() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("the-resource");
+        std::fs::write(dir.path().join("the-resource.lock"), b"new content")?;
+        assert_eq!(
+            git_lock::stale::status(dir.path().join("the-resource.lock"))?,
+            git_lock::stale::Status::Unknown
+        );
+        let options = git_lock::acquire::Options {
+            fail: Fail::Immediately,
+            break_stale_locks: true,
+        };
+        assert!(git_lock::File::acquire_to_update_resource(&resource, options, None).is_err());
+        Ok(())
+    }
 }
 mod commit {
     use git_lock::acquire::Fail;
@@ -0,0 +1,11 @@
+#![no_main]
+
+use git_object::immutable;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(commit) = immutable::Commit::from_bytes(data) {
+        let _a = commit.decode_message();
+    }
+    let _b = immutable::CommitIter::from_bytes(data).count();
+});
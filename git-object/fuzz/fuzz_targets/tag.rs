@@ -0,0 +1,9 @@
+#![no_main]
+
+use git_object::immutable;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _a = immutable::Tag::from_bytes(data);
+    let _b = immutable::TagIter::from_bytes(data).count();
+});
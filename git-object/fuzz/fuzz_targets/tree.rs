@@ -0,0 +1,10 @@
+#![no_main]
+
+use git_object::immutable;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _a = immutable::Tree::from_bytes(data);
+    let _b = immutable::Tree::from_bytes_strict(data);
+    let _c = immutable::TreeIter::from_bytes(data).count();
+});
@@ -0,0 +1,13 @@
+/// The amount of bytes at the beginning of a blob which git looks at to determine if it's binary.
+pub const BINARY_PEEK_LEN: usize = 8000;
+
+/// Return `true` if `data` is considered binary, which is the case if it contains a NUL byte within the first
+/// [`BINARY_PEEK_LEN`] bytes, as determined by git when deciding on text conversions or how to diff.
+pub fn is_binary(data: &[u8]) -> bool {
+    is_binary_with_peek_len(data, BINARY_PEEK_LEN)
+}
+
+/// Like [`is_binary()`], but look for a NUL byte within the first `peek_len` bytes of `data` instead.
+pub fn is_binary_with_peek_len(data: &[u8], peek_len: usize) -> bool {
+    data[..peek_len.min(data.len())].contains(&0)
+}
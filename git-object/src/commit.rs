@@ -34,8 +34,84 @@ where
         self.find_all("mergetag").map(|b| immutable::Tag::from_bytes(b))
     }
 
-    /// Return the cryptographic signature provided by gpg/pgp verbatim.
+    /// Return the first cryptographic signature provided by gpg/pgp verbatim, no matter which of the
+    /// [signature fields][PGP_SIGNATURE_FIELD_NAMES] it is stored in.
     pub fn pgp_signature(self) -> Option<&'a BStr> {
-        self.find("gpgsig")
+        self.pgp_signatures().next().map(|(_, signature)| signature)
+    }
+
+    /// Return an iterator over all cryptographic signatures provided by gpg/pgp verbatim along with the name of
+    /// their [signature field][PGP_SIGNATURE_FIELD_NAMES], in order of appearance.
+    ///
+    /// Commits may be signed multiple times, like with `gpgsig` for their SHA1 and `gpgsig-sha256` for their SHA256 representation.
+    pub fn pgp_signatures(self) -> impl Iterator<Item = (&'a BStr, &'a BStr)> {
+        self.inner.filter(|(k, _)| is_pgp_signature_field(k))
+    }
+}
+
+/// The names of header fields which contain a cryptographic signature of the commit.
+pub const PGP_SIGNATURE_FIELD_NAMES: &[&str] = &["gpgsig", "gpgsig-sha256"];
+
+pub(crate) fn is_pgp_signature_field(name: &BStr) -> bool {
+    PGP_SIGNATURE_FIELD_NAMES.iter().any(|field| name == field.as_bytes())
+}
+
+///
+pub mod message {
+    use bstr::BStr;
+    use std::borrow::Cow;
+
+    /// Decode the `message` of a commit into a string using its declared `encoding`, with UTF-8 being assumed if it is unset.
+    ///
+    /// UTF-8 and ISO-8859-1 are always supported, and with the `encoding` feature all other encodings known to the
+    /// [WHATWG Encoding Standard](https://encoding.spec.whatwg.org/#names-and-labels) are supported as well.
+    /// If the `encoding` is unknown or the `message` isn't valid in it, it's decoded as UTF-8 if possible or as ISO-8859-1
+    /// otherwise, which can't fail and is lossless as each byte is mapped to exactly one character.
+    pub fn decode<'a>(message: &'a BStr, encoding: Option<&BStr>) -> Cow<'a, str> {
+        let message: &[u8] = message.as_ref();
+        match encoding.map(|name| trim_ascii_whitespace(name.as_ref())) {
+            None => utf8_or_latin1(message),
+            Some(name) if is_label_of(name, &["utf-8", "utf8"]) => utf8_or_latin1(message),
+            Some(name)
+                if is_label_of(
+                    name,
+                    &["iso-8859-1", "iso8859-1", "iso_8859-1", "latin1", "latin-1", "l1"],
+                ) =>
+            {
+                latin1(message).into()
+            }
+            Some(name) => decode_with(name, message).unwrap_or_else(|| utf8_or_latin1(message)),
+        }
+    }
+
+    fn trim_ascii_whitespace(name: &[u8]) -> &[u8] {
+        let start = name.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(name.len());
+        let end = name
+            .iter()
+            .rposition(|b| !b.is_ascii_whitespace())
+            .map_or(start, |pos| pos + 1);
+        &name[start..end]
+    }
+
+    fn is_label_of(name: &[u8], labels: &[&str]) -> bool {
+        labels.iter().any(|label| name.eq_ignore_ascii_case(label.as_bytes()))
+    }
+
+    fn utf8_or_latin1(message: &[u8]) -> Cow<'_, str> {
+        std::str::from_utf8(message).map_or_else(|_| latin1(message).into(), Cow::Borrowed)
+    }
+
+    fn latin1(message: &[u8]) -> String {
+        message.iter().map(|b| *b as char).collect()
+    }
+
+    #[cfg(feature = "encoding")]
+    fn decode_with<'a>(name: &[u8], message: &'a [u8]) -> Option<Cow<'a, str>> {
+        encoding_rs::Encoding::for_label(name)?.decode_without_bom_handling_and_without_replacement(message)
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    fn decode_with<'a>(_name: &[u8], _message: &'a [u8]) -> Option<Cow<'a, str>> {
+        None
     }
 }
@@ -13,4 +13,9 @@ impl<'a> Blob<'a> {
     pub fn from_bytes(data: &[u8]) -> Result<Blob<'_>, Infallible> {
         Ok(Blob { data })
     }
+
+    /// Return `true` if our data is considered binary, see [`blob::is_binary()`][crate::blob::is_binary()].
+    pub fn is_binary(&self) -> bool {
+        crate::blob::is_binary(self.data)
+    }
 }
@@ -1,7 +1,10 @@
 use smallvec::SmallVec;
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::Range};
 
-use crate::{immutable::object, BStr};
+use crate::{
+    immutable::{object, parse},
+    BStr,
+};
 
 mod decode;
 
@@ -37,6 +40,34 @@ pub struct Commit<'a> {
     pub extra_headers: Vec<(&'a BStr, Cow<'a, BStr>)>,
 }
 
+/// The byte ranges of the fields of a [`Commit`] within the data it was parsed from, as returned by
+/// [`Commit::from_bytes_with_ranges()`].
+///
+/// These allow to change a commit by splicing bytes, which keeps everything else exactly as it was.
+/// Header fields cover their value only, without the field name and the trailing newline, unless noted otherwise.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Ranges {
+    /// The hexadecimal hash of the tree.
+    pub tree: Range<usize>,
+    /// The hexadecimal hash of each parent.
+    pub parents: SmallVec<[Range<usize>; 2]>,
+    /// The author signature.
+    pub author: Range<usize>,
+    /// The committer signature.
+    pub committer: Range<usize>,
+    /// The name of the message encoding, if present.
+    pub encoding: Option<Range<usize>>,
+    /// Each extra header field including its name, continuation lines and trailing newline.
+    pub extra_headers: Vec<Range<usize>>,
+    /// Each [signature header field][crate::commit::PGP_SIGNATURE_FIELD_NAMES] including its name, continuation lines and
+    /// trailing newline, which is empty if the commit isn't signed.
+    ///
+    /// These are also contained in `extra_headers`, and can be removed or replaced as a whole.
+    pub signatures: Vec<Range<usize>>,
+    /// The message following the empty line after the headers.
+    pub message: Range<usize>,
+}
+
 impl<'a> Commit<'a> {
     /// Deserialize a commit from the given `data` bytes while avoiding most allocations.
     pub fn from_bytes(data: &'a [u8]) -> Result<Commit<'a>, object::decode::Error> {
@@ -44,6 +75,38 @@ impl<'a> Commit<'a> {
             .map(|(_, t)| t)
             .map_err(object::decode::Error::from)
     }
+
+    /// Like [`from_bytes()`][Commit::from_bytes()], but also return the [byte ranges][Ranges] of all fields within `data`.
+    pub fn from_bytes_with_ranges(data: &'a [u8]) -> Result<(Commit<'a>, Ranges), object::decode::Error> {
+        let commit = Self::from_bytes(data)?;
+        let (headers, body) = parse::header_ranges(data);
+        let mut headers = headers.into_iter();
+        let mut next_value = || headers.next().expect("validated during parsing").value;
+        let tree = next_value();
+        let parents = (0..commit.parents.len()).map(|_| next_value()).collect();
+        let author = next_value();
+        let committer = next_value();
+        let encoding = commit.encoding.map(|_| next_value());
+        let extra_headers: Vec<_> = headers.map(|h| h.field).collect();
+        let signatures = commit
+            .extra_headers
+            .iter()
+            .zip(extra_headers.iter())
+            .filter(|((name, _), _)| crate::commit::is_pgp_signature_field(name))
+            .map(|(_, range)| range.clone())
+            .collect();
+        let ranges = Ranges {
+            tree,
+            parents,
+            author,
+            committer,
+            encoding,
+            extra_headers,
+            signatures,
+            message: body..data.len(),
+        };
+        Ok((commit, ranges))
+    }
     /// Return the `tree` fields hash digest.
     pub fn tree(&self) -> git_hash::ObjectId {
         git_hash::ObjectId::from_hex(self.tree).expect("prior validation of tree hash during parsing")
@@ -56,6 +119,11 @@ impl<'a> Commit<'a> {
             .map(|hex_hash| git_hash::ObjectId::from_hex(hex_hash).expect("prior validation of hashes during parsing"))
     }
 
+    /// Decode the `message` into a string using the declared `encoding`, see [`commit::message::decode()`][crate::commit::message::decode()].
+    pub fn decode_message(&self) -> Cow<'a, str> {
+        crate::commit::message::decode(self.message, self.encoding)
+    }
+
     /// Returns a convenient iterator over all extra headers.
     pub fn extra_headers(&self) -> crate::commit::ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         crate::commit::ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (*k, v.as_ref())))
@@ -9,6 +9,7 @@ use nom::{
 };
 
 use crate::ByteSlice;
+use std::ops::Range;
 
 pub(crate) const NL: &[u8] = b"\n";
 pub(crate) const SPACE: &[u8] = b" ";
@@ -79,3 +80,44 @@ pub(crate) fn signature<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
 ) -> IResult<&'a [u8], git_actor::immutable::Signature<'a>, E> {
     git_actor::immutable::signature::decode(i)
 }
+
+/// The location of a header field within the data of an object.
+pub(crate) struct HeaderRange {
+    /// The entire field including its name, continuation lines and the trailing newline.
+    pub field: Range<usize>,
+    /// The value of the field without its name and the trailing newline.
+    pub value: Range<usize>,
+}
+
+/// Return the ranges of all header fields in `data` in order, along with the offset at which the body starts after the
+/// newline separating it from the headers.
+///
+/// `data` is expected to have been validated by one of the object parsers.
+pub(crate) fn header_ranges(data: &[u8]) -> (Vec<HeaderRange>, usize) {
+    let line_end = |pos: usize| data[pos..].find_byte(NL[0]).map_or(data.len(), |end| pos + end);
+    let mut headers = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() && data[pos] != NL[0] {
+        let mut end = line_end(pos);
+        while data.get(end + 1) == Some(&SPACE[0]) {
+            end = line_end(end + 1);
+        }
+        let value_start = data[pos..end]
+            .find_byte(SPACE[0])
+            .map_or(end, |name_end| pos + name_end + 1);
+        let next = (end + 1).min(data.len());
+        headers.push(HeaderRange {
+            field: pos..next,
+            value: value_start..end,
+        });
+        pos = next;
+    }
+    let body = (pos + 1).min(data.len());
+    (headers, body)
+}
+
+/// Return the range of `part` within `data`, which it must have been borrowed from.
+pub(crate) fn range_of(data: &[u8], part: &[u8]) -> Range<usize> {
+    let start = part.as_ptr() as usize - data.as_ptr() as usize;
+    start..start + part.len()
+}
@@ -1,4 +1,9 @@
-use crate::{immutable::object, BStr};
+use std::ops::Range;
+
+use crate::{
+    immutable::{object, parse},
+    BStr,
+};
 
 /// Represents a git tag, commonly indicating a software release.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
@@ -19,6 +24,26 @@ pub struct Tag<'a> {
     pub pgp_signature: Option<&'a BStr>,
 }
 
+/// The byte ranges of the fields of a [`Tag`] within the data it was parsed from, as returned by
+/// [`Tag::from_bytes_with_ranges()`].
+///
+/// Header fields cover their value only, without the field name and the trailing newline.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Ranges {
+    /// The hexadecimal hash of the target object.
+    pub target: Range<usize>,
+    /// The kind of the target object.
+    pub target_kind: Range<usize>,
+    /// The name of the tag.
+    pub name: Range<usize>,
+    /// The tagger signature, if present.
+    pub tagger: Option<Range<usize>>,
+    /// The message describing the tag.
+    pub message: Range<usize>,
+    /// The PGP signature following the message, if the tag is signed.
+    pub pgp_signature: Option<Range<usize>>,
+}
+
 impl<'a> Tag<'a> {
     /// Deserialize a tag from `data`.
     pub fn from_bytes(data: &'a [u8]) -> Result<Tag<'a>, object::decode::Error> {
@@ -26,6 +51,23 @@ impl<'a> Tag<'a> {
             .map(|(_, t)| t)
             .map_err(object::decode::Error::from)
     }
+
+    /// Like [`from_bytes()`][Tag::from_bytes()], but also return the [byte ranges][Ranges] of all fields within `data`.
+    pub fn from_bytes_with_ranges(data: &'a [u8]) -> Result<(Tag<'a>, Ranges), object::decode::Error> {
+        let tag = Self::from_bytes(data)?;
+        let (headers, _body) = parse::header_ranges(data);
+        let mut headers = headers.into_iter();
+        let mut next_value = || headers.next().expect("validated during parsing").value;
+        let ranges = Ranges {
+            target: next_value(),
+            target_kind: next_value(),
+            name: next_value(),
+            tagger: tag.tagger.as_ref().map(|_| next_value()),
+            message: parse::range_of(data, tag.message),
+            pgp_signature: tag.pgp_signature.map(|sig| parse::range_of(data, sig)),
+        };
+        Ok((tag, ranges))
+    }
     /// The object this tag points to as `Id`.
     pub fn target(&self) -> git_hash::ObjectId {
         git_hash::ObjectId::from_hex(self.target).expect("prior validation")
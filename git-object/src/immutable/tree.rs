@@ -19,12 +19,22 @@ pub struct TreeIter<'a> {
     /// The directories and files contained in this tree.
     #[cfg_attr(feature = "serde1", serde(borrow))]
     data: &'a [u8],
+    /// If true, entries with non-canonical modes are considered an error.
+    strict: bool,
 }
 
 impl<'a> TreeIter<'a> {
     /// Instantiate an iterator from the given tree data.
+    ///
+    /// Non-canonical modes like `040000` or `100664` are accepted, see [`Entry::raw_mode`].
     pub fn from_bytes(data: &'a [u8]) -> TreeIter<'a> {
-        TreeIter { data }
+        TreeIter { data, strict: false }
+    }
+
+    /// Instantiate an iterator from the given tree data which fails on entries with non-canonical modes,
+    /// similar to what `git fsck` would do.
+    pub fn from_bytes_strict(data: &'a [u8]) -> TreeIter<'a> {
+        TreeIter { data, strict: true }
     }
 }
 
@@ -32,8 +42,10 @@ impl<'a> TreeIter<'a> {
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entry<'a> {
-    /// The kind of object to which `oid` is pointing.
+    /// The kind of object to which `oid` is pointing, canonicalized from [`raw_mode`][Entry::raw_mode].
     pub mode: tree::EntryMode,
+    /// The mode exactly as it was stored in the tree, which may be non-canonical like `040000` or `100664`.
+    pub raw_mode: &'a BStr,
     /// The name of the file in the parent tree.
     pub filename: &'a BStr,
     /// The id of the object representing the entry.
@@ -41,12 +53,29 @@ pub struct Entry<'a> {
     pub oid: &'a git_hash::oid,
 }
 
+impl<'a> Entry<'a> {
+    /// Return true if the [`raw_mode`][Entry::raw_mode] is the canonical representation of [`mode`][Entry::mode],
+    /// as it would be written by git.
+    pub fn has_canonical_mode(&self) -> bool {
+        self.raw_mode == self.mode.as_bytes()
+    }
+}
+
 impl<'a> Tree<'a> {
     /// Deserialize a Tree from `data`.
+    ///
+    /// Non-canonical modes like `040000` or `100664` are accepted, see [`Entry::raw_mode`].
     pub fn from_bytes(data: &'a [u8]) -> Result<Tree<'a>, object::decode::Error> {
         decode::tree(data).map(|(_, t)| t).map_err(object::decode::Error::from)
     }
 
+    /// Deserialize a Tree from `data`, but fail if any entry has a non-canonical mode, similar to what `git fsck` would do.
+    pub fn from_bytes_strict(data: &'a [u8]) -> Result<Tree<'a>, object::decode::Error> {
+        decode::tree_strict(data)
+            .map(|(_, t)| t)
+            .map_err(object::decode::Error::from)
+    }
+
     /// Create an instance of the empty tree.
     ///
     /// It's particularly useful as static part of a program.
@@ -64,7 +93,10 @@ impl<'a> TreeIter<'a> {
 
 impl<'a> Default for TreeIter<'a> {
     fn default() -> Self {
-        TreeIter { data: &[] }
+        TreeIter {
+            data: &[],
+            strict: false,
+        }
     }
 }
 
@@ -75,7 +107,12 @@ impl<'a> Iterator for TreeIter<'a> {
         if self.data.is_empty() {
             return None;
         }
-        match decode::entry(self.data) {
+        let res = if self.strict {
+            decode::entry_strict(self.data)
+        } else {
+            decode::entry(self.data)
+        };
+        match res {
             Ok((data_left, entry)) => {
                 self.data = data_left;
                 Some(Ok(entry))
@@ -88,18 +125,28 @@ impl<'a> Iterator for TreeIter<'a> {
     }
 }
 
+/// Parse possibly non-canonical modes like `040000` (zero-padded) or `100664` (rare and found in the linux kernel)
+/// into their canonical form, similar to what git does.
 impl<'a> TryFrom<&'a [u8]> for tree::EntryMode {
     type Error = &'a [u8];
 
     fn try_from(mode: &'a [u8]) -> Result<Self, Self::Error> {
-        Ok(match mode {
-            b"40000" => tree::EntryMode::Tree,
-            b"100644" => tree::EntryMode::Blob,
-            b"100664" => tree::EntryMode::Blob, // rare and found in the linux kernel
-            b"100640" => tree::EntryMode::Blob, // rare and found in the Rust repo
-            b"100755" => tree::EntryMode::BlobExecutable,
-            b"120000" => tree::EntryMode::Link,
-            b"160000" => tree::EntryMode::Commit,
+        if mode.is_empty() {
+            return Err(mode);
+        }
+        let mut value = 0u32;
+        for b in mode {
+            if !(b'0'..=b'7').contains(b) {
+                return Err(mode);
+            }
+            value = (value << 3) | u32::from(b - b'0');
+        }
+        Ok(match value & 0o170000 {
+            0o040000 => tree::EntryMode::Tree,
+            0o100000 if value & 0o100 != 0 => tree::EntryMode::BlobExecutable,
+            0o100000 => tree::EntryMode::Blob,
+            0o120000 => tree::EntryMode::Link,
+            0o160000 => tree::EntryMode::Commit,
             _ => return Err(mode),
         })
     }
@@ -126,8 +173,8 @@ mod decode {
     const NULL: &[u8] = b"\0";
 
     pub fn entry<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&[u8], Entry<'_>, E> {
-        let (i, mode) = terminated(take_while_m_n(5, 6, is_digit), tag(SPACE))(i)?;
-        let mode = tree::EntryMode::try_from(mode)
+        let (i, raw_mode) = terminated(take_while_m_n(1, 7, is_digit), tag(SPACE))(i)?;
+        let mode = tree::EntryMode::try_from(raw_mode)
             .map_err(|invalid| nom::Err::Error(E::from_error_kind(invalid, nom::error::ErrorKind::MapRes)))?;
         let (i, filename) = terminated(take_while1(|b| b != NULL[0]), tag(NULL))(i)?;
         let (i, oid) = take(20u8)(i)?;
@@ -136,6 +183,7 @@ mod decode {
             i,
             Entry {
                 mode,
+                raw_mode: raw_mode.as_bstr(),
                 filename: filename.as_bstr(),
                 oid: git_hash::oid::try_from(oid).expect("we counted exactly 20 bytes"),
             },
@@ -146,4 +194,20 @@ mod decode {
         let (i, entries) = all_consuming(many0(entry))(i)?;
         Ok((i, Tree { entries }))
     }
+
+    pub fn entry_strict<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], Entry<'a>, E> {
+        let (i, entry) = entry(i)?;
+        if !entry.has_canonical_mode() {
+            return Err(nom::Err::Error(E::from_error_kind(
+                entry.raw_mode,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+        Ok((i, entry))
+    }
+
+    pub fn tree_strict<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], Tree<'a>, E> {
+        let (i, entries) = all_consuming(many0(entry_strict))(i)?;
+        Ok((i, Tree { entries }))
+    }
 }
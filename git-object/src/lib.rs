@@ -7,12 +7,19 @@ use bstr::{BStr, BString, ByteSlice};
 
 /// For convenience to allow using `bstr` without adding it to own cargo manifest.
 pub use bstr;
+/// The ids identifying objects, for convenience to allow using them without adding `git-hash` to own cargo manifest.
+pub use git_hash::{oid, ObjectId};
 
 pub mod immutable;
 pub mod mutable;
 
 mod types;
-pub use types::{tree, Error, Kind};
+pub use types::{Error, Kind};
 
+///
+pub mod tree;
+
+///
+pub mod blob;
 ///
 pub mod commit;
@@ -0,0 +1,237 @@
+use crate::mutable::{tag::validated_name, Commit, Tag};
+use bstr::{BStr, BString, ByteSlice};
+use quick_error::quick_error;
+use smallvec::SmallVec;
+
+quick_error! {
+    /// The error returned by [`CommitBuilder::build()`] and [`TagBuilder::build()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        MissingAuthor {
+            display("A commit needs an author")
+        }
+        EmptyMessage {
+            display("The message must not be empty or consist of whitespace only")
+        }
+        InvalidSignature(field: &'static str, message: &'static str) {
+            display("The {} signature is invalid: {}", field, message)
+        }
+        InvalidEncoding(encoding: BString) {
+            display("The encoding {:?} is invalid", encoding)
+        }
+        InvalidExtraHeaderName(name: BString) {
+            display("The extra header name {:?} must not be empty or contain whitespace", name)
+        }
+        InvalidTagName(err: crate::mutable::tag::Error) {
+            display("The tag name is invalid")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// Build a [`Commit`] with validated signatures, message and encoding.
+///
+/// The committer defaults to the author if unset, and an empty message is refused unless
+/// [allowed explicitly][CommitBuilder::allow_empty_message()].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CommitBuilder {
+    tree: git_hash::ObjectId,
+    parents: SmallVec<[git_hash::ObjectId; 1]>,
+    author: Option<git_actor::Signature>,
+    committer: Option<git_actor::Signature>,
+    encoding: Option<BString>,
+    message: BString,
+    extra_headers: Vec<(BString, BString)>,
+    allow_empty_message: bool,
+}
+
+impl CommitBuilder {
+    /// Start building a commit recording the given `tree`.
+    pub fn new(tree: impl Into<git_hash::ObjectId>) -> Self {
+        CommitBuilder {
+            tree: tree.into(),
+            parents: Default::default(),
+            author: None,
+            committer: None,
+            encoding: None,
+            message: Default::default(),
+            extra_headers: Vec::new(),
+            allow_empty_message: false,
+        }
+    }
+
+    /// Add `id` to the list of parents of the commit.
+    pub fn parent(mut self, id: impl Into<git_hash::ObjectId>) -> Self {
+        self.parents.push(id.into());
+        self
+    }
+
+    /// Set the `author` of the commit, which is required.
+    pub fn author(mut self, author: git_actor::Signature) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Set the `committer` of the commit, which defaults to the author.
+    pub fn committer(mut self, committer: git_actor::Signature) -> Self {
+        self.committer = Some(committer);
+        self
+    }
+
+    /// Set the `encoding` of the message, which is omitted if it denotes the default encoding, UTF-8.
+    pub fn encoding(mut self, encoding: impl Into<BString>) -> Self {
+        self.encoding = Some(encoding.into());
+        self
+    }
+
+    /// Set the commit `message`.
+    pub fn message(mut self, message: impl Into<BString>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Add an extra header with `name` and `value`, which may span multiple lines.
+    pub fn extra_header(mut self, name: impl Into<BString>, value: impl Into<BString>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// If `allow` is true, messages which are empty or consist of whitespace only are permitted.
+    pub fn allow_empty_message(mut self, allow: bool) -> Self {
+        self.allow_empty_message = allow;
+        self
+    }
+
+    /// Validate all fields and produce the commit.
+    pub fn build(self) -> Result<Commit, Error> {
+        let author = self.author.ok_or(Error::MissingAuthor)?;
+        validate_signature("author", &author)?;
+        let committer = match self.committer {
+            Some(committer) => {
+                validate_signature("committer", &committer)?;
+                committer
+            }
+            None => author.clone(),
+        };
+        validate_message(self.message.as_ref(), self.allow_empty_message)?;
+        for (name, _) in &self.extra_headers {
+            if name.is_empty() || name.iter().any(|b| b.is_ascii_whitespace()) {
+                return Err(Error::InvalidExtraHeaderName(name.to_owned()));
+            }
+        }
+        Ok(Commit {
+            tree: self.tree,
+            parents: self.parents,
+            author,
+            committer,
+            encoding: self.encoding.map(normalized_encoding).transpose()?.flatten(),
+            message: self.message,
+            extra_headers: self.extra_headers,
+        })
+    }
+}
+
+/// Build a [`Tag`] with a validated name, tagger signature and message.
+///
+/// An empty message is refused unless [allowed explicitly][TagBuilder::allow_empty_message()].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TagBuilder {
+    target: git_hash::ObjectId,
+    target_kind: crate::Kind,
+    name: BString,
+    message: BString,
+    tagger: Option<git_actor::Signature>,
+    pgp_signature: Option<BString>,
+    allow_empty_message: bool,
+}
+
+impl TagBuilder {
+    /// Start building a tag called `name` pointing to `target` of kind `target_kind`.
+    pub fn new(target: impl Into<git_hash::ObjectId>, target_kind: crate::Kind, name: impl Into<BString>) -> Self {
+        TagBuilder {
+            target: target.into(),
+            target_kind,
+            name: name.into(),
+            message: Default::default(),
+            tagger: None,
+            pgp_signature: None,
+            allow_empty_message: false,
+        }
+    }
+
+    /// Set the tag `message`.
+    pub fn message(mut self, message: impl Into<BString>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Set the `tagger` who created the tag.
+    pub fn tagger(mut self, tagger: git_actor::Signature) -> Self {
+        self.tagger = Some(tagger);
+        self
+    }
+
+    /// Set the pgp `signature` over all bytes of the encoded tag, excluding the signature itself.
+    pub fn pgp_signature(mut self, signature: impl Into<BString>) -> Self {
+        self.pgp_signature = Some(signature.into());
+        self
+    }
+
+    /// If `allow` is true, messages which are empty or consist of whitespace only are permitted.
+    pub fn allow_empty_message(mut self, allow: bool) -> Self {
+        self.allow_empty_message = allow;
+        self
+    }
+
+    /// Validate all fields and produce the tag.
+    pub fn build(self) -> Result<Tag, Error> {
+        validated_name(self.name.as_ref())?;
+        if let Some(tagger) = &self.tagger {
+            validate_signature("tagger", tagger)?;
+        }
+        validate_message(self.message.as_ref(), self.allow_empty_message)?;
+        Ok(Tag {
+            target: self.target,
+            target_kind: self.target_kind,
+            name: self.name,
+            message: self.message,
+            signature: self.tagger,
+            pgp_signature: self.pgp_signature,
+        })
+    }
+}
+
+fn validate_signature(field: &'static str, signature: &git_actor::Signature) -> Result<(), Error> {
+    let has_invalid_bytes = |v: &BString| v.iter().any(|b| matches!(b, b'<' | b'>' | b'\n'));
+    if signature.name.trim_with(|c| c.is_ascii_whitespace()).is_empty() {
+        return Err(Error::InvalidSignature(field, "the name must not be empty"));
+    }
+    if has_invalid_bytes(&signature.name) || has_invalid_bytes(&signature.email) {
+        return Err(Error::InvalidSignature(
+            field,
+            "name and email must not contain '<', '>' or newlines",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_message(message: &BStr, allow_empty: bool) -> Result<(), Error> {
+    if !allow_empty && message.trim_with(|c| c.is_ascii_whitespace()).is_empty() {
+        return Err(Error::EmptyMessage);
+    }
+    Ok(())
+}
+
+/// Return `None` if `encoding` is UTF-8, which is assumed if no encoding is set, or the upper-cased `encoding` otherwise.
+fn normalized_encoding(encoding: BString) -> Result<Option<BString>, Error> {
+    let trimmed = encoding.trim_with(|c| c.is_ascii_whitespace());
+    if trimmed.is_empty() || trimmed.iter().any(|b| b.is_ascii_whitespace()) {
+        return Err(Error::InvalidEncoding(encoding));
+    }
+    if trimmed.eq_ignore_ascii_case(b"utf-8") || trimmed.eq_ignore_ascii_case(b"utf8") {
+        return Ok(None);
+    }
+    Ok(Some(trimmed.to_ascii_uppercase().into()))
+}
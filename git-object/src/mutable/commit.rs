@@ -35,6 +35,10 @@ impl Commit {
     pub fn extra_headers(&self) -> commit::ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         commit::ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (k.as_bstr(), v.as_bstr())))
     }
+    /// Decode the `message` into a string using the declared `encoding`, see [`commit::message::decode()`].
+    pub fn decode_message(&self) -> std::borrow::Cow<'_, str> {
+        commit::message::decode(self.message.as_bstr(), self.encoding.as_ref().map(|e| e.as_bstr()))
+    }
     /// Serializes this instance to `out` in the git serialization format.
     pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
         encode::trusted_header_id(b"tree", &self.tree, &mut out)?;
@@ -69,7 +69,9 @@ impl From<immutable::Tree<'_>> for mutable::Tree {
 
 impl From<immutable::tree::Entry<'_>> for mutable::tree::Entry {
     fn from(other: immutable::tree::Entry<'_>) -> mutable::tree::Entry {
-        let immutable::tree::Entry { mode, filename, oid } = other;
+        let immutable::tree::Entry {
+            mode, filename, oid, ..
+        } = other;
         mutable::tree::Entry {
             mode,
             filename: filename.to_owned(),
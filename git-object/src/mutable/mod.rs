@@ -20,6 +20,10 @@ pub use tree::Tree;
 mod commit;
 pub use commit::Commit;
 
+///
+pub mod builder;
+pub use builder::{CommitBuilder, TagBuilder};
+
 mod blob {
     use std::io;
 
@@ -36,6 +40,11 @@ mod blob {
         pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
             out.write_all(&self.data)
         }
+
+        /// Return `true` if our data is considered binary, see [`blob::is_binary()`][crate::blob::is_binary()].
+        pub fn is_binary(&self) -> bool {
+            crate::blob::is_binary(&self.data)
+        }
     }
 }
 pub use blob::Blob;
@@ -64,7 +64,7 @@ impl Tag {
     }
 }
 
-fn validated_name(name: &BStr) -> Result<&BStr, Error> {
+pub(crate) fn validated_name(name: &BStr) -> Result<&BStr, Error> {
     git_validate::tag::name(name)?;
     if name[0] == b'-' {
         return Err(Error::StartsWithDash);
@@ -48,10 +48,10 @@ impl PartialOrd for Entry {
 }
 
 impl Ord for Entry {
-    /// Entries compare by the common portion of the filename. This is critical for proper functioning of algorithms working on trees.
+    /// Entries compare by their filename the way git does, see [`compare_entries()`][crate::tree::compare_entries()].
+    /// This is critical for proper functioning of algorithms working on trees.
     fn cmp(&self, other: &Self) -> Ordering {
-        let len = self.filename.len().min(other.filename.len());
-        self.filename[..len].cmp(&other.filename[..len])
+        crate::tree::compare_entries(&self.filename, self.mode, &other.filename, other.mode)
     }
 }
 
@@ -0,0 +1,114 @@
+/// The mode of items storable in a tree, similar to the file mode on a unix file system.
+///
+/// Used in [mutable::Entry][crate::mutable::tree::Entry] and [immutable::Entry][crate::immutable::tree::Entry].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Ord, PartialOrd, Hash)]
+#[repr(u16)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum EntryMode {
+    Tree = 0o040000u16,
+    Blob = 0o100644,
+    BlobExecutable = 0o100755,
+    Link = 0o120000,
+    Commit = 0o160000,
+}
+
+impl EntryMode {
+    /// Return true if this entry mode represents a Tree/directory
+    pub fn is_tree(&self) -> bool {
+        *self == EntryMode::Tree
+    }
+
+    /// Return true if this entry mode represents anything BUT Tree/directory
+    pub fn is_no_tree(&self) -> bool {
+        *self != EntryMode::Tree
+    }
+}
+
+/// Compare the entry named `lhs_name` with mode `lhs_mode` to the one named `rhs_name` with mode `rhs_mode` the way git does,
+/// which treats the names of trees as if they had a trailing slash.
+///
+/// This is the order of entries within trees, which is why it must be used by all algorithms walking multiple trees in lockstep.
+/// Note that plain name comparison would sort `a.txt` after a tree named `a`, whereas git sorts it before, as `.` comes before `/`.
+pub fn compare_entries(
+    lhs_name: &[u8],
+    lhs_mode: EntryMode,
+    rhs_name: &[u8],
+    rhs_mode: EntryMode,
+) -> std::cmp::Ordering {
+    let common_len = lhs_name.len().min(rhs_name.len());
+    lhs_name[..common_len].cmp(&rhs_name[..common_len]).then_with(|| {
+        let byte_after_common_prefix = |name: &[u8], mode: EntryMode| {
+            name.get(common_len)
+                .copied()
+                .unwrap_or(if mode.is_tree() { b'/' } else { 0 })
+        };
+        byte_after_common_prefix(lhs_name, lhs_mode).cmp(&byte_after_common_prefix(rhs_name, rhs_mode))
+    })
+}
+
+///
+pub mod lookup {
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`lookup_entry()`][super::lookup_entry()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            NotFound{oid: git_hash::ObjectId} {
+                display("The tree {} could not be found", oid)
+            }
+            Decode(err: crate::immutable::object::decode::Error) {
+                display("A tree could not be decoded")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+
+/// Find the entry at `path`, like `a/b/c`, by walking the nested trees starting at the `root` tree, using `buf` to hold the
+/// data of each tree as obtained by `find(tree_id, buf)`.
+///
+/// Returns `None` if a path component doesn't exist or isn't a tree although more components follow it. Empty components,
+/// like in `a//b` or `a/`, are ignored, and an empty `path` yields an entry for the `root` tree itself.
+/// Fails if `find` can't find one of the trees, including the `root`, or if a tree can't be decoded.
+pub fn lookup_entry<Find>(
+    root: &git_hash::oid,
+    path: impl AsRef<[u8]>,
+    buf: &mut Vec<u8>,
+    mut find: Find,
+) -> Result<Option<crate::mutable::tree::Entry>, lookup::Error>
+where
+    Find: for<'a> FnMut(&git_hash::oid, &'a mut Vec<u8>) -> Option<crate::immutable::TreeIter<'a>>,
+{
+    let mut entry = crate::mutable::tree::Entry {
+        mode: EntryMode::Tree,
+        filename: Default::default(),
+        oid: root.to_owned(),
+    };
+    for component in path.as_ref().split(|b| *b == b'/').filter(|c| !c.is_empty()) {
+        if !entry.mode.is_tree() {
+            return Ok(None);
+        }
+        let tree = find(&entry.oid, buf).ok_or(lookup::Error::NotFound { oid: entry.oid })?;
+        let mut next = None;
+        for tree_entry in tree {
+            let tree_entry = tree_entry?;
+            if tree_entry.filename == component {
+                next = Some(crate::mutable::tree::Entry {
+                    mode: tree_entry.mode,
+                    filename: tree_entry.filename.to_owned(),
+                    oid: tree_entry.oid.to_owned(),
+                });
+                break;
+            }
+        }
+        match next {
+            Some(next) => entry = next,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(entry))
+}
@@ -0,0 +1,24 @@
+use git_object::{blob, immutable, mutable};
+
+#[test]
+fn text_is_not_binary() {
+    assert!(!blob::is_binary(b""));
+    assert!(!blob::is_binary("hello\nwörld\r\n".as_bytes()));
+}
+
+#[test]
+fn nul_bytes_make_data_binary() {
+    assert!(blob::is_binary(b"\0"));
+    assert!(blob::is_binary(b"hello\0world"));
+    assert!(immutable::Blob { data: b"a\0" }.is_binary());
+    assert!(mutable::Blob { data: b"a\0".to_vec() }.is_binary());
+}
+
+#[test]
+fn only_the_first_bytes_are_considered() {
+    let mut data = vec![b'a'; blob::BINARY_PEEK_LEN];
+    data.push(0);
+    assert!(!blob::is_binary(&data), "the NUL byte is just past the peek window");
+    assert!(blob::is_binary_with_peek_len(&data, data.len()));
+    assert!(!blob::is_binary_with_peek_len(b"a\0", 1));
+}
@@ -144,6 +144,49 @@ mod method {
         assert_eq!(commit.tree, "1b2dfb4ac5e42080b682fc676e9738c94ce6d54d");
         Ok(())
     }
+
+    mod decode_message {
+        use bstr::ByteSlice;
+        use git_object::{commit::message::decode, immutable::Commit, mutable};
+
+        #[test]
+        fn uses_the_declared_encoding() -> crate::Result {
+            let commit = Commit::from_bytes(b"tree 4a1c03029e7407c0afe9fc0320b3258e188b115e\nauthor a <a@example.com> 1592438199 +0800\ncommitter a <a@example.com> 1592438199 +0800\nencoding ISO-8859-1\n\nSch\xf6n")?;
+            assert_eq!(commit.decode_message(), "Schön");
+            assert_eq!(mutable::Commit::from(commit).decode_message(), "Schön");
+            Ok(())
+        }
+
+        #[test]
+        fn assumes_utf8_without_declared_encoding() {
+            assert_eq!(decode("Schön".as_bytes().as_bstr(), None), "Schön");
+            assert_eq!(decode("Schön".as_bytes().as_bstr(), Some(b"UTF-8".as_bstr())), "Schön");
+        }
+
+        #[test]
+        fn falls_back_to_latin1_if_the_message_is_invalid_in_the_declared_encoding() {
+            assert_eq!(decode(b"Sch\xf6n".as_bstr(), None), "Schön");
+            assert_eq!(decode(b"Sch\xf6n".as_bstr(), Some(b"UTF-8".as_bstr())), "Schön");
+            assert_eq!(decode(b"Sch\xf6".as_bstr(), Some(b"Shift_JIS".as_bstr())), "Schö");
+        }
+
+        #[test]
+        fn falls_back_to_utf8_if_the_encoding_is_unknown() {
+            assert_eq!(
+                decode("Schön".as_bytes().as_bstr(), Some(b"unknown".as_bstr())),
+                "Schön"
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "encoding")]
+        fn supports_other_encodings() {
+            assert_eq!(
+                decode(b"\x93\xfa\x96\x7b".as_bstr(), Some(b"Shift_JIS".as_bstr())),
+                "日本"
+            );
+        }
+    }
 }
 
 mod iter {
@@ -514,4 +557,125 @@ iyBBl69jASy41Ug/BlFJbw4+ItkShpXwkJKuBBV/JExChmvbxYWaS7QnyYC9UO0=
         assert!(commit.message.starts_with(b"Rollup"));
         Ok(())
     }
+
+    #[test]
+    fn signed_with_sha1_and_sha256() -> crate::Result {
+        let fixture = fixture_bytes("commit", "signed-sha1-and-sha256.txt");
+        let commit = Commit::from_bytes(&fixture)?;
+        let signatures: Vec<_> = commit.extra_headers().pgp_signatures().collect();
+        assert_eq!(
+            signatures.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            vec!["gpgsig", "gpgsig-sha256"],
+            "all signatures are returned with their field name"
+        );
+        assert!(signatures.iter().all(
+            |(_, signature)| signature.starts_with(b"-----BEGIN PGP SIGNATURE-----\n")
+                && signature.ends_with(b"-----END PGP SIGNATURE-----")
+        ));
+        assert_eq!(
+            commit.extra_headers().pgp_signature(),
+            Some(signatures[0].1),
+            "the first signature is the one of the SHA1 representation"
+        );
+        assert_eq!(commit.message, "update tasks\n");
+        Ok(())
+    }
+}
+
+mod from_bytes_with_ranges {
+    use crate::immutable::{fixture_bytes, signature};
+    use git_object::{bstr::ByteSlice, immutable::Commit};
+
+    fn signature_at(data: &[u8], range: std::ops::Range<usize>) -> git_actor::immutable::Signature<'_> {
+        git_actor::immutable::Signature::from_bytes::<()>(&data[range]).expect("valid signature")
+    }
+
+    #[test]
+    fn fields_can_be_located_in_the_original_data() -> crate::Result {
+        for name in &[
+            "unsigned.txt",
+            "whitespace.txt",
+            "signed.txt",
+            "signed-sha1-and-sha256.txt",
+            "signed-singleline.txt",
+            "signed-whitespace.txt",
+            "signed-with-encoding.txt",
+            "with-encoding.txt",
+            "merge.txt",
+            "mergetag.txt",
+            "two-multiline-headers.txt",
+        ] {
+            let data = fixture_bytes("commit", name);
+            let (commit, ranges) = Commit::from_bytes_with_ranges(&data)?;
+            assert_eq!(commit, Commit::from_bytes(&data)?);
+            assert_eq!(data[ranges.tree.clone()].as_bstr(), commit.tree, "{}", name);
+            assert_eq!(
+                ranges
+                    .parents
+                    .iter()
+                    .map(|r| data[r.clone()].as_bstr())
+                    .collect::<Vec<_>>(),
+                commit.parents.to_vec(),
+                "{}",
+                name
+            );
+            assert_eq!(signature_at(&data, ranges.author.clone()), commit.author, "{}", name);
+            assert_eq!(
+                signature_at(&data, ranges.committer.clone()),
+                commit.committer,
+                "{}",
+                name
+            );
+            assert_eq!(
+                ranges.encoding.clone().map(|r| data[r].as_bstr()),
+                commit.encoding,
+                "{}",
+                name
+            );
+            assert_eq!(ranges.extra_headers.len(), commit.extra_headers.len(), "{}", name);
+            for (range, (field_name, _)) in ranges.extra_headers.iter().zip(commit.extra_headers.iter()) {
+                assert!(data[range.clone()].starts_with(field_name), "{}", name);
+                assert!(data[range.clone()].ends_with(b"\n"), "{}", name);
+            }
+            assert_eq!(
+                ranges
+                    .signatures
+                    .iter()
+                    .map(|r| data[r.clone()].split_str(" ").next().expect("field name").as_bstr())
+                    .collect::<Vec<_>>(),
+                commit
+                    .extra_headers()
+                    .pgp_signatures()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+                "{}",
+                name
+            );
+            assert_eq!(data[ranges.message.clone()].as_bstr(), commit.message, "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn splicing_changes_only_the_affected_field() -> crate::Result {
+        let data = fixture_bytes("commit", "signed-sha1-and-sha256.txt");
+        let (commit, ranges) = Commit::from_bytes_with_ranges(&data)?;
+
+        let mut unsigned = data.clone();
+        for signature in ranges.signatures.iter().rev() {
+            unsigned.drain(signature.clone());
+        }
+        let unsigned_commit = Commit::from_bytes(&unsigned)?;
+        assert!(unsigned_commit.extra_headers.is_empty(), "all signatures were removed");
+        assert_eq!(unsigned_commit.message, commit.message);
+        assert_eq!(unsigned_commit.committer, commit.committer);
+
+        let mut rewritten = data.clone();
+        let tree = b"0000000000000000000000000000000000000000";
+        rewritten.splice(ranges.tree, tree.iter().copied());
+        let rewritten = Commit::from_bytes(&rewritten)?;
+        assert_eq!(rewritten.tree, tree.as_bstr());
+        assert_eq!(rewritten.author, signature(1592391367));
+        Ok(())
+    }
 }
@@ -239,3 +239,48 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         }),
     }
 }
+
+mod from_bytes_with_ranges {
+    use crate::immutable::fixture_bytes;
+    use git_object::{bstr::ByteSlice, immutable::Tag};
+
+    #[test]
+    fn fields_can_be_located_in_the_original_data() -> crate::Result {
+        for name in &[
+            "empty.txt",
+            "no-tagger.txt",
+            "signed.txt",
+            "whitespace.txt",
+            "with-newlines.txt",
+        ] {
+            let data = fixture_bytes("tag", name);
+            let (tag, ranges) = Tag::from_bytes_with_ranges(&data)?;
+            assert_eq!(tag, Tag::from_bytes(&data)?);
+            assert_eq!(data[ranges.target.clone()].as_bstr(), tag.target, "{}", name);
+            assert_eq!(
+                &data[ranges.target_kind.clone()],
+                tag.target_kind.as_bytes(),
+                "{}",
+                name
+            );
+            assert_eq!(data[ranges.name.clone()].as_bstr(), tag.name, "{}", name);
+            assert_eq!(
+                ranges
+                    .tagger
+                    .clone()
+                    .map(|r| git_actor::immutable::Signature::from_bytes::<()>(&data[r]).expect("valid")),
+                tag.tagger,
+                "{}",
+                name
+            );
+            assert_eq!(data[ranges.message.clone()].as_bstr(), tag.message, "{}", name);
+            assert_eq!(
+                ranges.pgp_signature.clone().map(|r| data[r].as_bstr()),
+                tag.pgp_signature,
+                "{}",
+                name
+            );
+        }
+        Ok(())
+    }
+}
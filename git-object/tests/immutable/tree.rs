@@ -29,26 +29,31 @@ mod iter {
             vec![
                 Entry {
                     mode: tree::EntryMode::BlobExecutable,
+                    raw_mode: b"100755".as_bstr(),
                     filename: b"exe".as_bstr(),
                     oid: &hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")
                 },
                 Entry {
                     mode: tree::EntryMode::Blob,
+                    raw_mode: b"100644".as_bstr(),
                     filename: b"file".as_bstr(),
                     oid: &hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")
                 },
                 Entry {
                     mode: tree::EntryMode::Commit,
+                    raw_mode: b"160000".as_bstr(),
                     filename: b"grit-submodule".as_bstr(),
                     oid: &hex_to_id("b2d1b5d684bdfda5f922b466cc13d4ce2d635cf8")
                 },
                 Entry {
                     mode: tree::EntryMode::Tree,
+                    raw_mode: b"40000".as_bstr(),
                     filename: b"subdir".as_bstr(),
                     oid: &hex_to_id("4d5fcadc293a348e88f777dc0920f11e7d71441c")
                 },
                 Entry {
                     mode: tree::EntryMode::Link,
+                    raw_mode: b"120000".as_bstr(),
                     filename: b"symlink".as_bstr(),
                     oid: &hex_to_id("1a010b1c0f081b2e8901d55307a15c29ff30af0e")
                 }
@@ -62,7 +67,7 @@ mod from_bytes {
     use crate::{hex_to_id, immutable::fixture_bytes};
     use git_object::{
         bstr::ByteSlice,
-        immutable::{tree::Entry, Tree},
+        immutable::{tree::Entry, Tree, TreeIter},
         tree,
     };
 
@@ -84,26 +89,31 @@ mod from_bytes {
                 entries: vec![
                     Entry {
                         mode: tree::EntryMode::BlobExecutable,
+                        raw_mode: b"100755".as_bstr(),
                         filename: b"exe".as_bstr(),
                         oid: &hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")
                     },
                     Entry {
                         mode: tree::EntryMode::Blob,
+                        raw_mode: b"100644".as_bstr(),
                         filename: b"file".as_bstr(),
                         oid: &hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")
                     },
                     Entry {
                         mode: tree::EntryMode::Commit,
+                        raw_mode: b"160000".as_bstr(),
                         filename: b"grit-submodule".as_bstr(),
                         oid: &hex_to_id("b2d1b5d684bdfda5f922b466cc13d4ce2d635cf8")
                     },
                     Entry {
                         mode: tree::EntryMode::Tree,
+                        raw_mode: b"40000".as_bstr(),
                         filename: b"subdir".as_bstr(),
                         oid: &hex_to_id("4d5fcadc293a348e88f777dc0920f11e7d71441c")
                     },
                     Entry {
                         mode: tree::EntryMode::Link,
+                        raw_mode: b"120000".as_bstr(),
                         filename: b"symlink".as_bstr(),
                         oid: &hex_to_id("1a010b1c0f081b2e8901d55307a15c29ff30af0e")
                     }
@@ -134,4 +144,59 @@ mod from_bytes {
         );
         Ok(())
     }
+
+    fn tree_with_non_canonical_modes() -> Vec<u8> {
+        let mut buf = Vec::new();
+        for (mode, name) in &[("040000", "a"), ("100664", "b"), ("100775", "c"), ("100644", "d")] {
+            buf.extend_from_slice(mode.as_bytes());
+            buf.push(b' ');
+            buf.extend_from_slice(name.as_bytes());
+            buf.push(0);
+            buf.extend_from_slice(hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").as_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn non_canonical_modes_are_preserved_and_canonicalized() -> crate::Result {
+        let data = tree_with_non_canonical_modes();
+        let tree = Tree::from_bytes(&data)?;
+        assert_eq!(
+            tree.entries
+                .iter()
+                .map(|e| (e.mode, e.raw_mode, e.has_canonical_mode()))
+                .collect::<Vec<_>>(),
+            vec![
+                (tree::EntryMode::Tree, b"040000".as_bstr(), false),
+                (tree::EntryMode::Blob, b"100664".as_bstr(), false),
+                (tree::EntryMode::BlobExecutable, b"100775".as_bstr(), false),
+                (tree::EntryMode::Blob, b"100644".as_bstr(), true),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn non_canonical_modes_fail_in_strict_mode() {
+        let data = tree_with_non_canonical_modes();
+        assert!(Tree::from_bytes_strict(&data).is_err());
+        assert!(
+            TreeIter::from_bytes_strict(&data).next().expect("one item").is_err(),
+            "the very first entry is zero-padded"
+        );
+        assert!(
+            Tree::from_bytes_strict(&fixture_bytes("tree", "everything.tree")).is_ok(),
+            "canonical trees pass"
+        );
+    }
+
+    #[test]
+    fn invalid_modes_are_still_rejected() {
+        let mut data = b"100888 a\0".to_vec();
+        data.extend_from_slice(hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").as_bytes());
+        assert!(Tree::from_bytes(&data).is_err());
+        let mut data = b"070000 a\0".to_vec();
+        data.extend_from_slice(hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").as_bytes());
+        assert!(Tree::from_bytes(&data).is_err(), "unknown file type");
+    }
 }
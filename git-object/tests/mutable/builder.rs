@@ -0,0 +1,134 @@
+use git_object::mutable::{builder::Error, TagBuilder};
+
+fn signature(name: &str) -> git_actor::Signature {
+    git_actor::Signature {
+        name: name.into(),
+        email: "sebastian.thiel@icloud.com".into(),
+        time: git_actor::Time {
+            time: 1528473343,
+            offset: 7200,
+            sign: git_actor::Sign::Plus,
+        },
+    }
+}
+
+fn id() -> git_hash::ObjectId {
+    git_hash::ObjectId::null_sha1()
+}
+
+mod commit {
+    use super::{id, signature};
+    use git_object::mutable::{builder::Error, CommitBuilder};
+
+    #[test]
+    fn committer_defaults_to_author_and_utf8_encoding_is_omitted() -> Result<(), Box<dyn std::error::Error>> {
+        let commit = CommitBuilder::new(id())
+            .parent(id())
+            .author(signature("Sebastian Thiel"))
+            .encoding("UTF8")
+            .message("hello\n")
+            .build()?;
+        assert_eq!(commit.committer, commit.author);
+        assert_eq!(commit.parents.len(), 1);
+        assert_eq!(
+            commit.encoding, None,
+            "UTF-8 is the default and doesn't need to be stored"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn other_encodings_are_normalized() -> Result<(), Box<dyn std::error::Error>> {
+        let commit = CommitBuilder::new(id())
+            .author(signature("Sebastian Thiel"))
+            .encoding(" iso-8859-1 ")
+            .message("hello")
+            .build()?;
+        assert_eq!(commit.encoding.as_ref().map(|e| e.as_slice()), Some(&b"ISO-8859-1"[..]));
+        Ok(())
+    }
+
+    #[test]
+    fn missing_author_is_an_error() {
+        assert!(matches!(
+            CommitBuilder::new(id()).message("hello").build(),
+            Err(Error::MissingAuthor)
+        ));
+    }
+
+    #[test]
+    fn invalid_signatures_are_rejected() {
+        for name in &["", "  ", "a <b>", "new\nline"] {
+            assert!(
+                matches!(
+                    CommitBuilder::new(id())
+                        .author(signature(name))
+                        .message("hello")
+                        .build(),
+                    Err(Error::InvalidSignature("author", _))
+                ),
+                "{:?}",
+                name
+            );
+        }
+        assert!(matches!(
+            CommitBuilder::new(id())
+                .author(signature("Sebastian Thiel"))
+                .committer(signature(""))
+                .message("hello")
+                .build(),
+            Err(Error::InvalidSignature("committer", _))
+        ));
+    }
+
+    #[test]
+    fn empty_messages_are_rejected_unless_allowed() {
+        let builder = CommitBuilder::new(id())
+            .author(signature("Sebastian Thiel"))
+            .message(" \n\t");
+        assert!(matches!(builder.clone().build(), Err(Error::EmptyMessage)));
+        assert!(builder.allow_empty_message(true).build().is_ok());
+    }
+
+    #[test]
+    fn extra_header_names_must_not_contain_whitespace() {
+        assert!(matches!(
+            CommitBuilder::new(id())
+                .author(signature("Sebastian Thiel"))
+                .extra_header("gpg sig", "value")
+                .message("hello")
+                .build(),
+            Err(Error::InvalidExtraHeaderName(_))
+        ));
+    }
+}
+
+#[test]
+fn tag_with_valid_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let tag = TagBuilder::new(id(), git_object::Kind::Commit, "v1.0.0")
+        .tagger(signature("Sebastian Thiel"))
+        .message("release")
+        .build()?;
+    assert_eq!(tag.signature, Some(signature("Sebastian Thiel")));
+    let mut buf = Vec::new();
+    tag.write_to(&mut buf)?;
+    Ok(())
+}
+
+#[test]
+fn tag_with_invalid_name_or_empty_message() {
+    assert!(matches!(
+        TagBuilder::new(id(), git_object::Kind::Commit, "-invalid")
+            .message("release")
+            .build(),
+        Err(Error::InvalidTagName(_))
+    ));
+    assert!(matches!(
+        TagBuilder::new(id(), git_object::Kind::Commit, "v1.0.0").build(),
+        Err(Error::EmptyMessage)
+    ));
+    assert!(TagBuilder::new(id(), git_object::Kind::Commit, "v1.0.0")
+        .allow_empty_message(true)
+        .build()
+        .is_ok());
+}
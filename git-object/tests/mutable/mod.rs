@@ -26,6 +26,7 @@ macro_rules! round_trip {
     };
 }
 
+mod builder;
 mod object;
 mod tag {
     round_trip!(
@@ -48,6 +49,7 @@ mod commit {
         "commit/mergetag.txt",
         "commit/merge.txt",
         "commit/signed.txt",
+        "commit/signed-sha1-and-sha256.txt",
         "commit/signed-singleline.txt",
         "commit/signed-with-encoding.txt",
         "commit/unsigned.txt",
@@ -57,7 +59,62 @@ mod commit {
 }
 
 mod tree {
+    use git_object::{immutable, mutable, tree::EntryMode};
+    use std::cmp::Ordering;
+
     round_trip!(mutable::Tree, immutable::Tree, "tree/everything.tree");
+
+    #[test]
+    fn entries_are_ordered_like_git_with_trees_having_a_trailing_slash() {
+        let entry = |filename: &str, mode| mutable::tree::Entry {
+            mode,
+            filename: filename.into(),
+            oid: git_hash::ObjectId::null_sha1(),
+        };
+        let mut entries = [
+            entry("b", EntryMode::Blob),
+            entry("a", EntryMode::Tree),
+            entry("a.txt", EntryMode::Blob),
+            entry("a-b", EntryMode::Link),
+            entry("a0", EntryMode::BlobExecutable),
+        ];
+        entries.sort();
+        assert_eq!(
+            entries.iter().map(|e| e.filename.to_string()).collect::<Vec<_>>(),
+            vec!["a-b", "a.txt", "a", "a0", "b"],
+            "'/' sorts after '.' and '-', but before '0'"
+        );
+    }
+
+    #[test]
+    fn compare_entries() {
+        use git_object::tree::compare_entries;
+        assert_eq!(
+            compare_entries(b"a", EntryMode::Blob, b"a.txt", EntryMode::Blob),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_entries(b"a", EntryMode::Tree, b"a.txt", EntryMode::Blob),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_entries(b"a", EntryMode::Blob, b"a", EntryMode::Tree),
+            Ordering::Less,
+            "the same name is only equal if both are trees or no trees"
+        );
+        assert_eq!(
+            compare_entries(b"a", EntryMode::Commit, b"a", EntryMode::Link),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn entries_of_trees_written_by_git_are_ordered() -> crate::Result {
+        let input = crate::fixture_bytes("tree/everything.tree");
+        let tree: mutable::Tree = immutable::Tree::from_bytes(&input)?.into();
+        assert!(tree.entries.windows(2).all(|w| w[0] < w[1]));
+        Ok(())
+    }
 }
 
 mod blob {
@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
+mod blob;
 mod immutable;
 mod mutable;
+mod tree;
 
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
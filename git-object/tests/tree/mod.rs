@@ -0,0 +1,106 @@
+mod lookup_entry {
+    use crate::hex_to_id;
+    use git_object::{
+        immutable, mutable,
+        tree::{self, EntryMode},
+        ObjectId,
+    };
+    use std::collections::HashMap;
+
+    fn entry(mode: EntryMode, filename: &str, oid: ObjectId) -> mutable::tree::Entry {
+        mutable::tree::Entry {
+            mode,
+            filename: filename.into(),
+            oid,
+        }
+    }
+
+    /// A root tree with a file `a`, and a tree `dir` containing a file `b` and the tree `sub` with file `c`.
+    fn db() -> crate::Result<(ObjectId, HashMap<ObjectId, Vec<u8>>)> {
+        let blob = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+        let (root, dir, sub) = (
+            hex_to_id("0000000000000000000000000000000000000001"),
+            hex_to_id("0000000000000000000000000000000000000002"),
+            hex_to_id("0000000000000000000000000000000000000003"),
+        );
+        let mut db = HashMap::new();
+        for (id, entries) in [
+            (
+                root,
+                vec![entry(EntryMode::Blob, "a", blob), entry(EntryMode::Tree, "dir", dir)],
+            ),
+            (
+                dir,
+                vec![
+                    entry(EntryMode::BlobExecutable, "b", blob),
+                    entry(EntryMode::Tree, "sub", sub),
+                ],
+            ),
+            (sub, vec![entry(EntryMode::Link, "c", blob)]),
+        ] {
+            let mut data = Vec::new();
+            mutable::Tree { entries }.write_to(&mut data)?;
+            db.insert(id, data);
+        }
+        Ok((root, db))
+    }
+
+    fn lookup(path: &str) -> crate::Result<Option<mutable::tree::Entry>> {
+        let (root, db) = db()?;
+        let mut buf = Vec::new();
+        Ok(tree::lookup_entry(&root, path, &mut buf, |oid, buf| {
+            let data = db.get(oid)?;
+            buf.clear();
+            buf.extend_from_slice(data);
+            Some(immutable::TreeIter::from_bytes(buf))
+        })?)
+    }
+
+    #[test]
+    fn nested_entries_are_found_with_their_mode() -> crate::Result {
+        let blob = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+        assert_eq!(lookup("a")?, Some(entry(EntryMode::Blob, "a", blob)));
+        assert_eq!(lookup("dir/b")?, Some(entry(EntryMode::BlobExecutable, "b", blob)));
+        assert_eq!(lookup("dir/sub/c")?, Some(entry(EntryMode::Link, "c", blob)));
+        assert_eq!(
+            lookup("dir/sub")?,
+            Some(entry(
+                EntryMode::Tree,
+                "sub",
+                hex_to_id("0000000000000000000000000000000000000003")
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_components_are_ignored_and_the_empty_path_is_the_root() -> crate::Result {
+        assert_eq!(lookup("dir//sub/")?, lookup("dir/sub")?);
+        let root = lookup("")?.expect("root is always found");
+        assert_eq!(root.mode, EntryMode::Tree);
+        assert_eq!(root.oid, hex_to_id("0000000000000000000000000000000000000001"));
+        Ok(())
+    }
+
+    #[test]
+    fn missing_paths_yield_none() -> crate::Result {
+        assert_eq!(lookup("missing")?, None);
+        assert_eq!(lookup("dir/missing")?, None);
+        assert_eq!(lookup("a/b")?, None, "blobs have no entries");
+        assert_eq!(lookup("di")?, None, "only full names match");
+        Ok(())
+    }
+
+    #[test]
+    fn missing_trees_are_an_error() {
+        let mut buf = Vec::new();
+        let err = tree::lookup_entry(
+            &hex_to_id("0000000000000000000000000000000000000001"),
+            "a",
+            &mut buf,
+            |_, _| None,
+        )
+        .expect_err("the root tree is missing");
+        assert!(matches!(err, tree::lookup::Error::NotFound { .. }));
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    ops::Deref,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::SystemTime,
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::pack;
+
+/// A pack bundle on disk as seen by a [`compound::Store`][crate::compound::Store], which is loaded lazily or eagerly depending
+/// on the [index loading policy][crate::compound::init::IndexLoading], and which may be unmapped again if a
+/// [cap on mapped packs][crate::compound::init::Options::max_mapped_packs] is set or if the store is
+/// [subject to the memory cap][crate::compound::init::Options::subject_to_memory_cap].
+pub struct Bundle {
+    /// The path to the pack index file, with the pack data file next to it.
+    pub index_path: PathBuf,
+    /// The size of the index file in bytes, which is proportional to the amount of objects in the pack.
+    pub index_size: u64,
+    /// The time at which the index file was last modified.
+    pub modified: SystemTime,
+    /// If true, a `.promisor` file exists next to the pack as it was received from a promisor remote during a partial clone
+    /// or fetch, which is why objects it refers to may be missing locally.
+    pub is_promisor: bool,
+    /// Bundles which are never unmapped once loaded, allowing to hand out references to their data.
+    pinned: OnceCell<pack::Bundle>,
+    /// Bundles which may be unmapped at any time, and which are shared while in use.
+    ///
+    /// It's shared with the [memory cap][crate::compound::memory] to allow it to unmap bundles of all participating stores.
+    pub(crate) evictable: Arc<Evictable>,
+}
+
+/// A clock shared by all stores to track when bundles were last used, the higher the more recent.
+static CLOCK: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) fn tick() -> usize {
+    CLOCK.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A bundle which may be unmapped at any time.
+pub(crate) struct Evictable {
+    bundle: RwLock<Option<Arc<pack::Bundle>>>,
+    /// A value of the shared clock at the time of the last use.
+    last_used: AtomicUsize,
+    index_size: u64,
+}
+
+impl Evictable {
+    pub(crate) fn mapped_bytes(&self) -> Option<u64> {
+        self.bundle
+            .read()
+            .expect("no panic while holding lock")
+            .as_ref()
+            .map(|bundle| mapped_bytes(self.index_size, bundle))
+    }
+
+    pub(crate) fn last_used_if_mapped(&self) -> Option<usize> {
+        self.bundle
+            .read()
+            .expect("no panic while holding lock")
+            .as_ref()
+            .map(|_| self.last_used.load(Ordering::Relaxed))
+    }
+
+    /// Unmap the bundle, returning true if it was mapped before.
+    ///
+    /// Note that the memory map is only released once all [shared references][BundleRef::Shared] are dropped.
+    pub(crate) fn unmap(&self) -> bool {
+        self.bundle
+            .write()
+            .expect("no panic while holding lock")
+            .take()
+            .is_some()
+    }
+}
+
+fn mapped_bytes(index_size: u64, bundle: &pack::Bundle) -> u64 {
+    index_size + bundle.pack.data_len() as u64
+}
+
+/// A loaded [`pack::Bundle`] as obtained by [`compound::Store::bundle()`][crate::compound::Store::bundle()].
+pub enum BundleRef<'a> {
+    /// The bundle will remain mapped for as long as the store exists.
+    Pinned(&'a pack::Bundle),
+    /// The bundle may be unmapped by the store once this instance is dropped.
+    Shared(Arc<pack::Bundle>),
+}
+
+impl<'a> BundleRef<'a> {
+    /// Return the bundle with the lifetime of the owning store if it is pinned, or `None` if it could be unmapped at any time.
+    pub fn pinned(&self) -> Option<&'a pack::Bundle> {
+        match self {
+            BundleRef::Pinned(b) => Some(b),
+            BundleRef::Shared(_) => None,
+        }
+    }
+}
+
+impl<'a> Deref for BundleRef<'a> {
+    type Target = pack::Bundle;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            BundleRef::Pinned(b) => b,
+            BundleRef::Shared(b) => b,
+        }
+    }
+}
+
+impl Bundle {
+    pub(crate) fn new(index_path: PathBuf, index_size: u64, modified: SystemTime) -> Self {
+        Bundle {
+            is_promisor: index_path.with_extension("promisor").is_file(),
+            index_path,
+            index_size,
+            modified,
+            pinned: OnceCell::new(),
+            evictable: Arc::new(Evictable {
+                bundle: RwLock::new(None),
+                last_used: AtomicUsize::new(0),
+                index_size,
+            }),
+        }
+    }
+
+    /// Return true if the bundle is currently loaded and mapped into memory.
+    pub fn is_mapped(&self) -> bool {
+        self.mapped_bytes().is_some()
+    }
+
+    /// Return the amount of bytes of the index and pack files currently mapped into memory, or `None` if the bundle isn't mapped.
+    pub fn mapped_bytes(&self) -> Option<u64> {
+        match self.pinned.get() {
+            Some(bundle) => Some(mapped_bytes(self.index_size, bundle)),
+            None => self.evictable.mapped_bytes(),
+        }
+    }
+
+    fn load(&self, sanity_check: bool) -> Result<pack::Bundle, pack::bundle::Error> {
+        let bundle = pack::Bundle::at(&self.index_path)?;
+        if sanity_check {
+            bundle.sanity_check()?;
+        }
+        Ok(bundle)
+    }
+
+    pub(crate) fn pinned_or_load(&self, sanity_check: bool) -> Result<&pack::Bundle, pack::bundle::Error> {
+        self.pinned.get_or_try_init(|| self.load(sanity_check))
+    }
+
+    pub(crate) fn pinned(&self) -> Option<&pack::Bundle> {
+        self.pinned.get()
+    }
+
+    pub(crate) fn shared(&self, tick: usize) -> Option<Arc<pack::Bundle>> {
+        let bundle = self
+            .evictable
+            .bundle
+            .read()
+            .expect("no panic while holding lock")
+            .clone();
+        if bundle.is_some() {
+            self.evictable.last_used.store(tick, Ordering::Relaxed);
+        }
+        bundle
+    }
+
+    /// Load the bundle if it is not yet loaded, and return it along with `true` if it was loaded by this call.
+    pub(crate) fn shared_or_load(
+        &self,
+        tick: usize,
+        sanity_check: bool,
+    ) -> Result<(Arc<pack::Bundle>, bool), pack::bundle::Error> {
+        let mut slot = self.evictable.bundle.write().expect("no panic while holding lock");
+        self.evictable.last_used.store(tick, Ordering::Relaxed);
+        Ok(match slot.as_ref() {
+            Some(bundle) => (Arc::clone(bundle), false),
+            None => {
+                let bundle = Arc::new(self.load(sanity_check)?);
+                *slot = Some(Arc::clone(&bundle));
+                (bundle, true)
+            }
+        })
+    }
+}
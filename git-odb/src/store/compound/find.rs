@@ -2,7 +2,7 @@ use crate::{
     pack,
     store::{compound, loose},
 };
-use git_pack::data;
+use git_pack::{data, find::Entry};
 
 /// Returned by [`compound::Store::find()`]
 #[derive(thiserror::Error, Debug)]
@@ -12,6 +12,10 @@ pub enum Error {
     Loose(#[from] loose::find::Error),
     #[error("An error occurred while obtaining an object from the packed object store")]
     Pack(#[from] pack::data::decode_entry::Error),
+    #[error("A pack could not be loaded")]
+    LoadPack(#[from] pack::bundle::Error),
+    #[error("An object in a promisor pack could not be decoded")]
+    Decode(#[from] git_object::immutable::object::decode::Error),
 }
 
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
@@ -22,7 +26,7 @@ pub(crate) struct PackLocation {
 
 impl compound::Store {
     /// Find an object as identified by [`ObjectId`][git_hash::ObjectId] and store its data in full in the provided `buffer`.
-    /// This will search the object in all contained object databases.
+    /// This will search the object in all contained object databases, in the order defined by our [`options`][compound::Store::options].
     /// Use a `pack_cache` to accelerate pack access by reducing the amount of work duplication, or [`pack::cache::Never`] to disable any caching.
     pub fn find<'a>(
         &self,
@@ -31,34 +35,144 @@ impl compound::Store {
         pack_cache: &mut impl pack::cache::DecodeEntry,
     ) -> Result<Option<data::Object<'a>>, Error> {
         let id = id.as_ref();
-        for bundle in &self.bundles {
-            if let Some(idx) = find_pack_index(bundle, id) {
-                let object = bundle.get_object_by_index(idx, buffer, pack_cache)?;
-                return Ok(Some(object));
-            }
+        if self.options.loose_first && self.loose.contains(id) {
+            return self.loose.find(id, buffer).map_err(Into::into);
+        }
+        if let Some(PackLocation {
+            bundle_index,
+            entry_index,
+        }) = self.internal_find_packed(id)?
+        {
+            return self
+                .internal_get_packed_object_by_index(bundle_index, entry_index, buffer, pack_cache)
+                .map(Some);
         }
-        if self.loose.contains(id) {
+        if !self.options.loose_first && self.loose.contains(id) {
             return self.loose.find(id, buffer).map_err(Into::into);
         }
         Ok(None)
     }
 
+    /// Return the location of the pack entry for the object with `id`, or `None` if it isn't stored in any of our packs.
+    ///
+    /// This doesn't decode the object, and can be used to reuse pack entries as is when generating packs.
+    /// Packs which fail to load are skipped as they can't provide a location anyway.
+    pub fn location_by_id(&self, id: impl AsRef<git_hash::oid>) -> Option<pack::bundle::Location> {
+        let id = id.as_ref();
+        (0..self.bundles.len()).find_map(|bundle_index| {
+            let bundle = self.bundle(bundle_index).ok()?;
+            find_pack_index(&bundle, id).map(|entry_index| bundle.location_at_index(entry_index))
+        })
+    }
+
+    /// Return the checksum of the pack with `pack_id` if it is one of our packs.
+    ///
+    /// Packs which fail to load are skipped as their checksum can't be obtained.
+    pub fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId> {
+        (0..self.bundles.len()).find_map(|bundle_index| {
+            let bundle = self.bundle(bundle_index).ok()?;
+            (bundle.pack.id == pack_id).then(|| bundle.pack.checksum())
+        })
+    }
+
+    /// Return the pack [`Entry`] at `location` if it is in one of our packs and that pack stays mapped for as long as we exist.
+    pub fn entry_by_location(&self, location: &pack::bundle::Location) -> Option<Entry<'_>> {
+        (0..self.bundles.len())
+            .filter_map(|idx| self.pinned_bundle(idx))
+            .find(|b| b.pack.id == location.pack_id)
+            .and_then(|bundle| {
+                bundle.pack.entry_slice(location.entry_range()).map(|data| Entry {
+                    data,
+                    crc32: bundle.index.crc32_at_index(location.index_file_id),
+                    version: bundle.pack.version(),
+                })
+            })
+    }
+
+    /// Return true if the given object `id` is contained in a pack or as loose object.
+    ///
+    /// Packs are checked first as looking up ids in their indices doesn't require any IO once they are loaded.
+    /// Packs which fail to load are skipped, so the object may still be found in other packs or as loose object.
+    pub fn contains(&self, id: impl AsRef<git_hash::oid>) -> bool {
+        let id = id.as_ref();
+        (0..self.bundles.len())
+            .any(|bundle_index| matches!(self.bundle(bundle_index).map(|b| find_pack_index(&b, id)), Ok(Some(_))))
+            || self.loose.contains(id)
+    }
+
+    /// Return the loaded pack bundle at `bundle_index` in [`bundles`][compound::Store::bundles], loading it if necessary.
+    ///
+    /// If a [cap on mapped packs][compound::init::Options::max_mapped_packs] is set, or if the store is
+    /// [subject to the memory cap][compound::init::Options::subject_to_memory_cap], loading a bundle may unmap
+    /// the least recently used ones.
+    /// Newly loaded bundles are [sanity-checked][pack::Bundle::sanity_check()] unless
+    /// [disabled][compound::init::Options::skip_sanity_check].
+    pub fn bundle(&self, bundle_index: usize) -> Result<compound::BundleRef<'_>, pack::bundle::Error> {
+        let slot = &self.bundles[bundle_index];
+        let sanity_check = !self.options.skip_sanity_check;
+        if self.options.max_mapped_packs.is_none() && !self.options.subject_to_memory_cap {
+            return slot.pinned_or_load(sanity_check).map(compound::BundleRef::Pinned);
+        }
+        let tick = compound::bundle::tick();
+        if let Some(bundle) = slot.shared(tick) {
+            return Ok(compound::BundleRef::Shared(bundle));
+        }
+
+        let _loading = self.loading.lock().expect("no panic while holding lock");
+        let (bundle, newly_loaded) = slot.shared_or_load(tick, sanity_check)?;
+        if newly_loaded {
+            if let Some(cap) = self.options.max_mapped_packs.map(|cap| cap.max(1)) {
+                let mut mapped = self.bundles.iter().filter(|b| b.is_mapped()).count();
+                while mapped > cap {
+                    let least_recently_used = self
+                        .bundles
+                        .iter()
+                        .enumerate()
+                        .filter(|(idx, _)| *idx != bundle_index)
+                        .filter_map(|(idx, b)| b.evictable.last_used_if_mapped().map(|last_used| (last_used, idx)))
+                        .min();
+                    match least_recently_used {
+                        Some((_, idx)) => {
+                            if self.bundles[idx].evictable.unmap() {
+                                mapped -= 1;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+            }
+            if self.options.subject_to_memory_cap {
+                compound::memory::enforce_soft_cap(&slot.evictable);
+            }
+        }
+        Ok(compound::BundleRef::Shared(bundle))
+    }
+
+    /// Return the bundle at `bundle_index` only if it is loaded and will stay mapped for as long as we exist.
+    pub(crate) fn pinned_bundle(&self, bundle_index: usize) -> Option<&pack::Bundle> {
+        self.bundles[bundle_index].pinned()
+    }
+
     /// Internal-use function to look up a packed object index or loose object.
     /// Used to avoid double-lookups in linked::Store::locate.
     /// (The polonius borrow-checker would support this via the locate
     /// function, so this can be [simplified](https://github.com/Byron/gitoxide/blob/0c5f4043da4615820cb180804a81c2d4fe75fe5e/git-odb/src/compound/locate.rs#L47)
     /// once polonius is stable.)
-    pub(crate) fn internal_find_packed(&self, id: impl AsRef<git_hash::oid>) -> Option<PackLocation> {
+    pub(crate) fn internal_find_packed(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+    ) -> Result<Option<PackLocation>, pack::bundle::Error> {
         let id = id.as_ref();
-        for (bundle_index, bundle) in self.bundles.iter().enumerate() {
-            if let Some(idx) = find_pack_index(bundle, id) {
-                return Some(PackLocation {
+        for bundle_index in 0..self.bundles.len() {
+            let bundle = self.bundle(bundle_index)?;
+            if let Some(idx) = find_pack_index(&bundle, id) {
+                return Ok(Some(PackLocation {
                     bundle_index,
                     entry_index: idx,
-                });
+                }));
             }
         }
-        None
+        Ok(None)
     }
 
     pub(crate) fn internal_get_packed_object_by_index<'a>(
@@ -67,8 +181,35 @@ impl compound::Store {
         object_index: u32,
         buffer: &'a mut Vec<u8>,
         pack_cache: &mut impl pack::cache::DecodeEntry,
-    ) -> Result<data::Object<'a>, pack::data::decode_entry::Error> {
-        self.bundles[pack_index].get_object_by_index(object_index, buffer, pack_cache)
+    ) -> Result<data::Object<'a>, Error> {
+        Ok(self
+            .bundle(pack_index)?
+            .get_object_by_index(object_index, buffer, pack_cache)?)
+    }
+}
+
+impl crate::Find for compound::Store {
+    type Error = Error;
+
+    fn find<'a>(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<data::Object<'a>>, Self::Error> {
+        compound::Store::find(self, id, buffer, pack_cache)
+    }
+
+    fn location_by_id(&self, id: impl AsRef<git_hash::oid>, _buf: &mut Vec<u8>) -> Option<pack::bundle::Location> {
+        compound::Store::location_by_id(self, id)
+    }
+
+    fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId> {
+        compound::Store::pack_checksum(self, pack_id)
+    }
+
+    fn entry_by_location(&self, location: &pack::bundle::Location) -> Option<Entry<'_>> {
+        compound::Store::entry_by_location(self, location)
     }
 }
 
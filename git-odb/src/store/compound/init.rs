@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc, sync::Mutex};
+
+use once_cell::sync::OnceCell;
 
 use crate::{
     pack,
@@ -17,6 +19,69 @@ pub enum Error {
     Alternate(#[from] Box<crate::alternate::Error>),
 }
 
+/// The order in which packs are searched for objects.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum PackOrder {
+    /// Search the largest packs first as they are most likely to contain the object.
+    BySize,
+    /// Search the most recently modified packs first, which favors objects that were recently fetched or written.
+    ByModificationTime,
+}
+
+impl Default for PackOrder {
+    fn default() -> Self {
+        PackOrder::BySize
+    }
+}
+
+/// Determines when pack indices and their packs are loaded and mapped into memory.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum IndexLoading {
+    /// Load all packs when the store is instantiated, failing early if one of them is invalid.
+    Eager,
+    /// Load packs only when they are needed for the first time, which makes instantiation fast
+    /// in repositories with many packs.
+    Lazy,
+}
+
+impl Default for IndexLoading {
+    fn default() -> Self {
+        IndexLoading::Eager
+    }
+}
+
+/// Configuration for [`compound::Store::at_opts()`].
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Options {
+    /// The order in which packs are searched.
+    pub pack_order: PackOrder,
+    /// If true, loose objects are searched before any pack.
+    pub loose_first: bool,
+    /// Whether to load packs when instantiating the store or when they are first used.
+    pub index_loading: IndexLoading,
+    /// If set, the amount of packs that may be mapped at the same time, with the least recently used pack being
+    /// unmapped to make room for a new one. A value of 0 is treated as 1.
+    ///
+    /// Note that packs which may be unmapped can't provide their entries for [copying][crate::Find::entry_by_location()],
+    /// which causes objects to be recompressed during pack generation instead.
+    pub max_mapped_packs: Option<usize>,
+    /// If true, the packs of this store count towards the process-wide [soft cap on mapped bytes][compound::memory::set_soft_cap()],
+    /// and the least recently used ones are unmapped once it is exceeded.
+    ///
+    /// Like with [`max_mapped_packs`][Options::max_mapped_packs], these packs can't provide their entries for copying.
+    pub subject_to_memory_cap: bool,
+    /// If true, objects are written as loose objects even if they are already contained in the store.
+    ///
+    /// By default, the id of objects is computed before writing them to skip those that already exist in a pack or as
+    /// loose object, which avoids duplicating objects received from multiple sources.
+    pub write_existing_objects: bool,
+    /// If true, packs are used without assuring they match their index when loading them.
+    ///
+    /// By default, each pack is [sanity-checked][pack::Bundle::sanity_check()] once it was loaded, which catches mixed-up
+    /// pairs of index and pack files early instead of failing with confusing errors when decoding objects.
+    pub skip_sanity_check: bool,
+}
+
 /// Instantiation
 impl compound::Store {
     /// Returns a compound database as initialized from the given git `objects_directory`, commonly `.git/objects`.
@@ -24,13 +89,18 @@ impl compound::Store {
     /// Only loose and packed objects will be considered. See the [linked Db][crate::store::linked::Store] for a database with
     /// support for _git alternates_, i.e. linking to other repositories.
     pub fn at(objects_directory: impl Into<PathBuf>) -> Result<compound::Store, Error> {
+        Self::at_opts(objects_directory, Options::default())
+    }
+
+    /// Like [`at()`][compound::Store::at()], but with `options` to control the lookup order and how packs are loaded.
+    pub fn at_opts(objects_directory: impl Into<PathBuf>, options: Options) -> Result<compound::Store, Error> {
         let loose_objects = objects_directory.into();
         if !loose_objects.is_dir() {
             return Err(Error::Inaccessible(loose_objects));
         }
-        let packs = match std::fs::read_dir(loose_objects.join("pack")) {
+        let bundles = match std::fs::read_dir(loose_objects.join("pack")) {
             Ok(entries) => {
-                let mut packs_and_sizes = entries
+                let mut bundles = entries
                     .filter_map(Result::ok)
                     .filter_map(|e| e.metadata().map(|md| (e.path(), md)).ok())
                     .filter(|(_, md)| md.file_type().is_file())
@@ -38,17 +108,39 @@ impl compound::Store {
                         p.extension().unwrap_or_default() == "idx"
                             && p.file_name().unwrap_or_default().to_string_lossy().starts_with("pack-")
                     })
-                    .map(|(p, md)| pack::Bundle::at(p).map(|b| (b, md.len())))
-                    .collect::<Result<Vec<_>, _>>()?;
-                packs_and_sizes.sort_by_key(|e| e.1);
-                packs_and_sizes.into_iter().rev().map(|(b, _)| b).collect()
+                    .map(|(p, md)| {
+                        let modified = md.modified().unwrap_or(std::time::UNIX_EPOCH);
+                        compound::Bundle::new(p, md.len(), modified)
+                    })
+                    .collect::<Vec<_>>();
+                match options.pack_order {
+                    PackOrder::BySize => bundles.sort_by_key(|b| b.index_size),
+                    PackOrder::ByModificationTime => bundles.sort_by_key(|b| b.modified),
+                }
+                bundles.reverse();
+                bundles
             }
             Err(_) => Vec::new(),
         };
 
-        Ok(compound::Store {
+        let db = compound::Store {
             loose: loose::Store::at(loose_objects),
-            bundles: packs,
-        })
+            bundles,
+            options,
+            loading: Mutex::new(()),
+            promised: OnceCell::new(),
+        };
+        if options.subject_to_memory_cap {
+            compound::memory::register(db.bundles.iter().map(|b| Arc::downgrade(&b.evictable)));
+        }
+        if options.index_loading == IndexLoading::Eager {
+            let num_bundles = options
+                .max_mapped_packs
+                .map_or(db.bundles.len(), |cap| cap.max(1).min(db.bundles.len()));
+            for bundle_index in 0..num_bundles {
+                db.bundle(bundle_index)?;
+            }
+        }
+        Ok(db)
     }
 }
@@ -0,0 +1,88 @@
+//! Accounting of the memory used by mapped packs of all stores participating in the process-wide soft cap, which unmaps
+//! the least recently used packs once it is exceeded.
+//!
+//! Stores participate if their [`subject_to_memory_cap`][crate::compound::init::Options::subject_to_memory_cap] option is set.
+//! This is useful for services which keep many repositories open at the same time.
+use std::sync::{Arc, Mutex, Weak};
+
+use once_cell::sync::Lazy;
+
+use crate::store::compound::bundle::Evictable;
+
+#[derive(Default)]
+struct Registry {
+    soft_cap: Option<u64>,
+    bundles: Vec<Weak<Evictable>>,
+}
+
+impl Registry {
+    /// Return all bundles which are still alive, forgetting about those whose stores were dropped.
+    fn live_bundles(&mut self) -> Vec<Arc<Evictable>> {
+        self.bundles.retain(|b| b.strong_count() > 0);
+        self.bundles.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    /// Unmap the least recently used bundles other than `keep` until the mapped bytes don't exceed the soft cap anymore.
+    fn enforce(&mut self, keep: Option<&Arc<Evictable>>) {
+        let soft_cap = match self.soft_cap {
+            Some(cap) => cap,
+            None => return,
+        };
+        let bundles = self.live_bundles();
+        let mut mapped_bytes: u64 = bundles.iter().filter_map(|b| b.mapped_bytes()).sum();
+        while mapped_bytes > soft_cap {
+            let least_recently_used = bundles
+                .iter()
+                .filter(|b| keep.map_or(true, |keep| !Arc::ptr_eq(b, keep)))
+                .filter_map(|b| b.last_used_if_mapped().map(|last_used| (last_used, b)))
+                .min_by_key(|(last_used, _)| *last_used);
+            match least_recently_used {
+                Some((_, bundle)) => {
+                    let bytes = bundle.mapped_bytes().unwrap_or_default();
+                    if bundle.unmap() {
+                        mapped_bytes -= bytes;
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+static REGISTRY: Lazy<Mutex<Registry>> = Lazy::new(Default::default);
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+    REGISTRY.lock().expect("no panic while holding lock")
+}
+
+/// Return the amount of bytes of index and pack files currently mapped by all participating stores.
+///
+/// Note that memory maps of unmapped packs are only released once all [shared references][crate::compound::BundleRef::Shared]
+/// to them are dropped.
+pub fn mapped_bytes() -> u64 {
+    registry().live_bundles().iter().filter_map(|b| b.mapped_bytes()).sum()
+}
+
+/// Return the currently set soft cap, if any.
+pub fn soft_cap() -> Option<u64> {
+    registry().soft_cap
+}
+
+/// Set the amount of bytes of index and pack files that participating stores may map at the same time to `cap`, or remove
+/// the cap if `None`.
+///
+/// Once exceeded, the least recently used packs are unmapped, which also happens right away if the new cap is already exceeded.
+/// The cap is soft as the pack being loaded is never unmapped to make room, even if it exceeds the cap on its own.
+pub fn set_soft_cap(cap: Option<u64>) {
+    let mut registry = registry();
+    registry.soft_cap = cap;
+    registry.enforce(None);
+}
+
+pub(crate) fn register(bundles: impl IntoIterator<Item = Weak<Evictable>>) {
+    registry().bundles.extend(bundles);
+}
+
+pub(crate) fn enforce_soft_cap(keep: &Arc<Evictable>) {
+    registry().enforce(Some(keep));
+}
@@ -1,17 +1,43 @@
 //! An object database delegating object access to multiple contained object databases with loose and packed objects.
-use crate::{pack, store::loose};
+use std::{collections::HashSet, sync::Mutex};
+
+use git_hash::ObjectId;
+use once_cell::sync::OnceCell;
+
+use crate::store::loose;
+
+mod bundle;
+pub use bundle::{Bundle, BundleRef};
 
 ///
 pub mod find;
 ///
 pub mod init;
-mod write;
+///
+pub mod memory;
+mod promisor;
+pub(crate) mod write;
 
 /// An object database with tiered lookup packs and loose objects.
 /// This is a typical git database as used in git repositories, sans 'alternates'.
 pub struct Store {
     /// A loose object database into which new objects are written
     pub loose: loose::Store,
-    /// All packs in the `objects/packs` directory
-    pub bundles: Vec<pack::Bundle>,
+    /// All packs in the `objects/packs` directory, in lookup order as defined by [`options`][Store::options].
+    ///
+    /// Use [`bundle()`][Store::bundle()] to access the loaded pack bundle.
+    pub bundles: Vec<Bundle>,
+    /// The policy used to find objects and to load packs.
+    pub options: init::Options,
+    /// A lock to serialize loading and unmapping of bundles which may be unmapped.
+    loading: Mutex<()>,
+    /// All objects referenced by objects in promisor packs, computed on first use.
+    promised: OnceCell<HashSet<ObjectId>>,
+}
+
+impl Store {
+    /// Return the amount of bytes of index and pack files currently mapped into memory by all of our [`bundles`][Store::bundles].
+    pub fn mapped_bytes(&self) -> u64 {
+        self.bundles.iter().filter_map(|b| b.mapped_bytes()).sum()
+    }
 }
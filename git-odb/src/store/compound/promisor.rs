@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+
+use git_hash::{oid, ObjectId};
+use git_object::{immutable, tree::EntryMode};
+
+use crate::{
+    pack,
+    store::compound::{self, find::Error},
+};
+
+/// Partial clones
+impl compound::Store {
+    /// Return true if at least one of our packs was received from a promisor remote, making this store part of a partial clone.
+    ///
+    /// In partial clones, objects may be missing locally as the remote promised to provide them on demand.
+    pub fn is_partial_clone(&self) -> bool {
+        self.bundles.iter().any(|b| b.is_promisor)
+    }
+
+    /// Return true if `id` is referenced by an object in one of our promisor packs, which is why it may be missing locally
+    /// without indicating corruption.
+    ///
+    /// The first call decodes all objects in promisor packs, which takes time proportional to their size.
+    pub fn is_promised(&self, id: impl AsRef<oid>) -> Result<bool, Error> {
+        if !self.is_partial_clone() {
+            return Ok(false);
+        }
+        Ok(self.promised_objects()?.contains(id.as_ref()))
+    }
+
+    fn promised_objects(&self) -> Result<&HashSet<ObjectId>, Error> {
+        self.promised.get_or_try_init(|| {
+            let mut promised = HashSet::new();
+            let mut buf = Vec::new();
+            for bundle_index in (0..self.bundles.len()).filter(|idx| self.bundles[*idx].is_promisor) {
+                let num_objects = self.bundle(bundle_index)?.index.num_objects();
+                for entry_index in 0..num_objects {
+                    let object = self.internal_get_packed_object_by_index(
+                        bundle_index,
+                        entry_index,
+                        &mut buf,
+                        &mut pack::cache::Never,
+                    )?;
+                    match object.decode()? {
+                        immutable::Object::Commit(commit) => {
+                            promised.insert(commit.tree());
+                            promised.extend(commit.parents());
+                        }
+                        immutable::Object::Tree(tree) => promised.extend(
+                            tree.entries
+                                .iter()
+                                .filter(|entry| entry.mode != EntryMode::Commit)
+                                .map(|entry| entry.oid.to_owned()),
+                        ),
+                        immutable::Object::Tag(tag) => {
+                            promised.insert(tag.target());
+                        }
+                        immutable::Object::Blob(_) => {}
+                    }
+                }
+            }
+            Ok(promised)
+        })
+    }
+}
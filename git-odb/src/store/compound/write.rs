@@ -7,11 +7,14 @@ impl crate::write::Write for compound::Store {
     type Error = loose::write::Error;
 
     fn write(&self, object: &mutable::Object, hash: git_hash::Kind) -> Result<git_hash::ObjectId, Self::Error> {
-        self.loose.write(object, hash)
+        let mut buf = Vec::with_capacity(2048);
+        object.write_to(&mut buf)?;
+        self.write_buf(object.kind(), &buf, hash)
     }
 
     fn write_buf(&self, object: Kind, from: &[u8], hash: git_hash::Kind) -> Result<git_hash::ObjectId, Self::Error> {
-        self.loose.write_buf(object, from, hash)
+        self.write_buf_with_outcome(object, from, hash)
+            .map(|outcome| outcome.id)
     }
 
     fn write_stream(
@@ -21,6 +24,77 @@ impl crate::write::Write for compound::Store {
         from: impl Read,
         hash: git_hash::Kind,
     ) -> Result<git_hash::ObjectId, Self::Error> {
-        self.loose.write_stream(kind, size, from, hash)
+        self.write_stream_with_outcome(kind, size, from, hash)
+            .map(|outcome| outcome.id)
     }
 }
+
+/// Writing with existence checks
+impl compound::Store {
+    /// Write the `object` kind with its encoded bytes in `from` as loose object, unless it is already contained in this store
+    /// as indicated by the returned [`Outcome`][loose::write::Outcome].
+    ///
+    /// The object id is computed before compressing anything, so existing objects cost no more than hashing them.
+    /// Use [`write_existing_objects`][compound::init::Options::write_existing_objects] to always write objects.
+    pub fn write_buf_with_outcome(
+        &self,
+        object: Kind,
+        from: &[u8],
+        hash: git_hash::Kind,
+    ) -> Result<loose::write::Outcome, loose::write::Error> {
+        write_buf_unless_present(&self.loose, self.options, object, from, hash, |id| self.contains(id))
+    }
+
+    /// As [`write_buf_with_outcome()`][compound::Store::write_buf_with_outcome()], but takes an input stream.
+    ///
+    /// As the object id is only known once the whole stream was read, existing objects are compressed but not written.
+    pub fn write_stream_with_outcome(
+        &self,
+        kind: Kind,
+        size: u64,
+        from: impl Read,
+        hash: git_hash::Kind,
+    ) -> Result<loose::write::Outcome, loose::write::Error> {
+        write_stream_unless_present(&self.loose, self.options, kind, size, from, hash, |id| {
+            self.contains(id)
+        })
+    }
+}
+
+pub(crate) fn write_buf_unless_present(
+    loose: &loose::Store,
+    options: compound::init::Options,
+    kind: Kind,
+    from: &[u8],
+    hash: git_hash::Kind,
+    is_present: impl FnOnce(&git_hash::oid) -> bool,
+) -> Result<loose::write::Outcome, loose::write::Error> {
+    use crate::write::Write;
+    if !options.write_existing_objects {
+        let id = crate::sink().write_buf(kind, from, hash)?;
+        if is_present(&id) {
+            return Ok(loose::write::Outcome {
+                id,
+                already_present: true,
+            });
+        }
+    }
+    loose.write_buf(kind, from, hash).map(|id| loose::write::Outcome {
+        id,
+        already_present: false,
+    })
+}
+
+pub(crate) fn write_stream_unless_present(
+    loose: &loose::Store,
+    options: compound::init::Options,
+    kind: Kind,
+    size: u64,
+    from: impl Read,
+    hash: git_hash::Kind,
+    is_present: impl FnOnce(&git_hash::oid) -> bool,
+) -> Result<loose::write::Outcome, loose::write::Error> {
+    loose.write_stream_unless_present(kind, size, from, hash, |id| {
+        !options.write_existing_objects && is_present(id)
+    })
+}
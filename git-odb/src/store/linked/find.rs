@@ -1,5 +1,4 @@
 use git_hash::oid;
-use std::convert::TryInto;
 
 use crate::{
     pack,
@@ -11,13 +10,25 @@ use git_pack::{data::Object, find::Entry};
 impl linked::Store {
     /// Return true if the given object `id` is contained in the store.
     pub fn contains(&self, id: impl AsRef<oid>) -> bool {
+        let id = id.as_ref();
+        self.dbs.iter().any(|db| db.contains(id))
+    }
+
+    /// Return true if any of our databases [is a partial clone][compound::Store::is_partial_clone()].
+    pub fn is_partial_clone(&self) -> bool {
+        self.dbs.iter().any(|db| db.is_partial_clone())
+    }
+
+    /// Return true if `id` is [promised][compound::Store::is_promised()] by any of our databases, which means it may be
+    /// missing locally without indicating corruption.
+    pub fn is_promised(&self, id: impl AsRef<oid>) -> Result<bool, compound::find::Error> {
         let id = id.as_ref();
         for db in self.dbs.iter() {
-            if db.internal_find_packed(id).is_some() || db.loose.contains(id) {
-                return true;
+            if db.is_promised(id)? {
+                return Ok(true);
             }
         }
-        false
+        Ok(false)
     }
 }
 
@@ -31,68 +42,46 @@ impl crate::Find for linked::Store {
         pack_cache: &mut impl pack::cache::DecodeEntry,
     ) -> Result<Option<Object<'a>>, Self::Error> {
         let id = id.as_ref();
-        for db in self.dbs.iter() {
-            match db.internal_find_packed(id) {
-                Some(compound::find::PackLocation {
-                    bundle_index: pack_id,
-                    entry_index,
-                }) => {
-                    return db
-                        .internal_get_packed_object_by_index(pack_id, entry_index, buffer, pack_cache)
-                        .map(Some)
-                        .map_err(Into::into)
+        let mut may_retry = true;
+        loop {
+            for db in self.dbs.iter() {
+                if db.options.loose_first && db.loose.contains(id) {
+                    return db.loose.find(id, buffer).map_err(Into::into);
                 }
-                None => {
-                    if db.loose.contains(id) {
-                        return db.loose.find(id, buffer).map_err(Into::into);
+                match db.internal_find_packed(id)? {
+                    Some(compound::find::PackLocation {
+                        bundle_index: pack_id,
+                        entry_index,
+                    }) => {
+                        return db
+                            .internal_get_packed_object_by_index(pack_id, entry_index, buffer, pack_cache)
+                            .map(Some)
+                    }
+                    None => {
+                        if !db.options.loose_first && db.loose.contains(id) {
+                            return db.loose.find(id, buffer).map_err(Into::into);
+                        }
                     }
                 }
             }
+            match &self.missing_object_handler {
+                Some(handler) if may_retry && self.is_partial_clone() && handler(id) => may_retry = false,
+                _ => return Ok(None),
+            }
         }
-        Ok(None)
     }
 
-    fn location_by_id(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<pack::bundle::Location> {
+    fn location_by_id(&self, id: impl AsRef<oid>, _buf: &mut Vec<u8>) -> Option<pack::bundle::Location> {
         let id = id.as_ref();
-        for db in self.dbs.iter() {
-            if let Some(compound::find::PackLocation {
-                bundle_index,
-                entry_index,
-            }) = db.internal_find_packed(id)
-            {
-                let bundle = &db.bundles[bundle_index];
-                let pack_offset = bundle.index.pack_offset_at_index(entry_index);
-                let entry = bundle.pack.entry(pack_offset);
+        self.dbs.iter().find_map(|db| db.location_by_id(id))
+    }
 
-                buf.resize(entry.decompressed_size.try_into().expect("representable szie"), 0);
-                return bundle
-                    .pack
-                    .decompress_entry(&entry, buf)
-                    .ok()
-                    .map(|entry_size_past_header| pack::bundle::Location {
-                        pack_id: bundle.pack.id,
-                        index_file_id: entry_index,
-                        entry_size: entry.header_size() + entry_size_past_header,
-                    });
-            }
-        }
-        None
+    fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId> {
+        self.dbs.iter().find_map(|db| db.pack_checksum(pack_id))
     }
 
     fn entry_by_location(&self, location: &pack::bundle::Location) -> Option<Entry<'_>> {
-        self.dbs
-            .iter()
-            .find_map(|db| db.bundles.iter().find(|p| p.pack.id == location.pack_id))
-            .map(|b| (b, location))
-            .and_then(|(bundle, l)| {
-                let crc32 = bundle.index.crc32_at_index(l.index_file_id);
-                let pack_offset = bundle.index.pack_offset_at_index(l.index_file_id);
-                bundle.pack.entry_slice(l.entry_range(pack_offset)).map(|data| Entry {
-                    data,
-                    crc32,
-                    version: bundle.pack.version(),
-                })
-            })
+        self.dbs.iter().find_map(|db| db.entry_by_location(location))
     }
 }
 
@@ -112,6 +101,10 @@ impl crate::Find for &linked::Store {
         (*self).location_by_id(id, buf)
     }
 
+    fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId> {
+        (*self).pack_checksum(pack_id)
+    }
+
     fn entry_by_location(&self, location: &pack::bundle::Location) -> Option<Entry<'_>> {
         (*self).entry_by_location(location)
     }
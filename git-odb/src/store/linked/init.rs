@@ -18,15 +18,23 @@ impl linked::Store {
     ///
     /// _git alternate_ files will be traversed to build a chain of [`compound::Store`] instances.
     pub fn at(objects_directory: impl Into<PathBuf>) -> Result<Self, Error> {
-        let mut dbs = vec![compound::Store::at(objects_directory.into())?];
+        Self::at_opts(objects_directory, compound::init::Options::default())
+    }
+
+    /// Like [`at()`][linked::Store::at()], but with `options` to use for all [`compound::Store`] instances.
+    pub fn at_opts(objects_directory: impl Into<PathBuf>, options: compound::init::Options) -> Result<Self, Error> {
+        let mut dbs = vec![compound::Store::at_opts(objects_directory.into(), options)?];
         for object_path in alternate::resolve(dbs[0].loose.path.clone())?.into_iter() {
-            dbs.push(compound::Store::at(object_path)?);
+            dbs.push(compound::Store::at_opts(object_path, options)?);
         }
         assert!(
             !dbs.is_empty(),
             "we can rely on at least one compound database to be present"
         );
-        Ok(linked::Store { dbs })
+        Ok(linked::Store {
+            dbs,
+            missing_object_handler: None,
+        })
     }
 }
 
@@ -1,8 +1,20 @@
 use git_hash::ObjectId;
 use std::{borrow::Borrow, option::Option::None, sync::Arc};
 
-use crate::store::linked;
-use crate::store::loose;
+use crate::{
+    pack,
+    store::{linked, loose},
+};
+
+/// The error returned by the [`AllObjects`] iterator.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Loose(#[from] loose::iter::Error),
+    #[error(transparent)]
+    LoadPack(#[from] pack::bundle::Error),
+}
 
 #[allow(clippy::large_enum_variant)]
 enum DbState {
@@ -53,7 +65,7 @@ impl<Db> Iterator for AllObjects<Db>
 where
     Db: Borrow<linked::Store>,
 {
-    type Item = Result<ObjectId, loose::iter::Error>;
+    type Item = Result<ObjectId, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let db = self.db.borrow();
@@ -67,8 +79,12 @@ where
                 entry_index,
             } => {
                 let db = &db.dbs[self.db_index];
-                match db.bundles.get(*pack_index) {
-                    Some(bundle) => {
+                if *pack_index >= db.bundles.len() {
+                    self.db_state = DbState::Loose { iter: db.loose.iter() };
+                    return self.next();
+                }
+                match db.bundle(*pack_index) {
+                    Ok(bundle) => {
                         if *entry_index < bundle.index.num_objects() {
                             let oid = bundle.index.oid_at_index(*entry_index).to_owned();
                             *entry_index += 1;
@@ -79,14 +95,15 @@ where
                             self.next()
                         }
                     }
-                    None => {
-                        self.db_state = DbState::Loose { iter: db.loose.iter() };
-                        self.next()
+                    Err(err) => {
+                        *pack_index += 1;
+                        *entry_index = 0;
+                        Some(Err(err.into()))
                     }
                 }
             }
             DbState::Loose { iter } => match iter.next() {
-                Some(id) => Some(id),
+                Some(id) => Some(id.map_err(Into::into)),
                 None => {
                     self.db_index += 1;
                     self.db_state = Default::default();
@@ -98,11 +115,13 @@ where
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         let packed_objects = self.db.borrow().dbs.iter().fold(0usize, |dbc, db| {
-            dbc.saturating_add(
-                db.bundles
-                    .iter()
-                    .fold(0, |pc, pack| pc.saturating_add(pack.index.num_objects() as usize)),
-            )
+            dbc.saturating_add((0..db.bundles.len()).fold(0, |pc, bundle_index| {
+                pc.saturating_add(
+                    db.bundle(bundle_index)
+                        .map(|b| b.index.num_objects() as usize)
+                        .unwrap_or(0),
+                )
+            }))
         });
         (packed_objects, None)
     }
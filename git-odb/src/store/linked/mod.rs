@@ -1,5 +1,9 @@
 //! An object database representing a list of [compound databases][compound::Store] commonly created using _git alternates_.
 use crate::store::compound;
+use git_hash::oid;
+
+/// A function called with the id of an object missing in a partial clone, which returns true if it retrieved the object.
+pub type MissingObjectHandler = Box<dyn Fn(&oid) -> bool + Send + Sync>;
 
 /// A database with a list of [compound databases][compound::Store] created by traversing git `alternates` files.
 ///
@@ -7,6 +11,12 @@ use crate::store::compound;
 pub struct Store {
     /// The compound databases containing the actual objects.
     pub dbs: Vec<compound::Store>,
+    /// If set and this store [is a partial clone][Store::is_partial_clone()], it will be called for each object that
+    /// can't be found, for example to fetch it from the promisor remote. If it returns true, the lookup is retried once.
+    ///
+    /// Note that packs are only discovered when the store is instantiated, which is why retrieved objects should be
+    /// written as loose objects to be found by the retry.
+    pub missing_object_handler: Option<MissingObjectHandler>,
 }
 
 ///
@@ -18,4 +28,4 @@ mod find;
 mod write;
 
 ///
-mod iter;
+pub mod iter;
@@ -1,17 +1,20 @@
 use git_object::{mutable, Kind};
 use std::io::Read;
 
-use crate::store::{linked, loose};
+use crate::store::{compound, linked, loose};
 
 impl crate::write::Write for linked::Store {
     type Error = loose::write::Error;
 
     fn write(&self, object: &mutable::Object, hash: git_hash::Kind) -> Result<git_hash::ObjectId, Self::Error> {
-        self.dbs[0].loose.write(object, hash)
+        let mut buf = Vec::with_capacity(2048);
+        object.write_to(&mut buf)?;
+        self.write_buf(object.kind(), &buf, hash)
     }
 
     fn write_buf(&self, object: Kind, from: &[u8], hash: git_hash::Kind) -> Result<git_hash::ObjectId, Self::Error> {
-        self.dbs[0].loose.write_buf(object, from, hash)
+        self.write_buf_with_outcome(object, from, hash)
+            .map(|outcome| outcome.id)
     }
 
     fn write_stream(
@@ -21,6 +24,36 @@ impl crate::write::Write for linked::Store {
         from: impl Read,
         hash: git_hash::Kind,
     ) -> Result<git_hash::ObjectId, Self::Error> {
-        self.dbs[0].loose.write_stream(kind, size, from, hash)
+        self.write_stream_with_outcome(kind, size, from, hash)
+            .map(|outcome| outcome.id)
+    }
+}
+
+/// Writing with existence checks
+impl linked::Store {
+    /// Write objects like [`compound::Store::write_buf_with_outcome()`] into the loose object store of our first database,
+    /// unless they are contained in any of our databases.
+    pub fn write_buf_with_outcome(
+        &self,
+        object: Kind,
+        from: &[u8],
+        hash: git_hash::Kind,
+    ) -> Result<loose::write::Outcome, loose::write::Error> {
+        let db = &self.dbs[0];
+        compound::write::write_buf_unless_present(&db.loose, db.options, object, from, hash, |id| self.contains(id))
+    }
+
+    /// As [`write_buf_with_outcome()`][linked::Store::write_buf_with_outcome()], but takes an input stream.
+    pub fn write_stream_with_outcome(
+        &self,
+        kind: Kind,
+        size: u64,
+        from: impl Read,
+        hash: git_hash::Kind,
+    ) -> Result<loose::write::Outcome, loose::write::Error> {
+        let db = &self.dbs[0];
+        compound::write::write_stream_unless_present(&db.loose, db.options, kind, size, from, hash, |id| {
+            self.contains(id)
+        })
     }
 }
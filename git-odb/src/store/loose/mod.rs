@@ -19,6 +19,19 @@ impl Store {
             path: objects_directory.into(),
         }
     }
+
+    /// Create all 256 fanout directories, `00` to `ff`, which otherwise are created on demand when writing objects.
+    ///
+    /// This can speed up the first writes on some filesystems. Directories which exist already are left untouched.
+    pub fn create_fanout_directories(&self) -> std::io::Result<()> {
+        for first_byte in 0..=255u8 {
+            match std::fs::create_dir(self.path.join(format!("{:02x}", first_byte))) {
+                Err(err) if err.kind() != std::io::ErrorKind::AlreadyExists => return Err(err),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
 }
 
 fn sha1_path(id: &git_hash::oid, mut root: PathBuf) -> PathBuf {
@@ -40,4 +53,8 @@ pub mod iter;
 #[doc(inline)]
 pub use iter::Iter;
 ///
+pub mod statistics;
+#[doc(inline)]
+pub use statistics::Statistics;
+///
 pub mod write;
@@ -0,0 +1,99 @@
+use crate::store::loose::Store;
+use std::{io, path::PathBuf};
+
+/// Returned by [`Store::statistics()`]
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the fanout directory or its entries at '{path}'")]
+    Io { source: io::Error, path: PathBuf },
+}
+
+/// The amount of objects and the space they take in a single fanout directory, like `objects/ab/`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+pub struct Fanout {
+    /// The amount of loose objects in the directory.
+    pub count: usize,
+    /// The sum of the sizes of all loose object files in bytes.
+    pub size_on_disk: u64,
+}
+
+/// Information about how loose objects are distributed across the 256 fanout directories, as returned by [`Store::statistics()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Statistics {
+    /// The statistics for each fanout directory, indexed by the first byte of the object ids within it.
+    pub fanout: Vec<Fanout>,
+}
+
+impl Statistics {
+    /// The total amount of loose objects.
+    pub fn count(&self) -> usize {
+        self.fanout.iter().map(|f| f.count).sum()
+    }
+
+    /// The total size of all loose objects on disk in bytes.
+    pub fn size_on_disk(&self) -> u64 {
+        self.fanout.iter().map(|f| f.size_on_disk).sum()
+    }
+
+    /// Return the fanout directory with the most objects along with its first byte, or `None` if there are no objects.
+    pub fn fullest(&self) -> Option<(u8, Fanout)> {
+        self.fanout
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.count != 0)
+            .max_by_key(|(_, f)| f.count)
+            .map(|(byte, f)| (byte as u8, *f))
+    }
+}
+
+/// Statistics
+impl Store {
+    /// Count the loose objects in each of the 256 fanout directories and sum up their sizes to help deciding when to repack.
+    ///
+    /// Files that aren't named like objects are ignored, as are missing fanout directories.
+    pub fn statistics(&self) -> Result<Statistics, Error> {
+        let fanout = (0..=255u8)
+            .map(|first_byte| self.fanout_statistics(first_byte))
+            .collect::<Result<_, _>>()?;
+        Ok(Statistics { fanout })
+    }
+
+    /// Estimate the total amount of loose objects by counting the ones in the single fanout directory for `first_byte`
+    /// and multiplying by 256, just like `git gc --auto` does to quickly determine if there are too many loose objects.
+    pub fn estimate_count(&self, first_byte: u8) -> Result<usize, Error> {
+        self.fanout_statistics(first_byte).map(|f| f.count * 256)
+    }
+
+    fn fanout_statistics(&self, first_byte: u8) -> Result<Fanout, Error> {
+        let dir = self.path.join(format!("{:02x}", first_byte));
+        let mut out = Fanout::default();
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(out),
+            Err(err) => return Err(Error::Io { source: err, path: dir }),
+        };
+        for entry in entries {
+            let entry = entry.map_err(|err| Error::Io {
+                source: err,
+                path: dir.clone(),
+            })?;
+            let is_object_name = match entry.file_name().to_str() {
+                Some(name) => name.len() == 38 && name.bytes().all(|b| b.is_ascii_hexdigit()),
+                None => false,
+            };
+            if !is_object_name {
+                continue;
+            }
+            let metadata = entry.metadata().map_err(|err| Error::Io {
+                source: err,
+                path: entry.path(),
+            })?;
+            if metadata.is_file() {
+                out.count += 1;
+                out.size_on_disk += metadata.len();
+            }
+        }
+        Ok(out)
+    }
+}
@@ -23,6 +23,16 @@ pub enum Error {
     },
 }
 
+/// The outcome of writing an object into a store which skips writing objects it already contains, like
+/// [`compound::Store::write_buf_with_outcome()`][crate::compound::Store::write_buf_with_outcome()].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Outcome {
+    /// The id of the object.
+    pub id: git_hash::ObjectId,
+    /// If true, the object already existed in the store and nothing was written.
+    pub already_present: bool,
+}
+
 impl crate::write::Write for Store {
     type Error = Error;
 
@@ -56,9 +66,30 @@ impl crate::write::Write for Store {
         &self,
         kind: git_object::Kind,
         size: u64,
-        mut from: impl io::Read,
+        from: impl io::Read,
         hash: git_hash::Kind,
     ) -> Result<git_hash::ObjectId, Self::Error> {
+        self.write_stream_unless_present(kind, size, from, hash, |_| false)
+            .map(|outcome| outcome.id)
+    }
+}
+
+type CompressedTempfile = deflate::Write<NamedTempFile>;
+
+/// Writing with existence checks
+impl Store {
+    /// As [`write_stream()`][crate::Write::write_stream()], but calls `is_present` with the id of the object once it is known,
+    /// and discards the object instead of persisting it if it returns true.
+    ///
+    /// As the id is only known once the whole stream was hashed, this avoids duplicate objects but not the cost of compressing them.
+    pub fn write_stream_unless_present(
+        &self,
+        kind: git_object::Kind,
+        size: u64,
+        mut from: impl io::Read,
+        hash: git_hash::Kind,
+        is_present: impl FnOnce(&git_hash::oid) -> bool,
+    ) -> Result<Outcome, Error> {
         match hash {
             git_hash::Kind::Sha1 => {
                 let mut to = self.write_header(kind, size, hash)?;
@@ -68,14 +99,12 @@ impl crate::write::Write for Store {
                     path: self.path.to_owned(),
                 })?;
                 to.flush()?;
-                self.finalize_object(to)
+                self.finalize_object_unless_present(to, is_present)
             }
         }
     }
 }
 
-type CompressedTempfile = deflate::Write<NamedTempFile>;
-
 impl Store {
     fn write_header(
         &self,
@@ -100,11 +129,23 @@ impl Store {
         Ok(to)
     }
 
-    fn finalize_object(
+    fn finalize_object(&self, to: hash::Write<CompressedTempfile>) -> Result<git_hash::ObjectId, Error> {
+        self.finalize_object_unless_present(to, |_| false)
+            .map(|outcome| outcome.id)
+    }
+
+    fn finalize_object_unless_present(
         &self,
         hash::Write { hash, inner: file }: hash::Write<CompressedTempfile>,
-    ) -> Result<git_hash::ObjectId, Error> {
+        is_present: impl FnOnce(&git_hash::oid) -> bool,
+    ) -> Result<Outcome, Error> {
         let id = git_hash::ObjectId::from(hash.digest());
+        if is_present(&id) {
+            return Ok(Outcome {
+                id,
+                already_present: true,
+            });
+        }
         let object_path = loose::sha1_path(&id, self.path.clone());
         let object_dir = object_path
             .parent()
@@ -120,6 +161,9 @@ impl Store {
             source: err,
             target: object_path,
         })?;
-        Ok(id)
+        Ok(Outcome {
+            id,
+            already_present: false,
+        })
     }
 }
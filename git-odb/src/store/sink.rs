@@ -1,7 +1,6 @@
 use git_features::zlib::stream::deflate;
 use std::{
     cell::RefCell,
-    convert::TryInto,
     io::{self, Write},
 };
 
@@ -37,41 +36,30 @@ impl crate::write::Write for Sink {
         &self,
         kind: git_object::Kind,
         size: u64,
-        mut from: impl io::Read,
+        from: impl io::Read,
         hash: git_hash::Kind,
     ) -> Result<git_hash::ObjectId, Self::Error> {
-        use git_features::hash::Sha1;
-        let mut buf = [0u8; 8096];
-
-        let possibly_compress = |buf: &[u8]| -> io::Result<()> {
-            if let Some(compressor) = self.compressor.as_ref() {
-                compressor.try_borrow_mut().expect("no recursion").write_all(buf)?;
-            }
-            Ok(())
+        let mut sink = io::sink();
+        let mut compressor = self
+            .compressor
+            .as_ref()
+            .map(|compressor| compressor.try_borrow_mut().expect("no recursion"));
+        let inner: &mut dyn io::Write = match compressor.as_mut() {
+            Some(compressor) => &mut **compressor,
+            None => &mut sink,
         };
-        match hash {
-            git_hash::Kind::Sha1 => {
-                let mut hasher = Sha1::default();
-                let header_len = git_pack::loose::object::header::encode(kind, size, &mut buf[..])?;
-                hasher.update(&buf[..header_len]);
-                possibly_compress(&buf[..header_len])?;
-
-                let mut size: usize = size.try_into().expect("object size to fit into usize");
-                while size != 0 {
-                    let bytes = size.min(buf.len());
-                    from.read_exact(&mut buf[..bytes])?;
-                    hasher.update(&buf[..bytes]);
-                    possibly_compress(&buf[..bytes])?;
-                    size -= bytes;
-                }
-                if let Some(compressor) = self.compressor.as_ref() {
-                    let mut c = compressor.borrow_mut();
-                    c.flush()?;
-                    c.reset();
-                }
-
-                Ok(hasher.digest().into())
-            }
+        let mut to = git_features::hash::Write::new(inner, hash);
+        git_pack::loose::object::header::encode(kind, size, &mut to)?;
+        let bytes_copied = io::copy(&mut from.take(size), &mut to)?;
+        if bytes_copied != size {
+            return Err(io::ErrorKind::UnexpectedEof.into());
         }
+        let id = to.hash.digest().into();
+        if let Some(compressor) = compressor.as_mut() {
+            compressor.flush()?;
+            compressor.reset();
+        }
+
+        Ok(id)
     }
 }
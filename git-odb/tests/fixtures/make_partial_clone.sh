@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q source
+(cd source
+  git config commit.gpgsign false
+  git config uploadpack.allowFilter true
+
+  echo a > a
+  mkdir dir && echo b > dir/b
+  git add . && git commit -q -m first
+
+  echo c > c
+  git add c && git commit -q -m second
+)
+
+git clone -q --bare --no-local --filter=blob:none "file://$PWD/source" partial.git
@@ -7,19 +7,204 @@ fn db() -> Store {
 }
 
 mod init {
-    use crate::odb::store::compound::db;
+    use git_odb::compound::{
+        init::{IndexLoading, Options, PackOrder},
+        memory, Store,
+    };
+
+    use crate::{fixture_path, odb::store::compound::db};
 
     #[test]
     fn has_packs() {
         assert_eq!(db().bundles.len(), 3)
     }
+
+    #[test]
+    fn packs_are_loaded_eagerly_by_default() {
+        assert!(db().bundles.iter().all(|b| b.is_mapped()));
+    }
+
+    #[test]
+    fn lazy_index_loading_maps_nothing_upfront() -> crate::Result {
+        let db = Store::at_opts(
+            fixture_path("objects"),
+            Options {
+                index_loading: IndexLoading::Lazy,
+                ..Default::default()
+            },
+        )?;
+        assert!(db.bundles.iter().all(|b| !b.is_mapped()));
+        assert!(db.bundle(1)?.pinned().is_some(), "without cap, bundles stay mapped");
+        assert_eq!(
+            db.bundles.iter().map(|b| b.is_mapped()).collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn packs_are_ordered_by_size_or_modification_time() -> crate::Result {
+        let db = db();
+        assert!(
+            db.bundles.windows(2).all(|w| w[0].index_size >= w[1].index_size),
+            "largest packs come first by default"
+        );
+        let db = Store::at_opts(
+            fixture_path("objects"),
+            Options {
+                pack_order: PackOrder::ByModificationTime,
+                index_loading: IndexLoading::Lazy,
+                ..Default::default()
+            },
+        )?;
+        assert!(
+            db.bundles.windows(2).all(|w| w[0].modified >= w[1].modified),
+            "most recent packs come first"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn max_mapped_packs_unmaps_least_recently_used() -> crate::Result {
+        let db = Store::at_opts(
+            fixture_path("objects"),
+            Options {
+                max_mapped_packs: Some(2),
+                ..Default::default()
+            },
+        )?;
+        let mapped = || db.bundles.iter().map(|b| b.is_mapped()).collect::<Vec<_>>();
+        assert_eq!(mapped(), vec![true, true, false], "eager loading respects the cap");
+
+        assert!(db.bundle(0)?.pinned().is_none(), "bundles may be unmapped");
+        db.bundle(2)?;
+        assert_eq!(
+            mapped(),
+            vec![true, false, true],
+            "the least recently used bundle was unmapped"
+        );
+        db.bundle(1)?;
+        assert_eq!(mapped(), vec![false, true, true]);
+        Ok(())
+    }
+
+    #[test]
+    fn mapped_bytes_account_for_index_and_pack_files() -> crate::Result {
+        let db = db();
+        for bundle in &db.bundles {
+            let pack_size = std::fs::metadata(bundle.index_path.with_extension("pack"))?.len();
+            assert_eq!(bundle.mapped_bytes(), Some(bundle.index_size + pack_size));
+        }
+        assert_eq!(
+            db.mapped_bytes(),
+            db.bundles.iter().filter_map(|b| b.mapped_bytes()).sum::<u64>()
+        );
+
+        let db = Store::at_opts(
+            fixture_path("objects"),
+            Options {
+                index_loading: IndexLoading::Lazy,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(db.mapped_bytes(), 0, "nothing is mapped upfront");
+        Ok(())
+    }
+
+    #[test]
+    fn memory_soft_cap_unmaps_least_recently_used() -> crate::Result {
+        let db = Store::at_opts(
+            fixture_path("objects"),
+            Options {
+                index_loading: IndexLoading::Lazy,
+                subject_to_memory_cap: true,
+                ..Default::default()
+            },
+        )?;
+        let sizes = db
+            .bundles
+            .iter()
+            .map(|b| Ok(b.index_size + std::fs::metadata(b.index_path.with_extension("pack"))?.len()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let mapped = || db.bundles.iter().map(|b| b.is_mapped()).collect::<Vec<_>>();
+
+        memory::set_soft_cap(Some(sizes[1] + sizes[2]));
+        assert!(db.bundle(1)?.pinned().is_none(), "bundles may be unmapped");
+        db.bundle(2)?;
+        assert_eq!(mapped(), vec![false, true, true], "both fit under the cap");
+        assert_eq!(memory::mapped_bytes(), sizes[1] + sizes[2]);
+
+        db.bundle(0)?;
+        assert_eq!(
+            mapped(),
+            vec![true, false, false],
+            "the least recently used bundles are unmapped until the cap is met"
+        );
+        assert_eq!(memory::mapped_bytes(), sizes[0]);
+
+        memory::set_soft_cap(Some(sizes[1]));
+        assert_eq!(
+            mapped(),
+            vec![false, false, false],
+            "lowering the cap unmaps right away"
+        );
+        db.bundle(2)?;
+        assert_eq!(
+            mapped(),
+            vec![false, false, true],
+            "a bundle exceeding the cap on its own is kept as it was just loaded"
+        );
+        assert_eq!(memory::mapped_bytes(), sizes[2]);
+
+        memory::set_soft_cap(None);
+        db.bundle(0)?;
+        assert_eq!(mapped(), vec![true, false, true], "without cap, nothing is unmapped");
+        Ok(())
+    }
+
+    #[test]
+    fn mixed_up_index_and_pack_are_detected_on_load() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let pack_dir = dir.path().join("pack");
+        std::fs::create_dir(&pack_dir)?;
+        let name = "pack-11fdfa9e156ab73caae3b6da867192221f2089c2";
+        std::fs::copy(
+            fixture_path("objects/pack").join(format!("{}.idx", name)),
+            pack_dir.join(format!("{}.idx", name)),
+        )?;
+        std::fs::copy(
+            fixture_path("objects/pack/pack-a2bf8e71d8c18879e499335762dd95119d93d9f1.pack"),
+            pack_dir.join(format!("{}.pack", name)),
+        )?;
+
+        let lazy = Options {
+            index_loading: IndexLoading::Lazy,
+            ..Default::default()
+        };
+        let db = Store::at_opts(dir.path(), lazy)?;
+        assert!(matches!(
+            db.bundle(0),
+            Err(git_pack::bundle::Error::PackChecksumMismatch { .. })
+        ));
+        assert!(!db.bundles[0].is_mapped());
+
+        let db = Store::at_opts(
+            dir.path(),
+            Options {
+                skip_sanity_check: true,
+                ..lazy
+            },
+        )?;
+        assert!(db.bundle(0).is_ok(), "the check can be skipped");
+        Ok(())
+    }
 }
 
 mod locate {
-    use git_odb::compound::Store;
+    use git_odb::compound::{init, Store};
 
     use crate::hex_to_id;
-    use crate::odb::store::compound::db;
+    use crate::{fixture_path, odb::store::compound::db};
 
     fn can_locate(db: &Store, hex_id: &str) {
         let mut buf = vec![];
@@ -40,4 +225,187 @@ mod locate {
         can_locate(&db(), "4dac9989f96bc5b5b1263b582c08f0c5f0b58542"); // pack a2bf
         can_locate(&db(), "dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043
     }
+
+    #[test]
+    fn any_object_with_non_default_options() -> crate::Result {
+        let db = Store::at_opts(
+            fixture_path("objects"),
+            init::Options {
+                pack_order: init::PackOrder::ByModificationTime,
+                loose_first: true,
+                index_loading: init::IndexLoading::Lazy,
+                max_mapped_packs: Some(1),
+                subject_to_memory_cap: false,
+                write_existing_objects: true,
+                skip_sanity_check: false,
+            },
+        )?;
+        for hex_id in &[
+            "37d4e6c5c48ba0d245164c4e10d5f41140cab980",
+            "501b297447a8255d3533c6858bb692575cdefaa0",
+            "4dac9989f96bc5b5b1263b582c08f0c5f0b58542",
+            "dd25c539efbb0ab018caa4cda2d133285634e9b5",
+        ] {
+            can_locate(&db, hex_id);
+        }
+        assert_eq!(
+            db.bundles.iter().filter(|b| b.is_mapped()).count(),
+            1,
+            "only one pack is mapped at a time"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn location_by_id_points_at_the_pack_entry() -> crate::Result {
+        let db = db();
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let location = db.location_by_id(id).expect("packed object");
+        let bundle = (0..db.bundles.len())
+            .filter_map(|idx| db.bundle(idx).ok())
+            .find(|b| b.pack.id == location.pack_id)
+            .expect("pack of location");
+        assert_eq!(
+            location.pack_offset,
+            bundle.index.pack_offset_at_index(location.index_file_id)
+        );
+        let entry = db.entry_by_location(&location).expect("pinned packs provide entries");
+        assert_eq!(entry.crc32, bundle.index.crc32_at_index(location.index_file_id));
+        assert!(db
+            .location_by_id(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"))
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn packs_failing_to_load_are_skipped_when_locating_objects() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let pack_dir = dir.path().join("pack");
+        std::fs::create_dir(&pack_dir)?;
+        let (broken, good) = (
+            "pack-c0438c19fb16422b6bbcce24387b3264416d485b",
+            "pack-a2bf8e71d8c18879e499335762dd95119d93d9f1",
+        );
+        std::fs::copy(
+            fixture_path("objects/pack").join(format!("{}.idx", broken)),
+            pack_dir.join(format!("{}.idx", broken)),
+        )?;
+        std::fs::copy(
+            fixture_path("objects/pack").join(format!("{}.pack", good)),
+            pack_dir.join(format!("{}.pack", broken)),
+        )?;
+        for ext in &["idx", "pack"] {
+            std::fs::copy(
+                fixture_path("objects/pack").join(format!("{}.{}", good, ext)),
+                pack_dir.join(format!("{}.{}", good, ext)),
+            )?;
+        }
+
+        let db = Store::at_opts(
+            dir.path(),
+            init::Options {
+                index_loading: init::IndexLoading::Lazy,
+                ..Default::default()
+            },
+        )?;
+        assert!(db.bundle(0).is_err(), "the largest pack comes first and fails to load");
+        let id = hex_to_id("4dac9989f96bc5b5b1263b582c08f0c5f0b58542");
+        assert!(db.contains(id), "the object is found in the next pack");
+        assert!(db.location_by_id(id).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn entries_are_not_provided_for_packs_that_may_be_unmapped() -> crate::Result {
+        let db = Store::at_opts(
+            fixture_path("objects"),
+            init::Options {
+                max_mapped_packs: Some(1),
+                ..Default::default()
+            },
+        )?;
+        let location = db
+            .location_by_id(hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"))
+            .expect("packed object");
+        assert!(db.entry_by_location(&location).is_none());
+        Ok(())
+    }
+}
+
+mod write {
+    use git_odb::{
+        compound::{init, Store},
+        Write,
+    };
+
+    use crate::{fixture_path, hex_to_id, odb::store::compound::db};
+
+    fn store_with_pack(dir: &std::path::Path, options: init::Options) -> crate::Result<Store> {
+        let pack_dir = dir.join("pack");
+        std::fs::create_dir(&pack_dir)?;
+        for extension in &["idx", "pack"] {
+            let name = format!("pack-11fdfa9e156ab73caae3b6da867192221f2089c2.{}", extension);
+            std::fs::copy(fixture_path("objects/pack").join(&name), pack_dir.join(name))?;
+        }
+        Ok(Store::at_opts(dir, options)?)
+    }
+
+    #[test]
+    fn objects_already_present_in_packs_are_not_written_again() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let db_with_pack = store_with_pack(dir.path(), Default::default())?;
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let mut buf = Vec::new();
+        let object = db().find(id, &mut buf, &mut git_pack::cache::Never)?.expect("present");
+
+        let outcome = db_with_pack.write_buf_with_outcome(object.kind, object.data, git_hash::Kind::Sha1)?;
+        assert_eq!(outcome.id, id);
+        assert!(outcome.already_present, "the object is in the pack");
+        let outcome = db_with_pack.write_stream_with_outcome(
+            object.kind,
+            object.data.len() as u64,
+            object.data,
+            git_hash::Kind::Sha1,
+        )?;
+        assert!(outcome.already_present, "streams are checked once they are hashed");
+        assert!(!db_with_pack.loose.contains(id), "no loose object was written");
+
+        let outcome = db_with_pack.write_buf_with_outcome(git_object::Kind::Blob, b"new", git_hash::Kind::Sha1)?;
+        assert!(!outcome.already_present, "new objects are written");
+        assert!(db_with_pack.loose.contains(outcome.id));
+        assert!(
+            db_with_pack
+                .write_buf_with_outcome(git_object::Kind::Blob, b"new", git_hash::Kind::Sha1)?
+                .already_present,
+            "loose objects count as well"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn existing_objects_can_be_written_anyway() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let db_with_pack = store_with_pack(
+            dir.path(),
+            init::Options {
+                write_existing_objects: true,
+                ..Default::default()
+            },
+        )?;
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let mut buf = Vec::new();
+        let object = db().find(id, &mut buf, &mut git_pack::cache::Never)?.expect("present");
+
+        let outcome = db_with_pack.write_buf_with_outcome(object.kind, object.data, git_hash::Kind::Sha1)?;
+        assert!(!outcome.already_present);
+        assert!(
+            db_with_pack.loose.contains(id),
+            "the packed object was duplicated as loose object"
+        );
+        assert_eq!(
+            db_with_pack.write_buf(object.kind, object.data, git_hash::Kind::Sha1)?,
+            id
+        );
+        Ok(())
+    }
 }
@@ -30,6 +30,20 @@ mod iter {
         }
         Ok(())
     }
+
+    #[test]
+    fn lazily_loaded_packs_with_cap() -> crate::Result {
+        let db = git_odb::linked::Store::at_opts(
+            crate::fixture_path("objects"),
+            git_odb::compound::init::Options {
+                index_loading: git_odb::compound::init::IndexLoading::Lazy,
+                max_mapped_packs: Some(1),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(db.iter().count(), 146, "it sees the same amount of objects");
+        Ok(())
+    }
 }
 
 mod locate {
@@ -55,6 +69,32 @@ mod locate {
         can_locate(&db(), "4dac9989f96bc5b5b1263b582c08f0c5f0b58542"); // pack a2bf
         can_locate(&db(), "dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043
     }
+
+    #[test]
+    fn location_of_packed_objects_matches_the_one_of_found_objects() {
+        let db = db();
+        let mut buf = vec![];
+        for hex_id in &[
+            "501b297447a8255d3533c6858bb692575cdefaa0",
+            "4dac9989f96bc5b5b1263b582c08f0c5f0b58542",
+            "dd25c539efbb0ab018caa4cda2d133285634e9b5",
+        ] {
+            let id = hex_to_id(hex_id);
+            let location = db.location_by_id(id, &mut buf).expect("packed object");
+            let obj = db
+                .find(id, &mut buf, &mut pack::cache::Never)
+                .expect("no read error")
+                .expect("object present");
+            assert_eq!(obj.pack_location.as_ref(), Some(&location));
+            let entry = db.entry_by_location(&location).expect("packs are mapped");
+            assert_eq!(entry.data.len(), location.entry_size);
+        }
+        assert!(
+            db.location_by_id(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"), &mut buf)
+                .is_none(),
+            "loose objects have no pack location"
+        );
+    }
 }
 
 mod init {
@@ -91,3 +131,104 @@ mod init {
         assert_eq!(db.dbs[0].bundles.len(), 3)
     }
 }
+
+mod partial_clone {
+    use git_odb::{linked, loose, pack, Find, FindExt, Write};
+    use std::{
+        path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use crate::odb::store::linked::db;
+
+    fn rev_parse(repo: &Path, spec: &str) -> git_hash::ObjectId {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(["rev-parse", spec])
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git can be executed");
+        assert!(output.status.success(), "rev-parse {} succeeds", spec);
+        git_hash::ObjectId::from_hex(&output.stdout[..40]).expect("valid hex")
+    }
+
+    #[test]
+    fn missing_objects_are_promised_by_promisor_packs() -> crate::Result {
+        let dir = crate::scripted_fixture_repo_read_only("make_partial_clone.sh")?;
+        let db = linked::Store::at(dir.join("partial.git").join("objects"))?;
+        assert!(db.is_partial_clone());
+        assert!(
+            !self::db().is_partial_clone(),
+            "regular repositories are no partial clones"
+        );
+
+        let source = dir.join("source");
+        let blob = rev_parse(&source, "HEAD:dir/b");
+        let mut buf = Vec::new();
+        assert!(
+            db.find(blob, &mut buf, &mut pack::cache::Never)?.is_none(),
+            "blobs are filtered"
+        );
+        assert!(db.is_promised(blob)?, "it's referenced by a tree in a promisor pack");
+
+        let tree = rev_parse(&source, "HEAD^{tree}");
+        assert!(db.contains(tree));
+        assert!(!db.is_promised(git_hash::ObjectId::null_sha1())?);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_object_handler_may_retrieve_objects_for_the_retry() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_writable("make_partial_clone.sh")?;
+        let objects_dir = dir.path().join("partial.git").join("objects");
+        let mut db = linked::Store::at(&objects_dir)?;
+
+        let source = linked::Store::at(dir.path().join("source").join(".git").join("objects"))?;
+        let loose = loose::Store::at(objects_dir);
+        let calls = Arc::new(AtomicUsize::new(0));
+        db.missing_object_handler = Some(Box::new({
+            let calls = Arc::clone(&calls);
+            move |id| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let mut buf = Vec::new();
+                match source
+                    .find(id, &mut buf, &mut pack::cache::Never)
+                    .expect("no read error")
+                {
+                    Some(obj) => {
+                        loose
+                            .write_buf(obj.kind, obj.data, git_hash::Kind::Sha1)
+                            .expect("write succeeds");
+                        true
+                    }
+                    None => false,
+                }
+            }
+        }));
+
+        let blob = rev_parse(&dir.path().join("source"), "HEAD:c");
+        let mut buf = Vec::new();
+        assert_eq!(
+            db.find_existing_blob(blob, &mut buf, &mut pack::cache::Never)?.data,
+            b"c\n"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "the handler was called once");
+
+        db.find_existing(blob, &mut buf, &mut pack::cache::Never)?;
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "the object is now present locally");
+
+        assert!(db
+            .find(git_hash::ObjectId::null_sha1(), &mut buf, &mut pack::cache::Never)?
+            .is_none());
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            2,
+            "the handler is consulted for unknown objects as well"
+        );
+        Ok(())
+    }
+}
@@ -26,6 +26,50 @@ fn iter() {
     oids.sort();
     assert_eq!(oids, object_ids())
 }
+
+mod statistics {
+    use crate::store::loose::backend::{ldb, object_ids};
+    use git_odb::{loose, Write};
+
+    #[test]
+    fn counts_objects_per_fanout_directory() -> Result<(), Box<dyn std::error::Error>> {
+        let stats = ldb().statistics()?;
+        assert_eq!(stats.fanout.len(), 256);
+        assert_eq!(stats.count(), object_ids().len());
+        for id in object_ids() {
+            let fanout = stats.fanout[id.as_slice()[0] as usize];
+            assert_eq!(fanout.count, 1, "each fixture object has its own fanout directory");
+            assert!(fanout.size_on_disk > 0);
+        }
+        assert_eq!(
+            stats.size_on_disk(),
+            stats.fanout.iter().map(|f| f.size_on_disk).sum::<u64>()
+        );
+        assert_eq!(ldb().estimate_count(0x37)?, 256);
+        assert_eq!(ldb().estimate_count(0x00)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn fanout_directories_can_be_created_upfront() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path());
+        db.create_fanout_directories()?;
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 256);
+        assert!(dir.path().join("00").is_dir() && dir.path().join("ff").is_dir());
+
+        let id = db.write_buf(git_object::Kind::Blob, b"hello", git_hash::Kind::Sha1)?;
+        db.create_fanout_directories()?;
+        let stats = db.statistics()?;
+        assert_eq!(stats.count(), 1);
+        assert_eq!(
+            stats.fullest().map(|(byte, f)| (byte, f.count)),
+            Some((id.as_slice()[0], 1))
+        );
+        Ok(())
+    }
+}
+
 pub fn locate_oid(id: git_hash::ObjectId, buf: &mut Vec<u8>) -> git_pack::data::Object<'_> {
     ldb().find(id, buf).expect("read success").expect("id present")
 }
@@ -187,6 +231,7 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
             entries: vec![
                 tree::Entry {
                     mode: EntryMode::Tree,
+                    raw_mode: b"40000".as_bstr(),
                     filename: b"dir".as_bstr(),
                     oid: as_id(&[
                         150, 174, 134, 139, 53, 57, 245, 81, 200, 143, 213, 240, 35, 148, 208, 34, 88, 27, 17, 176,
@@ -194,6 +239,7 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
                 },
                 tree::Entry {
                     mode: EntryMode::Blob,
+                    raw_mode: b"100644".as_bstr(),
                     filename: b"file.txt".as_bstr(),
                     oid: as_id(&[
                         55, 212, 230, 197, 196, 139, 160, 210, 69, 22, 76, 78, 16, 213, 244, 17, 64, 202, 185, 128,
@@ -32,28 +32,91 @@ impl crate::Bundle {
         out: &'a mut Vec<u8>,
         cache: &mut impl crate::cache::DecodeEntry,
     ) -> Result<data::Object<'a>, crate::data::decode_entry::Error> {
-        let ofs = self.index.pack_offset_at_index(idx);
-        let pack_entry = self.pack.entry(ofs);
-        let header_size = pack_entry.header_size();
+        let pack_offset = self.index.pack_offset_at_index(idx);
+        let header_size = self.pack.entry(pack_offset).header_size();
+        self.decode_at_index(idx, out, cache).map(move |r| crate::data::Object {
+            kind: r.kind,
+            data: out.as_slice(),
+            pack_location: Some(crate::bundle::Location {
+                pack_id: self.pack.id,
+                index_file_id: idx,
+                pack_offset,
+                entry_size: r.compressed_size + header_size,
+            }),
+        })
+    }
+
+    /// Decode the object at `idx` into `out`, resolving ref deltas within this pack only.
+    pub(crate) fn decode_at_index(
+        &self,
+        idx: u32,
+        out: &mut Vec<u8>,
+        cache: &mut impl crate::cache::DecodeEntry,
+    ) -> Result<crate::data::decode_entry::Outcome, crate::data::decode_entry::Error> {
+        let pack_entry = self.pack.entry(self.index.pack_offset_at_index(idx));
+        self.pack.decode_entry(
+            pack_entry,
+            out,
+            |id, _out| {
+                self.index
+                    .lookup(id)
+                    .map(|idx| crate::data::ResolvedBase::InPack(self.pack.entry(self.index.pack_offset_at_index(idx))))
+            },
+            cache,
+        )
+    }
+
+    /// Return the [`Location`][crate::bundle::Location] of the object at `idx` without decompressing it, using the offset
+    /// of the next entry in pack order to determine the entry size.
+    ///
+    /// # Panics
+    ///
+    /// If `idx` is out of bounds.
+    pub fn location_at_index(&self, idx: u32) -> crate::bundle::Location {
+        let pack_offset = self.index.pack_offset_at_index(idx);
+        let end = self
+            .index
+            .next_pack_offset_at_index(idx)
+            .unwrap_or_else(|| self.pack.pack_end() as u64);
+        crate::bundle::Location {
+            pack_id: self.pack.id,
+            index_file_id: idx,
+            pack_offset,
+            entry_size: (end - pack_offset) as usize,
+        }
+    }
+}
+
+impl crate::Find for crate::Bundle {
+    type Error = crate::data::decode_entry::Error;
+
+    fn find<'a>(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl crate::cache::DecodeEntry,
+    ) -> Result<Option<data::Object<'a>>, Self::Error> {
+        crate::Bundle::find(self, id, buffer, pack_cache)
+    }
+
+    fn location_by_id(&self, id: impl AsRef<git_hash::oid>, _buf: &mut Vec<u8>) -> Option<crate::bundle::Location> {
+        self.index.lookup(id).map(|idx| self.location_at_index(idx))
+    }
+
+    fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId> {
+        (self.pack.id == pack_id).then(|| self.pack.checksum())
+    }
+
+    fn entry_by_location(&self, location: &crate::bundle::Location) -> Option<crate::find::Entry<'_>> {
+        if location.pack_id != self.pack.id {
+            return None;
+        }
         self.pack
-            .decode_entry(
-                pack_entry,
-                out,
-                |id, _out| {
-                    self.index.lookup(id).map(|idx| {
-                        crate::data::ResolvedBase::InPack(self.pack.entry(self.index.pack_offset_at_index(idx)))
-                    })
-                },
-                cache,
-            )
-            .map(move |r| crate::data::Object {
-                kind: r.kind,
-                data: out.as_slice(),
-                pack_location: Some(crate::bundle::Location {
-                    pack_id: self.pack.id,
-                    index_file_id: idx,
-                    entry_size: r.compressed_size + header_size,
-                }),
+            .entry_slice(location.entry_range())
+            .map(|data| crate::find::Entry {
+                data,
+                crc32: self.index.crc32_at_index(location.index_file_id),
+                version: self.pack.version(),
             })
     }
 }
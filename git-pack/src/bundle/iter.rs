@@ -0,0 +1,88 @@
+use crate::{bundle, cache, data, index, Bundle};
+
+/// An entry of a [`Bundle`] as returned by [`EntriesWithData`], combining information from the index and the pack.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct EntryWithData {
+    /// The entry of the index, providing the object id, the offset into the pack and the CRC32 if available.
+    pub index_entry: index::Entry,
+    /// The header of the pack entry, which tells if the object is stored as a delta.
+    pub header: data::entry::Header,
+    /// The location of the pack entry, including its size in the pack.
+    pub location: bundle::Location,
+    /// Information about decoding the object, like the amount of deltas that had to be applied.
+    pub decode: data::decode_entry::Outcome,
+    /// The fully decoded object.
+    pub data: Vec<u8>,
+}
+
+/// An iterator over all entries of a [`Bundle`] in index order, decoding each of them. Created by
+/// [`Bundle::iter_entries_with_data()`].
+pub struct EntriesWithData<'a, C> {
+    bundle: &'a Bundle,
+    cache: C,
+    next_index: u32,
+}
+
+impl<'a, C> EntriesWithData<'a, C> {
+    /// Consume this instance and return the cache used to speed up decoding.
+    pub fn into_cache(self) -> C {
+        self.cache
+    }
+}
+
+impl<'a, C> Iterator for EntriesWithData<'a, C>
+where
+    C: cache::DecodeEntry,
+{
+    type Item = Result<EntryWithData, data::decode_entry::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_index == self.bundle.index.num_objects() {
+            return None;
+        }
+        let idx = self.next_index;
+        self.next_index += 1;
+
+        let index_entry = self.bundle.index.entry_at_index(idx);
+        let header = self.bundle.pack.entry(index_entry.pack_offset).header;
+        let mut data = Vec::new();
+        Some(
+            self.bundle
+                .decode_at_index(idx, &mut data, &mut self.cache)
+                .map(|decode| EntryWithData {
+                    location: self.bundle.location_at_index(idx),
+                    index_entry,
+                    header,
+                    decode,
+                    data,
+                }),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.bundle.index.num_objects() - self.next_index) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Iteration
+impl Bundle {
+    /// Return an iterator over all entries in index order, that is sorted by object id, along with their decoded data
+    /// and information about their delta chains.
+    ///
+    /// `cache` is used to avoid decoding the same base objects repeatedly, which is the more important the longer the
+    /// delta chains are. Use [`cache::Never`] to disable caching.
+    ///
+    /// This is useful to implement custom analyses like listing the largest objects. To merely obtain the locations of
+    /// entries, iterate over the [index][crate::index::File::iter()] and use [`Bundle::location_at_index()`] instead.
+    pub fn iter_entries_with_data<C>(&self, cache: C) -> EntriesWithData<'_, C>
+    where
+        C: cache::DecodeEntry,
+    {
+        EntriesWithData {
+            bundle: self,
+            cache,
+            next_index: 0,
+        }
+    }
+}
@@ -5,6 +5,8 @@ use std::{
 
 mod find;
 ///
+pub mod iter;
+///
 pub mod write;
 
 mod verify {
@@ -40,7 +42,7 @@ mod verify {
     }
 }
 
-/// Returned by [`Bundle::at()`]
+/// Returned by [`Bundle::at()`] and [`Bundle::sanity_check()`]
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -50,6 +52,18 @@ pub enum Error {
     Pack(#[from] crate::data::header::decode::Error),
     #[error(transparent)]
     Index(#[from] crate::index::init::Error),
+    #[error("The pack at '{}' has checksum {actual}, but its index expects {expected}", pack_path.display())]
+    PackChecksumMismatch {
+        pack_path: PathBuf,
+        expected: git_hash::ObjectId,
+        actual: git_hash::ObjectId,
+    },
+    #[error("The pack at '{}' contains {actual} objects, but its index lists {expected}", pack_path.display())]
+    ObjectCountMismatch {
+        pack_path: PathBuf,
+        expected: u32,
+        actual: u32,
+    },
 }
 
 /// A way to uniquely identify the location of an object within a pack bundle
@@ -60,14 +74,16 @@ pub struct Location {
     pub pack_id: u32,
     /// The index at which the object can be fonud in the index file
     pub index_file_id: u32,
+    /// The offset at which the entry starts in the pack data file
+    pub pack_offset: u64,
     /// The size of the entry of disk
     pub entry_size: usize,
 }
 
 impl Location {
     /// Compute a range suitable for lookup in pack data using the [`entry_slice()`][crate::data::File::entry_slice()] method.
-    pub fn entry_range(&self, pack_offset: u64) -> crate::data::EntryRange {
-        pack_offset..pack_offset + self.entry_size as u64
+    pub fn entry_range(&self) -> crate::data::EntryRange {
+        self.pack_offset..self.pack_offset + self.entry_size as u64
     }
 }
 
@@ -90,6 +106,33 @@ impl Bundle {
     }
 }
 
+impl Bundle {
+    /// Quickly assure that the pack and index file belong together by comparing the pack checksum recorded in the index
+    /// with the one in the pack trailer, and the amount of objects in both.
+    ///
+    /// Unlike [`verify_integrity()`][Bundle::verify_integrity()], no hashes are computed, which makes this check cheap enough
+    /// to catch mixed-up pairs of index and pack files right after loading them.
+    pub fn sanity_check(&self) -> Result<(), Error> {
+        let (expected, actual) = (self.index.pack_checksum(), self.pack.checksum());
+        if expected != actual {
+            return Err(Error::PackChecksumMismatch {
+                pack_path: self.pack.path().to_owned(),
+                expected,
+                actual,
+            });
+        }
+        let (expected, actual) = (self.index.num_objects(), self.pack.num_objects());
+        if expected != actual {
+            return Err(Error::ObjectCountMismatch {
+                pack_path: self.pack.path().to_owned(),
+                expected,
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
 impl TryFrom<&Path> for Bundle {
     type Error = Error;
 
@@ -0,0 +1,101 @@
+use super::{Error, Options, Outcome, ThinPackLookupFn, ThinPackLookupFnSend};
+use futures_lite::AsyncReadExt;
+use git_features::progress::Progress;
+use std::{
+    io,
+    path::Path,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+/// The size of the chunks read from the pack stream.
+const CHUNK_SIZE: usize = 4096 * 8;
+/// The amount of chunks that may be waiting to be indexed before reading pauses.
+const CHANNEL_CAPACITY: usize = 16;
+
+impl crate::Bundle {
+    /// Equivalent to [`write_to_directory()`][crate::Bundle::write_to_directory()] but reads the `pack` asynchronously
+    /// while decoding and indexing it on a thread of the [`blocking`] thread pool, hence the `Send + 'static` bounds, which
+    /// also apply to the `thin_pack_base_object_lookup_fn`.
+    ///
+    /// Chunks of the pack are passed to the indexer through a bounded channel, so reading pauses if indexing can't keep up.
+    /// An IO error while reading the `pack` is forwarded to the indexer which fails with it.
+    pub async fn write_to_directory_async(
+        mut pack: impl futures_io::AsyncRead + Unpin,
+        directory: Option<impl AsRef<Path> + Send + 'static>,
+        progress: impl Progress,
+        should_interrupt: Arc<AtomicBool>,
+        thin_pack_base_object_lookup_fn: Option<ThinPackLookupFnSend>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let (tx, rx) = async_channel::bounded(CHANNEL_CAPACITY);
+        let indexing = blocking::unblock(move || {
+            crate::Bundle::write_to_directory(
+                ChannelReader {
+                    rx,
+                    buf: Vec::new(),
+                    pos: 0,
+                },
+                directory,
+                progress,
+                &should_interrupt,
+                thin_pack_base_object_lookup_fn.map(|lookup| lookup as ThinPackLookupFn),
+                options,
+            )
+        });
+
+        loop {
+            let mut buf = vec![0; CHUNK_SIZE];
+            let chunk = match pack.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(bytes_read) => {
+                    buf.truncate(bytes_read);
+                    Ok(buf)
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => Err(err),
+            };
+            let is_err = chunk.is_err();
+            // The indexer hangs up if it fails, and will tell us why once we await it.
+            if tx.send(chunk).await.is_err() || is_err {
+                break;
+            }
+        }
+        drop(tx);
+        indexing.await
+    }
+}
+
+/// A blocking reader over chunks sent through a channel, which ends once the sender is dropped.
+struct ChannelReader {
+    rx: async_channel::Receiver<io::Result<Vec<u8>>>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl io::BufRead for ChannelReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            if let Ok(chunk) = futures_lite::future::block_on(self.rx.recv()) {
+                self.buf = chunk?;
+            }
+        }
+        Ok(&self.buf[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+    }
+}
+
+impl io::Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        use io::BufRead;
+        let available = self.fill_buf()?;
+        let bytes_read = available.len().min(buf.len());
+        buf[..bytes_read].copy_from_slice(&available[..bytes_read]);
+        self.consume(bytes_read);
+        Ok(bytes_read)
+    }
+}
@@ -1,6 +1,8 @@
 use std::io;
 
+/// The error returned by [`write_to_directory()`][crate::Bundle::write_to_directory()] and related functions.
 #[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
 pub enum Error {
     #[error("An IO error occurred when reading the pack or creating a temporary file")]
     Io(#[from] io::Error),
@@ -8,6 +10,13 @@ pub enum Error {
     PackIter(#[from] crate::data::input::Error),
     #[error("Could not move a temporary file into its desired place")]
     PeristError(#[from] tempfile::PersistError),
+    #[error("Objects identified by {object_hash:?} cannot be indexed with an index of version {index_kind:?}")]
+    UnsupportedObjectHash {
+        object_hash: git_hash::Kind,
+        index_kind: crate::index::Version,
+    },
+    #[error("Packs can't be indexed in place with iteration mode {0:?} as they would have to be rewritten")]
+    UnsupportedIterationMode(crate::data::input::Mode),
     #[error(transparent)]
     IndexWrite(#[from] crate::index::write::Error),
 }
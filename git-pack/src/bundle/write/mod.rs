@@ -9,11 +9,14 @@ use std::{
 use tempfile::NamedTempFile;
 
 mod error;
-use error::Error;
+pub use error::Error;
 
 mod types;
 use types::PassThrough;
-pub use types::{Options, Outcome};
+pub use types::{Options, Outcome, ThinPackLookupFn, ThinPackLookupFnSend};
+
+#[cfg(feature = "async-io")]
+mod async_io;
 
 impl crate::Bundle {
     /// Given a `pack` data stream, write it along with a generated index into the `directory` if `Some` or discard all output if `None`.
@@ -21,14 +24,19 @@ impl crate::Bundle {
     /// In the latter case, the functionality provided here is more akind of pack data stream validation.
     ///
     /// `progress` provides detailed progress information which can be discarded with [`git_features::progress::Discard`].
+    /// `thin_pack_base_object_lookup_fn` is used to find the base objects of ref-deltas which aren't part of the pack if it
+    /// is thin, as received when fetching into an existing repository. These are inserted to complete the pack, which is
+    /// then written with an updated header and trailer. If `None`, thin packs can't be written.
     /// `options` further configure how the task is performed.
     pub fn write_to_directory(
         pack: impl io::BufRead,
         directory: Option<impl AsRef<Path>>,
         mut progress: impl Progress,
         should_interrupt: &AtomicBool,
+        thin_pack_base_object_lookup_fn: Option<ThinPackLookupFn>,
         options: Options,
     ) -> Result<Outcome, Error> {
+        check_object_hash(&options)?;
         let mut read_progress = progress.add_child("read pack");
         read_progress.init(None, progress::bytes());
         let pack = progress::Read {
@@ -46,7 +54,7 @@ impl crate::Bundle {
                 inner: pack,
                 should_interrupt,
             },
-            writer: Some(data_file.clone()),
+            writer: thin_pack_base_object_lookup_fn.is_none().then(|| data_file.clone()),
         };
         // This buff-reader is required to assure we call 'read()' in order to fill the (extra) buffer. Otherwise all the counting
         // we do with the wrapped pack reader doesn't work as it does not expect anyone to call BufRead functions directly.
@@ -56,9 +64,16 @@ impl crate::Bundle {
         let pack_entries_iter = crate::data::BytesToEntriesIter::new_from_header(
             buffered_pack,
             options.iteration_mode,
-            crate::data::input::EntryDataMode::Crc32,
+            entry_data_mode(&thin_pack_base_object_lookup_fn),
         )?;
         let pack_kind = pack_entries_iter.kind();
+        let pack_entries_iter = resolve_thin_pack(
+            pack_entries_iter,
+            thin_pack_base_object_lookup_fn,
+            &data_file,
+            pack_kind,
+            &options,
+        )?;
         let (outcome, data_path, index_path) = crate::Bundle::inner_write(
             directory,
             progress,
@@ -90,8 +105,10 @@ impl crate::Bundle {
         directory: Option<impl AsRef<Path>>,
         mut progress: impl Progress,
         should_interrupt: &'static AtomicBool,
+        thin_pack_base_object_lookup_fn: Option<ThinPackLookupFn>,
         options: Options,
     ) -> Result<Outcome, Error> {
+        check_object_hash(&options)?;
         let mut read_progress = progress.add_child("read pack");
         read_progress.init(pack_size.map(|s| s as usize), progress::bytes());
         let pack = progress::Read {
@@ -109,19 +126,26 @@ impl crate::Bundle {
                 inner: pack,
                 should_interrupt,
             },
-            writer: Some(data_file.clone()),
+            writer: thin_pack_base_object_lookup_fn.is_none().then(|| data_file.clone()),
         };
         let eight_pages = 4096 * 8;
         let buffered_pack = io::BufReader::with_capacity(eight_pages, pack);
         let pack_entries_iter = crate::data::BytesToEntriesIter::new_from_header(
             buffered_pack,
             options.iteration_mode,
-            crate::data::input::EntryDataMode::Crc32,
+            entry_data_mode(&thin_pack_base_object_lookup_fn),
         )?;
         let pack_kind = pack_entries_iter.kind();
         let num_objects = pack_entries_iter.size_hint().0;
         let pack_entries_iter =
             git_features::parallel::EagerIterIf::new(move || num_objects > 25_000, pack_entries_iter, 5_000, 5);
+        let pack_entries_iter = resolve_thin_pack(
+            pack_entries_iter,
+            thin_pack_base_object_lookup_fn,
+            &data_file,
+            pack_kind,
+            &options,
+        )?;
 
         let (outcome, data_path, index_path) = crate::Bundle::inner_write(
             directory,
@@ -141,6 +165,97 @@ impl crate::Bundle {
         })
     }
 
+    /// Write an index for the existing pack at `pack_path` into `directory` along with a copy of the pack if `Some`,
+    /// or discard all output if `None`.
+    ///
+    /// Unlike [`write_to_directory_eagerly()`][crate::Bundle::write_to_directory_eagerly()], the pack is memory-mapped
+    /// once and both its entries and the bases of its deltas are read from the mapping, instead of streaming the pack through a
+    /// temporary file first. This keeps peak memory usage and wall time down for large packs.
+    /// The pack is only copied into `directory` if it doesn't yet contain a pack of the same name.
+    ///
+    /// Note that [`Mode::Restore`][crate::data::input::Mode::Restore] isn't supported as the pack itself is never rewritten.
+    pub fn write_index_for_pack_file(
+        pack_path: impl AsRef<Path>,
+        directory: Option<impl AsRef<Path>>,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        check_object_hash(&options)?;
+        if options.iteration_mode == crate::data::input::Mode::Restore {
+            return Err(Error::UnsupportedIterationMode(options.iteration_mode));
+        }
+        let pack_path = pack_path.as_ref();
+        let mapped_pack = FileBuffer::open(pack_path)?;
+        let mut read_progress = progress.add_child("read pack");
+        read_progress.init(Some(mapped_pack.len()), progress::bytes());
+        let pack = progress::Read {
+            reader: interrupt::Read {
+                inner: &mapped_pack[..],
+                should_interrupt,
+            },
+            progress: progress::ThroughputOnDrop::new(read_progress),
+        };
+        let pack_entries_iter = crate::data::BytesToEntriesIter::new_from_header(
+            pack,
+            options.iteration_mode,
+            crate::data::input::EntryDataMode::Crc32,
+        )?;
+        let pack_kind = pack_entries_iter.kind();
+        let make_resolver = || -> io::Result<_> {
+            Ok(|range: crate::data::EntryRange, out: &mut Vec<u8>| -> Option<()> {
+                mapped_pack
+                    .get(range.start as usize..range.end as usize)
+                    .map(|pack_entry| out.copy_from_slice(pack_entry))
+            })
+        };
+
+        let indexing_progress = progress.add_child("create index file");
+        let (index, data_path, index_path) = match directory {
+            Some(directory) => {
+                let directory = directory.as_ref();
+                let mut index_file = NamedTempFile::new_in(directory)?;
+                let outcome = crate::index::File::write_data_iter_to_stream(
+                    options.index_kind,
+                    make_resolver,
+                    pack_entries_iter,
+                    options.thread_limit,
+                    indexing_progress,
+                    &mut index_file,
+                    should_interrupt,
+                )?;
+
+                let data_path = directory.join(format!("{}.pack", outcome.data_hash.to_sha1_hex_string()));
+                let index_path = data_path.with_extension("idx");
+                if !data_path.is_file() {
+                    std::fs::copy(pack_path, &data_path)?;
+                }
+                index_file.persist(&index_path)?;
+                (outcome, Some(data_path), Some(index_path))
+            }
+            None => (
+                crate::index::File::write_data_iter_to_stream(
+                    options.index_kind,
+                    make_resolver,
+                    pack_entries_iter,
+                    options.thread_limit,
+                    indexing_progress,
+                    io::sink(),
+                    should_interrupt,
+                )?,
+                None,
+                None,
+            ),
+        };
+
+        Ok(Outcome {
+            index,
+            pack_kind,
+            data_path,
+            index_path,
+        })
+    }
+
     fn inner_write(
         directory: Option<impl AsRef<Path>>,
         mut progress: impl Progress,
@@ -148,6 +263,7 @@ impl crate::Bundle {
             thread_limit,
             iteration_mode: _,
             index_kind,
+            object_hash: _,
         }: Options,
         data_file: Arc<parking_lot::Mutex<NamedTempFile>>,
         data_path: PathBuf,
@@ -205,6 +321,45 @@ impl crate::Bundle {
     }
 }
 
+fn check_object_hash(options: &Options) -> Result<(), Error> {
+    if options.index_kind.hash() != options.object_hash {
+        return Err(Error::UnsupportedObjectHash {
+            object_hash: options.object_hash,
+            index_kind: options.index_kind,
+        });
+    }
+    Ok(())
+}
+
+/// Keep the compressed bytes of entries if we have to resolve a thin pack, as the pack is rewritten in the process.
+fn entry_data_mode(thin_pack_base_object_lookup_fn: &Option<ThinPackLookupFn>) -> crate::data::input::EntryDataMode {
+    if thin_pack_base_object_lookup_fn.is_some() {
+        crate::data::input::EntryDataMode::KeepAndCrc32
+    } else {
+        crate::data::input::EntryDataMode::Crc32
+    }
+}
+
+/// If `thin_pack_base_object_lookup_fn` is set, insert missing bases into the pack produced by `pack_entries_iter` and
+/// write the result into `data_file`, which otherwise receives the pack as is while it is read.
+fn resolve_thin_pack<'a>(
+    pack_entries_iter: impl Iterator<Item = Result<crate::data::input::Entry, crate::data::input::Error>> + 'a,
+    thin_pack_base_object_lookup_fn: Option<ThinPackLookupFn>,
+    data_file: &parking_lot::Mutex<NamedTempFile>,
+    pack_kind: crate::data::Version,
+    options: &Options,
+) -> io::Result<Box<dyn Iterator<Item = Result<crate::data::input::Entry, crate::data::input::Error>> + 'a>> {
+    Ok(match thin_pack_base_object_lookup_fn {
+        Some(lookup) => Box::new(crate::data::input::EntriesToBytesIter::new(
+            crate::data::input::LookupRefDeltaObjectsIter::new(pack_entries_iter, lookup),
+            data_file.lock().as_file().try_clone()?,
+            pack_kind,
+            options.object_hash,
+        )),
+        None => Box::new(pack_entries_iter),
+    })
+}
+
 fn new_pack_file_resolver(
     data_path: PathBuf,
 ) -> io::Result<impl Fn(crate::data::EntryRange, &mut Vec<u8>) -> Option<()> + Send + Sync> {
@@ -1,8 +1,9 @@
 use std::{io, path::PathBuf, sync::Arc};
 use tempfile::NamedTempFile;
 
-/// Configuration for [write_to_directory][crate::Bundle::write_to_directory()] or
-/// [write_to_directory_eagerly][crate::Bundle::write_to_directory_eagerly()]
+/// Configuration for [write_to_directory][crate::Bundle::write_to_directory()],
+/// [write_to_directory_eagerly][crate::Bundle::write_to_directory_eagerly()] or
+/// [write_index_for_pack_file][crate::Bundle::write_index_for_pack_file()]
 #[derive(Debug, Clone)]
 pub struct Options {
     /// The amount of threads to use at most when resolving the pack. If `None`, all logical cores are used.
@@ -11,6 +12,10 @@ pub struct Options {
     pub iteration_mode: crate::data::input::Mode,
     /// The version of pack index to write, should be [`crate::index::Version::default()`]
     pub index_kind: crate::index::Version,
+    /// The kind of hash identifying objects in the pack, which determines the length of the digests in the index.
+    ///
+    /// It must be the one used by the repository the pack was obtained from, and must be supported by `index_kind`.
+    pub object_hash: git_hash::Kind,
 }
 
 impl Default for Options {
@@ -20,12 +25,23 @@ impl Default for Options {
             thread_limit: None,
             iteration_mode: crate::data::input::Mode::Verify,
             index_kind: Default::default(),
+            object_hash: Default::default(),
         }
     }
 }
 
-/// Returned by [write_to_directory][crate::Bundle::write_to_directory()] or
-/// [write_to_directory_eagerly][crate::Bundle::write_to_directory_eagerly()]
+/// A function to find the object with the given id, writing its data into the buffer, to obtain the bases of ref-deltas
+/// which aren't part of a thin pack.
+pub type ThinPackLookupFn =
+    Box<dyn for<'a> FnMut(git_hash::ObjectId, &'a mut Vec<u8>) -> Option<crate::data::Object<'a>>>;
+
+/// Like [`ThinPackLookupFn`], but for use on other threads.
+pub type ThinPackLookupFnSend =
+    Box<dyn for<'a> FnMut(git_hash::ObjectId, &'a mut Vec<u8>) -> Option<crate::data::Object<'a>> + Send + 'static>;
+
+/// Returned by [write_to_directory][crate::Bundle::write_to_directory()],
+/// [write_to_directory_eagerly][crate::Bundle::write_to_directory_eagerly()] or
+/// [write_index_for_pack_file][crate::Bundle::write_index_for_pack_file()]
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Outcome {
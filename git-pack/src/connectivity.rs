@@ -0,0 +1,129 @@
+//! Assure all objects reachable from a set of tips are present, similar to the connectivity check `git fetch` performs
+//! after receiving a pack.
+use crate::{find, FindExt};
+use git_features::{parallel, parallel::ShardedSet};
+use git_hash::ObjectId;
+use git_object::{immutable, Kind};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The error returned by [`check()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<FindErr: std::error::Error + 'static> {
+    #[error(transparent)]
+    Find(FindErr),
+    #[error("The tip {oid} is missing")]
+    MissingTip { oid: ObjectId },
+    #[error("The object {oid} referenced by {referenced_by} is missing")]
+    Missing { oid: ObjectId, referenced_by: ObjectId },
+    #[error(transparent)]
+    Decode(#[from] immutable::object::decode::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Information gathered during [`check()`].
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The amount of objects that were looked up, which is all objects reachable from the tips that aren't reachable from
+    /// the complete objects.
+    pub objects: usize,
+}
+
+/// An object to look up, along with the object that refers to it, if it isn't a tip.
+struct Task {
+    id: ObjectId,
+    referenced_by: Option<ObjectId>,
+}
+
+/// Assure that all objects reachable from `tips` can be found in `db`, using up to `thread_limit` threads,
+/// failing with the first object that can't be found.
+///
+/// `complete` are objects like the previous tips of references, which are known to be present along with all objects
+/// reachable from them, stopping the traversal there. This keeps the check cheap after fetching, as only objects that are new
+/// are looked up. Submodule commits in trees are skipped as they aren't expected to be present.
+///
+/// * `make_cache` - a function to create a thread-local pack cache.
+/// * `should_interrupt` - if set, the check is aborted with [`Error::Interrupted`].
+pub fn check<Find, Cache>(
+    tips: impl IntoIterator<Item = ObjectId>,
+    complete: impl IntoIterator<Item = ObjectId>,
+    db: &Find,
+    make_cache: impl Fn() -> Cache + Send + Sync,
+    thread_limit: Option<usize>,
+    should_interrupt: &AtomicBool,
+) -> Result<Outcome, Error<Find::Error>>
+where
+    Find: crate::Find + Sync,
+    <Find as crate::Find>::Error: Send,
+    Cache: crate::cache::DecodeEntry + Send,
+{
+    let seen = ShardedSet::<ObjectId>::new();
+    for id in complete {
+        seen.insert(id);
+    }
+    let tips: Vec<_> = tips
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .map(|id| Task {
+            id,
+            referenced_by: None,
+        })
+        .collect();
+
+    let states = parallel::in_parallel_with_tasks(
+        tips,
+        thread_limit,
+        |_| (Vec::new(), make_cache(), Outcome::default()),
+        |Task { id, referenced_by }, (buf, cache, outcome), spawn| -> Result<(), Error<Find::Error>> {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let object = db.find_existing(id, buf, cache).map_err(|err| match err {
+                find::existing::Error::Find(err) => Error::Find(err),
+                find::existing::Error::NotFound { oid } => match referenced_by {
+                    Some(referenced_by) => Error::Missing { oid, referenced_by },
+                    None => Error::MissingTip { oid },
+                },
+            })?;
+            outcome.objects += 1;
+            let mut follow = |next: ObjectId| {
+                if seen.insert(next) {
+                    spawn(Task {
+                        id: next,
+                        referenced_by: Some(id),
+                    });
+                }
+            };
+            match object.kind {
+                Kind::Commit => {
+                    for token in immutable::CommitIter::from_bytes(object.data) {
+                        match token?.into_id() {
+                            Some(next) => follow(next),
+                            None => break,
+                        }
+                    }
+                }
+                Kind::Tree => {
+                    for entry in immutable::TreeIter::from_bytes(object.data) {
+                        let entry = entry?;
+                        if entry.mode != git_object::tree::EntryMode::Commit {
+                            follow(entry.oid.to_owned());
+                        }
+                    }
+                }
+                Kind::Tag => {
+                    if let Some(tag) = object.decode()?.as_tag() {
+                        follow(tag.target());
+                    }
+                }
+                Kind::Blob => {}
+            }
+            Ok(())
+        },
+    )?;
+    Ok(Outcome {
+        objects: states.into_iter().map(|(_, _, outcome)| outcome.objects).sum(),
+    })
+}
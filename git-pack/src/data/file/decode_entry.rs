@@ -13,6 +13,8 @@ use std::{convert::TryInto, ops::Range};
 pub enum Error {
     #[error("Failed to decompress pack entry")]
     ZlibInflate(#[from] zlib::inflate::Error),
+    #[error("The compressed data of the pack entry at data offset {data_offset} ended prematurely")]
+    ZlibTruncated { data_offset: u64 },
     #[error("A delta chain could not be applied as the ref base with id {0} could not be found")]
     DeltaBaseUnresolved(git_hash::ObjectId),
 }
@@ -118,10 +120,11 @@ impl File {
         let offset: usize = data_offset.try_into().expect("offset representable by machine");
         assert!(offset < self.data.len(), "entry offset out of bounds");
 
-        zlib::Inflate::default()
-            .once(&self.data[offset..], out)
-            .map_err(Into::into)
-            .map(|(_status, consumed_in, _consumed_out)| consumed_in)
+        let chunk = zlib::Inflate::default().chunk(&self.data[offset..], out)?;
+        match chunk.state {
+            zlib::inflate::State::NeedsInput => Err(Error::ZlibTruncated { data_offset }),
+            zlib::inflate::State::Done | zlib::inflate::State::NeedsOutput => Ok(chunk.consumed_in),
+        }
     }
 
     /// Decode an entry, resolving delta's as needed, while growing the `out` vector if there is not enough
@@ -0,0 +1,159 @@
+use crate::data::input;
+use std::{
+    io::{self, Read, Seek, SeekFrom, Write},
+    iter::Peekable,
+};
+
+/// An implementation of [`Iterator`] to write [encoded entries][input::Entry] to an inner implementation each time
+/// `next()` is called, passing the entries on unchanged except for the last one.
+///
+/// Once the last entry was written, the pack header is rewritten with the actual amount of written entries, the trailing
+/// hash over all written bytes is computed and appended, and it is set as the last entry's `trailer`.
+/// This makes it suitable for writing packs whose entries were altered on the fly, like when
+/// [resolving thin packs][input::LookupRefDeltaObjectsIter].
+///
+/// # Note
+///
+/// The input entries must [keep their compressed data][input::EntryDataMode::keep()].
+pub struct EntriesToBytesIter<I: Iterator, W> {
+    /// An iterator for input [`input::Entry`] instances
+    pub input: Peekable<I>,
+    /// A way of writing encoded bytes.
+    output: W,
+    /// Our trailing hash when done writing all input entries
+    trailer: Option<git_hash::ObjectId>,
+    /// The amount of bytes written so far, which is also the pack offset of the next entry.
+    written: u64,
+    /// The amount of entries written so far.
+    num_entries: u32,
+    /// The pack data version with which pack entries should be written.
+    data_version: crate::data::Version,
+    /// The kind of hash to use for the digest
+    hash_kind: git_hash::Kind,
+    /// If we are done, no additional writes will occour
+    is_done: bool,
+}
+
+impl<I, W> EntriesToBytesIter<I, W>
+where
+    I: Iterator<Item = Result<input::Entry, input::Error>>,
+    W: Read + Write + Seek,
+{
+    /// Create a new instance reading [entries][input::Entry] from an `input` iterator and write pack data bytes to
+    /// `output` writer, resembling a pack of `version`. The amount of entries will be dynamically determined and
+    /// the pack is completed once the last entry was written.
+    /// `hash_kind` is the kind of hash to use for the pack checksum and maybe other places, depending on the version.
+    ///
+    /// The `output` is read back to compute the trailing hash, so it must be positioned at the beginning of an empty file.
+    ///
+    /// # Panics
+    ///
+    /// Not all combinations of `hash_kind` and `version` are supported currently triggering assertion errors.
+    pub fn new(input: I, output: W, version: crate::data::Version, hash_kind: git_hash::Kind) -> Self {
+        assert!(
+            matches!(version, crate::data::Version::V2),
+            "currently only pack version 2 can be written",
+        );
+        assert!(
+            matches!(hash_kind, git_hash::Kind::Sha1),
+            "currently only Sha1 is supported",
+        );
+        EntriesToBytesIter {
+            input: input.peekable(),
+            output,
+            trailer: None,
+            written: 0,
+            num_entries: 0,
+            data_version: version,
+            hash_kind,
+            is_done: false,
+        }
+    }
+
+    /// Consume this instance and return the `output` implementation.
+    ///
+    /// _Note_ that the input can be moved out of this instance beforehand.
+    pub fn into_write(self) -> W {
+        self.output
+    }
+
+    /// Returns the trailing hash over all written entries once done.
+    /// It's `None` if we are not yet done writing.
+    pub fn digest(&self) -> Option<git_hash::ObjectId> {
+        self.trailer
+    }
+
+    fn next_inner(&mut self, mut entry: input::Entry) -> Result<input::Entry, input::Error> {
+        if self.num_entries == 0 {
+            let header_bytes = crate::data::header::encode(self.data_version, 0);
+            self.output.write_all(&header_bytes[..])?;
+            self.written += header_bytes.len() as u64;
+        }
+        debug_assert_eq!(
+            entry.pack_offset, self.written,
+            "entries must be consecutive to be written as is"
+        );
+        let header_size = entry.header.write_to(entry.decompressed_size, &mut self.output)?;
+        let compressed = entry
+            .compressed
+            .as_ref()
+            .expect("compressed data to be kept in order to write entries");
+        self.output.write_all(compressed)?;
+        self.written += (header_size + compressed.len()) as u64;
+        self.num_entries += 1;
+
+        if self.input.peek().is_none() {
+            let digest = self.write_header_and_digest()?;
+            entry.trailer = Some(digest);
+        }
+        Ok(entry)
+    }
+
+    /// Rewrite the header with the final amount of entries, then hash everything written so far and append the digest.
+    fn write_header_and_digest(&mut self) -> Result<git_hash::ObjectId, input::Error> {
+        let header_bytes = crate::data::header::encode(self.data_version, self.num_entries);
+        self.output.seek(SeekFrom::Start(0))?;
+        self.output.write_all(&header_bytes[..])?;
+        self.output.seek(SeekFrom::Start(0))?;
+
+        let mut hash = git_features::hash::Write::new(io::sink(), self.hash_kind);
+        let bytes_hashed = io::copy(&mut (&mut self.output).take(self.written), &mut hash)?;
+        if bytes_hashed != self.written {
+            return Err(input::Error::Io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "could not read back all written pack data",
+            )));
+        }
+        let digest = git_hash::ObjectId::from(hash.hash.digest());
+        self.output.write_all(digest.as_slice())?;
+        self.output.flush()?;
+
+        self.is_done = true;
+        self.trailer = Some(digest);
+        Ok(digest)
+    }
+}
+
+impl<I, W> Iterator for EntriesToBytesIter<I, W>
+where
+    I: Iterator<Item = Result<input::Entry, input::Error>>,
+    W: Read + Write + Seek,
+{
+    /// The entry just written, with the `trailer` set to the trailing hash of the pack if it's the last one.
+    type Item = Result<input::Entry, input::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+        let result = self.input.next()?.and_then(|entry| self.next_inner(entry));
+        if result.is_err() {
+            self.is_done = true;
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
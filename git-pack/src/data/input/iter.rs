@@ -79,15 +79,12 @@ where
         // Read header
         let entry = match self.hash.take() {
             Some(hash) => {
-                let mut read = read_and_pass_to(
-                    &mut self.read,
-                    hash::Write {
-                        inner: io::sink(),
-                        hash,
-                    },
-                );
+                let mut read = hash::Read {
+                    inner: &mut self.read,
+                    hash,
+                };
                 let res = crate::data::Entry::from_read(&mut read, self.offset);
-                self.hash = Some(read.write.hash);
+                self.hash = Some(read.hash);
                 res
             }
             None => crate::data::Entry::from_read(&mut self.read, self.offset),
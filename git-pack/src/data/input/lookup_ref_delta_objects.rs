@@ -0,0 +1,206 @@
+use crate::data::{entry::Header, input};
+use git_hash::ObjectId;
+use std::{collections::HashMap, io::Write};
+
+/// An iterator to resolve thin packs on the fly by inserting the base objects of ref-deltas which aren't part of the pack.
+///
+/// Each missing base is looked up and inserted as full object right in front of the first delta referring to it, turning
+/// all deltas referring to it into offset deltas. The pack offsets of all following entries as well as the distances of
+/// offset deltas whose base lies before an inserted entry are adjusted accordingly.
+///
+/// Ref-deltas whose base can't be looked up are passed on as is. These are either in-pack ref-deltas or a sign of an
+/// incomplete repository, and [`write_data_iter_to_stream()`][crate::index::File::write_data_iter_to_stream()] will
+/// fail on them.
+///
+/// # Note
+///
+/// The input entries must [keep their compressed data][input::EntryDataMode::keep()] to allow writing the altered
+/// entries into a new pack, for example with [`EntriesToBytesIter`][input::EntriesToBytesIter], and to recompute
+/// the CRC32 of entries whose header changed.
+pub struct LookupRefDeltaObjectsIter<I, LFn> {
+    /// The inner iterator whose entries we will resolve.
+    pub inner: I,
+    lookup: LFn,
+    /// The delta to return next, as its base was just returned in its place.
+    next_delta: Option<input::Entry>,
+    /// The original pack offsets from which on entries are shifted by the given amount of bytes, sorted by offset.
+    shifts: Vec<(u64, i64)>,
+    /// The amount of bytes by which the entry to be returned next is shifted, the sum of all changes so far.
+    shift: i64,
+    /// The new pack offsets of all base objects we inserted.
+    inserted_bases: HashMap<ObjectId, u64>,
+    buf: Vec<u8>,
+}
+
+impl<I, LFn> LookupRefDeltaObjectsIter<I, LFn>
+where
+    I: Iterator<Item = Result<input::Entry, input::Error>>,
+    LFn: for<'a> FnMut(ObjectId, &'a mut Vec<u8>) -> Option<crate::data::Object<'a>>,
+{
+    /// Create a new instance wrapping `iter` and using `lookup` as function to retrieve objects that will serve as bases
+    /// for ref deltas seen while traversing `iter`.
+    pub fn new(iter: I, lookup: LFn) -> Self {
+        LookupRefDeltaObjectsIter {
+            inner: iter,
+            lookup,
+            next_delta: None,
+            shifts: Vec::new(),
+            shift: 0,
+            inserted_bases: HashMap::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Return the amount of bytes entries at `original_pack_offset` were moved by.
+    fn shift_at(&self, original_pack_offset: u64) -> i64 {
+        match self
+            .shifts
+            .binary_search_by_key(&original_pack_offset, |(offset, _)| *offset)
+        {
+            Ok(idx) => self.shifts[idx].1,
+            Err(0) => 0,
+            Err(idx) => self.shifts[idx - 1].1,
+        }
+    }
+
+    /// Change the shift for all entries following the one at `original_pack_offset` by `bytes`.
+    fn shift_after(&mut self, original_pack_offset: u64, bytes: i64) {
+        if bytes == 0 {
+            return;
+        }
+        self.shift += bytes;
+        self.shifts.push((original_pack_offset + 1, self.shift));
+    }
+
+    fn next_inner(&mut self, mut entry: input::Entry) -> Result<input::Entry, input::Error> {
+        let original_pack_offset = entry.pack_offset;
+        entry.pack_offset = shifted(original_pack_offset, self.shift);
+        match entry.header {
+            Header::OfsDelta { base_distance } if self.shift != 0 => {
+                if let Some(original_base_offset) =
+                    Header::verified_base_pack_offset(original_pack_offset, base_distance)
+                {
+                    let base_pack_offset = shifted(original_base_offset, self.shift_at(original_base_offset));
+                    let new_base_distance = entry.pack_offset - base_pack_offset;
+                    if new_base_distance != base_distance {
+                        let size_change = set_header(
+                            &mut entry,
+                            Header::OfsDelta {
+                                base_distance: new_base_distance,
+                            },
+                        )?;
+                        self.shift_after(original_pack_offset, size_change);
+                    }
+                }
+            }
+            Header::RefDelta { base_id } => {
+                if let Some(base_pack_offset) = self.inserted_bases.get(&base_id).copied() {
+                    let header = Header::OfsDelta {
+                        base_distance: entry.pack_offset - base_pack_offset,
+                    };
+                    let size_change = set_header(&mut entry, header)?;
+                    self.shift_after(original_pack_offset, size_change);
+                } else if let Some(base) = (self.lookup)(base_id, &mut self.buf) {
+                    let base = base_entry(&base, entry.pack_offset)?;
+                    let base_size = base.header_size as u64 + base.compressed_size;
+                    self.inserted_bases.insert(base_id, base.pack_offset);
+
+                    self.shift += base_size as i64;
+                    self.shifts.push((original_pack_offset, self.shift));
+                    entry.pack_offset += base_size;
+
+                    let size_change = set_header(
+                        &mut entry,
+                        Header::OfsDelta {
+                            base_distance: base_size,
+                        },
+                    )?;
+                    self.shift_after(original_pack_offset, size_change);
+                    self.next_delta = Some(entry);
+                    return Ok(base);
+                }
+            }
+            _ => {}
+        }
+        Ok(entry)
+    }
+}
+
+impl<I, LFn> Iterator for LookupRefDeltaObjectsIter<I, LFn>
+where
+    I: Iterator<Item = Result<input::Entry, input::Error>>,
+    LFn: for<'a> FnMut(ObjectId, &'a mut Vec<u8>) -> Option<crate::data::Object<'a>>,
+{
+    type Item = Result<input::Entry, input::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(delta) = self.next_delta.take() {
+            return Some(Ok(delta));
+        }
+        match self.inner.next()? {
+            Ok(entry) => Some(self.next_inner(entry)),
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.inner.size_hint();
+        let pending = self.next_delta.is_some() as usize;
+        // each ref-delta may cause a base to be inserted
+        (
+            lower + pending,
+            upper.and_then(|upper| upper.checked_mul(2)).map(|u| u + pending),
+        )
+    }
+}
+
+fn shifted(pack_offset: u64, shift: i64) -> u64 {
+    (pack_offset as i64 + shift) as u64
+}
+
+/// Replace the header of `entry` with `header`, recompute its CRC32 if present and return by how many bytes it changed in size.
+fn set_header(entry: &mut input::Entry, header: Header) -> Result<i64, input::Error> {
+    let mut header_buf = Vec::with_capacity(32);
+    let header_size = header.write_to(entry.decompressed_size, &mut header_buf)?;
+    let compressed = entry
+        .compressed
+        .as_ref()
+        .expect("compressed data to be kept in order to alter entries");
+    if entry.crc32.is_some() {
+        let state = git_features::hash::crc32_update(0, &header_buf);
+        entry.crc32 = Some(git_features::hash::crc32_update(state, compressed));
+    }
+    let size_change = header_size as i64 - entry.header_size as i64;
+    entry.header = header;
+    entry.header_size = header_size as u16;
+    Ok(size_change)
+}
+
+/// Create a pack entry for the base `object` to be inserted at `pack_offset`.
+fn base_entry(object: &crate::data::Object<'_>, pack_offset: u64) -> Result<input::Entry, input::Error> {
+    let header = match object.kind {
+        git_object::Kind::Tree => Header::Tree,
+        git_object::Kind::Blob => Header::Blob,
+        git_object::Kind::Commit => Header::Commit,
+        git_object::Kind::Tag => Header::Tag,
+    };
+    let mut header_buf = Vec::with_capacity(32);
+    let header_size = header.write_to(object.data.len() as u64, &mut header_buf)?;
+
+    let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+    out.write_all(object.data)?;
+    out.flush()?;
+    let compressed = out.into_inner();
+
+    let state = git_features::hash::crc32_update(0, &header_buf);
+    Ok(input::Entry {
+        header,
+        header_size: header_size as u16,
+        pack_offset,
+        compressed_size: compressed.len() as u64,
+        crc32: Some(git_features::hash::crc32_update(state, &compressed)),
+        compressed: Some(compressed),
+        decompressed_size: object.data.len() as u64,
+        trailer: None,
+    })
+}
@@ -31,3 +31,9 @@ pub use types::{EntryDataMode, Error, Mode};
 
 mod iter;
 pub use iter::BytesToEntriesIter;
+
+mod lookup_ref_delta_objects;
+pub use lookup_ref_delta_objects::LookupRefDeltaObjectsIter;
+
+mod entries_to_bytes;
+pub use entries_to_bytes::EntriesToBytesIter;
@@ -91,6 +91,16 @@ impl File {
         self.data.get(entry_start..entry_end)
     }
 
+    /// Hint the operating system that the pack data in the given `range` will be accessed soon, allowing it to read it
+    /// ahead of time while the caller is busy with other work. This helps most if the pack isn't in the page cache yet.
+    ///
+    /// Parts of the `range` outside of the mapped pack data are ignored.
+    pub fn prefetch(&self, range: EntryRange) {
+        let end = (range.end as usize).min(self.data.len());
+        let start = (range.start as usize).min(end);
+        self.data.prefetch(start, end - start);
+    }
+
     /// Returns the CRC32 of the pack data indicated by `pack_offset` and the `size` of the mapped data.
     ///
     /// _Note:_ finding the right size is only possible by decompressing
@@ -1,6 +1,11 @@
 use crate::data::output;
 use git_features::hash;
-use std::io::Write;
+use git_hash::ObjectId;
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
 /// The error returned by `next()` in the [`FromEntriesIter`] iterator.
 #[allow(missing_docs)]
@@ -13,6 +18,54 @@ where
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Input(E),
+    #[error("Entry {id} refers to a delta base {nth_before} entries before it, but only {num_written} entries were written so far")]
+    DeltaBaseOutOfBounds {
+        id: ObjectId,
+        nth_before: usize,
+        num_written: usize,
+    },
+    #[error("Entry {id} would be at the end of a delta chain of length {depth}, exceeding the maximum of {max_depth}")]
+    DeltaDepthExceeded { id: ObjectId, depth: u32, max_depth: u32 },
+    #[error("The pack header announced {expected} entries, but {actual} were written")]
+    EntryCountMismatch { expected: u32, actual: usize },
+}
+
+/// Constraints on the entries written by [`FromEntriesIter`], usually derived from what the receiving client understands.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    /// If `false`, deltas against previous entries are written as deltas referring to their base by id instead of by
+    /// offset, as needed by clients which don't advertise the `ofs-delta` capability.
+    pub allow_ofs_delta: bool,
+    /// If set, entries at the end of delta chains longer than this cause an error.
+    pub max_delta_depth: Option<u32>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            allow_ofs_delta: true,
+            max_delta_depth: None,
+        }
+    }
+}
+
+/// Information about a written entry as needed to create an index for the pack, see [`FromEntriesIter::with_index()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct IndexEntry {
+    /// The id of the object stored in the entry.
+    pub id: ObjectId,
+    /// The offset of the entry in the pack.
+    pub pack_offset: u64,
+    /// The CRC32 over the entry header and the compressed data.
+    pub crc32: u32,
+}
+
+/// Information about an entry that was already written, needed to refer to it as delta base.
+struct WrittenEntry {
+    id: ObjectId,
+    pack_offset: u64,
+    delta_depth: u32,
 }
 
 /// An implementation of [`Iterator`] to write [encoded entries][output::Entry] to an inner implementation each time
@@ -27,8 +80,22 @@ pub struct FromEntriesIter<I, W> {
     /// The amount of objects in the iteration and the version of the packfile to be written.
     /// Will be `None` to signal the header was written already.
     header_info: Option<(crate::data::Version, u32)>,
+    /// The amount of objects announced in the header, to validate the amount of written entries with.
+    num_entries: u32,
+    /// If set, information about each written entry to create an index with.
+    index_entries: Option<Vec<IndexEntry>>,
+    /// A buffer for the header of an entry, to compute its CRC32.
+    header_buf: Vec<u8>,
     /// The pack data version with which pack entries should be written.
     entry_version: crate::data::Version,
+    /// The constraints for the entries to write.
+    options: Options,
+    /// The offset in the pack at which the next entry will be written.
+    pack_offset: u64,
+    /// All entries written so far, in order.
+    written: Vec<WrittenEntry>,
+    /// Maps object ids to their index in `written`, to find the delta depth of bases referred to by id.
+    index_by_id: HashMap<ObjectId, usize>,
     /// If we are done, no additional writes will occour
     is_done: bool,
 }
@@ -42,6 +109,7 @@ where
     /// Create a new instance reading [entries][output::Entry] from an `input` iterator and write pack data bytes to
     /// `output` writer, resembling a pack of `version` with exactly `num_entries` amount of objects contained in it.
     /// `hash_kind` is the kind of hash to use for the pack checksum and maybe other places, depending on the version.
+    /// `options` constrain how entries are written.
     ///
     /// The input chunks are expected to be sorted already. You can use the [InOrderIter][super::InOrderIter] to assure
     /// this happens on the fly holding entire chunks in memory as long as needed for them to be dispensed in order.
@@ -55,6 +123,7 @@ where
         num_entries: u32,
         version: crate::data::Version,
         hash_kind: git_hash::Kind,
+        options: Options,
     ) -> Self {
         assert!(
             matches!(version, crate::data::Version::V2),
@@ -69,11 +138,26 @@ where
             output: hash::Write::new(output, hash_kind),
             trailer: None,
             entry_version: version,
+            options,
+            pack_offset: 0,
+            written: Vec::new(),
+            index_by_id: HashMap::new(),
             header_info: Some((version, num_entries)),
+            num_entries,
+            index_entries: None,
+            header_buf: Vec::new(),
             is_done: false,
         }
     }
 
+    /// Record the information needed to create an index for the written pack, which can then be written with
+    /// [`write_index()`][FromEntriesIter::write_index()] or [`write_index_to_directory()`][FromEntriesIter::write_index_to_directory()]
+    /// once all entries were written, without the need to read the pack back.
+    pub fn with_index(mut self) -> Self {
+        self.index_entries = Some(Vec::with_capacity(self.num_entries as usize));
+        self
+    }
+
     /// Consume this instance and return the `output` implementation.
     ///
     /// _Note_ that the input can be moved out of this instance beforehand.
@@ -87,24 +171,100 @@ where
         self.trailer
     }
 
+    /// Returns information about all entries written so far, in pack order, if [`with_index()`][FromEntriesIter::with_index()]
+    /// was called.
+    pub fn index_entries(&self) -> Option<&[IndexEntry]> {
+        self.index_entries.as_deref()
+    }
+
+    /// Write a V2 index for the written pack to `out` and return information about it.
+    ///
+    /// # Panics
+    ///
+    /// If [`with_index()`][FromEntriesIter::with_index()] wasn't called or if the pack wasn't fully written yet.
+    pub fn write_index(&self, out: impl io::Write) -> io::Result<crate::index::write::Outcome> {
+        let pack_hash = self.trailer.expect("the pack to be written completely");
+        let mut entries = self
+            .index_entries
+            .clone()
+            .expect("with_index() to be called before writing the pack");
+        entries.sort_by_key(|e| e.id);
+        let index_hash = crate::index::write::encode::write_to(
+            out,
+            entries.iter().map(|e| (e.id, e.crc32, e.pack_offset)),
+            &pack_hash,
+            crate::index::Version::V2,
+            git_features::progress::Discard,
+        )?;
+        Ok(crate::index::write::Outcome {
+            index_kind: crate::index::Version::V2,
+            index_hash,
+            data_hash: pack_hash,
+            num_objects: entries.len() as u32,
+        })
+    }
+
+    /// Write a V2 index for the written pack into `directory`, named after the pack hash like `<hash>.idx`, and return
+    /// information about it along with its path.
+    ///
+    /// # Panics
+    ///
+    /// If [`with_index()`][FromEntriesIter::with_index()] wasn't called or if the pack wasn't fully written yet.
+    pub fn write_index_to_directory(
+        &self,
+        directory: impl AsRef<Path>,
+    ) -> io::Result<(crate::index::write::Outcome, PathBuf)> {
+        let directory = directory.as_ref();
+        let mut index_file = tempfile::NamedTempFile::new_in(directory)?;
+        let outcome = self.write_index(&mut index_file)?;
+        let index_path = directory.join(format!("{}.idx", outcome.data_hash));
+        index_file.persist(&index_path)?;
+        Ok((outcome, index_path))
+    }
+
     fn next_inner(&mut self) -> Result<u64, Error<E>> {
         let mut written = 0u64;
         if let Some((version, num_entries)) = self.header_info.take() {
             let header_bytes = crate::data::header::encode(version, num_entries);
             self.output.write_all(&header_bytes[..])?;
             written += header_bytes.len() as u64;
+            self.pack_offset += header_bytes.len() as u64;
         }
         match self.input.next() {
             Some(entries) => {
                 for entry in entries.map_err(Error::Input)? {
-                    let header = entry.to_entry_header(self.entry_version, |_index_offset| {
-                        unimplemented!("a way to calculate pack offsets from object index offsets")
+                    let (header, delta_depth) = self.entry_header_and_delta_depth(&entry)?;
+                    self.header_buf.clear();
+                    header.write_to(entry.decompressed_size as u64, &mut self.header_buf)?;
+                    self.output.write_all(&self.header_buf)?;
+                    self.output.write_all(&entry.compressed_data)?;
+                    let entry_size = (self.header_buf.len() + entry.compressed_data.len()) as u64;
+
+                    if let Some(index_entries) = self.index_entries.as_mut() {
+                        let crc32 = hash::crc32_update(hash::crc32(&self.header_buf), &entry.compressed_data);
+                        index_entries.push(IndexEntry {
+                            id: entry.id,
+                            pack_offset: self.pack_offset,
+                            crc32,
+                        });
+                    }
+                    self.index_by_id.insert(entry.id, self.written.len());
+                    self.written.push(WrittenEntry {
+                        id: entry.id,
+                        pack_offset: self.pack_offset,
+                        delta_depth,
                     });
-                    written += header.write_to(entry.decompressed_size as u64, &mut self.output)? as u64;
-                    written += std::io::copy(&mut &*entry.compressed_data, &mut self.output)? as u64;
+                    self.pack_offset += entry_size;
+                    written += entry_size;
                 }
             }
             None => {
+                if self.written.len() != self.num_entries as usize {
+                    return Err(Error::EntryCountMismatch {
+                        expected: self.num_entries,
+                        actual: self.written.len(),
+                    });
+                }
                 let digest = self.output.hash.clone().digest();
                 self.output.write_all(&digest[..])?;
                 written += digest.len() as u64;
@@ -115,6 +275,52 @@ where
         };
         Ok(written)
     }
+
+    fn entry_header_and_delta_depth(
+        &self,
+        entry: &output::Entry,
+    ) -> Result<(crate::data::entry::Header, u32), Error<E>> {
+        use output::entry::Kind::*;
+        let (header, delta_depth) = match entry.kind {
+            Base => (
+                entry.to_entry_header(self.entry_version, |_| unreachable!("no base")),
+                0,
+            ),
+            DeltaRef { nth_before } => {
+                let num_written = self.written.len();
+                let base = nth_before
+                    .checked_sub(1)
+                    .and_then(|n| num_written.checked_sub(n + 1))
+                    .map(|idx| &self.written[idx])
+                    .ok_or(Error::DeltaBaseOutOfBounds {
+                        id: entry.id,
+                        nth_before,
+                        num_written,
+                    })?;
+                let header = if self.options.allow_ofs_delta {
+                    entry.to_entry_header(self.entry_version, |_| self.pack_offset - base.pack_offset)
+                } else {
+                    crate::data::entry::Header::RefDelta { base_id: base.id }
+                };
+                (header, base.delta_depth + 1)
+            }
+            DeltaOid { id } => (
+                entry.to_entry_header(self.entry_version, |_| unreachable!("refers to base by id")),
+                // bases not in this pack are expected to be available to the receiver, like it's the case in thin packs.
+                self.index_by_id
+                    .get(&id)
+                    .map_or(1, |idx| self.written[*idx].delta_depth + 1),
+            ),
+        };
+        match self.options.max_delta_depth {
+            Some(max_depth) if delta_depth > max_depth => Err(Error::DeltaDepthExceeded {
+                id: entry.id,
+                depth: delta_depth,
+                max_depth,
+            }),
+            _ => Ok((header, delta_depth)),
+        }
+    }
 }
 
 impl<I, W, E> Iterator for FromEntriesIter<I, W>
@@ -1,9 +1,11 @@
 use crate::{data::output, find, FindExt};
-use dashmap::DashSet;
-use git_features::{parallel, progress::Progress};
+use git_features::{parallel, parallel::ShardedSet, progress::Progress};
 use git_hash::{oid, ObjectId};
 use git_object::immutable;
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 /// Generate [`Count`][output::Count] from input `objects` with object expansion based on [`options`][Options]
 /// to learn which objects would be part of a pack.
@@ -20,6 +22,80 @@ use std::sync::Arc;
 /// * `options`
 ///   * more configuration
 pub fn from_objects_iter<Find, Iter, Oid, Cache>(
+    db: Find,
+    make_cache: impl Fn() -> Cache + Send + Clone + Sync + 'static,
+    objects_ids: Iter,
+    progress: impl Progress,
+    options: Options,
+) -> impl Iterator<Item = Result<Vec<output::Count>, Error<find::existing::Error<Find::Error>>>>
+       + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error<find::existing::Error<Find::Error>>>>
+where
+    Find: crate::Find + Clone + Send + Sync + 'static,
+    <Find as crate::Find>::Error: Send,
+    Iter: Iterator<Item = Oid> + Send + 'static,
+    Oid: AsRef<oid> + Send + 'static,
+    Cache: crate::cache::DecodeEntry + Send,
+{
+    count_unseen(db, make_cache, objects_ids, progress, options, Default::default())
+}
+
+/// Like [`from_objects_iter()`], but the `excluded_commits`, their trees and all objects reachable from these trees are
+/// assumed to be known to the receiver of the pack and are never counted.
+///
+/// This is useful to produce a pack for a range of commits like `a..b`, where `excluded_commits` are the commits at the
+/// boundary of the range, that is excluded commits whose children are part of the range. The ancestors of
+/// `excluded_commits` are not traversed.
+///
+/// Returns an error if an excluded commit or any object reachable from its tree can't be found.
+pub fn from_objects_iter_excluding<Find, Iter, Oid, Cache>(
+    db: Find,
+    make_cache: impl Fn() -> Cache + Send + Clone + Sync + 'static,
+    objects_ids: Iter,
+    excluded_commits: impl IntoIterator<Item = impl AsRef<oid>>,
+    progress: impl Progress,
+    options: Options,
+) -> Result<
+    impl Iterator<Item = Result<Vec<output::Count>, Error<find::existing::Error<Find::Error>>>>
+        + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error<find::existing::Error<Find::Error>>>>,
+    Error<find::existing::Error<Find::Error>>,
+>
+where
+    Find: crate::Find + Clone + Send + Sync + 'static,
+    <Find as crate::Find>::Error: Send,
+    Iter: Iterator<Item = Oid> + Send + 'static,
+    Oid: AsRef<oid> + Send + 'static,
+    Cache: crate::cache::DecodeEntry + Send,
+{
+    let seen_objs = ShardedSet::<ObjectId>::new();
+    {
+        let (mut buf, mut cache) = (Vec::new(), make_cache());
+        let mut tree_traversal_state = git_traverse::tree::breadthfirst::State::default();
+        let mut traverse_delegate = tree::traverse::AllUnseen::new(&seen_objs);
+        for commit_id in excluded_commits {
+            let commit_id = commit_id.as_ref();
+            seen_objs.insert(commit_id.to_owned());
+            let tree_id = match db.find_existing(commit_id, &mut buf, &mut cache)?.into_commit_iter() {
+                Some(mut commit_iter) => commit_iter.tree_id().expect("every commit has a tree"),
+                None => continue,
+            };
+            if !seen_objs.insert(tree_id) {
+                continue;
+            }
+            let tree_iter = immutable::TreeIter::from_bytes(db.find_existing(tree_id, &mut buf, &mut cache)?.data);
+            git_traverse::tree::breadthfirst(
+                tree_iter,
+                &mut tree_traversal_state,
+                |oid, buf| db.find_existing_tree_iter(oid, buf, &mut cache).ok(),
+                &mut traverse_delegate,
+            )
+            .map_err(Error::TreeTraverse)?;
+            traverse_delegate.clear();
+        }
+    }
+    Ok(count_unseen(db, make_cache, objects_ids, progress, options, seen_objs))
+}
+
+fn count_unseen<Find, Iter, Oid, Cache>(
     db: Find,
     make_cache: impl Fn() -> Cache + Send + Clone + Sync + 'static,
     objects_ids: Iter,
@@ -29,6 +105,7 @@ pub fn from_objects_iter<Find, Iter, Oid, Cache>(
         input_object_expansion,
         chunk_size,
     }: Options,
+    seen_objs: ShardedSet<ObjectId>,
 ) -> impl Iterator<Item = Result<Vec<output::Count>, Error<find::existing::Error<Find::Error>>>>
        + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error<find::existing::Error<Find::Error>>>>
 where
@@ -36,20 +113,24 @@ where
     <Find as crate::Find>::Error: Send,
     Iter: Iterator<Item = Oid> + Send + 'static,
     Oid: AsRef<oid> + Send + 'static,
-    Cache: crate::cache::DecodeEntry,
+    Cache: crate::cache::DecodeEntry + Send,
 {
     let lower_bound = objects_ids.size_hint().0;
-    let (chunk_size, thread_limit, _) = parallel::optimize_chunk_size_and_thread_limit(
+    let available_threads = parallel::num_threads(thread_limit);
+    let (chunk_size, thread_limit, num_threads) = parallel::optimize_chunk_size_and_thread_limit(
         chunk_size,
         if lower_bound == 0 { None } else { Some(lower_bound) },
         thread_limit,
         None,
     );
+    // Threads not used for processing chunks are used to traverse trees, which is the only way to make use of them
+    // if there are only a few input objects.
+    let tree_thread_limit = Some((available_threads / num_threads).max(1));
     let chunks = util::Chunks {
         iter: objects_ids,
         size: chunk_size,
     };
-    let seen_objs = Arc::new(dashmap::DashSet::<ObjectId>::new());
+    let seen_objs = Arc::new(seen_objs);
     let progress = Arc::new(parking_lot::Mutex::new(progress));
 
     parallel::reduce::Stepwise::new(
@@ -57,6 +138,7 @@ where
         thread_limit,
         {
             let progress = Arc::clone(&progress);
+            let make_cache = make_cache.clone();
             move |n| {
                 (
                     Vec::new(),   // object data buffer
@@ -204,18 +286,25 @@ where
                                 push_obj_count_unique(&mut out, seen_objs, &id, &obj, progress, stats, false);
                                 match obj.kind {
                                     Tree => {
-                                        traverse_delegate.clear();
-                                        git_traverse::tree::breadthfirst(
-                                            git_object::immutable::TreeIter::from_bytes(obj.data),
-                                            &mut tree_traversal_state,
-                                            |oid, buf| {
-                                                stats.decoded_objects += 1;
+                                        let decoded_trees = AtomicUsize::new(0);
+                                        let objects = git_traverse::tree::parallel::unseen(
+                                            Some(id),
+                                            seen_objs,
+                                            tree_thread_limit,
+                                            |_| make_cache(),
+                                            |oid, buf, cache| {
+                                                if oid == id {
+                                                    buf.clear();
+                                                    buf.extend_from_slice(obj.data);
+                                                    return Some(immutable::TreeIter::from_bytes(buf));
+                                                }
+                                                decoded_trees.fetch_add(1, Ordering::Relaxed);
                                                 db.find_existing_tree_iter(oid, buf, cache).ok()
                                             },
-                                            &mut traverse_delegate,
                                         )
                                         .map_err(Error::TreeTraverse)?;
-                                        for id in traverse_delegate.objects.iter() {
+                                        stats.decoded_objects += decoded_trees.into_inner();
+                                        for id in objects.iter() {
                                             out.push(id_to_count(&db, buf1, id, progress, stats));
                                         }
                                         break;
@@ -252,21 +341,21 @@ where
 
 mod tree {
     pub mod changes {
-        use dashmap::DashSet;
         use git_diff::tree::{
             visit::{Action, Change},
             Visit,
         };
+        use git_features::parallel::ShardedSet;
         use git_hash::ObjectId;
         use git_object::bstr::BStr;
 
         pub struct AllNew<'a> {
             pub objects: Vec<ObjectId>,
-            all_seen: &'a DashSet<ObjectId>,
+            all_seen: &'a ShardedSet<ObjectId>,
         }
 
         impl<'a> AllNew<'a> {
-            pub fn new(all_seen: &'a DashSet<ObjectId>) -> Self {
+            pub fn new(all_seen: &'a ShardedSet<ObjectId>) -> Self {
                 AllNew {
                     objects: Default::default(),
                     all_seen,
@@ -302,18 +391,18 @@ mod tree {
     }
 
     pub mod traverse {
-        use dashmap::DashSet;
+        use git_features::parallel::ShardedSet;
         use git_hash::ObjectId;
         use git_object::{bstr::BStr, immutable::tree::Entry};
         use git_traverse::tree::visit::{Action, Visit};
 
         pub struct AllUnseen<'a> {
             pub objects: Vec<ObjectId>,
-            all_seen: &'a DashSet<ObjectId>,
+            all_seen: &'a ShardedSet<ObjectId>,
         }
 
         impl<'a> AllUnseen<'a> {
-            pub fn new(all_seen: &'a DashSet<ObjectId>) -> Self {
+            pub fn new(all_seen: &'a ShardedSet<ObjectId>) -> Self {
                 AllUnseen {
                     objects: Default::default(),
                     all_seen,
@@ -356,7 +445,7 @@ mod tree {
 
 fn push_obj_count_unique(
     out: &mut Vec<output::Count>,
-    all_seen: &DashSet<ObjectId>,
+    all_seen: &ShardedSet<ObjectId>,
     id: &oid,
     obj: &crate::data::Object<'_>,
     progress: &mut impl Progress,
@@ -486,6 +575,8 @@ mod types {
     #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
     pub struct Options {
         /// The amount of threads to use at most when resolving the pack. If `None`, all logical cores are used.
+        ///
+        /// Only if it is 1 the counts are produced in a deterministic order.
         pub thread_limit: Option<usize>,
         /// The amount of objects per chunk or unit of work to be sent to threads for processing
         /// TODO: could this become the window size?
@@ -26,4 +26,7 @@ impl Count {
 
 ///
 pub mod from_objects_iter;
-pub use from_objects_iter::from_objects_iter;
+pub use from_objects_iter::{from_objects_iter, from_objects_iter_excluding};
+
+///
+pub mod persisted;
@@ -0,0 +1,316 @@
+//! Persist the result of [counting objects][super::from_objects_iter()] to reuse it when generating packs for similar tips later.
+//!
+//! Counting is the most expensive part of pack generation for big repositories, and services like mirrors tend to produce
+//! packs for tips that barely change between runs. A [`Snapshot`] stores the tips along with their counts, and
+//! [`Snapshot::reuse()`] determines if these are still usable for a new set of tips.
+use crate::{data::output, FindExt};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use git_features::hash;
+use git_hash::ObjectId;
+use git_traverse::commit;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::Path,
+};
+
+const SIGNATURE: &[u8] = b"PCNT";
+const VERSION: u32 = 1;
+const HASH_LEN: usize = 20;
+
+/// The tips used as input for counting along with the resulting counts.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Snapshot {
+    /// The tips whose reachable objects are contained in `counts`.
+    pub tips: Vec<ObjectId>,
+    /// All objects reachable from `tips`, as produced by [`from_objects_iter()`][super::from_objects_iter()].
+    pub counts: Vec<output::Count>,
+    /// The checksums of all packs referred to by the pack locations in `counts`, keyed by their
+    /// [`pack_id`][crate::bundle::Location::pack_id].
+    pub packs: BTreeMap<u32, ObjectId>,
+}
+
+/// The reason a [`Snapshot`] can't be reused.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Staleness {
+    /// A tip of the snapshot isn't a tip anymore and isn't reachable from any of the new tips either, which means the snapshot
+    /// contains objects that aren't necessarily reachable.
+    TipRemoved(ObjectId),
+    /// More than [`Options::max_new_tips`] tips were added.
+    TooManyNewTips(usize),
+    /// The pack with the given id was removed or its checksum changed, invalidating the pack locations of counts referring to it.
+    PackChanged {
+        /// The id of the pack as seen in [`Location::pack_id`][crate::bundle::Location::pack_id].
+        pack_id: u32,
+    },
+}
+
+/// The outcome of [`Snapshot::reuse()`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Reuse {
+    /// No tips were added and all counts can be used as is.
+    Complete(Vec<output::Count>),
+    /// Some tips were added and need to be counted, with the results added to `counts`.
+    ///
+    /// Note that the new tips may be commits whose ancestors are already contained in `counts`, thus the caller may want to
+    /// count only commits not reachable from the previous tips, using the
+    /// [`TreeAdditionsComparedToAncestor`][super::from_objects_iter::ObjectExpansion::TreeAdditionsComparedToAncestor] expansion.
+    /// This can yield objects which are already contained in `counts`, so these have to be deduplicated by id.
+    Partial {
+        /// The counts of all objects reachable from the previous tips.
+        counts: Vec<output::Count>,
+        /// The tips that weren't part of the snapshot.
+        new_tips: Vec<ObjectId>,
+    },
+    /// The snapshot can't be used and counting has to be done from scratch.
+    Stale(Staleness),
+}
+
+/// Configuration for [`Snapshot::reuse()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Options {
+    /// The amount of tips that may be added before the snapshot is considered stale, as counting many new tips
+    /// from a partial result is not expected to be faster than starting from scratch.
+    pub max_new_tips: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { max_new_tips: 16 }
+    }
+}
+
+///
+pub mod decode {
+    /// The error returned by [`Snapshot::from_bytes()`][super::Snapshot::from_bytes()] and [`Snapshot::at()`][super::Snapshot::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the persisted counts")]
+        Io(#[from] std::io::Error),
+        #[error("The persisted counts are corrupt: {message}")]
+        Corrupt { message: &'static str },
+        #[error("Persisted counts of version {0} are not supported")]
+        UnsupportedVersion(u32),
+        #[error("The checksum of the persisted counts did not match: expected {expected}, actual {actual}")]
+        Mismatch {
+            expected: git_hash::ObjectId,
+            actual: git_hash::ObjectId,
+        },
+    }
+}
+
+impl Snapshot {
+    /// Create a new snapshot of `counts` obtained for `tips`, looking up the checksums of the packs they refer to in `db`.
+    ///
+    /// Packs that can't be found in `db` are not recorded, which makes the snapshot stale once it is [reused][Snapshot::reuse()].
+    pub fn new<Find>(tips: Vec<ObjectId>, counts: Vec<output::Count>, db: &Find) -> Self
+    where
+        Find: crate::Find,
+    {
+        let packs = pack_ids(&counts)
+            .into_iter()
+            .filter_map(|pack_id| db.pack_checksum(pack_id).map(|checksum| (pack_id, checksum)))
+            .collect();
+        Snapshot { tips, counts, packs }
+    }
+
+    /// Read a snapshot previously written with [`write_to()`][Snapshot::write_to()] from the file at `path`.
+    pub fn at(path: impl AsRef<Path>) -> Result<Self, decode::Error> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Decode a snapshot previously written with [`write_to()`][Snapshot::write_to()] from `data`, validating its checksum.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, decode::Error> {
+        let corrupt = |message| decode::Error::Corrupt { message };
+        if data.len() < SIGNATURE.len() + 4 * 4 + HASH_LEN {
+            return Err(corrupt("file too short"));
+        }
+        let (data, trailer) = data.split_at(data.len() - HASH_LEN);
+        let expected = ObjectId::from_20_bytes(trailer);
+        let actual = {
+            let mut hasher = hash::Sha1::default();
+            hasher.update(data);
+            ObjectId::new_sha1(hasher.digest())
+        };
+        if actual != expected {
+            return Err(decode::Error::Mismatch { expected, actual });
+        }
+        if &data[..SIGNATURE.len()] != SIGNATURE {
+            return Err(corrupt("signature mismatch"));
+        }
+        let mut data = &data[SIGNATURE.len()..];
+        let version = data.read_u32::<BigEndian>()?;
+        if version != VERSION {
+            return Err(decode::Error::UnsupportedVersion(version));
+        }
+        let num_tips = data.read_u32::<BigEndian>()? as usize;
+        let num_counts = data.read_u32::<BigEndian>()? as usize;
+        let num_packs = data.read_u32::<BigEndian>()? as usize;
+
+        let read_id = |data: &mut &[u8]| -> Result<ObjectId, decode::Error> {
+            if data.len() < HASH_LEN {
+                return Err(corrupt("truncated object id"));
+            }
+            let (id, rest) = data.split_at(HASH_LEN);
+            *data = rest;
+            Ok(ObjectId::from_20_bytes(id))
+        };
+        let tips = (0..num_tips)
+            .map(|_| read_id(&mut data))
+            .collect::<Result<Vec<_>, _>>()?;
+        let packs = (0..num_packs)
+            .map(|_| Ok((data.read_u32::<BigEndian>()?, read_id(&mut data)?)))
+            .collect::<Result<BTreeMap<_, _>, decode::Error>>()?;
+        let mut counts = Vec::with_capacity(num_counts);
+        for _ in 0..num_counts {
+            let id = read_id(&mut data)?;
+            let entry_pack_location = match data.read_u8()? {
+                0 => None,
+                1 => Some(crate::bundle::Location {
+                    pack_id: data.read_u32::<BigEndian>()?,
+                    index_file_id: data.read_u32::<BigEndian>()?,
+                    pack_offset: data.read_u64::<BigEndian>()?,
+                    entry_size: data.read_u64::<BigEndian>()? as usize,
+                }),
+                _ => return Err(corrupt("invalid pack location marker")),
+            };
+            counts.push(output::Count {
+                id,
+                entry_pack_location,
+            });
+        }
+        if !data.is_empty() {
+            return Err(corrupt("unexpected trailing bytes"));
+        }
+        Ok(Snapshot { tips, counts, packs })
+    }
+
+    /// Write this snapshot to `out` in a format suitable for [`from_bytes()`][Snapshot::from_bytes()], returning the checksum
+    /// written as trailer.
+    pub fn write_to(&self, out: impl io::Write) -> io::Result<ObjectId> {
+        use io::Write;
+        assert!(
+            self.tips.len() <= u32::MAX as usize
+                && self.counts.len() <= u32::MAX as usize
+                && self.packs.len() <= u32::MAX as usize,
+            "cannot persist more than u32::MAX tips, counts or packs"
+        );
+        let mut out = io::BufWriter::with_capacity(8 * 4096, hash::Write::new(out, git_hash::Kind::Sha1));
+        out.write_all(SIGNATURE)?;
+        out.write_u32::<BigEndian>(VERSION)?;
+        out.write_u32::<BigEndian>(self.tips.len() as u32)?;
+        out.write_u32::<BigEndian>(self.counts.len() as u32)?;
+        out.write_u32::<BigEndian>(self.packs.len() as u32)?;
+        for tip in &self.tips {
+            out.write_all(tip.as_slice())?;
+        }
+        for (pack_id, checksum) in &self.packs {
+            out.write_u32::<BigEndian>(*pack_id)?;
+            out.write_all(checksum.as_slice())?;
+        }
+        for count in &self.counts {
+            out.write_all(count.id.as_slice())?;
+            match &count.entry_pack_location {
+                None => out.write_u8(0)?,
+                Some(location) => {
+                    out.write_u8(1)?;
+                    out.write_u32::<BigEndian>(location.pack_id)?;
+                    out.write_u32::<BigEndian>(location.index_file_id)?;
+                    out.write_u64::<BigEndian>(location.pack_offset)?;
+                    out.write_u64::<BigEndian>(location.entry_size as u64)?;
+                }
+            }
+        }
+
+        let hash::Write { hash, mut inner } = out.into_inner()?;
+        let checksum = ObjectId::new_sha1(hash.digest());
+        inner.write_all(checksum.as_slice())?;
+        inner.flush()?;
+        Ok(checksum)
+    }
+
+    /// Determine if the counts of this snapshot can be used for `tips` with objects in `db`.
+    ///
+    /// Tips of the snapshot may be removed as long as they are commits reachable from the remaining or new `tips`, as is the case
+    /// when branches are fast-forwarded. The snapshot is stale if a removed tip isn't reachable anymore, if too many tips were added
+    /// as configured in `options`, or if a pack any of its counts refers to was removed or changed its checksum.
+    /// Objects without pack location remain valid as long as they exist, which is assumed.
+    pub fn reuse<Find>(self, tips: impl IntoIterator<Item = ObjectId>, db: &Find, options: Options) -> Reuse
+    where
+        Find: crate::Find,
+    {
+        let tips: BTreeSet<_> = tips.into_iter().collect();
+        let previous_tips: BTreeSet<_> = self.tips.into_iter().collect();
+        let new_tips: Vec<_> = tips.difference(&previous_tips).cloned().collect();
+        if new_tips.len() > options.max_new_tips {
+            return Reuse::Stale(Staleness::TooManyNewTips(new_tips.len()));
+        }
+
+        for pack_id in pack_ids(&self.counts) {
+            if db.pack_checksum(pack_id).as_ref() != self.packs.get(&pack_id) {
+                return Reuse::Stale(Staleness::PackChanged { pack_id });
+            }
+        }
+
+        let removed_tips: BTreeSet<_> = previous_tips.difference(&tips).cloned().collect();
+        if let Some(removed) = first_unreachable(removed_tips, &tips, db) {
+            return Reuse::Stale(Staleness::TipRemoved(removed));
+        }
+
+        if new_tips.is_empty() {
+            Reuse::Complete(self.counts)
+        } else {
+            Reuse::Partial {
+                counts: self.counts,
+                new_tips,
+            }
+        }
+    }
+}
+
+fn pack_ids(counts: &[output::Count]) -> BTreeSet<u32> {
+    counts
+        .iter()
+        .filter_map(|c| c.entry_pack_location.as_ref().map(|l| l.pack_id))
+        .collect()
+}
+
+/// Return the first of the `removed` tips that isn't a commit reachable from `tips`, whose tags are peeled to start the traversal.
+fn first_unreachable<Find>(mut removed: BTreeSet<ObjectId>, tips: &BTreeSet<ObjectId>, db: &Find) -> Option<ObjectId>
+where
+    Find: crate::Find,
+{
+    if removed.is_empty() {
+        return None;
+    }
+    let mut buf = Vec::new();
+    let commits: Vec<_> = tips
+        .iter()
+        .filter_map(|tip| {
+            let mut id = *tip;
+            loop {
+                let obj = db.find_existing(id, &mut buf, &mut crate::cache::Never).ok()?;
+                match obj.kind {
+                    git_object::Kind::Commit => return Some(id),
+                    git_object::Kind::Tag => id = obj.into_tag_iter()?.target_id()?,
+                    git_object::Kind::Tree | git_object::Kind::Blob => return None,
+                }
+            }
+        })
+        .collect();
+    for id in commit::Ancestors::new(commits, commit::ancestors::State::default(), |oid, buf| {
+        db.find_existing_commit_iter(oid, buf, &mut crate::cache::Never).ok()
+    }) {
+        match id {
+            Ok(id) => {
+                removed.remove(&id);
+                if removed.is_empty() {
+                    return None;
+                }
+            }
+            Err(_) => break,
+        }
+    }
+    removed.into_iter().next()
+}
@@ -14,8 +14,12 @@ use std::sync::Arc;
 ///
 /// * `counts`
 ///   * A list of previously counted objects to add to the pack. Duplication checks are not performed, no object is expected to be duplicated.
+/// * `order`
+///   * a way to reorder `counts` and to partition them before they are split into chunks, see [`Order`] for details.
+///     Use [`InputOrder`] to process `counts` as given.
 /// * `progress`
-///   * a way to obtain progress information
+///   * a way to obtain progress information, initialized with the amount of `counts` to process and advanced as chunks of
+///     entries are produced, along with a child tracking the amount of compressed bytes.
 /// * `options`
 ///   * more configuration
 ///
@@ -36,10 +40,11 @@ use std::sync::Arc;
 ///  or keeping enough state to write a pack and then generate an index with recorded data.
 ///
 pub fn from_counts_iter<Find, Cache>(
-    counts: Vec<output::Count>,
+    mut counts: Vec<output::Count>,
+    order: impl Order,
     db: Find,
     make_cache: impl Fn() -> Cache + Send + Clone + Sync + 'static,
-    progress: impl Progress,
+    mut progress: impl Progress,
     Options {
         version,
         thread_limit,
@@ -56,32 +61,31 @@ where
         matches!(version, crate::data::Version::V2),
         "currently we can only write version 2"
     );
+    let partitions = order.order(&mut counts);
     let counts = Arc::new(counts);
     let (chunk_size, thread_limit, _) =
         parallel::optimize_chunk_size_and_thread_limit(chunk_size, Some(counts.len()), thread_limit, None);
-    let chunks = util::Chunks::new(chunk_size, counts.len()).enumerate();
-    let progress = Arc::new(parking_lot::Mutex::new(progress));
+    let chunks = util::Chunks::new(chunk_size, partitions, counts.len()).enumerate();
+    progress.init(Some(counts.len()), git_features::progress::count("objects"));
+    let mut compressed = progress.add_child("compressed");
+    compressed.init(None, git_features::progress::bytes());
+    let progress = Arc::new(parking_lot::Mutex::new((progress, compressed)));
 
     parallel::reduce::Stepwise::new(
         chunks,
         thread_limit,
-        {
-            let progress = Arc::clone(&progress);
-            move |n| {
-                (
-                    Vec::new(),   // object data buffer
-                    make_cache(), // cache to speed up pack operations
-                    progress.lock().add_child(format!("thread {}", n)),
-                )
-            }
+        move |_n| {
+            (
+                Vec::new(),   // object data buffer
+                make_cache(), // cache to speed up pack operations
+            )
         },
         {
             let counts = Arc::clone(&counts);
-            move |(chunk_id, chunk): (ChunkId, std::ops::Range<usize>), (buf, cache, progress)| {
+            move |(chunk_id, chunk): (ChunkId, std::ops::Range<usize>), (buf, cache)| {
                 let mut out = Vec::new();
                 let chunk = &counts[chunk];
                 let mut stats = Outcome::default();
-                progress.init(Some(chunk.len()), git_features::progress::count("objects"));
 
                 for count in chunk {
                     out.push(
@@ -104,7 +108,11 @@ where
                             }
                         }?,
                     );
-                    progress.inc();
+                }
+                {
+                    let (objects, compressed) = &mut *progress.lock();
+                    objects.inc_by(chunk.len());
+                    compressed.inc_by(out.iter().map(|e| e.compressed_data.len()).sum());
                 }
                 Ok((chunk_id, out, stats))
             }
@@ -117,14 +125,25 @@ mod util {
     pub struct Chunks {
         cursor: usize,
         size: usize,
+        partition_ends: std::vec::IntoIter<usize>,
+        partition_end: usize,
         len: usize,
     }
 
     impl Chunks {
-        pub fn new(size: usize, total: usize) -> Self {
+        /// Note that `partition_ends` must be ascending and not exceed `total`, with an implied partition
+        /// ending at `total`.
+        pub fn new(size: usize, partition_ends: Vec<usize>, total: usize) -> Self {
+            assert!(
+                partition_ends.windows(2).all(|w| w[0] <= w[1]) && partition_ends.last().map_or(true, |e| *e <= total),
+                "partition ends must be ascending and within the amount of counts"
+            );
+            let mut partition_ends = partition_ends.into_iter();
             Chunks {
                 cursor: 0,
                 size,
+                partition_end: partition_ends.next().unwrap_or(total),
+                partition_ends,
                 len: total,
             }
         }
@@ -134,10 +153,13 @@ mod util {
         type Item = std::ops::Range<usize>;
 
         fn next(&mut self) -> Option<Self::Item> {
+            while self.cursor >= self.partition_end && self.partition_end < self.len {
+                self.partition_end = self.partition_ends.next().unwrap_or(self.len);
+            }
             if self.cursor >= self.len {
                 None
             } else {
-                let upper = (self.cursor + self.size).min(self.len);
+                let upper = (self.cursor + self.size).min(self.partition_end);
                 let range = self.cursor..upper;
                 self.cursor = upper;
                 Some(range)
@@ -186,7 +208,40 @@ mod reduce {
 }
 
 mod types {
-    use crate::data::output::entry;
+    use crate::data::output::{self, entry};
+
+    /// Determine the order in which counts are turned into entries, and how they are partitioned, before they are chunked
+    /// for processing by [`from_counts_iter()`][super::from_counts_iter()].
+    ///
+    /// The order of objects in a pack strongly affects the quality of deltas as well as IO patterns when reading it, hence
+    /// it's common to place commits first, followed by trees and then by blobs grouped by the hash of their path.
+    pub trait Order {
+        /// Reorder `counts` in place and return the exclusive end index of each partition, which must be ascending and
+        /// no larger than `counts.len()`. All counts past the last returned index form the final partition.
+        ///
+        /// Chunks of work never span more than one partition, which allows objects of similar kind to be processed together.
+        /// Return an empty `Vec` to treat all counts as a single partition.
+        fn order(self, counts: &mut Vec<output::Count>) -> Vec<usize>;
+    }
+
+    impl<F> Order for F
+    where
+        F: FnOnce(&mut Vec<output::Count>) -> Vec<usize>,
+    {
+        fn order(self, counts: &mut Vec<output::Count>) -> Vec<usize> {
+            self(counts)
+        }
+    }
+
+    /// An [`Order`] which keeps counts as they are provided, in a single partition.
+    #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+    pub struct InputOrder;
+
+    impl Order for InputOrder {
+        fn order(self, _counts: &mut Vec<output::Count>) -> Vec<usize> {
+            Vec::new()
+        }
+    }
 
     /// Information gathered during the run of [`from_counts_iter()`][super::from_counts_iter()].
     #[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -248,4 +303,4 @@ mod types {
         NewEntry(#[from] entry::Error),
     }
 }
-pub use types::{Error, Options, Outcome};
+pub use types::{Error, InputOrder, Options, Order, Outcome};
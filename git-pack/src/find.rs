@@ -31,6 +31,12 @@ pub trait Find {
     /// _Note_ that the object database may have no notion of packs and thus always returns `None`.
     fn location_by_id(&self, id: impl AsRef<git_hash::oid>, buf: &mut Vec<u8>) -> Option<crate::bundle::Location>;
 
+    /// Return the checksum of the pack with `pack_id` as seen in [`Location::pack_id`][crate::bundle::Location::pack_id],
+    /// or `None` if there is no such pack.
+    ///
+    /// _Note_ that the object database may have no notion of packs and thus always returns `None`.
+    fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId>;
+
     /// Return the [`Entry`] for `location` if it is backed by a pack.
     ///
     /// Note that this is only in the interest of avoiding duplicate work during pack generation.
@@ -243,6 +249,10 @@ mod find_impls {
             self.deref().location_by_id(id, buf)
         }
 
+        fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId> {
+            self.deref().pack_checksum(pack_id)
+        }
+
         fn entry_by_location(&self, object: &crate::bundle::Location) -> Option<Entry<'_>> {
             self.deref().entry_by_location(object)
         }
@@ -267,6 +277,10 @@ mod find_impls {
             self.deref().location_by_id(id, buf)
         }
 
+        fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId> {
+            self.deref().pack_checksum(pack_id)
+        }
+
         fn entry_by_location(&self, location: &crate::bundle::Location) -> Option<Entry<'_>> {
             self.deref().entry_by_location(location)
         }
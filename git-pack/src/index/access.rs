@@ -120,6 +120,37 @@ impl index::File {
         }
     }
 
+    /// Returns the [`Entry`] at the given `index`, including its CRC32 if available in this index version.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn entry_at_index(&self, index: u32) -> Entry {
+        Entry {
+            oid: self.oid_at_index(index).to_owned(),
+            pack_offset: self.pack_offset_at_index(index),
+            crc32: self.crc32_at_index(index),
+        }
+    }
+
+    /// Returns the offset of the entry following the one at `index` in pack order, or `None` if it is the last entry
+    /// in the pack, which is followed by the pack trailer instead.
+    ///
+    /// The difference between both offsets is the size of the compressed entry including its header, which allows to obtain
+    /// it from the pack without decompressing it. The offsets in pack order are computed on first use and cached.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn next_pack_offset_at_index(&self, index: u32) -> Option<PackOffset> {
+        let pack_offset = self.pack_offset_at_index(index);
+        let offsets = self.offsets_in_pack_order.get_or_init(|| self.sorted_offsets());
+        let pos = offsets
+            .binary_search(&pack_offset)
+            .expect("every offset in the index to be contained in the sorted offsets");
+        offsets.get(pos + 1).copied()
+    }
+
     /// Returns the `index` of the given SHA1 for use with the [`oid_at_index()`][index::File::oid_at_index()],
     /// [`pack_offset_at_index()`][index::File::pack_offset_at_index()] or [`crc32_at_index()`][index::File::crc32_at_index()].
     pub fn lookup(&self, id: impl AsRef<git_hash::oid>) -> Option<u32> {
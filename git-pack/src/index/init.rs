@@ -77,6 +77,7 @@ impl TryFrom<&Path> for index::File {
             version: kind,
             num_objects,
             fan,
+            offsets_in_pack_order: Default::default(),
         })
     }
 }
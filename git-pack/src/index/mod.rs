@@ -108,6 +108,8 @@ pub struct File {
     version: Version,
     num_objects: u32,
     fan: [u32; FAN_LEN],
+    /// Pack offsets in ascending order, lazily computed to determine the size of entries in the pack.
+    offsets_in_pack_order: once_cell::sync::OnceCell<Vec<access::PackOffset>>,
 }
 
 /// Basic file information
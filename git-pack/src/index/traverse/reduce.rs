@@ -1,5 +1,8 @@
 use crate::{data, index::traverse};
-use git_features::{parallel, progress::Progress};
+use git_features::{
+    parallel,
+    progress::{self, Progress},
+};
 use std::{
     sync::atomic::{AtomicBool, Ordering},
     time::Instant,
@@ -116,7 +119,7 @@ where
             self.entries_seen,
             elapsed_s,
             objects_per_second,
-            bytesize::ByteSize(self.stats.average.object_size * objects_per_second as u64)
+            progress::format::bytes(self.stats.average.object_size * objects_per_second as u64)
         ));
         Ok(self.stats)
     }
@@ -2,7 +2,7 @@ use super::{Error, Reducer};
 use crate::{data, index, index::util};
 use git_features::{
     parallel::{self, in_parallel_if},
-    progress::{self, unit, Progress},
+    progress::{self, Progress},
 };
 use std::sync::Arc;
 
@@ -21,6 +21,8 @@ mod options {
         /// A flag to indicate whether the algorithm should be interrupted. Will be checked occasionally allow stopping a running
         /// computation.
         pub should_interrupt: Arc<AtomicBool>,
+        /// If `true`, hint the operating system to read the pack data of the entries a thread is about to decode ahead of time.
+        pub prefetch: bool,
     }
 
     impl Default for Options {
@@ -29,6 +31,7 @@ mod options {
                 thread_limit: Default::default(),
                 check: Default::default(),
                 should_interrupt: Default::default(),
+                prefetch: true,
             }
         }
     }
@@ -52,6 +55,7 @@ impl index::File {
             thread_limit,
             check,
             should_interrupt,
+            prefetch,
         }: Options,
     ) -> Result<(git_hash::ObjectId, index::traverse::Outcome, P), Error<E>>
     where
@@ -114,13 +118,13 @@ impl index::File {
                     |entries: &[index::Entry],
                      (cache, ref mut processor, buf, progress)|
                      -> Result<Vec<data::decode_entry::Outcome>, Error<_>> {
-                        progress.init(
-                            Some(entries.len()),
-                            Some(unit::dynamic(unit::Human::new(
-                                unit::human::Formatter::new(),
-                                "objects",
-                            ))),
-                        );
+                        progress.init(Some(entries.len()), progress::count("objects"));
+                        if prefetch {
+                            if let (Some(first), Some(last)) = (entries.first(), entries.last()) {
+                                // The end of the last entry isn't known without decoding it, but the hint doesn't need to be exact.
+                                pack.prefetch(first.pack_offset..last.pack_offset + 1);
+                            }
+                        }
                         let mut stats = Vec::with_capacity(entries.len());
                         let mut header_buf = [0u8; 64];
                         for index_entry in entries.iter() {
@@ -7,7 +7,10 @@ use git_object::{
 };
 
 use crate::index;
-use std::{sync::atomic::AtomicBool, sync::Arc};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 /// Returned by [`index::File::verify_checksum()`]
 #[derive(thiserror::Error, Debug)]
@@ -33,6 +36,20 @@ pub enum Error {
     },
     #[error(transparent)]
     ObjectEncode(#[from] std::io::Error),
+    #[error("Object {id} is not contained in the index")]
+    ObjectNotFound { id: git_hash::ObjectId },
+}
+
+/// An entry which failed verification, identified by its object id and its offset into the pack data file.
+///
+/// Persist it to re-verify only this entry using [`index::File::verify_entries()`], for instance after attempting a repair.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Failure {
+    /// The id of the object which failed verification.
+    pub id: git_hash::ObjectId,
+    /// The offset of the object's entry in the pack data file.
+    pub pack_offset: u64,
 }
 
 /// Various ways in which a pack and index can be verified
@@ -141,18 +158,14 @@ impl index::File {
                 .traverse(
                     pack,
                     root.into_inner(),
-                    || {
-                        let mut encode_buf = Vec::with_capacity(2048);
-                        move |kind, data, index_entry, progress| {
-                            Self::verify_entry(mode, &mut encode_buf, kind, data, index_entry, progress)
-                        }
-                    },
+                    || Self::new_verify_processor(mode),
                     make_cache,
                     index::traverse::Options {
                         algorithm,
                         thread_limit,
                         check: index::traverse::SafetyCheck::All,
                         should_interrupt,
+                        prefetch: true,
                     },
                 )
                 .map(|(id, outcome, root)| (id, Some(outcome), root)),
@@ -163,6 +176,136 @@ impl index::File {
         }
     }
 
+    /// Find the first entry of `pack` which fails verification in the given `mode`, or `None` if all of them are intact.
+    ///
+    /// Unlike [`verify_integrity()`][index::File::verify_integrity()], checksums of the pack and index files are not verified, which
+    /// allows to find the entry responsible for a pack checksum mismatch that [`failure_of()`][index::File::failure_of()] can't
+    /// determine. Errors which don't concern a single entry are returned as such.
+    ///
+    /// The traversal always uses [`Algorithm::Lookup`][index::traverse::Algorithm::Lookup] as only it associates all errors with
+    /// the entry that caused them.
+    pub fn locate_failure<C, P>(
+        &self,
+        pack: &crate::data::File,
+        mode: Mode,
+        make_cache: impl Fn() -> C + Send + Sync,
+        thread_limit: Option<usize>,
+        progress: Option<P>,
+        should_interrupt: Arc<AtomicBool>,
+    ) -> Result<Option<Failure>, index::traverse::Error<Error>>
+    where
+        P: Progress,
+        C: crate::cache::DecodeEntry,
+    {
+        let res = self.traverse(
+            pack,
+            progress,
+            || Self::new_verify_processor(mode),
+            make_cache,
+            index::traverse::Options {
+                algorithm: index::traverse::Algorithm::Lookup,
+                thread_limit,
+                check: index::traverse::SafetyCheck::SkipFileChecksumVerification,
+                should_interrupt,
+                prefetch: true,
+            },
+        );
+        match res {
+            Ok(_) => Ok(None),
+            Err(err) => self.failure_of(&err).map(Some).ok_or(err),
+        }
+    }
+
+    /// Return the entry responsible for `err` as returned by [`verify_integrity()`][index::File::verify_integrity()],
+    /// or `None` if the error doesn't concern a single entry, like mismatching checksums of entire files.
+    ///
+    /// As verification stops at the first failing entry, it is the only one known to be faulty.
+    pub fn failure_of(&self, err: &index::traverse::Error<Error>) -> Option<Failure> {
+        use index::traverse::Error::*;
+        let (id, pack_offset) = match err {
+            PackDecode { id, offset, .. } => (*id, *offset),
+            PackObjectMismatch { expected, offset, .. } => (*expected, *offset),
+            Crc32Mismatch { offset, .. } => {
+                let entry = self.iter().find(|entry| entry.pack_offset == *offset)?;
+                (entry.oid, *offset)
+            }
+            Processor(Error::ObjectDecode { id, .. }) | Processor(Error::ObjectEncodeMismatch { id, .. }) => {
+                (*id, self.pack_offset_at_index(self.lookup(id)?))
+            }
+            _ => return None,
+        };
+        Some(Failure { id, pack_offset })
+    }
+
+    /// Verify only the given `entries` of `pack` in the given `mode`, and return those which still fail verification along
+    /// with the reason, in order of their appearance in `entries`.
+    ///
+    /// This is useful to quickly re-verify [failed entries][index::File::failure_of()] without traversing the entire pack again.
+    /// Each entry is looked up by its object id, and it's considered failing if it isn't contained in this index.
+    /// `cache` is used to decode entries, see [`crate::cache`] for available implementations.
+    pub fn verify_entries<C, P>(
+        &self,
+        pack: &crate::data::File,
+        entries: impl IntoIterator<Item = Failure>,
+        mode: Mode,
+        mut cache: C,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<(Failure, index::traverse::Error<Error>)>, index::traverse::Error<Error>>
+    where
+        C: crate::cache::DecodeEntry,
+        P: Progress,
+    {
+        if self.pack_checksum() != pack.checksum() {
+            return Err(index::traverse::Error::PackMismatch {
+                actual: pack.checksum(),
+                expected: self.pack_checksum(),
+            });
+        }
+        let entries: Vec<_> = entries.into_iter().collect();
+        progress.init(Some(entries.len()), progress::count("entries"));
+        let (mut buf, mut header_buf, mut processor) =
+            (Vec::with_capacity(4096), [0u8; 64], Self::new_verify_processor(mode));
+        let mut failures = Vec::new();
+        for failure in entries {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(index::traverse::Error::Interrupted);
+            }
+            let res = match self.lookup(failure.id) {
+                Some(index) => self
+                    .decode_and_process_entry(
+                        index::traverse::SafetyCheck::All,
+                        pack,
+                        &mut cache,
+                        &mut buf,
+                        &mut progress,
+                        &mut header_buf,
+                        &self.entry_at_index(index),
+                        &mut processor,
+                    )
+                    .map(|_| ()),
+                None => Err(Error::ObjectNotFound { id: failure.id }.into()),
+            };
+            if let Err(err) = res {
+                failures.push((failure, err));
+            }
+            progress.inc();
+        }
+        Ok(failures)
+    }
+
+    fn new_verify_processor<P>(
+        mode: Mode,
+    ) -> impl FnMut(git_object::Kind, &[u8], &index::Entry, &mut P) -> Result<(), Error>
+    where
+        P: Progress,
+    {
+        let mut encode_buf = Vec::with_capacity(2048);
+        move |kind, data, index_entry, progress| {
+            Self::verify_entry(mode, &mut encode_buf, kind, data, index_entry, progress)
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn verify_entry<P>(
         mode: Mode,
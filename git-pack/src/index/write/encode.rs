@@ -4,20 +4,18 @@ use git_features::{
     hash,
     progress::{self, Progress},
 };
-use std::{cmp::Ordering, collections::VecDeque, io};
+use std::{cmp::Ordering, io};
 
+/// Write an index file for the pack with `pack_hash` containing `entries_sorted_by_oid`, each being a tuple of
+/// object id, crc32 of the pack entry and its offset in the pack.
 pub(crate) fn write_to(
     out: impl io::Write,
-    entries_sorted_by_oid: VecDeque<crate::tree::Item<crate::index::write::TreeEntry>>,
+    entries_sorted_by_oid: impl ExactSizeIterator<Item = (git_hash::ObjectId, u32, u64)> + Clone,
     pack_hash: &git_hash::ObjectId,
     kind: crate::index::Version,
     mut progress: impl Progress,
 ) -> io::Result<git_hash::ObjectId> {
     use io::Write;
-    assert!(
-        !entries_sorted_by_oid.is_empty(),
-        "Empty packs do not exists, or so I think"
-    );
     assert_eq!(kind, crate::index::Version::V2, "Can only write V2 packs right now");
     assert!(
         entries_sorted_by_oid.len() <= u32::MAX as usize,
@@ -35,26 +33,27 @@ pub(crate) fn write_to(
     const LARGE_OFFSET_THRESHOLD: u64 = 0x7fff_ffff;
     const HIGH_BIT: u32 = 0x8000_0000;
 
-    let needs_64bit_offsets =
-        entries_sorted_by_oid.back().expect("at least one pack entry").offset > LARGE_OFFSET_THRESHOLD;
+    let needs_64bit_offsets = entries_sorted_by_oid
+        .clone()
+        .any(|(_, _, offset)| offset > LARGE_OFFSET_THRESHOLD);
     let mut fan_out_be = [0u32; 256];
     progress.init(Some(4), progress::steps());
     let start = std::time::Instant::now();
     let _info = progress.add_child("generating fan-out table");
 
     {
-        let mut iter = entries_sorted_by_oid.iter().enumerate();
+        let mut iter = entries_sorted_by_oid.clone().enumerate();
         let mut idx_and_entry = iter.next();
         let mut upper_bound = 0;
         let entries_len = entries_sorted_by_oid.len() as u32;
 
         for (offset_be, byte) in fan_out_be.iter_mut().zip(0u8..=255) {
             *offset_be = match idx_and_entry.as_ref() {
-                Some((_idx, entry)) => match entry.data.id.as_slice()[0].cmp(&byte) {
+                Some((_idx, (id, _, _))) => match id.as_slice()[0].cmp(&byte) {
                     Ordering::Less => unreachable!("ids should be ordered, and we make sure to keep ahead with them"),
                     Ordering::Greater => upper_bound,
                     Ordering::Equal => {
-                        idx_and_entry = iter.find(|(_, entry)| entry.data.id.as_slice()[0] != byte);
+                        idx_and_entry = iter.find(|(_, (id, _, _))| id.as_slice()[0] != byte);
                         upper_bound = match idx_and_entry.as_ref() {
                             Some((idx, _)) => *idx as u32,
                             None => entries_len,
@@ -74,30 +73,30 @@ pub(crate) fn write_to(
 
     progress.inc();
     let _info = progress.add_child("writing ids");
-    for entry in &entries_sorted_by_oid {
-        out.write_all(entry.data.id.as_slice())?;
+    for (id, _, _) in entries_sorted_by_oid.clone() {
+        out.write_all(id.as_slice())?;
     }
 
     progress.inc();
     let _info = progress.add_child("writing crc32");
-    for entry in &entries_sorted_by_oid {
-        out.write_u32::<BigEndian>(entry.data.crc32)?;
+    for (_, crc32, _) in entries_sorted_by_oid.clone() {
+        out.write_u32::<BigEndian>(crc32)?;
     }
 
     progress.inc();
     let _info = progress.add_child("writing offsets");
     {
         let mut offsets64_be = Vec::<u64>::new();
-        for entry in &entries_sorted_by_oid {
-            out.write_u32::<BigEndian>(if needs_64bit_offsets && entry.offset > LARGE_OFFSET_THRESHOLD {
+        for (_, _, offset) in entries_sorted_by_oid {
+            out.write_u32::<BigEndian>(if needs_64bit_offsets && offset > LARGE_OFFSET_THRESHOLD {
                 assert!(
                     offsets64_be.len() < LARGE_OFFSET_THRESHOLD as usize,
                     "Encoding breakdown - way too many 64bit offsets"
                 );
-                offsets64_be.push(entry.offset.to_be());
+                offsets64_be.push(offset.to_be());
                 ((offsets64_be.len() - 1) as u32) | HIGH_BIT
             } else {
-                entry.offset as u32
+                offset as u32
             })?;
         }
         if needs_64bit_offsets {
@@ -5,7 +5,7 @@ use crate::{
 use git_features::progress::{self, Progress};
 use std::{convert::TryInto, io, sync::atomic::AtomicBool};
 
-mod encode;
+pub(crate) mod encode;
 mod error;
 pub use error::Error;
 
@@ -51,7 +51,8 @@ impl crate::index::File {
     ///
     /// # Remarks
     ///
-    /// * neither in-pack nor out-of-pack Ref Deltas are supported here, these must have been resolved beforehand.
+    /// * neither in-pack nor out-of-pack Ref Deltas are supported here, these must have been resolved beforehand, for example
+    /// with a [`LookupRefDeltaObjectsIter`][crate::data::input::LookupRefDeltaObjectsIter].
     /// * `make_resolver()` will only be called after the iterator stopped returning elements and produces a function that
     /// provides all bytes belonging to a pack entry writing them to the given mutable output `Vec`.
     /// It should return `None` if the entry cannot be resolved from the pack that produced the `entries` iterator, causing
@@ -142,7 +143,8 @@ impl crate::index::File {
             num_objects += 1;
             objects_progress.inc();
         }
-        if num_objects != anticipated_num_objects {
+        // thin packs that were resolved on the fly may have more objects than anticipated
+        if num_objects < anticipated_num_objects {
             objects_progress.info(format!(
                 "Recovered from pack streaming error, anticipated {} objects, got {}",
                 anticipated_num_objects, num_objects
@@ -197,7 +199,9 @@ impl crate::index::File {
         let pack_hash = last_seen_trailer.ok_or(Error::IteratorInvariantTrailer)?;
         let index_hash = encode::write_to(
             out,
-            sorted_pack_offsets_by_oid,
+            sorted_pack_offsets_by_oid
+                .iter()
+                .map(|item| (item.data.id, item.data.crc32, item.offset)),
             &pack_hash,
             kind,
             root_progress.add_child("writing index file"),
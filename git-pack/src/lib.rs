@@ -25,6 +25,8 @@ pub use find::{Find, FindExt};
 ///
 pub mod cache;
 ///
+pub mod connectivity;
+///
 pub mod data;
 ///
 pub mod index;
@@ -78,6 +78,23 @@ impl<T> Tree<T> {
         Ok(())
     }
 
+    /// Make room for more items if we are at capacity, which happens if more items are added than anticipated when
+    /// creating the tree, for example if bases of thin packs are inserted.
+    ///
+    /// This is done by hand as growing the deque may move its roots and children into the same contiguous slice.
+    fn reserve_one(&mut self) {
+        if self.items.len() < self.items.capacity() {
+            return;
+        }
+        let capacity = self.items.capacity().max(1) * 2;
+        let mut roots = std::mem::replace(&mut self.items, VecDeque::with_capacity(capacity));
+        let children = roots.split_off(self.roots);
+        self.items.extend(children);
+        for root in roots.into_iter().rev() {
+            self.items.push_front(root);
+        }
+    }
+
     fn set_pack_entries_end(&mut self, pack_entries_end: u64) {
         if !self.items.is_empty() {
             self.items[self.last_index].next_offset = pack_entries_end;
@@ -88,6 +105,7 @@ impl<T> Tree<T> {
     /// custom `data` with it.
     pub fn add_root(&mut self, offset: u64, data: T) -> Result<(), Error> {
         self.assert_is_incrementing(offset)?;
+        self.reserve_one();
         self.last_index = 0;
         self.items.push_front(Item {
             offset,
@@ -102,6 +120,7 @@ impl<T> Tree<T> {
     /// Add a child of the item at `base_offset` which itself resides at pack `offset` and associate custom `data` with it.
     pub fn add_child(&mut self, base_offset: u64, offset: u64, data: T) -> Result<(), Error> {
         self.assert_is_incrementing(offset)?;
+        self.reserve_one();
         let (roots, children) = self.items.as_mut_slices();
         assert_eq!(
             roots.len(),
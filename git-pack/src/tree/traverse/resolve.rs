@@ -1,5 +1,5 @@
 use crate::{data::EntryRange, tree::traverse::Context, tree::traverse::Error};
-use git_features::progress::{unit, Progress};
+use git_features::progress::{self, Progress};
 use git_features::zlib;
 use std::{cell::RefCell, collections::BTreeMap};
 
@@ -33,13 +33,7 @@ where
     };
 
     // Traverse the tree breadth first and loose the data produced for the base as it won't be needed anymore.
-    progress.init(
-        None,
-        Some(unit::dynamic(unit::Human::new(
-            unit::human::Formatter::new(),
-            "objects",
-        ))),
-    );
+    progress.init(None, progress::count("objects"));
 
     // each node is a base, and its children always start out as deltas which become a base after applying them.
     // These will be pushed onto our stack until all are processed
@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+
+mkdir x y
+for f in $(seq 30); do seq $f > x/$f; seq $((f+1)) > y/$f; done
+seq 1000 > a
+git add .
+git commit -qm "bases"
+
+seq 1001 > a
+(seq 1001; echo c) > c
+echo change >> x/1
+echo change >> y/1
+(seq 1001; echo y) > y/new
+git add .
+git commit -qm "deltas against the bases and each other"
+
+# a pack with only the objects of the last commit, with deltas against objects of the previous one which aren't part of it
+echo -e "HEAD\n^HEAD~1" | git pack-objects --thin --revs --stdout > thin.pack
@@ -0,0 +1,144 @@
+//! Validate packs generated by us with native `git`, which has to be installed and in `PATH`.
+//!
+//! These tests run only with the `internal-testing-native-git-interop` feature to keep the dependency on `git` optional.
+use std::{path::Path, process::Command, sync::Arc};
+
+use git_features::progress;
+use git_odb::{compound, linked, pack, FindExt};
+use git_pack::data::output::{self, bytes, count, entry};
+use git_testtools::hex_to_id;
+use git_traverse::commit;
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn db() -> Result<Arc<linked::Store>> {
+    let objects_dir = git_testtools::scripted_fixture_repo_read_only("make_pack_gen_repo.sh")?
+        .join(".git")
+        .join("objects");
+    Ok(linked::Store::at(objects_dir)?.into())
+}
+
+/// Write a pack with all objects reachable from the fixture's `HEAD` using `expansion` and `options` into `directory`,
+/// returning its path, its hash and the index we would write for it.
+fn write_pack(
+    db: &Arc<linked::Store>,
+    expansion: count::from_objects_iter::ObjectExpansion,
+    options: bytes::Options,
+    directory: &Path,
+) -> Result<(std::path::PathBuf, git_hash::ObjectId, Vec<u8>)> {
+    let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+    let commits = commit::Ancestors::new(Some(head), commit::ancestors::State::default(), {
+        let db = Arc::clone(db);
+        move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+    })
+    .map(std::result::Result::unwrap);
+    let counts: Vec<_> = output::count::from_objects_iter(
+        Arc::clone(db),
+        || pack::cache::Never,
+        commits,
+        progress::Discard,
+        count::from_objects_iter::Options {
+            input_object_expansion: expansion,
+            ..Default::default()
+        },
+    )
+    .collect::<std::result::Result<Vec<_>, _>>()?
+    .into_iter()
+    .flatten()
+    .collect();
+    let entries: Vec<_> = output::InOrderIter::from(output::entry::from_counts_iter(
+        counts,
+        output::entry::from_counts_iter::InputOrder,
+        Arc::clone(db),
+        || pack::cache::Never,
+        progress::Discard,
+        Default::default(),
+    ))
+    .collect::<std::result::Result<Vec<_>, _>>()?
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let pack_path = directory.join("generated.pack");
+    let num_entries = entries.len() as u32;
+    let mut writer = bytes::FromEntriesIter::new(
+        std::iter::once(Ok::<_, entry::from_counts_iter::Error<compound::find::Error>>(entries)),
+        std::fs::File::create(&pack_path)?,
+        num_entries,
+        pack::data::Version::V2,
+        git_hash::Kind::Sha1,
+        options,
+    )
+    .with_index();
+    for written in writer.by_ref() {
+        written?;
+    }
+    let pack_hash = writer.digest().expect("the pack was written completely");
+    let mut index = Vec::new();
+    writer.write_index(&mut index)?;
+    Ok((pack_path, pack_hash, index))
+}
+
+/// Run `git index-pack --strict` on the pack at `pack_path` within an empty repository, so that all objects and links
+/// have to be contained in the pack, and return the hash of the pack as seen by `git`.
+fn git_index_pack_strict(pack_path: &Path, directory: &Path) -> Result<git_hash::ObjectId> {
+    let repo = directory.join("repo.git");
+    run(Command::new("git").args(["init", "--bare", "-q"]).arg(&repo))?;
+    let stdout = run(Command::new("git")
+        .arg("--git-dir")
+        .arg(&repo)
+        .args(["index-pack", "--strict"])
+        .arg(pack_path))?;
+    Ok(git_hash::ObjectId::from_hex(stdout.trim().as_bytes())?)
+}
+
+fn run(cmd: &mut Command) -> Result<String> {
+    let output = cmd.output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "{:?} failed with {}: {}",
+            cmd,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn generated_packs_are_accepted_by_git_index_pack_strict() -> Result {
+    let db = db()?;
+    for expansion in &[
+        count::from_objects_iter::ObjectExpansion::TreeContents,
+        count::from_objects_iter::ObjectExpansion::TreeAdditionsComparedToAncestor,
+    ] {
+        for allow_ofs_delta in &[true, false] {
+            let tmp = tempfile::TempDir::new()?;
+            let (pack_path, pack_hash, index) = write_pack(
+                &db,
+                *expansion,
+                bytes::Options {
+                    allow_ofs_delta: *allow_ofs_delta,
+                    ..Default::default()
+                },
+                tmp.path(),
+            )?;
+            assert_eq!(
+                git_index_pack_strict(&pack_path, tmp.path())?,
+                pack_hash,
+                "git agrees on the pack hash with {:?} and ofs-delta = {}",
+                expansion,
+                allow_ofs_delta
+            );
+            assert_eq!(
+                std::fs::read(pack_path.with_extension("idx"))?,
+                index,
+                "the index written by git is the same as ours with {:?} and ofs-delta = {}",
+                expansion,
+                allow_ofs_delta
+            );
+        }
+    }
+    Ok(())
+}
@@ -29,6 +29,18 @@ mod locate {
                         .find(entry.oid, &mut buf, &mut pack::cache::Never)?
                         .expect("id present");
                     obj.verify_checksum(entry.oid)?;
+
+                    let index = bundle.index.lookup(entry.oid).expect("id present");
+                    let location = bundle.location_at_index(index);
+                    assert_eq!(
+                        Some(&location),
+                        obj.pack_location.as_ref(),
+                        "the entry size is the same whether obtained by decompression or by the next offset"
+                    );
+                    if let Some(expected) = entry.crc32 {
+                        let actual = bundle.pack.entry_crc32(entry.pack_offset, location.entry_size);
+                        assert_eq!(actual, expected, "the entry can be sliced without decompressing it");
+                    }
                 }
             }
             Ok(())
@@ -72,12 +84,47 @@ mod locate {
     }
 }
 
+mod iter_entries_with_data {
+    use crate::{fixture_path, pack::PACKS_AND_INDICES};
+    use git_odb::pack;
+
+    #[test]
+    fn yields_all_entries_in_index_order_with_their_data() -> Result<(), Box<dyn std::error::Error>> {
+        for (index_path, _) in PACKS_AND_INDICES {
+            let bundle = pack::Bundle::at(fixture_path(index_path))?;
+            let iter = bundle.iter_entries_with_data(pack::cache::Never);
+            assert_eq!(iter.size_hint().0, bundle.index.num_objects() as usize);
+
+            let mut buf = Vec::new();
+            let mut num_entries = 0;
+            for (entry, index_entry) in iter.zip(bundle.index.iter()) {
+                let entry = entry?;
+                assert_eq!(entry.index_entry, index_entry, "entries are in index order");
+                let obj = bundle
+                    .find(index_entry.oid, &mut buf, &mut pack::cache::Never)?
+                    .expect("id present");
+                assert_eq!(entry.data, obj.data);
+                assert_eq!(entry.decode.kind, obj.kind);
+                assert_eq!(Some(&entry.location), obj.pack_location.as_ref());
+                assert_eq!(
+                    entry.header.is_delta(),
+                    entry.decode.num_deltas > 0,
+                    "without cache, only deltas need their chain to be resolved"
+                );
+                num_entries += 1;
+            }
+            assert_eq!(num_entries, bundle.index.num_objects());
+        }
+        Ok(())
+    }
+}
+
 mod write_to_directory {
     use crate::{fixture_path, pack::SMALL_PACK, pack::SMALL_PACK_INDEX};
     use git_features::progress;
     use git_odb::pack;
     use std::sync::atomic::AtomicBool;
-    use std::{fs, path::Path};
+    use std::{convert::TryInto, fs, path::Path};
     use tempfile::TempDir;
 
     fn expected_outcome() -> Result<pack::bundle::write::Outcome, Box<dyn std::error::Error>> {
@@ -128,6 +175,162 @@ mod write_to_directory {
         Ok(())
     }
 
+    #[test]
+    fn thin_pack_with_base_object_lookup() -> Result<(), Box<dyn std::error::Error>> {
+        let repo = crate::scripted_fixture_repo_read_only("make_thin_pack.sh")?;
+        let thin_pack = fs::read(repo.join("thin.pack"))?;
+        let should_interrupt = AtomicBool::new(false);
+        assert!(
+            pack::Bundle::write_to_directory(
+                thin_pack.as_slice(),
+                None::<&Path>,
+                progress::Discard,
+                &should_interrupt,
+                None,
+                Default::default(),
+            )
+            .is_err(),
+            "thin packs can't be completed without a way to look up the missing bases"
+        );
+
+        let odb = git_odb::compound::Store::at(repo.join(".git").join("objects"))?;
+        let dir = TempDir::new()?;
+        let res = pack::Bundle::write_to_directory(
+            thin_pack.as_slice(),
+            Some(&dir),
+            progress::Discard,
+            &should_interrupt,
+            Some(Box::new(move |id, buf| {
+                odb.find(id, buf, &mut pack::cache::Never).ok().flatten()
+            })),
+            Default::default(),
+        )?;
+
+        let (_, num_thin_pack_objects) = pack::data::header::decode(&thin_pack[..12].try_into()?)?;
+        assert!(
+            res.index.num_objects > num_thin_pack_objects,
+            "the missing bases were added to the pack"
+        );
+        assert_ne!(
+            res.index.data_hash.as_slice(),
+            &thin_pack[thin_pack.len() - 20..],
+            "the pack changed and so did its trailer"
+        );
+        let bundle = res.to_bundle().expect("a directory was given")?;
+        assert_eq!(
+            bundle.pack.num_objects(),
+            res.index.num_objects,
+            "the header was updated"
+        );
+        bundle.verify_integrity(
+            pack::index::verify::Mode::Sha1Crc32DecodeEncode,
+            pack::index::traverse::Algorithm::DeltaTreeLookup,
+            || pack::cache::Never,
+            None,
+            progress::Discard.into(),
+            std::sync::Arc::new(should_interrupt),
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn index_for_memory_mapped_pack_file() -> Result<(), Box<dyn std::error::Error>> {
+        let should_interrupt = AtomicBool::new(false);
+        let index_for = |directory: Option<&Path>, iteration_mode| {
+            pack::Bundle::write_index_for_pack_file(
+                fixture_path(SMALL_PACK),
+                directory,
+                progress::Discard,
+                &should_interrupt,
+                pack::bundle::write::Options {
+                    iteration_mode,
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(index_for(None, pack::data::input::Mode::Verify)?, expected_outcome()?);
+        assert!(
+            matches!(
+                index_for(None, pack::data::input::Mode::Restore),
+                Err(pack::bundle::write::Error::UnsupportedIterationMode(_))
+            ),
+            "the pack can't be rewritten"
+        );
+
+        let dir = TempDir::new()?;
+        let mut res = index_for(Some(dir.path()), pack::data::input::Mode::AsIs)?;
+        assert!(res.to_bundle().transpose()?.is_some());
+        let pack_hash = res.index.data_hash.to_sha1_hex_string();
+        assert_eq!(
+            res.index_path.take(),
+            Some(dir.path().join(format!("{}.idx", pack_hash)))
+        );
+        let data_path = res.data_path.take().expect("directory was given");
+        assert_eq!(data_path, dir.path().join(format!("{}.pack", pack_hash)));
+        assert_eq!(
+            fs::read(data_path)?,
+            fs::read(fixture_path(SMALL_PACK))?,
+            "the pack was copied"
+        );
+        assert_eq!(res, expected_outcome()?);
+        Ok(())
+    }
+
+    #[cfg(feature = "async-io")]
+    #[test]
+    fn from_an_async_reader() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let pack = futures_lite::io::Cursor::new(fs::read(fixture_path(SMALL_PACK))?);
+        let mut res = futures_lite::future::block_on(pack::Bundle::write_to_directory_async(
+            pack,
+            Some(dir.path().to_owned()),
+            progress::Discard,
+            Default::default(),
+            None,
+            Default::default(),
+        ))?;
+        assert!(res.to_bundle().transpose()?.is_some());
+        assert!(res.data_path.take().is_some());
+        assert!(res.index_path.take().is_some());
+        assert_eq!(res, expected_outcome()?);
+        Ok(())
+    }
+
+    #[cfg(feature = "async-io")]
+    #[test]
+    fn from_an_async_reader_that_fails() {
+        use futures_lite::AsyncReadExt;
+        let pack = futures_lite::io::Cursor::new(std::fs::read(fixture_path(SMALL_PACK)).unwrap())
+            .take(1024)
+            .chain(FailingReader);
+        let res = futures_lite::future::block_on(pack::Bundle::write_to_directory_async(
+            pack,
+            None::<std::path::PathBuf>,
+            progress::Discard,
+            Default::default(),
+            None,
+            Default::default(),
+        ));
+        assert!(
+            res.is_err(),
+            "the read error is forwarded to the indexer which fails with it"
+        );
+    }
+
+    #[cfg(feature = "async-io")]
+    struct FailingReader;
+
+    #[cfg(feature = "async-io")]
+    impl futures_lite::AsyncRead for FailingReader {
+        fn poll_read(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            _buf: &mut [u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            std::task::Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::Other, "broken pipe")))
+        }
+    }
+
     fn file_name(entry: &fs::DirEntry) -> String {
         entry.path().file_name().unwrap().to_str().unwrap().to_owned()
     }
@@ -144,12 +347,65 @@ mod write_to_directory {
             directory,
             progress::Discard,
             &SHOULD_INTERRUPT,
+            None,
             pack::bundle::write::Options {
                 thread_limit: None,
                 iteration_mode: pack::data::input::Mode::Verify,
                 index_kind: pack::index::Version::V2,
+                object_hash: git_hash::Kind::Sha1,
             },
         )
         .map_err(Into::into)
     }
 }
+
+mod sanity_check {
+    use crate::{
+        fixture_path,
+        pack::{INDEX_V2, PACKS_AND_INDICES, PACK_FOR_INDEX_V1, PACK_FOR_INDEX_V2},
+    };
+    use git_odb::pack;
+
+    fn bundle_with(
+        index_path: &str,
+        pack_data: Vec<u8>,
+    ) -> Result<(tempfile::TempDir, pack::Bundle), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let index_destination = dir.path().join("pack-bundle.idx");
+        std::fs::copy(fixture_path(index_path), &index_destination)?;
+        std::fs::write(index_destination.with_extension("pack"), pack_data)?;
+        let bundle = pack::Bundle::at(index_destination)?;
+        Ok((dir, bundle))
+    }
+
+    #[test]
+    fn matching_pairs_pass() -> Result<(), Box<dyn std::error::Error>> {
+        for (index_path, _) in PACKS_AND_INDICES {
+            pack::Bundle::at(fixture_path(index_path))?.sanity_check()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn mixed_up_pairs_are_detected() -> Result<(), Box<dyn std::error::Error>> {
+        let (_keep, bundle) = bundle_with(INDEX_V2, std::fs::read(fixture_path(PACK_FOR_INDEX_V1))?)?;
+        assert!(matches!(
+            bundle.sanity_check(),
+            Err(pack::bundle::Error::PackChecksumMismatch { expected, actual, .. })
+                if expected == bundle.index.pack_checksum() && actual == bundle.pack.checksum()
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn object_count_mismatches_are_detected() -> Result<(), Box<dyn std::error::Error>> {
+        let mut data = std::fs::read(fixture_path(PACK_FOR_INDEX_V2))?;
+        data[11] = data[11].wrapping_add(1);
+        let (_keep, bundle) = bundle_with(INDEX_V2, data)?;
+        assert!(matches!(
+            bundle.sanity_check(),
+            Err(pack::bundle::Error::ObjectCountMismatch { expected, actual, .. }) if expected + 1 == actual
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{fixture_path, hex_to_id, pack::SMALL_PACK_INDEX};
+use git_odb::pack;
+use git_pack::connectivity;
+use std::sync::atomic::AtomicBool;
+
+const TIP: &str = "779c5451ba9fe210ffd1f55db202e55f51acecac";
+
+fn bundle() -> pack::Bundle {
+    pack::Bundle::at(fixture_path(SMALL_PACK_INDEX)).expect("pack and idx")
+}
+
+/// A database without the object with id `hidden`.
+struct Without {
+    db: pack::Bundle,
+    hidden: git_hash::ObjectId,
+}
+
+impl git_pack::Find for Without {
+    type Error = git_pack::data::decode_entry::Error;
+
+    fn find<'a>(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<git_pack::data::Object<'a>>, Self::Error> {
+        if id.as_ref() == self.hidden {
+            return Ok(None);
+        }
+        self.db.find(id, buffer, pack_cache)
+    }
+
+    fn location_by_id(&self, id: impl AsRef<git_hash::oid>, buf: &mut Vec<u8>) -> Option<pack::bundle::Location> {
+        git_pack::Find::location_by_id(&self.db, id, buf)
+    }
+
+    fn pack_checksum(&self, pack_id: u32) -> Option<git_hash::ObjectId> {
+        git_pack::Find::pack_checksum(&self.db, pack_id)
+    }
+
+    fn entry_by_location(&self, location: &pack::bundle::Location) -> Option<git_pack::find::Entry<'_>> {
+        git_pack::Find::entry_by_location(&self.db, location)
+    }
+}
+
+fn check<Find>(
+    db: &Find,
+    complete: Option<&str>,
+) -> Result<connectivity::Outcome, connectivity::Error<<Find as git_pack::Find>::Error>>
+where
+    Find: git_pack::Find + Sync,
+    <Find as git_pack::Find>::Error: Send,
+{
+    connectivity::check(
+        Some(hex_to_id(TIP)),
+        complete.map(hex_to_id),
+        db,
+        || pack::cache::Never,
+        None,
+        &AtomicBool::new(false),
+    )
+}
+
+#[test]
+fn all_objects_reachable_from_the_tips_are_looked_up() -> crate::Result {
+    assert_eq!(
+        check(&bundle(), None)?.objects,
+        42,
+        "every object in the pack is reachable"
+    );
+    Ok(())
+}
+
+#[test]
+fn complete_objects_and_their_ancestors_are_skipped() -> crate::Result {
+    assert_eq!(
+        check(&bundle(), Some("60e868acbaebff8a3150956f72639c1ecf095b00"))?.objects,
+        3,
+        "only the tip commit, its tree and its blob remain"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_objects_are_reported_along_with_the_object_referring_to_them() {
+    let db = Without {
+        db: bundle(),
+        hidden: hex_to_id("f9bec6afd24c7cdaaad9b082cb0544a24c82ef53"),
+    };
+    match check(&db, None) {
+        Err(connectivity::Error::Missing { oid, referenced_by }) => {
+            assert_eq!(oid, db.hidden);
+            assert_eq!(referenced_by, hex_to_id("4dac9989f96bc5b5b1263b582c08f0c5f0b58542"));
+        }
+        res => panic!("expected a missing blob, got {:?}", res),
+    }
+}
+
+#[test]
+fn missing_tips_are_reported() {
+    let db = Without {
+        db: bundle(),
+        hidden: hex_to_id(TIP),
+    };
+    assert!(matches!(
+        check(&db, None),
+        Err(connectivity::Error::MissingTip { oid }) if oid == db.hidden
+    ));
+}
+
+#[test]
+fn interruptions_abort_the_check() {
+    let res = connectivity::check(
+        Some(hex_to_id(TIP)),
+        None,
+        &bundle(),
+        || pack::cache::Never,
+        None,
+        &AtomicBool::new(true),
+    );
+    assert!(matches!(res, Err(connectivity::Error::Interrupted)));
+}
@@ -36,7 +36,7 @@ fn db(kind: DbKind) -> crate::Result<Arc<linked::Store>> {
 }
 
 mod count_and_entries {
-    use std::sync::Arc;
+    use std::{collections::HashSet, sync::Arc};
 
     use crate::pack::{
         data::output::{db, DbKind},
@@ -178,6 +178,7 @@ mod count_and_entries {
 
             let mut entries_iter = output::entry::from_counts_iter(
                 counts,
+                output::entry::from_counts_iter::InputOrder,
                 db.clone(),
                 || pack::cache::Never,
                 progress::Discard,
@@ -202,6 +203,169 @@ mod count_and_entries {
         Ok(())
     }
 
+    #[test]
+    fn custom_order_and_partitions_are_respected_when_chunking() -> crate::Result {
+        let db = db(DbKind::DeterministicGeneratedContent)?;
+        let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+        let commits = commit::Ancestors::new(Some(head), commit::ancestors::State::default(), {
+            let db = Arc::clone(&db);
+            move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+        })
+        .map(Result::unwrap);
+        let counts: Vec<_> = output::count::from_objects_iter(
+            db.clone(),
+            || pack::cache::Never,
+            commits,
+            progress::Discard,
+            count::from_objects_iter::Options {
+                input_object_expansion: count::from_objects_iter::ObjectExpansion::TreeContents,
+                thread_limit: Some(1),
+                ..Default::default()
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+        let num_counts = counts.len();
+
+        let order = {
+            let db = Arc::clone(&db);
+            move |counts: &mut Vec<output::Count>| {
+                let rank = |kind: git_object::Kind| match kind {
+                    git_object::Kind::Commit => 0,
+                    git_object::Kind::Tree => 1,
+                    git_object::Kind::Blob => 2,
+                    git_object::Kind::Tag => 3,
+                };
+                let mut buf = Vec::new();
+                let mut ranked: Vec<_> = counts
+                    .drain(..)
+                    .map(|c| {
+                        let kind = db
+                            .find_existing(c.id, &mut buf, &mut pack::cache::Never)
+                            .expect("object present")
+                            .kind;
+                        (rank(kind), c)
+                    })
+                    .collect();
+                ranked.sort_by_key(|(rank, _)| *rank);
+                let partition_ends = ranked
+                    .windows(2)
+                    .enumerate()
+                    .filter_map(|(idx, w)| (w[0].0 != w[1].0).then(|| idx + 1))
+                    .collect();
+                counts.extend(ranked.into_iter().map(|(_, c)| c));
+                partition_ends
+            }
+        };
+        let chunks: Vec<_> = output::InOrderIter::from(output::entry::from_counts_iter(
+            counts,
+            order,
+            db.clone(),
+            || pack::cache::Never,
+            progress::Discard,
+            output::entry::from_counts_iter::Options {
+                thread_limit: Some(1),
+                chunk_size: 7,
+                ..Default::default()
+            },
+        ))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let kinds: Vec<_> = chunks.iter().flatten().map(|e| e.object_kind).collect();
+        assert_eq!(kinds.len(), num_counts, "no entry is lost or duplicated");
+        let first_tree = kinds.iter().position(|k| *k == git_object::Kind::Tree).expect("trees");
+        let first_blob = kinds.iter().position(|k| *k == git_object::Kind::Blob).expect("blobs");
+        assert!(
+            kinds[..first_tree].iter().all(|k| *k == git_object::Kind::Commit)
+                && kinds[first_tree..first_blob]
+                    .iter()
+                    .all(|k| *k == git_object::Kind::Tree)
+                && kinds[first_blob..].iter().all(|k| *k == git_object::Kind::Blob),
+            "commits come first, then trees, then blobs"
+        );
+        assert!(
+            chunks
+                .iter()
+                .all(|chunk| chunk.iter().all(|e| e.object_kind == chunk[0].object_kind)),
+            "chunks never span more than one partition"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn objects_reachable_from_excluded_commits_are_not_counted() -> crate::Result {
+        let db = db(DbKind::DeterministicGeneratedContent)?;
+        let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+        let parent = commit::Ancestors::new(Some(head), commit::ancestors::State::default(), {
+            let db = Arc::clone(&db);
+            move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+        })
+        .nth(1)
+        .expect("head has a parent")?;
+
+        let tree_objects = |commit_id: git_hash::ObjectId| -> crate::Result<HashSet<git_hash::ObjectId>> {
+            let mut buf = Vec::new();
+            let tree_id = db
+                .find_existing_commit_iter(commit_id, &mut buf, &mut pack::cache::Never)?
+                .tree_id()
+                .expect("every commit has a tree");
+            let mut recorder = git_traverse::tree::Recorder::default();
+            git_traverse::tree::breadthfirst(
+                db.find_existing_tree_iter(tree_id, &mut buf, &mut pack::cache::Never)?,
+                git_traverse::tree::breadthfirst::State::default(),
+                |oid, buf| db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok(),
+                &mut recorder,
+            )?;
+            Ok(recorder
+                .records
+                .into_iter()
+                .map(|e| e.oid)
+                .chain(Some(tree_id))
+                .collect())
+        };
+        let parent_objects = tree_objects(parent)?;
+        let expected: HashSet<_> = tree_objects(head)?
+            .difference(&parent_objects)
+            .copied()
+            .chain(Some(head))
+            .collect();
+
+        for expansion_mode in [
+            count::from_objects_iter::ObjectExpansion::TreeContents,
+            count::from_objects_iter::ObjectExpansion::TreeAdditionsComparedToAncestor,
+        ]
+        .iter()
+        .copied()
+        {
+            let counts: Vec<_> = output::count::from_objects_iter_excluding(
+                db.clone(),
+                || pack::cache::Never,
+                std::iter::once(head),
+                Some(parent),
+                progress::Discard,
+                count::from_objects_iter::Options {
+                    input_object_expansion: expansion_mode,
+                    thread_limit: Some(1),
+                    ..Default::default()
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+            let actual: HashSet<_> = counts.iter().map(|c| c.id).collect();
+            assert_eq!(counts.len(), actual.len(), "no object is counted twice");
+            assert_eq!(
+                actual, expected,
+                "{:?}: only the commit and the objects new to its tree are counted",
+                expansion_mode
+            );
+        }
+        Ok(())
+    }
+
     fn write_and_verify(entries: Vec<output::Entry>, _expected_pack_hash: git_hash::ObjectId) -> crate::Result {
         let tmp_dir = tempfile::TempDir::new()?;
         let pack_file_path = tmp_dir.path().join("new.pack");
@@ -209,7 +373,7 @@ mod count_and_entries {
             .write(true)
             .create_new(true)
             .open(&pack_file_path)?;
-        let (num_written_bytes, pack_hash) = {
+        let (num_written_bytes, pack_hash, index) = {
             let num_entries = entries.len();
             let mut pack_writer = output::bytes::FromEntriesIter::new(
                 std::iter::once(Ok::<_, entry::from_counts_iter::Error<compound::find::Error>>(entries)),
@@ -217,7 +381,9 @@ mod count_and_entries {
                 num_entries as u32,
                 pack::data::Version::V2,
                 git_hash::Kind::Sha1,
-            );
+                Default::default(),
+            )
+            .with_index();
             let mut n = pack_writer.next().expect("one entries bundle was written")?;
             n += pack_writer.next().expect("the trailer was written")?;
             assert!(
@@ -226,9 +392,13 @@ mod count_and_entries {
             );
             // verify we can still get the original parts back
             let hash = pack_writer.digest().expect("digest is available when iterator is done");
+            let mut index = Vec::new();
+            let index_outcome = pack_writer.write_index(&mut index)?;
+            assert_eq!(index_outcome.data_hash, hash);
+            assert_eq!(index_outcome.num_objects as usize, num_entries);
             let _ = pack_writer.input;
             let _ = pack_writer.into_write();
-            (n, hash)
+            (n, hash, index)
         };
         assert_eq!(
             num_written_bytes,
@@ -246,17 +416,20 @@ mod count_and_entries {
         assert_eq!(hash, _expected_pack_hash, "pack hashes are stable if the input is");
 
         // Re-generate the index from the pack for validation.
-        let bundle = pack::Bundle::at(
-            pack::Bundle::write_to_directory(
-                std::io::BufReader::new(std::fs::File::open(pack_file_path)?),
-                Some(tmp_dir.path()),
-                progress::Discard,
-                &should_interrupt,
-                pack::bundle::write::Options::default(),
-            )?
-            .data_path
-            .expect("directory set"),
+        let outcome = pack::Bundle::write_to_directory(
+            std::io::BufReader::new(std::fs::File::open(pack_file_path)?),
+            Some(tmp_dir.path()),
+            progress::Discard,
+            &should_interrupt,
+            None,
+            pack::bundle::write::Options::default(),
         )?;
+        assert_eq!(
+            std::fs::read(outcome.index_path.expect("directory set"))?,
+            index,
+            "the index created while writing the pack is the same as the one created from the pack"
+        );
+        let bundle = pack::Bundle::at(outcome.data_path.expect("directory set"))?;
         bundle.verify_integrity(
             pack::index::verify::Mode::Sha1Crc32DecodeEncode,
             pack::index::traverse::Algorithm::DeltaTreeLookup,
@@ -269,6 +442,158 @@ mod count_and_entries {
     }
 }
 
+mod count_persisted {
+    use std::sync::Arc;
+
+    use crate::pack::{
+        data::output::{db, DbKind},
+        hex_to_id,
+    };
+    use git_features::progress;
+    use git_odb::{pack, FindExt};
+    use git_pack::data::output::{
+        self,
+        count::{self, persisted},
+    };
+    use git_traverse::commit;
+
+    fn snapshot_of(tips: Vec<git_hash::ObjectId>) -> crate::Result<persisted::Snapshot> {
+        let db = db(DbKind::DeterministicGeneratedContent)?;
+        let commits = commit::Ancestors::new(tips.clone(), commit::ancestors::State::default(), {
+            let db = Arc::clone(&db);
+            move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+        })
+        .map(Result::unwrap);
+        let counts = output::count::from_objects_iter(
+            Arc::clone(&db),
+            || pack::cache::Never,
+            commits,
+            progress::Discard,
+            count::from_objects_iter::Options {
+                input_object_expansion: count::from_objects_iter::ObjectExpansion::TreeContents,
+                thread_limit: Some(1),
+                ..Default::default()
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+        Ok(persisted::Snapshot::new(tips, counts, &*db))
+    }
+
+    #[test]
+    fn round_trip_and_checksum_validation() -> crate::Result {
+        let snapshot = snapshot_of(vec![hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e")])?;
+        assert_eq!(snapshot.packs.len(), 1, "all objects are in a single pack");
+        let mut buf = Vec::new();
+        let checksum = snapshot.write_to(&mut buf)?;
+        assert_eq!(
+            &buf[buf.len() - 20..],
+            checksum.as_slice(),
+            "the checksum is the trailer"
+        );
+        assert_eq!(persisted::Snapshot::from_bytes(&buf)?, snapshot);
+
+        let tmp = tempfile::NamedTempFile::new()?;
+        std::fs::write(tmp.path(), &buf)?;
+        assert_eq!(persisted::Snapshot::at(tmp.path())?, snapshot);
+
+        buf[30] ^= 0xff;
+        assert!(matches!(
+            persisted::Snapshot::from_bytes(&buf),
+            Err(persisted::decode::Error::Mismatch { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn reuse_depends_on_tips_and_pack_checksums() -> crate::Result {
+        let db = db(DbKind::DeterministicGeneratedContent)?;
+        let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+        let tag = hex_to_id("e3fb53cbb4c346d48732a24f09cf445e49bc63d6");
+        let snapshot = snapshot_of(vec![head])?;
+        let options = persisted::Options::default();
+
+        assert_eq!(
+            snapshot.clone().reuse(Some(head), &*db, options),
+            persisted::Reuse::Complete(snapshot.counts.clone())
+        );
+        assert_eq!(
+            snapshot.clone().reuse(vec![head, tag], &*db, options),
+            persisted::Reuse::Partial {
+                counts: snapshot.counts.clone(),
+                new_tips: vec![tag]
+            }
+        );
+        assert_eq!(
+            snapshot
+                .clone()
+                .reuse(vec![head, tag], &*db, persisted::Options { max_new_tips: 0 }),
+            persisted::Reuse::Stale(persisted::Staleness::TooManyNewTips(1))
+        );
+
+        let pack_id = *snapshot.packs.keys().next().expect("objects are packed");
+        let mut changed = snapshot.clone();
+        changed.packs.insert(pack_id, git_hash::ObjectId::null_sha1());
+        assert_eq!(
+            changed.reuse(Some(head), &*db, options),
+            persisted::Reuse::Stale(persisted::Staleness::PackChanged { pack_id })
+        );
+
+        let mut removed = snapshot;
+        removed.packs.clear();
+        assert_eq!(
+            removed.reuse(Some(head), &*db, options),
+            persisted::Reuse::Stale(persisted::Staleness::PackChanged { pack_id }),
+            "packs unknown to the snapshot are considered changed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reuse_allows_removed_tips_reachable_from_new_tips() -> crate::Result {
+        let db = db(DbKind::DeterministicGeneratedContent)?;
+        let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+        let head_parent = hex_to_id("571dccdd9bfe31d8cfbca5c4a7ae151b5ca3658c");
+        let tag = hex_to_id("e3fb53cbb4c346d48732a24f09cf445e49bc63d6");
+        let options = persisted::Options::default();
+
+        let snapshot = snapshot_of(vec![head_parent])?;
+        assert_eq!(
+            snapshot.clone().reuse(Some(head), &*db, options),
+            persisted::Reuse::Partial {
+                counts: snapshot.counts.clone(),
+                new_tips: vec![head]
+            },
+            "fast-forwards keep the snapshot usable"
+        );
+
+        let snapshot = snapshot_of(vec![head_parent, head])?;
+        assert_eq!(
+            snapshot.clone().reuse(Some(head), &*db, options),
+            persisted::Reuse::Complete(snapshot.counts.clone()),
+            "removed tips may be reachable from remaining ones"
+        );
+
+        let snapshot = snapshot_of(vec![head])?;
+        assert_eq!(
+            snapshot.clone().reuse(Some(tag), &*db, options),
+            persisted::Reuse::Partial {
+                counts: snapshot.counts.clone(),
+                new_tips: vec![tag]
+            },
+            "tags are peeled to find the commits reachable from them"
+        );
+        assert_eq!(
+            snapshot.reuse(Some(head_parent), &*db, options),
+            persisted::Reuse::Stale(persisted::Staleness::TipRemoved(head)),
+            "rewinding makes tips unreachable"
+        );
+        Ok(())
+    }
+}
+
 mod in_order_iter {
     use git_odb::data::output::InOrderIter;
     use std::convert::Infallible;
@@ -326,3 +651,224 @@ mod in_order_iter {
         );
     }
 }
+
+mod from_entries_iter {
+    use git_odb::compound;
+    use git_pack::data::{self, output, output::bytes};
+    use git_testtools::hex_to_id;
+    use std::io::Write;
+
+    const BASE: &[u8] = b"hello world";
+    const RESULT: &[u8] = b"hello world!";
+    /// Copy all of `BASE` and append `!`.
+    const DELTA: &[u8] = &[11, 12, 0x90, 11, 0x01, b'!'];
+
+    fn entry(id: &str, kind: output::entry::Kind, data: &[u8]) -> crate::Result<output::Entry> {
+        let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+        out.write_all(data)?;
+        out.flush()?;
+        Ok(output::Entry {
+            id: hex_to_id(id),
+            object_kind: git_object::Kind::Blob,
+            kind,
+            decompressed_size: data.len(),
+            compressed_data: out.into_inner(),
+        })
+    }
+
+    fn base_and_delta() -> crate::Result<Vec<output::Entry>> {
+        Ok(vec![
+            entry(
+                "95d09f2b10159347eece71399a7e2e907ea3df4f",
+                output::entry::Kind::Base,
+                BASE,
+            )?,
+            entry(
+                "a0423896973644771497bdc03eb99d5281615b51",
+                output::entry::Kind::DeltaRef { nth_before: 1 },
+                DELTA,
+            )?,
+        ])
+    }
+
+    fn write(
+        entries: Vec<output::Entry>,
+        options: bytes::Options,
+    ) -> Result<(tempfile::TempDir, data::File), bytes::Error<compound::find::Error>> {
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("new.pack");
+        let num_entries = entries.len() as u32;
+        let writer = bytes::FromEntriesIter::new(
+            std::iter::once(Ok(entries)),
+            std::fs::File::create(&path)?,
+            num_entries,
+            data::Version::V2,
+            git_hash::Kind::Sha1,
+            options,
+        );
+        for res in writer {
+            res?;
+        }
+        let pack = data::File::at(&path).expect("valid pack");
+        Ok((dir, pack))
+    }
+
+    fn entries_of(pack: &data::File) -> crate::Result<Vec<data::input::Entry>> {
+        Ok(pack.streaming_iter()?.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    #[test]
+    fn deltas_against_previous_entries_are_written_as_ofs_delta_by_default() -> crate::Result {
+        let (_keep, pack) = write(base_and_delta()?, Default::default())?;
+        let entries = entries_of(&pack)?;
+        assert_eq!(
+            entries[1].header,
+            data::entry::Header::OfsDelta {
+                base_distance: entries[1].pack_offset - entries[0].pack_offset
+            }
+        );
+
+        let mut buf = Vec::new();
+        pack.decode_entry(
+            pack.entry(entries[1].pack_offset),
+            &mut buf,
+            |_, _| None,
+            &mut git_pack::cache::Never,
+        )?;
+        assert_eq!(buf, RESULT);
+        Ok(())
+    }
+
+    #[test]
+    fn deltas_are_written_as_ref_delta_if_ofs_delta_is_not_allowed() -> crate::Result {
+        let (_keep, pack) = write(
+            base_and_delta()?,
+            bytes::Options {
+                allow_ofs_delta: false,
+                ..Default::default()
+            },
+        )?;
+        let entries = entries_of(&pack)?;
+        let base_id = hex_to_id("95d09f2b10159347eece71399a7e2e907ea3df4f");
+        assert_eq!(entries[1].header, data::entry::Header::RefDelta { base_id });
+
+        let mut buf = Vec::new();
+        let base_offset = entries[0].pack_offset;
+        pack.decode_entry(
+            pack.entry(entries[1].pack_offset),
+            &mut buf,
+            |id, _| (id == base_id).then(|| data::ResolvedBase::InPack(pack.entry(base_offset))),
+            &mut git_pack::cache::Never,
+        )?;
+        assert_eq!(buf, RESULT);
+        Ok(())
+    }
+
+    #[test]
+    fn delta_chains_longer_than_the_maximum_depth_are_rejected() -> crate::Result {
+        let mut entries = base_and_delta()?;
+        entries.push(entry(
+            "b5ef9e1b5ea8d1d3d1a1c8b2f8bd2a3da6e2d0a1",
+            output::entry::Kind::DeltaRef { nth_before: 1 },
+            &[12, 12, 0x90, 12],
+        )?);
+        let options = bytes::Options {
+            max_delta_depth: Some(1),
+            ..Default::default()
+        };
+        assert!(matches!(
+            write(entries.clone(), options).err().expect("too deep"),
+            bytes::Error::DeltaDepthExceeded {
+                depth: 2,
+                max_depth: 1,
+                ..
+            }
+        ));
+
+        assert!(
+            write(
+                entries,
+                bytes::Options {
+                    max_delta_depth: Some(2),
+                    ..options
+                }
+            )
+            .is_ok(),
+            "chains as long as the maximum are fine"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delta_bases_must_have_been_written_before() -> crate::Result {
+        let mut entries = base_and_delta()?;
+        entries[1].kind = output::entry::Kind::DeltaRef { nth_before: 2 };
+        assert!(matches!(
+            write(entries, Default::default()).err().expect("out of bounds"),
+            bytes::Error::DeltaBaseOutOfBounds {
+                nth_before: 2,
+                num_written: 1,
+                ..
+            }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn the_amount_of_written_entries_must_match_the_header() -> crate::Result {
+        let entries = base_and_delta()?;
+        let writer = bytes::FromEntriesIter::new(
+            std::iter::once(Ok::<_, compound::find::Error>(entries)),
+            Vec::new(),
+            3,
+            data::Version::V2,
+            git_hash::Kind::Sha1,
+            Default::default(),
+        );
+        assert!(matches!(
+            writer.collect::<Result<Vec<_>, _>>().err().expect("too few entries"),
+            bytes::Error::EntryCountMismatch { expected: 3, actual: 2 }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn an_index_can_be_written_along_with_the_pack() -> crate::Result {
+        let dir = tempfile::TempDir::new()?;
+        let entries = base_and_delta()?;
+        let mut writer = bytes::FromEntriesIter::new(
+            std::iter::once(Ok::<_, compound::find::Error>(entries)),
+            Vec::new(),
+            2,
+            data::Version::V2,
+            git_hash::Kind::Sha1,
+            Default::default(),
+        )
+        .with_index();
+        for res in writer.by_ref() {
+            res?;
+        }
+        let index_entries = writer.index_entries().expect("recorded");
+        assert_eq!(index_entries.len(), 2);
+        assert_eq!(index_entries[0].pack_offset, 12, "right after the pack header");
+
+        let (outcome, index_path) = writer.write_index_to_directory(dir.path())?;
+        let pack_hash = writer.digest().expect("done");
+        assert_eq!(outcome.data_hash, pack_hash);
+        assert_eq!(index_path, dir.path().join(format!("{}.idx", pack_hash)));
+        let pack_path = index_path.with_extension("pack");
+        std::fs::write(&pack_path, writer.into_write())?;
+
+        let bundle = git_pack::Bundle::at(&pack_path)?;
+        assert_eq!(bundle.index.num_objects(), 2);
+        assert_eq!(bundle.index.index_checksum(), outcome.index_hash);
+        assert_eq!(bundle.index.pack_checksum(), pack_hash);
+        let mut buf = Vec::new();
+        let delta_id = hex_to_id("a0423896973644771497bdc03eb99d5281615b51");
+        let object = bundle
+            .find(delta_id, &mut buf, &mut git_pack::cache::Never)?
+            .expect("delta object is found through the index");
+        assert_eq!(object.data, RESULT);
+        Ok(())
+    }
+}
@@ -33,6 +33,7 @@ mod file {
                     assert_eq!(entry.oid.as_ref(), idx.oid_at_index(index));
                     assert_eq!(entry.pack_offset, idx.pack_offset_at_index(index));
                     assert_eq!(entry.crc32, idx.crc32_at_index(index));
+                    assert_eq!(entry, idx.entry_at_index(index));
                 }
                 Ok(())
             }
@@ -62,6 +63,7 @@ mod file {
                     assert_eq!(entry.oid.as_ref(), idx.oid_at_index(index));
                     assert_eq!(entry.pack_offset, idx.pack_offset_at_index(index));
                     assert_eq!(entry.crc32, idx.crc32_at_index(index), "{} {:?}", index, entry);
+                    assert_eq!(entry, idx.entry_at_index(index));
                 }
                 Ok(())
             }
@@ -362,6 +364,131 @@ mod file {
         Ok(())
     }
 
+    #[test]
+    fn traverse_with_and_without_prefetching_yields_the_same_outcome() -> Result<(), Box<dyn std::error::Error>> {
+        for (index_path, pack_path) in &[(INDEX_V2, PACK_FOR_INDEX_V2), (SMALL_PACK_INDEX, SMALL_PACK)] {
+            let idx = index::File::at(&fixture_path(index_path))?;
+            let pack = pack::data::File::at(&fixture_path(pack_path))?;
+            for algorithm in ALGORITHMS {
+                let mut outcomes = [true, false].iter().map(|prefetch| {
+                    idx.traverse(
+                        &pack,
+                        progress::Discard.into(),
+                        || |_, _: &[u8], _: &index::Entry, _: &mut _| Ok::<_, std::io::Error>(()),
+                        || cache::Never,
+                        index::traverse::Options {
+                            algorithm: *algorithm,
+                            prefetch: *prefetch,
+                            ..Default::default()
+                        },
+                    )
+                    .map(|(id, outcome, _)| (id, outcome))
+                });
+                assert_eq!(
+                    outcomes.next().expect("with prefetching")?,
+                    outcomes.next().expect("without prefetching")?,
+                    "{:?}",
+                    algorithm
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn failed_entries_can_be_located_and_verified_on_their_own() -> Result<(), Box<dyn std::error::Error>> {
+        let idx = index::File::at(&fixture_path(SMALL_PACK_INDEX))?;
+        let first_entry = idx
+            .iter()
+            .min_by_key(|entry| entry.pack_offset)
+            .expect("pack isn't empty");
+        let pack = pack::data::File::at(&fixture_path(SMALL_PACK))?;
+        let dir = tempfile::TempDir::new()?;
+        let corrupt_pack_path = dir.path().join("corrupt.pack");
+        let mut corrupt_pack_data = std::fs::read(fixture_path(SMALL_PACK))?;
+        let zlib_header_offset = pack.entry(first_entry.pack_offset).data_offset as usize;
+        corrupt_pack_data[zlib_header_offset] ^= 0xff;
+        std::fs::write(&corrupt_pack_path, corrupt_pack_data)?;
+        let corrupt_pack = pack::data::File::at(&corrupt_pack_path)?;
+
+        let err = idx
+            .verify_integrity(
+                Some((&corrupt_pack, MODES[0], ALGORITHMS[0], || cache::Never)),
+                None,
+                progress::Discard.into(),
+                Default::default(),
+            )
+            .map(|_| ())
+            .expect_err("the pack is corrupt");
+        let expected_failure = index::verify::Failure {
+            id: first_entry.oid,
+            pack_offset: first_entry.pack_offset,
+        };
+        if let Some(failure) = idx.failure_of(&err) {
+            assert_eq!(failure, expected_failure, "either the failing entry is known…");
+        }
+        assert_eq!(
+            idx.locate_failure(
+                &corrupt_pack,
+                MODES[0],
+                || cache::Never,
+                None,
+                progress::Discard.into(),
+                Default::default()
+            )?,
+            Some(expected_failure),
+            "…or it can be located"
+        );
+        assert_eq!(
+            idx.locate_failure(
+                &pack,
+                MODES[0],
+                || cache::Never,
+                None,
+                progress::Discard.into(),
+                Default::default()
+            )?,
+            None,
+            "intact packs have no failing entry"
+        );
+
+        let should_interrupt = std::sync::atomic::AtomicBool::default();
+        for mode in MODES {
+            let failures = idx.verify_entries(
+                &corrupt_pack,
+                Some(expected_failure),
+                *mode,
+                cache::Never,
+                progress::Discard,
+                &should_interrupt,
+            )?;
+            assert_eq!(failures.len(), 1, "the entry is still broken");
+            assert_eq!(failures[0].0, expected_failure);
+            assert!(matches!(failures[0].1, index::traverse::Error::PackDecode { .. }));
+
+            let repaired_pack = &pack;
+            let unknown_id = index::verify::Failure {
+                id: git_hash::ObjectId::null_sha1(),
+                pack_offset: 0,
+            };
+            let failures = idx.verify_entries(
+                repaired_pack,
+                vec![expected_failure, unknown_id],
+                *mode,
+                cache::Never,
+                progress::Discard,
+                &should_interrupt,
+            )?;
+            assert_eq!(failures.len(), 1, "only the entry that doesn't exist fails");
+            assert_eq!(failures[0].0, unknown_id);
+            assert!(matches!(
+                failures[0].1,
+                index::traverse::Error::Verify(index::verify::Error::ObjectNotFound { .. })
+            ));
+        }
+        Ok(())
+    }
+
     #[test]
     fn iter() -> Result<(), Box<dyn std::error::Error>> {
         for (path, kind, num_objects, index_checksum, pack_checksum) in &[
@@ -138,3 +138,121 @@ mod new_from_header {
         Ok(())
     }
 }
+
+mod lookup_ref_delta_objects {
+    use crate::hex_to_id;
+    use git_odb::{
+        pack,
+        pack::data::{entry::Header, input},
+    };
+
+    fn entry(header: Header, pack_offset: u64, header_size: u16) -> input::Entry {
+        input::Entry {
+            header,
+            header_size,
+            pack_offset,
+            compressed: Some(vec![0; 10]),
+            compressed_size: 10,
+            crc32: Some(0),
+            decompressed_size: 5,
+            trailer: None,
+        }
+    }
+
+    #[test]
+    fn bases_are_inserted_in_front_of_their_first_delta_and_distances_are_adjusted(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let base_id = hex_to_id("1179824569dcb14413904cb2b5cb036a9551024d");
+        let input = vec![
+            entry(Header::Blob, 12, 1),
+            entry(Header::RefDelta { base_id }, 23, 21),
+            entry(Header::OfsDelta { base_distance: 42 }, 54, 2),
+            entry(Header::RefDelta { base_id }, 66, 21),
+        ];
+        let mut num_lookups = 0;
+        let entries = pack::data::input::LookupRefDeltaObjectsIter::new(input.into_iter().map(Ok), |id, buf| {
+            assert_eq!(id, base_id);
+            num_lookups += 1;
+            buf.clear();
+            buf.extend_from_slice(b"hello");
+            Some(pack::data::Object::new(git_object::Kind::Blob, buf))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(num_lookups, 1, "bases are only looked up once");
+        assert_eq!(entries.len(), 5, "the base was inserted");
+
+        let base = &entries[1];
+        assert_eq!(base.header, Header::Blob);
+        assert_eq!(base.pack_offset, 23, "the base takes the place of its first delta");
+        let base_size = base.header_size as u64 + base.compressed_size;
+
+        let delta = &entries[2];
+        assert_eq!(delta.pack_offset, 23 + base_size);
+        assert_eq!(
+            delta.header,
+            Header::OfsDelta {
+                base_distance: base_size
+            }
+        );
+        assert_eq!(
+            delta.header_size, 2,
+            "the ref-delta header shrinks to an ofs-delta header"
+        );
+        assert_ne!(delta.crc32, Some(0), "the crc32 is recomputed with the new header");
+        let shift = base_size as i64 + 2 - 21;
+
+        let ofs_delta = &entries[3];
+        assert_eq!(ofs_delta.pack_offset as i64, 54 + shift);
+        assert_eq!(
+            ofs_delta.header,
+            Header::OfsDelta {
+                base_distance: (42 + shift) as u64
+            },
+            "the base lies before the inserted object, increasing the distance"
+        );
+
+        let second_delta = &entries[4];
+        assert_eq!(second_delta.pack_offset as i64, 66 + shift);
+        assert_eq!(
+            second_delta.header,
+            Header::OfsDelta {
+                base_distance: second_delta.pack_offset - 23
+            },
+            "the already inserted base is reused"
+        );
+        Ok(())
+    }
+}
+
+mod entries_to_bytes {
+    use crate::{fixture_path, pack::SMALL_PACK};
+    use git_odb::{
+        pack,
+        pack::data::input::{EntryDataMode, Mode},
+    };
+    use std::fs;
+
+    #[test]
+    fn unaltered_entries_reproduce_the_pack() -> Result<(), Box<dyn std::error::Error>> {
+        let pack = fs::read(fixture_path(SMALL_PACK))?;
+        let entries = pack::data::BytesToEntriesIter::new_from_header(
+            std::io::BufReader::new(pack.as_slice()),
+            Mode::Verify,
+            EntryDataMode::KeepAndCrc32,
+        )?;
+        let mut iter = pack::data::input::EntriesToBytesIter::new(
+            entries,
+            std::io::Cursor::new(Vec::new()),
+            pack::data::Version::V2,
+            git_hash::Kind::Sha1,
+        );
+        let last_entry = iter.by_ref().last().expect("entries")?;
+        assert_eq!(
+            last_entry.trailer,
+            Some(pack::data::File::at(fixture_path(SMALL_PACK))?.checksum())
+        );
+        assert_eq!(iter.digest(), last_entry.trailer);
+        assert_eq!(iter.into_write().into_inner(), pack, "the pack is written as it was");
+        Ok(())
+    }
+}
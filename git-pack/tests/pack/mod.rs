@@ -31,6 +31,7 @@ pub fn fixup(v: Vec<u8>) -> Vec<u8> {
 }
 
 mod bundle;
+mod connectivity;
 mod data;
 mod index;
 mod iter;
@@ -0,0 +1,58 @@
+use std::io::Read;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use git_packetline::{encode, PacketLine, StreamingPeekableIter};
+
+const PAYLOAD_LEN: usize = 4 * 1024 * 1024;
+/// The largest amount of data fitting into a single side-band line, as sent by `git upload-pack`.
+const BAND_LEN: usize = 65515;
+const LINE_LEN: usize = BAND_LEN + 5;
+
+fn payload() -> Vec<u8> {
+    (0..PAYLOAD_LEN).map(|n| n as u8).collect()
+}
+
+fn sideband_stream(payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + payload.len() / BAND_LEN * 5 + 4);
+    for chunk in payload.chunks(BAND_LEN) {
+        encode::band_to_vec(git_packetline::Channel::Data, chunk, &mut out).expect("chunk fits");
+    }
+    encode::flush_to_vec(&mut out);
+    out
+}
+
+fn read(c: &mut Criterion) {
+    let payload = payload();
+    let stream = sideband_stream(&payload);
+    let mut buf = vec![0; LINE_LEN];
+
+    let mut group = c.benchmark_group("read");
+    group.throughput(Throughput::Bytes(payload.len() as u64));
+    group.bench_function("raw io::Read", |b| {
+        b.iter(|| {
+            let mut rd = black_box(payload.as_slice());
+            while rd.read(&mut buf).expect("infallible") != 0 {}
+        })
+    });
+    group.bench_function("StreamingPeekableIter::read_line()", |b| {
+        let mut rd = StreamingPeekableIter::new(black_box(stream.as_slice()), &[PacketLine::Flush]);
+        b.iter(|| {
+            rd.replace(black_box(stream.as_slice()));
+            while let Some(line) = rd.read_line() {
+                black_box(line.expect("no io error").expect("valid line"));
+            }
+        })
+    });
+    group.bench_function("WithSidebands as io::Read", |b| {
+        let mut rd = StreamingPeekableIter::new(black_box(stream.as_slice()), &[PacketLine::Flush]);
+        b.iter(|| {
+            rd.replace(black_box(stream.as_slice()));
+            let mut sidebands = rd.as_read();
+            while sidebands.read(&mut buf).expect("infallible") != 0 {}
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, read);
+criterion_main!(benches);
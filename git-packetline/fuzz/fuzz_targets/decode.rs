@@ -0,0 +1,15 @@
+#![no_main]
+
+use git_packetline::{decode, PacketLine, SliceIter};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _a = decode::streaming(data);
+    let mut lines = SliceIter::new(data, &[PacketLine::Flush]);
+    lines.fail_on_err_lines(true);
+    for line in lines.by_ref().flatten() {
+        let _b = line.decode_band();
+        let _c = line.as_text();
+    }
+    let _d = lines.remaining();
+});
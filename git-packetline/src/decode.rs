@@ -56,8 +56,9 @@ pub enum PacketLineOrWantedSize<'a> {
 }
 
 /// Decode the `four_bytes` packet line prefix provided in hexadecimal form and check it for validity.
+///
+/// Any other amount of bytes is rejected with a [hex decoding error][Error::HexDecode].
 pub fn hex_prefix(four_bytes: &[u8]) -> Result<PacketLineOrWantedSize<'_>, Error> {
-    debug_assert_eq!(four_bytes.len(), 4, "need four hex bytes");
     for (line_bytes, line_type) in &[
         (FLUSH_LINE, PacketLine::Flush),
         (DELIMITER_LINE, PacketLine::Delimiter),
@@ -1,4 +1,4 @@
-use crate::MAX_DATA_LEN;
+use crate::{Channel, DELIMITER_LINE, ERR_PREFIX, FLUSH_LINE, MAX_DATA_LEN, RESPONSE_END_LINE, U16_HEX_BYTES};
 use quick_error::quick_error;
 
 quick_error! {
@@ -25,6 +25,75 @@ mod blocking_io;
 #[cfg(feature = "blocking-io")]
 pub use blocking_io::*;
 
+/// Return the amount of bytes a packet line carrying `data` will have once encoded, including its length prefix.
+///
+/// Note that text lines and side-band lines are one byte longer due to their newline or channel byte respectively,
+/// while error lines are four bytes longer.
+pub fn encoded_len(data: &[u8]) -> usize {
+    U16_HEX_BYTES + data.len()
+}
+
+/// Append a response-end message to `out`.
+pub fn response_end_to_vec(out: &mut Vec<u8>) -> usize {
+    out.extend_from_slice(RESPONSE_END_LINE);
+    RESPONSE_END_LINE.len()
+}
+
+/// Append a delim message to `out`.
+pub fn delim_to_vec(out: &mut Vec<u8>) -> usize {
+    out.extend_from_slice(DELIMITER_LINE);
+    DELIMITER_LINE.len()
+}
+
+/// Append a flush message to `out`.
+pub fn flush_to_vec(out: &mut Vec<u8>) -> usize {
+    out.extend_from_slice(FLUSH_LINE);
+    FLUSH_LINE.len()
+}
+
+/// Append an error `message` to `out`.
+pub fn error_to_vec(message: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+    prefixed_and_suffixed_data_to_vec(ERR_PREFIX, message, &[], out)
+}
+
+/// Append `data` of `kind` to `out` using side-band encoding.
+pub fn band_to_vec(kind: Channel, data: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+    prefixed_and_suffixed_data_to_vec(&[kind as u8], data, &[], out)
+}
+
+/// Append a `data` message to `out`.
+pub fn data_to_vec(data: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+    prefixed_and_suffixed_data_to_vec(&[], data, &[], out)
+}
+
+/// Append a `text` message to `out`, which is assured to end in a newline.
+pub fn text_to_vec(text: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+    prefixed_and_suffixed_data_to_vec(&[], text, b"\n", out)
+}
+
+fn prefixed_and_suffixed_data_to_vec(
+    prefix: &[u8],
+    data: &[u8],
+    suffix: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<usize, Error> {
+    let data_len = prefix.len() + data.len() + suffix.len();
+    if data_len > MAX_DATA_LEN {
+        return Err(Error::DataLengthLimitExceeded(data_len));
+    }
+    if data.is_empty() {
+        return Err(Error::DataIsEmpty);
+    }
+
+    let line_len = data_len + U16_HEX_BYTES;
+    out.reserve(line_len);
+    out.extend_from_slice(&u16_to_hex(line_len as u16));
+    out.extend_from_slice(prefix);
+    out.extend_from_slice(data);
+    out.extend_from_slice(suffix);
+    Ok(line_len)
+}
+
 pub(crate) fn u16_to_hex(value: u16) -> [u8; 4] {
     let mut buf = [0u8; 4];
     hex::encode_to_slice((value as u16).to_be_bytes(), &mut buf).expect("two bytes to 4 hex chars never fails");
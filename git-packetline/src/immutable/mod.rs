@@ -17,14 +17,14 @@ pub enum PacketLine<'a> {
 
 impl<'a> PacketLine<'a> {
     /// Return this instance as slice if it's [`Data`][PacketLine::Data].
-    pub fn as_slice(&self) -> Option<&[u8]> {
+    pub fn as_slice(&self) -> Option<&'a [u8]> {
         match self {
             PacketLine::Data(d) => Some(d),
             PacketLine::Flush | PacketLine::Delimiter | PacketLine::ResponseEnd => None,
         }
     }
     /// Return this instance's [`as_slice()`][PacketLine::as_slice()] as [`BStr`].
-    pub fn as_bstr(&self) -> Option<&BStr> {
+    pub fn as_bstr(&self) -> Option<&'a BStr> {
         self.as_slice().map(Into::into)
     }
     /// Interpret this instance's [`as_slice()`][PacketLine::as_slice()] as [`Error`].
@@ -33,13 +33,13 @@ impl<'a> PacketLine<'a> {
     ///
     /// Note that this creates an unchecked error using the slice verbatim, which is useful to [serialize it][Error::write_to()].
     /// See [`check_error()`][PacketLine::check_error()] for a version that assures the error information is in the expected format.
-    pub fn as_error(&self) -> Option<Error<'_>> {
+    pub fn as_error(&self) -> Option<Error<'a>> {
         self.as_slice().map(Error)
     }
     /// Check this instance's [`as_slice()`][PacketLine::as_slice()] is a valid [`Error`] and return it.
     ///
     /// This works for any data received in an error [channel][crate::Channel].
-    pub fn check_error(&self) -> Option<Error<'_>> {
+    pub fn check_error(&self) -> Option<Error<'a>> {
         self.as_slice().and_then(|data| {
             if data.len() >= ERR_PREFIX.len() && &data[..ERR_PREFIX.len()] == ERR_PREFIX {
                 Some(Error(&data[ERR_PREFIX.len()..]))
@@ -49,7 +49,7 @@ impl<'a> PacketLine<'a> {
         })
     }
     /// Return this instance as text, with the trailing newline truncated if present.
-    pub fn as_text(&self) -> Option<Text<'_>> {
+    pub fn as_text(&self) -> Option<Text<'a>> {
         self.as_slice().map(Into::into)
     }
 
@@ -57,7 +57,7 @@ impl<'a> PacketLine<'a> {
     ///
     /// Note that this is only relevant in a side-band channel.
     /// See [`decode_band()`][PacketLine::decode_band()] in case `kind` is unknown.
-    pub fn as_band(&self, kind: Channel) -> Option<Band<'_>> {
+    pub fn as_band(&self, kind: Channel) -> Option<Band<'a>> {
         self.as_slice().map(|d| match kind {
             Channel::Data => Band::Data(d),
             Channel::Progress => Band::Progress(d),
@@ -65,14 +65,17 @@ impl<'a> PacketLine<'a> {
         })
     }
 
-    /// Decode the band of this [`slice`][PacketLine::as_slice()], or panic if it is not actually a side-band line.
-    pub fn decode_band(&self) -> Result<Band<'_>, DecodeBandError> {
-        let d = self.as_slice().ok_or(DecodeBandError::NonDataLine)?;
-        Ok(match d[0] {
-            1 => Band::Data(&d[1..]),
-            2 => Band::Progress(&d[1..]),
-            3 => Band::Error(&d[1..]),
-            band => return Err(DecodeBandError::InvalidSideBand(band)),
+    /// Decode the band of this [`slice`][PacketLine::as_slice()], or fail if it is not actually a side-band line.
+    pub fn decode_band(&self) -> Result<Band<'a>, DecodeBandError> {
+        let (band, d) = self
+            .as_slice()
+            .and_then(|d| d.split_first())
+            .ok_or(DecodeBandError::NonDataLine)?;
+        Ok(match band {
+            1 => Band::Data(d),
+            2 => Band::Progress(d),
+            3 => Band::Error(d),
+            band => return Err(DecodeBandError::InvalidSideBand(*band)),
         })
     }
 }
@@ -111,11 +114,11 @@ impl<'a> From<&'a [u8]> for Text<'a> {
 
 impl<'a> Text<'a> {
     /// Return this instance's data.
-    pub fn as_slice(&self) -> &[u8] {
+    pub fn as_slice(&self) -> &'a [u8] {
         self.0
     }
     /// Return this instance's data as [`BStr`].
-    pub fn as_bstr(&self) -> &BStr {
+    pub fn as_bstr(&self) -> &'a BStr {
         self.0.into()
     }
 }
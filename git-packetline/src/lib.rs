@@ -1,6 +1,7 @@
 //! Read and write the git packet line wire format without copying it.
 //!
-//! For reading the packet line format use the [`StreamingPeekableIter`], and for writing the `Writer`.
+//! For reading the packet line format use the [`StreamingPeekableIter`], or the [`SliceIter`] if all data is in memory,
+//! and for writing the `Writer`.
 #![deny(unsafe_code, rust_2018_idioms, missing_docs)]
 
 const U16_HEX_BYTES: usize = 4;
@@ -30,7 +31,7 @@ pub use immutable::PacketLine;
 ///
 pub mod read;
 #[doc(inline)]
-pub use read::StreamingPeekableIter;
+pub use read::{SliceIter, StreamingPeekableIter};
 
 ///
 #[cfg(any(feature = "async-io", feature = "blocking-io"))]
@@ -1,7 +1,7 @@
 use crate::{
     decode,
-    read::{ExhaustiveOutcome, WithSidebands},
-    PacketLine, StreamingPeekableIter, MAX_LINE_LEN, U16_HEX_BYTES,
+    read::{Delimiters, ExhaustiveOutcome, WithSidebands},
+    PacketLine, StreamingPeekableIter, U16_HEX_BYTES,
 };
 use bstr::ByteSlice;
 use std::io;
@@ -11,7 +11,7 @@ impl<T> StreamingPeekableIter<T>
 where
     T: io::Read,
 {
-    fn read_line_inner<'a>(reader: &mut T, buf: &'a mut Vec<u8>) -> io::Result<Result<PacketLine<'a>, decode::Error>> {
+    fn read_line_inner<'a>(reader: &mut T, buf: &'a mut [u8]) -> io::Result<Result<PacketLine<'a>, decode::Error>> {
         let (hex_bytes, data_bytes) = buf.split_at_mut(4);
         reader.read_exact(hex_bytes)?;
         let num_data_bytes = match decode::hex_prefix(hex_bytes) {
@@ -32,44 +32,27 @@ where
     /// It contains a bunch of logic shared between peek and read_line invocations.
     fn read_line_inner_exhaustive<'a>(
         reader: &mut T,
-        buf: &'a mut Vec<u8>,
-        delimiters: &[PacketLine<'static>],
+        buf: &'a mut [u8],
+        delimiters: &Delimiters,
         fail_on_err_lines: bool,
-        buf_resize: bool,
     ) -> ExhaustiveOutcome<'a> {
         (
             false,
             None,
             Some(match Self::read_line_inner(reader, buf) {
                 Ok(Ok(line)) => {
-                    if delimiters.contains(&line) {
-                        let stopped_at = delimiters.iter().find(|l| **l == line).cloned();
-                        buf.clear();
-                        return (true, stopped_at, None);
+                    if let Some(stopped_at) = delimiters.find(&line) {
+                        return (true, Some(stopped_at), None);
                     } else if fail_on_err_lines {
                         if let Some(err) = line.check_error() {
                             let err = err.0.as_bstr().to_string();
-                            buf.clear();
                             return (true, None, Some(Err(io::Error::new(io::ErrorKind::Other, err))));
                         }
                     }
-                    let len = line
-                        .as_slice()
-                        .map(|s| s.len() + U16_HEX_BYTES)
-                        .unwrap_or(U16_HEX_BYTES);
-                    if buf_resize {
-                        buf.resize(len, 0);
-                    }
-                    Ok(Ok(crate::decode(buf).expect("only valid data here")))
-                }
-                Ok(Err(err)) => {
-                    buf.clear();
-                    Ok(Err(err))
-                }
-                Err(err) => {
-                    buf.clear();
-                    Err(err)
+                    Ok(Ok(line))
                 }
+                Ok(Err(err)) => Ok(Err(err)),
+                Err(err) => Err(err),
             }),
         )
     }
@@ -85,20 +68,17 @@ where
         if self.is_done {
             return None;
         }
-        if !self.peek_buf.is_empty() {
-            std::mem::swap(&mut self.peek_buf, &mut self.buf);
-            self.peek_buf.clear();
-            Some(Ok(Ok(crate::decode(&self.buf).expect("only valid data in peek buf"))))
+        if self.peek_len != 0 {
+            let peek_len = std::mem::take(&mut self.peek_len);
+            Some(Ok(Ok(
+                crate::decode(&self.buf[..peek_len]).expect("only valid data in peeked line")
+            )))
         } else {
-            if self.buf.len() != MAX_LINE_LEN {
-                self.buf.resize(MAX_LINE_LEN, 0);
-            }
             let (is_done, stopped_at, res) = Self::read_line_inner_exhaustive(
                 &mut self.read,
                 &mut self.buf,
                 &self.delimiters,
                 self.fail_on_err_lines,
-                false,
             );
             self.is_done = is_done;
             self.stopped_at = stopped_at;
@@ -113,20 +93,23 @@ where
         if self.is_done {
             return None;
         }
-        if self.peek_buf.is_empty() {
-            self.peek_buf.resize(MAX_LINE_LEN, 0);
+        if self.peek_len == 0 {
             let (is_done, stopped_at, res) = Self::read_line_inner_exhaustive(
                 &mut self.read,
-                &mut self.peek_buf,
+                &mut self.buf,
                 &self.delimiters,
                 self.fail_on_err_lines,
-                true,
             );
             self.is_done = is_done;
             self.stopped_at = stopped_at;
+            if let Some(Ok(Ok(line))) = &res {
+                self.peek_len = line.as_slice().map(|s| s.len()).unwrap_or(0) + U16_HEX_BYTES;
+            }
             res
         } else {
-            Some(Ok(Ok(crate::decode(&self.peek_buf).expect("only valid data here"))))
+            Some(Ok(Ok(
+                crate::decode(&self.buf[..self.peek_len]).expect("only valid data in peeked line")
+            )))
         }
     }
 
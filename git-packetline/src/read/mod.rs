@@ -1,6 +1,6 @@
+use crate::PacketLine;
 #[cfg(any(feature = "blocking-io", feature = "async-io"))]
-use crate::MAX_LINE_LEN;
-use crate::{PacketLine, U16_HEX_BYTES};
+use crate::{MAX_LINE_LEN, U16_HEX_BYTES};
 
 #[cfg(any(feature = "blocking-io", feature = "async-io"))]
 type ExhaustiveOutcome<'a> = (
@@ -9,31 +9,97 @@ type ExhaustiveOutcome<'a> = (
     Option<std::io::Result<Result<PacketLine<'a>, crate::decode::Error>>>, // actual method result
 );
 
+/// A set of packet lines which stop the iteration once encountered, stored inline to avoid allocations.
+///
+/// It can hold up to [`Delimiters::MAX`] lines, which is enough for all special lines of the protocol, and is usually created
+/// from a slice or array of lines.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Delimiters {
+    lines: [PacketLine<'static>; Delimiters::MAX],
+    len: usize,
+}
+
+impl Delimiters {
+    /// The maximum amount of delimiters that can be stored.
+    pub const MAX: usize = 4;
+
+    /// Create a new set from the given `lines`.
+    ///
+    /// # Panics
+    ///
+    /// If there are more than [`Delimiters::MAX`] `lines`.
+    pub fn new(lines: &[PacketLine<'static>]) -> Self {
+        assert!(
+            lines.len() <= Self::MAX,
+            "at most {} delimiters are supported, got {}",
+            Self::MAX,
+            lines.len()
+        );
+        let mut out = Delimiters {
+            lines: [PacketLine::Flush; Self::MAX],
+            len: lines.len(),
+        };
+        out.lines[..lines.len()].copy_from_slice(lines);
+        out
+    }
+
+    /// Return the delimiter matching `line`, if there is one.
+    pub fn find(&self, line: &PacketLine<'_>) -> Option<PacketLine<'static>> {
+        self.as_slice().iter().find(|l| *l == line).copied()
+    }
+
+    /// Return all delimiters as slice.
+    pub fn as_slice(&self) -> &[PacketLine<'static>] {
+        &self.lines[..self.len]
+    }
+}
+
+impl Default for Delimiters {
+    fn default() -> Self {
+        Delimiters::new(&[])
+    }
+}
+
+impl From<&[PacketLine<'static>]> for Delimiters {
+    fn from(lines: &[PacketLine<'static>]) -> Self {
+        Delimiters::new(lines)
+    }
+}
+
+impl<const N: usize> From<&[PacketLine<'static>; N]> for Delimiters {
+    fn from(lines: &[PacketLine<'static>; N]) -> Self {
+        Delimiters::new(&lines[..])
+    }
+}
+
 /// Read pack lines one after another, without consuming more than needed from the underlying
 /// [`Read`][std::io::Read]. [`Flush`][PacketLine::Flush] lines cause the reader to stop producing lines forever,
 /// leaving [`Read`][std::io::Read] at the start of whatever comes next.
 ///
-/// This implementation tries hard not to allocate at all which leads to quite some added complexity and plenty of extra memory copies.
+/// This implementation doesn't allocate after creation, as all lines are read into a single buffer which is also used when peeking.
 pub struct StreamingPeekableIter<T> {
     read: T,
-    peek_buf: Vec<u8>,
+    /// The length of the peeked line at the beginning of `buf`, or 0 if no line was peeked.
+    #[cfg(any(feature = "blocking-io", feature = "async-io"))]
+    peek_len: usize,
     #[cfg(any(feature = "blocking-io", feature = "async-io"))]
     buf: Vec<u8>,
     fail_on_err_lines: bool,
-    delimiters: &'static [PacketLine<'static>],
+    delimiters: Delimiters,
     is_done: bool,
     stopped_at: Option<PacketLine<'static>>,
 }
 
 impl<T> StreamingPeekableIter<T> {
     /// Return a new instance from `read` which will stop decoding packet lines when receiving one of the given `delimiters`.
-    pub fn new(read: T, delimiters: &'static [PacketLine<'static>]) -> Self {
+    pub fn new(read: T, delimiters: impl Into<Delimiters>) -> Self {
         StreamingPeekableIter {
             read,
             #[cfg(any(feature = "blocking-io", feature = "async-io"))]
             buf: vec![0; MAX_LINE_LEN],
-            peek_buf: Vec::new(),
-            delimiters,
+            #[cfg(any(feature = "blocking-io", feature = "async-io"))]
+            peek_len: 0,
+            delimiters: delimiters.into(),
             fail_on_err_lines: false,
             is_done: false,
             stopped_at: None,
@@ -47,13 +113,19 @@ impl<T> StreamingPeekableIter<T> {
     /// standard line readers to read the next line as usual.
     ///
     /// **Note** that `position` does not include the 4 bytes prefix (they are invisible outside the reader)
+    ///
+    /// # Panics
+    ///
+    /// If no line was peeked or if `position` is out of its bounds.
+    #[cfg(any(feature = "blocking-io", feature = "async-io"))]
     pub fn peek_buffer_replace_and_truncate(&mut self, position: usize, replace_with: u8) {
         let position = position + U16_HEX_BYTES;
-        self.peek_buf[position] = replace_with;
+        assert!(position < self.peek_len, "position must be within the peeked line");
+        self.buf[position] = replace_with;
 
         let new_len = position + 1;
-        self.peek_buf.truncate(new_len);
-        self.peek_buf[..4].copy_from_slice(&crate::encode::u16_to_hex((new_len) as u16));
+        self.peek_len = new_len;
+        self.buf[..4].copy_from_slice(&crate::encode::u16_to_hex((new_len) as u16));
     }
 
     /// Returns the packet line that stopped the iteration, or
@@ -62,17 +134,32 @@ impl<T> StreamingPeekableIter<T> {
         self.stopped_at
     }
 
+    /// Returns true if the iteration stopped, either because one of the [`delimiters()`][StreamingPeekableIter::delimiters()]
+    /// was encountered, the end of the input was reached or an `ERR` line was received.
+    ///
+    /// Use [`stopped_at()`][StreamingPeekableIter::stopped_at()] to learn which delimiter stopped the iteration, if any.
+    pub fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    /// Returns the packet lines which stop the iteration once encountered.
+    pub fn delimiters(&self) -> &[PacketLine<'static>] {
+        self.delimiters.as_slice()
+    }
+
     /// Reset all iteration state allowing to continue a stopped iteration that is not yet at EOF.
     ///
     /// This can happen once a delimiter is reached.
     pub fn reset(&mut self) {
-        let delimiters = std::mem::take(&mut self.delimiters);
-        self.reset_with(delimiters);
+        self.reset_with(self.delimiters);
     }
 
     /// Similar to [`reset()`][StreamingPeekableIter::reset()] with support to changing the `delimiters`.
-    pub fn reset_with(&mut self, delimiters: &'static [PacketLine<'static>]) {
-        self.delimiters = delimiters;
+    ///
+    /// This allows to reuse the same instance for multiple responses on the same connection, like it's the case for
+    /// commands invoked in protocol V2, each of which uses its own set of delimiters.
+    pub fn reset_with(&mut self, delimiters: impl Into<Delimiters>) {
+        self.delimiters = delimiters.into();
         self.is_done = false;
         self.stopped_at = None;
     }
@@ -80,7 +167,6 @@ impl<T> StreamingPeekableIter<T> {
     /// If `value` is `true` the provider will check for special `ERR` packet lines and stop iteration when one is encountered.
     ///
     /// Use [`stopped_at()]`[StreamingPeekableIter::stopped_at()] to inspect the cause of the end of the iteration.
-    /// ne
     pub fn fail_on_err_lines(&mut self, value: bool) {
         self.fail_on_err_lines = value;
     }
@@ -105,6 +191,9 @@ mod blocking_io;
 #[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
 mod async_io;
 
+mod slice;
+pub use slice::SliceIter;
+
 mod sidebands;
 #[cfg(any(feature = "blocking-io", feature = "async-io"))]
 pub use sidebands::WithSidebands;
@@ -114,7 +114,7 @@ where
     }
 
     /// Forwards to the parent [StreamingPeekableIter::reset_with()]
-    pub fn reset_with(&mut self, delimiters: &'static [PacketLine<'static>]) {
+    pub fn reset_with(&mut self, delimiters: impl Into<crate::read::Delimiters>) {
         if let State::Idle { ref mut parent } = self.state {
             parent.as_mut().unwrap().reset_with(delimiters)
         }
@@ -70,7 +70,7 @@ where
     }
 
     /// Forwards to the parent [StreamingPeekableIter::reset_with()]
-    pub fn reset_with(&mut self, delimiters: &'static [PacketLine<'static>]) {
+    pub fn reset_with(&mut self, delimiters: impl Into<crate::read::Delimiters>) {
         self.parent.reset_with(delimiters)
     }
 
@@ -0,0 +1,148 @@
+use crate::{decode, read::Delimiters, PacketLine};
+
+/// Read packet lines one after another directly from a byte slice, returning lines which borrow from it without copying.
+///
+/// It's the counterpart of the [`StreamingPeekableIter`][crate::StreamingPeekableIter] for data that is entirely in memory,
+/// like captured protocol dumps or fuzzing input, and follows the same rules regarding delimiters and `ERR` lines.
+/// As no buffering is involved, lines remain valid even after the iterator is dropped.
+pub struct SliceIter<'a> {
+    data: &'a [u8],
+    delimiters: Delimiters,
+    fail_on_err_lines: bool,
+    is_done: bool,
+    stopped_at: Option<PacketLine<'static>>,
+}
+
+/// The outcome of decoding the next line: the amount of bytes to consume, whether we are done, the line that stopped us
+/// and the actual method result.
+type Outcome<'a> = (
+    usize,
+    bool,
+    Option<PacketLine<'static>>,
+    Option<Result<PacketLine<'a>, decode::Error>>,
+);
+
+impl<'a> SliceIter<'a> {
+    /// Return a new instance reading from `data` which will stop decoding packet lines when receiving one of the given `delimiters`.
+    pub fn new(data: &'a [u8], delimiters: impl Into<Delimiters>) -> Self {
+        SliceIter {
+            data,
+            delimiters: delimiters.into(),
+            fail_on_err_lines: false,
+            is_done: false,
+            stopped_at: None,
+        }
+    }
+
+    fn decode_next_line(&self) -> Outcome<'a> {
+        if self.data.is_empty() {
+            return (0, true, None, None);
+        }
+        let (line, bytes_consumed) = match decode::streaming(self.data) {
+            Ok(decode::Stream::Complete { line, bytes_consumed }) => (line, bytes_consumed),
+            Ok(decode::Stream::Incomplete { bytes_needed }) => {
+                return (0, true, None, Some(Err(decode::Error::NotEnoughData(bytes_needed))))
+            }
+            Err(err) => return (0, true, None, Some(Err(err))),
+        };
+        if let Some(stopped_at) = self.delimiters.find(&line) {
+            return (bytes_consumed, true, Some(stopped_at), None);
+        }
+        if self.fail_on_err_lines {
+            if let Some(err) = line.check_error() {
+                return (
+                    bytes_consumed,
+                    true,
+                    None,
+                    Some(Err(decode::Error::Line(err.0.into(), bytes_consumed))),
+                );
+            }
+        }
+        (bytes_consumed, false, None, Some(Ok(line)))
+    }
+
+    /// Read the next packet line from the slice and return it.
+    ///
+    /// Returns `None` if the end of iteration is reached because of one of the following:
+    ///
+    ///  * the end of the slice
+    ///  * ERR packet line encountered if [`fail_on_err_lines()`][SliceIter::fail_on_err_lines()] is true,
+    ///    which is returned as [`decode::Error::Line`] beforehand.
+    ///  * A `delimiter` packet line encountered
+    ///
+    /// As the input can't be advanced past invalid or incomplete lines, decoding errors end the iteration as well.
+    pub fn read_line(&mut self) -> Option<Result<PacketLine<'a>, decode::Error>> {
+        if self.is_done {
+            return None;
+        }
+        let (bytes_consumed, is_done, stopped_at, res) = self.decode_next_line();
+        self.data = &self.data[bytes_consumed..];
+        self.is_done = is_done;
+        self.stopped_at = stopped_at;
+        res
+    }
+
+    /// Peek the next packet line without consuming it.
+    ///
+    /// Multiple calls to peek will return the same packet line, if there is one.
+    pub fn peek_line(&mut self) -> Option<Result<PacketLine<'a>, decode::Error>> {
+        if self.is_done {
+            return None;
+        }
+        match self.decode_next_line() {
+            (_, _, _, Some(Ok(line))) => Some(Ok(line)),
+            _ => self.read_line(),
+        }
+    }
+
+    /// Returns the packet line that stopped the iteration, or
+    /// `None` if the end wasn't reached yet, at the end of the slice, or if [`fail_on_err_lines()`][SliceIter::fail_on_err_lines()] was true.
+    pub fn stopped_at(&self) -> Option<PacketLine<'static>> {
+        self.stopped_at
+    }
+
+    /// Returns true if the iteration stopped, either because one of the [`delimiters()`][SliceIter::delimiters()]
+    /// was encountered, the end of the input was reached, an `ERR` line was received or a line couldn't be decoded.
+    pub fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    /// Returns the packet lines which stop the iteration once encountered.
+    pub fn delimiters(&self) -> &[PacketLine<'static>] {
+        self.delimiters.as_slice()
+    }
+
+    /// Returns all data which wasn't consumed yet, for example binary data following a delimiter.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Reset all iteration state allowing to continue a stopped iteration that is not yet at the end of the slice.
+    ///
+    /// This can happen once a delimiter is reached.
+    pub fn reset(&mut self) {
+        self.reset_with(self.delimiters);
+    }
+
+    /// Similar to [`reset()`][SliceIter::reset()] with support to changing the `delimiters`.
+    pub fn reset_with(&mut self, delimiters: impl Into<Delimiters>) {
+        self.delimiters = delimiters.into();
+        self.is_done = false;
+        self.stopped_at = None;
+    }
+
+    /// If `value` is `true` the iterator will check for special `ERR` packet lines and stop iteration when one is encountered.
+    ///
+    /// Use [`stopped_at()`][SliceIter::stopped_at()] to inspect the cause of the end of the iteration.
+    pub fn fail_on_err_lines(&mut self, value: bool) {
+        self.fail_on_err_lines = value;
+    }
+}
+
+impl<'a> Iterator for SliceIter<'a> {
+    type Item = Result<PacketLine<'a>, decode::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_line()
+    }
+}
@@ -134,6 +134,21 @@ mod streaming {
         assert_err_display(streaming(b"0004"), "Received an invalid empty line");
     }
 
+    #[test]
+    fn error_on_hex_prefix_of_invalid_length() {
+        for input in &[&b"000"[..], b"00005"] {
+            assert!(matches!(decode::hex_prefix(input), Err(decode::Error::HexDecode(_))));
+        }
+    }
+
+    #[test]
+    fn error_on_decoding_the_band_of_empty_data() {
+        assert!(matches!(
+            PacketLine::Data(b"").decode_band(),
+            Err(git_packetline::immutable::DecodeBandError::NonDataLine)
+        ));
+    }
+
     mod incomplete {
         use git_packetline::decode::{self, streaming, Stream};
 
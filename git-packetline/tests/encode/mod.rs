@@ -100,3 +100,47 @@ mod flush_delim_response_end {
         Ok(())
     }
 }
+
+mod to_vec {
+    use crate::assert_err_display;
+    use bstr::ByteSlice;
+    use git_packetline::{encode, Channel};
+
+    #[test]
+    fn lines_are_appended_and_their_length_returned() -> crate::Result {
+        let mut out = Vec::new();
+        assert_eq!(encode::data_to_vec(b"hello", &mut out)?, encode::encoded_len(b"hello"));
+        assert_eq!(encode::text_to_vec(b"a", &mut out)?, 6);
+        assert_eq!(encode::band_to_vec(Channel::Progress, b"p", &mut out)?, 6);
+        assert_eq!(encode::error_to_vec(b"e", &mut out)?, 9);
+        assert_eq!(encode::delim_to_vec(&mut out), 4);
+        assert_eq!(encode::response_end_to_vec(&mut out), 4);
+        assert_eq!(encode::flush_to_vec(&mut out), 4);
+        assert_eq!(
+            out.as_bstr(),
+            b"0009hello0006a\n0006\x02p0009ERR e000100020000".as_bstr()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors_leave_the_buffer_untouched() {
+        let mut out = Vec::new();
+        assert_err_display(encode::data_to_vec(&[], &mut out), "Empty lines are invalid");
+        assert_err_display(
+            encode::data_to_vec(&vec![0; 65516 + 1], &mut out),
+            "Cannot encode more than 65516 bytes, got 65517",
+        );
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn encoded_len_is_exact() -> crate::Result {
+        let data = vec![b'x'; 65516];
+        let mut out = Vec::new();
+        encode::data_to_vec(&data, &mut out)?;
+        assert_eq!(out.len(), encode::encoded_len(&data));
+        assert_eq!(&out[..4], b"fff0");
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 mod sideband;
+mod slice_iter;
 
 pub mod streaming_peek_iter {
     use bstr::ByteSlice;
@@ -96,6 +97,62 @@ pub mod streaming_peek_iter {
         Ok(())
     }
 
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn reset_with_allows_reuse_for_multiple_responses() -> crate::Result {
+        let mut rd =
+            git_packetline::StreamingPeekableIter::new(&b"0005a00000005b00010005c0000"[..], &[PacketLine::Flush]);
+        assert!(!rd.is_done());
+        assert_eq!(rd.delimiters(), &[PacketLine::Flush]);
+        let lines = exhaust(&mut rd).await;
+        assert_eq!(lines, 1);
+        assert!(rd.is_done(), "the first response ends with a flush");
+        assert_eq!(rd.stopped_at(), Some(PacketLine::Flush));
+
+        rd.reset_with(&[PacketLine::Delimiter, PacketLine::Flush]);
+        assert!(!rd.is_done());
+        assert_eq!(rd.stopped_at(), None, "reset clears the previous stop reason");
+        assert_eq!(rd.delimiters(), &[PacketLine::Delimiter, PacketLine::Flush]);
+        let lines = exhaust(&mut rd).await;
+        assert_eq!(lines, 1);
+        assert_eq!(rd.stopped_at(), Some(PacketLine::Delimiter), "sections are delimited");
+        rd.reset();
+        assert_eq!(
+            rd.delimiters(),
+            &[PacketLine::Delimiter, PacketLine::Flush],
+            "delimiters are kept"
+        );
+        let lines = exhaust(&mut rd).await;
+        assert_eq!(lines, 1);
+        assert_eq!(rd.stopped_at(), Some(PacketLine::Flush));
+
+        rd.reset();
+        let res = rd.read_line().await;
+        assert_eq!(
+            res.expect("error").unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof,
+            "there is nothing left to read"
+        );
+        assert_eq!(rd.stopped_at(), None, "the end of input isn't a delimiter");
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn delimiters_can_be_chosen_at_runtime() -> crate::Result {
+        let delimiters: Vec<_> = vec![PacketLine::ResponseEnd];
+        let mut rd = git_packetline::StreamingPeekableIter::new(&b"0005a00000002"[..], delimiters.as_slice());
+        drop(delimiters);
+        let lines = exhaust(&mut rd).await;
+        assert_eq!(lines, 2, "the flush line is returned as it's no delimiter");
+        assert_eq!(rd.stopped_at(), Some(PacketLine::ResponseEnd));
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_delimiters_are_rejected() {
+        git_packetline::read::Delimiters::new(&[PacketLine::Flush; git_packetline::read::Delimiters::MAX + 1]);
+    }
+
     #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
     async fn fail_on_err_lines() -> crate::Result {
         let input = b"00010009ERR e0002";
@@ -0,0 +1,96 @@
+use crate::read::streaming_peek_iter::fixture_bytes;
+use bstr::ByteSlice;
+use git_packetline::{decode, PacketLine, SliceIter};
+
+#[test]
+fn read_from_fixture_and_continue_after_delimiter() {
+    let mut bytes = fixture_bytes("v1/fetch/01-many-refs.response");
+    bytes.extend(fixture_bytes("v1/fetch/01-many-refs.response"));
+    let mut rd = SliceIter::new(&bytes, &[PacketLine::Flush]);
+    assert_eq!(rd.by_ref().count(), 1561, "it stops after seeing the flush line");
+    assert_eq!(rd.stopped_at(), Some(PacketLine::Flush));
+    assert!(rd.is_done());
+    assert_eq!(rd.remaining().len(), bytes.len() / 2, "the delimiter was consumed");
+
+    rd.reset();
+    assert_eq!(rd.by_ref().count(), 1561, "it reads the second copy of the file");
+    assert!(rd.remaining().is_empty());
+    rd.reset();
+    assert!(
+        rd.read_line().is_none(),
+        "at the end of the slice there is nothing more to read"
+    );
+    assert_eq!(rd.stopped_at(), None);
+}
+
+#[test]
+fn lines_borrow_from_the_input_and_outlive_the_iterator() {
+    let bytes = b"0009hello0000";
+    let line = SliceIter::new(bytes, &[PacketLine::Flush])
+        .next()
+        .expect("a line")
+        .expect("valid line");
+    assert_eq!(line.as_bstr(), Some(b"hello".as_bstr()));
+    assert_eq!(
+        line.as_slice().expect("data").as_ptr(),
+        bytes[4..].as_ptr(),
+        "no copy is made"
+    );
+}
+
+#[test]
+fn peek_does_not_consume_and_follows_delimiter_logic() {
+    let mut rd = SliceIter::new(b"0005a00000005b", &[PacketLine::Flush]);
+    assert_eq!(rd.peek_line().expect("line").expect("valid"), PacketLine::Data(b"a"));
+    assert_eq!(rd.peek_line().expect("line").expect("valid"), PacketLine::Data(b"a"));
+    assert_eq!(rd.read_line().expect("line").expect("valid"), PacketLine::Data(b"a"));
+    assert!(rd.peek_line().is_none(), "we hit the delimiter");
+    assert_eq!(
+        rd.stopped_at(),
+        Some(PacketLine::Flush),
+        "peeking tracks delimiters too"
+    );
+    rd.reset();
+    assert_eq!(
+        rd.read_line().expect("line").expect("valid"),
+        PacketLine::Data(b"b"),
+        "after resetting, we get past the delimiter"
+    );
+    assert!(rd.read_line().is_none());
+}
+
+#[test]
+fn err_lines_are_data_unless_configured_otherwise() {
+    let mut rd = SliceIter::new(b"0005a0009ERR e0000", &[PacketLine::Flush]);
+    assert_eq!(rd.by_ref().count(), 2, "ERR lines are returned by default");
+
+    let mut rd = SliceIter::new(b"0005a0009ERR e0000", &[PacketLine::Flush]);
+    rd.fail_on_err_lines(true);
+    assert_eq!(rd.read_line().expect("line").expect("valid"), PacketLine::Data(b"a"));
+    match rd.peek_line().expect("an error") {
+        Err(decode::Error::Line(msg, consumed)) => {
+            assert_eq!(msg, "e");
+            assert_eq!(consumed, 9);
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+    assert!(rd.read_line().is_none(), "errors end the iteration");
+    assert_eq!(rd.stopped_at(), None);
+    assert_eq!(rd.remaining(), b"0000", "the ERR line was consumed");
+}
+
+#[test]
+fn incomplete_and_invalid_lines_end_the_iteration() {
+    let mut rd = SliceIter::new(b"0005a0009ERR", &[]);
+    assert_eq!(rd.read_line().expect("line").expect("valid"), PacketLine::Data(b"a"));
+    assert!(matches!(
+        rd.read_line().expect("an error"),
+        Err(decode::Error::NotEnoughData(2))
+    ));
+    assert!(rd.read_line().is_none());
+    assert_eq!(rd.remaining(), b"0009ERR", "nothing is consumed on error");
+
+    let mut rd = SliceIter::new(b"zzzz", &[]);
+    assert!(matches!(rd.next(), Some(Err(decode::Error::HexDecode(_)))));
+    assert!(rd.next().is_none());
+}
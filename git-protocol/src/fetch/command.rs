@@ -119,7 +119,8 @@ mod with_io {
                             .filter(|feature| match *feature {
                                 "side-band" if has_sideband_64k => false,
                                 "multi_ack" if has_multi_ack_detailed => false,
-                                "include-tag" | "no-progress" => false,
+                                // We always conclude the negotiation with 'done' once the server is ready.
+                                "include-tag" | "no-progress" | "no-done" => false,
                                 feature => server_capabilities.contains(feature),
                             })
                             .map(|s| (s, None))
@@ -192,7 +193,7 @@ mod with_io {
                             continue;
                         }
                         match *feature {
-                            "agent" => {}
+                            "agent" | "object-format" => {}
                             _ => panic!("{}: V2 feature/capability {} is not supported", self.as_str(), feature),
                         }
                     }
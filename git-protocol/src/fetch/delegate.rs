@@ -12,7 +12,17 @@ pub enum Action {
     /// Continue the typical flow of operations in this flow.
     Continue,
     /// Return at the next possible opportunity without making further requests, possibly after closing the connection.
+    ///
+    /// Note that when returned by [`DelegateBlocking::negotiate()`], this concludes the negotiation by sending 'done'
+    /// which causes the server to send a pack.
     Cancel,
+    /// Return right away without receiving a pack, after telling the server that we are done with our requests
+    /// in stateful connections to let it shut down cleanly.
+    ///
+    /// This is the same as [`Action::Cancel`] unless returned by [`DelegateBlocking::negotiate()`], and allows to
+    /// stop the negotiation at any round, for instance to learn about the server's state without fetching anything.
+    /// Note that in protocol V1 servers may consider the connection closed unexpectedly if this happens during negotiation.
+    Abort,
 }
 
 /// What to do after [`DelegateBlocking::prepare_ls_refs`].
@@ -40,6 +50,15 @@ pub trait DelegateBlocking {
     fn handshake_extra_parameters(&self) -> Vec<(String, Option<String>)> {
         Vec::new()
     }
+
+    /// Return the kind of hash used by the local repository to identify objects.
+    ///
+    /// The server must use the same kind of hash as advertised with its `object-format` capability, or SHA1 if there is none,
+    /// as object ids cannot be converted. Otherwise the fetch fails right after the handshake.
+    fn object_hash(&self) -> git_hash::Kind {
+        git_hash::Kind::Sha1
+    }
+
     /// Called before invoking 'ls-refs' on the server to allow providing it with additional `arguments` and to enable `features`.
     /// If the server `capabilities` don't match the requirements abort with an error to abort the entire fetch operation.
     ///
@@ -106,10 +125,14 @@ pub trait DelegateBlocking {
     /// the `previous` response of the server to see which objects they acknowledged to have. You have to maintain
     /// enough state to be able to walk down from your tips on each call, if they are not in common, and keep setting `have`
     /// for those which are in common if that helps teaching the server about our state and to acknowledge their existence on _their_ end.
-    /// This method is called until the other side signals they are ready to send a pack.
+    /// This method is called until the other side signals they are ready to send a pack, after which the negotiation is
+    /// concluded without calling it again.
     /// Return `Action::Close` if you want to give up before finding a common base. This can happen if the remote repository
     /// has radically changed so there are no bases, or they are very far in the past, causing all objects to be sent.
     ///
+    /// Return [`Action::Abort`] to end the interaction without sending 'done' and without receiving a pack, no matter
+    /// if `previous` is set or not.
+    ///
     /// ### 'ref-in-want'
     ///
     /// The 'ref-in-want' feature requires special attention: 'want-refs' need to be
@@ -125,6 +148,10 @@ impl<T: DelegateBlocking> DelegateBlocking for Box<T> {
         self.deref().handshake_extra_parameters()
     }
 
+    fn object_hash(&self) -> git_hash::Kind {
+        self.deref().object_hash()
+    }
+
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
@@ -159,6 +186,10 @@ impl<T: DelegateBlocking> DelegateBlocking for &mut T {
         self.deref().handshake_extra_parameters()
     }
 
+    fn object_hash(&self) -> git_hash::Kind {
+        self.deref().object_hash()
+    }
+
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
@@ -190,7 +221,7 @@ impl<T: DelegateBlocking> DelegateBlocking for &mut T {
 
 #[cfg(feature = "blocking-client")]
 mod blocking_io {
-    use crate::fetch::{DelegateBlocking, Ref, Response};
+    use crate::fetch::{DelegateBlocking, Negotiated, Ref, Response};
     use git_features::progress::Progress;
     use std::{
         io::{self, BufRead},
@@ -211,12 +242,15 @@ mod blocking_io {
         ///
         /// `refs` of the remote side are provided for convenience, along with the parsed `previous` response in case you want
         /// to check additional acks.
+        /// `negotiated` contains the features agreed upon with the server, for instance to learn if the pack may be thin
+        /// and needs its missing base objects to be added.
         fn receive_pack(
             &mut self,
             input: impl io::BufRead,
             progress: impl Progress,
             refs: &[Ref],
             previous: &Response,
+            negotiated: &Negotiated,
         ) -> io::Result<()>;
     }
 
@@ -227,8 +261,10 @@ mod blocking_io {
             progress: impl Progress,
             refs: &[Ref],
             previous: &Response,
+            negotiated: &Negotiated,
         ) -> io::Result<()> {
-            self.deref_mut().receive_pack(input, progress, refs, previous)
+            self.deref_mut()
+                .receive_pack(input, progress, refs, previous, negotiated)
         }
     }
 
@@ -239,8 +275,10 @@ mod blocking_io {
             progress: impl Progress,
             refs: &[Ref],
             previous: &Response,
+            negotiated: &Negotiated,
         ) -> io::Result<()> {
-            self.deref_mut().receive_pack(input, progress, refs, previous)
+            self.deref_mut()
+                .receive_pack(input, progress, refs, previous, negotiated)
         }
     }
 }
@@ -249,7 +287,7 @@ pub use blocking_io::Delegate;
 
 #[cfg(feature = "async-client")]
 mod async_io {
-    use crate::fetch::{DelegateBlocking, Ref, Response};
+    use crate::fetch::{DelegateBlocking, Negotiated, Ref, Response};
     use async_trait::async_trait;
     use futures_io::AsyncBufRead;
     use git_features::progress::Progress;
@@ -271,12 +309,15 @@ mod async_io {
         ///
         /// `refs` of the remote side are provided for convenience, along with the parsed `previous` response in case you want
         /// to check additional acks.
+        /// `negotiated` contains the features agreed upon with the server, for instance to learn if the pack may be thin
+        /// and needs its missing base objects to be added.
         async fn receive_pack(
             &mut self,
             input: impl AsyncBufRead + Unpin + 'async_trait,
             progress: impl Progress,
             refs: &[Ref],
             previous: &Response,
+            negotiated: &Negotiated,
         ) -> io::Result<()>;
     }
     #[async_trait(?Send)]
@@ -287,8 +328,11 @@ mod async_io {
             progress: impl Progress,
             refs: &[Ref],
             previous: &Response,
+            negotiated: &Negotiated,
         ) -> io::Result<()> {
-            self.deref_mut().receive_pack(input, progress, refs, previous).await
+            self.deref_mut()
+                .receive_pack(input, progress, refs, previous, negotiated)
+                .await
         }
     }
 
@@ -300,8 +344,11 @@ mod async_io {
             progress: impl Progress,
             refs: &[Ref],
             previous: &Response,
+            negotiated: &Negotiated,
         ) -> io::Result<()> {
-            self.deref_mut().receive_pack(input, progress, refs, previous).await
+            self.deref_mut()
+                .receive_pack(input, progress, refs, previous, negotiated)
+                .await
         }
     }
 }
@@ -2,6 +2,7 @@ use crate::{
     credentials,
     fetch::{refs, response},
 };
+use bstr::BString;
 use git_transport::client;
 use quick_error::quick_error;
 use std::io;
@@ -32,6 +33,12 @@ quick_error! {
         TransportProtocolPolicyViolation{actual_version: git_transport::Protocol} {
             display("The transport didn't accept the advertised server version {:?} and closed the connection client side", actual_version)
         }
+        UnsupportedObjectFormat{name: BString} {
+            display("The server uses the object format '{}' which is not supported", name)
+        }
+        ObjectFormatMismatch{local: git_hash::Kind, remote: git_hash::Kind} {
+            display("The server uses {:?} to identify objects, but the local repository uses {:?}", remote, local)
+        }
         Ref(err: refs::Error) {
             display("A reference could not be parsed or invariants were not met")
             from()
@@ -1,15 +1,36 @@
 use crate::{
     credentials,
-    fetch::{refs, Action, Arguments, Command, Delegate, Error, LsRefsAction, Response},
-};
-use git_features::{progress, progress::Progress};
-use git_transport::{
-    client,
-    client::{SetServiceResponse, TransportV2Ext},
-    Service,
+    fetch::{
+        command::Feature, handshake, negotiated::Sideband, refs, Action, Arguments, Command, Delegate, Error,
+        LsRefsAction, Negotiated, Response,
+    },
 };
+use git_features::progress::Progress;
+use git_transport::{client, client::TransportV2Ext};
 use maybe_async::maybe_async;
-use std::io;
+
+/// Define what to do with the connection once a [`fetch()`] completes.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum FetchConnection {
+    /// Inform the server that no further commands will be sent once the fetch completed successfully, or once the
+    /// delegate decided not to fetch after listing references.
+    ///
+    /// This is only relevant for stateful transports speaking protocol V2, as well as for early terminations in protocol V1.
+    /// Note that in most client-side failure modes, the end of the interaction is indicated to the server automatically.
+    TerminateOnSuccessfulCompletion,
+    /// Keep the connection open after a successful fetch to allow subsequent `ls-refs` or `fetch` commands on the same
+    /// stateful connection by calling [`fetch()`] again with the same transport, which skips the handshake in protocol V2.
+    ///
+    /// This has no effect on stateless transports, like the ones over HTTP, and on protocol V1 which doesn't support
+    /// more than one command per connection.
+    AllowReuse,
+}
+
+impl Default for FetchConnection {
+    fn default() -> Self {
+        FetchConnection::TerminateOnSuccessfulCompletion
+    }
+}
 
 /// Perform a 'fetch' operation with the server using `transport`, with `delegate` handling all server interactions.
 /// **Note** that `delegate` has blocking operations and thus this entire call should be on an executor which can handle
@@ -18,89 +39,35 @@ use std::io;
 /// * `authenticate(operation_to_perform)` is used to receive credentials for the connection and potentially store it
 ///   if the server indicates 'permission denied'. Note that not all transport support authentication or authorization.
 /// * `progress` is used to emit progress messages.
+/// * `fetch_mode` determines whether the connection is kept open for subsequent commands once the operation completes.
+///    Pass `&mut transport` to be able to use it again.
 ///
 /// _Note_ that depending on the `delegate`, the actual action performed can be `ls-refs`, `clone` or `fetch`.
 #[maybe_async]
 pub async fn fetch<F, D, T>(
     mut transport: T,
     mut delegate: D,
-    mut authenticate: F,
+    authenticate: F,
     mut progress: impl Progress,
+    fetch_mode: FetchConnection,
 ) -> Result<(), Error>
 where
     F: FnMut(credentials::Action<'_>) -> credentials::Result,
     D: Delegate,
     T: client::Transport,
 {
-    let (protocol_version, parsed_refs, capabilities) = {
-        progress.init(None, progress::steps());
-        progress.set_name("handshake");
-        progress.step();
-
-        let extra_parameters = delegate.handshake_extra_parameters();
-        let extra_parameters: Vec<_> = extra_parameters
-            .iter()
-            .map(|(k, v)| (k.as_str(), v.as_ref().map(|s| s.as_str())))
-            .collect();
-        let supported_versions: Vec<_> = transport.supported_protocol_versions().into();
-
-        let result = transport.handshake(Service::UploadPack, &extra_parameters).await;
-        let SetServiceResponse {
-            actual_protocol,
-            capabilities,
-            refs,
-        } = match result {
-            Ok(v) => Ok(v),
-            Err(client::Error::Io { ref err }) if err.kind() == io::ErrorKind::PermissionDenied => {
-                drop(result); // needed to workaround this: https://github.com/rust-lang/rust/issues/76149
-                let url = transport.to_url();
-                progress.set_name("authentication");
-                let credentials::Outcome { identity, next } =
-                    authenticate(credentials::Action::Fill(&url))?.expect("FILL provides an identity");
-                transport.set_identity(identity)?;
-                progress.step();
-                progress.set_name("handshake (authenticated)");
-                match transport.handshake(Service::UploadPack, &extra_parameters).await {
-                    Ok(v) => {
-                        authenticate(next.approve())?;
-                        Ok(v)
-                    }
-                    // Still no permission? Reject the credentials.
-                    Err(client::Error::Io { err }) if err.kind() == io::ErrorKind::PermissionDenied => {
-                        authenticate(next.reject())?;
-                        Err(client::Error::Io { err })
-                    }
-                    // Otherwise, do nothing, as we don't know if it actually got to try the credentials.
-                    // If they were previously stored, they remain. In the worst case, the user has to enter them again
-                    // next time they try.
-                    Err(err) => Err(err),
-                }
-            }
-            Err(err) => Err(err),
-        }?;
-
-        if !supported_versions.is_empty() && !supported_versions.contains(&actual_protocol) {
-            return Err(Error::TransportProtocolPolicyViolation {
-                actual_version: actual_protocol,
-            });
-        }
-
-        let parsed_refs = match refs {
-            Some(mut refs) => {
-                assert_eq!(
-                    actual_protocol,
-                    git_transport::Protocol::V1,
-                    "Only V1 auto-responds with refs"
-                );
-                Some(
-                    refs::from_v1_refs_received_as_part_of_handshake_and_capabilities(&mut refs, capabilities.iter())
-                        .await?,
-                )
-            }
-            None => None,
-        };
-        (actual_protocol, parsed_refs, capabilities)
-    }; // this scope is needed, see https://github.com/rust-lang/rust/issues/76149
+    let handshake::Outcome {
+        server_protocol_version: protocol_version,
+        refs: parsed_refs,
+        capabilities,
+    } = handshake(
+        &mut transport,
+        authenticate,
+        delegate.handshake_extra_parameters(),
+        &mut progress,
+    )
+    .await?;
+    let (object_hash, object_format) = server_object_hash(&capabilities, delegate.object_hash())?;
 
     let parsed_refs = match parsed_refs {
         Some(refs) => refs,
@@ -113,6 +80,7 @@ where
 
             let ls_refs = Command::LsRefs;
             let mut ls_features = ls_refs.default_features(protocol_version, &capabilities);
+            ls_features.extend(object_format);
             let mut ls_args = ls_refs.initial_arguments(&ls_features);
             match delegate.prepare_ls_refs(&capabilities, &mut ls_args, &mut ls_features) {
                 Ok(LsRefsAction::Skip) => Vec::new(),
@@ -149,8 +117,18 @@ where
 
     let fetch = Command::Fetch;
     let mut fetch_features = fetch.default_features(protocol_version, &capabilities);
+    fetch_features.extend(object_format);
     match delegate.prepare_fetch(protocol_version, &capabilities, &mut fetch_features, &parsed_refs) {
-        Ok(Action::Cancel) => return indicate_end_of_interaction(transport).await,
+        Ok(Action::Cancel) => {
+            return if protocol_version == git_transport::Protocol::V1
+                || fetch_mode == FetchConnection::TerminateOnSuccessfulCompletion
+            {
+                indicate_end_of_interaction(transport).await
+            } else {
+                Ok(())
+            };
+        }
+        Ok(Action::Abort) => return indicate_end_of_interaction(transport).await,
         Ok(Action::Continue) => {
             fetch.validate_argument_prefixes_or_panic(protocol_version, &capabilities, &[], &fetch_features);
         }
@@ -161,7 +139,8 @@ where
     }
 
     Response::check_required_features(protocol_version, &fetch_features)?;
-    let sideband_all = fetch_features.iter().any(|(n, _)| *n == "sideband-all");
+    let negotiated = Negotiated::new(protocol_version, &fetch_features, object_hash);
+    let sideband_all = negotiated.sideband == Some(Sideband::All);
     let mut arguments = Arguments::new(protocol_version, fetch_features);
     let mut previous_response = None::<Response>;
     let mut round = 1;
@@ -169,32 +148,67 @@ where
         progress.step();
         progress.set_name(format!("negotiate (round {})", round));
         round += 1;
-        let action = delegate.negotiate(&parsed_refs, &mut arguments, previous_response.as_ref())?;
+        let action = match previous_response.as_ref() {
+            // The server won't listen to more 'have's, so all that's left is to conclude the negotiation.
+            Some(response) if response.is_ready() => Action::Cancel,
+            previous_response => delegate.negotiate(&parsed_refs, &mut arguments, previous_response)?,
+        };
+        if action == Action::Abort {
+            return indicate_end_of_interaction(transport).await;
+        }
         let mut reader = arguments.send(&mut transport, action == Action::Cancel).await?;
         if sideband_all {
             setup_remote_progress(&mut progress, &mut reader);
         }
-        let response = Response::from_line_reader(protocol_version, &mut reader).await?;
+        let response = Response::from_line_reader(protocol_version, &mut reader, action == Action::Cancel).await?;
         previous_response = if response.has_pack() {
             progress.step();
             progress.set_name("receiving pack");
             if !sideband_all {
                 setup_remote_progress(&mut progress, &mut reader);
             }
-            delegate.receive_pack(reader, progress, &parsed_refs, &response).await?;
+            delegate
+                .receive_pack(reader, progress, &parsed_refs, &response, &negotiated)
+                .await?;
             break 'negotiation;
         } else {
             match action {
                 Action::Cancel => break 'negotiation,
                 Action::Continue => Some(response),
+                Action::Abort => unreachable!("aborting happens before sending arguments"),
             }
         }
     }
+    if protocol_version == git_transport::Protocol::V2 && fetch_mode == FetchConnection::TerminateOnSuccessfulCompletion
+    {
+        indicate_end_of_interaction(transport).await?;
+    }
     Ok(())
 }
 
+/// Return the kind of hash the server uses according to its `object-format` capability, or SHA1 if it doesn't advertise one,
+/// along with the feature to send to acknowledge the format if it was advertised.
+/// Fail if it's unknown or doesn't match the `local` kind of hash.
+fn server_object_hash(
+    server: &client::Capabilities,
+    local: git_hash::Kind,
+) -> Result<(git_hash::Kind, Option<Feature>), Error> {
+    let (remote, feature) = match server.capability("object-format").as_ref().and_then(|c| c.value()) {
+        Some(name) if name == "sha1" => (git_hash::Kind::Sha1, Some(("object-format", Some("sha1")))),
+        Some(name) => return Err(Error::UnsupportedObjectFormat { name: name.to_owned() }),
+        None => (git_hash::Kind::Sha1, None),
+    };
+    if remote != local {
+        return Err(Error::ObjectFormatMismatch { local, remote });
+    }
+    Ok((remote, feature))
+}
+
+/// Tell the server that no further commands will be sent via `transport`, which is only needed for stateful connections
+/// which are kept open after a [`fetch()`] with [`FetchConnection::AllowReuse`] or commands sent with
+/// [`invoke()`][crate::fetch::invoke()].
 #[maybe_async]
-async fn indicate_end_of_interaction(mut transport: impl client::Transport) -> Result<(), Error> {
+pub async fn indicate_end_of_interaction(mut transport: impl client::Transport) -> Result<(), Error> {
     // An empty request marks the (early) end of the interaction. Only relevant in stateful transports though.
     if transport.is_stateful() {
         transport
@@ -0,0 +1,118 @@
+use crate::fetch::Ref;
+use git_transport::client::Capabilities;
+
+/// The result of the [`handshake()`][crate::fetch::handshake()] with the server.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The protocol version the server responded with, which might be lower than the desired one.
+    pub server_protocol_version: git_transport::Protocol,
+    /// The references the server advertised as part of the handshake, which happens only in protocol V1.
+    pub refs: Option<Vec<Ref>>,
+    /// The capabilities the server advertised, which in protocol V2 includes the commands it supports.
+    pub capabilities: Capabilities,
+}
+
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub(crate) mod function {
+    use super::Outcome;
+    use crate::{credentials, fetch::refs, fetch::Error};
+    use git_features::{progress, progress::Progress};
+    use git_transport::{client, client::SetServiceResponse, Service};
+    use maybe_async::maybe_async;
+    use std::io;
+
+    /// Perform a handshake with the server on the other side of `transport`, with `authenticate` being used if authentication
+    /// turns out to be required, and `extra_parameters` being the parameters to provide during the handshake.
+    ///
+    /// The outcome contains the capabilities of the server which are required to [invoke][crate::fetch::invoke()] commands.
+    /// Pass `&mut transport` to be able to use it again.
+    #[maybe_async]
+    pub async fn handshake<F, T>(
+        mut transport: T,
+        mut authenticate: F,
+        extra_parameters: Vec<(String, Option<String>)>,
+        progress: &mut impl Progress,
+    ) -> Result<Outcome, Error>
+    where
+        F: FnMut(credentials::Action<'_>) -> credentials::Result,
+        T: client::Transport,
+    {
+        let (server_protocol_version, refs, capabilities) = {
+            progress.init(None, progress::steps());
+            progress.set_name("handshake");
+            progress.step();
+
+            let extra_parameters: Vec<_> = extra_parameters
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_ref().map(|s| s.as_str())))
+                .collect();
+            let supported_versions: Vec<_> = transport.supported_protocol_versions().into();
+
+            let result = transport.handshake(Service::UploadPack, &extra_parameters).await;
+            let SetServiceResponse {
+                actual_protocol,
+                capabilities,
+                refs,
+            } = match result {
+                Ok(v) => Ok(v),
+                Err(client::Error::Io { ref err }) if err.kind() == io::ErrorKind::PermissionDenied => {
+                    drop(result); // needed to workaround this: https://github.com/rust-lang/rust/issues/76149
+                    let url = transport.to_url();
+                    progress.set_name("authentication");
+                    let credentials::Outcome { identity, next } =
+                        authenticate(credentials::Action::Fill(&url))?.expect("FILL provides an identity");
+                    transport.set_identity(identity)?;
+                    progress.step();
+                    progress.set_name("handshake (authenticated)");
+                    match transport.handshake(Service::UploadPack, &extra_parameters).await {
+                        Ok(v) => {
+                            authenticate(next.approve())?;
+                            Ok(v)
+                        }
+                        // Still no permission? Reject the credentials.
+                        Err(client::Error::Io { err }) if err.kind() == io::ErrorKind::PermissionDenied => {
+                            authenticate(next.reject())?;
+                            Err(client::Error::Io { err })
+                        }
+                        // Otherwise, do nothing, as we don't know if it actually got to try the credentials.
+                        // If they were previously stored, they remain. In the worst case, the user has to enter them again
+                        // next time they try.
+                        Err(err) => Err(err),
+                    }
+                }
+                Err(err) => Err(err),
+            }?;
+
+            if !supported_versions.is_empty() && !supported_versions.contains(&actual_protocol) {
+                return Err(Error::TransportProtocolPolicyViolation {
+                    actual_version: actual_protocol,
+                });
+            }
+
+            let parsed_refs = match refs {
+                Some(mut refs) => {
+                    assert_eq!(
+                        actual_protocol,
+                        git_transport::Protocol::V1,
+                        "Only V1 auto-responds with refs"
+                    );
+                    Some(
+                        refs::from_v1_refs_received_as_part_of_handshake_and_capabilities(
+                            &mut refs,
+                            capabilities.iter(),
+                        )
+                        .await?,
+                    )
+                }
+                None => None,
+            };
+            (actual_protocol, parsed_refs, capabilities)
+        }; // this scope is needed, see https://github.com/rust-lang/rust/issues/76149
+
+        Ok(Outcome {
+            server_protocol_version,
+            refs,
+            capabilities,
+        })
+    }
+}
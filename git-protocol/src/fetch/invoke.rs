@@ -0,0 +1,124 @@
+use bstr::BString;
+use git_transport::client;
+use quick_error::quick_error;
+use std::io;
+
+quick_error! {
+    /// The error used in [`invoke()`][crate::fetch::invoke()].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Io(err: io::Error) {
+            display("Failed to read the response of the server")
+            from()
+            source(err)
+        }
+        Transport(err: client::Error) {
+            display("An error occurred on the transport layer while invoking a command")
+            from()
+            source(err)
+        }
+        UnsupportedCommand{command: String} {
+            display("The server does not advertise the '{}' command", command)
+        }
+        UnsupportedFeature{command: String, feature: String} {
+            display("The '{}' command of the server does not support the '{}' feature", command, feature)
+        }
+    }
+}
+
+/// A section of a response to an [invoked][crate::fetch::invoke()] command, with each line stripped of its trailing newline.
+pub type Section = Vec<BString>;
+
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub(crate) mod function {
+    use super::{Error, Section};
+    use bstr::BString;
+    #[cfg(feature = "async-client")]
+    use futures_lite::AsyncBufReadExt;
+    use git_transport::{
+        client,
+        client::{ExtendedBufRead, TransportV2Ext},
+        Protocol,
+    };
+    use maybe_async::maybe_async;
+    #[cfg(feature = "blocking-client")]
+    use std::io::BufRead;
+
+    /// Fail if `command` or one of its `features` isn't advertised in the `server` capabilities of protocol V2.
+    ///
+    /// The `agent` and `object-format` features are always allowed as they apply to all commands.
+    fn validate(server: &client::Capabilities, command: &str, features: &[(&str, Option<&str>)]) -> Result<(), Error> {
+        let capability = server.capability(command).ok_or_else(|| Error::UnsupportedCommand {
+            command: command.into(),
+        })?;
+        for (feature, _) in features {
+            match *feature {
+                "agent" | "object-format" => {}
+                feature => {
+                    if !capability.values().into_iter().flatten().any(|v| v == feature) {
+                        return Err(Error::UnsupportedFeature {
+                            command: command.into(),
+                            feature: feature.into(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Invoke the protocol V2 `command` on the server connected via `transport`, after validating that it and all of its
+    /// `features` are advertised in the `server` capabilities as obtained by a [`handshake()`][crate::fetch::handshake()],
+    /// and send `arguments` as is.
+    ///
+    /// This allows to use commands that don't have special support in this crate yet. The response is returned in
+    /// sections as separated by delimiter packets, each containing the lines the server sent.
+    /// Pass `&mut transport` to be able to use it again, for instance to indicate the end of the interaction.
+    #[maybe_async]
+    pub async fn invoke<T>(
+        mut transport: T,
+        server: &client::Capabilities,
+        command: &str,
+        features: Vec<(&str, Option<&str>)>,
+        arguments: Vec<BString>,
+    ) -> Result<Vec<Section>, Error>
+    where
+        T: client::Transport,
+    {
+        validate(server, command, &features)?;
+        let mut reader = transport
+            .invoke(
+                command,
+                features.into_iter(),
+                if arguments.is_empty() {
+                    None
+                } else {
+                    Some(arguments.into_iter())
+                },
+            )
+            .await?;
+
+        reader.reset(Protocol::V2);
+        let mut sections = vec![Section::new()];
+        loop {
+            // Each call yields the data of exactly one packet line, which may or may not end with a newline.
+            let line = reader.fill_buf().await?;
+            let consumed = line.len();
+            if consumed == 0 {
+                if reader.stopped_at() != Some(client::MessageKind::Delimiter) {
+                    break;
+                }
+                reader.reset(Protocol::V2);
+                sections.push(Section::new());
+                continue;
+            }
+            sections
+                .last_mut()
+                .expect("at least one section")
+                .push(line.strip_suffix(b"\n").unwrap_or(line).into());
+            reader.consume(consumed);
+        }
+        Ok(sections)
+    }
+}
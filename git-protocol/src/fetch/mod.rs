@@ -19,6 +19,25 @@ pub use delegate::{Action, DelegateBlocking, LsRefsAction};
 mod error;
 pub use error::Error;
 
+///
+pub mod handshake;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use handshake::function::handshake;
+
+///
+pub mod invoke;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use invoke::function::invoke;
+
+///
+pub mod object_info;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use object_info::function::object_info;
+
+///
+pub mod negotiated;
+pub use negotiated::Negotiated;
+
 ///
 pub mod refs;
 pub use refs::Ref;
@@ -30,7 +49,7 @@ pub use response::Response;
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
 mod function;
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
-pub use function::fetch;
+pub use function::{fetch, indicate_end_of_interaction, FetchConnection};
 
 #[cfg(test)]
 mod tests;
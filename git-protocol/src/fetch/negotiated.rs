@@ -0,0 +1,75 @@
+use crate::fetch::command::Feature;
+use git_transport::Protocol;
+
+/// The kind of side-band used to multiplex the pack with progress and error messages.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Sideband {
+    /// Packet lines carry up to 1000 bytes of data, as negotiated with `side-band` in V1.
+    Basic,
+    /// Packet lines carry up to 65520 bytes of data, as negotiated with `side-band-64k` in V1 and always used in V2.
+    Large,
+    /// Like [`Large`][Sideband::Large], but all sections of the response are multiplexed, as negotiated with `sideband-all` in V2.
+    All,
+}
+
+/// The features agreed upon with the server for the `fetch` command, as passed to
+/// [`Delegate::receive_pack()`][crate::fetch::Delegate::receive_pack()] to help configuring how to handle the pack.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Negotiated {
+    /// The protocol version used for fetching.
+    pub version: Protocol,
+    /// The side-band the pack is received with, or `None` if it's received as is.
+    pub sideband: Option<Sideband>,
+    /// If true, the pack may be thin and contain deltas against base objects that aren't part of it, but which are
+    /// expected to exist locally. These have to be added to complete the pack.
+    pub thin_pack: bool,
+    /// If true, the pack may contain deltas referring to their base object by offset.
+    pub ofs_delta: bool,
+    /// If true, annotated tags pointing to sent objects are included in the pack, even if they weren't asked for.
+    pub include_tag: bool,
+    /// If true, the server was asked not to send progress messages.
+    pub no_progress: bool,
+    /// The kind of hash used to identify objects, as advertised by the server and matching the one of the local repository.
+    pub object_hash: git_hash::Kind,
+    /// All features sent with the `fetch` command, including the ones above.
+    pub features: Vec<Feature>,
+}
+
+impl Negotiated {
+    #[cfg(any(feature = "async-client", feature = "blocking-client"))]
+    pub(crate) fn new(version: Protocol, features: &[Feature], object_hash: git_hash::Kind) -> Self {
+        let has = |name: &str| features.iter().any(|(feature, _)| *feature == name);
+        let (sideband, always_on) = match version {
+            Protocol::V1 => (
+                if has("side-band-64k") {
+                    Some(Sideband::Large)
+                } else if has("side-band") {
+                    Some(Sideband::Basic)
+                } else {
+                    None
+                },
+                false,
+            ),
+            Protocol::V2 => (
+                Some(if has("sideband-all") {
+                    Sideband::All
+                } else {
+                    Sideband::Large
+                }),
+                // these are always sent as arguments
+                true,
+            ),
+        };
+        Negotiated {
+            version,
+            sideband,
+            thin_pack: always_on || has("thin-pack"),
+            ofs_delta: always_on || has("ofs-delta"),
+            include_tag: always_on || has("include-tag"),
+            no_progress: has("no-progress"),
+            object_hash,
+            features: features.to_owned(),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::fetch::invoke;
+use bstr::BString;
+use quick_error::quick_error;
+
+quick_error! {
+    /// The error used in [`object_info()`][crate::fetch::object_info()].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Invoke(err: invoke::Error) {
+            display("Could not invoke the 'object-info' command")
+            from()
+            source(err)
+        }
+        UnexpectedSections(count: usize) {
+            display("Expected a single section in the response, got {}", count)
+        }
+        MissingAttributes {
+            display("The server response did not start with the list of attributes")
+        }
+        Parse(line: BString) {
+            display("Could not parse object information line '{}'", line)
+        }
+    }
+}
+
+/// The size of an object as reported by the server, which is `None` if the server doesn't have the object.
+pub type Size = Option<u64>;
+
+/// Parse the lines of an `object-info` response to a request for the `size` attribute, which is
+/// a line with the attributes followed by one line per object with its id and size.
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+fn parse_size_response(lines: &[BString]) -> Result<Vec<(git_hash::ObjectId, Size)>, Error> {
+    use bstr::ByteSlice;
+    let (attributes, lines) = lines.split_first().ok_or(Error::MissingAttributes)?;
+    if attributes != "size" {
+        return Err(Error::MissingAttributes);
+    }
+    lines
+        .iter()
+        .map(|line| {
+            let parse_error = || Error::Parse(line.to_owned());
+            let mut tokens = line.splitn(2, |b| *b == b' ');
+            let id = tokens
+                .next()
+                .and_then(|hex| git_hash::ObjectId::from_hex(hex).ok())
+                .ok_or_else(parse_error)?;
+            let size = match tokens.next().ok_or_else(parse_error)? {
+                // The server responds with an empty size for objects it doesn't have.
+                b"" => None,
+                size => Some(
+                    size.to_str()
+                        .ok()
+                        .and_then(|size| size.parse().ok())
+                        .ok_or_else(parse_error)?,
+                ),
+            };
+            Ok((id, size))
+        })
+        .collect()
+}
+
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub(crate) mod function {
+    use super::{parse_size_response, Error, Size};
+    use crate::fetch::{agent, invoke};
+    use git_hash::ObjectId;
+    use git_transport::client;
+    use maybe_async::maybe_async;
+
+    /// Use the protocol V2 `object-info` command to ask the server connected via `transport` for the size of the objects with
+    /// the given `ids` without fetching them, returning one `(id, size)` pair per requested object in order.
+    ///
+    /// `server` are the capabilities obtained by a [`handshake()`][crate::fetch::handshake()], which have to advertise
+    /// the `object-info` command.
+    /// Pass `&mut transport` to be able to use it again.
+    #[maybe_async]
+    pub async fn object_info<T>(
+        transport: T,
+        server: &client::Capabilities,
+        ids: impl IntoIterator<Item = ObjectId>,
+    ) -> Result<Vec<(ObjectId, Size)>, Error>
+    where
+        T: client::Transport,
+    {
+        let arguments = Some("size".into())
+            .into_iter()
+            .chain(ids.into_iter().map(|id| format!("oid {}", id).into()))
+            .collect();
+        let sections = invoke(transport, server, "object-info", vec![agent()], arguments).await?;
+        match sections.as_slice() {
+            [lines] => parse_size_response(lines),
+            _ => Err(Error::UnexpectedSections(sections.len())),
+        }
+    }
+}
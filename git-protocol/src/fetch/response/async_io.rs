@@ -31,15 +31,21 @@ async fn parse_v2_section<T>(
 
 impl Response {
     /// Parse a response of the given `version` of the protocol from `reader`.
+    ///
+    /// `client_expects_pack` must be true if the negotiation was concluded with `done`, to know that in V1 a pack follows
+    /// the acknowledgements. Otherwise only the acknowledgements of the current round of negotiation are read, as stateful
+    /// servers wait for more `have` lines afterwards.
     pub async fn from_line_reader(
         version: Protocol,
         reader: &mut (impl client::ExtendedBufRead + Unpin),
+        client_expects_pack: bool,
     ) -> Result<Response, response::Error> {
         match version {
             Protocol::V1 => {
                 let mut line = String::new();
                 let mut acks = Vec::<Acknowledgement>::new();
                 let mut shallows = Vec::<ShallowUpdate>::new();
+                let mut is_ready = false;
                 let has_pack = 'lines: loop {
                     line.clear();
                     let peeked_line = match reader.peek_data_line().await {
@@ -74,13 +80,20 @@ impl Response {
                     if Response::parse_v1_ack_or_shallow_or_assume_pack(&mut acks, &mut shallows, &peeked_line) {
                         break 'lines true;
                     }
+                    let is_round_end = !client_expects_pack && Response::is_v1_negotiation_round_end(&peeked_line);
                     assert_ne!(reader.read_line(&mut line).await?, 0, "consuming a peeked line works");
+                    if is_round_end {
+                        is_ready = line.starts_with("ACK ");
+                        break 'lines false;
+                    }
                 };
+                is_ready |= acks.contains(&Acknowledgement::Ready);
                 Ok(Response {
                     acks,
                     shallows,
                     wanted_refs: vec![],
                     has_pack,
+                    is_ready,
                 })
             }
             Protocol::V2 => {
@@ -127,6 +140,7 @@ impl Response {
                     shallows,
                     wanted_refs,
                     has_pack,
+                    is_ready: false,
                 })
             }
         }
@@ -32,12 +32,16 @@ quick_error! {
 }
 
 /// An 'ACK' line received from the server.
+///
+/// In V1, the status following the id of an 'ACK' line depends on the negotiated `multi_ack` mode: `continue` is sent with
+/// `multi_ack`, and `common` as well as `ready` are sent with `multi_ack_detailed`. Without either, a single 'ACK' without status
+/// is sent for the first object in common.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub enum Acknowledgement {
-    /// The contained `id` is in common.
+    /// The contained `id` is in common, as indicated by an 'ACK' line without status, or with the `common` or `continue` status.
     Common(git_hash::ObjectId),
-    /// The server is ready to receive more lines.
+    /// The server is ready to send a pack, and no more `have` lines need to be sent.
     Ready,
     /// The server isn't ready yet.
     Nak,
@@ -97,7 +101,7 @@ impl Acknowledgement {
                     };
                     if let Some(description) = description {
                         match description {
-                            "common" => {}
+                            "common" | "continue" => {}
                             "ready" => return Ok(Acknowledgement::Ready),
                             _ => return Err(Error::UnknownLineType(line.to_owned())),
                         }
@@ -138,6 +142,7 @@ pub struct Response {
     shallows: Vec<ShallowUpdate>,
     wanted_refs: Vec<WantedRef>,
     has_pack: bool,
+    is_ready: bool,
 }
 
 impl Response {
@@ -146,6 +151,14 @@ impl Response {
         self.has_pack
     }
 
+    /// Return true if the server indicated that it is ready to send a pack, which means no more `have` lines should be
+    /// sent and the negotiation must be concluded with `done`.
+    ///
+    /// This is only relevant in V1, as servers using V2 send the pack right away once they are ready.
+    pub fn is_ready(&self) -> bool {
+        self.is_ready
+    }
+
     /// Return an error if the given `features` don't contain the required ones for the given `version` of the protocol.
     ///
     /// Even though technically any set of features supported by the server could work, we only implement the ones that
@@ -154,10 +167,6 @@ impl Response {
         match version {
             Protocol::V1 => {
                 let has = |name: &str| features.iter().any(|f| f.0 == name);
-                // Let's focus on V2 standards, and simply not support old servers to keep our code simpler
-                if !has("multi_ack_detailed") {
-                    return Err(Error::MissingServerCapability("multi_ack_detailed"));
-                }
                 // It's easy to NOT do sideband for us, but then again, everyone supports it.
                 // CORRECTION: If side-band is off, it would send the packfile without packet line encoding,
                 // which is nothing we ever want to deal with (despite it being more efficient). In V2, this
@@ -214,6 +223,15 @@ impl Response {
         };
         false
     }
+
+    /// Return true if `line` concludes the server response to a round of V1 negotiation that didn't end with `done`.
+    ///
+    /// This is the case for a 'NAK', or an 'ACK' without status which is only sent if no `multi_ack` mode was negotiated.
+    /// In the latter case, the server won't respond to any more `have` lines, so the next request must conclude the negotiation.
+    fn is_v1_negotiation_round_end(line: &str) -> bool {
+        let line = line.trim_end();
+        line == "NAK" || (line.starts_with("ACK ") && line.split(' ').count() == 2)
+    }
 }
 
 #[cfg(feature = "async-client")]
@@ -26,7 +26,7 @@ mod v1 {
             }
 
             #[test]
-            fn it_chooses_all_supported_non_stacking_capabilities_and_leaves_no_progress() {
+            fn it_chooses_all_supported_non_stacking_capabilities_and_leaves_no_progress_and_no_done() {
                 assert_eq!(
                     Command::Fetch.default_features(git_transport::Protocol::V1, &capabilities(GITHUB_CAPABILITIES)),
                     &[
@@ -40,11 +40,10 @@ mod v1 {
                         ("deepen-relative", None),
                         ("allow-tip-sha1-in-want", None),
                         ("allow-reachable-sha1-in-want", None),
-                        ("no-done", None),
                         ("filter", None),
                         fetch::agent()
                     ],
-                    "we don't enforce include-tag or no-progress"
+                    "we don't enforce include-tag or no-progress, and always send 'done'"
                 );
             }
         }
@@ -36,6 +36,9 @@ impl<'a> RemoteProgress<'a> {
 
     /// Parse `text`, which is interpreted as error if `is_error` is true, as [`RemoteProgress`] and call the respective
     /// methods on the given `progress` instance.
+    ///
+    /// As git terminates progress updates with `\r` and may send several of them at once, `text` is split into lines which are
+    /// translated one after another. Progress is only re-initialized if the action or the maximum changes to keep its throughput.
     pub fn translate_to_progress(is_error: bool, text: &[u8], progress: &mut impl git_features::progress::Progress) {
         fn progress_name(current: Option<String>, action: &[u8]) -> String {
             match current {
@@ -53,21 +56,30 @@ impl<'a> RemoteProgress<'a> {
                 progress.fail(progress_name(None, text));
             }
         } else {
-            match Self::from_bytes(text) {
-                Some(RemoteProgress {
-                    action,
-                    percent: _,
-                    step,
-                    max,
-                }) => {
-                    progress.set_name(progress_name(progress.name(), action));
-                    progress.init(max, git_features::progress::count("objects"));
-                    if let Some(step) = step {
-                        progress.set(step);
+            for line in text
+                .split(|b| *b == b'\r' || *b == b'\n')
+                .filter(|line| !line.is_empty())
+            {
+                match Self::from_bytes(line) {
+                    Some(RemoteProgress {
+                        action,
+                        percent: _,
+                        step,
+                        max,
+                    }) => {
+                        let current_name = progress.name();
+                        let name = progress_name(current_name.clone(), action);
+                        if current_name.as_ref() != Some(&name) || progress.max() != max {
+                            progress.set_name(name);
+                            progress.init(max, git_features::progress::count("objects"));
+                        }
+                        if let Some(step) = step {
+                            progress.set(step);
+                        }
                     }
-                }
-                None => progress.set_name(progress_name(progress.name(), text)),
-            };
+                    None => progress.set_name(progress_name(progress.name(), line)),
+                };
+            }
         }
     }
 }
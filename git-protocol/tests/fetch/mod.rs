@@ -14,7 +14,10 @@ type Cursor = futures_lite::io::Cursor<Vec<u8>>;
 #[derive(Default)]
 pub struct CloneDelegate {
     pack_bytes: usize,
+    negotiated: Option<fetch::Negotiated>,
     abort_with: Option<std::io::Error>,
+    abort_in_negotiation_round: Option<usize>,
+    negotiation_rounds: usize,
 }
 
 impl fetch::DelegateBlocking for CloneDelegate {
@@ -34,12 +37,24 @@ impl fetch::DelegateBlocking for CloneDelegate {
         &mut self,
         refs: &[Ref],
         arguments: &mut Arguments,
-        _previous_result: Option<&Response>,
+        previous_result: Option<&Response>,
     ) -> io::Result<Action> {
-        for r in refs {
-            arguments.want(r.unpack().1);
+        self.negotiation_rounds += 1;
+        if self.abort_in_negotiation_round == Some(self.negotiation_rounds) {
+            return Ok(Action::Abort);
+        }
+        if previous_result.is_none() {
+            for r in refs {
+                arguments.want(r.unpack().1);
+            }
         }
-        Ok(Action::Cancel)
+        Ok(match self.abort_in_negotiation_round {
+            Some(_) => {
+                arguments.have(git_hash::ObjectId::null_sha1());
+                Action::Continue
+            }
+            None => Action::Cancel,
+        })
     }
 }
 
@@ -52,6 +67,9 @@ pub struct CloneRefInWantDelegate {
     /// Number of bytes received of the final packfile.
     pack_bytes: usize,
 
+    /// The features agreed upon with the server when receiving the pack.
+    negotiated: Option<fetch::Negotiated>,
+
     /// Refs advertised by `ls-refs` -- should always be empty, as we skip `ls-refs`.
     refs: Vec<fetch::Ref>,
 
@@ -163,7 +181,9 @@ mod blocking_io {
             _progress: impl Progress,
             _refs: &[Ref],
             _previous: &Response,
+            negotiated: &fetch::Negotiated,
         ) -> io::Result<()> {
+            self.negotiated = Some(negotiated.clone());
             self.pack_bytes = io::copy(&mut input, &mut io::sink())? as usize;
             Ok(())
         }
@@ -176,7 +196,9 @@ mod blocking_io {
             _progress: impl Progress,
             _refs: &[Ref],
             previous: &Response,
+            negotiated: &fetch::Negotiated,
         ) -> io::Result<()> {
+            self.negotiated = Some(negotiated.clone());
             for wanted in previous.wanted_refs() {
                 self.wanted_refs.push(fetch::Ref::Direct {
                     path: wanted.path.clone(),
@@ -195,6 +217,7 @@ mod blocking_io {
             _progress: impl Progress,
             _refs: &[Ref],
             _previous: &Response,
+            _negotiated: &fetch::Negotiated,
         ) -> io::Result<()> {
             unreachable!("Should not be called for ls-refs");
         }
@@ -221,7 +244,9 @@ mod async_io {
             _progress: impl Progress,
             _refs: &[Ref],
             _previous: &Response,
+            negotiated: &fetch::Negotiated,
         ) -> io::Result<()> {
+            self.negotiated = Some(negotiated.clone());
             self.pack_bytes = futures_lite::io::copy(&mut input, &mut futures_lite::io::sink()).await? as usize;
             Ok(())
         }
@@ -235,7 +260,9 @@ mod async_io {
             _progress: impl Progress,
             _refs: &[Ref],
             previous: &Response,
+            negotiated: &fetch::Negotiated,
         ) -> io::Result<()> {
+            self.negotiated = Some(negotiated.clone());
             for wanted in previous.wanted_refs() {
                 self.wanted_refs.push(fetch::Ref::Direct {
                     path: wanted.path.clone(),
@@ -255,16 +282,13 @@ mod async_io {
             _progress: impl Progress,
             _refs: &[Ref],
             _previous: &Response,
+            _negotiated: &fetch::Negotiated,
         ) -> io::Result<()> {
             unreachable!("Should not be called for ls-refs");
         }
     }
 }
 
-pub fn oid(hex_sha: &str) -> git_hash::ObjectId {
-    git_hash::ObjectId::from_hex(hex_sha.as_bytes()).expect("valid input")
-}
-
 #[cfg(feature = "async-client")]
 pub fn transport<'a, W: futures_io::AsyncWrite + Unpin>(
     out: W,
@@ -6,19 +6,16 @@ fn mock_reader(path: &str) -> git_packetline::StreamingPeekableIter<Cursor> {
     git_packetline::StreamingPeekableIter::new(Cursor::new(buf), &[git_packetline::PacketLine::Flush])
 }
 
-fn id(hex: &str) -> git_hash::ObjectId {
-    git_hash::ObjectId::from_hex(hex.as_bytes()).expect("expect valid hex id")
-}
-
 mod v1 {
     mod from_line_reader {
-        use crate::fetch::response::{id, mock_reader};
+        use crate::fetch::response::mock_reader;
         #[cfg(feature = "async-client")]
         use futures_lite::io::AsyncReadExt;
         use git_protocol::fetch::{
             self,
             response::{Acknowledgement, ShallowUpdate},
         };
+        use git_testtools::hex_to_id;
         use git_transport::Protocol;
         #[cfg(feature = "blocking-client")]
         use std::io::Read;
@@ -27,7 +24,7 @@ mod v1 {
         async fn clone() -> crate::Result {
             let mut provider = mock_reader("v1/clone-only.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
             assert_eq!(r.acknowledgements(), &[Acknowledgement::Nak]);
             assert!(r.has_pack());
             let mut buf = Vec::new();
@@ -40,10 +37,12 @@ mod v1 {
         async fn shallow_clone() -> crate::Result {
             let mut provider = mock_reader("v1/clone-deepen-1.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
             assert_eq!(
                 r.shallow_updates(),
-                &[ShallowUpdate::Shallow(id("808e50d724f604f69ab93c6da2919c014667bedb"))]
+                &[ShallowUpdate::Shallow(hex_to_id(
+                    "808e50d724f604f69ab93c6da2919c014667bedb"
+                ))]
             );
             assert_eq!(r.acknowledgements(), &[Acknowledgement::Nak]);
             assert!(r.has_pack());
@@ -57,7 +56,7 @@ mod v1 {
         async fn empty_shallow_clone_due_to_depth_being_too_high() -> crate::Result {
             let mut provider = mock_reader("v1/clone-deepen-5.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
             assert!(r.shallow_updates().is_empty());
             assert_eq!(r.acknowledgements(), &[Acknowledgement::Nak]);
             assert!(r.has_pack());
@@ -70,15 +69,83 @@ mod v1 {
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn fetch_acks_without_pack() -> crate::Result {
             let mut provider = mock_reader("v1/fetch-no-pack.response");
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands()).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), false)
+                .await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(hex_to_id("47ee0b7fe4f3a7d776c78794873e6467e1c47e59")),
+                    Acknowledgement::Common(hex_to_id("3f02c0ad360d96e8dbba92f97b42ebbaa4319db1")),
+                    Acknowledgement::Nak,
+                ]
+            );
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_with_multi_ack_stop_at_the_end_of_each_round() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch-multi-ack-two-rounds.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, false).await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(hex_to_id("47ee0b7fe4f3a7d776c78794873e6467e1c47e59")),
+                    Acknowledgement::Common(hex_to_id("3f02c0ad360d96e8dbba92f97b42ebbaa4319db1")),
+                    Acknowledgement::Nak,
+                ],
+                "'continue' acknowledges objects in common"
+            );
+            assert!(!r.has_pack());
+            assert!(!r.is_ready());
+
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, false).await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
-                    Acknowledgement::Common(id("47ee0b7fe4f3a7d776c78794873e6467e1c47e59")),
-                    Acknowledgement::Common(id("3f02c0ad360d96e8dbba92f97b42ebbaa4319db1")),
+                    Acknowledgement::Common(hex_to_id("6504930888c9c5337e7e065c964f87b60d16a7d7")),
                     Acknowledgement::Nak,
+                ],
+                "the second round is read separately"
+            );
+            assert!(!r.has_pack());
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_ack_without_multi_ack_makes_the_server_ready() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch-no-multi-ack-ready.response");
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), false)
+                .await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[Acknowledgement::Common(hex_to_id(
+                    "47ee0b7fe4f3a7d776c78794873e6467e1c47e59"
+                ))]
+            );
+            assert!(!r.has_pack());
+            assert!(
+                r.is_ready(),
+                "the server won't respond to more haves after acknowledging the first common one"
+            );
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_with_multi_ack_detailed_and_ready() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch-multi-ack-detailed-ready.response");
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), false)
+                .await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(hex_to_id("47ee0b7fe4f3a7d776c78794873e6467e1c47e59")),
+                    Acknowledgement::Ready,
+                    Acknowledgement::Nak
                 ]
             );
+            assert!(!r.has_pack());
+            assert!(r.is_ready());
             Ok(())
         }
 
@@ -86,13 +153,13 @@ mod v1 {
         async fn fetch_acks_and_pack() -> crate::Result {
             let mut provider = mock_reader("v1/fetch.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
-                    Acknowledgement::Common(id("6504930888c9c5337e7e065c964f87b60d16a7d7")),
-                    Acknowledgement::Common(id("fe17165c392110d1305674c06e4aec35728bfab7")),
-                    Acknowledgement::Common(id("f22743895a3024bb0c958335981439f1fa747d57")),
+                    Acknowledgement::Common(hex_to_id("6504930888c9c5337e7e065c964f87b60d16a7d7")),
+                    Acknowledgement::Common(hex_to_id("fe17165c392110d1305674c06e4aec35728bfab7")),
+                    Acknowledgement::Common(hex_to_id("f22743895a3024bb0c958335981439f1fa747d57")),
                     Acknowledgement::Ready,
                     Acknowledgement::Nak,
                 ]
@@ -107,13 +174,14 @@ mod v1 {
 }
 mod v2 {
     mod from_line_reader {
-        use crate::fetch::response::{id, mock_reader};
+        use crate::fetch::response::mock_reader;
         #[cfg(feature = "async-client")]
         use futures_lite::io::AsyncReadExt;
         use git_protocol::fetch::{
             self,
             response::{Acknowledgement, ShallowUpdate},
         };
+        use git_testtools::hex_to_id;
         use git_transport::Protocol;
         #[cfg(feature = "blocking-client")]
         use std::io::Read;
@@ -122,7 +190,7 @@ mod v2 {
         async fn clone() -> crate::Result {
             let mut provider = mock_reader("v2/clone-only.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
             assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
             assert!(r.has_pack());
             let mut buf = Vec::new();
@@ -135,11 +203,13 @@ mod v2 {
         async fn shallow_clone() -> crate::Result {
             let mut provider = mock_reader("v2/clone-deepen-1.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
             assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
             assert_eq!(
                 r.shallow_updates(),
-                &[ShallowUpdate::Shallow(id("808e50d724f604f69ab93c6da2919c014667bedb"))]
+                &[ShallowUpdate::Shallow(hex_to_id(
+                    "808e50d724f604f69ab93c6da2919c014667bedb"
+                ))]
             );
             assert!(r.has_pack());
             let mut pack = Vec::new();
@@ -152,7 +222,7 @@ mod v2 {
         async fn empty_shallow_clone() -> crate::Result {
             let mut provider = mock_reader("v2/clone-deepen-5.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
             assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
             assert!(r.shallow_updates().is_empty(), "it should go straight to the packfile");
             assert!(r.has_pack());
@@ -166,7 +236,7 @@ mod v2 {
         async fn clone_with_sidebands() -> crate::Result {
             let mut provider = mock_reader("v2/clone-only-2.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
             assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
             assert!(r.has_pack());
 
@@ -187,7 +257,8 @@ mod v2 {
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn fetch_acks_without_pack() -> crate::Result {
             let mut provider = mock_reader("v2/fetch-no-pack.response");
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut provider.as_read_without_sidebands()).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut provider.as_read_without_sidebands(), false)
+                .await?;
             assert_eq!(r.acknowledgements(), &[Acknowledgement::Nak,]);
             Ok(())
         }
@@ -196,12 +267,12 @@ mod v2 {
         async fn fetch_acks_and_pack() -> crate::Result {
             let mut provider = mock_reader("v2/fetch.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
-                    Acknowledgement::Common(id("190c3f6b2319c1f4ec854215533caf8623f8f870")),
-                    Acknowledgement::Common(id("97c5a932b3940a09683e924ef6a92b31a6f7c6de")),
+                    Acknowledgement::Common(hex_to_id("190c3f6b2319c1f4ec854215533caf8623f8f870")),
+                    Acknowledgement::Common(hex_to_id("97c5a932b3940a09683e924ef6a92b31a6f7c6de")),
                     Acknowledgement::Ready,
                 ]
             );
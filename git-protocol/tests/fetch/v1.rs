@@ -1,7 +1,8 @@
-use crate::fetch::{oid, transport, CloneDelegate, LsRemoteDelegate};
+use crate::fetch::{transport, CloneDelegate, LsRemoteDelegate};
 use bstr::ByteSlice;
 use git_features::progress;
 use git_protocol::fetch;
+use git_testtools::hex_to_id;
 use git_transport::Protocol;
 
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
@@ -18,9 +19,17 @@ async fn clone() -> crate::Result {
         &mut dlg,
         git_protocol::credentials::helper,
         progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
     )
     .await?;
     assert_eq!(dlg.pack_bytes, 876, "It be able to read pack bytes");
+    let negotiated = dlg.negotiated.expect("a pack was received");
+    assert_eq!(negotiated.version, Protocol::V1);
+    assert_eq!(negotiated.sideband, Some(fetch::negotiated::Sideband::Large));
+    assert!(negotiated.thin_pack, "the server supports it, so we ask for it");
+    assert!(negotiated.ofs_delta);
+    assert!(!negotiated.include_tag, "we don't ask for tags by default");
+    assert!(!negotiated.no_progress);
     Ok(())
 }
 
@@ -39,6 +48,7 @@ async fn ls_remote() -> crate::Result {
         &mut delegate,
         git_protocol::credentials::helper,
         progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
     )
     .await?;
 
@@ -47,12 +57,12 @@ async fn ls_remote() -> crate::Result {
         vec![
             fetch::Ref::Symbolic {
                 path: "HEAD".into(),
-                object: oid("808e50d724f604f69ab93c6da2919c014667bedb"),
+                object: hex_to_id("808e50d724f604f69ab93c6da2919c014667bedb"),
                 target: "refs/heads/master".into()
             },
             fetch::Ref::Direct {
                 path: "refs/heads/master".into(),
-                object: oid("808e50d724f604f69ab93c6da2919c014667bedb")
+                object: hex_to_id("808e50d724f604f69ab93c6da2919c014667bedb")
             }
         ]
     );
@@ -79,6 +89,7 @@ async fn ls_remote_handshake_failure_due_to_downgrade() -> crate::Result {
         delegate,
         git_protocol::credentials::helper,
         progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
     )
     .await
     {
@@ -1,7 +1,8 @@
-use crate::fetch::{oid, transport, CloneDelegate, CloneRefInWantDelegate, LsRemoteDelegate};
+use crate::fetch::{transport, CloneDelegate, CloneRefInWantDelegate, LsRemoteDelegate};
 use bstr::ByteSlice;
 use git_features::progress;
 use git_protocol::fetch;
+use git_testtools::hex_to_id;
 use git_transport::Protocol;
 
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
@@ -20,6 +21,7 @@ async fn clone_abort_prep() -> crate::Result {
         &mut dlg,
         git_protocol::credentials::helper,
         progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
     )
     .await
     .expect_err("fetch aborted");
@@ -30,6 +32,7 @@ async fn clone_abort_prep() -> crate::Result {
         format!(
             "002fgit-upload-pack does/not/matter\0\0version=2\00014command=ls-refs
 001aagent={}
+0017object-format=sha1
 0001000csymrefs
 0009peel
 00000000",
@@ -48,6 +51,98 @@ async fn clone_abort_prep() -> crate::Result {
     Ok(())
 }
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn clone_abort_before_negotiation() -> crate::Result {
+    let mut dlg = CloneDelegate {
+        abort_in_negotiation_round: Some(1),
+        ..Default::default()
+    };
+    let mut transport = transport(
+        Vec::new(),
+        "v2/clone.response",
+        Protocol::V2,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    git_protocol::fetch(
+        &mut transport,
+        &mut dlg,
+        git_protocol::credentials::helper,
+        progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+    )
+    .await?;
+    assert_eq!(dlg.pack_bytes, 0, "no pack is received");
+    assert_eq!(dlg.negotiation_rounds, 1);
+
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        format!(
+            "002fgit-upload-pack does/not/matter\0\0version=2\00014command=ls-refs
+001aagent={}
+0017object-format=sha1
+0001000csymrefs
+0009peel
+00000000",
+            fetch::agent().1.expect("value set")
+        )
+        .as_bytes()
+        .as_bstr(),
+        "a flush packet tells the server there are no more requests"
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn fetch_abort_after_negotiation_round() -> crate::Result {
+    let mut dlg = CloneDelegate {
+        abort_in_negotiation_round: Some(2),
+        ..Default::default()
+    };
+    let mut transport = transport(
+        Vec::new(),
+        "v2/fetch-acks-only.response",
+        Protocol::V2,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    git_protocol::fetch(
+        &mut transport,
+        &mut dlg,
+        git_protocol::credentials::helper,
+        progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+    )
+    .await?;
+    assert_eq!(dlg.pack_bytes, 0, "no pack is received");
+    assert_eq!(dlg.negotiation_rounds, 2);
+
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        format!(
+            "002fgit-upload-pack does/not/matter\0\0version=2\00014command=ls-refs
+001aagent={}
+0017object-format=sha1
+0001000csymrefs
+0009peel
+00000012command=fetch
+001aagent={}
+0017object-format=sha1
+0001000ethin-pack
+0010include-tag
+000eofs-delta
+0032want 808e50d724f604f69ab93c6da2919c014667bedb
+0032want 808e50d724f604f69ab93c6da2919c014667bedb
+0032have 0000000000000000000000000000000000000000
+00000000",
+            fetch::agent().1.expect("value set"),
+            fetch::agent().1.expect("value set")
+        )
+        .as_bytes()
+        .as_bstr(),
+        "'done' is never sent, and a flush packet ends the interaction after reading the acknowledgments"
+    );
+    Ok(())
+}
+
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
 async fn ls_remote() -> crate::Result {
     let out = Vec::new();
@@ -63,6 +158,7 @@ async fn ls_remote() -> crate::Result {
         &mut delegate,
         git_protocol::credentials::helper,
         progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
     )
     .await?;
 
@@ -71,12 +167,12 @@ async fn ls_remote() -> crate::Result {
         vec![
             fetch::Ref::Symbolic {
                 path: "HEAD".into(),
-                object: oid("808e50d724f604f69ab93c6da2919c014667bedb"),
+                object: hex_to_id("808e50d724f604f69ab93c6da2919c014667bedb"),
                 target: "refs/heads/master".into()
             },
             fetch::Ref::Direct {
                 path: "refs/heads/master".into(),
-                object: oid("808e50d724f604f69ab93c6da2919c014667bedb")
+                object: hex_to_id("808e50d724f604f69ab93c6da2919c014667bedb")
             }
         ]
     );
@@ -85,6 +181,7 @@ async fn ls_remote() -> crate::Result {
         format!(
             "0044git-upload-pack does/not/matter\0\0version=2\0value-only\0key=value\00014command=ls-refs
 001aagent={}
+0017object-format=sha1
 0001000csymrefs
 0009peel
 00000000",
@@ -96,6 +193,86 @@ async fn ls_remote() -> crate::Result {
     Ok(())
 }
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn ls_remote_twice_on_the_same_connection() -> crate::Result {
+    let mut transport = transport(
+        Vec::new(),
+        "v2/ls-remote-twice.response",
+        Protocol::V2,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    for fetch_mode in &[
+        fetch::FetchConnection::AllowReuse,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+    ] {
+        let mut delegate = LsRemoteDelegate::default();
+        git_protocol::fetch(
+            &mut transport,
+            &mut delegate,
+            git_protocol::credentials::helper,
+            progress::Discard,
+            *fetch_mode,
+        )
+        .await?;
+        assert_eq!(delegate.refs.len(), 2, "both listings see all refs");
+    }
+
+    let agent = fetch::agent().1.expect("value set");
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        format!(
+            "0044git-upload-pack does/not/matter\0\0version=2\0value-only\0key=value\00014command=ls-refs
+001aagent={}
+0017object-format=sha1
+0001000csymrefs
+0009peel
+00000014command=ls-refs
+001aagent={}
+0017object-format=sha1
+0001000csymrefs
+0009peel
+00000000",
+            agent, agent
+        )
+        .as_bytes()
+        .as_bstr(),
+        "the handshake happens only once, and only the last listing ends the interaction"
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn unsupported_object_format_fails_right_after_handshake() -> crate::Result {
+    let mut delegate = LsRemoteDelegate::default();
+    let mut transport = transport(
+        Vec::new(),
+        "v2/clone-sha256.response",
+        Protocol::V2,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    let err = git_protocol::fetch(
+        &mut transport,
+        &mut delegate,
+        git_protocol::credentials::helper,
+        progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+    )
+    .await
+    .expect_err("sha256 isn't supported");
+
+    match err {
+        fetch::Error::UnsupportedObjectFormat { name } => assert_eq!(name, "sha256"),
+        err => panic!("unexpected error: {:?}", err),
+    }
+    assert!(delegate.refs.is_empty(), "no refs are listed");
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        "0044git-upload-pack does/not/matter\0\0version=2\0value-only\0key=value\0",
+        "nothing but the handshake is sent"
+    );
+    Ok(())
+}
+
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
 async fn ls_remote_abort_in_prep_ls_refs() -> crate::Result {
     let out = Vec::new();
@@ -112,6 +289,7 @@ async fn ls_remote_abort_in_prep_ls_refs() -> crate::Result {
         &mut delegate,
         git_protocol::credentials::helper,
         progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
     )
     .await
     .expect_err("ls-refs preparation is aborted");
@@ -152,6 +330,7 @@ async fn ref_in_want() -> crate::Result {
         &mut delegate,
         git_protocol::credentials::helper,
         progress::Discard,
+        fetch::FetchConnection::TerminateOnSuccessfulCompletion,
     )
     .await?;
 
@@ -160,26 +339,208 @@ async fn ref_in_want() -> crate::Result {
         delegate.wanted_refs,
         vec![fetch::Ref::Direct {
             path: "refs/heads/main".into(),
-            object: oid("9e320b9180e0b5580af68fa3255b7f3d9ecd5af0"),
+            object: hex_to_id("9e320b9180e0b5580af68fa3255b7f3d9ecd5af0"),
         }]
     );
     assert_eq!(delegate.pack_bytes, 641, "Should get packfile");
+    let negotiated = delegate.negotiated.expect("a pack was received");
+    assert_eq!(negotiated.version, Protocol::V2);
+    assert_eq!(negotiated.sideband, Some(fetch::negotiated::Sideband::Large));
+    assert!(
+        negotiated.thin_pack && negotiated.ofs_delta && negotiated.include_tag,
+        "these are always requested in V2"
+    );
     assert_eq!(
         transport.into_inner().1.as_bstr(),
         format!(
             "002fgit-upload-pack does/not/matter\0\0version=2\00012command=fetch
 001aagent={}
+0017object-format=sha1
 0001000ethin-pack
 0010include-tag
 000eofs-delta
 001dwant-ref refs/heads/main
 0009done
+00000000",
+            fetch::agent().1.expect("value set")
+        )
+        .as_bytes()
+        .as_bstr(),
+        "a flush packet tells the server there are no more requests after receiving the pack"
+    );
+
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn invoke_object_info_after_handshake() -> crate::Result {
+    let mut transport = transport(
+        Vec::new(),
+        "v2/object-info.response",
+        Protocol::V2,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    let outcome = fetch::handshake(
+        &mut transport,
+        git_protocol::credentials::helper,
+        Vec::new(),
+        &mut progress::Discard,
+    )
+    .await?;
+    assert_eq!(outcome.server_protocol_version, Protocol::V2);
+    assert!(outcome.refs.is_none(), "V2 doesn't advertise refs during the handshake");
+
+    let sections = fetch::invoke(
+        &mut transport,
+        &outcome.capabilities,
+        "object-info",
+        vec![fetch::agent()],
+        vec![
+            "size".into(),
+            "oid 808e50d724f604f69ab93c6da2919c014667bedb".into(),
+            "oid ff333b5f53da25e6ca3c66e9b6a0f4a4f2a2a2a2".into(),
+        ],
+    )
+    .await?;
+    assert_eq!(
+        sections,
+        vec![vec![
+            "size",
+            "808e50d724f604f69ab93c6da2919c014667bedb 42",
+            "ff333b5f53da25e6ca3c66e9b6a0f4a4f2a2a2a2 "
+        ]]
+    );
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        format!(
+            "002fgit-upload-pack does/not/matter\0\0version=2\00018command=object-info
+001aagent={}
+00010009size
+0031oid 808e50d724f604f69ab93c6da2919c014667bedb
+0031oid ff333b5f53da25e6ca3c66e9b6a0f4a4f2a2a2a2
 0000",
             fetch::agent().1.expect("value set")
         )
         .as_bytes()
         .as_bstr()
     );
+    Ok(())
+}
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn invoke_splits_response_into_sections() -> crate::Result {
+    let mut transport = transport(
+        Vec::new(),
+        "v2/invoke-sections.response",
+        Protocol::V2,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    let outcome = fetch::handshake(
+        &mut transport,
+        git_protocol::credentials::helper,
+        Vec::new(),
+        &mut progress::Discard,
+    )
+    .await?;
+    let sections = fetch::invoke(
+        &mut transport,
+        &outcome.capabilities,
+        "object-info",
+        Vec::new(),
+        Vec::new(),
+    )
+    .await?;
+    assert_eq!(sections, vec![vec!["first", "line"], vec!["second"]]);
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn invoke_validates_command_and_features() -> crate::Result {
+    let mut transport = transport(
+        Vec::new(),
+        "v2/object-info.response",
+        Protocol::V2,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    let outcome = fetch::handshake(
+        &mut transport,
+        git_protocol::credentials::helper,
+        Vec::new(),
+        &mut progress::Discard,
+    )
+    .await?;
+
+    let err = fetch::invoke(
+        &mut transport,
+        &outcome.capabilities,
+        "frobnicate",
+        Vec::new(),
+        Vec::new(),
+    )
+    .await
+    .expect_err("the command isn't advertised");
+    assert!(matches!(err, fetch::invoke::Error::UnsupportedCommand { command } if command == "frobnicate"));
+
+    let err = fetch::invoke(
+        &mut transport,
+        &outcome.capabilities,
+        "fetch",
+        vec![("filter", None)],
+        Vec::new(),
+    )
+    .await
+    .expect_err("the feature isn't advertised for the command");
+    assert!(matches!(
+        err,
+        fetch::invoke::Error::UnsupportedFeature { command, feature } if command == "fetch" && feature == "filter"
+    ));
+
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        "002fgit-upload-pack does/not/matter\0\0version=2\0",
+        "nothing is sent if validation fails"
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn object_info() -> crate::Result {
+    let mut transport = transport(
+        Vec::new(),
+        "v2/object-info.response",
+        Protocol::V2,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    let outcome = fetch::handshake(
+        &mut transport,
+        git_protocol::credentials::helper,
+        Vec::new(),
+        &mut progress::Discard,
+    )
+    .await?;
+    let ids = vec![
+        hex_to_id("808e50d724f604f69ab93c6da2919c014667bedb"),
+        hex_to_id("ff333b5f53da25e6ca3c66e9b6a0f4a4f2a2a2a2"),
+    ];
+    let sizes = fetch::object_info(&mut transport, &outcome.capabilities, ids.clone()).await?;
+    assert_eq!(
+        sizes,
+        vec![(ids[0], Some(42)), (ids[1], None)],
+        "objects unknown to the server have no size"
+    );
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        format!(
+            "002fgit-upload-pack does/not/matter\0\0version=2\00018command=object-info
+001aagent={}
+00010009size
+0031oid 808e50d724f604f69ab93c6da2919c014667bedb
+0031oid ff333b5f53da25e6ca3c66e9b6a0f4a4f2a2a2a2
+0000",
+            fetch::agent().1.expect("value set")
+        )
+        .as_bytes()
+        .as_bstr()
+    );
     Ok(())
 }
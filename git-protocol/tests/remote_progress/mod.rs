@@ -36,3 +36,39 @@ mod parse {
         )
     }
 }
+
+mod translate_to_progress {
+    use git_protocol::RemoteProgress;
+
+    #[test]
+    fn all_lines_of_a_message_update_the_progress() {
+        let root = prodash::Tree::new();
+        let mut progress = root.add_child("remote");
+        RemoteProgress::translate_to_progress(
+            false,
+            b"Counting objects:  45% (9/20)\rCounting objects:  50% (10/20)\r",
+            &mut progress,
+        );
+        assert_eq!(progress.name().as_deref(), Some("remote: Counting objects"));
+        assert_eq!(progress.max(), Some(20));
+        assert_eq!(progress.step(), Some(10));
+
+        RemoteProgress::translate_to_progress(false, b"Counting objects: 100% (20/20), done.\n", &mut progress);
+        assert_eq!(progress.step(), Some(20));
+
+        RemoteProgress::translate_to_progress(false, b"Compressing objects:  33% (1/3)\r", &mut progress);
+        assert_eq!(progress.name().as_deref(), Some("remote: Compressing objects"));
+        assert_eq!((progress.step(), progress.max()), (Some(1), Some(3)));
+    }
+
+    #[test]
+    fn lines_without_progress_become_the_name() {
+        let root = prodash::Tree::new();
+        let mut progress = root.add_child("remote");
+        RemoteProgress::translate_to_progress(false, b"Total 3 (delta 0), reused 0 (delta 0)\n", &mut progress);
+        assert_eq!(
+            progress.name().as_deref(),
+            Some("remote: Total 3 (delta 0), reused 0 (delta 0)")
+        );
+    }
+}
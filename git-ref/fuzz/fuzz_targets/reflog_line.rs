@@ -0,0 +1,10 @@
+#![no_main]
+
+use git_ref::file::log;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(line) = log::Line::from_bytes(data) {
+        let _a = log::mutable::Line::from(line).write_to(Vec::new());
+    }
+});
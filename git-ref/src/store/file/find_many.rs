@@ -0,0 +1,134 @@
+use crate::{
+    file::{self, loose::find_one::candidate_paths},
+    mutable, packed, PartialName, Target,
+};
+use bstr::ByteSlice;
+use git_hash::ObjectId;
+use std::{convert::TryInto, path::Path};
+
+/// A reference as returned by [`file::Store::find_many()`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Reference<'a> {
+    /// A reference stored in its own file, which takes precedence over a packed reference of the same name.
+    Loose(file::Reference<'a>),
+    /// A reference stored in the `packed-refs` file.
+    Packed(Packed),
+}
+
+/// A reference copied out of the `packed-refs` file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Packed {
+    /// The full name of the reference.
+    pub name: mutable::FullName,
+    /// The object the reference points to.
+    pub target: ObjectId,
+    /// The object the reference ultimately points to if it was peeled when packing, like it's done for annotated tags.
+    pub object: Option<ObjectId>,
+}
+
+impl<'a> Reference<'a> {
+    /// Return the full name of the reference.
+    pub fn name(&self) -> mutable::FullName {
+        match self {
+            Reference::Loose(r) => r.name(),
+            Reference::Packed(r) => r.name.clone(),
+        }
+    }
+
+    /// Return the target the reference points to.
+    pub fn target(&self) -> Target<'_> {
+        match self {
+            Reference::Loose(r) => r.target(),
+            Reference::Packed(r) => Target::Peeled(&r.target),
+        }
+    }
+}
+
+impl file::Store {
+    /// Find all references with the given `names`, returning the outcome of each lookup in the order of `names`, with `None` if
+    /// the reference doesn't exist.
+    ///
+    /// Each name is resolved like in [`find_one()`][file::Store::find_one()], but packed references are considered as well.
+    /// All lookups use the same snapshot of the `packed-refs` file which is read only once, and loose references are looked up
+    /// in sorted order.
+    pub fn find_many<'a, Name, E>(
+        &self,
+        names: impl IntoIterator<Item = Name>,
+    ) -> Result<Vec<Result<Option<Reference<'_>>, Error>>, packed::buffer::open::Error>
+    where
+        Name: TryInto<PartialName<'a>, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        let packed = self.packed()?;
+        let mut names: Vec<_> = names
+            .into_iter()
+            .map(|name| name.try_into().map_err(|err| Error::RefnameValidation(err.into())))
+            .enumerate()
+            .collect();
+        names.sort_by(|(_, lhs), (_, rhs)| {
+            lhs.as_ref()
+                .ok()
+                .map(|name| name.as_bstr())
+                .cmp(&rhs.as_ref().ok().map(|name| name.as_bstr()))
+        });
+        let mut results: Vec<_> = names
+            .into_iter()
+            .map(|(index, name)| {
+                let res = name.and_then(|name| self.find_in(name.to_partial_path().as_ref(), packed.as_ref()));
+                (index, res)
+            })
+            .collect();
+        results.sort_by_key(|(index, _)| *index);
+        Ok(results.into_iter().map(|(_, res)| res).collect())
+    }
+
+    fn find_in(&self, relative_path: &Path, packed: Option<&packed::Buffer>) -> Result<Option<Reference<'_>>, Error> {
+        use os_str_bytes::OsStrBytes;
+        for full_path in candidate_paths(relative_path) {
+            let full_name = full_path.as_path().to_raw_bytes().into_owned();
+            if let Some(r) = self.find_at(full_path)? {
+                return Ok(Some(Reference::Loose(r)));
+            }
+            let packed = match packed {
+                Some(packed) if full_name.starts_with(b"refs/") => packed,
+                _ => continue,
+            };
+            if let Some(r) = packed.find_full_name(crate::FullName(full_name.as_bstr()))? {
+                return Ok(Some(Reference::Packed(Packed {
+                    name: mutable::FullName(r.full_name.to_owned()),
+                    target: r.target(),
+                    object: r.object.map(|_| r.object()),
+                })));
+            }
+        }
+        Ok(None)
+    }
+}
+
+mod error {
+    use crate::{file::find_one, packed};
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned for each name by [file::Store::find_many()][crate::file::Store::find_many()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            RefnameValidation(err: crate::name::Error) {
+                display("The ref name or path is not a valid ref name")
+                source(err)
+            }
+            Loose(err: find_one::Error) {
+                display("A loose reference could not be read")
+                from()
+                source(err)
+            }
+            Packed(err: packed::find::Error) {
+                display("A packed reference could not be read")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+pub use error::Error;
@@ -113,11 +113,7 @@ pub mod decode {
         use super::*;
         use bstr::ByteSlice;
         use git_actor::{Sign, Time};
-        use git_hash::ObjectId;
-
-        fn hex_to_oid(hex: &str) -> ObjectId {
-            ObjectId::from_hex(hex.as_bytes()).unwrap()
-        }
+        use git_testtools::hex_to_id;
 
         fn with_newline(mut v: Vec<u8>) -> Vec<u8> {
             v.push(b'\n');
@@ -202,9 +198,9 @@ pub mod decode {
                 assert_eq!(res, actual);
                 assert_eq!(
                     actual.previous_oid(),
-                    hex_to_oid("a5828ae6b52137b913b978e16cd2334482eb4c1f")
+                    hex_to_id("a5828ae6b52137b913b978e16cd2334482eb4c1f")
                 );
-                assert_eq!(actual.new_oid(), hex_to_oid("89b43f80a514aee58b662ad606e6352e03eaeee4"));
+                assert_eq!(actual.new_oid(), hex_to_id("89b43f80a514aee58b662ad606e6352e03eaeee4"));
             }
         }
 
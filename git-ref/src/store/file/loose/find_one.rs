@@ -33,46 +33,16 @@ impl file::Store {
         &self,
         relative_path: &Path,
     ) -> Result<Option<file::Reference<'_>>, Error> {
-        let is_all_uppercase = relative_path
-            .to_string_lossy()
-            .as_ref()
-            .chars()
-            .all(|c| c.is_ascii_uppercase());
-        if relative_path.components().count() == 1 && is_all_uppercase {
-            if let Some(r) = self.find_inner("", &relative_path, Transform::None)? {
+        for full_path in candidate_paths(relative_path) {
+            if let Some(r) = self.find_at(full_path)? {
                 return Ok(Some(r));
             }
         }
-
-        for inbetween in &["", "tags", "heads", "remotes"] {
-            match self.find_inner(*inbetween, &relative_path, Transform::EnforceRefsPrefix) {
-                Ok(Some(r)) => return Ok(Some(r)),
-                Ok(None) => continue,
-                Err(err) => return Err(err),
-            }
-        }
-        self.find_inner("remotes", &relative_path.join("HEAD"), Transform::EnforceRefsPrefix)
+        Ok(None)
     }
 
-    fn find_inner(
-        &self,
-        inbetween: &str,
-        relative_path: &Path,
-        transform: Transform,
-    ) -> Result<Option<file::Reference<'_>>, Error> {
-        let relative_path = match transform {
-            Transform::EnforceRefsPrefix => {
-                if relative_path.starts_with("refs") {
-                    PathBuf::new()
-                } else {
-                    PathBuf::from("refs")
-                }
-            }
-            Transform::None => PathBuf::new(),
-        }
-        .join(inbetween)
-        .join(relative_path);
-
+    /// Find the loose reference at `relative_path`, which is the full path of a reference relative to our base.
+    pub(in crate::store::file) fn find_at(&self, relative_path: PathBuf) -> Result<Option<file::Reference<'_>>, Error> {
         let contents = match self.ref_contents(&relative_path)? {
             None => return Ok(None),
             Some(c) => c,
@@ -84,10 +54,46 @@ impl file::Store {
     }
 }
 
+/// Return the full paths at which the reference at `relative_path` is looked for, in order of precedence.
+pub(in crate::store::file) fn candidate_paths(relative_path: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    let is_all_uppercase = relative_path
+        .to_string_lossy()
+        .as_ref()
+        .chars()
+        .all(|c| c.is_ascii_uppercase());
+    let top_level = (relative_path.components().count() == 1 && is_all_uppercase)
+        .then(|| transform("", relative_path, Transform::None));
+    top_level
+        .into_iter()
+        .chain(
+            ["", "tags", "heads", "remotes"]
+                .iter()
+                .map(move |inbetween| transform(inbetween, relative_path, Transform::EnforceRefsPrefix)),
+        )
+        .chain(std::iter::once_with(move || {
+            transform("remotes", &relative_path.join("HEAD"), Transform::EnforceRefsPrefix)
+        }))
+}
+
+fn transform(inbetween: &str, relative_path: &Path, transform: Transform) -> PathBuf {
+    match transform {
+        Transform::EnforceRefsPrefix => {
+            if relative_path.starts_with("refs") {
+                PathBuf::new()
+            } else {
+                PathBuf::from("refs")
+            }
+        }
+        Transform::None => PathBuf::new(),
+    }
+    .join(inbetween)
+    .join(relative_path)
+}
+
 impl file::Store {
     /// Implements the logic required to transform a fully qualified refname into a filesystem path
     pub(crate) fn ref_path(&self, name: &Path) -> PathBuf {
-        self.base.join(name)
+        self.base_for(name).join(name)
     }
 
     /// Read the file contents with a verified full reference path and return it in the given vector if possible.
@@ -114,7 +114,7 @@ impl file::Store {
         if !refs.is_dir() {
             return Err(std::io::ErrorKind::NotFound.into());
         }
-        Ok(Loose::at_root(self, refs, self.base.clone()))
+        Ok(Loose::at_root(self, refs, self.common_dir().to_owned()))
     }
 
     pub fn loose_iter_prefixed(&self, prefix: impl AsRef<Path>) -> std::io::Result<Loose<'_>> {
@@ -125,11 +125,12 @@ impl file::Store {
                 "prefix must be a relative path, like 'refs/heads'",
             ));
         }
-        Ok(Loose::at_root(self, self.base.join(prefix), self.base.clone()))
+        let base = self.base_for(prefix);
+        Ok(Loose::at_root(self, base.join(prefix), base.to_owned()))
     }
 
     fn refs_dir(&self) -> PathBuf {
-        self.base.join("refs")
+        self.common_dir().join("refs")
     }
 }
 
@@ -7,6 +7,47 @@ pub mod reflog;
 ///
 pub mod iter;
 
+mod location {
+    use crate::store::file;
+    use std::path::{Component, Path};
+
+    impl file::Store {
+        /// The directory shared by all worktrees, which is the same as `base` unless this store is for a linked worktree.
+        pub fn common_dir(&self) -> &Path {
+            self.common_dir.as_deref().unwrap_or(&self.base)
+        }
+
+        /// Return true if the reference with the given `full_name` is private to each worktree, like `HEAD` or
+        /// `refs/bisect/bad`, instead of being shared among all of them.
+        pub fn is_per_worktree_ref(full_name: &Path) -> bool {
+            let mut components = full_name.components();
+            match (components.next(), components.next()) {
+                (Some(Component::Normal(_)), None) => true,
+                (Some(Component::Normal(refs)), Some(Component::Normal(category))) if refs == "refs" => {
+                    category == "worktree" || category == "bisect" || category == "rewritten"
+                }
+                _ => false,
+            }
+        }
+
+        /// Return the directory below which the reference or reflog for `full_name` is stored.
+        pub(crate) fn base_for(&self, full_name: &Path) -> &Path {
+            if Self::is_per_worktree_ref(full_name) {
+                &self.base
+            } else {
+                self.common_dir()
+            }
+        }
+
+        /// Strip the directory of this store from `path`, where it may be the private or the common directory.
+        pub(crate) fn strip_base<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+            path.strip_prefix(&self.base)
+                .ok()
+                .or_else(|| path.strip_prefix(self.common_dir()).ok())
+        }
+    }
+}
+
 mod init {
     use crate::store::file;
     use std::path::PathBuf;
@@ -18,6 +59,23 @@ mod init {
             file::Store {
                 base: git_dir.into(),
                 write_reflog,
+                common_dir: None,
+                ignore_case: cfg!(any(windows, target_os = "macos")),
+            }
+        }
+
+        /// Create a new instance for the linked worktree whose private directory is `git_dir`, sharing all but its
+        /// per-worktree references with the repository at `common_dir`.
+        pub fn for_linked_worktree(
+            git_dir: impl Into<PathBuf>,
+            common_dir: impl Into<PathBuf>,
+            write_reflog: crate::file::WriteReflog,
+        ) -> Self {
+            file::Store {
+                base: git_dir.into(),
+                write_reflog,
+                common_dir: Some(common_dir.into()),
+                ignore_case: cfg!(any(windows, target_os = "macos")),
             }
         }
     }
@@ -68,6 +68,15 @@ impl file::Store {
     }
 }
 
+///
+pub mod expire;
+
+///
+pub mod query;
+
+///
+pub mod names;
+
 ///
 pub mod create_or_update {
     use crate::store::{file, file::WriteReflog};
@@ -166,25 +175,21 @@ pub mod create_or_update {
         }
 
         fn reflock_resource_full_name(&self, reflock: &git_lock::Marker) -> PathBuf {
-            reflock
-                .resource_path()
-                .strip_prefix(&self.base)
+            self.strip_base(&reflock.resource_path())
                 .expect("lock must be held within this store")
                 .to_owned()
         }
 
         fn reflock_resource_to_log_path(&self, reflock: &git_lock::Marker) -> PathBuf {
             self.reflog_path_inner(
-                reflock
-                    .resource_path()
-                    .strip_prefix(&self.base)
+                self.strip_base(&reflock.resource_path())
                     .expect("lock must be held within this store"),
             )
         }
 
         /// Returns the base and a full path (including the base) to the reflog for a ref of the given `full_name`
         pub(in crate::store::file::loose::reflog) fn reflog_path_inner(&self, full_name: &Path) -> PathBuf {
-            self.base.join("logs").join(full_name)
+            self.base_for(full_name).join("logs").join(full_name)
         }
     }
 
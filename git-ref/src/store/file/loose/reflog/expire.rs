@@ -0,0 +1,130 @@
+use crate::{
+    store::{file, file::log},
+    FullName,
+};
+use git_hash::{oid, ObjectId};
+use std::{convert::TryInto, io::Write};
+
+/// Determines which reflog entries are removed by [`file::Store::expire_reflog()`], akin to `gc.reflogExpire` and
+/// `gc.reflogExpireUnreachable`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Policy {
+    /// Remove all entries older than this time in seconds since epoch, or keep them if `None`.
+    pub expire: Option<u32>,
+    /// Remove entries older than this time in seconds since epoch if their new object isn't reachable from the
+    /// current tip of the reference, or keep them if `None`.
+    pub expire_unreachable: Option<u32>,
+}
+
+/// The outcome of [`file::Store::expire_reflog()`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of entries that were kept.
+    pub kept: usize,
+    /// The amount of entries that were removed.
+    pub removed: usize,
+}
+
+impl file::Store {
+    /// Remove entries from the reflog of the reference with the given `name` according to `policy`, returning `Ok(None)` if
+    /// there is no reflog.
+    ///
+    /// `is_reachable(id)` returns true if `id` is reachable from the current tip of the reference, and is only called for
+    /// entries subject to [`Policy::expire_unreachable`].
+    /// The reference is locked with `lock_mode` while its log is rewritten, and the previous object id of each kept entry
+    /// is set to the new object id of the kept entry before it to keep the chain of entries continuous.
+    pub fn expire_reflog<'a, Name, E>(
+        &self,
+        name: Name,
+        policy: Policy,
+        lock_mode: git_lock::acquire::Fail,
+        mut is_reachable: impl FnMut(&oid) -> bool,
+    ) -> Result<Option<Outcome>, Error>
+    where
+        Name: TryInto<FullName<'a>, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        let name: FullName<'_> = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+        let relative_path = name.to_path();
+        let log_path = self.reflog_path(name);
+        // Check first to avoid creating lock files, and possibly their directories, for references without a log.
+        if !log_path.is_file() {
+            return Ok(None);
+        }
+        let _ref_lock = git_lock::Marker::acquire_to_hold_resource(
+            self.ref_path(&relative_path),
+            lock_mode,
+            Some(self.base_for(&relative_path).to_owned()),
+        )?;
+        let mut log_lock = git_lock::File::acquire_to_update_resource(&log_path, lock_mode, None)?;
+
+        let buf = match std::fs::read(&log_path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut outcome = Outcome::default();
+        let mut last_kept_oid: Option<ObjectId> = None;
+        let mut out = Vec::with_capacity(buf.len());
+        for line in log::iter::forward(&buf) {
+            let line = line?;
+            let time = line.signature.time.time;
+            let new_oid = line.new_oid();
+            let is_expired = matches!(policy.expire, Some(cutoff) if time < cutoff)
+                || matches!(policy.expire_unreachable, Some(cutoff)
+                    if time < cutoff && (new_oid.is_null() || !is_reachable(&new_oid)));
+            if is_expired {
+                outcome.removed += 1;
+                continue;
+            }
+            let mut line = line.to_mutable();
+            if let Some(previous_oid) = last_kept_oid {
+                line.previous_oid = previous_oid;
+            }
+            line.write_to(&mut out)?;
+            last_kept_oid = Some(new_oid);
+            outcome.kept += 1;
+        }
+
+        if outcome.removed != 0 {
+            log_lock.with_mut(|file| file.write_all(&out))?;
+            log_lock.commit().map_err(|err| Error::Io(err.error))?;
+        }
+        Ok(Some(outcome))
+    }
+}
+
+mod error {
+    use crate::store::file::log;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [crate::file::Store::expire_reflog()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            RefnameValidation(err: crate::name::Error) {
+                display("The reflog name or path is not a valid ref name")
+                from()
+                source(err)
+            }
+            LockAcquire(err: git_lock::acquire::Error) {
+                display("The reference or its reflog could not be locked")
+                from()
+                source(err)
+            }
+            Decode(err: log::iter::decode::Error) {
+                display("A reflog line could not be decoded")
+                from()
+                source(err)
+            }
+            Io(err: std::io::Error) {
+                display("The reflog could not be read or written")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+pub use error::Error;
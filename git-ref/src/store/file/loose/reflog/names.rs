@@ -0,0 +1,107 @@
+use crate::{mutable::FullName, store::file};
+use bstr::ByteSlice;
+use git_features::fs::walkdir::DirEntryIter;
+use os_str_bytes::OsStrBytes;
+use std::path::{Path, PathBuf};
+
+/// Which reflogs of a logs directory to yield, as linked worktrees share all but their per-worktree reflogs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Selection {
+    All,
+    PerWorktree,
+    Shared,
+}
+
+struct Walk {
+    logs_dir: PathBuf,
+    file_walk: DirEntryIter,
+    selection: Selection,
+}
+
+impl Walk {
+    fn at(logs_dir: PathBuf, selection: Selection) -> Option<Self> {
+        logs_dir.is_dir().then(|| Walk {
+            file_walk: git_features::fs::walkdir_new(&logs_dir).into_iter(),
+            logs_dir,
+            selection,
+        })
+    }
+
+    fn name_of(&self, path: impl AsRef<Path>) -> Option<FullName> {
+        let relative_path = path
+            .as_ref()
+            .strip_prefix(&self.logs_dir)
+            .expect("prefix-stripping cannot fail as prefix is our root");
+        let is_selected = match self.selection {
+            Selection::All => true,
+            Selection::PerWorktree => file::Store::is_per_worktree_ref(relative_path),
+            Selection::Shared => !file::Store::is_per_worktree_ref(relative_path),
+        };
+        if !is_selected {
+            return None;
+        }
+        let full_name = relative_path.to_raw_bytes();
+        #[cfg(windows)]
+        let full_name: Vec<u8> = full_name.into_owned().replace(b"\\", b"/");
+        git_validate::reference::name_partial(full_name.as_bstr())
+            .ok()
+            .map(|name| FullName(name.into()))
+    }
+}
+
+/// An iterator over the names of all references which have a reflog, created by [`file::Store::iter_reflogs()`].
+pub struct Names {
+    walks: std::vec::IntoIter<Walk>,
+    current: Option<Walk>,
+}
+
+impl Iterator for Names {
+    type Item = std::io::Result<FullName>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let walk = match self.current.as_mut() {
+                Some(walk) => walk,
+                None => {
+                    self.current = Some(self.walks.next()?);
+                    continue;
+                }
+            };
+            match walk.file_walk.next() {
+                Some(Ok(entry)) => {
+                    if !entry.file_type().is_file() {
+                        continue;
+                    }
+                    if let Some(name) = walk.name_of(entry.path()) {
+                        return Some(Ok(name));
+                    }
+                }
+                Some(Err(err)) => return Some(Err(err.into_io_error().expect("no symlink related errors"))),
+                None => self.current = None,
+            }
+        }
+    }
+}
+
+impl file::Store {
+    /// Return an iterator over the full names of all references which have a reflog, in file system order, by walking the
+    /// `logs` directories of this store.
+    ///
+    /// For linked worktrees, per-worktree reflogs are taken from the private directory and all others from the
+    /// [common directory][file::Store::common_dir()].
+    /// Files in the `logs` directory whose path isn't a valid reference name, like lock files, are silently ignored.
+    /// Note that the respective reference may not exist anymore.
+    pub fn iter_reflogs(&self) -> Names {
+        let walks: Vec<_> = match &self.common_dir {
+            None => Walk::at(self.base.join("logs"), Selection::All).into_iter().collect(),
+            Some(common_dir) => Walk::at(self.base.join("logs"), Selection::PerWorktree)
+                .into_iter()
+                .chain(Walk::at(common_dir.join("logs"), Selection::Shared))
+                .collect(),
+        };
+        Names {
+            walks: walks.into_iter(),
+            current: None,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    store::{file, file::log},
+    FullName,
+};
+use bstr::{BStr, ByteSlice};
+use std::convert::TryInto;
+
+/// Selects a single entry of a reflog, as used in `<ref>@{<n>}` and `<ref>@{<date>}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Query {
+    /// The n-th entry counting from the most recent one, which is `0`, as in `main@{2}`.
+    Nth(usize),
+    /// The most recent entry made at or before the given time in seconds since epoch, as in `main@{yesterday}`.
+    Before(u32),
+}
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+impl Query {
+    /// Parse the part between the braces of `@{…}`, using `now` in seconds since epoch to resolve relative dates.
+    ///
+    /// Plain numbers are entry indices. Supported dates are `now`, `yesterday`, `<n> <unit>[s] ago` with units from
+    /// `second` to `year` and `.` allowed as separator, as well as `YYYY-MM-DD[ HH:MM[:SS]]` which is interpreted as UTC.
+    pub fn parse(spec: &BStr, now: u32) -> Result<Self, parse::Error> {
+        let invalid = || parse::Error::Invalid { spec: spec.to_owned() };
+        if !spec.is_empty() && spec.iter().all(u8::is_ascii_digit) {
+            return spec
+                .to_str()
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .map(Query::Nth)
+                .ok_or_else(invalid);
+        }
+        let spec_str = spec.to_str().map_err(|_| invalid())?.trim();
+        match spec_str {
+            "now" => return Ok(Query::Before(now)),
+            "yesterday" => return Ok(Query::Before(now.saturating_sub(SECONDS_PER_DAY))),
+            _ => {}
+        }
+        relative_date(spec_str, now)
+            .or_else(|| absolute_date(spec_str))
+            .map(Query::Before)
+            .ok_or_else(invalid)
+    }
+}
+
+/// Split `spec` like `main@{2}` into the ref name and the query between the braces, or return `None` if there is no
+/// `@{…}` suffix.
+///
+/// The returned name may be empty, as in `@{1}`, which refers to the current branch.
+pub fn split_suffix(spec: &BStr) -> Option<(&BStr, &BStr)> {
+    if !spec.ends_with(b"}") {
+        return None;
+    }
+    let start = spec.rfind(b"@{")?;
+    Some((spec[..start].as_bstr(), spec[start + 2..spec.len() - 1].as_bstr()))
+}
+
+fn relative_date(spec: &str, now: u32) -> Option<u32> {
+    let mut tokens = spec.split(&[' ', '.'][..]).filter(|t| !t.is_empty());
+    let count: u32 = tokens.next()?.parse().ok()?;
+    let unit = tokens.next()?;
+    if tokens.next()? != "ago" || tokens.next().is_some() {
+        return None;
+    }
+    let seconds_per_unit = match unit.strip_suffix('s').unwrap_or(unit) {
+        "second" => 1,
+        "minute" => 60,
+        "hour" => 60 * 60,
+        "day" => SECONDS_PER_DAY,
+        "week" => 7 * SECONDS_PER_DAY,
+        "month" => 30 * SECONDS_PER_DAY,
+        "year" => 365 * SECONDS_PER_DAY,
+        _ => return None,
+    };
+    Some(now.saturating_sub(count.checked_mul(seconds_per_unit)?))
+}
+
+fn absolute_date(spec: &str) -> Option<u32> {
+    let (date, time) = match spec.find(&[' ', 'T'][..]) {
+        Some(pos) => (&spec[..pos], Some(&spec[pos + 1..])),
+        None => (spec, None),
+    };
+    let mut ymd = date.split('-');
+    let year: i64 = ymd.next()?.parse().ok()?;
+    let month: u32 = ymd.next()?.parse().ok()?;
+    let day: u32 = ymd.next()?.parse().ok()?;
+    if ymd.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut seconds = days_from_civil(year, month, day) * i64::from(SECONDS_PER_DAY);
+    if let Some(time) = time {
+        let mut hms = time.split(':');
+        let hours: u32 = hms.next()?.parse().ok()?;
+        let minutes: u32 = hms.next()?.parse().ok()?;
+        let secs: u32 = hms.next().map(str::parse).unwrap_or(Ok(0)).ok()?;
+        if hms.next().is_some() || hours > 23 || minutes > 59 || secs > 60 {
+            return None;
+        }
+        seconds += i64::from(hours * 60 * 60 + minutes * 60 + secs);
+    }
+    seconds.try_into().ok()
+}
+
+/// The amount of days since 1970-01-01 for the given date in the proleptic gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+impl file::Store {
+    /// Find the reflog entry of the reference with the given `name` selected by `query`, reading the log from the back
+    /// in chunks using `buf`.
+    ///
+    /// Returns `Ok(None)` if there is no reflog, or if it doesn't contain the selected entry. Unlike `git`, dates older than
+    /// the oldest entry don't resolve to the oldest entry.
+    pub fn resolve_reflog_entry<'a, Name, E>(
+        &self,
+        name: Name,
+        query: Query,
+        buf: &mut [u8],
+    ) -> Result<Option<log::mutable::Line>, Error>
+    where
+        Name: TryInto<FullName<'a>, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        let mut lines = match self.reflog_iter_rev(name, buf) {
+            Ok(Some(lines)) => lines,
+            Ok(None) => return Ok(None),
+            Err(log::Error::RefnameValidation(err)) => return Err(Error::RefnameValidation(err)),
+            Err(log::Error::Io(err)) => return Err(err.into()),
+        };
+        match query {
+            Query::Nth(n) => Ok(lines.nth(n).transpose()?.transpose()?),
+            Query::Before(cutoff) => {
+                for line in lines {
+                    let line = line??;
+                    if line.signature.time.time <= cutoff {
+                        return Ok(Some(line));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+///
+pub mod parse {
+    use bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`Query::parse()`][super::Query::parse()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            Invalid { spec: BString } {
+                display("'{}' is neither a reflog entry index nor a date that could be understood", spec)
+            }
+        }
+    }
+}
+
+mod error {
+    use crate::store::file::log;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [crate::file::Store::resolve_reflog_entry()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            RefnameValidation(err: crate::name::Error) {
+                display("The reflog name or path is not a valid ref name")
+                from()
+                source(err)
+            }
+            Decode(err: log::iter::decode::Error) {
+                display("A reflog line could not be decoded")
+                from()
+                source(err)
+            }
+            Io(err: std::io::Error) {
+                display("The reflog could not be read")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+pub use error::Error;
@@ -36,6 +36,16 @@ pub struct Store {
     pub base: PathBuf,
     /// The way to handle reflog edits
     pub write_reflog: WriteReflog,
+    /// The directory shared by all worktrees of a repository, if `base` is the private directory of a linked worktree.
+    ///
+    /// All references which are not [per-worktree][Store::is_per_worktree_ref()] as well as packed references are
+    /// located here.
+    pub common_dir: Option<PathBuf>,
+    /// If true, reference names which differ only in case are considered the same, as their files would clash on case-insensitive
+    /// filesystems. Creating such a reference fails instead of silently changing the existing one.
+    ///
+    /// Defaults to true on Windows and MacOS.
+    pub ignore_case: bool,
 }
 
 mod traits {
@@ -73,11 +83,14 @@ mod packed {
 
         /// Return the path at which packed-refs would usually be stored
         pub fn packed_refs_path(&self) -> PathBuf {
-            self.base.join("packed-refs")
+            self.common_dir().join("packed-refs")
         }
     }
 }
 
+///
+pub mod find_many;
+
 ///
 pub mod reference;
 
@@ -83,7 +83,7 @@ pub mod to_id {
                 storage = next_ref;
                 cursor = &mut storage;
                 if seen.contains(&cursor.relative_path) {
-                    return Err(Error::Cycle(cursor.parent.base.join(&cursor.relative_path)));
+                    return Err(Error::Cycle(cursor.parent.ref_path(&cursor.relative_path)));
                 }
                 seen.insert(cursor.relative_path.clone());
                 count += 1;
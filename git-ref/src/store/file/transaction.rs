@@ -1,11 +1,16 @@
 use crate::{
-    mutable::Target,
+    mutable::{FullName, Target},
     store::file,
-    transaction::{Change, Create, RefEdit, RefEditsExt, RefLog},
+    transaction::{Change, Create, LogChange, RefEdit, RefEditsExt, RefLog},
 };
-use bstr::BString;
+use bstr::{BStr, BString, ByteSlice, ByteVec};
 use git_hash::ObjectId;
-use std::io::Write;
+use os_str_bytes::OsStrBytes;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug)]
 struct Edit {
@@ -45,6 +50,56 @@ pub struct Transaction<'a> {
 }
 
 impl<'a> Transaction<'a> {
+    /// Fail if a reference to be written can't be placed on disk as another reference is in the way, either as file where a
+    /// directory is needed or vice versa, or if it differs only in case from another one while the store ignores case.
+    /// Other references are the ones in this transaction as well as existing loose and packed references.
+    fn assure_names_are_available(store: &file::Store, updates: &[Edit]) -> Result<(), Error> {
+        let writes_ref = |edit: &&Edit| matches!(&edit.update.change, Change::Update { log, .. } if log.mode == RefLog::AndReference);
+        let names: Vec<_> = updates
+            .iter()
+            .filter(writes_ref)
+            .map(|edit| edit.update.name.0.as_bstr())
+            .collect();
+        if names.is_empty() {
+            return Ok(());
+        }
+        let mut listings = DirectoryListings::default();
+        for (idx, name) in names.iter().enumerate() {
+            for other in &names[..idx] {
+                match conflict(name, other, store.ignore_case) {
+                    Some(Conflict::Case) => {
+                        return Err(Error::CaseConflict {
+                            full_name: name.to_owned().into(),
+                            conflicting: other.to_owned().into(),
+                        })
+                    }
+                    Some(Conflict::DirectoryFile) => {
+                        return Err(Error::DirectoryFileConflict {
+                            full_name: name.to_owned().into(),
+                            conflicting: other.to_owned().into(),
+                        })
+                    }
+                    None => {}
+                }
+            }
+            assure_name_is_available_on_disk(store, name, &mut listings)?;
+        }
+        if let Some(packed) = store.packed()? {
+            for reference in packed.iter()? {
+                let packed_name = reference?.full_name;
+                for name in &names {
+                    if let Some(Conflict::DirectoryFile) = conflict(name, packed_name, false) {
+                        return Err(Error::DirectoryFileConflict {
+                            full_name: name.to_owned().into(),
+                            conflicting: packed_name.to_owned(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn lock_ref_and_apply_change(
         store: &file::Store,
         lock_fail_mode: git_lock::acquire::Fail,
@@ -72,7 +127,7 @@ impl<'a> Transaction<'a> {
                 let lock = git_lock::Marker::acquire_to_hold_resource(
                     store.ref_path(&relative_path),
                     lock_fail_mode,
-                    Some(store.base.to_owned()),
+                    Some(store.base_for(&relative_path).to_owned()),
                 )
                 .map_err(|err| Error::LockAcquire {
                     err,
@@ -106,12 +161,14 @@ impl<'a> Transaction<'a> {
                 lock
             }
             Change::Update {
-                mode: previous, new, ..
+                mode: previous,
+                new,
+                log,
             } => {
                 let mut lock = git_lock::File::acquire_to_update_resource(
                     store.ref_path(&relative_path),
                     lock_fail_mode,
-                    Some(store.base.to_owned()),
+                    Some(store.base_for(&relative_path).to_owned()),
                 )
                 .map_err(|err| Error::LockAcquire {
                     err,
@@ -160,6 +217,11 @@ impl<'a> Transaction<'a> {
                     (Create::Only | Create::OrUpdate { previous: None }, None | Some(_)) => {}
                 };
 
+                if let Some(existing) = existing_ref.as_ref().filter(|r| r.kind() == crate::Kind::Symbolic) {
+                    // Symbolic refs log the transition of the value they resolve to, like HEAD when switching branches.
+                    change.leaf_referent_previous_oid =
+                        existing.clone().peel_to_id_in_place().ok().map(ToOwned::to_owned);
+                }
                 *previous = match existing_ref {
                     None => Create::Only,
                     Some(existing) => Create::OrUpdate {
@@ -167,10 +229,13 @@ impl<'a> Transaction<'a> {
                     },
                 };
 
-                lock.with_mut(|file| match new {
-                    Target::Peeled(oid) => write!(file, "{}", oid),
-                    Target::Symbolic(name) => write!(file, "ref: {}", name.0),
-                })?;
+                // Log-only updates merely hold the lock to keep the reference stable while the reflog is written.
+                if log.mode == RefLog::AndReference {
+                    lock.with_mut(|file| match new {
+                        Target::Peeled(oid) => write!(file, "{}", oid),
+                        Target::Symbolic(name) => write!(file, "ref: {}", name.0),
+                    })?;
+                }
 
                 lock.close()?
             }
@@ -203,6 +268,7 @@ impl<'a> Transaction<'a> {
                         leaf_referent_previous_oid: None,
                     })
                     .map_err(Error::PreprocessingFailed)?;
+                Self::assure_names_are_available(self.store, &self.updates)?;
 
                 for cid in 0..self.updates.len() {
                     let change = &mut self.updates[cid];
@@ -281,18 +347,31 @@ impl<'a> Transaction<'a> {
                                 RefLog::AndReference => (true, true),
                             };
                             if update_reflog {
-                                match new {
-                                    Target::Symbolic(_) => {} // no reflog for symref changes
-                                    Target::Peeled(oid) => {
-                                        self.store.reflog_create_or_append(
-                                            &lock,
-                                            mode.previous_oid().or(change.leaf_referent_previous_oid),
-                                            oid,
-                                            committer,
-                                            log.message.as_ref(),
-                                            log.force_create_reflog,
-                                        )?;
-                                    }
+                                let new_oid = match (new, mode) {
+                                    (
+                                        Target::Symbolic(_),
+                                        Create::OrUpdate {
+                                            previous: Some(previous),
+                                        },
+                                    ) if previous == new => None,
+                                    // symbolic refs are only logged if they change and their new target resolves to an object
+                                    (Target::Symbolic(name), _) => self
+                                        .store
+                                        .find_one(name.to_partial())
+                                        .ok()
+                                        .flatten()
+                                        .and_then(|mut r| r.peel_to_id_in_place().ok().map(ToOwned::to_owned)),
+                                    (Target::Peeled(oid), _) => Some(*oid),
+                                };
+                                if let Some(new_oid) = new_oid {
+                                    self.store.reflog_create_or_append(
+                                        &lock,
+                                        mode.previous_oid().or(change.leaf_referent_previous_oid),
+                                        &new_oid,
+                                        committer,
+                                        log.message.as_ref(),
+                                        log.force_create_reflog,
+                                    )?;
                                 }
                             }
                             if update_ref {
@@ -367,6 +446,119 @@ impl<'a> Transaction<'a> {
     }
 }
 
+enum Conflict {
+    Case,
+    DirectoryFile,
+}
+
+/// Determine if references `a` and `b` can't both exist as loose reference files.
+fn conflict(a: &BStr, b: &BStr, ignore_case: bool) -> Option<Conflict> {
+    let (mut a, mut b) = (a.split_str("/"), b.split_str("/"));
+    loop {
+        match (a.next(), b.next()) {
+            (Some(a), Some(b)) if a == b => continue,
+            (Some(a), Some(b)) if ignore_case && a.eq_ignore_ascii_case(b) => return Some(Conflict::Case),
+            (Some(_), Some(_)) | (None, None) => return None,
+            (Some(_), None) | (None, Some(_)) => return Some(Conflict::DirectoryFile),
+        }
+    }
+}
+
+/// The names of the entries of directories, read only once per transaction.
+type DirectoryListings = HashMap<PathBuf, Vec<BString>>;
+
+fn assure_name_is_available_on_disk(
+    store: &file::Store,
+    name: &BStr,
+    listings: &mut DirectoryListings,
+) -> Result<(), Error> {
+    let full_name = || BString::from(name);
+    let relative_path = name.to_path_lossy();
+    let mut path = store.base_for(&relative_path).to_owned();
+    let mut prefix = BString::default();
+    let mut components = name.split_str("/").peekable();
+    while let Some(component) = components.next() {
+        if store.ignore_case {
+            if let Some(existing) = entry_differing_in_case(listings, &path, component)? {
+                prefix.push_str(existing);
+                return Err(Error::CaseConflict {
+                    full_name: full_name(),
+                    conflicting: prefix,
+                });
+            }
+        }
+        path.push(component.to_path_lossy());
+        prefix.push_str(component);
+        let is_last = components.peek().is_none();
+        if !is_last && path.is_file() {
+            return Err(Error::DirectoryFileConflict {
+                full_name: full_name(),
+                conflicting: prefix,
+            });
+        }
+        if is_last && path.is_dir() {
+            if let Some(file) = first_file_in(&path)? {
+                prefix.push_byte(b'/');
+                prefix.push_str(file.to_raw_bytes().replace(std::path::MAIN_SEPARATOR.to_string(), "/"));
+                return Err(Error::DirectoryFileConflict {
+                    full_name: full_name(),
+                    conflicting: prefix,
+                });
+            }
+        }
+        prefix.push_byte(b'/');
+    }
+    Ok(())
+}
+
+/// Return the name of the entry in `dir` which equals `name` if case is ignored, unless there also is an exact match.
+///
+/// `dir` is only read if it isn't in `listings` yet.
+fn entry_differing_in_case(
+    listings: &mut DirectoryListings,
+    dir: &Path,
+    name: &[u8],
+) -> std::io::Result<Option<BString>> {
+    let entries = match listings.entry(dir.to_owned()) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(entry_names(dir)?),
+    };
+    if entries.iter().any(|entry_name| entry_name == name) {
+        return Ok(None);
+    }
+    Ok(entries
+        .iter()
+        .find(|entry_name| entry_name.eq_ignore_ascii_case(name))
+        .cloned())
+}
+
+/// Return the names of all entries in `dir`, which are none if it doesn't exist.
+fn entry_names(dir: &Path) -> std::io::Result<Vec<BString>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    entries
+        .map(|entry| entry.map(|entry| BString::from(entry.file_name().to_raw_bytes().into_owned())))
+        .collect()
+}
+
+/// Return the path of the first file found in `dir` or any of its subdirectories, relative to `dir`.
+fn first_file_in(dir: &Path) -> std::io::Result<Option<std::path::PathBuf>> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            if let Some(file) = first_file_in(&entry.path())? {
+                return Ok(Some(Path::new(&entry.file_name()).join(file)));
+            }
+        } else {
+            return Ok(Some(entry.file_name().into()));
+        }
+    }
+    Ok(None)
+}
+
 /// The state of a [`Transaction`]
 enum State {
     /// The transaction was just created but isn't prepared yet.
@@ -398,6 +590,49 @@ impl file::Store {
             lock_fail_mode: lock,
         }
     }
+
+    /// Make the reference `name` point to the reference `target`, like it's done with `HEAD` when switching branches,
+    /// and return its previous value if it existed.
+    ///
+    /// If `target` resolves to an object, the reflog of `name` receives an entry with `message` describing the move
+    /// from the object the reference previously resolved to, if any, to the one of `target`.
+    /// No entry is written if the reference already pointed to `target`.
+    pub fn set_symbolic_target(
+        &self,
+        name: FullName,
+        target: FullName,
+        message: impl Into<BString>,
+        committer: &git_actor::Signature,
+        lock: git_lock::acquire::Fail,
+    ) -> Result<Option<Target>, Error> {
+        let edit = self
+            .transaction(
+                Some(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: message.into(),
+                        },
+                        mode: Create::OrUpdate { previous: None },
+                        new: Target::Symbolic(target),
+                    },
+                    name,
+                    deref: false,
+                }),
+                lock,
+            )
+            .commit(committer)?
+            .pop()
+            .expect("exactly one edit without splits");
+        Ok(match edit.change {
+            Change::Update {
+                mode: Create::OrUpdate { previous },
+                ..
+            } => previous,
+            _ => None,
+        })
+    }
 }
 
 mod error {
@@ -452,6 +687,22 @@ mod error {
             ReferenceOutOfDate { full_name: BString, expected: Target, actual: Target } {
                 display("The reference '{}' should have content {}, actual content was {}", full_name, expected, actual)
             }
+            CaseConflict { full_name: BString, conflicting: BString } {
+                display("Reference '{}' differs only in case from '{}' which would clash on case-insensitive filesystems", full_name, conflicting)
+            }
+            DirectoryFileConflict { full_name: BString, conflicting: BString } {
+                display("Reference '{}' cannot be written as '{}' is in the way, a reference can't be a directory of other references", full_name, conflicting)
+            }
+            PackedRefsOpen(err: crate::packed::buffer::open::Error) {
+                display("The packed-refs file could not be opened to check for conflicting reference names")
+                from()
+                source(err)
+            }
+            PackedRefsIter(err: crate::packed::iter::Error) {
+                display("A packed reference could not be read to check for conflicting reference names")
+                from()
+                source(err)
+            }
             ReferenceDecode(err: file::reference::decode::Error) {
                 display("Could not read reference")
                 from()
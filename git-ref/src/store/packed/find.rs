@@ -26,26 +26,31 @@ impl packed::Buffer {
                 full_name.extend_from_slice(name.0);
                 full_name.into()
             };
-            match self.binary_search_by(name.as_ref().try_into().expect("our full names are never invalid")) {
-                Ok(line_start) => {
-                    return Ok(Some(
-                        packed::decode::reference::<()>(&self.as_ref()[line_start..])
-                            .map_err(|_| Error::Parse)?
-                            .1,
-                    ))
-                }
-                Err(parse_failure) => {
-                    if parse_failure {
-                        return Err(Error::Parse);
-                    } else {
-                        continue;
-                    }
-                }
+            if let Some(r) = self.find_full_name(name.as_ref().try_into().expect("our full names are never invalid"))? {
+                return Ok(Some(r));
             }
         }
         Ok(None)
     }
 
+    /// Find a reference by its `full_name` only, without trying any other locations like [`find()`][packed::Buffer::find()] does.
+    pub(crate) fn find_full_name(&self, full_name: FullName<'_>) -> Result<Option<packed::Reference<'_>>, Error> {
+        match self.binary_search_by(full_name) {
+            Ok(line_start) => Ok(Some(
+                packed::decode::reference::<()>(&self.as_ref()[line_start..])
+                    .map_err(|_| Error::Parse)?
+                    .1,
+            )),
+            Err(parse_failure) => {
+                if parse_failure {
+                    Err(Error::Parse)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
     /// Find a reference with the given `name` and return it.
     pub fn find_existing<'a, Name, E>(&self, name: Name) -> Result<packed::Reference<'_>, existing::Error>
     where
@@ -61,9 +66,16 @@ impl packed::Buffer {
 
     /// Perform a binary search where `Ok(pos)` is the beginning of the line that matches `name` perfectly and `Err(pos)`
     /// is the beginning of the line at which `name` could be inserted to still be in sort order.
+    ///
+    /// The header line, if present, is never part of the search as it isn't a reference.
     fn binary_search_by(&self, full_name: FullName<'_>) -> Result<usize, bool> {
-        // TODO: remove the runtime constraint once we do lookup correctly
-        let a = self.as_ref();
+        let buf = self.as_ref();
+        let records_start = if buf.first() == Some(&b'#') {
+            buf.find_byte(b'\n').map_or(buf.len(), |pos| pos + 1)
+        } else {
+            0
+        };
+        let a = &buf[records_start..];
         let search_start_of_record = |ofs: usize| {
             a[..ofs]
                 .rfind(b"\n")
@@ -88,7 +100,7 @@ impl packed::Buffer {
                 })
                 .unwrap_or(&[])
         })
-        .map(search_start_of_record)
+        .map(|ofs| records_start + search_start_of_record(ofs))
         .map_err(|_| encountered_parse_failure)
     }
 }
@@ -18,8 +18,12 @@ use crate::mutable::{FullName, Target};
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub struct LogChange {
     /// How to treat the reference log.
+    ///
+    /// With [`RefLog::Only`] a log entry is written as if the reference was changed to its new value, but the reference
+    /// itself remains untouched. This is useful for bookkeeping, like recording a change to a reference made by other means.
     pub mode: RefLog,
-    /// If set, create a reflog even though it would otherwise not be the case as prohibited by general rules.
+    /// If set, create a reflog even though it would otherwise not be the case as prohibited by general rules,
+    /// which only create reflogs for `HEAD` and references in `refs/heads/`, `refs/remotes/` and `refs/notes/`.
     /// Note that ref-log writing might be prohibited in the entire repository which is when this flag has no effect either.
     pub force_create_reflog: bool,
     /// The message to put into the reference log. It must be a single line, hence newlines are forbidden.
@@ -74,6 +78,8 @@ pub enum Change {
     /// Otherwise it functions as `create-or-update`.
     Update {
         /// The desired change to the reference log.
+        ///
+        /// Changes to symbolic references are logged with the objects they resolve to, but only if their new target exists.
         log: LogChange,
         /// The create mode.
         /// If a ref was existing previously it will be updated to reflect the previous value for bookkeeping purposes
@@ -122,12 +128,14 @@ pub struct RefEdit {
     pub deref: bool,
 }
 
-/// The way to deal with the Reflog in deletions.
+/// The way to deal with the Reflog in deletions and updates.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 pub enum RefLog {
     /// Delete or update the reference and the log
     AndReference,
-    /// Delete or update only the reflog
+    /// Delete or update only the reflog, leaving the reference as is.
+    ///
+    /// When updating, the reflog entry records the transition from the current value of the reference to the new one.
     Only,
 }
 
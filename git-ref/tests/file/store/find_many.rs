@@ -0,0 +1,75 @@
+use crate::file::{store, store_with_packed_refs, store_writable};
+use git_ref::file::find_many;
+
+#[test]
+fn results_are_in_order_of_names_and_resolve_like_find_one() -> crate::Result {
+    let store = store()?;
+    let names = ["main", "origin", "does-not-exist", "dt1", "HEAD", "heads/d1"];
+    let results = store.find_many(names.iter().copied())?;
+    assert_eq!(results.len(), names.len());
+    for (name, found) in names.iter().zip(results) {
+        let expected = store.find_one(*name)?.map(|r| r.name());
+        assert_eq!(found?.map(|r| r.name()), expected, "{}", name);
+    }
+    Ok(())
+}
+
+#[test]
+fn packed_refs_are_found_along_with_loose_ones() -> crate::Result {
+    let store = store_with_packed_refs()?;
+    let packed = store.packed()?.expect("packed-refs exist");
+    let mut results = store
+        .find_many(vec!["t1", "main", "origin", "HEAD", "foobar"])?
+        .into_iter();
+
+    match results.next().expect("t1")?.expect("exists") {
+        find_many::Reference::Packed(r) => {
+            assert_eq!(r.name.as_ref(), "refs/tags/t1");
+            assert_eq!(r.target, packed.find_existing("t1")?.target());
+            assert_eq!(r.object, None, "lightweight tags aren't peeled");
+        }
+        r => panic!("Expected packed reference, got {:?}", r),
+    }
+    match results.next().expect("main")?.expect("exists") {
+        find_many::Reference::Packed(r) => assert_eq!(r.name.as_ref(), "refs/heads/main"),
+        r => panic!("Expected packed reference, got {:?}", r),
+    }
+    for expected in &["refs/remotes/origin/HEAD", "HEAD"] {
+        match results.next().expect("symbolic ref")?.expect("exists") {
+            r @ find_many::Reference::Loose(_) => {
+                assert_eq!(r.name().as_ref(), *expected);
+                assert_eq!(r.target().kind(), git_ref::Kind::Symbolic);
+            }
+            r => panic!("Expected loose reference, got {:?}", r),
+        }
+    }
+    assert!(results.next().expect("foobar")?.is_none());
+    assert!(results.next().is_none());
+    Ok(())
+}
+
+#[test]
+fn loose_refs_take_precedence_over_packed_ones() -> crate::Result {
+    let (_keep, store) = store_writable("make_packed_ref_repository.sh")?;
+    let id = "0000000000000000000000000000000000000001";
+    std::fs::write(store.base.join("refs/heads/main"), format!("{}\n", id))?;
+
+    let found = store
+        .find_many(Some("main"))?
+        .pop()
+        .expect("one result")?
+        .expect("exists");
+    assert!(matches!(found, find_many::Reference::Loose(_)));
+    assert_eq!(found.target().as_id().expect("peeled").to_string(), id);
+    Ok(())
+}
+
+#[test]
+fn failures_are_reported_per_name() -> crate::Result {
+    let store = store()?;
+    let results = store.find_many(vec!["main", "broken", "../escaping"])?;
+    assert!(results[0].as_ref().expect("no error").is_some());
+    assert!(matches!(results[1], Err(find_many::Error::Loose(_))));
+    assert!(matches!(results[2], Err(find_many::Error::RefnameValidation(_))));
+    Ok(())
+}
@@ -1,3 +1,5 @@
+mod find_many;
 mod find_one;
 mod iter;
 mod reflog;
+mod worktree;
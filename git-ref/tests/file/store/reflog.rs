@@ -64,3 +64,258 @@ mod iter_rev {
         Ok(())
     }
 }
+
+mod iter_reflogs {
+    use crate::file::store::reflog::store;
+
+    #[test]
+    fn lists_all_refs_with_reflog() -> crate::Result {
+        let store = store()?;
+        let mut names = store
+            .iter_reflogs()
+            .map(|name| name.map(|name| name.as_ref().to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        names.sort();
+        assert_eq!(names, vec!["HEAD", "refs/heads/main"]);
+        Ok(())
+    }
+
+    #[test]
+    fn each_name_can_be_used_to_read_its_log() -> crate::Result {
+        let store = store()?;
+        let mut buf = Vec::new();
+        for name in store.iter_reflogs() {
+            let name = name?;
+            assert!(store.reflog_iter(name.borrow(), &mut buf)?.is_some());
+        }
+        Ok(())
+    }
+}
+
+mod expire {
+    use crate::file::store_writable;
+    use git_ref::file::log::expire::{Outcome, Policy};
+    use git_ref::file::log::mutable::Line;
+
+    const FIXTURE_TIME: u32 = 946771200;
+
+    fn lines(store: &git_ref::file::Store, name: &str) -> crate::Result<Vec<Line>> {
+        let mut buf = Vec::new();
+        let lines = store
+            .reflog_iter(name, &mut buf)?
+            .expect("exists")
+            .map(|l| l.map(|l| l.to_mutable()))
+            .collect::<Result<_, _>>()?;
+        Ok(lines)
+    }
+
+    #[test]
+    fn non_existing_returns_none() -> crate::Result {
+        let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+        assert_eq!(
+            store.expire_reflog(
+                "refs/heads/does-not-exist",
+                Policy::default(),
+                Default::default(),
+                |_| true
+            )?,
+            None
+        );
+        assert_eq!(
+            store.expire_reflog(
+                "refs/heads/nested/does-not-exist",
+                Policy::default(),
+                Default::default(),
+                |_| true
+            )?,
+            None
+        );
+        assert!(
+            !store.base.join("refs/heads/nested").exists(),
+            "neither lock files nor their directories are created"
+        );
+        assert!(!store.base.join("refs/heads/does-not-exist.lock").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn by_time() -> crate::Result {
+        let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+        let before = lines(&store, "HEAD")?;
+        let policy = Policy {
+            expire: Some(FIXTURE_TIME),
+            expire_unreachable: None,
+        };
+        assert_eq!(
+            store.expire_reflog("HEAD", policy, Default::default(), |_| unreachable!("not called"))?,
+            Some(Outcome { kept: 5, removed: 0 }),
+            "entries at the cutoff time are kept"
+        );
+        assert_eq!(lines(&store, "HEAD")?, before, "nothing was changed");
+
+        let policy = Policy {
+            expire: Some(FIXTURE_TIME + 1),
+            expire_unreachable: None,
+        };
+        assert_eq!(
+            store.expire_reflog("HEAD", policy, Default::default(), |_| unreachable!("not called"))?,
+            Some(Outcome { kept: 0, removed: 5 })
+        );
+        assert!(lines(&store, "HEAD")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn by_unreachability_keeps_the_chain_continuous() -> crate::Result {
+        let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+        let before = lines(&store, "refs/heads/main")?;
+        let unreachable = before[2].new_oid;
+        let policy = Policy {
+            expire: None,
+            expire_unreachable: Some(FIXTURE_TIME + 1),
+        };
+        assert_eq!(
+            store.expire_reflog("refs/heads/main", policy, Default::default(), |id| id != unreachable)?,
+            Some(Outcome { kept: 4, removed: 1 })
+        );
+
+        let after = lines(&store, "refs/heads/main")?;
+        assert_eq!(after.len(), 4);
+        assert_eq!(after[..2], before[..2], "entries before the removed one are untouched");
+        assert_eq!(
+            after[2].previous_oid, after[1].new_oid,
+            "the previous id of the entry after the removed one is adjusted"
+        );
+        assert_eq!(after[2].new_oid, before[3].new_oid);
+        assert_eq!(after[3], before[4]);
+        Ok(())
+    }
+}
+
+mod resolve_reflog_entry {
+    use crate::file::{store::reflog::store, store_writable};
+    use git_ref::file::log::{
+        mutable::Line,
+        query::{split_suffix, Query},
+    };
+
+    const FIXTURE_TIME: u32 = 946771200;
+    const DAY: u32 = 24 * 60 * 60;
+
+    #[test]
+    fn query_parsing() {
+        let now = FIXTURE_TIME;
+        for (spec, expected) in &[
+            ("0", Query::Nth(0)),
+            ("42", Query::Nth(42)),
+            ("now", Query::Before(now)),
+            ("yesterday", Query::Before(now - DAY)),
+            ("1 day ago", Query::Before(now - DAY)),
+            ("2.weeks.ago", Query::Before(now - 14 * DAY)),
+            ("3 hours ago", Query::Before(now - 3 * 60 * 60)),
+            ("2000-01-02", Query::Before(FIXTURE_TIME)),
+            ("2000-01-01 12:30", Query::Before(FIXTURE_TIME - DAY / 2 + 30 * 60)),
+            ("1970-01-01T00:00:01", Query::Before(1)),
+        ] {
+            assert_eq!(
+                Query::parse(spec.as_bytes().into(), now).expect("valid"),
+                *expected,
+                "{}",
+                spec
+            );
+        }
+        for spec in &[
+            "",
+            "-1",
+            "tomorrow",
+            "1 fortnight ago",
+            "2 days",
+            "2000-13-01",
+            "2000-01-01 25:00",
+        ] {
+            assert!(
+                Query::parse(spec.as_bytes().into(), now).is_err(),
+                "{} is invalid",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn suffix_splitting() {
+        assert_eq!(split_suffix("main@{2}".into()), Some(("main".into(), "2".into())));
+        assert_eq!(
+            split_suffix("@{1 day ago}".into()),
+            Some(("".into(), "1 day ago".into()))
+        );
+        assert_eq!(split_suffix("main".into()), None);
+        assert_eq!(split_suffix("main@{2".into()), None);
+    }
+
+    #[test]
+    fn non_existing_returns_none() -> crate::Result {
+        let store = store()?;
+        let mut buf = [0u8; 256];
+        assert_eq!(
+            store.resolve_reflog_entry("refs/heads/does-not-exist", Query::Nth(0), &mut buf)?,
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nth_counts_from_the_most_recent_entry() -> crate::Result {
+        let store = store()?;
+        let mut buf = Vec::new();
+        let mut lines = store
+            .reflog_iter("refs/heads/main", &mut buf)?
+            .expect("exists")
+            .map(|l| l.map(|l| l.to_mutable()))
+            .collect::<Result<Vec<_>, _>>()?;
+        lines.reverse();
+
+        let mut buf = [0u8; 256];
+        for (n, expected) in lines.iter().enumerate() {
+            let line = store.resolve_reflog_entry("refs/heads/main", Query::Nth(n), &mut buf)?;
+            assert_eq!(line.as_ref(), Some(expected));
+        }
+        assert_eq!(
+            store.resolve_reflog_entry("refs/heads/main", Query::Nth(lines.len()), &mut buf)?,
+            None,
+            "there is no entry beyond the oldest one"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn before_selects_the_most_recent_entry_not_after_the_given_time() -> crate::Result {
+        let (dir, store) = store_writable("make_repo_for_reflog.sh")?;
+        let mut buf = Vec::new();
+        let mut lines = store
+            .reflog_iter("HEAD", &mut buf)?
+            .expect("exists")
+            .map(|l| l.map(|l| l.to_mutable()))
+            .collect::<Result<Vec<Line>, _>>()?;
+        let mut out = Vec::new();
+        for (idx, line) in lines.iter_mut().enumerate() {
+            line.signature.time.time = FIXTURE_TIME + idx as u32 * DAY;
+            line.write_to(&mut out)?;
+        }
+        std::fs::write(dir.path().join(".git").join("logs").join("HEAD"), out)?;
+
+        let mut buf = [0u8; 256];
+        for (idx, expected) in lines.iter().enumerate() {
+            let time = FIXTURE_TIME + idx as u32 * DAY;
+            for cutoff in &[time, time + DAY - 1] {
+                let line = store.resolve_reflog_entry("HEAD", Query::Before(*cutoff), &mut buf)?;
+                assert_eq!(line.as_ref(), Some(expected));
+            }
+        }
+        assert_eq!(
+            store.resolve_reflog_entry("HEAD", Query::Before(FIXTURE_TIME - 1), &mut buf)?,
+            None,
+            "the log doesn't reach back this far"
+        );
+        Ok(())
+    }
+}
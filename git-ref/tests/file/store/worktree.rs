@@ -0,0 +1,94 @@
+use git_ref::file;
+use std::path::Path;
+
+fn worktree_store() -> crate::Result<(std::path::PathBuf, file::Store)> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_worktree_repository.sh")?;
+    let common_dir = dir.join("repo").join(".git");
+    let git_dir = common_dir.join("worktrees").join("wt");
+    Ok((
+        dir,
+        file::Store::for_linked_worktree(git_dir, common_dir, Default::default()),
+    ))
+}
+
+#[test]
+fn per_worktree_refs_are_classified_by_name() {
+    for name in &[
+        "HEAD",
+        "ORIG_HEAD",
+        "refs/bisect/bad",
+        "refs/worktree/foo",
+        "refs/rewritten/x",
+    ] {
+        assert!(file::Store::is_per_worktree_ref(Path::new(name)), "{}", name);
+    }
+    for name in &["refs/heads/main", "refs/tags/t1", "refs/remotes/origin/HEAD"] {
+        assert!(!file::Store::is_per_worktree_ref(Path::new(name)), "{}", name);
+    }
+}
+
+#[test]
+fn head_is_private_and_branches_are_shared() -> crate::Result {
+    let (dir, store) = worktree_store()?;
+    let head = store.find_one_existing("HEAD")?;
+    assert_eq!(
+        head.target().as_name(),
+        Some(b"refs/heads/wt-branch".as_ref().into()),
+        "HEAD is read from the worktree's private directory"
+    );
+    store.find_one_existing("refs/bisect/bad")?;
+    store.find_one_existing("main")?;
+    store.find_one_existing("wt-branch")?;
+
+    let main_store = file::Store::from(dir.join("repo").join(".git"));
+    assert_eq!(
+        main_store.find_one_existing("HEAD")?.target().as_name(),
+        Some(b"refs/heads/main".as_ref().into())
+    );
+    assert!(
+        main_store.find_one("refs/bisect/bad")?.is_none(),
+        "bisect refs are not visible in other worktrees"
+    );
+    assert_eq!(store.common_dir(), main_store.base.as_path());
+    assert_eq!(store.packed_refs_path(), main_store.packed_refs_path());
+    Ok(())
+}
+
+#[test]
+fn loose_iteration_lists_shared_references() -> crate::Result {
+    let (_dir, store) = worktree_store()?;
+    let names = store
+        .loose_iter()?
+        .map(|r| r.map(|r| r.relative_path().to_owned()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(names.iter().any(|n| n == Path::new("refs/heads/main")));
+    assert!(names.iter().any(|n| n == Path::new("refs/heads/wt-branch")));
+    Ok(())
+}
+
+#[test]
+fn reflogs_follow_their_reference() -> crate::Result {
+    let (_dir, store) = worktree_store()?;
+    let mut buf = Vec::new();
+    assert!(
+        store.reflog_iter("HEAD", &mut buf)?.is_some(),
+        "the worktree HEAD log is private"
+    );
+    assert!(
+        store.reflog_iter("refs/heads/wt-branch", &mut buf)?.is_some(),
+        "branch logs are shared"
+    );
+    Ok(())
+}
+
+#[test]
+fn reflog_iteration_lists_private_and_shared_reflogs_once() -> crate::Result {
+    let (_dir, store) = worktree_store()?;
+    let mut names = store
+        .iter_reflogs()
+        .map(|name| name.map(|name| name.as_ref().to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    names.sort();
+    assert_eq!(names, vec!["HEAD", "refs/heads/main", "refs/heads/wt-branch"]);
+    Ok(())
+}
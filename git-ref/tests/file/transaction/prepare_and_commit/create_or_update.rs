@@ -56,11 +56,9 @@ mod reference_with_equally_named {
             } else {
                 let err = edits.unwrap_err();
                 match err {
-                    #[cfg_attr(target_os = "windows", allow(unused_variables))]
-                    transaction::Error::LockCommit { err, full_name } => {
+                    transaction::Error::DirectoryFileConflict { full_name, conflicting } => {
                         assert_eq!(full_name, "HEAD");
-                        #[cfg(not(target_os = "windows"))]
-                        assert_eq!(err.to_string(), "Directory not empty");
+                        assert_eq!(conflicting, "HEAD/file.ext", "the file in the way is named");
                     }
                     _ => unreachable!("other errors shouldn't happen here"),
                 };
@@ -70,6 +68,177 @@ mod reference_with_equally_named {
     }
 }
 
+mod name_conflicts {
+    use crate::file::transaction::prepare_and_commit::{committer, empty_store};
+    use git_lock::acquire::Fail;
+    use git_ref::{
+        file,
+        file::transaction,
+        mutable::Target,
+        transaction::{Change, Create, LogChange, RefEdit},
+    };
+    use git_testtools::hex_to_id;
+    use std::convert::TryInto;
+
+    fn create(name: &str) -> RefEdit {
+        RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                mode: Create::Only,
+                new: Target::Peeled(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242")),
+            },
+            name: name.try_into().expect("valid"),
+            deref: false,
+        }
+    }
+
+    fn store_with(ignore_case: bool, names: &[&str]) -> crate::Result<(tempfile::TempDir, file::Store)> {
+        let (dir, mut store) = empty_store()?;
+        store.ignore_case = ignore_case;
+        store
+            .transaction(names.iter().copied().map(create), Fail::Immediately)
+            .commit(&committer())?;
+        Ok((dir, store))
+    }
+
+    fn store_with_packed(names: &[&str]) -> crate::Result<(tempfile::TempDir, file::Store)> {
+        let (dir, store) = empty_store()?;
+        let mut packed_refs = String::from("# pack-refs with: peeled fully-peeled sorted \n");
+        for name in names {
+            packed_refs.push_str(&format!("28ce6a8b26aa170e1de65536fe8abe1832bd3242 {}\n", name));
+        }
+        std::fs::write(dir.path().join("packed-refs"), packed_refs)?;
+        Ok((dir, store))
+    }
+
+    fn prepare_err(store: &file::Store, names: &[&str]) -> transaction::Error {
+        store
+            .transaction(names.iter().copied().map(create), Fail::Immediately)
+            .prepare()
+            .err()
+            .expect("there is a conflict")
+    }
+
+    fn assert_directory_file_conflict(err: transaction::Error, expected_name: &str, expected_conflicting: &str) {
+        match err {
+            transaction::Error::DirectoryFileConflict { full_name, conflicting } => {
+                assert_eq!(full_name, expected_name);
+                assert_eq!(conflicting, expected_conflicting);
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    fn assert_case_conflict(err: transaction::Error, expected_name: &str, expected_conflicting: &str) {
+        match err {
+            transaction::Error::CaseConflict { full_name, conflicting } => {
+                assert_eq!(full_name, expected_name);
+                assert_eq!(conflicting, expected_conflicting);
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn existing_reference_where_a_directory_is_needed() -> crate::Result {
+        let (dir, store) = store_with(false, &["refs/heads/foo"])?;
+        assert_directory_file_conflict(
+            prepare_err(&store, &["refs/heads/foo/bar"]),
+            "refs/heads/foo/bar",
+            "refs/heads/foo",
+        );
+        assert!(
+            !dir.path().join("refs/heads/foo.lock").exists(),
+            "conflicts are detected before locking"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn existing_references_where_a_file_is_needed() -> crate::Result {
+        let (_dir, store) = store_with(false, &["refs/heads/foo/bar/baz"])?;
+        assert_directory_file_conflict(
+            prepare_err(&store, &["refs/heads/foo"]),
+            "refs/heads/foo",
+            "refs/heads/foo/bar/baz",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn packed_reference_where_a_directory_is_needed() -> crate::Result {
+        let (_dir, store) = store_with_packed(&["refs/heads/foo"])?;
+        assert_directory_file_conflict(
+            prepare_err(&store, &["refs/heads/foo/bar"]),
+            "refs/heads/foo/bar",
+            "refs/heads/foo",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn packed_references_where_a_file_is_needed() -> crate::Result {
+        let (_dir, store) = store_with_packed(&["refs/heads/a", "refs/heads/foo/bar/baz", "refs/tags/foo"])?;
+        assert_directory_file_conflict(
+            prepare_err(&store, &["refs/heads/foo"]),
+            "refs/heads/foo",
+            "refs/heads/foo/bar/baz",
+        );
+        store
+            .transaction(
+                vec![create("refs/heads/foo-bar"), create("refs/heads/b/c")],
+                Fail::Immediately,
+            )
+            .commit(&committer())?;
+        Ok(())
+    }
+
+    #[test]
+    fn references_in_the_same_transaction() -> crate::Result {
+        let (_dir, store) = empty_store()?;
+        assert_directory_file_conflict(
+            prepare_err(&store, &["refs/heads/a/b", "refs/heads/a"]),
+            "refs/heads/a",
+            "refs/heads/a/b",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn references_differing_in_case_if_case_is_ignored() -> crate::Result {
+        let (_dir, store) = store_with(true, &["refs/heads/foo", "refs/heads/dir/a"])?;
+        assert_case_conflict(
+            prepare_err(&store, &["refs/heads/Foo"]),
+            "refs/heads/Foo",
+            "refs/heads/foo",
+        );
+        assert_case_conflict(
+            prepare_err(&store, &["refs/heads/DIR/b"]),
+            "refs/heads/DIR/b",
+            "refs/heads/dir",
+        );
+        assert_case_conflict(
+            prepare_err(&store, &["refs/heads/new", "refs/heads/NEW"]),
+            "refs/heads/NEW",
+            "refs/heads/new",
+        );
+        store
+            .transaction(Some(create("refs/heads/dir/b")), Fail::Immediately)
+            .commit(&committer())?;
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(any(windows, target_os = "macos")))]
+    fn references_differing_in_case_if_case_is_not_ignored() -> crate::Result {
+        let (_dir, store) = store_with(false, &["refs/heads/foo"])?;
+        store
+            .transaction(Some(create("refs/heads/Foo")), Fail::Immediately)
+            .commit(&committer())?;
+        Ok(())
+    }
+}
+
 #[test]
 fn reference_with_old_value_must_exist_when_creating_it() -> crate::Result {
     let (_keep, store) = empty_store()?;
@@ -458,3 +627,177 @@ fn write_reference_to_which_head_points_to_does_not_update_heads_reflog_even_tho
     );
     Ok(())
 }
+
+#[test]
+fn reflog_only_update_writes_log_entry_but_leaves_reference_unchanged() -> crate::Result {
+    let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+    let head = store.find_one_existing("HEAD")?;
+    let referent = head.target().as_name().expect("symbolic ref").to_owned();
+    let previous_id = hex_to_id("02a7a22d90d7c02fb494ed25551850b868e634f0");
+    let previous_reflog = reflog_lines(&store, &referent.to_string())?;
+
+    let new_id = hex_to_id("01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc");
+    let edits = store
+        .transaction(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::Only,
+                        force_create_reflog: false,
+                        message: "bookkeeping".into(),
+                    },
+                    mode: Create::OrUpdate { previous: None },
+                    new: Target::Peeled(new_id),
+                },
+                name: referent.as_bstr().try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+        )
+        .commit(&committer())?;
+
+    assert_eq!(
+        edits[0].change.previous_value(),
+        Some(git_ref::Target::Peeled(&previous_id)),
+        "the previous value is still filled in"
+    );
+    assert_eq!(
+        store.find_one_existing(referent.as_bstr())?.target().as_id(),
+        Some(previous_id.as_ref()),
+        "the reference itself wasn't changed"
+    );
+    let reflog = reflog_lines(&store, &referent.to_string())?;
+    assert_eq!(reflog.len(), previous_reflog.len() + 1, "a line was added");
+    assert_eq!(
+        reflog.last().expect("at least one line"),
+        &log_line(previous_id, new_id, "bookkeeping"),
+        "it records the change as if it had happened"
+    );
+    Ok(())
+}
+
+#[test]
+fn reflog_is_created_for_refs_outside_the_default_policy_only_if_forced() -> crate::Result {
+    let new_id = hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242");
+    for force_create_reflog in &[false, true] {
+        let (_keep, store) = empty_store()?;
+        let name = "refs/special/name";
+        store
+            .transaction(
+                Some(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: *force_create_reflog,
+                            message: "forced".into(),
+                        },
+                        mode: Create::Only,
+                        new: Target::Peeled(new_id),
+                    },
+                    name: name.try_into()?,
+                    deref: false,
+                }),
+                Fail::Immediately,
+            )
+            .commit(&committer())?;
+
+        assert!(store.find_one(name)?.is_some(), "the reference is created either way");
+        let mut buf = Vec::new();
+        if *force_create_reflog {
+            assert_eq!(
+                reflog_lines(&store, name)?,
+                vec![log_line(ObjectId::null_sha1(), new_id, "forced")]
+            );
+        } else {
+            assert!(
+                store.reflog_iter(name, &mut buf)?.is_none(),
+                "refs outside of refs/heads/, refs/remotes/ and refs/notes/ don't get logs by default"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn set_symbolic_target_logs_the_move_of_the_symbolic_ref() -> crate::Result {
+    let (_keep, store) = empty_store()?;
+    let (main_id, feature_id) = (
+        hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"),
+        hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+    );
+    store
+        .transaction(
+            vec![("refs/heads/main", main_id), ("refs/heads/feature", feature_id)]
+                .into_iter()
+                .map(|(name, id)| RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            message: "branch: Created".into(),
+                            ..Default::default()
+                        },
+                        mode: Create::Only,
+                        new: Target::Peeled(id),
+                    },
+                    name: name.try_into().expect("valid"),
+                    deref: false,
+                }),
+            Fail::Immediately,
+        )
+        .commit(&committer())?;
+
+    let previous = store.set_symbolic_target(
+        "HEAD".try_into()?,
+        "refs/heads/main".try_into()?,
+        "init",
+        &committer(),
+        Fail::Immediately,
+    )?;
+    assert_eq!(previous, None, "HEAD didn't exist yet");
+    let previous = store.set_symbolic_target(
+        "HEAD".try_into()?,
+        "refs/heads/feature".try_into()?,
+        "checkout: moving from main to feature",
+        &committer(),
+        Fail::Immediately,
+    )?;
+    assert_eq!(previous, Some(Target::Symbolic("refs/heads/main".try_into()?)));
+
+    let head = store.find_one_existing("HEAD")?;
+    assert_eq!(head.kind(), git_ref::Kind::Symbolic, "HEAD remains symbolic");
+    assert_eq!(head.target().as_name(), Some(b"refs/heads/feature".as_bstr()));
+    let expected_head_log = vec![
+        log_line(ObjectId::null_sha1(), main_id, "init"),
+        log_line(main_id, feature_id, "checkout: moving from main to feature"),
+    ];
+    assert_eq!(reflog_lines(&store, "HEAD")?, expected_head_log);
+    assert_eq!(
+        reflog_lines(&store, "refs/heads/feature")?,
+        vec![log_line(ObjectId::null_sha1(), feature_id, "branch: Created")],
+        "the referent isn't affected"
+    );
+
+    store.set_symbolic_target(
+        "HEAD".try_into()?,
+        "refs/heads/feature".try_into()?,
+        "no-op",
+        &committer(),
+        Fail::Immediately,
+    )?;
+    store.set_symbolic_target(
+        "HEAD".try_into()?,
+        "refs/heads/unborn".try_into()?,
+        "checkout: moving from feature to unborn",
+        &committer(),
+        Fail::Immediately,
+    )?;
+    assert_eq!(
+        store.find_one_existing("HEAD")?.target().as_name(),
+        Some(b"refs/heads/unborn".as_bstr())
+    );
+    assert_eq!(
+        reflog_lines(&store, "HEAD")?,
+        expected_head_log,
+        "neither pointing to the same ref again nor to an unborn one is logged"
+    );
+    Ok(())
+}
@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git config commit.gpgsign false
+  git checkout -q -b main
+  git commit -q --allow-empty -m c1
+  git worktree add -q -b wt-branch ../wt
+)
+(cd wt
+  git commit -q --allow-empty -m c2
+  git update-ref refs/bisect/bad HEAD
+)
@@ -116,3 +116,19 @@ bogus refs/tags/git-actor-v0.1.0
     }
     Ok(())
 }
+
+#[test]
+fn missing_refs_are_not_found_even_if_the_search_touches_the_header() -> crate::Result {
+    let packed_refs = b"# pack-refs with: peeled fully-peeled sorted
+916840c0e2f67d370291042cb5274a597f4fa9bc refs/heads/main
+c4cebba92af964f2d126be90b8a6298c4cf84d45 refs/tags/git-actor-v0.1.0
+^13da90b54699a6b500ec5cd7d175f2cd5a1bed06\n";
+    let (_keep, path) = write_packed_refs_with(packed_refs)?;
+
+    let buf = packed::Buffer::open(path, 1024)?;
+    for name in &["refs/a", "refs/heads/a", "refs/heads/z", "refs/tags/z"] {
+        assert!(buf.find(*name)?.is_none(), "{} doesn't exist", name);
+    }
+    assert!(buf.find("main")?.is_some(), "partial names still work");
+    Ok(())
+}
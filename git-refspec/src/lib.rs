@@ -0,0 +1,121 @@
+//! Parse refspecs as used for fetching and pushing, and use them to map reference names between repositories.
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms, missing_docs)]
+
+use bstr::{BStr, BString, ByteSlice};
+use std::io;
+
+///
+pub mod parse;
+#[doc(inline)]
+pub use parse::parse;
+
+///
+pub mod match_group;
+
+/// The operation a refspec is used for, which affects how it is parsed.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operation {
+    /// The refspec maps remote references to local ones, like `+refs/heads/*:refs/remotes/origin/*`.
+    Fetch,
+    /// The refspec maps local references to remote ones, like `refs/heads/main:refs/heads/main`.
+    Push,
+}
+
+/// How matching references are treated.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// Update the destination only if it is a fast-forward.
+    Normal,
+    /// Update the destination even if it isn't a fast-forward, as indicated by a leading `+`.
+    Force,
+    /// Exclude all references matching the source from other refspecs, as indicated by a leading `^`.
+    Negative,
+}
+
+/// A validated refspec, obtained with [`parse()`].
+///
+/// Sources and destinations may contain a single `*` if the refspec [is a pattern][RefSpec::is_pattern()].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefSpec {
+    mode: Mode,
+    operation: Operation,
+    source: Option<BString>,
+    destination: Option<BString>,
+}
+
+/// Access
+impl RefSpec {
+    /// Return how references matching this refspec are treated.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Return the operation this refspec was parsed for.
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Return the source side of the refspec, which is `None` for push refspecs that delete their destination
+    /// or match all branches with `:`.
+    ///
+    /// Empty sources and `@` are normalized to `HEAD`.
+    pub fn source(&self) -> Option<&BStr> {
+        self.source.as_ref().map(|s| s.as_bstr())
+    }
+
+    /// Return the destination side of the refspec, which is `None` if there is no reference to update.
+    pub fn destination(&self) -> Option<&BStr> {
+        self.destination.as_ref().map(|d| d.as_bstr())
+    }
+
+    /// Return true if source and destination contain a `*` to match many references.
+    pub fn is_pattern(&self) -> bool {
+        matches!(&self.source, Some(source) if source.contains(&b'*'))
+    }
+
+    /// Return true if this is the push refspec `:`, which pushes all branches that exist on both sides.
+    pub fn is_matching(&self) -> bool {
+        self.source.is_none() && self.destination.is_none()
+    }
+
+    /// Return true if this is a push refspec like `:refs/heads/topic` which deletes its destination on the remote.
+    pub fn is_deletion(&self) -> bool {
+        self.source.is_none() && self.destination.is_some()
+    }
+}
+
+/// Serialization
+impl RefSpec {
+    /// Write this refspec to `out` in a form that [`parse()`] understands.
+    pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
+        match self.mode {
+            Mode::Normal => {}
+            Mode::Force => out.write_all(b"+")?,
+            Mode::Negative => out.write_all(b"^")?,
+        }
+        if self.is_matching() {
+            return out.write_all(b":");
+        }
+        if let Some(source) = &self.source {
+            out.write_all(source)?;
+        }
+        if let Some(destination) = &self.destination {
+            out.write_all(b":")?;
+            out.write_all(destination)?;
+        }
+        Ok(())
+    }
+
+    /// Transform ourselves into a binary string, losslessly.
+    pub fn to_bstring(&self) -> BString {
+        let mut buf = Vec::with_capacity(
+            1 + self.source.as_ref().map_or(0, |s| s.len()) + 1 + self.destination.as_ref().map_or(0, |d| d.len()),
+        );
+        self.write_to(&mut buf).expect("io cannot fail in memory");
+        buf.into()
+    }
+}
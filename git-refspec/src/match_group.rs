@@ -0,0 +1,128 @@
+use crate::{Mode, Operation, RefSpec};
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// A reference name matched by one of the refspecs in a group, along with the name it maps to.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Mapping<'a> {
+    /// The refspec that matched.
+    pub spec: &'a RefSpec,
+    /// The name on the other side of the refspec, or `None` if there is none, like for fetch refspecs
+    /// without destination or push refspecs deleting their destination.
+    pub name: Option<BString>,
+}
+
+/// Matching of individual reference names
+impl RefSpec {
+    /// Return true if the reference `name` matches our source.
+    ///
+    /// Note that the matching push refspec `:` matches all names, leaving it to the caller to assure the reference
+    /// exists on both sides.
+    pub fn matches_source(&self, name: &BStr) -> bool {
+        match self.source() {
+            Some(source) => capture(source, name).is_some(),
+            None => self.is_matching(),
+        }
+    }
+
+    /// Return true if the reference `name` matches our destination, or our source for push refspecs without destination.
+    pub fn matches_destination(&self, name: &BStr) -> bool {
+        match self.destination_pattern() {
+            Some(destination) => capture(destination, name).is_some(),
+            None => self.is_matching(),
+        }
+    }
+
+    /// Map the reference `name` matching our source to its destination, or return `None` if it doesn't match or
+    /// there is no destination.
+    pub fn destination_for(&self, name: &BStr) -> Option<BString> {
+        if self.is_matching() {
+            return Some(name.to_owned());
+        }
+        let matched = capture(self.source()?, name)?;
+        Some(substitute(self.destination_pattern()?, matched))
+    }
+
+    /// Map the reference `name` matching our destination back to its source, or return `None` if it doesn't match or
+    /// there is no source.
+    pub fn source_for(&self, name: &BStr) -> Option<BString> {
+        if self.is_matching() {
+            return Some(name.to_owned());
+        }
+        let matched = capture(self.destination_pattern()?, name)?;
+        Some(substitute(self.source()?, matched))
+    }
+
+    fn destination_pattern(&self) -> Option<&BStr> {
+        match (self.mode(), self.operation()) {
+            (Mode::Negative, _) => None,
+            (_, Operation::Push) => self.destination().or_else(|| self.source()),
+            (_, Operation::Fetch) => self.destination(),
+        }
+    }
+}
+
+/// Map the source reference `name` to destinations using all non-negative `specs` that match it, in order.
+///
+/// If any negative refspec matches `name`, it is excluded and no mappings are returned.
+pub fn source_to_destination<'a>(specs: &'a [RefSpec], name: &BStr) -> Vec<Mapping<'a>> {
+    if is_excluded(specs, name) {
+        return Vec::new();
+    }
+    specs
+        .iter()
+        .filter(|spec| spec.mode() != Mode::Negative && spec.matches_source(name))
+        .map(|spec| Mapping {
+            spec,
+            name: spec.destination_for(name),
+        })
+        .collect()
+}
+
+/// Map the destination reference `name` back to its sources using all non-negative `specs` that match it, in order.
+///
+/// Sources excluded by a negative refspec are skipped.
+pub fn destination_to_source<'a>(specs: &'a [RefSpec], name: &BStr) -> Vec<Mapping<'a>> {
+    specs
+        .iter()
+        .filter(|spec| spec.mode() != Mode::Negative && spec.matches_destination(name))
+        .map(|spec| Mapping {
+            spec,
+            name: spec.source_for(name),
+        })
+        .filter(|mapping| match &mapping.name {
+            Some(source) => !is_excluded(specs, source.as_bstr()),
+            None => true,
+        })
+        .collect()
+}
+
+fn is_excluded(specs: &[RefSpec], name: &BStr) -> bool {
+    specs
+        .iter()
+        .any(|spec| spec.mode() == Mode::Negative && spec.matches_source(name))
+}
+
+/// Return the portion of `name` matched by the `*` in `pattern`, or an empty string if `pattern` is no pattern and
+/// equals `name`.
+fn capture<'a>(pattern: &BStr, name: &'a BStr) -> Option<&'a BStr> {
+    match pattern.find_byte(b'*') {
+        None => (pattern == name).then(|| name[..0].as_bstr()),
+        Some(pos) => {
+            let (prefix, suffix) = (&pattern[..pos], &pattern[pos + 1..]);
+            (name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix))
+                .then(|| name[prefix.len()..name.len() - suffix.len()].as_bstr())
+        }
+    }
+}
+
+fn substitute(pattern: &BStr, matched: &BStr) -> BString {
+    match pattern.find_byte(b'*') {
+        None => pattern.to_owned(),
+        Some(pos) => {
+            let mut out = BString::from(&pattern[..pos]);
+            out.push_str(matched);
+            out.push_str(&pattern[pos + 1..]);
+            out
+        }
+    }
+}
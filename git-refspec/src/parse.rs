@@ -0,0 +1,150 @@
+use crate::{Mode, Operation, RefSpec};
+use bstr::{BStr, BString, ByteSlice};
+use quick_error::quick_error;
+
+quick_error! {
+    /// The error returned by [`parse()`][crate::parse()].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        NegativeWithDestination {
+            display("Negative refspecs cannot have destinations as they exclude sources")
+        }
+        NegativeObjectId {
+            display("Negative refspecs must not be object ids")
+        }
+        PushToEmpty {
+            display("Push refspecs must not have an empty destination")
+        }
+        PatternUnsupported { pattern: BString } {
+            display("The pattern '{}' contains more than one '*'", pattern)
+        }
+        PatternUnbalanced {
+            display("Both sides of the refspec must be a pattern, or neither of them")
+        }
+        ReferenceName(err: git_validate::reference::name::Error) {
+            display("The reference name in the refspec is invalid")
+            from()
+            source(err)
+        }
+    }
+}
+
+/// Parse `spec` for use in the given `operation`, validating it like git does.
+///
+/// Negative refspecs like `^refs/heads/wip` are supported, as is the push refspec `:` to push all matching branches.
+/// Push sources that aren't patterns are not validated as they may be any revision.
+pub fn parse(mut spec: &BStr, operation: Operation) -> Result<RefSpec, Error> {
+    let mode = match spec.first() {
+        Some(b'+') => {
+            spec = spec[1..].as_bstr();
+            Mode::Force
+        }
+        Some(b'^') => {
+            spec = spec[1..].as_bstr();
+            Mode::Negative
+        }
+        _ => Mode::Normal,
+    };
+
+    if operation == Operation::Push && spec == ":" && mode != Mode::Negative {
+        return Ok(RefSpec {
+            mode,
+            operation,
+            source: None,
+            destination: None,
+        });
+    }
+
+    let (source, destination) = match spec.rfind_byte(b':') {
+        Some(pos) => (spec[..pos].as_bstr(), Some(spec[pos + 1..].as_bstr())),
+        None => (spec, None),
+    };
+
+    let source_is_pattern = is_pattern(source)?;
+    let destination_is_pattern = destination.map(is_pattern).transpose()?.unwrap_or(false);
+    if mode == Mode::Negative {
+        if destination.is_some() {
+            return Err(Error::NegativeWithDestination);
+        }
+        if looks_like_object_id(source) {
+            return Err(Error::NegativeObjectId);
+        }
+        validate(source, source_is_pattern)?;
+        return Ok(RefSpec {
+            mode,
+            operation,
+            source: Some(source.to_owned()),
+            destination: None,
+        });
+    }
+
+    let destination = destination.filter(|d| !d.is_empty() || operation == Operation::Push);
+    let is_pattern = match (source_is_pattern, destination) {
+        (true, Some(_)) if !destination_is_pattern => return Err(Error::PatternUnbalanced),
+        (true, None) if operation == Operation::Fetch => return Err(Error::PatternUnbalanced),
+        (false, Some(_)) if destination_is_pattern => return Err(Error::PatternUnbalanced),
+        (is_pattern, _) => is_pattern,
+    };
+
+    let source = if source == "@" { "HEAD".into() } else { source };
+    let source = match operation {
+        Operation::Fetch => {
+            if source.is_empty() {
+                Some("HEAD".into())
+            } else {
+                if !looks_like_object_id(source) {
+                    validate(source, is_pattern)?;
+                }
+                Some(source.to_owned())
+            }
+        }
+        Operation::Push => match destination {
+            None => Some(validate(source, is_pattern)?.to_owned()),
+            Some(_) if source.is_empty() => None,
+            Some(_) => {
+                if is_pattern {
+                    validate(source, is_pattern)?;
+                }
+                Some(source.to_owned())
+            }
+        },
+    };
+    let destination = match destination {
+        Some(destination) if destination.is_empty() => return Err(Error::PushToEmpty),
+        Some(destination) => Some(validate(destination, is_pattern)?.to_owned()),
+        None => None,
+    };
+
+    Ok(RefSpec {
+        mode,
+        operation,
+        source,
+        destination,
+    })
+}
+
+fn is_pattern(name: &BStr) -> Result<bool, Error> {
+    match name.find_iter("*").count() {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(Error::PatternUnsupported {
+            pattern: name.to_owned(),
+        }),
+    }
+}
+
+fn looks_like_object_id(name: &BStr) -> bool {
+    name.len() == 40 && name.iter().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Validate `name` as partial reference name, with its `*` replaced by a valid character if it `is_pattern`.
+fn validate(name: &BStr, is_pattern: bool) -> Result<&BStr, Error> {
+    if is_pattern {
+        let name = name.replace("*", "a");
+        git_validate::reference::name_partial(name.as_bstr())?;
+    } else {
+        git_validate::reference::name_partial(name)?;
+    }
+    Ok(name)
+}
@@ -0,0 +1,111 @@
+use bstr::ByteSlice;
+use git_refspec::{match_group, Operation, RefSpec};
+
+fn specs(operation: Operation, specs: &[&str]) -> Vec<RefSpec> {
+    specs
+        .iter()
+        .map(|spec| git_refspec::parse(spec.as_bytes().as_bstr(), operation).expect("valid spec"))
+        .collect()
+}
+
+fn names(mappings: Vec<match_group::Mapping<'_>>) -> Vec<Option<String>> {
+    mappings
+        .into_iter()
+        .map(|m| m.name.map(|name| name.to_string()))
+        .collect()
+}
+
+fn some(name: &str) -> Option<String> {
+    Some(name.into())
+}
+
+#[test]
+fn fetch_patterns_map_source_to_destination_and_back() {
+    let specs = specs(Operation::Fetch, &["+refs/heads/*:refs/remotes/origin/*"]);
+    assert_eq!(
+        names(match_group::source_to_destination(&specs, "refs/heads/feat/x".into())),
+        vec![some("refs/remotes/origin/feat/x")]
+    );
+    assert!(match_group::source_to_destination(&specs, "refs/tags/v1".into()).is_empty());
+    assert_eq!(
+        names(match_group::destination_to_source(
+            &specs,
+            "refs/remotes/origin/main".into()
+        )),
+        vec![some("refs/heads/main")]
+    );
+}
+
+#[test]
+fn patterns_with_suffix_capture_only_the_middle() {
+    let spec = &specs(Operation::Fetch, &["refs/heads/feature-*-wip:refs/wip/*"])[0];
+    assert_eq!(
+        spec.destination_for("refs/heads/feature-a/b-wip".into()),
+        Some("refs/wip/a/b".into())
+    );
+    assert!(!spec.matches_source("refs/heads/feature-wip".into()));
+    assert_eq!(spec.destination_for("refs/heads/main".into()), None);
+}
+
+#[test]
+fn exact_refspecs_and_refspecs_without_destination() {
+    let specs = specs(
+        Operation::Fetch,
+        &[
+            "refs/heads/main:refs/remotes/origin/main",
+            "refs/heads/main",
+            "refs/tags/*:refs/tags/*",
+        ],
+    );
+    assert_eq!(
+        names(match_group::source_to_destination(&specs, "refs/heads/main".into())),
+        vec![some("refs/remotes/origin/main"), None],
+        "all matching specs are returned in order"
+    );
+    assert_eq!(
+        names(match_group::source_to_destination(&specs, "refs/tags/v1.0".into())),
+        vec![some("refs/tags/v1.0")]
+    );
+}
+
+#[test]
+fn negative_refspecs_exclude_sources_in_both_directions() {
+    let specs = specs(
+        Operation::Fetch,
+        &["+refs/heads/*:refs/remotes/origin/*", "^refs/heads/wip/*"],
+    );
+    assert!(match_group::source_to_destination(&specs, "refs/heads/wip/x".into()).is_empty());
+    assert!(match_group::destination_to_source(&specs, "refs/remotes/origin/wip/x".into()).is_empty());
+    assert_eq!(
+        names(match_group::source_to_destination(&specs, "refs/heads/main".into())),
+        vec![some("refs/remotes/origin/main")]
+    );
+}
+
+#[test]
+fn push_refspecs_without_destination_map_to_themselves() {
+    let specs = specs(Operation::Push, &["refs/heads/*", ":refs/heads/gone"]);
+    assert_eq!(
+        names(match_group::source_to_destination(&specs, "refs/heads/main".into())),
+        vec![some("refs/heads/main")]
+    );
+    assert_eq!(
+        names(match_group::destination_to_source(&specs, "refs/heads/gone".into())),
+        vec![some("refs/heads/gone"), None],
+        "deletions match their destination but have no source"
+    );
+}
+
+#[test]
+fn the_matching_push_refspec_matches_everything() {
+    let spec = &specs(Operation::Push, &[":"])[0];
+    assert!(spec.matches_source("refs/heads/main".into()));
+    assert_eq!(
+        spec.destination_for("refs/heads/main".into()),
+        Some("refs/heads/main".into())
+    );
+    assert_eq!(
+        spec.source_for("refs/heads/main".into()),
+        Some("refs/heads/main".into())
+    );
+}
@@ -0,0 +1,181 @@
+use bstr::ByteSlice;
+use git_refspec::{parse::Error, Mode, Operation, RefSpec};
+
+fn fetch(spec: &str) -> Result<RefSpec, Error> {
+    git_refspec::parse(spec.as_bytes().as_bstr(), Operation::Fetch)
+}
+
+fn push(spec: &str) -> Result<RefSpec, Error> {
+    git_refspec::parse(spec.as_bytes().as_bstr(), Operation::Push)
+}
+
+fn assert_spec(spec: &RefSpec, mode: Mode, source: Option<&str>, destination: Option<&str>) {
+    assert_eq!(spec.mode(), mode);
+    assert_eq!(spec.source(), source.map(|s| s.as_bytes().as_bstr()));
+    assert_eq!(spec.destination(), destination.map(|d| d.as_bytes().as_bstr()));
+}
+
+mod fetch {
+    use super::{assert_spec, fetch};
+    use git_refspec::{parse::Error, Mode};
+
+    #[test]
+    fn default_clone_refspec() {
+        let spec = fetch("+refs/heads/*:refs/remotes/origin/*").unwrap();
+        assert_spec(&spec, Mode::Force, Some("refs/heads/*"), Some("refs/remotes/origin/*"));
+        assert!(spec.is_pattern());
+        assert!(!spec.is_matching());
+        assert_eq!(spec.to_bstring(), "+refs/heads/*:refs/remotes/origin/*");
+    }
+
+    #[test]
+    fn patterns_may_match_partial_components() {
+        let spec = fetch("refs/heads/feature-*-wip:refs/remotes/origin/wip/*").unwrap();
+        assert_spec(
+            &spec,
+            Mode::Normal,
+            Some("refs/heads/feature-*-wip"),
+            Some("refs/remotes/origin/wip/*"),
+        );
+    }
+
+    #[test]
+    fn source_without_destination() {
+        let spec = fetch("refs/heads/main").unwrap();
+        assert_spec(&spec, Mode::Normal, Some("refs/heads/main"), None);
+        assert_spec(
+            &fetch("refs/heads/main:").unwrap(),
+            Mode::Normal,
+            Some("refs/heads/main"),
+            None,
+        );
+    }
+
+    #[test]
+    fn empty_source_and_at_are_head() {
+        assert_spec(&fetch("").unwrap(), Mode::Normal, Some("HEAD"), None);
+        assert_spec(
+            &fetch(":refs/heads/fetched").unwrap(),
+            Mode::Normal,
+            Some("HEAD"),
+            Some("refs/heads/fetched"),
+        );
+        assert_spec(&fetch("@").unwrap(), Mode::Normal, Some("HEAD"), None);
+    }
+
+    #[test]
+    fn object_ids_are_valid_sources() {
+        let id = "0123456789012345678901234567890123456789";
+        assert_spec(
+            &fetch(&format!("{}:refs/heads/x", id)).unwrap(),
+            Mode::Normal,
+            Some(id),
+            Some("refs/heads/x"),
+        );
+    }
+
+    #[test]
+    fn negative() {
+        let spec = fetch("^refs/heads/wip/*").unwrap();
+        assert_spec(&spec, Mode::Negative, Some("refs/heads/wip/*"), None);
+        assert_eq!(spec.to_bstring(), "^refs/heads/wip/*");
+
+        assert!(matches!(
+            fetch("^refs/heads/wip:refs/heads/wip").unwrap_err(),
+            Error::NegativeWithDestination
+        ));
+        assert!(matches!(
+            fetch("^0123456789012345678901234567890123456789").unwrap_err(),
+            Error::NegativeObjectId
+        ));
+        assert!(matches!(fetch("^").unwrap_err(), Error::ReferenceName(_)));
+    }
+
+    #[test]
+    fn unbalanced_patterns_are_invalid() {
+        for spec in &[
+            "refs/heads/*:refs/remotes/origin/main",
+            "refs/heads/main:refs/remotes/*",
+            "refs/heads/*",
+        ] {
+            assert!(
+                matches!(fetch(spec).unwrap_err(), Error::PatternUnbalanced),
+                "{} should be unbalanced",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn multiple_globs_are_invalid() {
+        assert!(matches!(
+            fetch("refs/*/*:refs/remotes/*/*").unwrap_err(),
+            Error::PatternUnsupported { .. }
+        ));
+    }
+
+    #[test]
+    fn invalid_reference_names() {
+        for spec in &[
+            "refs/heads/a..b",
+            "refs/heads/main:refs/heads/x~1",
+            "refs//heads/x",
+            "refs/heads/x.lock",
+        ] {
+            assert!(
+                matches!(fetch(spec).unwrap_err(), Error::ReferenceName(_)),
+                "{} should be invalid",
+                spec
+            );
+        }
+    }
+}
+
+mod push {
+    use super::{assert_spec, push};
+    use git_refspec::{parse::Error, Mode};
+
+    #[test]
+    fn matching() {
+        let spec = push(":").unwrap();
+        assert!(spec.is_matching());
+        assert_spec(&spec, Mode::Normal, None, None);
+        assert_eq!(spec.to_bstring(), ":");
+        let spec = push("+:").unwrap();
+        assert!(spec.is_matching());
+        assert_eq!(spec.mode(), Mode::Force);
+        assert_eq!(spec.to_bstring(), "+:");
+    }
+
+    #[test]
+    fn deletion() {
+        let spec = push(":refs/heads/topic").unwrap();
+        assert!(spec.is_deletion());
+        assert_spec(&spec, Mode::Normal, None, Some("refs/heads/topic"));
+        assert_eq!(spec.to_bstring(), ":refs/heads/topic");
+    }
+
+    #[test]
+    fn revisions_are_valid_sources_if_there_is_a_destination() {
+        assert_spec(
+            &push("HEAD~1:refs/heads/main").unwrap(),
+            Mode::Normal,
+            Some("HEAD~1"),
+            Some("refs/heads/main"),
+        );
+        assert!(matches!(push("HEAD~1").unwrap_err(), Error::ReferenceName(_)));
+    }
+
+    #[test]
+    fn patterns_without_destination_push_to_the_same_name() {
+        let spec = push("refs/heads/*").unwrap();
+        assert_spec(&spec, Mode::Normal, Some("refs/heads/*"), None);
+        assert!(spec.is_pattern());
+    }
+
+    #[test]
+    fn empty_destinations_are_invalid() {
+        assert!(matches!(push("refs/heads/main:").unwrap_err(), Error::PushToEmpty));
+        assert!(matches!(push("").unwrap_err(), Error::ReferenceName(_)));
+    }
+}
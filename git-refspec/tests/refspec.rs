@@ -0,0 +1,2 @@
+mod match_group;
+mod parse;
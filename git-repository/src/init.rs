@@ -94,7 +94,25 @@ fn create_dir(p: &Path) -> Result<(), Error> {
     fs::create_dir_all(p).map_err(|e| Error::CreateDirectory(e, p.to_owned()))
 }
 
+/// Options for [`repository_with_options()`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If true, all 256 fanout directories of the loose object database are created right away, which can speed up
+    /// the first writes on some filesystems.
+    pub precreate_object_fanout: bool,
+}
+
 pub fn repository(directory: impl Into<PathBuf>) -> Result<(), Error> {
+    repository_with_options(directory, Options::default())
+}
+
+/// Like [`repository()`], but configured with the given `options`.
+pub fn repository_with_options(
+    directory: impl Into<PathBuf>,
+    Options {
+        precreate_object_fanout,
+    }: Options,
+) -> Result<(), Error> {
     let mut cursor = directory.into();
     cursor.push(GIT_DIR_NAME);
 
@@ -132,6 +150,12 @@ pub fn repository(directory: impl Into<PathBuf>) -> Result<(), Error> {
         let mut cursor = NewDir(&mut cursor).at("objects")?;
         create_dir(PathCursor(cursor.as_mut()).at("info"))?;
         create_dir(PathCursor(cursor.as_mut()).at("pack"))?;
+        if precreate_object_fanout {
+            let objects_dir = cursor.as_mut().clone();
+            git_odb::loose::Store::at(objects_dir.clone())
+                .create_fanout_directories()
+                .map_err(|e| Error::CreateDirectory(e, objects_dir))?;
+        }
     }
 
     {
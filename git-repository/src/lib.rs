@@ -33,6 +33,7 @@
 //! * [`odb`]
 //!   * [`pack`][odb::pack]
 //! * [`refs`]
+//! * [`refspec`]
 //! * [`interrupt`]
 //! * [`tempfile`]
 //! * [`traverse`]
@@ -61,6 +62,8 @@ pub use git_odb as odb;
 #[cfg(feature = "git-protocol")]
 pub use git_protocol as protocol;
 pub use git_ref as refs;
+#[cfg(feature = "git-refspec")]
+pub use git_refspec as refspec;
 pub use git_tempfile as tempfile;
 #[cfg(feature = "git-traverse")]
 pub use git_traverse as traverse;
@@ -85,6 +88,15 @@ pub use path::Path;
 
 pub mod repository;
 
+#[cfg(feature = "git-traverse")]
+pub mod revision;
+
+pub mod submodule;
+pub use submodule::Submodule;
+
+pub mod worktree;
+pub use worktree::Worktree;
+
 pub struct Repository {
     pub refs: git_ref::file::Store,
     pub working_tree: Option<PathBuf>,
@@ -99,9 +111,19 @@ impl Repository {
         }
     }
 
+    /// The repository directory private to our worktree, containing `HEAD` and the `index`.
     pub fn git_dir(&self) -> &std::path::Path {
         &self.refs.base
     }
+    /// The repository directory shared among all worktrees, which is the same as [`git_dir()`][Repository::git_dir()]
+    /// unless this is a linked worktree.
+    pub fn common_dir(&self) -> &std::path::Path {
+        self.refs.common_dir()
+    }
+    /// The location of the index file of our worktree.
+    pub fn index_path(&self) -> std::path::PathBuf {
+        self.git_dir().join("index")
+    }
     pub fn objects_dir(&self) -> &std::path::Path {
         &self.odb.dbs[0].loose.path
     }
@@ -31,7 +31,16 @@ pub fn existing(directory: impl AsRef<Path>) -> Result<crate::Path, existing::Er
             break Ok(crate::Path::from_dot_git_dir(cursor, kind));
         }
         let git_dir = cursor.join(".git");
-        if let Ok(kind) = path::is_git(&git_dir) {
+        if git_dir.is_file() {
+            if let Ok(linked_git_dir) = path::from_gitfile(&git_dir) {
+                if path::is_git(&linked_git_dir).is_ok() {
+                    break Ok(crate::Path::LinkedWorkingTree {
+                        work_dir: cursor.to_owned(),
+                        git_dir: linked_git_dir,
+                    });
+                }
+            }
+        } else if let Ok(kind) = path::is_git(&git_dir) {
             break Ok(crate::Path::from_dot_git_dir(git_dir, kind));
         }
         match cursor.parent() {
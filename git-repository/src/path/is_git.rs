@@ -18,6 +18,11 @@ quick_error! {
         MissingRefsDirectory(missing: PathBuf) {
             display("Expected a refs directory at '{}'", missing.display())
         }
+        CommonDir(err: std::io::Error) {
+            display("Could not read the 'commondir' file")
+            from()
+            source(err)
+        }
     }
 }
 
@@ -31,16 +36,18 @@ pub fn is_bare(git_dir: impl AsRef<Path>) -> bool {
 /// What constitutes a valid git repository, and what's yet to be implemented.
 ///
 /// * [x] a valid head
-/// * [ ] git common directory
-///   * [ ] respect GIT_COMMON_DIR
+/// * [x] git common directory
+///   * [x] respect GIT_COMMON_DIR
 /// * [x] an objects directory
 ///   * [x] respect GIT_OBJECT_DIRECTORY
 /// * [x] a refs directory
 pub fn is_git(git_dir: impl AsRef<Path>) -> Result<crate::Kind, Error> {
     let dot_git = git_dir.as_ref();
+    let common_dir = crate::path::common_dir(dot_git)?;
+    let common_dir = common_dir.as_deref().unwrap_or(dot_git);
 
     {
-        let refs = git_ref::file::Store::at(&dot_git, Default::default());
+        let refs = git_ref::file::Store::for_linked_worktree(dot_git, common_dir, Default::default());
         let head = refs.find_one_existing("HEAD")?;
         if head.relative_path() != Path::new("HEAD") {
             return Err(Error::MisplacedHead(head.into_relative_path()));
@@ -50,13 +57,13 @@ pub fn is_git(git_dir: impl AsRef<Path>) -> Result<crate::Kind, Error> {
     {
         let objects_path = std::env::var("GIT_OBJECT_DIRECTORY")
             .map(PathBuf::from)
-            .unwrap_or_else(|_| dot_git.join("objects"));
+            .unwrap_or_else(|_| common_dir.join("objects"));
         if !objects_path.is_dir() {
             return Err(Error::MissingObjectsDirectory(objects_path));
         }
     }
     {
-        let refs_path = dot_git.join("refs");
+        let refs_path = common_dir.join("refs");
         if !refs_path.is_dir() {
             return Err(Error::MissingRefsDirectory(refs_path));
         }
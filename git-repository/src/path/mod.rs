@@ -1,4 +1,5 @@
 use crate::Kind;
+use git_object::bstr::ByteSlice;
 use std::path::PathBuf;
 
 pub mod discover;
@@ -8,13 +9,18 @@ pub use is_git::{is_bare, is_git};
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Path {
     WorkingTree(PathBuf),
+    /// A working tree whose `.git` is a _gitfile_ pointing to its `git_dir`, as used by linked worktrees and submodules.
+    LinkedWorkingTree {
+        work_dir: PathBuf,
+        git_dir: PathBuf,
+    },
     Repository(PathBuf),
 }
 
 impl AsRef<std::path::Path> for Path {
     fn as_ref(&self) -> &std::path::Path {
         match self {
-            Path::WorkingTree(path) | Path::Repository(path) => path,
+            Path::WorkingTree(path) | Path::LinkedWorkingTree { work_dir: path, .. } | Path::Repository(path) => path,
         }
     }
 }
@@ -29,7 +35,7 @@ impl Path {
     }
     pub fn kind(&self) -> Kind {
         match self {
-            Path::WorkingTree(_) => Kind::WorkingTree,
+            Path::WorkingTree(_) | Path::LinkedWorkingTree { .. } => Kind::WorkingTree,
             Path::Repository(_) => Kind::Bare,
         }
     }
@@ -37,7 +43,55 @@ impl Path {
     pub fn into_repository_directory(self) -> PathBuf {
         match self {
             Path::WorkingTree(path) => path.join(".git"),
+            Path::LinkedWorkingTree { git_dir, .. } => git_dir,
             Path::Repository(path) => path,
         }
     }
 }
+
+/// Read the repository location from the _gitfile_ at `path`, which is a file with a single `gitdir: <path>` line.
+///
+/// Relative locations are resolved relative to the directory containing the gitfile.
+pub fn from_gitfile(path: impl AsRef<std::path::Path>) -> std::io::Result<PathBuf> {
+    let path = path.as_ref();
+    let content = std::fs::read(path)?;
+    let dir = content
+        .trim_with(|c| c.is_ascii_whitespace())
+        .strip_prefix(b"gitdir:")
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Expected 'gitdir: <path>' in gitfile at '{}'", path.display()),
+            )
+        })?
+        .trim_with(|c| c.is_ascii_whitespace())
+        .to_path_lossy();
+    Ok(if dir.is_relative() {
+        path.parent().expect("gitfile in directory").join(dir)
+    } else {
+        dir.into_owned()
+    })
+}
+
+/// Return the directory shared by all worktrees if `git_dir` belongs to a linked worktree, or `None` if `git_dir`
+/// is the common directory itself.
+///
+/// The `GIT_COMMON_DIR` environment variable takes precedence over the `commondir` file in `git_dir`, whose
+/// relative paths are resolved relative to `git_dir`.
+pub fn common_dir(git_dir: impl AsRef<std::path::Path>) -> std::io::Result<Option<PathBuf>> {
+    if let Some(dir) = std::env::var_os("GIT_COMMON_DIR") {
+        return Ok(Some(dir.into()));
+    }
+    let git_dir = git_dir.as_ref();
+    let content = match std::fs::read(git_dir.join("commondir")) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let dir = content.trim_with(|c| c.is_ascii_whitespace()).to_path_lossy();
+    Ok(Some(if dir.is_relative() {
+        git_dir.join(dir)
+    } else {
+        dir.into_owned()
+    }))
+}
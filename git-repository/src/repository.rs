@@ -25,6 +25,11 @@ pub mod discover {
                 from()
                 source(err)
             }
+            CommonDir(err: std::io::Error) {
+                display("Could not read the 'commondir' file")
+                from()
+                source(err)
+            }
             ObjectStoreInitialization(err: git_odb::linked::init::Error) {
                 display("Could not initialize the object database")
                 from()
@@ -38,18 +43,21 @@ pub mod discover {
             let path = discover::existing(directory)?;
             let (git_dir, working_tree) = match path {
                 crate::Path::WorkingTree(working_tree) => (working_tree.join(".git"), Some(working_tree)),
+                crate::Path::LinkedWorkingTree { work_dir, git_dir } => (git_dir, Some(work_dir)),
                 crate::Path::Repository(repository) => (repository, None),
             };
+            let common_dir = crate::path::common_dir(&git_dir)?;
+            let write_reflog = if working_tree.is_none() {
+                git_ref::file::WriteReflog::Disable
+            } else {
+                git_ref::file::WriteReflog::Normal
+            };
             Ok(Repository {
-                odb: git_odb::linked::Store::at(git_dir.join("objects"))?,
-                refs: git_ref::file::Store::at(
-                    git_dir,
-                    if working_tree.is_none() {
-                        git_ref::file::WriteReflog::Disable
-                    } else {
-                        git_ref::file::WriteReflog::Normal
-                    },
-                ),
+                odb: git_odb::linked::Store::at(common_dir.as_ref().unwrap_or(&git_dir).join("objects"))?,
+                refs: match common_dir {
+                    Some(common_dir) => git_ref::file::Store::for_linked_worktree(git_dir, common_dir, write_reflog),
+                    None => git_ref::file::Store::at(git_dir, write_reflog),
+                },
                 working_tree,
             })
         }
@@ -0,0 +1,198 @@
+//! Parsing of revisions and revision ranges like `a..b` into the commits to include and exclude during traversal.
+use std::collections::HashSet;
+
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BStr, BString, ByteSlice},
+    immutable,
+};
+use git_traverse::commit::{ancestors, Ancestors};
+
+/// A single revision or a range of revisions as [parsed][parse()] from its textual representation.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Spec<'a> {
+    /// `rev`, the commit `rev` along with all of its ancestors.
+    Include(&'a BStr),
+    /// `^rev`, hiding the commit `rev` along with all of its ancestors.
+    Exclude(&'a BStr),
+    /// `from..to`, the commits reachable from `to` but not from `from`.
+    Range {
+        /// The revision whose ancestors to hide.
+        from: &'a BStr,
+        /// The revision whose ancestors to include.
+        to: &'a BStr,
+    },
+    /// `left...right`, the commits reachable from either `left` or `right`, but not from both.
+    SymmetricDifference {
+        /// One side of the difference.
+        left: &'a BStr,
+        /// The other side of the difference.
+        right: &'a BStr,
+    },
+    /// `rev^!`, the commit `rev` without any of its ancestors.
+    ExcludeParents(&'a BStr),
+}
+
+///
+pub mod parse {
+    use git_object::bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`parse()`][super::parse()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            Empty {
+                display("A revision must not be empty")
+            }
+            InvalidCombination(spec: BString) {
+                display("'{}' combines range notations which can't be used together", spec)
+            }
+        }
+    }
+}
+
+/// Parse a single revision or range of revisions from `spec`, like `rev`, `^rev`, `a..b`, `a...b` or `rev^!`.
+///
+/// Omitted revisions on either side of a range, like in `..b` or `a...`, refer to `HEAD`.
+/// The revisions themselves are not validated, which happens when [resolving][Tips::from_specs()] them.
+pub fn parse(spec: &BStr) -> Result<Spec<'_>, parse::Error> {
+    fn or_head(rev: &[u8]) -> &BStr {
+        if rev.is_empty() {
+            b"HEAD".as_bstr()
+        } else {
+            rev.as_bstr()
+        }
+    }
+    let is_range = |rev: &[u8]| rev.find("..").is_some();
+    if spec.is_empty() {
+        return Err(parse::Error::Empty);
+    }
+    if let Some(rev) = spec.strip_suffix(b"^!") {
+        return match rev {
+            [] => Err(parse::Error::Empty),
+            rev if is_range(rev) || rev.starts_with(b"^") => Err(parse::Error::InvalidCombination(spec.into())),
+            rev => Ok(Spec::ExcludeParents(rev.as_bstr())),
+        };
+    }
+    if let Some(rev) = spec.strip_prefix(b"^") {
+        return match rev {
+            [] => Err(parse::Error::Empty),
+            rev if is_range(rev) => Err(parse::Error::InvalidCombination(spec.into())),
+            rev => Ok(Spec::Exclude(rev.as_bstr())),
+        };
+    }
+    Ok(if let Some(pos) = spec.find("...") {
+        Spec::SymmetricDifference {
+            left: or_head(&spec[..pos]),
+            right: or_head(&spec[pos + 3..]),
+        }
+    } else if let Some(pos) = spec.find("..") {
+        Spec::Range {
+            from: or_head(&spec[..pos]),
+            to: or_head(&spec[pos + 2..]),
+        }
+    } else {
+        Spec::Include(spec)
+    })
+}
+
+/// The commits to start a traversal from, and the commits to hide along with all of their ancestors.
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct Tips {
+    /// The commits to include along with their ancestors.
+    pub include: Vec<ObjectId>,
+    /// The commits to hide along with their ancestors, even if they are reachable from included commits.
+    pub exclude: Vec<ObjectId>,
+}
+
+///
+pub mod tips {
+    use git_hash::ObjectId;
+    use git_object::bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`Tips::from_specs()`][super::Tips::from_specs()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            NotFound(name: BString) {
+                display("The revision '{}' could not be resolved to a commit", name)
+            }
+            FindCommit(id: ObjectId) {
+                display("The commit {} could not be found", id)
+            }
+            ObjectDecode(err: git_object::immutable::object::decode::Error) {
+                display("A commit could not be decoded")
+                from()
+                source(err)
+            }
+            Traverse(err: git_traverse::commit::ancestors::Error) {
+                display("The ancestors of a commit could not be traversed")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+
+impl Tips {
+    /// Turn all `specs` into the commits to include and exclude during traversal.
+    ///
+    /// `resolve` turns a single revision into the id of the commit it refers to, or `None` if it can't be resolved.
+    /// `find` looks up commits as needed by [`Ancestors`] to obtain the parents of commits, which is
+    /// only done for `rev^!` and to find the commits reachable from both sides of `left...right`.
+    pub fn from_specs<'a, Find>(
+        specs: impl IntoIterator<Item = Spec<'a>>,
+        mut resolve: impl FnMut(&BStr) -> Option<ObjectId>,
+        mut find: Find,
+    ) -> Result<Self, tips::Error>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<immutable::CommitIter<'b>>,
+    {
+        let mut resolve = |rev: &BStr| resolve(rev).ok_or_else(|| tips::Error::NotFound(BString::from(rev)));
+        let mut tips = Tips::default();
+        for spec in specs {
+            match spec {
+                Spec::Include(rev) => tips.include.push(resolve(rev)?),
+                Spec::Exclude(rev) => tips.exclude.push(resolve(rev)?),
+                Spec::Range { from, to } => {
+                    tips.exclude.push(resolve(from)?);
+                    tips.include.push(resolve(to)?);
+                }
+                Spec::SymmetricDifference { left, right } => {
+                    let (left, right) = (resolve(left)?, resolve(right)?);
+                    let reachable_from_left = Ancestors::new(Some(left), ancestors::State::default(), &mut find)
+                        .collect::<Result<HashSet<_>, _>>()?;
+                    let exclude = &mut tips.exclude;
+                    // Hiding the first commits reachable from both sides also hides everything they can reach.
+                    for id in Ancestors::filtered(Some(right), ancestors::State::default(), &mut find, |id| {
+                        let reachable_from_both = reachable_from_left.contains(id);
+                        if reachable_from_both {
+                            exclude.push(id.to_owned());
+                        }
+                        !reachable_from_both
+                    }) {
+                        id?;
+                    }
+                    tips.include.extend(vec![left, right]);
+                }
+                Spec::ExcludeParents(rev) => {
+                    let id = resolve(rev)?;
+                    let mut buf = Vec::new();
+                    for token in find(&id, &mut buf).ok_or(tips::Error::FindCommit(id))? {
+                        match token? {
+                            immutable::commit::iter::Token::Tree { .. } => {}
+                            immutable::commit::iter::Token::Parent { id } => tips.exclude.push(id),
+                            _ => break,
+                        }
+                    }
+                    tips.include.push(id);
+                }
+            }
+        }
+        Ok(tips)
+    }
+}
@@ -0,0 +1,196 @@
+//! Access to submodules as configured in `.gitmodules` files and recorded as _gitlinks_ in trees.
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use git_hash::{oid, ObjectId};
+use git_object::bstr::{BStr, BString, ByteSlice};
+use git_odb::FindExt;
+
+use crate::Repository;
+
+/// A submodule as configured in a `.gitmodules` file.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Submodule {
+    /// The name of the submodule, as used in the `submodule.<name>` section and to locate its repository in `.git/modules`.
+    pub name: BString,
+    /// The path of the submodule relative to the root of the superproject's working tree.
+    pub path: BString,
+    /// The url to clone the submodule from, which may be relative to the superproject's remote.
+    pub url: Option<BString>,
+    /// The branch to track when updating the submodule.
+    pub branch: Option<BString>,
+}
+
+///
+pub mod decode {
+    use git_object::bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        #[derive(Debug)]
+        pub enum Error {
+            Config(message: String) {
+                display("The .gitmodules file could not be parsed: {}", message)
+            }
+            MissingPath(name: BString) {
+                display("The submodule '{}' does not have a path configured", name)
+            }
+            InvalidName(name: BString) {
+                display("The submodule name '{}' is not allowed as it could escape the modules directory", name)
+            }
+        }
+    }
+}
+
+/// Parse all submodules from the `data` of a `.gitmodules` file, in order of appearance.
+///
+/// Submodules configured more than once are only returned once, following the _last one wins_ rule for their values.
+pub fn from_bytes(data: &[u8]) -> Result<Vec<Submodule>, decode::Error> {
+    let parser = git_config::parser::parse_from_bytes(data).map_err(|err| decode::Error::Config(err.to_string()))?;
+    let mut names = Vec::<String>::new();
+    for section in parser.sections() {
+        let header = &section.section_header;
+        if !header.name.0.eq_ignore_ascii_case("submodule") {
+            continue;
+        }
+        if let Some(name) = header.subsection_name.as_ref() {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    let config = git_config::file::GitConfig::from(parser);
+    let value = |name: &str, key: &str| -> Option<BString> {
+        config
+            .get_raw_value("submodule", Some(name), key)
+            .ok()
+            .map(|v| git_config::values::normalize_cow(v).into_owned().into())
+    };
+    names
+        .iter()
+        .map(|name| {
+            if !is_valid_name(name.as_bytes().as_bstr()) {
+                return Err(decode::Error::InvalidName(name.as_str().into()));
+            }
+            Ok(Submodule {
+                name: name.as_str().into(),
+                path: value(name, "path").ok_or_else(|| decode::Error::MissingPath(name.as_str().into()))?,
+                url: value(name, "url"),
+                branch: value(name, "branch"),
+            })
+        })
+        .collect()
+}
+
+/// Names are used as path below `.git/modules` and must thus not be able to point anywhere else.
+fn is_valid_name(name: &BStr) -> bool {
+    !name.is_empty() && !name.starts_with(b"/") && !name.split(|b| *b == b'/' || *b == b'\\').any(|c| c == b"..")
+}
+
+impl Submodule {
+    /// The location of the submodule's repository within the superproject's `git_dir`, which is where git
+    /// places it when cloning or absorbing submodules.
+    pub fn modules_dir(&self, git_dir: impl AsRef<Path>) -> PathBuf {
+        git_dir.as_ref().join("modules").join(to_path(self.name.as_ref()))
+    }
+
+    /// The location of the submodule's checkout within the superproject's `working_tree`.
+    pub fn work_tree(&self, working_tree: impl AsRef<Path>) -> PathBuf {
+        working_tree.as_ref().join(to_path(self.path.as_ref()))
+    }
+
+    /// Return the location of the submodule's repository, if it exists, or `None` if it was not yet cloned.
+    ///
+    /// If a `working_tree` is given, a `.git` directory or _gitfile_ in the submodule's checkout takes precedence
+    /// over the repository location in the `modules` directory below the superproject's `git_dir`.
+    pub fn git_dir(&self, git_dir: impl AsRef<Path>, working_tree: Option<&Path>) -> Option<PathBuf> {
+        if let Some(dot_git) = working_tree.map(|wt| self.work_tree(wt).join(".git")) {
+            if dot_git.is_dir() {
+                return Some(dot_git);
+            }
+            if let Ok(dir) = crate::path::from_gitfile(&dot_git) {
+                return Some(dir);
+            }
+        }
+        Some(self.modules_dir(git_dir)).filter(|dir| dir.is_dir())
+    }
+}
+
+fn to_path(path: &BStr) -> Cow<'_, Path> {
+    path.to_path_lossy()
+}
+
+///
+pub mod find {
+    use quick_error::quick_error;
+
+    quick_error! {
+        #[derive(Debug)]
+        pub enum Error {
+            FindExistingObject(err: git_odb::pack::find::existing_object::Error<git_odb::compound::find::Error>) {
+                display("A commit or blob could not be found")
+                from()
+                source(err)
+            }
+            LookupEntry(err: git_object::tree::lookup::Error) {
+                display("An entry could not be looked up in a tree")
+                from()
+                source(err)
+            }
+            Decode(err: super::decode::Error) {
+                display("The .gitmodules file could not be decoded")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+
+impl Repository {
+    /// Return all submodules configured in the `.gitmodules` file of the commit with `commit_id`, along with the
+    /// commit they are pinned to by the _gitlink_ at their path in the commit's tree, if there is one.
+    ///
+    /// A commit without `.gitmodules` file has no submodules.
+    pub fn submodules(&self, commit_id: impl AsRef<oid>) -> Result<Vec<(Submodule, Option<ObjectId>)>, find::Error> {
+        let mut buf = Vec::new();
+        let tree_id = self
+            .odb
+            .find_existing_commit(commit_id, &mut buf, &mut git_odb::pack::cache::Never)?
+            .tree();
+        let modules_id = match git_object::tree::lookup_entry(&tree_id, ".gitmodules", &mut buf, |id, buf| {
+            self.odb
+                .find_existing_tree_iter(id, buf, &mut git_odb::pack::cache::Never)
+                .ok()
+        })? {
+            Some(entry) if !entry.mode.is_tree() => entry.oid,
+            _ => return Ok(Vec::new()),
+        };
+        let modules = from_bytes(
+            self.odb
+                .find_existing_blob(modules_id, &mut buf, &mut git_odb::pack::cache::Never)?
+                .data,
+        )?;
+        modules
+            .into_iter()
+            .map(|sm| {
+                let gitlink = self.gitlink(&tree_id, sm.path.as_ref(), &mut buf)?;
+                Ok((sm, gitlink))
+            })
+            .collect()
+    }
+
+    /// Return the commit id recorded by the _gitlink_ at the slash-separated `path` in the tree with `tree_id`,
+    /// or `None` if there is no entry at `path` or if it is not a gitlink.
+    pub fn gitlink(&self, tree_id: &oid, path: &BStr, buf: &mut Vec<u8>) -> Result<Option<ObjectId>, find::Error> {
+        Ok(git_object::tree::lookup_entry(tree_id, path, buf, |id, buf| {
+            self.odb
+                .find_existing_tree_iter(id, buf, &mut git_odb::pack::cache::Never)
+                .ok()
+        })?
+        .filter(|entry| entry.mode == git_object::tree::EntryMode::Commit)
+        .map(|entry| entry.oid))
+    }
+}
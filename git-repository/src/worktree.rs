@@ -0,0 +1,95 @@
+//! Access to the linked worktrees of a repository as created by `git worktree add`.
+use std::path::{Path, PathBuf};
+
+use git_object::bstr::{BString, ByteSlice};
+
+use crate::Repository;
+
+/// A linked worktree whose private repository directory is located at `<common_dir>/worktrees/<name>`.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Worktree {
+    /// The name of the worktree, which is the name of its private directory within the common directory.
+    pub name: BString,
+    /// The private repository directory of the worktree, containing its `HEAD` and `index`.
+    pub git_dir: PathBuf,
+    /// The root of the worktree's checkout, or `None` if the `gitdir` file pointing to it is missing.
+    ///
+    /// Note that the checkout may not exist anymore, in which case the worktree can be pruned.
+    pub work_dir: Option<PathBuf>,
+    /// If the worktree is locked, this is the possibly empty reason for it.
+    pub lock_reason: Option<BString>,
+}
+
+impl Worktree {
+    /// Read the worktree information from its private repository directory at `git_dir`.
+    pub fn at(git_dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let git_dir = git_dir.into();
+        let name = git_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned().into())
+            .unwrap_or_default();
+        let work_dir = read_optional(&git_dir.join("gitdir"))?.map(|dot_git| {
+            let dot_git = dot_git
+                .trim_with(|c| c.is_ascii_whitespace())
+                .to_path_lossy()
+                .into_owned();
+            match dot_git.parent() {
+                Some(parent) if dot_git.ends_with(".git") => parent.to_owned(),
+                _ => dot_git,
+            }
+        });
+        let lock_reason =
+            read_optional(&git_dir.join("locked"))?.map(|reason| reason.trim_with(|c| c.is_ascii_whitespace()).into());
+        Ok(Worktree {
+            name,
+            git_dir,
+            work_dir,
+            lock_reason,
+        })
+    }
+
+    /// Return true if the worktree is locked and thus must not be pruned.
+    pub fn is_locked(&self) -> bool {
+        self.lock_reason.is_some()
+    }
+
+    /// Return a reference store to access the worktree's `HEAD` and other per-worktree references, which shares
+    /// all other references with the repository at `common_dir`.
+    pub fn refs(&self, common_dir: impl Into<PathBuf>) -> git_ref::file::Store {
+        git_ref::file::Store::for_linked_worktree(&self.git_dir, common_dir, Default::default())
+    }
+
+    /// The location of the worktree's index file.
+    pub fn index_path(&self) -> PathBuf {
+        self.git_dir.join("index")
+    }
+}
+
+fn read_optional(path: &Path) -> std::io::Result<Option<Vec<u8>>> {
+    match std::fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+impl Repository {
+    /// Return all linked worktrees of this repository, sorted by name, which excludes the main worktree.
+    pub fn worktrees(&self) -> std::io::Result<Vec<Worktree>> {
+        let dir = self.common_dir().join("worktrees");
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut worktrees = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                worktrees.push(Worktree::at(path)?);
+            }
+        }
+        worktrees.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(worktrees)
+    }
+}
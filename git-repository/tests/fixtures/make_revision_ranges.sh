@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+
+git checkout -q -b main
+git commit -q --allow-empty -m base
+
+git checkout -q -b left
+git commit -q --allow-empty -m l1
+
+git checkout -q -b right main
+git commit -q --allow-empty -m r1
+
+git checkout -q -b merge
+git merge -q --no-ff --no-edit -m merge left
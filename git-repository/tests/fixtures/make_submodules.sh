@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q module1
+(cd module1
+  git config commit.gpgsign false
+  touch this
+  git add this
+  git commit -q -m c1
+)
+
+git init -q super
+(cd super
+  git config commit.gpgsign false
+  touch file
+  git add file
+  git commit -q -m c1
+  git -c protocol.file.allow=always submodule add -q ../module1 m1
+  git -c protocol.file.allow=always submodule add -q ../module1 dir/m2
+  git config -f .gitmodules submodule.m1.branch main
+  git add .gitmodules
+  git commit -q -m "add submodules"
+)
@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git config commit.gpgsign false
+  git checkout -q -b main
+  touch file
+  git add file
+  git commit -q -m c1
+  git worktree add -q -b wt-branch ../wt
+  git worktree add -q --detach ../locked
+  git worktree lock --reason "on removable media" ../locked
+)
+(cd wt
+  git commit -q --allow-empty -m c2
+)
@@ -0,0 +1,19 @@
+#[test]
+fn precreate_object_fanout() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    git_repository::init::repository_with_options(
+        dir.path(),
+        git_repository::init::Options {
+            precreate_object_fanout: true,
+        },
+    )?;
+    let objects = dir.path().join(".git").join("objects");
+    assert!(objects.join("00").is_dir());
+    assert!(objects.join("ff").is_dir());
+    assert_eq!(
+        std::fs::read_dir(&objects)?.count(),
+        256 + 2,
+        "all fanout directories along with 'info' and 'pack'"
+    );
+    Ok(())
+}
@@ -1,3 +1,11 @@
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 mod discover;
+
+mod init;
+
+mod revision;
+
+mod submodule;
+
+mod worktree;
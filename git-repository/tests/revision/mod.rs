@@ -0,0 +1,179 @@
+mod parse {
+    use git_repository::{
+        object::bstr::ByteSlice,
+        revision::{parse, Spec},
+    };
+
+    fn spec(input: &str) -> Spec<'_> {
+        parse(input.as_bytes().as_bstr()).expect("valid spec")
+    }
+
+    #[test]
+    fn revisions_and_exclusions() {
+        assert_eq!(spec("a"), Spec::Include("a".as_bytes().as_bstr()));
+        assert_eq!(spec("^a"), Spec::Exclude("a".as_bytes().as_bstr()));
+        assert_eq!(spec("a^!"), Spec::ExcludeParents("a".as_bytes().as_bstr()));
+    }
+
+    #[test]
+    fn ranges_with_omitted_sides_referring_to_head() {
+        assert_eq!(
+            spec("a..b"),
+            Spec::Range {
+                from: "a".as_bytes().as_bstr(),
+                to: "b".as_bytes().as_bstr()
+            }
+        );
+        assert_eq!(
+            spec("..b"),
+            Spec::Range {
+                from: "HEAD".as_bytes().as_bstr(),
+                to: "b".as_bytes().as_bstr()
+            }
+        );
+        assert_eq!(
+            spec("a..."),
+            Spec::SymmetricDifference {
+                left: "a".as_bytes().as_bstr(),
+                right: "HEAD".as_bytes().as_bstr()
+            }
+        );
+        assert_eq!(
+            spec("a...b"),
+            Spec::SymmetricDifference {
+                left: "a".as_bytes().as_bstr(),
+                right: "b".as_bytes().as_bstr()
+            }
+        );
+    }
+
+    #[test]
+    fn empty_revisions_and_invalid_combinations_are_errors() {
+        for input in &["", "^", "^!"] {
+            assert!(
+                matches!(
+                    parse(input.as_bytes().as_bstr()),
+                    Err(git_repository::revision::parse::Error::Empty)
+                ),
+                "{:?} is empty",
+                input
+            );
+        }
+        for input in &["^a..b", "^a^!", "a..b^!", "a...b^!"] {
+            assert!(
+                matches!(
+                    parse(input.as_bytes().as_bstr()),
+                    Err(git_repository::revision::parse::Error::InvalidCombination(_))
+                ),
+                "{:?} can't be parsed",
+                input
+            );
+        }
+    }
+}
+
+mod tips {
+    use git_repository::{
+        hash::ObjectId,
+        object::bstr::ByteSlice,
+        odb::pack,
+        prelude::FindExt,
+        revision::{parse, Tips},
+        Repository,
+    };
+
+    fn tips(repo: &Repository, specs: &[&str]) -> Result<Tips, Box<dyn std::error::Error>> {
+        let specs = specs
+            .iter()
+            .map(|spec| parse(spec.as_bytes().as_bstr()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Tips::from_specs(
+            specs,
+            |name| try_id(repo, name.to_str().ok()?),
+            |oid, buf| {
+                repo.odb
+                    .find_existing_commit_iter(oid, buf, &mut pack::cache::Never)
+                    .ok()
+            },
+        )?)
+    }
+
+    fn try_id(repo: &Repository, name: &str) -> Option<ObjectId> {
+        Some(
+            repo.refs
+                .find_one_existing(name)
+                .ok()?
+                .peel_to_id_in_place()
+                .ok()?
+                .to_owned(),
+        )
+    }
+
+    fn id(repo: &Repository, name: &str) -> ObjectId {
+        try_id(repo, name).expect("reference exists")
+    }
+
+    fn repo() -> crate::Result<Repository> {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_revision_ranges.sh")?;
+        Ok(git_repository::discover(dir)?)
+    }
+
+    #[test]
+    fn ranges_and_exclusions() -> crate::Result {
+        let repo = repo()?;
+        assert_eq!(
+            tips(&repo, &["left..right", "^main", "merge"])?,
+            Tips {
+                include: vec![id(&repo, "right"), id(&repo, "merge")],
+                exclude: vec![id(&repo, "left"), id(&repo, "main")],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn symmetric_differences_exclude_the_commits_reachable_from_both_sides() -> crate::Result {
+        let repo = repo()?;
+        assert_eq!(
+            tips(&repo, &["left...right"])?,
+            Tips {
+                include: vec![id(&repo, "left"), id(&repo, "right")],
+                exclude: vec![id(&repo, "main")],
+            }
+        );
+        assert_eq!(
+            tips(&repo, &["left...merge"])?,
+            Tips {
+                include: vec![id(&repo, "left"), id(&repo, "merge")],
+                exclude: vec![id(&repo, "left"), id(&repo, "main")],
+            },
+            "if one side is reachable from the other, it is excluded"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn excluding_parents_of_merges() -> crate::Result {
+        let repo = repo()?;
+        assert_eq!(
+            tips(&repo, &["merge^!"])?,
+            Tips {
+                include: vec![id(&repo, "merge")],
+                exclude: vec![id(&repo, "right"), id(&repo, "left")],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unresolvable_revisions_are_an_error() -> crate::Result {
+        let repo = repo()?;
+        assert!(matches!(
+            tips(&repo, &["main..does-not-exist"])
+                .expect_err("reference doesn't exist")
+                .downcast_ref::<git_repository::revision::tips::Error>(),
+            Some(git_repository::revision::tips::Error::NotFound(name)) if name == "does-not-exist"
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+mod from_bytes {
+    use git_repository::{object::bstr::ByteSlice, submodule, Submodule};
+
+    #[test]
+    fn values_with_last_one_winning_and_optional_fields() -> crate::Result {
+        let modules = submodule::from_bytes(
+            br#"[submodule "a"]
+    path = a
+    url = https://example.com/a
+[submodule "b/c"]
+    path = "deeply/nested"
+[submodule "a"]
+    branch = main
+"#,
+        )?;
+        assert_eq!(
+            modules,
+            vec![
+                Submodule {
+                    name: "a".into(),
+                    path: "a".into(),
+                    url: Some("https://example.com/a".into()),
+                    branch: Some("main".into()),
+                },
+                Submodule {
+                    name: "b/c".into(),
+                    path: "deeply/nested".into(),
+                    url: None,
+                    branch: None,
+                }
+            ]
+        );
+        assert_eq!(
+            modules[1].modules_dir(".git"),
+            std::path::Path::new(".git/modules/b/c"),
+            "names are used as paths"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn names_escaping_the_modules_directory_are_rejected() {
+        for name in &["..", "a/../../b", "/absolute"] {
+            let data = format!("[submodule \"{}\"]\n\tpath = p\n", name);
+            assert!(matches!(
+                submodule::from_bytes(data.as_bytes()),
+                Err(submodule::decode::Error::InvalidName(n)) if n == name.as_bytes().as_bstr()
+            ));
+        }
+    }
+
+    #[test]
+    fn path_is_required() {
+        assert!(matches!(
+            submodule::from_bytes(b"[submodule \"a\"]\n\turl = u\n"),
+            Err(submodule::decode::Error::MissingPath(_))
+        ));
+    }
+}
+
+mod repository {
+    use git_repository::object::bstr::ByteSlice;
+
+    #[test]
+    fn submodules_with_gitlinks_and_repository_location() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_submodules.sh")?;
+        let repo = git_repository::discover(dir.join("super"))?;
+        let commit_id = repo.refs.find_one_existing("HEAD")?.peel_to_id_in_place()?.to_owned();
+        let submodules = repo.submodules(commit_id)?;
+        assert_eq!(
+            submodules
+                .iter()
+                .map(|(sm, gitlink)| (
+                    sm.name.as_slice().as_bstr(),
+                    sm.path.as_slice().as_bstr(),
+                    sm.branch.is_some(),
+                    gitlink.is_some()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("m1".as_bytes().as_bstr(), "m1".as_bytes().as_bstr(), true, true),
+                (
+                    "dir/m2".as_bytes().as_bstr(),
+                    "dir/m2".as_bytes().as_bstr(),
+                    false,
+                    true
+                )
+            ]
+        );
+        assert_eq!(submodules[0].1, submodules[1].1, "both point to the same commit");
+
+        for (sm, _) in &submodules {
+            let git_dir = sm
+                .git_dir(repo.git_dir(), repo.working_tree.as_deref())
+                .expect("cloned");
+            assert_eq!(
+                git_dir.canonicalize()?,
+                sm.modules_dir(repo.git_dir()).canonicalize()?,
+                "gitfiles are followed"
+            );
+        }
+        Ok(())
+    }
+}
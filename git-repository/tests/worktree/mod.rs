@@ -0,0 +1,99 @@
+use git_repository::Kind;
+use std::path::PathBuf;
+
+fn fixture() -> crate::Result<PathBuf> {
+    Ok(git_testtools::scripted_fixture_repo_read_only("make_worktrees.sh")?.canonicalize()?)
+}
+
+#[test]
+fn discovery_follows_the_gitfile_and_the_common_dir() -> crate::Result {
+    let dir = fixture()?;
+    let path = git_repository::path::discover::existing(dir.join("wt"))?;
+    assert_eq!(path.kind(), Kind::WorkingTree);
+    assert_eq!(path.as_ref(), dir.join("wt"));
+
+    let repo = git_repository::Repository::discover(dir.join("wt"))?;
+    let common_dir = dir.join("repo").join(".git");
+    assert_eq!(repo.git_dir().canonicalize()?, common_dir.join("worktrees").join("wt"));
+    assert_eq!(repo.common_dir().canonicalize()?, common_dir);
+    assert_eq!(repo.objects_dir().canonicalize()?, common_dir.join("objects"));
+    assert_eq!(repo.working_tree.as_deref(), Some(dir.join("wt").as_path()));
+    assert!(repo.index_path().is_file(), "each worktree has its own index");
+    Ok(())
+}
+
+#[test]
+fn head_is_private_to_each_worktree_while_objects_are_shared() -> crate::Result {
+    let dir = fixture()?;
+    let main = git_repository::Repository::discover(dir.join("repo"))?;
+    let linked = git_repository::Repository::discover(dir.join("wt"))?;
+
+    let main_head = main.refs.find_one_existing("HEAD")?.peel_to_id_in_place()?.to_owned();
+    let linked_head = linked.refs.find_one_existing("HEAD")?.peel_to_id_in_place()?.to_owned();
+    assert_ne!(main_head, linked_head, "the linked worktree is one commit ahead");
+    assert_eq!(
+        linked
+            .refs
+            .find_one_existing("wt-branch")?
+            .peel_to_id_in_place()?
+            .to_owned(),
+        linked_head
+    );
+    assert_eq!(
+        main.refs
+            .find_one_existing("wt-branch")?
+            .peel_to_id_in_place()?
+            .to_owned(),
+        linked_head,
+        "branches are shared among all worktrees"
+    );
+    assert_eq!(main.common_dir(), main.git_dir());
+    Ok(())
+}
+
+#[test]
+fn listing() -> crate::Result {
+    let dir = fixture()?;
+    let repo = git_repository::Repository::discover(dir.join("repo"))?;
+    let worktrees = repo.worktrees()?;
+    assert_eq!(
+        worktrees.iter().map(|wt| wt.name.to_string()).collect::<Vec<_>>(),
+        vec!["locked", "wt"]
+    );
+
+    let locked = &worktrees[0];
+    assert!(locked.is_locked());
+    assert_eq!(
+        locked.lock_reason.as_ref().map(|r| r.to_string()).as_deref(),
+        Some("on removable media")
+    );
+    assert_eq!(
+        locked.work_dir.as_ref().map(|d| d.canonicalize()).transpose()?,
+        Some(dir.join("locked"))
+    );
+    assert!(
+        locked
+            .refs(repo.common_dir())
+            .find_one_existing("HEAD")?
+            .target()
+            .as_id()
+            .is_some(),
+        "detached HEAD"
+    );
+
+    let wt = &worktrees[1];
+    assert!(!wt.is_locked());
+    assert_eq!(
+        wt.refs(repo.common_dir()).find_one_existing("HEAD")?.target().as_name(),
+        Some(b"refs/heads/wt-branch".as_ref().into())
+    );
+    assert!(wt.index_path().is_file());
+
+    let linked = git_repository::Repository::discover(dir.join("wt"))?;
+    assert_eq!(
+        linked.worktrees()?.into_iter().map(|wt| wt.name).collect::<Vec<_>>(),
+        worktrees.into_iter().map(|wt| wt.name).collect::<Vec<_>>(),
+        "all worktrees see the same list"
+    );
+    Ok(())
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use git_transport::client::Capabilities;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((capabilities, _delimiter_pos)) = Capabilities::from_bytes(data) {
+        for capability in capabilities.iter() {
+            let _a = capability.values().map(|values| values.count());
+        }
+    }
+
+    let mut lines = data.splitn(2, |b| *b == b'\n');
+    let first_line = lines
+        .next()
+        .map(|line| Ok::<_, std::io::Error>(String::from_utf8_lossy(line).into_owned()));
+    let remaining_lines = String::from_utf8_lossy(lines.next().unwrap_or_default());
+    let _b = Capabilities::from_lines(first_line, remaining_lines);
+});
@@ -9,5 +9,5 @@ pub use traits::{SetServiceResponse, Transport, TransportV2Ext};
 
 ///
 pub mod connect {
-    pub use crate::client::non_io_types::connect::Error;
+    pub use crate::client::non_io_types::connect::{Error, Options};
 }
@@ -1,6 +1,6 @@
 use crate::client::Transport;
 
-pub use crate::client::non_io_types::connect::Error;
+pub use crate::client::non_io_types::connect::{Error, Options};
 
 /// A general purpose connector connecting to a repository identified by the given `url`.
 ///
@@ -8,10 +8,16 @@ pub use crate::client::non_io_types::connect::Error;
 /// [local repositories][crate::client::file::connect()],
 /// [repositories over ssh][crate::client::ssh::connect()],
 /// [git daemons][crate::client::git::connect()],
+/// if compiled in with the `git-tls` feature, git daemons behind TLS,
 /// and if compiled in connections to [git repositories over https][crate::client::http::connect()].
 ///
 /// Use `desired_version` to set the desired protocol version to use when connecting, but not that the server may downgrade it.
-pub fn connect(url: &[u8], desired_version: crate::Protocol) -> Result<Box<dyn Transport + Send>, Error> {
+/// `options` are applied to the transports supporting them.
+pub fn connect(
+    url: &[u8],
+    desired_version: crate::Protocol,
+    options: Options,
+) -> Result<Box<dyn Transport + Send>, Error> {
     let urlb = url;
     let url = git_url::parse(urlb)?;
     Ok(match url.scheme {
@@ -46,7 +52,27 @@ pub fn connect(url: &[u8], desired_version: crate::Protocol) -> Result<Box<dyn T
                     desired_version,
                     url.port,
                 )
-                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
+                .extra_parameters(options.extra_parameters),
+            )
+        }
+        #[cfg(not(feature = "git-tls"))]
+        git_url::Scheme::GitTls => return Err(Error::CompiledWithoutTls(url.scheme)),
+        #[cfg(feature = "git-tls")]
+        git_url::Scheme::GitTls => {
+            if url.user.is_some() {
+                return Err(Error::UnsupportedUrlTokens(urlb.into(), url.scheme));
+            }
+            Box::new(
+                crate::client::git::connect_tls(
+                    &url.host.as_ref().expect("host is present in url"),
+                    url.path,
+                    desired_version,
+                    url.port,
+                    &Default::default(),
+                )
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
+                .extra_parameters(options.extra_parameters),
             )
         }
         #[cfg(not(feature = "http-client-curl"))]
@@ -56,7 +82,9 @@ pub fn connect(url: &[u8], desired_version: crate::Protocol) -> Result<Box<dyn T
             use bstr::ByteSlice;
             Box::new(
                 crate::client::http::connect(urlb.to_str()?, desired_version)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
+                    .extra_parameters(options.extra_parameters)
+                    .extra_headers(options.extra_headers),
             )
         }
     })
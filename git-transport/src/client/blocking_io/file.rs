@@ -73,6 +73,7 @@ impl SpawnProcessOnDemand {
             url: git_url::Url {
                 scheme: git_url::Scheme::File,
                 user: None,
+                password: None,
                 host: None,
                 port: None,
                 path: path.clone(),
@@ -150,12 +151,31 @@ impl client::Transport for SpawnProcessOnDemand {
     }
 }
 
+/// Expand a leading `~` or `~user` in `path` to the respective home directory on the local machine, or return it unchanged
+/// if it doesn't start with either or the home directory is unknown.
+fn expand_home(path: BString) -> BString {
+    use bstr::ByteVec;
+    git_url::expand_path::parse(path.as_bstr())
+        .ok()
+        .and_then(|(user, relative_path)| {
+            let user = user?;
+            git_url::expand_path(Some(&user), relative_path.as_bstr()).ok()
+        })
+        .and_then(|expanded| Vec::from_path_buf(expanded).ok())
+        .map(Into::into)
+        .unwrap_or(path)
+}
+
 /// Connect to a locally readable repository at `path` using the given `desired_version`.
 ///
-/// This will spawn a `git` process locally.
+/// This will spawn a `git` process locally. A leading `~` or `~user` in `path` is expanded to the respective home directory
+/// using [`git_url::expand_path::LocalUsers`].
 pub fn connect(
     path: impl Into<BString>,
     desired_version: Protocol,
 ) -> Result<SpawnProcessOnDemand, std::convert::Infallible> {
-    Ok(SpawnProcessOnDemand::new_local(path.into(), desired_version))
+    Ok(SpawnProcessOnDemand::new_local(
+        expand_home(path.into()),
+        desired_version,
+    ))
 }
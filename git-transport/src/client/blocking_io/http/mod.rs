@@ -1,7 +1,7 @@
 use std::{
     borrow::Cow,
     convert::Infallible,
-    io::{self, BufRead, Read},
+    io::{self, BufRead, Read, Write},
 };
 
 use git_packetline::PacketLine;
@@ -33,6 +33,9 @@ pub struct Transport<H: Http> {
     service: Option<Service>,
     line_provider: Option<git_packetline::StreamingPeekableIter<H::ResponseBody>>,
     identity: Option<client::Identity>,
+    max_retries: usize,
+    extra_parameters: Vec<(String, Option<String>)>,
+    extra_headers: Vec<String>,
 }
 
 impl Transport<Impl> {
@@ -48,19 +51,43 @@ impl Transport<Impl> {
             http: Impl::default(),
             line_provider: None,
             identity: None,
+            max_retries: 0,
+            extra_parameters: Vec::new(),
+            extra_headers: Vec::new(),
         }
     }
 }
 
 impl<H: Http> Transport<H> {
+    /// Retry requests of idempotent commands, namely all of those to the `upload-pack` service, up to `count` times
+    /// if they fail with an IO error before the response headers were received, reconnecting each time.
+    ///
+    /// This requires buffering the entire request in memory before sending it. The default of `0` disables retries
+    /// and streams requests directly.
+    pub fn max_retries(mut self, count: usize) -> Self {
+        self.max_retries = count;
+        self
+    }
+
+    /// Set `parameters` to send in the `Git-Protocol` header of each handshake in addition to the ones passed to `handshake()`.
+    pub fn extra_parameters(mut self, parameters: Vec<(String, Option<String>)>) -> Self {
+        self.extra_parameters = parameters;
+        self
+    }
+
+    /// Set `headers` to send verbatim with each request, like `Authorization: Bearer <token>` or trace ids.
+    pub fn extra_headers(mut self, headers: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.extra_headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+
     fn check_content_type(service: Service, kind: &str, headers: <H as Http>::Headers) -> Result<(), client::Error> {
+        Self::check_content_type_lines(service, kind, &headers.lines().collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn check_content_type_lines(service: Service, kind: &str, headers: &[String]) -> Result<(), client::Error> {
         let wanted_content_type = format!("Content-Type: application/x-{}-{}", service.as_str(), kind);
-        if !headers
-            .lines()
-            .collect::<Result<Vec<_>, _>>()?
-            .iter()
-            .any(|l| l == &wanted_content_type)
-        {
+        if !headers.iter().any(|l| l == &wanted_content_type) {
             return Err(client::Error::Http(Error::Detail(format!(
                 "Didn't find '{}' header to indicate 'smart' protocol, and 'dumb' protocol is not supported.",
                 wanted_content_type
@@ -91,6 +118,26 @@ impl<H: Http> Transport<H> {
     }
 }
 
+/// Post the entire `request` to `url` and wait for the response headers, failing with any IO error that occurs until then.
+fn post_and_receive_headers<H: Http>(
+    http: &mut H,
+    url: &str,
+    headers: &[String],
+    request: &[u8],
+) -> io::Result<(Vec<String>, H::ResponseBody)> {
+    let PostResponse {
+        headers,
+        body,
+        mut post_body,
+    } = http
+        .post(url, headers)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    post_body.write_all(request)?;
+    drop(post_body);
+    let headers = headers.lines().collect::<Result<Vec<_>, _>>()?;
+    Ok((headers, body))
+}
+
 fn append_url(base: &str, suffix: &str) -> String {
     if base.ends_with('/') {
         format!("{}{}", base, suffix)
@@ -118,7 +165,7 @@ impl<H: Http> client::TransportWithoutIO for Transport<H> {
             format!("Accept: application/x-{}-result", service.as_str()).into(),
             "Expect:".into(), // needed to avoid sending Expect: 100-continue, which adds another response and only CURL wants that
         ];
-        let mut dynamic_headers = Vec::new();
+        let mut dynamic_headers: Vec<_> = self.extra_headers.iter().map(|h| Cow::Borrowed(h.as_str())).collect();
         self.add_basic_auth_if_present(&mut dynamic_headers)?;
         if self.actual_version != Protocol::V1 {
             dynamic_headers.push(Cow::Owned(format!(
@@ -127,6 +174,44 @@ impl<H: Http> client::TransportWithoutIO for Transport<H> {
             )));
         }
 
+        if self.max_retries > 0 && service == Service::UploadPack {
+            let headers: Vec<String> = static_headers
+                .iter()
+                .chain(&dynamic_headers)
+                .map(|h| h.as_ref().to_owned())
+                .collect();
+            let max_retries = self.max_retries;
+            let http = &mut self.http;
+            let line_provider = self
+                .line_provider
+                .as_mut()
+                .expect("handshake to have been called first");
+            return Ok(RequestWriter::new_buffered(
+                move |request| {
+                    let mut attempt = 0;
+                    let body = loop {
+                        match post_and_receive_headers(http, &url, &headers, request) {
+                            Ok((response_headers, body)) => {
+                                <Transport<H>>::check_content_type_lines(service, "result", &response_headers)
+                                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+                                break body;
+                            }
+                            Err(_) if attempt < max_retries => attempt += 1,
+                            Err(err) => return Err(err),
+                        }
+                    };
+                    line_provider.replace(body);
+                    Ok(Box::new(HeadersThenBody::<H, _> {
+                        service,
+                        headers: None,
+                        body: line_provider.as_read_without_sidebands(),
+                    }) as Box<dyn ExtendedBufRead + Unpin>)
+                },
+                write_mode,
+                on_into_read,
+            ));
+        }
+
         let PostResponse {
             headers,
             body,
@@ -170,7 +255,16 @@ impl<H: Http> client::Transport for Transport<H> {
     ) -> Result<client::SetServiceResponse<'_>, client::Error> {
         let url = append_url(&self.url, &format!("info/refs?service={}", service.as_str()));
         let static_headers = [Cow::Borrowed(self.user_agent_header)];
-        let mut dynamic_headers = Vec::<Cow<'_, str>>::new();
+        let mut dynamic_headers: Vec<_> = self.extra_headers.iter().map(|h| Cow::Borrowed(h.as_str())).collect();
+        let extra_parameters: Vec<_> = extra_parameters
+            .iter()
+            .copied()
+            .chain(
+                self.extra_parameters
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_deref())),
+            )
+            .collect();
         if self.desired_version != Protocol::V1 || !extra_parameters.is_empty() {
             let mut parameters = if self.desired_version != Protocol::V1 {
                 let mut p = format!("version={}", self.desired_version as usize);
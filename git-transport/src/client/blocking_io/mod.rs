@@ -10,7 +10,10 @@ pub mod http;
 mod bufread_ext;
 pub use bufread_ext::{ExtendedBufRead, HandleProgress};
 
-mod request;
+///
+pub mod recording;
+
+pub(in crate::client) mod request;
 pub use request::RequestWriter;
 
 ///
@@ -0,0 +1,198 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bstr::BString;
+
+use crate::{
+    client::{self, git, SetServiceResponse},
+    Protocol, Service,
+};
+
+/// Return the paths to the files holding the request and the response of the recording called `name` in `directory`,
+/// using the same naming scheme as the fixtures of this crate, like `clone.request` and `clone.response`.
+pub fn paths(directory: impl AsRef<Path>, name: &str) -> (PathBuf, PathBuf) {
+    let directory = directory.as_ref();
+    (
+        directory.join(format!("{}.request", name)),
+        directory.join(format!("{}.response", name)),
+    )
+}
+
+/// A reader or writer which copies all bytes passing through it to `out`.
+pub struct Tee<T> {
+    inner: T,
+    out: fs::File,
+}
+
+impl<T: io::Read> io::Read for Tee<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        io::Write::write_all(&mut self.out, &buf[..bytes_read])?;
+        Ok(bytes_read)
+    }
+}
+
+impl<T: io::Write> io::Write for Tee<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes_written = self.inner.write(buf)?;
+        self.out.write_all(&buf[..bytes_written])?;
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        self.out.flush()
+    }
+}
+
+/// A transport wrapping a [`git::Connection`] to write every byte sent to the server and every byte received from it
+/// into files, to be served back later using a [`Replay`].
+pub struct Transport<R, W> {
+    connection: git::Connection<Tee<R>, Tee<W>>,
+}
+
+impl<R, W> Transport<R, W>
+where
+    R: io::Read,
+    W: io::Write,
+{
+    /// Record all communication of `connection` into the files for the recording called `name` in `directory`,
+    /// as returned by [`paths()`], truncating existing ones.
+    ///
+    /// The `connection` must not have been used yet, as its handshake is part of the recording.
+    pub fn new(connection: git::Connection<R, W>, directory: impl AsRef<Path>, name: &str) -> io::Result<Self> {
+        let (request_path, response_path) = paths(directory, name);
+        let (request, response) = (fs::File::create(request_path)?, fs::File::create(response_path)?);
+        Ok(Transport {
+            connection: connection.map_io(
+                |inner| Tee { inner, out: response },
+                |inner| Tee { inner, out: request },
+            ),
+        })
+    }
+
+    /// Return the connection which was recorded, without the ability to record any more.
+    pub fn into_inner(self) -> git::Connection<R, W> {
+        self.connection.map_io(|read| read.inner, |write| write.inner)
+    }
+}
+
+impl<R, W> client::TransportWithoutIO for Transport<R, W>
+where
+    R: io::Read,
+    W: io::Write,
+{
+    fn request(
+        &mut self,
+        write_mode: client::WriteMode,
+        on_into_read: client::MessageKind,
+    ) -> Result<client::RequestWriter<'_>, client::Error> {
+        self.connection.request(write_mode, on_into_read)
+    }
+
+    fn to_url(&self) -> String {
+        self.connection.to_url()
+    }
+
+    fn supported_protocol_versions(&self) -> &[Protocol] {
+        self.connection.supported_protocol_versions()
+    }
+
+    fn is_stateful(&self) -> bool {
+        self.connection.is_stateful()
+    }
+}
+
+impl<R, W> client::Transport for Transport<R, W>
+where
+    R: io::Read,
+    W: io::Write,
+{
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        self.connection.handshake(service, extra_parameters)
+    }
+}
+
+/// A transport serving the response of a recording made with a recording [`Transport`], while keeping all bytes
+/// written to it for comparison with the recorded request.
+pub struct Replay {
+    connection: git::Connection<io::Cursor<Vec<u8>>, Vec<u8>>,
+    request: Vec<u8>,
+}
+
+impl Replay {
+    /// Load the recording called `name` from `directory`, as returned by [`paths()`], to replay it as if we were connected
+    /// to the server it was recorded from.
+    ///
+    /// All other arguments are the same as in [`git::Connection::new()`], and should match the ones of the recorded
+    /// connection for the bytes written to be the same as the ones recorded.
+    pub fn new(
+        directory: impl AsRef<Path>,
+        name: &str,
+        desired_version: Protocol,
+        repository_path: impl Into<BString>,
+        virtual_host: Option<(impl Into<String>, Option<u16>)>,
+        mode: git::ConnectMode,
+    ) -> io::Result<Self> {
+        let (request_path, response_path) = paths(directory, name);
+        Ok(Replay {
+            connection: git::Connection::new(
+                io::Cursor::new(fs::read(response_path)?),
+                Vec::new(),
+                desired_version,
+                repository_path,
+                virtual_host,
+                mode,
+            ),
+            request: fs::read(request_path)?,
+        })
+    }
+
+    /// The bytes sent by the client while recording.
+    pub fn recorded_request(&self) -> &[u8] {
+        &self.request
+    }
+
+    /// The bytes sent by the client so far during the replay.
+    pub fn written(&self) -> &[u8] {
+        &self.connection.writer
+    }
+}
+
+impl client::TransportWithoutIO for Replay {
+    fn request(
+        &mut self,
+        write_mode: client::WriteMode,
+        on_into_read: client::MessageKind,
+    ) -> Result<client::RequestWriter<'_>, client::Error> {
+        self.connection.request(write_mode, on_into_read)
+    }
+
+    fn to_url(&self) -> String {
+        self.connection.to_url()
+    }
+
+    fn supported_protocol_versions(&self) -> &[Protocol] {
+        self.connection.supported_protocol_versions()
+    }
+
+    fn is_stateful(&self) -> bool {
+        self.connection.is_stateful()
+    }
+}
+
+impl client::Transport for Replay {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        self.connection.handshake(service, extra_parameters)
+    }
+}
@@ -1,13 +1,68 @@
 use crate::client::{ExtendedBufRead, MessageKind, WriteMode};
 use std::io;
 
+/// The function to transform a buffered request into the reader for its response.
+type SendBuffered<'a> = Box<dyn FnOnce(&[u8]) -> io::Result<Box<dyn ExtendedBufRead + Unpin + 'a>> + 'a>;
+
+/// A stream to write a request to, which is informed once the request is complete.
+pub(in crate::client) trait Stream: io::Write {
+    /// Called once the request was written entirely, and before its response is read.
+    fn request_complete(&mut self) -> io::Result<()>;
+}
+
+struct Plain<W>(W);
+
+impl<W: io::Write> io::Write for Plain<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<W: io::Write> Stream for Plain<W> {
+    fn request_complete(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+enum Sink<'a> {
+    /// Writes go straight to the other side.
+    Stream(Box<dyn Stream + 'a>),
+    /// Writes are kept in memory to be sent once the request is complete.
+    Buffer(Vec<u8>),
+}
+
+impl<'a> io::Write for Sink<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Stream(w) => w.write(buf),
+            Sink::Buffer(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Stream(w) => w.flush(),
+            Sink::Buffer(w) => w.flush(),
+        }
+    }
+}
+
+enum Response<'a> {
+    Ready(Box<dyn ExtendedBufRead + Unpin + 'a>),
+    AfterSending(SendBuffered<'a>),
+}
+
 /// A [`Write`][io::Write] implementation optimized for writing packet lines.
 /// A type implementing `Write` for packet lines, which when done can be transformed into a `Read` for
 /// obtaining the response.
 pub struct RequestWriter<'a> {
     on_into_read: MessageKind,
-    writer: git_packetline::Writer<Box<dyn io::Write + 'a>>,
-    reader: Box<dyn ExtendedBufRead + Unpin + 'a>,
+    writer: git_packetline::Writer<Sink<'a>>,
+    response: Response<'a>,
 }
 
 impl<'a> io::Write for RequestWriter<'a> {
@@ -31,7 +86,40 @@ impl<'a> RequestWriter<'a> {
         write_mode: WriteMode,
         on_into_read: MessageKind,
     ) -> Self {
-        let mut writer = git_packetline::Writer::new(Box::new(writer) as Box<dyn io::Write>);
+        Self::new_from_stream(Box::new(Plain(writer)), reader, write_mode, on_into_read)
+    }
+
+    /// Like [`new_from_bufread()`][RequestWriter::new_from_bufread()], but with a `stream` which is informed once
+    /// the request is complete.
+    pub(in crate::client) fn new_from_stream(
+        stream: Box<dyn Stream + 'a>,
+        reader: Box<dyn ExtendedBufRead + Unpin + 'a>,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+    ) -> Self {
+        Self::new(Sink::Stream(stream), Response::Ready(reader), write_mode, on_into_read)
+    }
+
+    /// Create a new instance which keeps the entire request in memory, and which calls `send` with it once this instance
+    /// is [converted into a reader][RequestWriter::into_read()] to send it and obtain the reader for its response.
+    ///
+    /// As `send` has access to the complete request, it may send it multiple times, for instance to retry after
+    /// transient failures. `write_mode` and `on_into_read` are the same as in [`new_from_bufread()`][RequestWriter::new_from_bufread()].
+    pub fn new_buffered(
+        send: impl FnOnce(&[u8]) -> io::Result<Box<dyn ExtendedBufRead + Unpin + 'a>> + 'a,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+    ) -> Self {
+        Self::new(
+            Sink::Buffer(Vec::new()),
+            Response::AfterSending(Box::new(send)),
+            write_mode,
+            on_into_read,
+        )
+    }
+
+    fn new(sink: Sink<'a>, response: Response<'a>, write_mode: WriteMode, on_into_read: MessageKind) -> Self {
+        let mut writer = git_packetline::Writer::new(sink);
         match write_mode {
             WriteMode::Binary => writer.enable_binary_mode(),
             WriteMode::OneLfTerminatedLinePerWriteCall => writer.enable_text_mode(),
@@ -39,7 +127,7 @@ impl<'a> RequestWriter<'a> {
         RequestWriter {
             on_into_read,
             writer,
-            reader,
+            response,
         }
     }
 
@@ -57,6 +145,14 @@ impl<'a> RequestWriter<'a> {
     /// Discard the ability to write and turn this instance into the reader for obtaining the other side's response.
     pub fn into_read(mut self) -> std::io::Result<Box<dyn ExtendedBufRead + Unpin + 'a>> {
         self.write_message(self.on_into_read)?;
-        Ok(self.reader)
+        match (self.writer.into_inner(), self.response) {
+            (Sink::Stream(mut stream), Response::Ready(reader)) => {
+                stream.request_complete()?;
+                Ok(reader)
+            }
+            (Sink::Buffer(_), Response::Ready(_)) => unreachable!("buffers are never ready without sending"),
+            (Sink::Buffer(request), Response::AfterSending(send)) => send(&request),
+            (Sink::Stream(_), Response::AfterSending(_)) => unreachable!("streams are never buffered"),
+        }
     }
 }
@@ -67,6 +67,7 @@ pub fn connect(
     let url = git_url::Url {
         scheme: git_url::Scheme::Ssh,
         user: user.map(Into::into),
+        password: None,
         host: Some(host.clone()),
         port,
         path: path.clone(),
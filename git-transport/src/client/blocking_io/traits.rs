@@ -32,6 +32,9 @@ pub trait Transport: TransportWithoutIO {
     /// if [TransportWithoutIO::supported_protocol_versions()] includes it.
     /// Exhaust the returned [BufReader][SetServiceResponse::refs] for a list of references in case of protocol V1
     /// before making another request.
+    ///
+    /// Stateful transports speaking protocol V2 perform the handshake only once per connection, and return the previously
+    /// advertised capabilities on subsequent calls to allow more commands to be sent on the same connection.
     fn handshake<'a>(
         &mut self,
         service: Service,
@@ -18,6 +18,8 @@ where
         write_mode: client::WriteMode,
         on_into_read: client::MessageKind,
     ) -> Result<client::RequestWriter<'_>, client::Error> {
+        // Each response is read until a flush packet, independently of how previous responses were read.
+        self.line_provider.reset_with(&[PacketLine::Flush]);
         Ok(client::RequestWriter::new_from_bufread(
             &mut self.writer,
             Box::new(self.line_provider.as_read_without_sidebands()),
@@ -31,6 +33,7 @@ where
                 git_url::Url {
                     scheme: git_url::Scheme::File,
                     user: None,
+                    password: None,
                     host: None,
                     port: None,
                     path: self.path.clone(),
@@ -67,17 +70,24 @@ where
         service: Service,
         extra_parameters: &'a [(&'a str, Option<&'a str>)],
     ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if let Some(capabilities) = self.v2_capabilities.clone() {
+            // V2 servers advertise their capabilities only once, and then wait for commands on the same connection.
+            return Ok(SetServiceResponse {
+                actual_protocol: Protocol::V2,
+                capabilities,
+                refs: None,
+            });
+        }
         if self.mode == git::ConnectMode::Daemon {
+            let message = git::message::connect(
+                service,
+                self.desired_version,
+                &self.path,
+                self.virtual_host.as_ref(),
+                &self.all_extra_parameters(extra_parameters),
+            );
             let mut line_writer = git_packetline::Writer::new(&mut self.writer).binary_mode();
-            line_writer
-                .write_all(&git::message::connect(
-                    service,
-                    self.desired_version,
-                    &self.path,
-                    self.virtual_host.as_ref(),
-                    extra_parameters,
-                ))
-                .await?;
+            line_writer.write_all(&message).await?;
             line_writer.flush().await?;
         }
 
@@ -86,6 +96,9 @@ where
             refs,
             protocol: actual_protocol,
         } = Capabilities::from_lines_with_version_detection(&mut self.line_provider).await?;
+        if actual_protocol == Protocol::V2 {
+            self.v2_capabilities = Some(capabilities.clone());
+        }
         Ok(SetServiceResponse {
             actual_protocol,
             capabilities,
@@ -120,6 +133,8 @@ where
             custom_url: None,
             supported_versions: [desired_version],
             mode,
+            extra_parameters: Vec::new(),
+            v2_capabilities: None,
         }
     }
 }
@@ -16,12 +16,21 @@ where
         write_mode: client::WriteMode,
         on_into_read: client::MessageKind,
     ) -> Result<client::RequestWriter<'_>, client::Error> {
-        Ok(client::RequestWriter::new_from_bufread(
-            &mut self.writer,
-            Box::new(self.line_provider.as_read_without_sidebands()),
-            write_mode,
-            on_into_read,
-        ))
+        // Each response is read until a flush packet, independently of how previous responses were read.
+        self.line_provider.reset_with(&[PacketLine::Flush]);
+        let reader = Box::new(self.line_provider.as_read_without_sidebands());
+        Ok(match self.shutdown_write_after_request {
+            Some(shutdown) => client::RequestWriter::new_from_stream(
+                Box::new(ShutdownAfterRequest {
+                    writer: &mut self.writer,
+                    shutdown,
+                }),
+                reader,
+                write_mode,
+                on_into_read,
+            ),
+            None => client::RequestWriter::new_from_bufread(&mut self.writer, reader, write_mode, on_into_read),
+        })
     }
 
     fn to_url(&self) -> String {
@@ -30,6 +39,7 @@ where
                 git_url::Url {
                     scheme: git_url::Scheme::File,
                     user: None,
+                    password: None,
                     host: None,
                     port: None,
                     path: self.path.clone(),
@@ -67,15 +77,24 @@ where
         service: Service,
         extra_parameters: &'a [(&'a str, Option<&'a str>)],
     ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if let Some(capabilities) = self.v2_capabilities.clone() {
+            // V2 servers advertise their capabilities only once, and then wait for commands on the same connection.
+            return Ok(SetServiceResponse {
+                actual_protocol: Protocol::V2,
+                capabilities,
+                refs: None,
+            });
+        }
         if self.mode == git::ConnectMode::Daemon {
-            let mut line_writer = git_packetline::Writer::new(&mut self.writer).binary_mode();
-            line_writer.write_all(&git::message::connect(
+            let message = git::message::connect(
                 service,
                 self.desired_version,
                 &self.path,
                 self.virtual_host.as_ref(),
-                extra_parameters,
-            ))?;
+                &self.all_extra_parameters(extra_parameters),
+            );
+            let mut line_writer = git_packetline::Writer::new(&mut self.writer).binary_mode();
+            line_writer.write_all(&message)?;
             line_writer.flush()?;
         }
 
@@ -84,6 +103,9 @@ where
             refs,
             protocol: actual_protocol,
         } = Capabilities::from_lines_with_version_detection(&mut self.line_provider)?;
+        if actual_protocol == Protocol::V2 {
+            self.v2_capabilities = Some(capabilities.clone());
+        }
         Ok(SetServiceResponse {
             actual_protocol,
             capabilities,
@@ -118,6 +140,9 @@ where
             custom_url: None,
             supported_versions: [desired_version],
             mode,
+            extra_parameters: Vec::new(),
+            v2_capabilities: None,
+            shutdown_write_after_request: None,
         }
     }
     pub(crate) fn new_for_spawned_process(
@@ -137,11 +162,86 @@ where
     }
 }
 
+/// A writer which can be shut down to signal the end of all writes to the other side, while the reading half
+/// of the connection remains usable.
+pub trait ShutdownWrite: io::Write {
+    /// Shut down the writing half of the connection, causing the other side to see EOF once it has read
+    /// everything written so far.
+    fn shutdown_write(&self) -> io::Result<()>;
+}
+
+impl ShutdownWrite for std::net::TcpStream {
+    fn shutdown_write(&self) -> io::Result<()> {
+        self.shutdown(std::net::Shutdown::Write)
+    }
+}
+
+#[cfg(unix)]
+impl ShutdownWrite for std::os::unix::net::UnixStream {
+    fn shutdown_write(&self) -> io::Result<()> {
+        self.shutdown(std::net::Shutdown::Write)
+    }
+}
+
+struct ShutdownAfterRequest<'a, W> {
+    writer: &'a mut W,
+    shutdown: fn(&W) -> io::Result<()>,
+}
+
+impl<'a, W: io::Write> io::Write for ShutdownAfterRequest<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<'a, W: io::Write> client::blocking_io::request::Stream for ShutdownAfterRequest<'a, W> {
+    fn request_complete(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        (self.shutdown)(self.writer)
+    }
+}
+
+impl<R, W> git::Connection<R, W>
+where
+    R: io::Read,
+    W: ShutdownWrite,
+{
+    /// Shut down the writing half of the connection to signal the end of all requests to the server, while
+    /// its response can still be read.
+    ///
+    /// Note that no further requests can be made afterwards.
+    pub fn shutdown_write(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        self.writer.shutdown_write()
+    }
+
+    /// If `toggle` is true, shut down the writing half of the connection once the next request is complete and before
+    /// its response is read.
+    ///
+    /// This is useful for servers which rely on seeing EOF to know that a request is complete, like some implementations
+    /// of stateless protocol V1 services. Note that no further requests can be made afterwards.
+    pub fn shutdown_write_after_request(mut self, toggle: bool) -> Self {
+        self.shutdown_write_after_request = if toggle {
+            Some(<W as ShutdownWrite>::shutdown_write)
+        } else {
+            None
+        };
+        self
+    }
+}
+
 ///
 pub mod connect {
+    #[cfg(unix)]
+    use std::os::unix::net::UnixStream;
     use std::{
         io,
         net::{TcpStream, ToSocketAddrs},
+        path::Path,
     };
 
     use crate::client::git;
@@ -160,6 +260,11 @@ pub mod connect {
             VirtualHostInvalid(host: String) {
                 display("Could not parse '{}' as virtual host with format <host>[:port]", host)
             }
+            #[cfg(feature = "git-tls")]
+            Tls(err: native_tls::Error) {
+                display("The TLS connection to the server could not be established")
+                source(err)
+            }
         }
     }
 
@@ -175,6 +280,13 @@ pub mod connect {
         })
     }
 
+    fn virtual_host_from_env() -> Result<Option<(String, Option<u16>)>, Error> {
+        std::env::var("GIT_OVERRIDE_VIRTUAL_HOST")
+            .ok()
+            .map(parse_host)
+            .transpose()
+    }
+
     /// Connect to a git daemon running on `host` and optionally `port` and a repository at `path`.
     ///
     /// Use `desired_version` to specify a preferred protocol to use, knowing that it can be downgraded by a server not supporting it.
@@ -184,27 +296,185 @@ pub mod connect {
         desired_version: crate::Protocol,
         port: Option<u16>,
     ) -> Result<git::Connection<TcpStream, TcpStream>, Error> {
-        let read = TcpStream::connect_timeout(
-            &(host, port.unwrap_or(9418))
+        let read = connect_tcp(host, port, git_url::Scheme::Git)?;
+        let write = read.try_clone()?;
+        Ok(git::Connection::new(
+            read,
+            write,
+            desired_version,
+            path,
+            virtual_host_from_env()?,
+            git::ConnectMode::Daemon,
+        ))
+    }
+
+    fn connect_tcp(host: &str, port: Option<u16>, scheme: git_url::Scheme) -> Result<TcpStream, Error> {
+        let port = port
+            .or_else(|| scheme.default_port())
+            .expect("only called for schemes with default port");
+        Ok(TcpStream::connect_timeout(
+            &(host, port)
                 .to_socket_addrs()?
                 .next()
                 .expect("after successful resolution there is an IP address"),
             std::time::Duration::from_secs(5),
-        )?;
+        )?)
+    }
+
+    /// Options to control how TLS connections are established by [`connect_tls()`].
+    #[cfg(feature = "git-tls")]
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct TlsOptions {
+        /// The name of the server to send via SNI and to validate its certificate against, or the host connected to if `None`.
+        pub domain: Option<String>,
+        /// If `true`, the default, the name of the server is sent during the handshake to support multiple servers
+        /// behind the same address.
+        pub use_sni: bool,
+        /// If `true`, the default, the certificate presented by the server must be valid and signed by a trusted root certificate.
+        ///
+        /// Disabling this makes the connection vulnerable to man-in-the-middle attacks, and should only be done for testing.
+        pub verify_certificate: bool,
+        /// If `true`, the default, the certificate presented by the server must be issued for the `domain` connected to.
+        pub verify_hostname: bool,
+        /// PEM encoded certificates to trust in addition to the ones trusted by the system, like the ones of a private certificate authority.
+        pub root_certificates: Vec<Vec<u8>>,
+    }
+
+    #[cfg(feature = "git-tls")]
+    impl Default for TlsOptions {
+        fn default() -> Self {
+            TlsOptions {
+                domain: None,
+                use_sni: true,
+                verify_certificate: true,
+                verify_hostname: true,
+                root_certificates: Vec::new(),
+            }
+        }
+    }
+
+    /// A TLS encrypted TCP stream to a git daemon, shared between the reading and the writing half of a [`git::Connection`].
+    #[cfg(feature = "git-tls")]
+    #[derive(Clone)]
+    pub struct TlsStream(std::sync::Arc<std::sync::Mutex<native_tls::TlsStream<TcpStream>>>);
+
+    #[cfg(feature = "git-tls")]
+    impl TlsStream {
+        fn lock(&self) -> std::sync::MutexGuard<'_, native_tls::TlsStream<TcpStream>> {
+            // A panic while reading or writing leaves the stream in no worse state than an IO error would.
+            self.0.lock().unwrap_or_else(|err| err.into_inner())
+        }
+    }
+
+    #[cfg(feature = "git-tls")]
+    impl io::Read for TlsStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            io::Read::read(&mut *self.lock(), buf)
+        }
+    }
+
+    #[cfg(feature = "git-tls")]
+    impl io::Write for TlsStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            io::Write::write(&mut *self.lock(), buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            io::Write::flush(&mut *self.lock())
+        }
+    }
+
+    /// Connect to a git daemon running behind TLS on `host` and optionally `port` and a repository at `path`.
+    ///
+    /// Use `desired_version` to specify a preferred protocol to use, knowing that it can be downgraded by a server not supporting it.
+    /// `options` control the server name indication and verification of the certificate presented by the server.
+    #[cfg(feature = "git-tls")]
+    pub fn connect_tls(
+        host: &str,
+        path: BString,
+        desired_version: crate::Protocol,
+        port: Option<u16>,
+        options: &TlsOptions,
+    ) -> Result<git::Connection<TlsStream, TlsStream>, Error> {
+        let mut builder = native_tls::TlsConnector::builder();
+        builder
+            .use_sni(options.use_sni)
+            .danger_accept_invalid_certs(!options.verify_certificate)
+            .danger_accept_invalid_hostnames(!options.verify_hostname);
+        for certificate in &options.root_certificates {
+            builder.add_root_certificate(native_tls::Certificate::from_pem(certificate).map_err(Error::Tls)?);
+        }
+        let connector = builder.build().map_err(Error::Tls)?;
+
+        let stream = connect_tcp(host, port, git_url::Scheme::GitTls)?;
+        let stream = connector
+            .connect(options.domain.as_deref().unwrap_or(host), stream)
+            .map_err(|err| match err {
+                native_tls::HandshakeError::Failure(err) => Error::Tls(err),
+                native_tls::HandshakeError::WouldBlock(_) => Error::Io(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "blocking sockets don't block",
+                )),
+            })?;
+        let read = TlsStream(std::sync::Arc::new(std::sync::Mutex::new(stream)));
+        let write = read.clone();
+        Ok(git::Connection::new(
+            read,
+            write,
+            desired_version,
+            path,
+            virtual_host_from_env()?,
+            git::ConnectMode::Daemon,
+        ))
+    }
+
+    /// Connect to a git daemon listening on the unix domain socket at `socket_path` and a repository at `path`.
+    ///
+    /// Use `desired_version` to specify a preferred protocol to use, knowing that it can be downgraded by a server not supporting it.
+    #[cfg(unix)]
+    pub fn connect_unix_socket(
+        socket_path: impl AsRef<Path>,
+        path: BString,
+        desired_version: crate::Protocol,
+    ) -> Result<git::Connection<UnixStream, UnixStream>, Error> {
+        let read = UnixStream::connect(socket_path)?;
+        let write = read.try_clone()?;
+        Ok(git::Connection::new(
+            read,
+            write,
+            desired_version,
+            path,
+            virtual_host_from_env()?,
+            git::ConnectMode::Daemon,
+        ))
+    }
+
+    /// Connect to a git daemon listening on the named pipe at `pipe_path`, like `\\.\pipe\git-daemon`, and a repository at `path`.
+    ///
+    /// Use `desired_version` to specify a preferred protocol to use, knowing that it can be downgraded by a server not supporting it.
+    #[cfg(all(windows, feature = "windows-named-pipe"))]
+    pub fn connect_named_pipe(
+        pipe_path: impl AsRef<Path>,
+        path: BString,
+        desired_version: crate::Protocol,
+    ) -> Result<git::Connection<std::fs::File, std::fs::File>, Error> {
+        let read = std::fs::OpenOptions::new().read(true).write(true).open(pipe_path)?;
         let write = read.try_clone()?;
-        let vhost = std::env::var("GIT_OVERRIDE_VIRTUAL_HOST")
-            .ok()
-            .map(parse_host)
-            .transpose()?;
         Ok(git::Connection::new(
             read,
             write,
             desired_version,
             path,
-            vhost,
+            virtual_host_from_env()?,
             git::ConnectMode::Daemon,
         ))
     }
 }
 
 pub use connect::connect;
+#[cfg(all(windows, feature = "windows-named-pipe"))]
+pub use connect::connect_named_pipe;
+#[cfg(unix)]
+pub use connect::connect_unix_socket;
+#[cfg(feature = "git-tls")]
+pub use connect::{connect_tls, TlsOptions};
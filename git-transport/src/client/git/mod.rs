@@ -10,7 +10,7 @@ pub enum ConnectMode {
     Process,
 }
 
-/// A TCP connection to either a `git` daemon or a spawned `git` process.
+/// A connection to either a `git` daemon, via TCP or a local socket, or a spawned `git` process.
 ///
 /// When connecting to a daemon, additional context information is sent with the first line of the handshake. Otherwise that
 /// context is passed using command line arguments to a [spawned `git` process][crate::client::file::SpawnProcessOnDemand].
@@ -23,6 +23,11 @@ pub struct Connection<R, W> {
     supported_versions: [Protocol; 1],
     custom_url: Option<String>,
     pub(in crate::client) mode: ConnectMode,
+    pub(in crate::client) extra_parameters: Vec<(String, Option<String>)>,
+    /// The capabilities advertised by a protocol V2 server during the first handshake, as they are sent only once per connection.
+    pub(in crate::client) v2_capabilities: Option<crate::client::Capabilities>,
+    #[cfg(feature = "blocking-client")]
+    pub(in crate::client) shutdown_write_after_request: Option<fn(&W) -> std::io::Result<()>>,
 }
 
 impl<R, W> Connection<R, W> {
@@ -40,9 +45,60 @@ impl<R, W> Connection<R, W> {
         self.custom_url = url;
         self
     }
+
+    /// Set `parameters` to send with each handshake in addition to the ones passed to `handshake()`, which is only done
+    /// when connecting to a git daemon.
+    pub fn extra_parameters(mut self, parameters: Vec<(String, Option<String>)>) -> Self {
+        self.extra_parameters = parameters;
+        self
+    }
+
+    /// Return the `extra_parameters` passed to `handshake()` followed by the ones set with
+    /// [`extra_parameters()`][Self::extra_parameters()].
+    pub(in crate::client) fn all_extra_parameters<'a>(
+        &'a self,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Vec<(&'a str, Option<&'a str>)> {
+        extra_parameters
+            .iter()
+            .copied()
+            .chain(
+                self.extra_parameters
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_deref())),
+            )
+            .collect()
+    }
+
+    /// Transform the reader and writer of this connection, which must not have been used yet.
+    ///
+    /// Note that shutting down the writer after each request can't be carried over to the new writer.
+    #[cfg(feature = "blocking-client")]
+    pub(in crate::client) fn map_io<R2, W2>(
+        self,
+        read: impl FnOnce(R) -> R2,
+        write: impl FnOnce(W) -> W2,
+    ) -> Connection<R2, W2> {
+        Connection {
+            writer: write(self.writer),
+            line_provider: git_packetline::StreamingPeekableIter::new(
+                read(self.line_provider.into_inner()),
+                &[git_packetline::PacketLine::Flush],
+            ),
+            path: self.path,
+            virtual_host: self.virtual_host,
+            desired_version: self.desired_version,
+            supported_versions: self.supported_versions,
+            custom_url: self.custom_url,
+            mode: self.mode,
+            extra_parameters: self.extra_parameters,
+            v2_capabilities: self.v2_capabilities,
+            shutdown_write_after_request: None,
+        }
+    }
 }
 
-mod message {
+pub(crate) mod message {
     use crate::{Protocol, Service};
     use bstr::{BString, ByteVec};
 
@@ -174,3 +230,11 @@ mod async_io;
 mod blocking_io;
 #[cfg(feature = "blocking-client")]
 pub use blocking_io::connect;
+#[cfg(all(feature = "blocking-client", windows, feature = "windows-named-pipe"))]
+pub use blocking_io::connect_named_pipe;
+#[cfg(all(feature = "blocking-client", unix))]
+pub use blocking_io::connect_unix_socket;
+#[cfg(feature = "blocking-client")]
+pub use blocking_io::ShutdownWrite;
+#[cfg(feature = "git-tls")]
+pub use blocking_io::{connect_tls, TlsOptions};
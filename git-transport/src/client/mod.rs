@@ -14,7 +14,8 @@ mod blocking_io;
 pub use blocking_io::http;
 #[cfg(feature = "blocking-client")]
 pub use blocking_io::{
-    connect, file, ssh, ExtendedBufRead, HandleProgress, RequestWriter, SetServiceResponse, Transport, TransportV2Ext,
+    connect, file, recording, ssh, ExtendedBufRead, HandleProgress, RequestWriter, SetServiceResponse, Transport,
+    TransportV2Ext,
 };
 #[cfg(feature = "blocking-client")]
 #[doc(inline)]
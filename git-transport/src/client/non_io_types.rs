@@ -44,6 +44,21 @@ pub enum Identity {
 
 pub(crate) mod connect {
     use quick_error::quick_error;
+
+    /// Options applied by [`connect()`] to the transports supporting them.
+    #[cfg(any(feature = "blocking-client", feature = "async-client"))]
+    #[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Options {
+        /// Parameters to send with each handshake in addition to those passed to `handshake()`, sent as `key=value` if the
+        /// value is `Some`, or as `key` otherwise.
+        ///
+        /// They are sent to git daemons and via HTTP, but transports spawning a `git` process don't pass them on.
+        pub extra_parameters: Vec<(String, Option<String>)>,
+        /// Headers like `Authorization: Bearer <token>` or trace ids to send with each HTTP request, ignored by all other transports.
+        pub extra_headers: Vec<String>,
+    }
+
     quick_error! {
         /// The error used in [`connect()`].
         #[derive(Debug)]
@@ -70,6 +85,10 @@ pub(crate) mod connect {
             UnsupportedScheme(scheme: git_url::Scheme) {
                 display("The '{}' protocol is currently unsupported", scheme)
             }
+            #[cfg(not(feature = "git-tls"))]
+            CompiledWithoutTls(scheme: git_url::Scheme) {
+                display("'{}' is not compiled in. Compile with the 'git-tls' cargo feature", scheme)
+            }
             #[cfg(not(feature = "http-client-curl"))]
             CompiledWithoutHttp(scheme: git_url::Scheme) {
                 display("'{}' is not compiled in. Compile with the 'http-client-curl' cargo feature", scheme)
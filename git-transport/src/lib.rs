@@ -2,9 +2,14 @@
 //! [`connect()`] to establish a connection given a repository URL.
 //!
 //! All git transports are supported, including `ssh`, `git`, `http` and `https`, as well as local repository paths.
+//!
+//! The [server] side is limited to parsing the requests sent to a git daemon for now.
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms, missing_docs)]
 
+/// A convenience export allowing users of git-transport to use the packet line encoding without their own cargo dependency.
+pub use git_packetline as packetline;
+
 /// The version of the way client and server communicate.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -32,10 +37,20 @@ impl Service {
             Service::UploadPack => "git-upload-pack",
         }
     }
+
+    /// Parse the `name` of a service as rendered by [`as_str()`][Service::as_str()], or return `None` if it is unknown.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"git-receive-pack" => Service::ReceivePack,
+            b"git-upload-pack" => Service::UploadPack,
+            _ => return None,
+        })
+    }
 }
 
 ///
 pub mod client;
+pub mod server;
 
 #[doc(inline)]
 #[cfg(feature = "blocking-client")]
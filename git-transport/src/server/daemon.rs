@@ -0,0 +1,268 @@
+use crate::{Protocol, Service};
+use bstr::{BString, ByteSlice};
+use quick_error::quick_error;
+
+quick_error! {
+    /// The error returned by [`Request::from_bytes()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        MissingService(line: BString) {
+            display("The request line '{}' didn't contain a service followed by a space", line)
+        }
+        UnknownService(service: BString) {
+            display("The service '{}' is unknown", service)
+        }
+        EmptyPath {
+            display("The path of the repository to serve must not be empty")
+        }
+        VirtualHostInvalid(host: BString) {
+            display("Could not parse '{}' as virtual host with format <host>[:port]", host)
+        }
+    }
+}
+
+/// The request sent by a client connecting to a git daemon, which it does with the first packet line of the handshake.
+///
+/// It's the counterpart to the line written by a [client connection][crate::client::git::Connection] in
+/// [daemon mode][crate::client::git::ConnectMode::Daemon].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Request {
+    /// The service the client wants to use.
+    pub service: Service,
+    /// The path to the repository as sent by the client, which may be relative to a base path or to a user's home directory.
+    pub path: BString,
+    /// The host and optional port the client connected to, allowing to serve different repositories per host.
+    pub virtual_host: Option<(String, Option<u16>)>,
+    /// The highest protocol version the client asked for, or [`Protocol::V1`] if it didn't ask for any.
+    pub desired_version: Protocol,
+    /// All extra parameters except for the `version`, as key and optional value.
+    pub extra_parameters: Vec<(BString, Option<BString>)>,
+}
+
+impl Request {
+    /// Parse the request from `line`, the data of the first packet line sent by the client, like
+    /// `git-upload-pack /project.git\0host=example.com\0\0version=2\0`.
+    ///
+    /// A trailing newline is ignored, as are extra parameters specifying unknown protocol versions.
+    pub fn from_bytes(line: &[u8]) -> Result<Self, Error> {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let space = line.find_byte(b' ').ok_or_else(|| Error::MissingService(line.into()))?;
+        let service = Service::from_bytes(&line[..space]).ok_or_else(|| Error::UnknownService(line[..space].into()))?;
+
+        let mut tokens = line[space + 1..].split(|b| *b == 0);
+        let path = tokens.next().unwrap_or_default();
+        if path.is_empty() {
+            return Err(Error::EmptyPath);
+        }
+
+        let mut virtual_host = None;
+        let mut desired_version = Protocol::V1;
+        let mut extra_parameters = Vec::new();
+        let mut in_extra_parameters = false;
+        for token in tokens {
+            if token.is_empty() {
+                in_extra_parameters = true;
+                continue;
+            }
+            if !in_extra_parameters {
+                if let Some(host) = token.strip_prefix(b"host=") {
+                    virtual_host = Some(parse_host(host)?);
+                }
+                continue;
+            }
+            let (key, value) = match token.find_byte(b'=') {
+                Some(pos) => (&token[..pos], Some(&token[pos + 1..])),
+                None => (token, None),
+            };
+            if key == b"version" {
+                let version = match value {
+                    Some(b"2") => Protocol::V2,
+                    Some(b"1") | Some(b"0") => Protocol::V1,
+                    _ => continue,
+                };
+                desired_version = desired_version.max(version);
+            } else {
+                extra_parameters.push((key.into(), value.map(Into::into)));
+            }
+        }
+
+        Ok(Request {
+            service,
+            path: path.into(),
+            virtual_host,
+            desired_version,
+            extra_parameters,
+        })
+    }
+}
+
+fn parse_host(input: &[u8]) -> Result<(String, Option<u16>), Error> {
+    let invalid = || Error::VirtualHostInvalid(input.into());
+    let host = input.to_str().map_err(|_| invalid())?;
+    let (host, port) = match host.rfind(':') {
+        // a colon within brackets belongs to an IPv6 address
+        Some(pos) if !host.starts_with('[') || host[..pos].ends_with(']') => (&host[..pos], Some(&host[pos + 1..])),
+        _ => (host, None),
+    };
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    Ok((
+        host.to_owned(),
+        port.map(|port| port.parse().map_err(|_| invalid())).transpose()?,
+    ))
+}
+
+/// Route [requests][Request] to the handler of their service, serving as entry point for a daemon.
+///
+/// Services without a handler are disabled, which is why by default no service is enabled.
+#[derive(Clone)]
+pub struct Router<T> {
+    handlers: Vec<(Service, T)>,
+}
+
+impl<T> Default for Router<T> {
+    fn default() -> Self {
+        Router { handlers: Vec::new() }
+    }
+}
+
+impl<T> Router<T> {
+    /// Enable `service` by handling it with `handler`, replacing any previous handler.
+    pub fn with_handler(mut self, service: Service, handler: T) -> Self {
+        self.handlers.retain(|(s, _)| *s != service);
+        self.handlers.push((service, handler));
+        self
+    }
+
+    /// Return true if there is a handler for `service`.
+    pub fn is_enabled(&self, service: Service) -> bool {
+        self.handlers.iter().any(|(s, _)| *s == service)
+    }
+
+    /// Return the handler for the service of `request`, or `None` if the service is disabled.
+    pub fn route(&mut self, request: &Request) -> Option<&mut T> {
+        self.handlers
+            .iter_mut()
+            .find(|(service, _)| *service == request.service)
+            .map(|(_, handler)| handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(
+        service: Service,
+        path: &str,
+        virtual_host: Option<(&str, Option<u16>)>,
+        desired_version: Protocol,
+        extra_parameters: &[(&str, Option<&str>)],
+    ) -> Request {
+        Request {
+            service,
+            path: path.into(),
+            virtual_host: virtual_host.map(|(host, port)| (host.to_owned(), port)),
+            desired_version,
+            extra_parameters: extra_parameters
+                .iter()
+                .map(|(key, value)| ((*key).into(), value.map(Into::into)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    #[cfg(any(feature = "blocking-client", feature = "async-client"))]
+    fn roundtrips_through_client_connect_messages() {
+        use crate::client::git::message;
+        for (host, version, extra_parameters) in &[
+            (None, Protocol::V1, &[][..]),
+            (None, Protocol::V2, &[][..]),
+            (Some(("example.org", None)), Protocol::V1, &[][..]),
+            (Some(("example.org", Some(9418))), Protocol::V2, &[][..]),
+            (None, Protocol::V1, &[("key", Some("value")), ("value-only", None)][..]),
+            (
+                Some(("[::1]", Some(404))),
+                Protocol::V2,
+                &[("key", Some("value")), ("value-only", None)][..],
+            ),
+        ] {
+            let virtual_host = host.map(|(host, port)| (host.to_owned(), port));
+            let line = message::connect(
+                Service::UploadPack,
+                *version,
+                b"/path/project.git",
+                virtual_host.as_ref(),
+                extra_parameters,
+            );
+            assert_eq!(
+                Request::from_bytes(&line).expect("valid request"),
+                request(
+                    Service::UploadPack,
+                    "/path/project.git",
+                    *host,
+                    *version,
+                    extra_parameters
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn lines_without_host_and_parameters_and_with_trailing_newline() {
+        assert_eq!(
+            Request::from_bytes(b"git-receive-pack /project.git\n").expect("valid"),
+            request(Service::ReceivePack, "/project.git", None, Protocol::V1, &[])
+        );
+    }
+
+    #[test]
+    fn the_highest_known_version_is_desired() {
+        assert_eq!(
+            Request::from_bytes(b"git-upload-pack /p\0\0version=3\0version=2\0version=1\0")
+                .expect("valid")
+                .desired_version,
+            Protocol::V2
+        );
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert!(matches!(
+            Request::from_bytes(b"git-upload-pack").unwrap_err(),
+            Error::MissingService(_)
+        ));
+        assert!(matches!(
+            Request::from_bytes(b"git-frobnicate /p\0").unwrap_err(),
+            Error::UnknownService(_)
+        ));
+        assert!(matches!(
+            Request::from_bytes(b"git-upload-pack \0host=a\0").unwrap_err(),
+            Error::EmptyPath
+        ));
+        for host in &["a:port", ":80", "a:99999"] {
+            assert!(matches!(
+                Request::from_bytes(format!("git-upload-pack /p\0host={}\0", host).as_bytes()).unwrap_err(),
+                Error::VirtualHostInvalid(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn router_only_routes_enabled_services() {
+        let mut router = Router::default().with_handler(Service::UploadPack, 1);
+        assert!(router.is_enabled(Service::UploadPack));
+        assert!(!router.is_enabled(Service::ReceivePack));
+        let upload_pack = request(Service::UploadPack, "/p", None, Protocol::V2, &[]);
+        assert_eq!(router.route(&upload_pack), Some(&mut 1));
+        assert_eq!(
+            router.route(&request(Service::ReceivePack, "/p", None, Protocol::V1, &[])),
+            None
+        );
+
+        let mut router = router.with_handler(Service::UploadPack, 2);
+        assert_eq!(router.route(&upload_pack), Some(&mut 2), "handlers are replaced");
+    }
+}
@@ -0,0 +1,6 @@
+//! Types and functions to implement the server side of the git transport layer, as needed by a git daemon.
+//!
+//! Currently only the parsing of the first line sent by clients is implemented, which is free of IO.
+
+///
+pub mod daemon;
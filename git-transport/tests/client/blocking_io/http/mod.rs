@@ -299,6 +299,42 @@ Accept: application/x-git-upload-pack-result
     Ok(())
 }
 
+#[test]
+fn extra_parameters_and_headers_are_sent_with_each_request() -> crate::Result {
+    let (server, c) = mock::serve_and_connect(
+        "v1/http-handshake.response",
+        "path/not/important/due/to/mock",
+        Protocol::V1,
+    )?;
+    let mut c = c
+        .extra_parameters(vec![("trace".into(), Some("abc".into())), ("flag".into(), None)])
+        .extra_headers(Some("Authorization: Bearer token"));
+    let SetServiceResponse { refs, .. } = c.handshake(Service::UploadPack, &[("key", Some("value"))])?;
+    io::copy(&mut refs.expect("refs in protocol V1"), &mut io::sink())?;
+    let received = server.received_as_string();
+    assert!(
+        received.lines().any(|l| l == "Git-Protocol: key=value:trace=abc:flag"),
+        "extra parameters of the transport follow the ones passed to the handshake"
+    );
+    assert!(received.lines().any(|l| l == "Authorization: Bearer token"));
+
+    server.next_read_and_respond_with(fixture_bytes("v1/http-clone.response"));
+    let mut writer = c.request(
+        client::WriteMode::OneLfTerminatedLinePerWriteCall,
+        client::MessageKind::Text(b"done"),
+    )?;
+    writer.write_all(b"hello")?;
+    io::copy(&mut writer.into_read()?, &mut io::sink())?;
+    assert!(
+        server
+            .received_as_string()
+            .lines()
+            .any(|l| l == "Authorization: Bearer token"),
+        "extra headers are sent with requests as well"
+    );
+    Ok(())
+}
+
 #[test]
 fn handshake_and_lsrefs_and_fetch_v2() -> crate::Result {
     let (server, mut c) = mock::serve_and_connect(
@@ -450,3 +486,54 @@ Git-Protocol: version=2
     );
     Ok(())
 }
+
+fn lsrefs_after_dropped_connection(
+    max_retries: usize,
+) -> Result<(mock::Server, Result<Vec<String>, client::Error>), crate::Error> {
+    let (server, c) = mock::serve_and_connect(
+        "v2/http-handshake.response",
+        "path/not/important/due/to/mock",
+        Protocol::V2,
+    )?;
+    let mut c = c.max_retries(max_retries);
+    c.handshake(Service::UploadPack, &[])?;
+    server.received();
+
+    server.next_read_and_respond_with(Vec::new());
+    let server = std::thread::spawn(move || {
+        server.received();
+        server.next_read_and_respond_with(fixture_bytes("v2/http-lsrefs.response"));
+        server
+    });
+    let res = c
+        .invoke("ls-refs", std::iter::empty(), None::<IntoIter<bstr::BString>>)
+        .and_then(|res| res.lines().collect::<Result<Vec<_>, _>>().map_err(Into::into));
+    let server = server.join().expect("no panic");
+    Ok((server, res))
+}
+
+#[test]
+fn idempotent_requests_are_retried_after_io_errors() -> crate::Result {
+    let (server, res) = lsrefs_after_dropped_connection(1)?;
+    assert_eq!(
+        res?,
+        vec![
+            "808e50d724f604f69ab93c6da2919c014667bedb HEAD symref-target:refs/heads/master",
+            "808e50d724f604f69ab93c6da2919c014667bedb refs/heads/master"
+        ]
+    );
+    assert!(
+        server
+            .received_as_string()
+            .ends_with("0014command=ls-refs\n0000\r\n0\r\n\r\n"),
+        "the buffered request is sent again in full"
+    );
+    Ok(())
+}
+
+#[test]
+fn io_errors_are_surfaced_without_retries() -> crate::Result {
+    let (_server, res) = lsrefs_after_dropped_connection(0)?;
+    assert!(res.is_err(), "the connection was dropped without response");
+    Ok(())
+}
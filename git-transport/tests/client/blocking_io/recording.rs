@@ -0,0 +1,87 @@
+use std::io::{BufRead, Write};
+
+use bstr::ByteSlice;
+use git_transport::{
+    client::{self, git, recording, Transport, TransportWithoutIO},
+    Protocol, Service,
+};
+
+use crate::fixture_bytes;
+
+fn handshake_and_request(transport: &mut impl Transport) -> crate::Result<Vec<String>> {
+    let refs = transport
+        .handshake(Service::UploadPack, &[])?
+        .refs
+        .expect("v1 protocol provides refs")
+        .lines()
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut writer = transport.request(
+        client::WriteMode::OneLfTerminatedLinePerWriteCall,
+        client::MessageKind::Flush,
+    )?;
+    writer.write_all(b"hello")?;
+    let nak_line = writer.into_read()?.lines().next().expect("exactly one line")?;
+    Ok(refs.into_iter().chain(Some(nak_line)).collect())
+}
+
+fn connection<'a>(response: &'a [u8], out: &'a mut Vec<u8>) -> git::Connection<&'a [u8], &'a mut Vec<u8>> {
+    git::Connection::new(
+        response,
+        out,
+        Protocol::V1,
+        "/foo.git",
+        Some(("example.org", None)),
+        git::ConnectMode::Daemon,
+    )
+}
+
+#[test]
+fn recordings_can_be_replayed() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let server_response = fixture_bytes("v1/clone.response");
+    let mut out = Vec::new();
+
+    let mut recording = recording::Transport::new(connection(&server_response, &mut out), dir.path(), "clone")?;
+    let recorded_lines = handshake_and_request(&mut recording)?;
+    drop(recording);
+
+    let (request_path, response_path) = recording::paths(dir.path(), "clone");
+    assert_eq!(
+        std::fs::read(request_path)?.as_bstr(),
+        out.as_bstr(),
+        "all writes are recorded"
+    );
+    let response = std::fs::read(response_path)?;
+    assert!(
+        server_response.starts_with(&response) && response.len() < server_response.len(),
+        "only what was read is recorded, the pack wasn't read"
+    );
+
+    let mut replay = recording::Replay::new(
+        dir.path(),
+        "clone",
+        Protocol::V1,
+        "/foo.git",
+        Some(("example.org", None)),
+        git::ConnectMode::Daemon,
+    )?;
+    assert_eq!(replay.to_url(), "file:///foo.git");
+    assert_eq!(handshake_and_request(&mut replay)?, recorded_lines);
+    assert_eq!(replay.written().as_bstr(), replay.recorded_request().as_bstr());
+    Ok(())
+}
+
+#[test]
+fn the_recorded_connection_can_be_used_without_recording() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let server_response = fixture_bytes("v1/clone.response");
+    let mut out = Vec::new();
+    let recording = recording::Transport::new(connection(&server_response, &mut out), dir.path(), "nothing")?;
+    let mut c = recording.into_inner();
+    c.handshake(Service::UploadPack, &[])?;
+
+    let (request_path, response_path) = recording::paths(dir.path(), "nothing");
+    assert!(std::fs::read(request_path)?.is_empty());
+    assert!(std::fs::read(response_path)?.is_empty());
+    Ok(())
+}
@@ -160,6 +160,29 @@ async fn handshake_v1_process_mode() -> crate::Result {
     Ok(())
 }
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn handshake_with_extra_parameters_of_the_connection() -> crate::Result {
+    let mut out = Vec::new();
+    let server_response = fixture_bytes("v1/clone.response");
+    let mut c = git::Connection::new(
+        server_response.as_slice(),
+        &mut out,
+        Protocol::V1,
+        "/foo.git",
+        Some(("example.org", None)),
+        git::ConnectMode::Daemon,
+    )
+    .extra_parameters(vec![("trace".into(), Some("abc".into())), ("flag".into(), None)]);
+    c.handshake(Service::UploadPack, &[("key", Some("value"))]).await?;
+
+    assert_eq!(
+        out.as_slice().as_bstr(),
+        b"0048git-upload-pack /foo.git\0host=example.org\0\0key=value\0trace=abc\0flag\0".as_bstr(),
+        "extra parameters of the connection follow the ones passed to the handshake"
+    );
+    Ok(())
+}
+
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
 async fn handshake_v2_downgrade_to_v1() -> crate::Result {
     let mut out = Vec::new();
@@ -353,3 +376,105 @@ async fn handshake_v2_and_request_inner() -> crate::Result {
     );
     Ok(())
 }
+
+#[cfg(all(unix, feature = "blocking-client"))]
+#[test]
+fn handshake_v1_over_unix_socket() -> crate::Result {
+    use std::{io::Read, os::unix::net::UnixListener};
+
+    let socket_path = std::env::temp_dir().join(format!("git-transport-test-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = UnixListener::bind(&socket_path)?;
+    let server = std::thread::spawn(move || -> std::io::Result<Vec<u8>> {
+        let (mut stream, _) = listener.accept()?;
+        let mut hex_len = [0u8; 4];
+        stream.read_exact(&mut hex_len)?;
+        let len = usize::from_str_radix(std::str::from_utf8(&hex_len).expect("ascii"), 16).expect("valid hex");
+        let mut request = vec![0; len - hex_len.len()];
+        stream.read_exact(&mut request)?;
+        stream.write_all(&fixture_bytes("v1/clone.response"))?;
+        Ok(request)
+    });
+
+    let mut c = git::connect_unix_socket(&socket_path, "/foo.git".into(), Protocol::V1)?;
+    assert!(c.is_stateful(), "socket connections are stateful");
+    let mut res = c.handshake(Service::UploadPack, &[])?;
+    assert_eq!(res.actual_protocol, Protocol::V1);
+    let refs = res
+        .refs
+        .as_mut()
+        .expect("v1 protocol provides refs")
+        .lines()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        refs,
+        vec![
+            "808e50d724f604f69ab93c6da2919c014667bedb HEAD",
+            "808e50d724f604f69ab93c6da2919c014667bedb refs/heads/master"
+        ]
+    );
+
+    let request = server.join().expect("no panic in server thread")?;
+    std::fs::remove_file(&socket_path)?;
+    assert_eq!(
+        request.as_bstr(),
+        b"git-upload-pack /foo.git\0".as_bstr(),
+        "the daemon receives the same request as if connected via TCP"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "blocking-client")]
+#[test]
+fn shutdown_write_after_request_lets_servers_waiting_for_eof_respond() -> crate::Result {
+    use std::{io::Read, net::TcpListener};
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
+    let server = std::thread::spawn(move || -> std::io::Result<Vec<u8>> {
+        let (mut stream, _) = listener.accept()?;
+        let mut hex_len = [0u8; 4];
+        stream.read_exact(&mut hex_len)?;
+        let len = usize::from_str_radix(std::str::from_utf8(&hex_len).expect("ascii"), 16).expect("valid hex");
+        let mut connect_request = vec![0; len - hex_len.len()];
+        stream.read_exact(&mut connect_request)?;
+
+        let response = fixture_bytes("v1/clone.response");
+        let refs_end = response.find(b"0000").expect("flush packet after refs") + 4;
+        stream.write_all(&response[..refs_end])?;
+
+        let mut request = Vec::new();
+        stream.read_to_end(&mut request)?;
+        stream.write_all(&response[refs_end..])?;
+        Ok(request)
+    });
+
+    let mut c =
+        git::connect("127.0.0.1", "/foo.git".into(), Protocol::V1, Some(port))?.shutdown_write_after_request(true);
+    let mut res = c.handshake(Service::UploadPack, &[])?;
+    assert_eq!(res.actual_protocol, Protocol::V1);
+    let refs = res
+        .refs
+        .as_mut()
+        .expect("v1 protocol provides refs")
+        .lines()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(refs.len(), 2);
+    drop(res);
+
+    let mut writer = c.request(
+        client::WriteMode::OneLfTerminatedLinePerWriteCall,
+        client::MessageKind::Text(b"done"),
+    )?;
+    writer.write_all(b"want 808e50d724f604f69ab93c6da2919c014667bedb")?;
+    let nak_line = writer.into_read()?.lines().next().expect("exactly one line")?;
+    assert_eq!(nak_line, "NAK", "the server only responds once it sees EOF");
+
+    let request = server.join().expect("no panic in server thread")?;
+    assert_eq!(
+        request.as_bstr(),
+        b"0032want 808e50d724f604f69ab93c6da2919c014667bedb\n0009done\n".as_bstr(),
+        "the complete request was received before the writing half was shut down"
+    );
+    Ok(())
+}
@@ -1,3 +1,8 @@
+///
+pub mod rewrite;
+#[doc(inline)]
+pub use rewrite::rewrite;
+
 ///
 pub mod ancestors {
     use git_hash::{oid, ObjectId};
@@ -0,0 +1,110 @@
+use git_hash::{oid, ObjectId};
+use git_object::{immutable, mutable};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The error returned by [`rewrite()`].
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum Error<E> {
+    NotFound { oid: ObjectId },
+    Edit { oid: ObjectId, err: E },
+    Write { oid: ObjectId, err: E },
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NotFound { oid } => write!(f, "The commit {} could not be found", oid),
+            Error::Edit { oid, err } => write!(f, "The commit {} could not be edited: {}", oid, err),
+            Error::Write { oid, err } => write!(f, "The rewritten commit {} could not be written: {}", oid, err),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::NotFound { .. } => None,
+            Error::Edit { err, .. } | Error::Write { err, .. } => Some(err),
+        }
+    }
+}
+
+/// A mapping of the ids of all visited commits to the ids of their rewritten counterparts, or to themselves if they
+/// didn't change.
+pub type Map = BTreeMap<ObjectId, ObjectId>;
+
+/// Rewrite the commits reachable from `tips`, parents before their children, and return a [`Map`] of original to new ids.
+///
+/// * `find` - a way to lookup commits by their id, writing their data into the buffer and returning the decoded commit if
+///   it is present and is a commit. Not finding a commit is an [error][Error::NotFound].
+/// * `predicate` - indicate whether a given commit should be rewritten and whether its parents should be traversed.
+///   Commits for which it returns false keep their id and are not part of the returned map.
+/// * `edit` - called with the original id of each commit and a copy of it whose parents are already remapped to
+///   their rewritten counterparts. It may change any field, like the author, the message or the tree.
+/// * `write` - store the given commit and return its id. It is only called for commits which differ from their original.
+pub fn rewrite<Find, Predicate, Edit, Write, E>(
+    tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+    mut find: Find,
+    mut predicate: Predicate,
+    mut edit: Edit,
+    mut write: Write,
+) -> Result<Map, Error<E>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::Commit<'a>>,
+    Predicate: FnMut(&oid) -> bool,
+    Edit: FnMut(&oid, &mut mutable::Commit) -> Result<(), E>,
+    Write: FnMut(mutable::Commit) -> Result<ObjectId, E>,
+{
+    let mut buf = Vec::new();
+    let mut is_included = {
+        let mut included = BTreeMap::new();
+        move |id: &oid| *included.entry(id.to_owned()).or_insert_with(|| predicate(id))
+    };
+    let mut visited = BTreeSet::new();
+    let mut pending = BTreeMap::new();
+    let mut map = Map::new();
+    let mut stack = Vec::new();
+    for tip in tips.into_iter().map(Into::into) {
+        if is_included(&tip) {
+            stack.push((tip, false));
+        }
+    }
+    stack.reverse();
+
+    // Depth-first in post-order, handling each commit only once all of its parents were handled.
+    // Commits may be on the stack more than once if they are reachable through multiple paths, but are only visited
+    // when they are popped first, which assures parents are always visited before their children.
+    while let Some((id, parents_handled)) = stack.pop() {
+        if parents_handled {
+            let original: mutable::Commit = pending.remove(&id).expect("commit decoded when first visited");
+            let mut commit = original.clone();
+            for parent in commit.parents.iter_mut() {
+                if let Some(new_parent) = map.get(parent) {
+                    *parent = *new_parent;
+                }
+            }
+            edit(&id, &mut commit).map_err(|err| Error::Edit { oid: id, err })?;
+            let new_id = if commit == original {
+                id
+            } else {
+                write(commit).map_err(|err| Error::Write { oid: id, err })?
+            };
+            map.insert(id, new_id);
+            continue;
+        }
+        if !visited.insert(id) {
+            continue;
+        }
+
+        let commit: mutable::Commit = find(&id, &mut buf).ok_or(Error::NotFound { oid: id })?.into();
+        stack.push((id, true));
+        for parent in commit.parents.iter().rev() {
+            if !visited.contains(parent) && is_included(parent) {
+                stack.push((*parent, false));
+            }
+        }
+        pending.insert(id, commit);
+    }
+    Ok(map)
+}
@@ -12,3 +12,11 @@ pub use recorder::Recorder;
 pub mod breadthfirst;
 #[doc(inline)]
 pub use breadthfirst::traverse as breadthfirst;
+
+///
+pub mod paths;
+#[doc(inline)]
+pub use paths::Paths;
+
+///
+pub mod parallel;
@@ -0,0 +1,47 @@
+use crate::tree::breadthfirst::Error;
+use git_features::parallel::{self, ShardedSet};
+use git_hash::{oid, ObjectId};
+use git_object::{immutable, tree};
+
+/// Traverse the `roots` trees and all trees they contain using up to `thread_limit` threads, and return the ids of all entries
+/// which weren't yet contained in `seen`, adding them to it in the process.
+///
+/// Each tree to traverse is a task picked up by whichever thread is idle first, which keeps all threads busy even if there is
+/// only a single root tree. Entries are returned in no particular order, and trees already contained in `seen` aren't entered.
+///
+/// * `new_state(thread_number)` - create state for use by `find` in each thread, like an object cache.
+/// * `find` - like in [`breadthfirst()`][crate::tree::breadthfirst()], a way to lookup tree data by its id, writing it into the
+///   given buffer and returning an iterator over its entries, or `None` if it couldn't be found which is returned
+///   as [`Error::NotFound`].
+pub fn unseen<State, Find>(
+    roots: impl IntoIterator<Item = ObjectId>,
+    seen: &ShardedSet<ObjectId>,
+    thread_limit: Option<usize>,
+    new_state: impl Fn(usize) -> State + Send + Sync,
+    find: Find,
+) -> Result<Vec<ObjectId>, Error>
+where
+    Find: for<'a> Fn(&oid, &'a mut Vec<u8>, &mut State) -> Option<immutable::TreeIter<'a>> + Send + Sync,
+    State: Send,
+{
+    let states = parallel::in_parallel_with_tasks(
+        roots,
+        thread_limit,
+        |n| (new_state(n), Vec::new(), Vec::new()),
+        |tree_id, (state, buf, unseen): &mut (State, Vec<u8>, Vec<ObjectId>), spawn| -> Result<(), Error> {
+            let tree = find(&tree_id, buf, state).ok_or(Error::NotFound { oid: tree_id })?;
+            for entry in tree {
+                let entry = entry?;
+                let id = entry.oid.to_owned();
+                if seen.insert(id) {
+                    unseen.push(id);
+                    if entry.mode == tree::EntryMode::Tree {
+                        spawn(id);
+                    }
+                }
+            }
+            Ok(())
+        },
+    )?;
+    Ok(states.into_iter().flat_map(|(_, _, unseen)| unseen).collect())
+}
@@ -0,0 +1,139 @@
+use crate::tree::recorder::Entry;
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BString, ByteVec},
+    immutable, tree,
+};
+use quick_error::quick_error;
+use std::{borrow::BorrowMut, collections::VecDeque, ops::Range};
+
+quick_error! {
+    /// The error is part of the item returned by the [`Paths`] iterator.
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        NotFound{oid: ObjectId} {
+            display("The tree {} could not be found", oid)
+        }
+        ObjectDecode(err: immutable::object::decode::Error) {
+            display("An object could not be decoded")
+            source(err)
+            from()
+        }
+    }
+}
+
+/// The state used and potentially shared by multiple tree traversals, holding all buffers to be reused.
+#[derive(Default, Clone)]
+pub struct State {
+    /// Trees yet to be traversed along with their full path.
+    next: VecDeque<(ObjectId, BString)>,
+    /// The entries of the current tree, with their filename as range into `names`.
+    entries: VecDeque<(tree::EntryMode, ObjectId, Range<usize>)>,
+    names: Vec<u8>,
+    /// The path of the current tree.
+    tree_path: BString,
+    /// The path of the current entry.
+    path: BString,
+    buf: Vec<u8>,
+}
+
+impl State {
+    fn clear(&mut self) {
+        self.next.clear();
+        self.entries.clear();
+        self.names.clear();
+        self.tree_path.clear();
+        self.path.clear();
+        self.buf.clear();
+    }
+}
+
+/// An iterator over all entries of a tree and its subtrees in breadth-first order, yielding each entry along with its
+/// full path relative to the root tree.
+///
+/// Paths are assembled in a buffer reused across entries and copied only when an entry is returned.
+pub struct Paths<Find, StateMut> {
+    find: Find,
+    state: StateMut,
+}
+
+impl<Find, StateMut> Paths<Find, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    StateMut: BorrowMut<State>,
+{
+    /// Create a new instance.
+    ///
+    /// * `root` - the id of the tree to start the traversal at.
+    /// * `state` - all state used for the traversal. If multiple traversals are performed, allocations can be minimized by reusing
+    ///   this state.
+    /// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+    ///   an iterator over entries if the object is present and is a tree. Caching should be implemented within this function
+    ///   as needed. Not finding a tree should be considered an error as all objects in the tree DAG should be present in the
+    ///   database. Hence [`Error::NotFound`] should be escalated into a more specific error if its encountered by the caller.
+    pub fn new(root: impl Into<ObjectId>, mut state: StateMut, find: Find) -> Self {
+        {
+            let state = state.borrow_mut();
+            state.clear();
+            state.next.push_back((root.into(), BString::default()));
+        }
+        Paths { find, state }
+    }
+
+    fn load_next_tree(&mut self) -> Option<Result<(), Error>> {
+        let state = self.state.borrow_mut();
+        let (oid, path) = state.next.pop_front()?;
+        state.tree_path = path;
+        state.names.clear();
+        let tree = match (self.find)(&oid, &mut state.buf) {
+            Some(tree) => tree,
+            None => return Some(Err(Error::NotFound { oid })),
+        };
+        for entry in tree {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err.into())),
+            };
+            let start = state.names.len();
+            state.names.extend_from_slice(entry.filename);
+            state
+                .entries
+                .push_back((entry.mode, entry.oid.to_owned(), start..state.names.len()));
+        }
+        Some(Ok(()))
+    }
+}
+
+impl<Find, StateMut> Iterator for Paths<Find, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<immutable::TreeIter<'a>>,
+    StateMut: BorrowMut<State>,
+{
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.state.borrow_mut().entries.is_empty() {
+            if let Err(err) = self.load_next_tree()? {
+                self.state.borrow_mut().clear();
+                return Some(Err(err));
+            }
+        }
+        let state = self.state.borrow_mut();
+        let (mode, oid, name) = state.entries.pop_front().expect("at least one entry");
+        state.path.clear();
+        state.path.push_str(&state.tree_path);
+        if !state.path.is_empty() {
+            state.path.push(b'/');
+        }
+        state.path.push_str(&state.names[name]);
+        if mode == tree::EntryMode::Tree {
+            state.next.push_back((oid, state.path.clone()));
+        }
+        Some(Ok(Entry {
+            mode,
+            filepath: state.path.clone(),
+            oid,
+        }))
+    }
+}
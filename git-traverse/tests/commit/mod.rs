@@ -162,3 +162,186 @@ mod ancestor {
         )
     }
 }
+
+mod rewrite {
+    use std::collections::HashMap;
+
+    use git_hash::ObjectId;
+    use git_object::{bstr::ByteSlice, mutable};
+    use git_odb::{linked::Store, pack, FindExt, Write};
+    use git_traverse::commit;
+
+    use crate::hex_to_id;
+
+    const M1B1: &str = "01ec18a3ebf2855708ad3c9d244306bc1fae3e9b";
+    const C5: &str = "efd9a841189668f1bab5b8ebade9cd0a1b139a37";
+    const B1C2: &str = "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353";
+    const C4: &str = "9556057aee5abb06912922e9f26c46386a816822";
+    const B1C1: &str = "9152eeee2328073cf23dcf8e90c949170b711659";
+    const C3: &str = "17d78c64cef6c33a10a604573fd2c429e477fd63";
+    const C2: &str = "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7";
+    const C1: &str = "134385f6d781b7e97062102c6a483440bfda2a03";
+
+    /// Rewrite the history of all `tips` and return the id map along with all written commits by their new id.
+    fn rewrite(
+        tips: &[&str],
+        predicate: impl FnMut(&git_hash::oid) -> bool,
+        edit: impl FnMut(&git_hash::oid, &mut mutable::Commit) -> Result<(), std::io::Error>,
+    ) -> crate::Result<(commit::rewrite::Map, HashMap<ObjectId, mutable::Commit>)> {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits.sh")?;
+        let db = Store::at(dir.join(".git").join("objects"))?;
+        let sink = git_odb::sink();
+        let mut written = HashMap::new();
+        let map = commit::rewrite(
+            tips.iter().copied().map(hex_to_id),
+            |oid, buf| db.find_existing_commit(oid, buf, &mut pack::cache::Never).ok(),
+            predicate,
+            edit,
+            |commit| {
+                let id = sink.write(&mutable::Object::Commit(commit.clone()), git_hash::Kind::Sha1)?;
+                written.insert(id, commit);
+                Ok(id)
+            },
+        )?;
+        Ok((map, written))
+    }
+
+    #[test]
+    fn unchanged_commits_map_to_themselves_and_nothing_is_written() -> crate::Result {
+        let (map, written) = rewrite(&[M1B1], |_| true, |_, _| Ok(()))?;
+        assert_eq!(map.len(), 8, "all commits were visited");
+        assert!(map.iter().all(|(old, new)| old == new));
+        assert!(written.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn parents_of_descendants_are_remapped_including_merges() -> crate::Result {
+        let (map, written) = rewrite(
+            &[M1B1],
+            |_| true,
+            |id, commit| {
+                if id == hex_to_id(B1C1) {
+                    commit.message = "rewritten".into();
+                }
+                Ok(())
+            },
+        )?;
+        for unchanged in &[C5, C4, C3] {
+            let id = hex_to_id(unchanged);
+            assert_eq!(map[&id], id, "{} is not affected by the rewrite", unchanged);
+        }
+        assert_eq!(written.len(), 3, "the edited commit and all of its descendants");
+
+        let new_b1c1 = map[&hex_to_id(B1C1)];
+        assert_eq!(written[&new_b1c1].message.as_bstr(), "rewritten");
+        assert_eq!(written[&new_b1c1].parents.as_slice(), &[hex_to_id(C4)]);
+
+        let new_b1c2 = map[&hex_to_id(B1C2)];
+        assert_ne!(new_b1c2, hex_to_id(B1C2));
+        assert_eq!(written[&new_b1c2].parents.as_slice(), &[new_b1c1]);
+
+        let new_merge = map[&hex_to_id(M1B1)];
+        assert_eq!(
+            written[&new_merge].parents.as_slice(),
+            &[hex_to_id(C5), new_b1c2],
+            "parent order is retained"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn the_predicate_excludes_commits_and_their_ancestors() -> crate::Result {
+        let mut edited = Vec::new();
+        let (map, written) = rewrite(
+            &[C5],
+            |id| id != hex_to_id(C3),
+            |id, commit| {
+                edited.push(id.to_owned());
+                commit.message.push(b'!');
+                Ok(())
+            },
+        )?;
+        assert_eq!(
+            edited,
+            vec![hex_to_id(C4), hex_to_id(C5)],
+            "parents are edited before their children"
+        );
+        assert_eq!(map.len(), 2);
+        assert!(!map.contains_key(&hex_to_id(C3)));
+        let new_c4 = map[&hex_to_id(C4)];
+        assert_eq!(
+            written[&new_c4].parents.as_slice(),
+            &[hex_to_id(C3)],
+            "excluded parents are kept as is"
+        );
+        assert_eq!(written[&map[&hex_to_id(C5)]].parents.as_slice(), &[new_c4]);
+        Ok(())
+    }
+
+    #[test]
+    fn ancestors_shared_by_multiple_tips_are_rewritten_before_their_descendants() -> crate::Result {
+        let mut edited = Vec::new();
+        let (map, written) = rewrite(
+            &[C5, C4],
+            |_| true,
+            |id, commit| {
+                edited.push(id.to_owned());
+                if id == hex_to_id(C3) {
+                    commit.message = "rewritten".into();
+                }
+                Ok(())
+            },
+        )?;
+        assert_eq!(
+            &edited[..3],
+            &[hex_to_id(C1), hex_to_id(C2), hex_to_id(C3)],
+            "parents are edited before their children"
+        );
+        assert_eq!(edited.len(), 5, "each commit is edited once");
+        assert_eq!(written.len(), 3, "the edited commit and all of its descendants");
+        let (new_c3, new_c4) = (map[&hex_to_id(C3)], map[&hex_to_id(C4)]);
+        assert_eq!(written[&new_c4].parents.as_slice(), &[new_c3]);
+        assert_eq!(
+            written[&map[&hex_to_id(C5)]].parents.as_slice(),
+            &[new_c4],
+            "the second tip was rewritten before the first one"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn diamonds_are_rewritten_once_with_all_parents_remapped() -> crate::Result {
+        let mut edited = Vec::new();
+        let (map, written) = rewrite(
+            &[M1B1, B1C1],
+            |_| true,
+            |id, commit| {
+                edited.push(id.to_owned());
+                if id == hex_to_id(C4) {
+                    commit.message = "rewritten".into();
+                }
+                Ok(())
+            },
+        )?;
+        assert_eq!(edited.len(), 8, "each commit is edited once");
+        assert_eq!(written.len(), 5, "the edited commit and all of its descendants");
+        for commit in written.values() {
+            for parent in &commit.parents {
+                assert!(
+                    !matches!(map.get(parent), Some(new_parent) if new_parent != parent),
+                    "parent {} was rewritten before its child",
+                    parent
+                );
+            }
+        }
+        let new_c4 = map[&hex_to_id(C4)];
+        assert_eq!(written[&map[&hex_to_id(B1C1)]].parents.as_slice(), &[new_c4]);
+        assert_eq!(written[&map[&hex_to_id(C5)]].parents.as_slice(), &[new_c4]);
+        assert_eq!(
+            written[&map[&hex_to_id(M1B1)]].parents.as_slice(),
+            &[map[&hex_to_id(C5)], map[&hex_to_id(B1C2)]]
+        );
+        Ok(())
+    }
+}
@@ -100,3 +100,107 @@ fn basic_nesting() -> crate::Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn paths_yields_the_same_entries_as_breadthfirst_recorder() -> crate::Result<()> {
+    let db = db()?;
+    let mut buf = Vec::new();
+    let tree_id = db
+        .find_existing_commit_iter(
+            hex_to_id("85df34aa34848b8138b2b3dcff5fb5c2b734e0ce"),
+            &mut buf,
+            &mut pack::cache::Never,
+        )?
+        .tree_id()
+        .expect("a tree is available in a commit");
+
+    let mut recorder = tree::Recorder::default();
+    git_traverse::tree::breadthfirst(
+        db.find_existing_tree_iter(tree_id, &mut buf, &mut pack::cache::Never)?,
+        tree::breadthfirst::State::default(),
+        |oid, buf| db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok(),
+        &mut recorder,
+    )?;
+
+    let mut state = tree::paths::State::default();
+    for _round in 0..2 {
+        let entries = tree::Paths::new(tree_id, &mut state, |oid, buf| {
+            db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(entries, recorder.records, "state can be reused across traversals");
+    }
+    Ok(())
+}
+
+#[test]
+fn paths_fails_if_a_tree_is_missing() -> crate::Result<()> {
+    let missing = hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbee4904");
+    let mut paths = tree::Paths::new(missing, tree::paths::State::default(), |_, _| None);
+    assert!(matches!(
+        paths.next(),
+        Some(Err(tree::paths::Error::NotFound { oid })) if oid == missing
+    ));
+    assert!(paths.next().is_none(), "the iteration stops after an error");
+    Ok(())
+}
+
+#[test]
+fn parallel_unseen_yields_each_entry_once_and_skips_seen_ones() -> crate::Result<()> {
+    let db = db()?;
+    let mut buf = Vec::new();
+    let tree_id = db
+        .find_existing_commit_iter(
+            hex_to_id("85df34aa34848b8138b2b3dcff5fb5c2b734e0ce"),
+            &mut buf,
+            &mut pack::cache::Never,
+        )?
+        .tree_id()
+        .expect("a tree is available in a commit");
+
+    let mut recorder = tree::Recorder::default();
+    git_traverse::tree::breadthfirst(
+        db.find_existing_tree_iter(tree_id, &mut buf, &mut pack::cache::Never)?,
+        tree::breadthfirst::State::default(),
+        |oid, buf| db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok(),
+        &mut recorder,
+    )?;
+    let mut expected: Vec<_> = recorder.records.iter().map(|e| e.oid).collect();
+    expected.sort();
+    expected.dedup();
+
+    let seen = git_features::parallel::ShardedSet::default();
+    let mut unseen = tree::parallel::unseen(
+        Some(tree_id),
+        &seen,
+        None,
+        |_| (),
+        |oid, buf, _| db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok(),
+    )?;
+    unseen.sort();
+    assert_eq!(unseen, expected, "each distinct entry is returned exactly once");
+    assert_eq!(seen.len(), expected.len());
+
+    assert!(
+        tree::parallel::unseen(
+            Some(tree_id),
+            &seen,
+            None,
+            |_| (),
+            |oid, buf, _| { db.find_existing_tree_iter(oid, buf, &mut pack::cache::Never).ok() }
+        )?
+        .is_empty(),
+        "nothing is returned if everything was seen before"
+    );
+    Ok(())
+}
+
+#[test]
+fn parallel_unseen_fails_if_a_tree_is_missing() {
+    let missing = hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbee4904");
+    let seen = git_features::parallel::ShardedSet::default();
+    assert!(matches!(
+        tree::parallel::unseen(Some(missing), &seen, None, |_| (), |_, _, _: &mut ()| None),
+        Err(tree::breadthfirst::Error::NotFound { oid }) if oid == missing
+    ));
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(url) = git_url::parse(data) {
+        url.to_bstring().expect("parsed URLs can always be serialized");
+        let _a = url.to_string();
+    }
+});
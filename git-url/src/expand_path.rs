@@ -38,11 +38,14 @@ quick_error! {
 }
 
 fn path_segments(path: &BStr) -> Option<impl Iterator<Item = &[u8]>> {
-    if path.starts_with(b"/") {
-        Some(path[1..].split(|c| *c == b'/'))
+    let path = if path.starts_with(b"/") {
+        &path[1..]
+    } else if path.starts_with(b"~") {
+        path
     } else {
-        None
-    }
+        return None;
+    };
+    Some(path.split(|c| *c == b'/'))
 }
 
 /// Parse user information from the given `path`, returning `(possible user information, adjusted input path)`.
@@ -50,6 +53,8 @@ fn path_segments(path: &BStr) -> Option<impl Iterator<Item = &[u8]>> {
 /// Supported formats for user extraction are…
 /// * `~/repopath` - the currently logged in user's home.
 /// * `~user/repopath` - the repository in the given user's home.
+///
+/// Both may be prefixed with a `/` as it's the case for paths of URLs with a host.
 pub fn parse(path: &BStr) -> Result<(Option<ForUser>, BString), Error> {
     Ok(path_segments(path)
         .and_then(|mut iter| {
@@ -96,20 +101,75 @@ pub fn for_shell(path: BString) -> BString {
     }
 }
 
-/// Expand `path` for the given `user`, which can be obtained by [`parse()`], resolving them with `home_for_user(&user)`.
+/// A way to obtain the home directory of a user for use in [`with()`].
+///
+/// It's implemented for closures of the form `Fn(&ForUser) -> Option<PathBuf>`.
+pub trait HomeResolver {
+    /// Return the home directory of `user`, or `None` if it is unknown.
+    fn home_dir(&self, user: &ForUser) -> Option<PathBuf>;
+}
+
+impl<F> HomeResolver for F
+where
+    F: Fn(&ForUser) -> Option<PathBuf>,
+{
+    fn home_dir(&self, user: &ForUser) -> Option<PathBuf> {
+        self(user)
+    }
+}
+
+/// Resolve the home directories of users of the local machine, as used by [`expand_path()`].
+///
+/// The home of the current user is obtained from the environment. The homes of other users are looked up in the
+/// `user_database`, and if they are not listed there they are assumed to be next to the home of the current user.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct LocalUsers {
+    /// The path to a user database in the format of `/etc/passwd`, which is the default on unix, or `None` to not use one.
+    pub user_database: Option<PathBuf>,
+}
+
+impl Default for LocalUsers {
+    fn default() -> Self {
+        LocalUsers {
+            user_database: if cfg!(unix) { Some("/etc/passwd".into()) } else { None },
+        }
+    }
+}
+
+impl LocalUsers {
+    fn home_from_user_database(&self, name: &BStr) -> Option<PathBuf> {
+        let database = std::fs::read(self.user_database.as_ref()?).ok()?;
+        database
+            .lines()
+            .filter(|line| !line.starts_with(b"#"))
+            .map(|line| line.split_str(":").collect::<Vec<_>>())
+            .find(|fields| fields.len() >= 6 && fields[0] == name)
+            .and_then(|fields| fields[5].to_path().ok().map(ToOwned::to_owned))
+    }
+}
+
+impl HomeResolver for LocalUsers {
+    fn home_dir(&self, user: &ForUser) -> Option<PathBuf> {
+        match user {
+            ForUser::Current => home::home_dir(),
+            ForUser::Name(name) => self.home_from_user_database(name.as_bstr()).or_else(|| {
+                home::home_dir().and_then(|home| home.parent().map(|home_dirs| home_dirs.join(name.to_string())))
+            }),
+        }
+    }
+}
+
+/// Expand `path` for the given `user`, which can be obtained by [`parse()`], resolving their home directory with `home_for_user`.
 ///
 /// For the common case consider using [`expand_path()]` instead.
-pub fn with(
-    user: Option<&ForUser>,
-    path: &BStr,
-    home_for_user: impl FnOnce(&ForUser) -> Option<PathBuf>,
-) -> Result<PathBuf, Error> {
+pub fn with(user: Option<&ForUser>, path: &BStr, home_for_user: impl HomeResolver) -> Result<PathBuf, Error> {
     fn make_relative(path: &Path) -> PathBuf {
         path.components().skip(1).collect()
     }
     let path = path.to_path()?;
     Ok(match user {
-        Some(user) => home_for_user(user)
+        Some(user) => home_for_user
+            .home_dir(user)
             .ok_or_else(|| Error::MissingHome(user.to_owned().into()))?
             .join(make_relative(path)),
         None => path.into(),
@@ -117,14 +177,9 @@ pub fn with(
 }
 
 /// Expand `path` for the given `user`, which can be obtained by [`parse()`], resolving the home directories
-/// of `user` automatically.
+/// of `user` automatically using [`LocalUsers`].
 ///
 /// If more precise control of the resolution mechanism is needed, then use the [`with()`] function.
 pub fn expand_path(user: Option<&ForUser>, path: &BStr) -> Result<PathBuf, Error> {
-    with(user, path, |user| match user {
-        ForUser::Current => home::home_dir(),
-        ForUser::Name(user) => {
-            home::home_dir().and_then(|home| home.parent().map(|home_dirs| home_dirs.join(user.to_string())))
-        }
-    })
+    with(user, path, LocalUsers::default())
 }
@@ -2,11 +2,8 @@
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
 
-use bstr::ByteSlice;
-use std::{
-    convert::TryFrom,
-    fmt::{self, Write},
-};
+use bstr::{BString, ByteSlice};
+use std::{convert::TryFrom, fmt, io};
 
 ///
 pub mod parse;
@@ -21,33 +18,57 @@ pub use expand_path::expand_path;
 /// A scheme for use in a [`Url`]
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Scheme {
     File,
     Git,
+    /// The git protocol spoken over a TLS connection, as used by `git+tls://` urls.
+    GitTls,
     Ssh,
     Http,
     Https,
     Radicle,
 }
 
-impl fmt::Display for Scheme {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Scheme {
+    /// Return the name of the scheme as it appears in URLs, like `git+tls`.
+    pub fn as_str(&self) -> &'static str {
         use Scheme::*;
-        f.write_str(match self {
+        match self {
             File => "file",
             Git => "git",
+            GitTls => "git+tls",
             Ssh => "ssh",
             Http => "http",
             Https => "https",
             Radicle => "rad",
+        }
+    }
+
+    /// Return the port to connect to if a URL with this scheme doesn't specify one, or `None` if there is no such port
+    /// as no network connection is made or the port is chosen by other means.
+    pub fn default_port(&self) -> Option<u16> {
+        use Scheme::*;
+        Some(match self {
+            Git | GitTls => 9418,
+            Ssh => 22,
+            Http => 80,
+            Https => 443,
+            File | Radicle => return None,
         })
     }
 }
 
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// A URL with support for specialized git related capabilities.
 ///
-/// Additionally there is support for [deserialization][Url::from_bytes()] and serialization
-/// (_see the `Display::fmt()` implementation_).
+/// Additionally there is support for [deserialization][Url::from_bytes()] and [serialization][Url::write_to()].
+/// The `Display` implementation is meant for diagnostics and [redacts passwords][Url::to_redacted()].
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Url {
@@ -55,6 +76,11 @@ pub struct Url {
     pub scheme: Scheme,
     /// The user to impersonate on the remote.
     pub user: Option<String>,
+    /// The password to authenticate the user with, only valid if `user` is set.
+    ///
+    /// It is never serialized to prevent credentials from ending up in machine-readable output.
+    #[cfg_attr(feature = "serde1", serde(skip_serializing, default))]
+    pub password: Option<String>,
     /// The host to which to connect. Localhost is implied if `None`.
     pub host: Option<String>,
     /// The port to use when connecting to a host. If `None`, standard ports depending on `scheme` will be used.
@@ -68,6 +94,7 @@ impl Default for Url {
         Url {
             scheme: Scheme::Ssh,
             user: None,
+            password: None,
             host: None,
             port: None,
             path: bstr::BString::default(),
@@ -75,29 +102,81 @@ impl Default for Url {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Url {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Url {
+            scheme: u.arbitrary()?,
+            user: u.arbitrary()?,
+            password: u.arbitrary()?,
+            host: u.arbitrary()?,
+            port: u.arbitrary()?,
+            path: Vec::<u8>::arbitrary(u)?.into(),
+        })
+    }
+}
+
 impl fmt::Display for Url {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.scheme.fmt(f)?;
-        f.write_str("://")?;
-        match (&self.user, &self.host) {
-            (Some(user), Some(host)) => f.write_fmt(format_args!("{}@{}", user, host)),
-            (None, Some(host)) => f.write_str(host),
-            (None, None) => Ok(()),
-            _ => return Err(fmt::Error),
-        }?;
-        if let Some(port) = &self.port {
-            f.write_char(':')?;
-            f.write_fmt(format_args!("{}", port))?;
-        }
-        f.write_str(self.path.to_str_lossy().as_ref())
+        let mut buf = Vec::new();
+        self.to_redacted().write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(buf.to_str_lossy().as_ref())
     }
 }
 
 impl Url {
+    /// The placeholder used in place of passwords by [`Url::to_redacted()`].
+    pub const REDACTED_PASSWORD: &'static str = "<redacted>";
+
     /// Parse a URL from `bytes`
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, parse::Error> {
         parse(bytes)
     }
+
+    /// Return the port to connect to, which is the `port` set in this URL or the [default port][Scheme::default_port()]
+    /// of its `scheme` otherwise.
+    pub fn port_or_default(&self) -> Option<u16> {
+        self.port.or_else(|| self.scheme.default_port())
+    }
+
+    /// Return a copy of this URL with its password replaced by [`Url::REDACTED_PASSWORD`], making it safe to show
+    /// in logs or diagnostics.
+    pub fn to_redacted(&self) -> Self {
+        Url {
+            password: self.password.as_ref().map(|_| Self::REDACTED_PASSWORD.into()),
+            ..self.clone()
+        }
+    }
+
+    /// Write this URL losslessly to `out`, including its password if set, so that it can be [parsed][Url::from_bytes()]
+    /// again.
+    pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
+        out.write_all(self.scheme.as_str().as_bytes())?;
+        out.write_all(b"://")?;
+        match (&self.user, &self.password, &self.host) {
+            (Some(user), Some(password), Some(host)) => write!(out, "{}:{}@{}", user, password, host),
+            (Some(user), None, Some(host)) => write!(out, "{}@{}", user, host),
+            (None, None, Some(host)) => out.write_all(host.as_bytes()),
+            (None, None, None) => Ok(()),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Invalid URL structure: user or password without host, or password without user",
+                ))
+            }
+        }?;
+        if let Some(port) = &self.port {
+            write!(out, ":{}", port)?;
+        }
+        out.write_all(&self.path)
+    }
+
+    /// Serialize this URL losslessly into a byte string, see [`Url::write_to()`].
+    pub fn to_bstring(&self) -> io::Result<BString> {
+        let mut buf = Vec::with_capacity(self.path.len() + 16);
+        self.write_to(&mut buf)?;
+        Ok(buf.into())
+    }
 }
 
 impl TryFrom<&[u8]> for Url {
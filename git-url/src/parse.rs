@@ -33,6 +33,7 @@ fn str_to_protocol(s: &str) -> Result<Scheme, Error> {
         "ssh" => Scheme::Ssh,
         "file" => Scheme::File,
         "git" => Scheme::Git,
+        "git+tls" => Scheme::GitTls,
         "http" => Scheme::Http,
         "https" => Scheme::Https,
         "rad" => Scheme::Radicle,
@@ -75,11 +76,12 @@ fn possibly_strip_file_protocol(url: &[u8]) -> &[u8] {
 fn to_owned_url(url: url::Url) -> Result<crate::Url, Error> {
     Ok(crate::Url {
         scheme: str_to_protocol(url.scheme())?,
-        user: if url.username().is_empty() {
+        user: if url.username().is_empty() && url.password().is_none() {
             None
         } else {
             Some(url.username().into())
         },
+        password: url.password().map(Into::into),
         host: url.host_str().map(Into::into),
         port: url.port(),
         path: url.path().into(),
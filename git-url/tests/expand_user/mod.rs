@@ -44,3 +44,35 @@ fn with_username() -> crate::Result {
     assert_eq!(resolved_path, expected_path());
     Ok(())
 }
+
+#[test]
+fn without_username_and_leading_slash() -> crate::Result {
+    let (user, resolved_path) = expand_path::parse(b"~/hello/git".as_bstr())?;
+    assert_eq!(user, Some(ForUser::Current));
+    let resolved_path = expand_path::with(user.as_ref(), resolved_path.as_ref(), |_user: &ForUser| {
+        Some(user_home("byron"))
+    })?;
+    assert_eq!(resolved_path, expected_path());
+    Ok(())
+}
+
+#[test]
+fn with_username_from_user_database() -> crate::Result {
+    let local_users = expand_path::LocalUsers {
+        user_database: Some(Path::new("tests").join("fixtures").join("passwd")),
+    };
+    let (user, resolved_path) = expand_path::parse(b"~byron/hello/git".as_bstr())?;
+    assert_eq!(
+        expand_path::with(user.as_ref(), resolved_path.as_ref(), local_users.clone())?,
+        Path::new("/Users/byron/hello/git"),
+        "the home directory is taken from the user database"
+    );
+
+    let (user, resolved_path) = expand_path::parse(b"/~unknown/hello/git".as_bstr())?;
+    let resolved_path = expand_path::with(user.as_ref(), resolved_path.as_ref(), local_users)?;
+    assert!(
+        resolved_path.ends_with("unknown/hello/git"),
+        "users who aren't listed are assumed to live next to the current user"
+    );
+    Ok(())
+}
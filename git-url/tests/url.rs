@@ -3,3 +3,37 @@ pub type Result = std::result::Result<(), Error>;
 
 mod expand_user;
 mod parse;
+
+mod port {
+    use git_url::Scheme;
+
+    #[test]
+    fn defaults_depend_on_the_scheme() {
+        for (scheme, port) in &[
+            (Scheme::Git, Some(9418)),
+            (Scheme::GitTls, Some(9418)),
+            (Scheme::Ssh, Some(22)),
+            (Scheme::Http, Some(80)),
+            (Scheme::Https, Some(443)),
+            (Scheme::File, None),
+            (Scheme::Radicle, None),
+        ] {
+            assert_eq!(scheme.default_port(), *port, "{}", scheme);
+        }
+    }
+
+    #[test]
+    fn explicit_ports_take_precedence() -> crate::Result {
+        assert_eq!(git_url::parse(b"git://example.com/repo")?.port_or_default(), Some(9418));
+        assert_eq!(
+            git_url::parse(b"git://example.com:1234/repo")?.port_or_default(),
+            Some(1234)
+        );
+        assert_eq!(
+            git_url::parse(b"git@github.com:byron/gitoxide")?.port_or_default(),
+            Some(22)
+        );
+        assert_eq!(git_url::parse(b"/path/to/repo")?.port_or_default(), None);
+        Ok(())
+    }
+}
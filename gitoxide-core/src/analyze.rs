@@ -0,0 +1,236 @@
+use crate::OutputFormat;
+use anyhow::{anyhow, Result};
+use git_repository::{
+    hash::ObjectId,
+    interrupt,
+    object::{bstr::BString, bstr::ByteVec, tree::EntryMode},
+    odb,
+    prelude::*,
+    progress::format,
+    Progress,
+};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    io,
+    path::Path,
+};
+
+/// The amount of memory used to cache decoded base objects while iterating packs.
+const PACK_CACHE_SIZE_IN_BYTES: usize = 64 * 1024 * 1024;
+
+pub struct Context<W> {
+    /// The amount of blobs to list
+    pub count: usize,
+    pub format: OutputFormat,
+    /// Where to write the report
+    pub out: W,
+}
+
+/// A blob as listed by [`largest()`].
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Blob {
+    pub id: String,
+    /// The size of the blob when checked out.
+    pub size: u64,
+    /// The size of the blob as stored, which is compressed and possibly a delta for packed blobs.
+    pub size_on_disk: u64,
+    /// A path at which the blob can be found in the tree of a commit reachable from a reference, newer commits first,
+    /// or `None` if it isn't reachable.
+    pub path: Option<String>,
+}
+
+/// Find the `count` largest blobs in all loose objects and packs of the `repository` along with an example path for each
+/// of them, and write them to `out`.
+pub fn largest<W: io::Write>(
+    repository: impl AsRef<Path>,
+    mut progress: impl Progress,
+    Context { count, format, out }: Context<W>,
+) -> Result<()> {
+    let repo = git_repository::discover(repository)?;
+    let mut largest = Largest::new(count);
+    {
+        let start = std::time::Instant::now();
+        let mut progress = progress.add_child("objects");
+        progress.init(None, git_repository::progress::count("objects"));
+        let mut buf = Vec::new();
+        for db in &repo.odb.dbs {
+            for id in interrupt::Iter::new(db.loose.iter(), || anyhow!("Cancelled by user")) {
+                let id = id??;
+                if let Some(obj) = db.loose.find(id, &mut buf)? {
+                    if obj.kind == git_repository::object::Kind::Blob {
+                        let hex = id.to_sha1_hex_string();
+                        let size_on_disk = std::fs::metadata(db.loose.path.join(&hex[..2]).join(&hex[2..]))?.len();
+                        largest.track(id, obj.data.len() as u64, size_on_disk);
+                    }
+                }
+                progress.inc();
+            }
+            for bundle_index in 0..db.bundles.len() {
+                let bundle = db.bundle(bundle_index)?;
+                let cache = odb::pack::cache::lru::MemoryCappedHashmap::new(PACK_CACHE_SIZE_IN_BYTES);
+                for entry in interrupt::Iter::new(bundle.iter_entries_with_data(cache), || anyhow!("Cancelled by user"))
+                {
+                    let entry = entry??;
+                    if entry.decode.kind == git_repository::object::Kind::Blob {
+                        largest.track(
+                            entry.index_entry.oid,
+                            entry.data.len() as u64,
+                            entry.location.entry_size as u64,
+                        );
+                    }
+                    progress.inc();
+                }
+            }
+        }
+        progress.show_throughput(start);
+    }
+
+    let mut blobs = largest.into_sorted_vec();
+    {
+        let mut progress = progress.add_child("paths");
+        progress.init(None, git_repository::progress::count("trees"));
+        let mut paths = find_paths(&repo, blobs.iter().map(|(_, id, _)| *id).collect(), &mut progress)?;
+        let blobs = blobs
+            .drain(..)
+            .map(|(size, id, size_on_disk)| Blob {
+                id: id.to_string(),
+                size,
+                size_on_disk,
+                path: paths.remove(&id).map(|p| p.to_string()),
+            })
+            .collect::<Vec<_>>();
+        match format {
+            OutputFormat::Human => drop(print(out, &blobs)),
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => serde_json::to_writer_pretty(out, &blobs)?,
+            #[cfg(feature = "serde1")]
+            OutputFormat::JsonLines => crate::write_json_line(out, &blobs)?,
+        }
+    }
+    Ok(())
+}
+
+/// Keeps the largest blobs seen so far, ignoring duplicates of blobs stored in multiple places.
+struct Largest {
+    count: usize,
+    heap: BinaryHeap<Reverse<(u64, ObjectId, u64)>>,
+    ids: HashSet<ObjectId>,
+}
+
+impl Largest {
+    fn new(count: usize) -> Self {
+        Largest {
+            count,
+            heap: BinaryHeap::with_capacity(count + 1),
+            ids: HashSet::with_capacity(count + 1),
+        }
+    }
+
+    fn track(&mut self, id: ObjectId, size: u64, size_on_disk: u64) {
+        if self.ids.contains(&id) {
+            return;
+        }
+        self.heap.push(Reverse((size, id, size_on_disk)));
+        self.ids.insert(id);
+        if self.heap.len() > self.count {
+            if let Some(Reverse((_, id, _))) = self.heap.pop() {
+                self.ids.remove(&id);
+            }
+        }
+    }
+
+    /// Return (size, id, size_on_disk) tuples, largest first.
+    fn into_sorted_vec(self) -> Vec<(u64, ObjectId, u64)> {
+        self.heap.into_sorted_vec().into_iter().map(|Reverse(t)| t).collect()
+    }
+}
+
+/// Find a path for each of the `blobs` by traversing the trees of all commits reachable from references, newest first,
+/// visiting each tree only once and stopping early once all paths were found.
+fn find_paths(
+    repo: &git_repository::Repository,
+    mut blobs: HashSet<ObjectId>,
+    progress: &mut impl Progress,
+) -> Result<HashMap<ObjectId, BString>> {
+    let mut paths = HashMap::new();
+    if blobs.is_empty() {
+        return Ok(paths);
+    }
+    let mut pack_cache = odb::pack::cache::Never;
+    let mut buf = Vec::new();
+    let mut tips = Vec::new();
+    for name in crate::reflog::all_refs(repo)? {
+        if let Some(mut reference) = repo.refs.find_one(name.as_str())? {
+            let id = reference.peel_to_id_in_place()?.to_owned();
+            if repo
+                .odb
+                .find_existing_commit_iter(id, &mut buf, &mut pack_cache)
+                .is_ok()
+            {
+                tips.push(id);
+            }
+        }
+    }
+
+    let mut seen_trees = HashSet::new();
+    let mut trees = VecDeque::new();
+    let commits = git_repository::traverse::commit::Ancestors::new(
+        tips,
+        git_repository::traverse::commit::ancestors::State::default(),
+        |oid, buf| {
+            repo.odb
+                .find_existing_commit_iter(oid, buf, &mut odb::pack::cache::Never)
+                .ok()
+        },
+    );
+    for commit in interrupt::Iter::new(commits, || anyhow!("Cancelled by user")) {
+        let commit = commit??;
+        let tree = repo
+            .odb
+            .find_existing_commit_iter(commit, &mut buf, &mut pack_cache)?
+            .tree_id()
+            .ok_or_else(|| anyhow!("Commit {} has no tree", commit))?;
+        trees.push_back((tree, BString::default()));
+        while let Some((tree, tree_path)) = trees.pop_front() {
+            if !seen_trees.insert(tree) {
+                continue;
+            }
+            progress.inc();
+            for entry in repo.odb.find_existing_tree_iter(tree, &mut buf, &mut pack_cache)? {
+                let entry = entry?;
+                let mut path = tree_path.clone();
+                if !path.is_empty() {
+                    path.push(b'/');
+                }
+                path.push_str(entry.filename);
+                match entry.mode {
+                    EntryMode::Tree => trees.push_back((entry.oid.to_owned(), path)),
+                    _ => {
+                        if blobs.remove(entry.oid) {
+                            paths.insert(entry.oid.to_owned(), path);
+                        }
+                    }
+                }
+            }
+            if blobs.is_empty() {
+                return Ok(paths);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+fn print(mut out: impl io::Write, blobs: &[Blob]) -> io::Result<()> {
+    for blob in blobs {
+        writeln!(
+            out,
+            "{} {} ({} on disk) {}",
+            blob.id,
+            format::bytes(blob.size),
+            format::bytes(blob.size_on_disk),
+            blob.path.as_deref().unwrap_or("<unreachable>")
+        )?;
+    }
+    Ok(())
+}
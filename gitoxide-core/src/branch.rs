@@ -0,0 +1,352 @@
+use crate::OutputFormat;
+use anyhow::{anyhow, bail, Result};
+use git_config::file::GitConfig;
+use git_repository::{
+    hash::ObjectId,
+    interrupt,
+    object::{bstr::ByteSlice, immutable::TagIter, Kind},
+    odb::pack,
+    prelude::*,
+    refs::{
+        mutable::{FullName, Target},
+        transaction::{Change, Create, LogChange, RefEdit, RefLog},
+    },
+    Repository,
+};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    convert::{TryFrom, TryInto},
+    io,
+    path::Path,
+};
+
+const BRANCH_PREFIX: &str = "refs/heads/";
+
+pub struct Context<W> {
+    /// The way to output the branches when listing them.
+    pub format: OutputFormat,
+    /// Where to write information about the branches.
+    pub out: W,
+}
+
+/// A local branch as listed by [`list()`].
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    /// The short name of the branch, like `main`.
+    pub name: String,
+    /// The commit the branch points to, hex encoded.
+    pub target: String,
+    /// True if `HEAD` points to this branch.
+    pub is_head: bool,
+    /// The short name of the remote-tracking branch or local branch it tracks, like `origin/main`, as configured
+    /// with `branch.<name>.remote` and `branch.<name>.merge`.
+    pub upstream: Option<String>,
+}
+
+/// List all local branches of the repository at `repository`, loose and packed, sorted by name.
+pub fn list(repository: impl AsRef<Path>, Context { format, mut out }: Context<impl io::Write>) -> Result<()> {
+    let repo = git_repository::discover(repository)?;
+    let config = read_config(&repo);
+    let config = parse_config(&config)?;
+    let head = head_branch(&repo)?;
+
+    let mut tips = BTreeMap::new();
+    if let Some(packed) = repo.refs.packed()? {
+        for reference in packed.iter()? {
+            let reference = reference?;
+            if let Some(name) = reference.full_name.to_str()?.strip_prefix(BRANCH_PREFIX) {
+                tips.insert(name.to_owned(), reference.target());
+            }
+        }
+    }
+    let mut loose_names = Vec::new();
+    for reference in repo.refs.loose_iter_prefixed("refs/heads")? {
+        loose_names.push(reference?.name().borrow().as_bstr().to_str()?.to_owned());
+    }
+    for (full_name, target) in loose_names
+        .iter()
+        .zip(peel_many(&repo, loose_names.iter().map(String::as_str))?)
+    {
+        if let Some(target) = target {
+            tips.insert(full_name[BRANCH_PREFIX.len()..].to_owned(), target);
+        }
+    }
+
+    let branches: Vec<_> = tips
+        .into_iter()
+        .map(|(name, target)| Branch {
+            is_head: head.as_deref() == Some(name.as_str()),
+            upstream: upstream(&config, &name).map(|upstream| shorten(&upstream).to_owned()),
+            target: target.to_string(),
+            name,
+        })
+        .collect();
+    match format {
+        OutputFormat::Human => {
+            for branch in &branches {
+                write!(
+                    out,
+                    "{} {} {}",
+                    if branch.is_head { "*" } else { " " },
+                    branch.name,
+                    &branch.target[..7]
+                )?;
+                if let Some(upstream) = &branch.upstream {
+                    write!(out, " [{}]", upstream)?;
+                }
+                writeln!(out)?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &branches)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => {
+            for branch in &branches {
+                crate::write_json_line(&mut out, branch)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Create the branch `name` pointing to the commit `start_point` resolves to, or the one of `HEAD` if unset, in the
+/// repository at `repository`.
+///
+/// `start_point` is a reference name like `main` or `v1.0`, whose annotated tags are peeled, or a full hexadecimal object id.
+/// Existing branches are only overwritten if `force` is set, but never the one `HEAD` points to.
+pub fn create(
+    repository: impl AsRef<Path>,
+    name: &str,
+    start_point: Option<&str>,
+    force: bool,
+    mut out: impl io::Write,
+) -> Result<()> {
+    let repo = git_repository::discover(repository)?;
+    let full_name: FullName = format!("{}{}", BRANCH_PREFIX, name)
+        .as_str()
+        .try_into()
+        .map_err(|err| anyhow!("'{}' is not a valid branch name: {}", name, err))?;
+    if force && head_branch(&repo)?.as_deref() == Some(name) {
+        bail!("Cannot force update the branch '{}' as HEAD points to it", name);
+    }
+    if !force && peel(&repo, full_name.borrow().as_bstr().to_str()?)?.is_some() {
+        bail!("A branch named '{}' already exists, use --force to overwrite it", name);
+    }
+    let start_point = start_point.unwrap_or("HEAD");
+    let target = resolve_commit(&repo, start_point)?;
+
+    repo.refs
+        .transaction(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("branch: Created from {}", start_point).into(),
+                    },
+                    mode: if force {
+                        Create::OrUpdate { previous: None }
+                    } else {
+                        Create::Only
+                    },
+                    new: Target::Peeled(target),
+                },
+                name: full_name,
+                deref: false,
+            }),
+            git_lock::acquire::Fail::Immediately,
+        )
+        .commit(&crate::repository::committer(&repo)?)
+        .map_err(|err| anyhow!("Could not create branch '{}': {}", name, err))?;
+    writeln!(out, "Created branch {} at {}", name, target)?;
+    Ok(())
+}
+
+/// Delete the branches `names` in the repository at `repository`.
+///
+/// Unless `force` is set, each branch must be merged into its upstream, or into `HEAD` if it has none.
+/// The branch `HEAD` points to is never deleted.
+pub fn delete(repository: impl AsRef<Path>, names: Vec<String>, force: bool, mut out: impl io::Write) -> Result<()> {
+    let repo = git_repository::discover(repository)?;
+    let config = read_config(&repo);
+    let config = parse_config(&config)?;
+    let head = head_branch(&repo)?;
+    for name in names {
+        if head.as_deref() == Some(name.as_str()) {
+            bail!("Cannot delete the branch '{}' as HEAD points to it", name);
+        }
+        let full_name = format!("{}{}", BRANCH_PREFIX, name);
+        // Deleting only the loose reference would bring back the packed one.
+        let is_packed = match repo.refs.packed()? {
+            Some(packed) => packed.find(full_name.as_str())?.is_some(),
+            None => false,
+        };
+        if is_packed {
+            bail!(
+                "Cannot delete the packed branch '{}' as packed references can't be edited yet",
+                name
+            )
+        }
+        let tip = match repo.refs.find_one(full_name.as_str())? {
+            Some(_) => peel(&repo, &full_name)?.ok_or_else(|| anyhow!("Branch '{}' points to nothing", name))?,
+            None => bail!("Branch '{}' not found", name),
+        };
+        if !force {
+            let (merged_into, base) = match upstream(&config, &name)
+                .and_then(|upstream| resolve_commit(&repo, &upstream).ok().map(|id| (upstream, id)))
+            {
+                Some(upstream) => upstream,
+                None => ("HEAD".into(), resolve_commit(&repo, "HEAD")?),
+            };
+            if !is_ancestor(&repo, tip, base)? {
+                bail!(
+                    "The branch '{}' is not fully merged into '{}', use --force to delete it anyway",
+                    name,
+                    shorten(&merged_into)
+                );
+            }
+        }
+        repo.refs
+            .transaction(
+                Some(RefEdit {
+                    change: Change::Delete {
+                        previous: Some(Target::Peeled(tip)),
+                        log: RefLog::AndReference,
+                    },
+                    name: full_name.as_str().try_into()?,
+                    deref: false,
+                }),
+                git_lock::acquire::Fail::Immediately,
+            )
+            .commit(&crate::repository::committer(&repo)?)
+            .map_err(|err| anyhow!("Could not delete branch '{}': {}", name, err))?;
+        writeln!(out, "Deleted branch {} (was {})", name, &tip.to_string()[..7])?;
+    }
+    Ok(())
+}
+
+/// Parse the repository configuration in `config`, as read by [`read_config()`].
+fn parse_config(config: &[u8]) -> Result<GitConfig<'_>> {
+    GitConfig::try_from(config).map_err(|err| anyhow!("Invalid repository configuration: {}", err))
+}
+
+fn read_config(repo: &Repository) -> Vec<u8> {
+    std::fs::read(repo.common_dir().join("config")).unwrap_or_default()
+}
+
+/// Return the short name of the branch `HEAD` points to, if any.
+fn head_branch(repo: &Repository) -> Result<Option<String>> {
+    Ok(repo.refs.find_one("HEAD")?.and_then(|head| {
+        head.target()
+            .as_name()
+            .and_then(|name| name.to_str().ok())
+            .and_then(|name| name.strip_prefix(BRANCH_PREFIX))
+            .map(ToOwned::to_owned)
+    }))
+}
+
+/// Return the full name of the reference the branch `name` tracks, assuming the default refspec for remotes.
+fn upstream(config: &GitConfig<'_>, name: &str) -> Option<String> {
+    let value = |key: &str| {
+        config
+            .value::<Cow<'_, [u8]>>("branch", Some(name), key)
+            .ok()
+            .and_then(|value| value.to_str().ok().map(ToOwned::to_owned))
+    };
+    let remote = value("remote")?;
+    let merge = value("merge")?;
+    if remote == "." {
+        return Some(merge);
+    }
+    merge
+        .strip_prefix(BRANCH_PREFIX)
+        .map(|branch| format!("refs/remotes/{}/{}", remote, branch))
+}
+
+/// Follow the reference `spec` to the object it points to, looking up loose and packed references alike, or return `None`
+/// if it or one of the references it points to doesn't exist.
+pub(crate) fn peel(repo: &Repository, spec: &str) -> Result<Option<ObjectId>> {
+    Ok(peel_many(repo, Some(spec))?.pop().expect("one result per spec"))
+}
+
+/// Like [`peel()`], but for all `specs` at once, reading `packed-refs` only once for each level of indirection.
+pub(crate) fn peel_many<'a>(
+    repo: &Repository,
+    specs: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<Option<ObjectId>>> {
+    const MAX_REF_DEPTH: usize = 5;
+    let specs: Vec<_> = specs.into_iter().collect();
+    let mut peeled = vec![None; specs.len()];
+    let mut pending: Vec<_> = specs.iter().map(|spec| spec.to_string()).enumerate().collect();
+    for _ in 0..MAX_REF_DEPTH {
+        if pending.is_empty() {
+            return Ok(peeled);
+        }
+        let found = repo.refs.find_many(pending.iter().map(|(_, name)| name.as_str()))?;
+        let mut next_pending = Vec::new();
+        for ((index, _), reference) in pending.into_iter().zip(found) {
+            if let Some(reference) = reference? {
+                match reference.target().to_owned() {
+                    Target::Peeled(id) => peeled[index] = Some(id),
+                    Target::Symbolic(next) => next_pending.push((index, next.borrow().as_bstr().to_str()?.to_owned())),
+                }
+            }
+        }
+        pending = next_pending;
+    }
+    match pending.first() {
+        Some((index, _)) => bail!(
+            "Refusing to follow more than {} levels of indirection starting at '{}'",
+            MAX_REF_DEPTH,
+            specs[*index]
+        ),
+        None => Ok(peeled),
+    }
+}
+
+/// Strip the well-known prefixes off the full reference `name` for display.
+fn shorten(name: &str) -> &str {
+    name.strip_prefix(BRANCH_PREFIX)
+        .or_else(|| name.strip_prefix("refs/remotes/"))
+        .unwrap_or(name)
+}
+
+/// Resolve `spec`, a reference name or full hexadecimal object id, to the commit it points to, peeling annotated tags.
+fn resolve_commit(repo: &Repository, spec: &str) -> Result<ObjectId> {
+    let mut id = match ObjectId::from_hex(spec.as_bytes()) {
+        Ok(id) => id,
+        Err(_) => peel(repo, spec)?.ok_or_else(|| anyhow!("'{}' is neither a reference nor an object id", spec))?,
+    };
+    let mut buf = Vec::new();
+    loop {
+        let object = repo
+            .odb
+            .find_existing(id, &mut buf, &mut pack::cache::Never)
+            .map_err(|_| anyhow!("Object {} pointed to by '{}' does not exist", id, spec))?;
+        match object.kind {
+            Kind::Tag => {
+                id = TagIter::from_bytes(object.data)
+                    .target_id()
+                    .ok_or_else(|| anyhow!("Tag {} has no target", id))?;
+            }
+            Kind::Commit => return Ok(id),
+            kind => bail!("'{}' points to a {}, not a commit", spec, kind),
+        }
+    }
+}
+
+/// Return true if `ancestor` is reachable from `tip`.
+fn is_ancestor(repo: &Repository, ancestor: ObjectId, tip: ObjectId) -> Result<bool> {
+    let mut pack_cache = pack::cache::Never;
+    for id in interrupt::Iter::new(
+        tip.ancestors_iter(|oid, buf| repo.odb.find_existing_commit_iter(oid, buf, &mut pack_cache).ok()),
+        || anyhow!("Cancelled by user"),
+    ) {
+        if id?? == ancestor {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -49,6 +49,8 @@ where
         Some(OutputFormat::Human) => drop(print_human_output(&mut out, &stats)),
         #[cfg(feature = "serde1")]
         Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, &stats)?,
+        #[cfg(feature = "serde1")]
+        Some(OutputFormat::JsonLines) => crate::write_json_line(out, &stats)?,
         _ => {}
     }
 
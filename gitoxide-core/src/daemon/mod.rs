@@ -0,0 +1,173 @@
+//! A minimal, read-only server for the `git://` protocol, serving `upload-pack` for repositories in an export directory.
+use anyhow::{anyhow, bail, Result};
+use git_repository::{
+    interrupt,
+    object::bstr::{BStr, ByteSlice},
+    protocol::transport::{
+        packetline::{self, PacketLine},
+        server::daemon,
+        Service,
+    },
+    Progress, Repository,
+};
+use std::{
+    io,
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+mod upload_pack;
+
+/// The address to listen on by default, which is the git protocol port on the local host.
+pub const DEFAULT_ADDRESS: &str = "localhost:9418";
+/// The file which needs to be present in a repository for it to be served, unless all repositories are exported.
+pub const EXPORT_OK_FILE: &str = "git-daemon-export-ok";
+
+/// The function to serve a particular service on a connection.
+type Handler<P> = fn(&daemon::Request, Repository, TcpStream, Option<usize>, &mut P) -> Result<()>;
+
+/// The configuration of [`serve()`].
+pub struct Context<W> {
+    /// The directory containing all repositories to serve. Paths requested by clients are relative to it.
+    pub export_path: PathBuf,
+    /// If set, serve all repositories in the `export_path`, instead of only the ones containing the [`EXPORT_OK_FILE`].
+    pub export_all: bool,
+    /// If set, don't use more than this amount of threads to create a pack for a single client.
+    pub thread_limit: Option<usize>,
+    /// Where to write the address we are listening on.
+    pub out: W,
+}
+
+/// Listen on `address` and serve `upload-pack` to all clients connecting to it, each on its own thread, until interrupted.
+///
+/// Each connection is logged using its own child of `progress`.
+pub fn serve<P, W>(
+    address: impl ToSocketAddrs,
+    mut progress: P,
+    Context {
+        export_path,
+        export_all,
+        thread_limit,
+        mut out,
+    }: Context<W>,
+) -> Result<()>
+where
+    P: Progress,
+    P::SubProgress: Send + 'static,
+    W: io::Write,
+{
+    let export_path = export_path
+        .canonicalize()
+        .map_err(|err| anyhow!("Could not access export path '{}': {}", export_path.display(), err))?;
+    let router =
+        daemon::Router::default().with_handler(Service::UploadPack, upload_pack::serve as Handler<P::SubProgress>);
+
+    let listener = TcpListener::bind(address)?;
+    writeln!(out, "Listening on {}", listener.local_addr()?)?;
+    out.flush()?;
+    // Accept connections without blocking to be able to react to interrupts.
+    listener.set_nonblocking(true)?;
+    while !interrupt::is_triggered() {
+        let (stream, peer) = match listener.accept() {
+            Ok(connection) => connection,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let mut progress = progress.add_child(peer.to_string());
+        let mut router = router.clone();
+        let export_path = export_path.clone();
+        std::thread::spawn(move || {
+            if let Err(err) = handle_connection(
+                stream,
+                &mut router,
+                &export_path,
+                export_all,
+                thread_limit,
+                &mut progress,
+            ) {
+                progress.fail(format!("{:#}", err));
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection<P: Progress>(
+    stream: TcpStream,
+    router: &mut daemon::Router<Handler<P>>,
+    export_path: &Path,
+    export_all: bool,
+    thread_limit: Option<usize>,
+    progress: &mut P,
+) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    let request = {
+        let mut lines = packetline::StreamingPeekableIter::new(&stream, &[PacketLine::Flush]);
+        let line = match lines.read_line() {
+            Some(line) => line??
+                .as_slice()
+                .ok_or_else(|| anyhow!("Expected the request as first line"))?
+                .to_owned(),
+            None => bail!("The connection was closed before a request was received"),
+        };
+        daemon::Request::from_bytes(&line)?
+    };
+    progress.info(format!("{} {}", request.service.as_str(), request.path));
+
+    let handler_and_repository = router
+        .route(&request)
+        .copied()
+        .ok_or_else(|| anyhow!("The '{}' service is not enabled", request.service.as_str()))
+        .and_then(|handler| {
+            let git_dir = find_repository(export_path, request.path.as_ref(), export_all)?;
+            Ok((handler, Repository::discover(git_dir)?))
+        });
+    match handler_and_repository {
+        Ok((handler, repository)) => handler(&request, repository, stream, thread_limit, progress),
+        Err(err) => {
+            packetline::encode::error_to_write(err.to_string().as_bytes(), &stream)?;
+            Err(err)
+        }
+    }
+}
+
+/// Find the git directory of the repository at `path` as requested by a client, relative to `export_path`, if it is exported.
+///
+/// Like `git daemon`, the `.git` suffix and the `.git` directory of non-bare repositories may be omitted. To not reveal anything
+/// about the directory structure, repositories which don't exist or aren't exported produce the same error.
+fn find_repository(export_path: &Path, path: &BStr, export_all: bool) -> Result<PathBuf> {
+    let denied = || anyhow!("access denied or repository not exported: {}", path);
+    let relative_path = Path::new(path.to_str().map_err(|_| denied())?.trim_start_matches('/'));
+    if relative_path
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+        || path.starts_with(b"~")
+    {
+        return Err(denied());
+    }
+
+    let path = export_path.join(relative_path);
+    let mut with_git_suffix = path.clone().into_os_string();
+    with_git_suffix.push(".git");
+    let with_git_suffix = PathBuf::from(with_git_suffix);
+    let git_dir = [
+        path.clone(),
+        with_git_suffix.clone(),
+        path.join(".git"),
+        with_git_suffix.join(".git"),
+    ]
+    .iter()
+    .find(|dir| git_repository::path::is_git(dir).is_ok())
+    .ok_or_else(denied)?
+    .canonicalize()?;
+
+    // Symlinks may point anywhere, but only repositories in the export path are served.
+    if !git_dir.starts_with(export_path) || !(export_all || git_dir.join(EXPORT_OK_FILE).is_file()) {
+        return Err(denied());
+    }
+    Ok(git_dir)
+}
@@ -0,0 +1,651 @@
+//! Serve `upload-pack` in protocol V1 and V2, sending packs created from the objects reachable from the wanted commits.
+//!
+//! Negotiation is kept simple: in V1 only `multi_ack_detailed` is supported besides the basic negotiation, and the server
+//! never signals that it is ready. In V2 it is ready to send a pack right after acknowledging the first batch of haves,
+//! which may lead to larger packs than necessary.
+use anyhow::{anyhow, bail, Result};
+use git_repository::{
+    hash::ObjectId,
+    interrupt,
+    object::{
+        bstr::{BString, ByteSlice, ByteVec},
+        immutable::TagIter,
+        Kind,
+    },
+    odb::{linked, pack},
+    prelude::{Finalize, FindExt},
+    protocol::{
+        fetch::agent,
+        transport::{
+            packetline::{self, PacketLine},
+            server::daemon,
+            Protocol,
+        },
+    },
+    refs, traverse, Progress, Repository,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{self, BufWriter, Write},
+    net::TcpStream,
+    sync::Arc,
+};
+
+/// The largest amount of pack data fitting into a packet line on the data band with `side-band-64k`, and always in V2.
+const LARGE_SIDEBAND_DATA_LEN: usize = 65515;
+/// The largest amount of pack data fitting into a packet line on the data band with `side-band`.
+const SIDEBAND_DATA_LEN: usize = 995;
+
+/// A reference as advertised to clients.
+struct Ref {
+    name: BString,
+    id: ObjectId,
+    /// The object the reference points to after peeling all annotated tags, or `None` if it doesn't point to a tag.
+    peeled: Option<ObjectId>,
+    /// The name of the reference a symbolic reference points to.
+    symref_target: Option<BString>,
+}
+
+pub(crate) fn serve<P: Progress>(
+    request: &daemon::Request,
+    repository: Repository,
+    stream: TcpStream,
+    thread_limit: Option<usize>,
+    progress: &mut P,
+) -> Result<()> {
+    let refs = list_refs(&repository)?;
+    let db = Arc::new(repository.odb);
+    match request.desired_version {
+        Protocol::V1 => serve_v1(&db, &refs, &stream, thread_limit, progress),
+        Protocol::V2 => serve_v2(&db, &refs, &stream, thread_limit, progress),
+    }
+}
+
+fn serve_v1(
+    db: &Arc<linked::Store>,
+    refs: &[Ref],
+    stream: &TcpStream,
+    thread_limit: Option<usize>,
+    progress: &mut impl Progress,
+) -> Result<()> {
+    let mut out = BufWriter::new(stream);
+    write_v1_advertisement(refs, &mut out)?;
+    out.flush()?;
+
+    let mut lines = packetline::StreamingPeekableIter::new(stream, &[PacketLine::Flush]);
+    let mut wants = Vec::new();
+    let mut capabilities = Vec::new();
+    while let Some(line) = read_line(&mut lines)? {
+        let mut tokens = line.split_str(" ");
+        let want = match (tokens.next(), tokens.next()) {
+            (Some(b"want"), Some(id)) => parse_id(id)?,
+            _ => bail!("Expected a 'want' line, got '{}'", line),
+        };
+        if wants.is_empty() {
+            capabilities = tokens.map(|c| c.as_bstr().to_owned()).collect();
+        }
+        wants.push(want);
+    }
+    if wants.is_empty() {
+        // The client only wanted to list references.
+        return Ok(());
+    }
+    verify_wants(refs, &wants, &mut out)?;
+
+    let has = |name: &str| capabilities.iter().any(|c| c == name);
+    let multi_ack_detailed = has("multi_ack_detailed");
+    let mut common = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        lines.reset();
+        while let Some(line) = read_line(&mut lines)? {
+            if let Some(id) = line.strip_prefix(b"have ") {
+                let id = parse_id(id)?;
+                if !common.contains(&id) && is_commit(db, id, &mut buf) {
+                    common.push(id);
+                    if multi_ack_detailed {
+                        packetline::encode::text_to_write(format!("ACK {} common", id).as_bytes(), &mut out)?;
+                    } else if common.len() == 1 {
+                        packetline::encode::text_to_write(format!("ACK {}", id).as_bytes(), &mut out)?;
+                    }
+                }
+            } else if line == "done" {
+                match common.last() {
+                    Some(last) if multi_ack_detailed => {
+                        packetline::encode::text_to_write(format!("ACK {}", last).as_bytes(), &mut out)?;
+                    }
+                    Some(_) => {}
+                    None => {
+                        packetline::encode::text_to_write(b"NAK", &mut out)?;
+                    }
+                }
+                let max_data_len = if has("side-band-64k") {
+                    Some(LARGE_SIDEBAND_DATA_LEN)
+                } else if has("side-band") {
+                    Some(SIDEBAND_DATA_LEN)
+                } else {
+                    None
+                };
+                let options = PackOptions {
+                    allow_ofs_delta: has("ofs-delta"),
+                    include_tag: has("include-tag"),
+                    thread_limit,
+                };
+                return send_pack(db, refs, &wants, &common, options, max_data_len, out, progress);
+            } else {
+                bail!("Expected a 'have' line or 'done', got '{}'", line);
+            }
+        }
+        match lines.stopped_at() {
+            Some(PacketLine::Flush) => {
+                if common.is_empty() || multi_ack_detailed {
+                    packetline::encode::text_to_write(b"NAK", &mut out)?;
+                }
+                out.flush()?;
+            }
+            // The client hung up as it didn't need a pack after all.
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn write_v1_advertisement(refs: &[Ref], mut out: impl Write) -> io::Result<()> {
+    let mut capabilities = BString::from(
+        "multi_ack_detailed side-band side-band-64k ofs-delta no-progress include-tag object-format=sha1",
+    );
+    if let Some(target) = refs
+        .iter()
+        .find(|r| r.name == "HEAD")
+        .and_then(|head| head.symref_target.as_ref())
+    {
+        capabilities.push_str(" symref=HEAD:");
+        capabilities.push_str(target);
+    }
+    let (name, value) = agent();
+    capabilities.push_str(format!(" {}={}", name, value.expect("agent has a value")));
+
+    let write_first_line = |id: &ObjectId, name: &[u8], out: &mut dyn Write| {
+        let mut line = BString::from(format!("{} ", id));
+        line.push_str(name);
+        line.push(0);
+        line.push_str(&capabilities);
+        line.push(b'\n');
+        packetline::encode::data_to_write(&line, out)
+    };
+    match refs.split_first() {
+        Some((first, rest)) => {
+            write_first_line(&first.id, &first.name, &mut out)?;
+            write_peeled_v1(first, &mut out)?;
+            for r in rest {
+                let mut line = BString::from(format!("{} ", r.id));
+                line.push_str(&r.name);
+                packetline::encode::text_to_write(&line, &mut out)?;
+                write_peeled_v1(r, &mut out)?;
+            }
+        }
+        None => {
+            write_first_line(&ObjectId::null_sha1(), b"capabilities^{}", &mut out)?;
+        }
+    }
+    packetline::encode::flush_to_write(out)?;
+    Ok(())
+}
+
+fn write_peeled_v1(r: &Ref, out: impl Write) -> io::Result<()> {
+    if let Some(peeled) = r.peeled {
+        let mut line = BString::from(format!("{} ", peeled));
+        line.push_str(&r.name);
+        line.push_str("^{}");
+        packetline::encode::text_to_write(&line, out)?;
+    }
+    Ok(())
+}
+
+fn serve_v2(
+    db: &Arc<linked::Store>,
+    refs: &[Ref],
+    stream: &TcpStream,
+    thread_limit: Option<usize>,
+    progress: &mut impl Progress,
+) -> Result<()> {
+    let mut out = BufWriter::new(stream);
+    let (name, value) = agent();
+    for line in &[
+        "version 2".into(),
+        format!("{}={}", name, value.expect("agent has a value")),
+        "ls-refs".into(),
+        "fetch".into(),
+        "object-format=sha1".into(),
+    ] {
+        packetline::encode::text_to_write(line.as_bytes(), &mut out)?;
+    }
+    packetline::encode::flush_to_write(&mut out)?;
+    out.flush()?;
+
+    let mut lines = packetline::StreamingPeekableIter::new(stream, &[PacketLine::Flush, PacketLine::Delimiter]);
+    loop {
+        lines.reset();
+        let mut command = None;
+        while let Some(line) = read_line(&mut lines)? {
+            if let Some(name) = line.strip_prefix(b"command=") {
+                command = Some(name.as_bstr().to_owned());
+            }
+        }
+        let mut arguments = Vec::new();
+        match lines.stopped_at() {
+            Some(PacketLine::Delimiter) => {
+                lines.reset();
+                while let Some(line) = read_line(&mut lines)? {
+                    arguments.push(line);
+                }
+            }
+            Some(_) => {}
+            // The client is done and hung up.
+            None => return Ok(()),
+        }
+
+        match command.as_ref().map(|c| c.as_slice()) {
+            Some(b"ls-refs") => ls_refs(refs, &arguments, &mut out)?,
+            Some(b"fetch") => {
+                fetch(db, refs, &arguments, thread_limit, &mut out, progress)?;
+                // Stateless clients hang up after receiving a pack, but may also reuse the connection.
+                continue;
+            }
+            Some(command) => {
+                let err = anyhow!("Unknown command '{}'", command.as_bstr());
+                packetline::encode::error_to_write(err.to_string().as_bytes(), &mut out)?;
+                out.flush()?;
+                return Err(err);
+            }
+            None => return Ok(()),
+        }
+        out.flush()?;
+    }
+}
+
+fn ls_refs(refs: &[Ref], arguments: &[BString], mut out: impl Write) -> Result<()> {
+    let symrefs = arguments.iter().any(|a| a == "symrefs");
+    let peel = arguments.iter().any(|a| a == "peel");
+    let prefixes: Vec<_> = arguments
+        .iter()
+        .filter_map(|a| a.strip_prefix(b"ref-prefix "))
+        .collect();
+    for r in refs
+        .iter()
+        .filter(|r| prefixes.is_empty() || prefixes.iter().any(|p| r.name.starts_with(p)))
+    {
+        let mut line = BString::from(format!("{} ", r.id));
+        line.push_str(&r.name);
+        if let (true, Some(target)) = (symrefs, r.symref_target.as_ref()) {
+            line.push_str(" symref-target:");
+            line.push_str(target);
+        }
+        if let (true, Some(peeled)) = (peel, r.peeled) {
+            line.push_str(format!(" peeled:{}", peeled));
+        }
+        packetline::encode::text_to_write(&line, &mut out)?;
+    }
+    packetline::encode::flush_to_write(out)?;
+    Ok(())
+}
+
+fn fetch<W: Write>(
+    db: &Arc<linked::Store>,
+    refs: &[Ref],
+    arguments: &[BString],
+    thread_limit: Option<usize>,
+    out: &mut W,
+    progress: &mut impl Progress,
+) -> Result<()> {
+    let mut wants = Vec::new();
+    let mut haves = Vec::new();
+    let mut options = PackOptions {
+        allow_ofs_delta: false,
+        include_tag: false,
+        thread_limit,
+    };
+    let mut done = false;
+    for argument in arguments {
+        if let Some(id) = argument.strip_prefix(b"want ") {
+            wants.push(parse_id(id)?);
+        } else if let Some(id) = argument.strip_prefix(b"have ") {
+            haves.push(parse_id(id)?);
+        } else {
+            match argument.as_slice() {
+                b"done" => done = true,
+                b"ofs-delta" => options.allow_ofs_delta = true,
+                b"include-tag" => options.include_tag = true,
+                // We never send progress or thin packs.
+                b"no-progress" | b"thin-pack" => {}
+                _ => {
+                    let err = anyhow!("Unsupported fetch argument '{}'", argument);
+                    packetline::encode::error_to_write(err.to_string().as_bytes(), &mut *out)?;
+                    out.flush()?;
+                    return Err(err);
+                }
+            }
+        }
+    }
+    verify_wants(refs, &wants, &mut *out)?;
+
+    let mut buf = Vec::new();
+    let common: Vec<_> = haves.into_iter().filter(|id| is_commit(db, *id, &mut buf)).collect();
+    if !done {
+        packetline::encode::text_to_write(b"acknowledgments", &mut *out)?;
+        if common.is_empty() {
+            packetline::encode::text_to_write(b"NAK", &mut *out)?;
+        }
+        for id in &common {
+            packetline::encode::text_to_write(format!("ACK {}", id).as_bytes(), &mut *out)?;
+        }
+        packetline::encode::text_to_write(b"ready", &mut *out)?;
+        packetline::encode::delim_to_write(&mut *out)?;
+    }
+    packetline::encode::text_to_write(b"packfile", &mut *out)?;
+    send_pack(
+        db,
+        refs,
+        &wants,
+        &common,
+        options,
+        Some(LARGE_SIDEBAND_DATA_LEN),
+        out,
+        progress,
+    )
+}
+
+/// Fail if one of the `wants` isn't the target of one of the advertised `refs`, after letting the client know.
+fn verify_wants(refs: &[Ref], wants: &[ObjectId], mut out: impl Write) -> Result<()> {
+    if let Some(want) = wants
+        .iter()
+        .find(|want| !refs.iter().any(|r| r.id == **want || r.peeled == Some(**want)))
+    {
+        let err = anyhow!("upload-pack: not our ref {}", want);
+        packetline::encode::error_to_write(err.to_string().as_bytes(), &mut out)?;
+        out.flush()?;
+        return Err(err);
+    }
+    Ok(())
+}
+
+struct PackOptions {
+    allow_ofs_delta: bool,
+    /// If set, annotated tags pointing to commits in the pack are sent as well.
+    include_tag: bool,
+    thread_limit: Option<usize>,
+}
+
+/// Send a pack with all objects reachable from `wants` but not from the `common` commits, on the data band with packet lines
+/// of at most `max_data_len` bytes, or as is if `None`.
+#[allow(clippy::too_many_arguments)]
+fn send_pack(
+    db: &Arc<linked::Store>,
+    refs: &[Ref],
+    wants: &[ObjectId],
+    common: &[ObjectId],
+    options: PackOptions,
+    max_data_len: Option<usize>,
+    mut out: impl Write,
+    progress: &mut impl Progress,
+) -> Result<()> {
+    match max_data_len {
+        Some(max_data_len) => {
+            let mut band = BufWriter::with_capacity(
+                max_data_len,
+                SidebandWrite {
+                    out: &mut out,
+                    max_data_len,
+                },
+            );
+            write_pack(db, refs, wants, common, options, &mut band, progress)?;
+            band.flush()?;
+            drop(band);
+            packetline::encode::flush_to_write(&mut out)?;
+        }
+        None => write_pack(db, refs, wants, common, options, &mut out, progress)?,
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn write_pack(
+    db: &Arc<linked::Store>,
+    refs: &[Ref],
+    wants: &[ObjectId],
+    common: &[ObjectId],
+    PackOptions {
+        allow_ofs_delta,
+        include_tag,
+        thread_limit,
+    }: PackOptions,
+    out: impl Write,
+    progress: &mut impl Progress,
+) -> Result<()> {
+    let make_cancellation_err = || anyhow!("Cancelled by user");
+    let mut buf = Vec::new();
+    let mut tips = Vec::new();
+    let mut tags = Vec::new();
+    for want in wants {
+        let (target, kind) = peel_tags(db, *want, &mut buf)?;
+        if kind != Kind::Commit {
+            bail!(
+                "Can only send commits or tags pointing to commits, but {} points to a {}",
+                want,
+                kind
+            );
+        }
+        if target != *want {
+            tags.push(*want);
+        }
+        tips.push(target);
+    }
+
+    let excluded = interrupt::Iter::new(
+        traverse::commit::Ancestors::new(
+            common.to_vec(),
+            traverse::commit::ancestors::State::default(),
+            |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+        ),
+        make_cancellation_err,
+    )
+    .collect::<Result<Result<HashSet<_>, _>, _>>()??;
+    let commits = interrupt::Iter::new(
+        traverse::commit::Ancestors::filtered(
+            tips,
+            traverse::commit::ancestors::State::default(),
+            |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+            |id| !excluded.contains(id),
+        ),
+        make_cancellation_err,
+    )
+    .collect::<Result<Result<Vec<_>, _>, _>>()??;
+    if include_tag {
+        let sent_commits: HashSet<_> = commits.iter().collect();
+        tags.extend(
+            refs.iter()
+                .filter(|r| matches!(r.peeled, Some(peeled) if sent_commits.contains(&peeled)))
+                .map(|r| r.id),
+        );
+    }
+
+    let chunk_size = 200;
+    let mut counts = Vec::new();
+    {
+        let mut counts_iter = interrupt::Iter::new(
+            pack::data::output::count::from_objects_iter(
+                Arc::clone(db),
+                pack::cache::lru::StaticLinkedList::<64>::default,
+                commits.into_iter().chain(tags),
+                progress.add_child("counting"),
+                pack::data::output::count::from_objects_iter::Options {
+                    // Counting with multiple threads is faster, but yields different packs each time.
+                    thread_limit: Some(1),
+                    chunk_size,
+                    input_object_expansion:
+                        pack::data::output::count::from_objects_iter::ObjectExpansion::TreeAdditionsComparedToAncestor,
+                },
+            ),
+            make_cancellation_err,
+        );
+        for c in counts_iter.by_ref() {
+            counts.extend(c??);
+        }
+        counts_iter.into_inner().finalize()?;
+    }
+
+    let num_objects = counts.len();
+    let mut entries = pack::data::output::InOrderIter::from(pack::data::output::entry::from_counts_iter(
+        counts,
+        pack::data::output::entry::from_counts_iter::InputOrder,
+        Arc::clone(db),
+        pack::cache::lru::StaticLinkedList::<64>::default,
+        progress.add_child("creating entries"),
+        pack::data::output::entry::from_counts_iter::Options {
+            thread_limit,
+            chunk_size,
+            version: Default::default(),
+        },
+    ));
+    let mut bytes = interrupt::Iter::new(
+        pack::data::output::bytes::FromEntriesIter::new(
+            entries.by_ref(),
+            out,
+            num_objects as u32,
+            pack::data::Version::default(),
+            git_repository::hash::Kind::default(),
+            pack::data::output::bytes::Options {
+                allow_ofs_delta,
+                ..Default::default()
+            },
+        ),
+        make_cancellation_err,
+    );
+    for written in bytes.by_ref() {
+        written??;
+    }
+    entries.inner.finalize()?;
+    progress.info(format!("sent {} objects", num_objects));
+    Ok(())
+}
+
+/// Write all data as packet lines on the data band, splitting it into lines of at most `max_data_len` bytes.
+struct SidebandWrite<W> {
+    out: W,
+    max_data_len: usize,
+}
+
+impl<W: Write> Write for SidebandWrite<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.max_data_len);
+        packetline::encode::band_to_write(packetline::Channel::Data, &buf[..len], &mut self.out)?;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Read the next data line without its trailing newline, or return `None` if a delimiter was reached or the client hung up.
+fn read_line(lines: &mut packetline::StreamingPeekableIter<&TcpStream>) -> Result<Option<BString>> {
+    match lines.read_line() {
+        Some(Ok(line)) => {
+            let line = line?;
+            let data = line
+                .as_slice()
+                .ok_or_else(|| anyhow!("Expected a data line, got {:?}", line))?;
+            Ok(Some(data.strip_suffix(b"\n").unwrap_or(data).as_bstr().to_owned()))
+        }
+        Some(Err(err)) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Some(Err(err)) => Err(err.into()),
+        None => Ok(None),
+    }
+}
+
+fn parse_id(hex: &[u8]) -> Result<ObjectId> {
+    ObjectId::from_hex(hex).map_err(|err| anyhow!("Invalid object id '{}': {}", hex.as_bstr(), err))
+}
+
+fn is_commit(db: &linked::Store, id: ObjectId, buf: &mut Vec<u8>) -> bool {
+    db.find_existing_commit_iter(id, buf, &mut pack::cache::Never).is_ok()
+}
+
+/// Follow the annotated tag at `id` to the object it ultimately points to, and return it along with its kind.
+fn peel_tags(db: &linked::Store, mut id: ObjectId, buf: &mut Vec<u8>) -> Result<(ObjectId, Kind)> {
+    loop {
+        let object = db.find_existing(id, buf, &mut pack::cache::Never)?;
+        match object.kind {
+            Kind::Tag => {
+                id = TagIter::from_bytes(object.data)
+                    .target_id()
+                    .ok_or_else(|| anyhow!("Tag {} has no target", id))?;
+            }
+            kind => return Ok((id, kind)),
+        }
+    }
+}
+
+/// List all references with `HEAD` first, followed by all others sorted by name.
+///
+/// Symbolic references are resolved by looking up their target among all other references, and skipped if it doesn't exist.
+fn list_refs(repository: &Repository) -> Result<Vec<Ref>> {
+    let mut ids = BTreeMap::<BString, ObjectId>::new();
+    let mut symbolic = Vec::new();
+    if let Some(packed) = repository.refs.packed()? {
+        for r in packed.iter()? {
+            let r = r?;
+            ids.insert(r.full_name.to_owned(), r.target());
+        }
+    }
+    let mut track = |r: refs::file::Reference<'_>| {
+        let name = r.name().as_ref().to_owned();
+        match r.target() {
+            refs::Target::Peeled(id) => {
+                ids.insert(name, id.to_owned());
+            }
+            refs::Target::Symbolic(target) => symbolic.push((name, target.to_owned())),
+        }
+    };
+    for r in repository.refs.loose_iter()? {
+        track(r?);
+    }
+    if let Some(head) = repository.refs.find_one("HEAD")? {
+        track(head);
+    }
+
+    let mut symbolic_ids = BTreeMap::new();
+    for (name, target) in symbolic {
+        if let Some(id) = ids.get(&target) {
+            symbolic_ids.insert(name, (*id, target));
+        }
+    }
+    let head = symbolic_ids
+        .remove(&BString::from("HEAD"))
+        .map(|(id, target)| ("HEAD".into(), id, Some(target)))
+        .or_else(|| ids.remove(&BString::from("HEAD")).map(|id| ("HEAD".into(), id, None)));
+    let mut all: Vec<(BString, ObjectId, Option<BString>)> = ids
+        .into_iter()
+        .map(|(name, id)| (name, id, None))
+        .chain(
+            symbolic_ids
+                .into_iter()
+                .map(|(name, (id, target))| (name, id, Some(target))),
+        )
+        .collect();
+    all.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let db = &repository.odb;
+    let mut buf = Vec::new();
+    head.into_iter()
+        .chain(all)
+        .map(|(name, id, symref_target)| {
+            let (target, _kind) = peel_tags(db, id, &mut buf)?;
+            Ok(Ref {
+                name,
+                id,
+                peeled: if target != id { Some(target) } else { None },
+                symref_target,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,136 @@
+//! Defaults for command-line options as configured in git configuration files and environment variables.
+use crate::{net, OutputFormat};
+use anyhow::{anyhow, Result};
+use git_config::file::GitConfig;
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The environment variable overriding `gitoxide.threads`.
+pub const THREADS_ENV: &str = "GITOXIDE_THREADS";
+/// The environment variable overriding `protocol.version`.
+pub const PROTOCOL_VERSION_ENV: &str = "GITOXIDE_PROTOCOL_VERSION";
+/// The environment variable overriding `gitoxide.format`.
+pub const FORMAT_ENV: &str = "GITOXIDE_FORMAT";
+
+/// Values for command-line options which weren't specified, with `None` for those that aren't configured either.
+#[derive(Debug, Default, PartialEq)]
+pub struct Defaults {
+    /// The amount of threads to use as read from `gitoxide.threads`, where 0 means there is no limit.
+    pub threads: Option<usize>,
+    /// The protocol version to use as read from `protocol.version`, where git's version 0 is treated like version 1.
+    pub protocol: Option<net::Protocol>,
+    /// The output format as read from `gitoxide.format`.
+    pub format: Option<OutputFormat>,
+}
+
+impl Defaults {
+    /// Read the global git configuration of the current user, the configuration of the repository the current working directory
+    /// is in, if any, and finally environment variables, with later values overriding earlier ones.
+    ///
+    /// Missing configuration files are ignored, but invalid values cause an error.
+    pub fn from_environment() -> Result<Self> {
+        let mut defaults = Self::from_env_vars(|name| std::env::var(name).ok())?;
+        for path in config_paths().iter().rev() {
+            let config = match std::fs::read(path) {
+                Ok(config) => config,
+                Err(_) => continue,
+            };
+            defaults = defaults.or(Self::from_config(&config)
+                .map_err(|err| anyhow!("Invalid configuration in '{}': {}", path.display(), err))?);
+        }
+        Ok(defaults)
+    }
+
+    /// Read defaults from the git configuration file `config`.
+    pub fn from_config(config: &[u8]) -> Result<Self> {
+        let config = GitConfig::try_from(config).map_err(|err| anyhow!("{}", err))?;
+        let value = |section: &str, key: &str| -> Result<Option<String>> {
+            Ok(match config.value::<Cow<'_, [u8]>>(section, None, key) {
+                Ok(value) => Some(
+                    std::str::from_utf8(&git_config::values::normalize_cow(value))
+                        .map_err(|_| anyhow!("{}.{} is not valid UTF-8", section, key))?
+                        .trim()
+                        .to_owned(),
+                ),
+                Err(_) => None,
+            })
+        };
+        Self::parse(
+            value("gitoxide", "threads")?
+                .as_deref()
+                .map(|v| ("gitoxide.threads", v)),
+            value("protocol", "version")?
+                .as_deref()
+                .map(|v| ("protocol.version", v)),
+            value("gitoxide", "format")?.as_deref().map(|v| ("gitoxide.format", v)),
+        )
+    }
+
+    /// Read defaults from environment variables as returned by `var(name)`.
+    pub fn from_env_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        Self::parse(
+            var(THREADS_ENV).as_deref().map(|v| (THREADS_ENV, v)),
+            var(PROTOCOL_VERSION_ENV).as_deref().map(|v| (PROTOCOL_VERSION_ENV, v)),
+            var(FORMAT_ENV).as_deref().map(|v| (FORMAT_ENV, v)),
+        )
+    }
+
+    /// Use our values, or the ones of `other` where we don't have any.
+    pub fn or(self, other: Defaults) -> Self {
+        Defaults {
+            threads: self.threads.or(other.threads),
+            protocol: self.protocol.or(other.protocol),
+            format: self.format.or(other.format),
+        }
+    }
+
+    fn parse(
+        threads: Option<(&str, &str)>,
+        protocol: Option<(&str, &str)>,
+        format: Option<(&str, &str)>,
+    ) -> Result<Self> {
+        let invalid = |name: &str, value: &str, err: String| anyhow!("Invalid value '{}' for {}: {}", value, name, err);
+        Ok(Defaults {
+            threads: threads
+                .map(|(name, value)| usize::from_str(value).map_err(|err| invalid(name, value, err.to_string())))
+                .transpose()?,
+            protocol: protocol
+                .map(|(name, value)| {
+                    net::Protocol::from_str(if value == "0" { "1" } else { value })
+                        .map_err(|err| invalid(name, value, err))
+                })
+                .transpose()?,
+            format: format
+                .map(|(name, value)| OutputFormat::from_str(value).map_err(|err| invalid(name, value, err)))
+                .transpose()?,
+        })
+    }
+}
+
+/// The configuration files to read in order, with later ones taking precedence.
+pub(crate) fn config_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let home = home::home_dir();
+    match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(config_home) => paths.push(PathBuf::from(config_home).join("git").join("config")),
+        None => paths.extend(
+            home.as_ref()
+                .map(|home| home.join(".config").join("git").join("config")),
+        ),
+    }
+    paths.extend(home.map(|home| home.join(".gitconfig")));
+    paths.extend(repository_config_path(Path::new(".")));
+    paths
+}
+
+fn repository_config_path(directory: &Path) -> Option<PathBuf> {
+    let git_dir = git_repository::path::discover::existing(directory)
+        .ok()?
+        .into_repository_directory();
+    let common_dir = git_repository::path::common_dir(&git_dir).ok().flatten();
+    Some(common_dir.unwrap_or(git_dir).join("config"))
+}
@@ -4,11 +4,22 @@
 
 use std::str::FromStr;
 
+/// The way results are written to the output.
+///
+/// The field names used in `json` and `json-lines` output are considered stable and only change with breaking releases.
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub enum OutputFormat {
+    /// Output meant to be read by humans, which may change at any time.
     Human,
+    /// A single JSON document written once the operation is complete.
     #[cfg(feature = "serde1")]
     Json,
+    /// One compact JSON record per line.
+    ///
+    /// Long-running operations write a record per item as soon as it is produced, others write their only document
+    /// on a single line.
+    #[cfg(feature = "serde1")]
+    JsonLines,
 }
 
 impl OutputFormat {
@@ -17,6 +28,8 @@ impl OutputFormat {
             "human",
             #[cfg(feature = "serde1")]
             "json",
+            #[cfg(feature = "serde1")]
+            "json-lines",
         ]
     }
 }
@@ -30,22 +43,70 @@ impl FromStr for OutputFormat {
             "human" => OutputFormat::Human,
             #[cfg(feature = "serde1")]
             "json" => OutputFormat::Json,
+            #[cfg(feature = "serde1")]
+            "json-lines" => OutputFormat::JsonLines,
             _ => return Err(format!("Invalid output format: '{}'", s)),
         })
     }
 }
 
+/// Write `record` as compact JSON followed by a newline, as used by [`OutputFormat::JsonLines`].
+#[cfg(feature = "serde1")]
+pub(crate) fn write_json_line(mut out: impl std::io::Write, record: &impl serde::Serialize) -> std::io::Result<()> {
+    serde_json::to_writer(&mut out, record)?;
+    writeln!(out)
+}
+
+/// Return true if `text` matches `pattern` in full, where `*` matches any amount of characters including `/`,
+/// and `?` matches exactly one.
+pub(crate) fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
 pub mod net;
 
+pub mod analyze;
+pub mod branch;
 pub mod commitgraph;
+#[cfg(feature = "blocking-client")]
+pub mod daemon;
+pub mod defaults;
 #[cfg(feature = "estimate-hours")]
 pub mod hours;
+pub mod lock;
 #[cfg(feature = "organize")]
 pub mod organize;
 pub mod pack;
+pub mod reflog;
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
 pub mod remote;
 pub mod repository;
+pub mod stats;
+pub mod tag;
+pub mod url_rewrite;
+pub mod verify;
 
 #[cfg(all(feature = "async-client", feature = "blocking-client"))]
 compile_error!("Cannot set both 'blocking-client' and 'async-client' features as they are mutually exclusive");
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use git_lock::stale::Status;
+use std::{io, path::Path};
+
+/// What to do with the locks found in a directory.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Mode {
+    /// Only list locks along with their status.
+    List,
+    /// Remove locks known to be stale and list them.
+    Clean,
+}
+
+/// Find all lock files in `directory` and its subdirectories and write their status to `out`, or remove the stale ones
+/// depending on `mode`.
+///
+/// Only locks whose owner is known to be dead are considered stale, which excludes all locks that don't record their owner.
+pub fn run(mode: Mode, directory: impl AsRef<Path>, mut out: impl io::Write, mut err: impl io::Write) -> Result<()> {
+    let mut walk = git_features::fs::walk(
+        directory,
+        git_features::fs::walk::Options {
+            sorted: true,
+            ..Default::default()
+        },
+    );
+    for entry in walk
+        .by_ref()
+        .filter(|e| e.file_type.is_file() && e.path.extension() == Some("lock".as_ref()))
+    {
+        let status = git_lock::stale::status(&entry.path)?;
+        match (mode, status) {
+            (Mode::Clean, Status::Dead(owner)) => {
+                std::fs::remove_file(&entry.path)?;
+                writeln!(out, "removed\t{}\t(pid {})", entry.path.display(), owner.pid)?;
+            }
+            (Mode::Clean, _) => {}
+            (Mode::List, Status::Dead(owner)) => writeln!(out, "stale\t{}\t(pid {})", entry.path.display(), owner.pid)?,
+            (Mode::List, Status::Alive(owner)) => {
+                writeln!(out, "alive\t{}\t(pid {})", entry.path.display(), owner.pid)?
+            }
+            (Mode::List, Status::Unknown) => writeln!(out, "unknown\t{}", entry.path.display())?,
+        }
+    }
+    for walk_err in walk.into_errors() {
+        writeln!(err, "Could not read directory entry: {}", walk_err)?;
+    }
+    Ok(())
+}
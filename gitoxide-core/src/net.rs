@@ -58,8 +58,12 @@ mod async_io {
         path: BString,
         desired_version: transport::Protocol,
         port: Option<u16>,
+        extra_parameters: Vec<(String, Option<String>)>,
     ) -> Result<git::Connection<TcpStream, TcpStream>, Error> {
-        let read = TcpStream::connect(&(host, port.unwrap_or(9418)))
+        let port = port
+            .or_else(|| git_repository::url::Scheme::Git.default_port())
+            .expect("git has a default port");
+        let read = TcpStream::connect(&(host, port))
             .or(async {
                 async_io::Timer::after(Duration::from_secs(5)).await;
                 Err(io::ErrorKind::TimedOut.into())
@@ -74,12 +78,14 @@ mod async_io {
             path,
             None::<(String, _)>,
             git::ConnectMode::Daemon,
-        ))
+        )
+        .extra_parameters(extra_parameters))
     }
 
     pub async fn connect(
         url: &[u8],
         desired_version: transport::Protocol,
+        options: client::connect::Options,
     ) -> Result<impl client::Transport + Send, Error> {
         let urlb = url;
         let url = git_repository::url::parse(urlb)?;
@@ -93,6 +99,7 @@ mod async_io {
                     url.path,
                     desired_version,
                     url.port,
+                    options.extra_parameters,
                 )
                 .await?
             }
@@ -1,8 +1,11 @@
+use crate::OutputFormat;
 use git_config::file::GitConfig;
 use git_repository::{object::bstr::ByteSlice, progress, Progress};
 use std::{
+    collections::BTreeSet,
     convert::TryFrom,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -17,6 +20,40 @@ impl Default for Mode {
     }
 }
 
+/// How repositories are put into place in the destination directory.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Placement {
+    /// Move the repository, leaving nothing at its original location.
+    Move,
+    /// Create a symbolic link to the repository, which stays where it is.
+    Symlink,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Placement::Move
+    }
+}
+
+impl Placement {
+    pub fn variants() -> &'static [&'static str] {
+        &["move", "symlink"]
+    }
+}
+
+impl FromStr for Placement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s_lc = s.to_ascii_lowercase();
+        Ok(match s_lc.as_str() {
+            "move" => Placement::Move,
+            "symlink" => Placement::Symlink,
+            _ => return Err(format!("Invalid placement mode: '{}'", s)),
+        })
+    }
+}
+
 enum RepoKind {
     Bare,
     WorkingTree,
@@ -112,13 +149,80 @@ fn find_origin_remote(repo: &Path) -> anyhow::Result<Option<git_url::Url>> {
     Ok(config.value("remote", Some("origin"), "url").ok())
 }
 
+/// A repository whose destination was already taken by another one, causing it to be placed at a suffixed `destination`.
+struct Collision {
+    source: PathBuf,
+    destination: PathBuf,
+}
+
+/// What happened to a single repository, as reported in `json` and `json-lines` output.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Record {
+    /// The path to the repository that was found.
+    pub source: PathBuf,
+    /// Where the repository was placed, or would be placed when simulating, or `None` if it wasn't.
+    pub destination: Option<PathBuf>,
+    /// If `true`, the desired destination was already taken and `destination` has a numeric suffix.
+    pub suffixed: bool,
+    /// The reason for leaving the repository where it is, if it wasn't placed and no error occurred.
+    pub skipped: Option<String>,
+    /// The error that occurred when handling the repository.
+    pub error: Option<String>,
+}
+
+/// All repositories that were handled by [`run()`], in the order they were found.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    pub repositories: Vec<Record>,
+}
+
+/// The outcome of handling a single repository.
+enum Handled {
+    /// The repository was left where it is for the given reason.
+    Skipped(String),
+    /// The repository was, or would be, placed at `destination`, which is suffixed if the desired one was taken.
+    Placed { destination: PathBuf, suffixed: bool },
+}
+
+/// Return `None` if the repository at `git_workdir` already is at `desired` or one of its suffixed variants, or the first
+/// of these that is neither `claimed` nor present on disk after claiming it.
+///
+/// As repositories are handled in sorted order, the first one to claim a destination gets it, and the following ones
+/// are suffixed with `-2`, `-3` and so forth, keeping the extension of bare repositories.
+fn claim_destination(desired: &Path, git_workdir: &Path, claimed: &mut BTreeSet<PathBuf>) -> Option<PathBuf> {
+    let mut candidate = desired.to_owned();
+    for suffix in 2.. {
+        let exists = candidate.symlink_metadata().is_ok();
+        if exists && candidate.canonicalize().ok().as_deref() == Some(git_workdir) {
+            return None;
+        }
+        if !exists && !claimed.contains(&candidate) {
+            break;
+        }
+        let mut file_name = desired
+            .file_stem()
+            .expect("repo destination is not the root")
+            .to_owned();
+        file_name.push(format!("-{}", suffix));
+        if let Some(extension) = desired.extension() {
+            file_name.push(".");
+            file_name.push(extension);
+        }
+        candidate = desired.with_file_name(file_name);
+    }
+    claimed.insert(candidate.clone());
+    Some(candidate)
+}
+
 fn handle(
     mode: Mode,
+    placement: Placement,
     kind: RepoKind,
     git_workdir: &Path,
     canonicalized_destination: &Path,
+    claimed: &mut BTreeSet<PathBuf>,
     progress: &mut impl Progress,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Handled> {
     fn to_relative(path: PathBuf) -> PathBuf {
         path.components()
             .skip_while(|c| c == &std::path::Component::RootDir)
@@ -150,7 +254,10 @@ fn handle(
             git_workdir.display(),
             parent_repo_path
         ));
-        return Ok(());
+        return Ok(Handled::Skipped(format!(
+            "nested within repository {:?}",
+            parent_repo_path
+        )));
     }
 
     let url = match find_origin_remote(git_workdir)? {
@@ -159,7 +266,7 @@ fn handle(
                 "Skipping repository {:?} without 'origin' remote",
                 git_workdir.display()
             ));
-            return Ok(());
+            return Ok(Handled::Skipped("no 'origin' remote".into()));
         }
         Some(url) => url,
     };
@@ -169,7 +276,10 @@ fn handle(
             git_workdir.display(),
             url.to_string()
         ));
-        return Ok(());
+        return Ok(Handled::Skipped(format!(
+            "remote does not have a path: {:?}",
+            url.to_string()
+        )));
     }
 
     let destination = canonicalized_destination
@@ -189,24 +299,48 @@ fn handle(
             }
         }));
 
-    if let Ok(destination) = destination.canonicalize() {
-        if git_workdir.canonicalize()? == destination {
-            return Ok(());
-        }
-    }
+    let canonicalized_git_workdir = git_workdir.canonicalize()?;
+    let desired_destination = destination;
+    let destination = match claim_destination(&desired_destination, &canonicalized_git_workdir, claimed) {
+        Some(destination) => destination,
+        None => return Ok(Handled::Skipped("already in place".into())),
+    };
+    let verb = match placement {
+        Placement::Move => "move",
+        Placement::Symlink => "symlink",
+    };
     match mode {
         Mode::Simulate => progress.info(format!(
-            "WOULD move {} to {}",
+            "WOULD {} {} to {}",
+            verb,
             git_workdir.display(),
             destination.display()
         )),
         Mode::Execute => {
             std::fs::create_dir_all(destination.parent().expect("repo destination is not the root"))?;
-            progress.done(format!("Moving {} to {}", git_workdir.display(), destination.display()));
-            std::fs::rename(git_workdir, &destination)?;
+            match placement {
+                Placement::Move => {
+                    progress.done(format!("Moving {} to {}", git_workdir.display(), destination.display()));
+                    std::fs::rename(git_workdir, &destination)?;
+                }
+                Placement::Symlink => {
+                    progress.done(format!(
+                        "Symlinking {} to {}",
+                        git_workdir.display(),
+                        destination.display()
+                    ));
+                    #[cfg(unix)]
+                    std::os::unix::fs::symlink(&canonicalized_git_workdir, &destination)?;
+                    #[cfg(windows)]
+                    std::os::windows::fs::symlink_dir(&canonicalized_git_workdir, &destination)?;
+                }
+            }
         }
     }
-    Ok(())
+    Ok(Handled::Placed {
+        suffixed: destination != desired_destination,
+        destination,
+    })
 }
 
 /// Find all working directories in the given `source_dir` and print them to `out` while providing `progress`.
@@ -224,25 +358,88 @@ where
     Ok(())
 }
 
+/// Put all repositories found in `source_dir` into `destination` according to their `origin` remote URL, by moving or
+/// symlinking them as determined by `placement`, or only print what would be done if `mode` is [`Mode::Simulate`].
+///
+/// Repositories with the same destination, like the same URL cloned twice, are placed at a destination with a numeric
+/// suffix, and are reported once all repositories were handled.
+///
+/// Unless `format` is [`OutputFormat::Human`], a [`Record`] for each repository is written to `out`, either all at once
+/// as [`Report`] or each one as soon as the repository was handled with [`OutputFormat::JsonLines`].
+#[cfg_attr(not(feature = "serde1"), allow(unused_variables, unused_mut))]
 pub fn run<P: Progress>(
     mode: Mode,
+    placement: Placement,
     source_dir: impl AsRef<Path>,
     destination: impl AsRef<Path>,
+    format: OutputFormat,
+    mut out: impl std::io::Write,
     mut progress: P,
 ) -> anyhow::Result<()>
 where
     <<P as Progress>::SubProgress as Progress>::SubProgress: Sync,
 {
     let mut num_errors = 0usize;
+    let mut claimed = BTreeSet::new();
+    let mut collisions = Vec::new();
+    let mut records = Vec::new();
     let destination = destination.as_ref().canonicalize()?;
     for (path_to_move, kind) in find_git_repository_workdirs(source_dir, progress.add_child("Searching repositories")) {
-        if let Err(err) = handle(mode, kind, &path_to_move, &destination, &mut progress) {
-            progress.fail(format!(
-                "Error when handling directory {:?}: {}",
-                path_to_move.display(),
-                err.to_string()
-            ));
-            num_errors += 1;
+        let mut record = Record {
+            source: path_to_move.clone(),
+            destination: None,
+            suffixed: false,
+            skipped: None,
+            error: None,
+        };
+        match handle(
+            mode,
+            placement,
+            kind,
+            &path_to_move,
+            &destination,
+            &mut claimed,
+            &mut progress,
+        ) {
+            Ok(Handled::Placed { destination, suffixed }) => {
+                if suffixed {
+                    collisions.push(Collision {
+                        source: path_to_move,
+                        destination: destination.clone(),
+                    });
+                }
+                record.destination = Some(destination);
+                record.suffixed = suffixed;
+            }
+            Ok(Handled::Skipped(reason)) => record.skipped = Some(reason),
+            Err(err) => {
+                progress.fail(format!(
+                    "Error when handling directory {:?}: {}",
+                    path_to_move.display(),
+                    err.to_string()
+                ));
+                record.error = Some(err.to_string());
+                num_errors += 1;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        if format == OutputFormat::JsonLines {
+            crate::write_json_line(&mut out, &record)?;
+        }
+        records.push(record);
+    }
+    #[cfg(feature = "serde1")]
+    if format == OutputFormat::Json {
+        serde_json::to_writer_pretty(&mut out, &Report { repositories: records })?;
+    }
+
+    if !collisions.is_empty() {
+        progress.info(format!(
+            "{} repositories had a destination that was already taken and were suffixed:",
+            collisions.len()
+        ));
+        for Collision { source, destination } in collisions {
+            progress.info(format!("{} -> {}", source.display(), destination.display()));
         }
     }
 
@@ -4,12 +4,20 @@ use git_repository::{
     hash,
     hash::ObjectId,
     interrupt,
-    object::bstr::ByteVec,
+    object::bstr::{ByteSlice, ByteVec},
     odb::{linked, pack},
     prelude::{Finalize, FindExt},
-    progress, traverse, Progress,
+    progress, revision, traverse, Progress,
+};
+use std::{
+    collections::HashSet,
+    ffi::{OsStr, OsString},
+    io,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::Instant,
 };
-use std::{ffi::OsStr, io, path::Path, str::FromStr, sync::Arc, time::Instant};
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
 
@@ -99,20 +107,42 @@ where
     progress.init(Some(4), progress::steps());
     let tips = tips.into_iter();
     let make_cancellation_err = || anyhow!("Cancelled by user");
+    let mut boundary = HashSet::new();
     let input: Box<dyn Iterator<Item = ObjectId> + Send + 'static> = match input {
         None => Box::new({
             let mut progress = progress.add_child("traversing");
             progress.init(None, progress::count("commits"));
             let start = Instant::now();
+            let revision::Tips {
+                include: tips,
+                exclude: excluded_tips,
+            } = parse_tips(tips, &db)?;
+            boundary.extend(excluded_tips.iter().copied());
+            let excluded = interrupt::Iter::new(
+                traverse::commit::Ancestors::new(excluded_tips, traverse::commit::ancestors::State::default(), {
+                    let db = Arc::clone(&db);
+                    move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
+                }),
+                make_cancellation_err,
+            )
+            .collect::<Result<Result<HashSet<_>, _>, _>>()??;
             let iter = interrupt::Iter::new(
-                traverse::commit::Ancestors::new(
-                    tips.map(|t| ObjectId::from_hex(&Vec::from_os_str_lossy(t.as_ref())))
-                        .collect::<Result<Vec<_>, _>>()?,
+                traverse::commit::Ancestors::filtered(
+                    tips,
                     traverse::commit::ancestors::State::default(),
                     {
                         let db = Arc::clone(&db);
                         move |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok()
                     },
+                    |id| {
+                        // excluded parents of included commits are the boundary, whose objects the receiver has
+                        if excluded.contains(id) {
+                            boundary.insert(id.to_owned());
+                            false
+                        } else {
+                            true
+                        }
+                    },
                 )
                 .inspect(|_| progress.inc()),
                 make_cancellation_err,
@@ -133,14 +163,19 @@ where
     let mut stats = Statistics::default();
     let chunk_size = 200;
     let start = Instant::now();
+    if expansion == ObjectExpansion::None {
+        // without expansion, objects of the boundary can't be encountered
+        boundary.clear();
+    }
     let counts = {
         let mut progress = progress.add_child("counting");
         progress.init(None, progress::count("objects"));
         let mut interruptible_counts_iter = interrupt::Iter::new(
-            pack::data::output::count::from_objects_iter(
+            pack::data::output::count::from_objects_iter_excluding(
                 Arc::clone(&db),
                 pack::cache::lru::StaticLinkedList::<64>::default,
                 input,
+                boundary,
                 progress.add_child("threads"),
                 pack::data::output::count::from_objects_iter::Options {
                     thread_limit: if nondeterministic_count || matches!(expansion, ObjectExpansion::None) {
@@ -151,7 +186,7 @@ where
                     chunk_size,
                     input_object_expansion: expansion.into(),
                 },
-            ),
+            )?,
             make_cancellation_err,
         );
         let mut counts = Vec::new();
@@ -172,6 +207,7 @@ where
         let progress = progress.add_child("creating entries");
         pack::data::output::InOrderIter::from(pack::data::output::entry::from_counts_iter(
             counts,
+            pack::data::output::entry::from_counts_iter::InputOrder,
             Arc::clone(&db),
             pack::cache::lru::StaticLinkedList::<64>::default,
             progress,
@@ -202,18 +238,24 @@ where
             (&mut sink_store, None)
         }
     };
+    let output_iter = pack::data::output::bytes::FromEntriesIter::new(
+        in_order_entries.by_ref().inspect(|e| {
+            if let Ok(entries) = e {
+                entries_progress.inc_by(entries.len())
+            }
+        }),
+        &mut pack_file,
+        num_objects as u32,
+        pack::data::Version::default(),
+        hash::Kind::default(),
+        Default::default(),
+    );
     let mut interruptible_output_iter = interrupt::Iter::new(
-        pack::data::output::bytes::FromEntriesIter::new(
-            in_order_entries.by_ref().inspect(|e| {
-                if let Ok(entries) = e {
-                    entries_progress.inc_by(entries.len())
-                }
-            }),
-            &mut pack_file,
-            num_objects as u32,
-            pack::data::Version::default(),
-            hash::Kind::default(),
-        ),
+        if output_directory.is_some() {
+            output_iter.with_index()
+        } else {
+            output_iter
+        },
         make_cancellation_err,
     );
     for io_res in interruptible_output_iter.by_ref() {
@@ -221,13 +263,23 @@ where
         write_progress.inc_by(written as usize);
     }
 
-    let hash = interruptible_output_iter
-        .into_inner()
-        .digest()
-        .expect("iteration is done");
+    let output_iter = interruptible_output_iter.into_inner();
+    let hash = output_iter.digest().expect("iteration is done");
+    let index_file = match &output_directory {
+        Some(dir) => {
+            let mut index_file = tempfile::NamedTempFile::new_in(dir.as_ref())?;
+            output_iter.write_index(&mut index_file)?;
+            Some(index_file)
+        }
+        None => None,
+    };
+    drop(output_iter);
     let pack_name = format!("{}.pack", hash);
-    if let (Some(pack_file), Some(dir)) = (named_tempfile_store.take(), output_directory) {
-        pack_file.persist(dir.as_ref().join(pack_name))?;
+    if let (Some(pack_file), Some(index_file), Some(dir)) = (named_tempfile_store.take(), index_file, output_directory)
+    {
+        let pack_path = dir.as_ref().join(pack_name);
+        pack_file.persist(&pack_path)?;
+        index_file.persist(pack_path.with_extension("idx"))?;
     } else {
         writeln!(out, "{}", pack_name)?;
     }
@@ -242,6 +294,37 @@ where
     Ok(())
 }
 
+/// Return `tips` along with all `excluded` tips, prefixed with `^` to exclude them, for use in [`create()`].
+pub fn tips_with_exclusions(tips: Vec<OsString>, excluded: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    tips.into_iter()
+        .chain(excluded.into_iter().map(|tip| {
+            let mut excluded_tip = OsString::from("^");
+            excluded_tip.push(tip);
+            excluded_tip
+        }))
+        .collect()
+}
+
+/// Split `tips` into the commits to start the traversal from and the commits to exclude along with all of their ancestors.
+///
+/// All revisions are expected to be hexadecimal commit ids, optionally using range notation like `^a`, `a..b`,
+/// `a...b` or `a^!`.
+fn parse_tips(tips: impl IntoIterator<Item = impl AsRef<OsStr>>, db: &linked::Store) -> anyhow::Result<revision::Tips> {
+    let tips: Vec<_> = tips
+        .into_iter()
+        .map(|tip| Vec::from_os_str_lossy(tip.as_ref()).into_owned())
+        .collect();
+    let specs = tips
+        .iter()
+        .map(|tip| revision::parse(tip.as_bstr()).map_err(|err| anyhow!("Invalid tip '{}': {}", tip.as_bstr(), err)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(revision::Tips::from_specs(
+        specs,
+        |hex| ObjectId::from_hex(hex).ok(),
+        |oid, buf| db.find_existing_commit_iter(oid, buf, &mut pack::cache::Never).ok(),
+    )?)
+}
+
 fn find_db(repository: impl AsRef<Path>) -> anyhow::Result<linked::Store> {
     let path = repository.as_ref();
     Ok(linked::Store::at(
@@ -256,6 +339,8 @@ fn print(stats: Statistics, format: OutputFormat, out: impl std::io::Write) -> a
         OutputFormat::Human => human_output(stats, out).map_err(Into::into),
         #[cfg(feature = "serde1")]
         OutputFormat::Json => serde_json::to_writer_pretty(out, &stats).map_err(Into::into),
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => crate::write_json_line(out, &stats).map_err(Into::into),
     }
 }
 
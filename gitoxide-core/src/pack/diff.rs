@@ -0,0 +1,202 @@
+use crate::OutputFormat;
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use git_repository::{hash::ObjectId, odb::pack};
+use std::{collections::HashMap, io, path::Path};
+
+pub struct Context<W: io::Write> {
+    /// The way statistics and differences are presented
+    pub format: OutputFormat,
+    /// A stream to which to output the comparison
+    pub out: W,
+}
+
+/// The way an object is stored in a pack.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Storage {
+    /// The object is stored in full.
+    Base,
+    /// The object is stored as delta against the given base object.
+    Delta { base: ObjectId },
+}
+
+/// Information about how an object is stored within a pack.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    /// The amount of bytes the object occupies in the pack, including its entry header.
+    pub entry_size: u64,
+    pub storage: Storage,
+}
+
+/// An object present in both packs whose storage differs.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Change {
+    pub id: ObjectId,
+    pub a: Entry,
+    pub b: Entry,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// Objects which are only present in the first pack
+    pub only_in_a: Vec<ObjectId>,
+    /// Objects which are only present in the second pack
+    pub only_in_b: Vec<ObjectId>,
+    /// Objects present in both packs, but stored differently
+    pub changed: Vec<Change>,
+    /// The amount of objects present and stored the same way in both packs
+    pub num_unchanged: usize,
+    /// The size of all entries in the first pack
+    pub total_entry_size_a: u64,
+    /// The size of all entries in the second pack
+    pub total_entry_size_b: u64,
+}
+
+/// Compare the packs identified by `a` and `b`, either given by their '.idx' or '.pack' path, and report objects that
+/// are only present in one of them or stored differently in both.
+pub fn packs<W: io::Write>(
+    a: impl AsRef<Path>,
+    b: impl AsRef<Path>,
+    Context { format, mut out }: Context<W>,
+) -> Result<Outcome> {
+    let a = open(a.as_ref())?;
+    let b = open(b.as_ref())?;
+
+    let mut outcome = Outcome::default();
+    let (mut ia, mut ib) = (0, 0);
+    let (na, nb) = (a.bundle.index.num_objects(), b.bundle.index.num_objects());
+    while ia < na || ib < nb {
+        let ordering = match (ia < na, ib < nb) {
+            (true, true) => a.bundle.index.oid_at_index(ia).cmp(b.bundle.index.oid_at_index(ib)),
+            (true, false) => std::cmp::Ordering::Less,
+            (false, _) => std::cmp::Ordering::Greater,
+        };
+        use std::cmp::Ordering::*;
+        match ordering {
+            Less => {
+                let entry = a.entry_at_index(ia)?;
+                outcome.total_entry_size_a += entry.entry_size;
+                outcome.only_in_a.push(a.bundle.index.oid_at_index(ia).to_owned());
+                ia += 1;
+            }
+            Greater => {
+                let entry = b.entry_at_index(ib)?;
+                outcome.total_entry_size_b += entry.entry_size;
+                outcome.only_in_b.push(b.bundle.index.oid_at_index(ib).to_owned());
+                ib += 1;
+            }
+            Equal => {
+                let (entry_a, entry_b) = (a.entry_at_index(ia)?, b.entry_at_index(ib)?);
+                outcome.total_entry_size_a += entry_a.entry_size;
+                outcome.total_entry_size_b += entry_b.entry_size;
+                if entry_a == entry_b {
+                    outcome.num_unchanged += 1;
+                } else {
+                    outcome.changed.push(Change {
+                        id: a.bundle.index.oid_at_index(ia).to_owned(),
+                        a: entry_a,
+                        b: entry_b,
+                    });
+                }
+                ia += 1;
+                ib += 1;
+            }
+        }
+    }
+
+    match format {
+        OutputFormat::Human => print_human_output(&mut out, &outcome)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &outcome)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => crate::write_json_line(out, &outcome)?,
+    }
+    Ok(outcome)
+}
+
+struct Pack {
+    bundle: pack::Bundle,
+    id_by_offset: HashMap<u64, ObjectId>,
+}
+
+fn open(path: &Path) -> Result<Pack> {
+    let bundle = pack::Bundle::at(path).with_context(|| format!("Could not open pack at '{}'", path.display()))?;
+    let id_by_offset = bundle.index.iter().map(|e| (e.pack_offset, e.oid)).collect();
+    Ok(Pack { bundle, id_by_offset })
+}
+
+impl Pack {
+    fn entry_at_index(&self, index: u32) -> Result<Entry> {
+        let location = self.bundle.location_at_index(index);
+        let pack_entry = self.bundle.pack.entry(self.bundle.index.pack_offset_at_index(index));
+        use pack::data::entry::Header::*;
+        let storage = match pack_entry.header {
+            Commit | Tree | Blob | Tag => Storage::Base,
+            RefDelta { base_id } => Storage::Delta { base: base_id },
+            OfsDelta { base_distance } => {
+                let pack_offset = pack_entry.pack_offset();
+                let base = pack_offset
+                    .checked_sub(base_distance)
+                    .and_then(|base_offset| self.id_by_offset.get(&base_offset).copied())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "The delta at pack offset {} refers to a base {} bytes before it where no entry starts in pack at '{}'",
+                            pack_offset,
+                            base_distance,
+                            self.bundle.pack.path().display()
+                        )
+                    })?;
+                Storage::Delta { base }
+            }
+        };
+        Ok(Entry {
+            entry_size: location.entry_size as u64,
+            storage,
+        })
+    }
+}
+
+fn print_human_output(out: &mut impl io::Write, outcome: &Outcome) -> io::Result<()> {
+    for id in &outcome.only_in_a {
+        writeln!(out, "- {}", id)?;
+    }
+    for id in &outcome.only_in_b {
+        writeln!(out, "+ {}", id)?;
+    }
+    for Change { id, a, b } in &outcome.changed {
+        writeln!(
+            out,
+            "~ {} {} -> {} bytes ({} -> {})",
+            id,
+            a.entry_size,
+            b.entry_size,
+            storage_to_string(&a.storage),
+            storage_to_string(&b.storage)
+        )?;
+    }
+    writeln!(
+        out,
+        "only in a: {}, only in b: {}, changed: {}, unchanged: {}",
+        outcome.only_in_a.len(),
+        outcome.only_in_b.len(),
+        outcome.changed.len(),
+        outcome.num_unchanged
+    )?;
+    writeln!(
+        out,
+        "total entry size: {} -> {} bytes ({:+})",
+        outcome.total_entry_size_a,
+        outcome.total_entry_size_b,
+        outcome.total_entry_size_b as i64 - outcome.total_entry_size_a as i64
+    )
+}
+
+fn storage_to_string(storage: &Storage) -> String {
+    match storage {
+        Storage::Base => "base".into(),
+        Storage::Delta { base } => format!("delta of {}", base),
+    }
+}
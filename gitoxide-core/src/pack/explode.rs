@@ -236,7 +236,8 @@ pub fn pack_or_pack_index(
             algorithm,
             thread_limit,
             check: check.into(),
-            should_interrupt
+            should_interrupt,
+            prefetch: true,
         },
     ).map(|(_, _, c)| progress::DoOrDiscard::from(c)).with_context(|| "Failed to explode the entire pack - some loose objects may have been created nonetheless")?;
 
@@ -51,6 +51,7 @@ impl From<IterationMode> for pack::data::input::Mode {
 pub struct Context<'a, W: io::Write> {
     pub thread_limit: Option<usize>,
     pub iteration_mode: IterationMode,
+    pub memory_map: bool,
     pub format: OutputFormat,
     pub should_interrupt: &'a AtomicBool,
     pub out: W,
@@ -84,10 +85,14 @@ pub fn from_pack(
         thread_limit: ctx.thread_limit,
         iteration_mode: ctx.iteration_mode.into(),
         index_kind: pack::index::Version::default(),
+        object_hash: Default::default(),
     };
     let out = ctx.out;
     let format = ctx.format;
     let res = match pack {
+        PathOrRead::Path(pack) if ctx.memory_map => {
+            pack::Bundle::write_index_for_pack_file(pack, directory, progress, ctx.should_interrupt, options)
+        }
         PathOrRead::Path(pack) => {
             let pack_len = pack.metadata()?.len();
             let pack_file = fs::File::open(pack)?;
@@ -97,18 +102,30 @@ pub fn from_pack(
                 directory,
                 progress,
                 ctx.should_interrupt,
+                None,
                 options,
             )
         }
-        PathOrRead::Read(input) => {
-            pack::Bundle::write_to_directory_eagerly(input, None, directory, progress, ctx.should_interrupt, options)
+        PathOrRead::Read(_) if ctx.memory_map => {
+            anyhow::bail!("Only packs given by path can be memory-mapped")
         }
+        PathOrRead::Read(input) => pack::Bundle::write_to_directory_eagerly(
+            input,
+            None,
+            directory,
+            progress,
+            ctx.should_interrupt,
+            None,
+            options,
+        ),
     }
     .with_context(|| "Failed to write pack and index")?;
     match format {
         OutputFormat::Human => drop(human_output(out, res)),
         #[cfg(feature = "serde1")]
         OutputFormat::Json => serde_json::to_writer_pretty(out, &res)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => crate::write_json_line(out, &res)?,
     };
     Ok(())
 }
@@ -1,3 +1,4 @@
+pub mod diff;
 pub mod explode;
 pub mod index;
 pub mod verify;
@@ -1,7 +1,7 @@
 use crate::{remote::refs::JsonRef, OutputFormat};
 use git_repository::{
     hash::ObjectId,
-    object::bstr::{BString, ByteSlice},
+    object::bstr::{BStr, BString, ByteSlice},
     odb::pack,
     protocol,
     protocol::{
@@ -9,8 +9,20 @@ use git_repository::{
         transport,
         transport::client::Capabilities,
     },
+    refs::{
+        mutable::{FullName, Target},
+        transaction::{Change, Create, LogChange, RefEdit, RefLog},
+    },
+    Repository,
+};
+use quick_error::quick_error;
+use std::{
+    collections::BTreeSet,
+    convert::{TryFrom, TryInto},
+    io,
+    path::PathBuf,
+    sync::{atomic::AtomicBool, Arc},
 };
-use std::{io, path::PathBuf, sync::atomic::AtomicBool, sync::Arc};
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
@@ -21,13 +33,319 @@ pub struct Context<W> {
     pub out: W,
 }
 
+/// An existing repository to receive a pack into, similar to what a fetch from the remote named `remote_name` would do.
+pub struct IntoRepository {
+    /// The repository to write the pack into, or a directory within it.
+    pub path: PathBuf,
+    /// The name of the remote, placing remote-tracking references into `refs/remotes/<remote_name>/`.
+    pub remote_name: String,
+}
+
 struct CloneDelegate<W> {
     ctx: Context<W>,
+    url: String,
     directory: Option<PathBuf>,
     refs_directory: Option<PathBuf>,
-    ref_filter: Option<&'static [&'static str]>,
+    repository: Option<(Repository, String)>,
+    ref_patterns: Vec<String>,
+}
+/// The references to receive if no patterns are given.
+pub static DEFAULT_REF_PATTERNS: &[&str] = &["HEAD", "refs/tags", "refs/heads"];
+
+impl<W> CloneDelegate<W> {
+    fn new(
+        ctx: Context<W>,
+        url: &str,
+        mut directory: Option<PathBuf>,
+        refs_directory: Option<PathBuf>,
+        into: Option<IntoRepository>,
+        ref_patterns: Vec<String>,
+    ) -> anyhow::Result<Self> {
+        let repository = match into {
+            Some(IntoRepository { path, remote_name }) => {
+                if directory.is_some() || refs_directory.is_some() {
+                    anyhow::bail!("Cannot write pack or references into directories when receiving into a repository");
+                }
+                // fail early if the remote name can't be used in reference names
+                FullName::try_from(format!("refs/remotes/{}/HEAD", remote_name).as_str())?;
+                let repo = git_repository::discover(path)?;
+                directory = Some(repo.common_dir().join("objects").join("pack"));
+                Some((repo, remote_name))
+            }
+            None => None,
+        };
+        Ok(CloneDelegate {
+            ctx,
+            url: git_repository::url::parse(url.as_bytes())?.to_string(),
+            directory,
+            refs_directory,
+            repository,
+            ref_patterns: if ref_patterns.is_empty() {
+                DEFAULT_REF_PATTERNS.iter().map(|p| p.to_string()).collect()
+            } else {
+                ref_patterns
+            },
+        })
+    }
+
+    fn is_wanted(&self, path: &BString) -> bool {
+        self.ref_patterns.iter().any(|pattern| matches_ref(pattern, path))
+    }
+
+    fn matching_refs(&self, refs: &[Ref]) -> Vec<Ref> {
+        refs.iter().filter(|r| self.is_wanted(r.unpack().0)).cloned().collect()
+    }
+
+    /// Update the remote-tracking references and tags of our repository, if there is one, to match `refs`,
+    /// writing reflog entries along the way.
+    ///
+    /// Like git, existing tags which changed on the remote are not updated, and their names are returned as rejected.
+    fn update_refs_in_repository(&mut self, refs: &[Ref]) -> io::Result<Vec<BString>> {
+        let (repo, remote_name) = match self.repository.take() {
+            Some(repo_and_remote) => repo_and_remote,
+            None => return Ok(Vec::new()),
+        };
+        fn to_io_err(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+            io::Error::new(io::ErrorKind::Other, err)
+        }
+        let log = LogChange {
+            mode: RefLog::AndReference,
+            force_create_reflog: false,
+            message: format!("pack-receive: storing refs from {}", self.url).into(),
+        };
+        let mut updates = Vec::new();
+        for r in refs {
+            let (path, new) = match r {
+                Ref::Symbolic { path, target, .. } => match local_name(&remote_name, target.as_ref()) {
+                    Some(target) => (path, Target::Symbolic(target.as_str().try_into().map_err(to_io_err)?)),
+                    None => continue,
+                },
+                Ref::Peeled { path, tag: object, .. } | Ref::Direct { path, object } => (path, Target::Peeled(*object)),
+            };
+            if let Some(name) = local_name(&remote_name, path.as_ref()) {
+                updates.push((path, name, new));
+            }
+        }
+        let existing = repo
+            .refs
+            .find_many(updates.iter().map(|(_, name, _)| name.as_str()))
+            .map_err(to_io_err)?;
+        let mut edits = Vec::new();
+        let mut rejected_tags = Vec::new();
+        for ((path, name, new), existing) in updates.into_iter().zip(existing) {
+            let is_tag = path.starts_with_str("refs/tags/");
+            match existing {
+                Ok(Some(existing)) if existing.target() == new.borrow() => continue,
+                Ok(Some(_)) if is_tag => {
+                    rejected_tags.push(path.to_owned());
+                    continue;
+                }
+                _ => {}
+            }
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: log.clone(),
+                    // Tags are never clobbered, even if they were created concurrently.
+                    mode: if is_tag {
+                        Create::Only
+                    } else {
+                        Create::OrUpdate { previous: None }
+                    },
+                    new,
+                },
+                name: name.as_str().try_into().map_err(to_io_err)?,
+                deref: false,
+            });
+        }
+        repo.refs
+            .transaction(edits, git_lock::acquire::Fail::Immediately)
+            .commit(&crate::repository::committer(&repo).map_err(to_io_err)?)
+            .map_err(to_io_err)?;
+        Ok(rejected_tags)
+    }
+
+    /// Assure all objects reachable from `refs` are present in the pack described by `outcome` or in our repository, if
+    /// there is one, like `git fetch` does. Otherwise the written pack is removed and an error is returned, leaving
+    /// everything as it was.
+    fn check_connectivity(&self, outcome: &pack::bundle::write::Outcome, refs: &[Ref]) -> io::Result<()> {
+        let bundle = match outcome.to_bundle() {
+            Some(bundle) => bundle.map_err(|err| io::Error::new(io::ErrorKind::Other, err))?,
+            None => return Ok(()),
+        };
+        let tips = refs.iter().map(|r| *r.unpack().1);
+        let (thread_limit, should_interrupt) = (self.ctx.thread_limit, &self.ctx.should_interrupt);
+        let res = match &self.repository {
+            Some((repo, _)) => pack::connectivity::check(
+                tips,
+                existing_tips(repo)?,
+                &PackAndRepository { pack: bundle, repo },
+                || pack::cache::Never,
+                thread_limit,
+                should_interrupt,
+            )
+            .map_err(|err| err.to_string()),
+            None => pack::connectivity::check(
+                tips,
+                None,
+                &bundle,
+                || pack::cache::Never,
+                thread_limit,
+                should_interrupt,
+            )
+            .map_err(|err| err.to_string()),
+        };
+        if let Err(err) = res {
+            for path in outcome.index_path.iter().chain(outcome.data_path.iter()) {
+                std::fs::remove_file(path).ok();
+            }
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("The received pack is incomplete: {}", err),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Give the pack written into our repository, if there is one, the `pack-<hash>` names git uses, which is also what
+    /// our object database looks for.
+    fn name_pack_like_git(&self, outcome: &mut pack::bundle::write::Outcome) -> io::Result<()> {
+        if self.repository.is_none() {
+            return Ok(());
+        }
+        for path in outcome.data_path.iter_mut().chain(outcome.index_path.iter_mut()) {
+            let file_name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .expect("pack file names are hex hashes");
+            let new_path = path.with_file_name(format!("pack-{}", file_name));
+            std::fs::rename(&path, &new_path)?;
+            *path = new_path;
+        }
+        Ok(())
+    }
+
+    /// Return a way to look up the bases of deltas in a thin pack in our repository, if there is one.
+    fn thin_pack_lookup(&self) -> io::Result<Option<pack::bundle::write::ThinPackLookupFnSend>> {
+        let odb = match &self.repository {
+            Some((repo, _)) => git_repository::odb::linked::Store::at(repo.objects_dir())
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?,
+            None => return Ok(None),
+        };
+        Ok(Some(Box::new(move |id, buf| {
+            pack::Find::find(&odb, id, buf, &mut pack::cache::Never).ok().flatten()
+        })))
+    }
+}
+
+/// The objects of a newly received pack along with the ones of the repository receiving it, which doesn't know the pack yet.
+struct PackAndRepository<'a> {
+    pack: pack::Bundle,
+    repo: &'a Repository,
+}
+
+quick_error! {
+    #[derive(Debug)]
+    enum FindError {
+        Pack(err: pack::data::decode_entry::Error) {
+            display("{}", err)
+            source(err)
+            from()
+        }
+        Repository(err: git_repository::odb::compound::find::Error) {
+            display("{}", err)
+            source(err)
+            from()
+        }
+    }
+}
+
+impl pack::Find for PackAndRepository<'_> {
+    type Error = FindError;
+
+    fn find<'a>(
+        &self,
+        id: impl AsRef<git_repository::hash::oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl pack::cache::DecodeEntry,
+    ) -> Result<Option<pack::data::Object<'a>>, Self::Error> {
+        let id = id.as_ref();
+        if self.pack.index.lookup(id).is_some() {
+            Ok(self.pack.find(id, buffer, pack_cache)?)
+        } else {
+            Ok(self.repo.odb.find(id, buffer, pack_cache)?)
+        }
+    }
+
+    fn location_by_id(
+        &self,
+        id: impl AsRef<git_repository::hash::oid>,
+        buf: &mut Vec<u8>,
+    ) -> Option<pack::bundle::Location> {
+        let id = id.as_ref();
+        match self.pack.index.lookup(id) {
+            Some(idx) => Some(self.pack.location_at_index(idx)),
+            None => self.repo.odb.location_by_id(id, buf),
+        }
+    }
+
+    fn pack_checksum(&self, pack_id: u32) -> Option<git_repository::hash::ObjectId> {
+        if pack_id == self.pack.pack.id {
+            Some(self.pack.pack.checksum())
+        } else {
+            self.repo.odb.pack_checksum(pack_id)
+        }
+    }
+
+    fn entry_by_location(&self, location: &pack::bundle::Location) -> Option<pack::find::Entry<'_>> {
+        if location.pack_id == self.pack.pack.id {
+            pack::Find::entry_by_location(&self.pack, location)
+        } else {
+            self.repo.odb.entry_by_location(location)
+        }
+    }
+}
+
+/// Return the objects all references of `repo` point to, which are assumed to be present along with all objects reachable
+/// from them.
+fn existing_tips(repo: &Repository) -> io::Result<Vec<ObjectId>> {
+    fn to_io_err(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+        io::Error::new(io::ErrorKind::Other, err)
+    }
+    let mut tips = Vec::new();
+    if let Some(packed) = repo.refs.packed().map_err(to_io_err)? {
+        for reference in packed.iter().map_err(to_io_err)? {
+            tips.push(reference.map_err(to_io_err)?.target());
+        }
+    }
+    match repo.refs.loose_iter() {
+        Ok(references) => {
+            for reference in references {
+                if let Some(id) = reference.map_err(to_io_err)?.target().as_id() {
+                    tips.push(id.to_owned());
+                }
+            }
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    Ok(tips)
+}
+
+/// Return the name of the local reference to store the remote reference at `path` in, or `None` if it shouldn't be stored.
+///
+/// Branches and `HEAD` turn into remote-tracking references of `remote_name` while tags are stored as is.
+fn local_name(remote_name: &str, path: &BStr) -> Option<String> {
+    let path = path.to_str().ok()?;
+    if path == "HEAD" {
+        Some(format!("refs/remotes/{}/HEAD", remote_name))
+    } else if let Some(branch) = path.strip_prefix("refs/heads/") {
+        Some(format!("refs/remotes/{}/{}", remote_name, branch))
+    } else if path.starts_with("refs/tags/") {
+        Some(path.to_owned())
+    } else {
+        None
+    }
 }
-static FILTER: &[&str] = &["HEAD", "refs/tags", "refs/heads"];
 
 impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
     fn prepare_ls_refs(
@@ -37,20 +355,27 @@ impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
         _features: &mut Vec<(&str, Option<&str>)>,
     ) -> io::Result<LsRefsAction> {
         if server.contains("ls-refs") {
-            arguments.extend(FILTER.iter().map(|r| format!("ref-prefix {}", r).into()));
+            let prefixes: Vec<_> = self.ref_patterns.iter().map(|p| literal_prefix(p)).collect();
+            // an empty prefix matches all refs, but limiting with other prefixes would exclude some of them.
+            if prefixes.iter().all(|p| !p.is_empty()) {
+                arguments.extend(prefixes.into_iter().map(|p| format!("ref-prefix {}", p).into()));
+            }
         }
         Ok(LsRefsAction::Continue)
     }
 
     fn prepare_fetch(
         &mut self,
-        version: transport::Protocol,
+        _version: transport::Protocol,
         _server: &Capabilities,
         _features: &mut Vec<(&str, Option<&str>)>,
-        _refs: &[Ref],
+        refs: &[Ref],
     ) -> io::Result<Action> {
-        if version == transport::Protocol::V1 {
-            self.ref_filter = Some(&FILTER);
+        if !refs.iter().any(|r| self.is_wanted(r.unpack().0)) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("No remote reference matched any of {:?}", self.ref_patterns),
+            ));
         }
         Ok(Action::Continue)
     }
@@ -61,32 +386,50 @@ impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
         arguments: &mut Arguments,
         _previous: Option<&Response>,
     ) -> io::Result<Action> {
+        // V1 always advertises all refs, and V2 ref-prefixes are a superset of what glob patterns match.
         for r in refs {
             let (path, id) = r.unpack();
-            match self.ref_filter {
-                Some(ref_prefixes) => {
-                    if ref_prefixes.iter().any(|prefix| path.starts_with_str(prefix)) {
-                        arguments.want(id);
-                    }
-                }
-                None => arguments.want(id),
+            if self.is_wanted(path) {
+                arguments.want(id);
+            }
+        }
+        // Let the server know what we have to receive only the objects we don't have yet.
+        if let Some((repo, _)) = &self.repository {
+            for id in existing_tips(repo)?.into_iter().collect::<BTreeSet<_>>() {
+                arguments.have(id);
             }
         }
         Ok(Action::Cancel)
     }
 }
 
+const GLOB_CHARS: &[char] = &['*', '?'];
+
+/// Return the portion of `pattern` before its first glob character, usable as `ref-prefix` argument.
+fn literal_prefix(pattern: &str) -> &str {
+    pattern.find(GLOB_CHARS).map_or(pattern, |pos| &pattern[..pos])
+}
+
+/// Patterns without glob characters match refs they are a prefix of, like `refs/heads`. Otherwise, `*` matches any
+/// amount of characters including `/`, and `?` matches exactly one, and the whole ref name has to match.
+fn matches_ref(pattern: &str, path: &BString) -> bool {
+    if !pattern.contains(GLOB_CHARS) {
+        return path.starts_with_str(pattern);
+    }
+    crate::glob_match(pattern.as_bytes(), path.as_slice())
+}
+
 #[cfg(feature = "blocking-client")]
 mod blocking_io {
     #[cfg(feature = "serde1")]
-    use super::JsonOutcome;
-    use super::{CloneDelegate, Context};
+    use super::{write_json_lines, JsonOutcome};
+    use super::{CloneDelegate, Context, IntoRepository};
     use crate::{net, pack::receive::print, OutputFormat};
     use git_repository::{
         object::bstr::{BString, ByteSlice},
         odb::pack,
         protocol,
-        protocol::fetch::{Ref, Response},
+        protocol::fetch::{Negotiated, Ref, Response},
         Progress,
     };
     use std::{io, io::BufRead, path::PathBuf};
@@ -98,21 +441,32 @@ mod blocking_io {
             progress: impl Progress,
             refs: &[Ref],
             _previous: &Response,
+            negotiated: &Negotiated,
         ) -> io::Result<()> {
+            let refs = self.matching_refs(refs);
+            let refs = refs.as_slice();
             let options = pack::bundle::write::Options {
                 thread_limit: self.ctx.thread_limit,
                 index_kind: pack::index::Version::V2,
                 iteration_mode: pack::data::input::Mode::Verify,
+                object_hash: negotiated.object_hash,
             };
-            let outcome = pack::bundle::Bundle::write_to_directory(
+            let thin_pack_lookup = self
+                .thin_pack_lookup()?
+                .map(|lookup| lookup as pack::bundle::write::ThinPackLookupFn);
+            let mut outcome = pack::bundle::Bundle::write_to_directory(
                 input,
                 self.directory.take(),
                 progress,
                 &self.ctx.should_interrupt,
+                thin_pack_lookup,
                 options,
             )
             .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
 
+            self.name_pack_like_git(&mut outcome)?;
+            self.check_connectivity(&outcome, refs)?;
+            let rejected_tags = self.update_refs_in_repository(refs)?;
             if let Some(directory) = self.refs_directory.take() {
                 let assure_dir = |path: &BString| {
                     assert!(!path.starts_with_str("/"), "no ref start with a /, they are relative");
@@ -131,32 +485,41 @@ mod blocking_io {
             }
 
             match self.ctx.format {
-                OutputFormat::Human => drop(print(&mut self.ctx.out, outcome, refs)),
+                OutputFormat::Human => drop(print(&mut self.ctx.out, outcome, refs, &rejected_tags)),
+                #[cfg(feature = "serde1")]
+                OutputFormat::Json => serde_json::to_writer_pretty(
+                    &mut self.ctx.out,
+                    &JsonOutcome::from_outcome_and_refs(&self.url, outcome, refs, &rejected_tags),
+                )?,
                 #[cfg(feature = "serde1")]
-                OutputFormat::Json => {
-                    serde_json::to_writer_pretty(&mut self.ctx.out, &JsonOutcome::from_outcome_and_refs(outcome, refs))?
+                OutputFormat::JsonLines => {
+                    write_json_lines(&mut self.ctx.out, &self.url, outcome, refs, &rejected_tags)?
                 }
             };
             Ok(())
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn receive<P: Progress, W: io::Write>(
         protocol: Option<net::Protocol>,
         url: &str,
         directory: Option<PathBuf>,
         refs_directory: Option<PathBuf>,
+        into: Option<IntoRepository>,
+        ref_patterns: Vec<String>,
         progress: P,
         ctx: Context<W>,
     ) -> anyhow::Result<()> {
-        let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into())?;
-        let delegate = CloneDelegate {
-            ctx,
-            directory,
-            refs_directory,
-            ref_filter: None,
-        };
-        protocol::fetch(transport, delegate, protocol::credentials::helper, progress)?;
+        let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into(), Default::default())?;
+        let delegate = CloneDelegate::new(ctx, url, directory, refs_directory, into, ref_patterns)?;
+        protocol::fetch(
+            transport,
+            delegate,
+            protocol::credentials::helper,
+            progress,
+            protocol::fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+        )?;
         Ok(())
     }
 }
@@ -166,8 +529,8 @@ pub use blocking_io::receive;
 #[cfg(feature = "async-client")]
 mod async_io {
     #[cfg(feature = "serde1")]
-    use super::JsonOutcome;
-    use super::{CloneDelegate, Context};
+    use super::{write_json_lines, JsonOutcome};
+    use super::{CloneDelegate, Context, IntoRepository};
     use crate::{net, pack::receive::print, OutputFormat};
     use async_trait::async_trait;
     use futures_io::AsyncBufRead;
@@ -175,7 +538,7 @@ mod async_io {
         object::bstr::{BString, ByteSlice},
         odb::pack,
         protocol,
-        protocol::fetch::{Ref, Response},
+        protocol::fetch::{Negotiated, Ref, Response},
         Progress,
     };
     use std::{io, io::BufRead, path::PathBuf};
@@ -188,21 +551,31 @@ mod async_io {
             progress: impl Progress,
             refs: &[Ref],
             _previous: &Response,
+            negotiated: &Negotiated,
         ) -> io::Result<()> {
+            let refs = self.matching_refs(refs);
+            let refs = refs.as_slice();
             let options = pack::bundle::write::Options {
                 thread_limit: self.ctx.thread_limit,
                 index_kind: pack::index::Version::V2,
                 iteration_mode: pack::data::input::Mode::Verify,
+                object_hash: negotiated.object_hash,
             };
-            let outcome = pack::Bundle::write_to_directory(
-                futures_lite::io::BlockOn::new(input),
+            let thin_pack_lookup = self.thin_pack_lookup()?;
+            let mut outcome = pack::Bundle::write_to_directory_async(
+                input,
                 self.directory.take(),
                 progress,
-                &self.ctx.should_interrupt,
+                self.ctx.should_interrupt.clone(),
+                thin_pack_lookup,
                 options,
             )
+            .await
             .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
 
+            self.name_pack_like_git(&mut outcome)?;
+            self.check_connectivity(&outcome, refs)?;
+            let rejected_tags = self.update_refs_in_repository(refs)?;
             if let Some(directory) = self.refs_directory.take() {
                 let assure_dir_exists = |path: &BString| {
                     assert!(!path.starts_with_str("/"), "no ref start with a /, they are relative");
@@ -220,40 +593,42 @@ mod async_io {
                 }
             }
 
-            let refs = refs.to_owned();
             match self.ctx.format {
-                OutputFormat::Human => drop(print(&mut self.ctx.out, outcome, &refs)),
+                OutputFormat::Human => drop(print(&mut self.ctx.out, outcome, refs, &rejected_tags)),
                 #[cfg(feature = "serde1")]
                 OutputFormat::Json => serde_json::to_writer_pretty(
                     &mut self.ctx.out,
-                    &JsonOutcome::from_outcome_and_refs(outcome, &refs),
+                    &JsonOutcome::from_outcome_and_refs(&self.url, outcome, refs, &rejected_tags),
                 )?,
+                #[cfg(feature = "serde1")]
+                OutputFormat::JsonLines => {
+                    write_json_lines(&mut self.ctx.out, &self.url, outcome, refs, &rejected_tags)?
+                }
             };
             Ok(())
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn receive<P: Progress, W: io::Write + Send + 'static>(
         protocol: Option<net::Protocol>,
         url: &str,
         directory: Option<PathBuf>,
         refs_directory: Option<PathBuf>,
+        into: Option<IntoRepository>,
+        ref_patterns: Vec<String>,
         progress: P,
         ctx: Context<W>,
     ) -> anyhow::Result<()> {
-        let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into()).await?;
-        let mut delegate = CloneDelegate {
-            ctx,
-            directory,
-            refs_directory,
-            ref_filter: None,
-        };
+        let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into(), Default::default()).await?;
+        let delegate = CloneDelegate::new(ctx, url, directory, refs_directory, into, ref_patterns)?;
         blocking::unblock(move || {
             futures_lite::future::block_on(protocol::fetch(
                 transport,
                 delegate,
                 protocol::credentials::helper,
                 progress,
+                protocol::fetch::FetchConnection::TerminateOnSuccessfulCompletion,
             ))
         })
         .await?;
@@ -285,6 +660,8 @@ impl From<pack::index::write::Outcome> for JsonBundleWriteOutcome {
 
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct JsonOutcome {
+    /// The URL the pack was received from, with its password redacted.
+    pub url: String,
     pub index: JsonBundleWriteOutcome,
     pub pack_kind: pack::data::Version,
 
@@ -292,20 +669,77 @@ pub struct JsonOutcome {
     pub data_path: Option<PathBuf>,
 
     pub refs: Vec<JsonRef>,
+    /// The names of existing tags which changed on the remote but were not updated in the repository received into.
+    pub rejected_tags: Vec<String>,
 }
 
 impl JsonOutcome {
-    pub fn from_outcome_and_refs(v: pack::bundle::write::Outcome, refs: &[Ref]) -> Self {
+    pub fn from_outcome_and_refs(
+        url: &str,
+        v: pack::bundle::write::Outcome,
+        refs: &[Ref],
+        rejected_tags: &[BString],
+    ) -> Self {
         JsonOutcome {
+            url: url.into(),
             index: v.index.into(),
             pack_kind: v.pack_kind,
             index_path: v.index_path,
             data_path: v.data_path,
-            refs: refs.iter().cloned().map(Into::into).collect(),
+            refs: JsonRef::from_refs(refs),
+            rejected_tags: rejected_tags.iter().map(ToString::to_string).collect(),
         }
     }
 }
 
+/// The received pack as reported in `json-lines` output.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct JsonPack {
+    /// The URL the pack was received from, with its password redacted.
+    pub url: String,
+    pub index: JsonBundleWriteOutcome,
+    pub pack_kind: pack::data::Version,
+
+    pub index_path: Option<PathBuf>,
+    pub data_path: Option<PathBuf>,
+}
+
+/// A single record of `json-lines` output, with one line per received reference and rejected tag followed by one line
+/// for the pack.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum JsonLine {
+    Ref(JsonRef),
+    /// The name of an existing tag which changed on the remote but was not updated.
+    RejectedTag(String),
+    Pack(JsonPack),
+}
+
+#[cfg(feature = "serde1")]
+fn write_json_lines(
+    mut out: impl io::Write,
+    url: &str,
+    v: pack::bundle::write::Outcome,
+    refs: &[Ref],
+    rejected_tags: &[BString],
+) -> io::Result<()> {
+    for r in JsonRef::from_refs(refs) {
+        crate::write_json_line(&mut out, &JsonLine::Ref(r))?;
+    }
+    for name in rejected_tags {
+        crate::write_json_line(&mut out, &JsonLine::RejectedTag(name.to_string()))?;
+    }
+    crate::write_json_line(
+        out,
+        &JsonLine::Pack(JsonPack {
+            url: url.into(),
+            index: v.index.into(),
+            pack_kind: v.pack_kind,
+            index_path: v.index_path,
+            data_path: v.data_path,
+        }),
+    )
+}
+
 fn print_hash_and_path(out: &mut impl io::Write, name: &str, id: ObjectId, path: Option<PathBuf>) -> io::Result<()> {
     match path {
         Some(path) => writeln!(out, "{}: {} ({})", name, id, path.display()),
@@ -313,10 +747,18 @@ fn print_hash_and_path(out: &mut impl io::Write, name: &str, id: ObjectId, path:
     }
 }
 
-fn print(out: &mut impl io::Write, res: pack::bundle::write::Outcome, refs: &[Ref]) -> io::Result<()> {
+fn print(
+    out: &mut impl io::Write,
+    res: pack::bundle::write::Outcome,
+    refs: &[Ref],
+    rejected_tags: &[BString],
+) -> io::Result<()> {
     print_hash_and_path(out, "index", res.index.index_hash, res.index_path)?;
     print_hash_and_path(out, "pack", res.index.data_hash, res.data_path)?;
     writeln!(out)?;
-    crate::remote::refs::print(out, refs)?;
+    crate::remote::refs::print(&mut *out, refs)?;
+    for name in rejected_tags {
+        writeln!(out, "rejected {} (would clobber existing tag)", name)?;
+    }
     Ok(())
 }
@@ -1,15 +1,15 @@
 use crate::OutputFormat;
 use anyhow::{anyhow, Context as AnyhowContext, Result};
-use bytesize::ByteSize;
 use git_repository::{
     hash::ObjectId,
     object, odb,
     odb::{pack, pack::index},
-    progress, Progress,
+    progress::{self, format},
+    Progress,
 };
 use std::{
     io,
-    path::Path,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::{atomic::AtomicBool, Arc},
 };
@@ -65,6 +65,16 @@ pub struct Context<W1: io::Write, W2: io::Write> {
     pub mode: index::verify::Mode,
     pub algorithm: Algorithm,
     pub should_interrupt: Arc<AtomicBool>,
+    /// If set, write the entry which failed verification to this file, to verify only this entry later with `retry_from`.
+    pub failure_report: Option<PathBuf>,
+    /// If set, verify only the entries listed in the failure report at this path instead of the whole pack.
+    pub retry_from: Option<PathBuf>,
+}
+
+/// The entries of a pack which failed verification, as written to and read from a failure report.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct FailureReport {
+    pub failures: Vec<index::verify::Failure>,
 }
 
 impl Default for Context<Vec<u8>, Vec<u8>> {
@@ -77,6 +87,8 @@ impl Default for Context<Vec<u8>, Vec<u8>> {
             out: Vec::new(),
             err: Vec::new(),
             should_interrupt: Default::default(),
+            failure_report: None,
+            retry_from: None,
         }
     }
 }
@@ -113,6 +125,8 @@ pub fn pack_or_pack_index<W1, W2>(
         thread_limit,
         algorithm,
         should_interrupt,
+        failure_report,
+        retry_from,
     }: Context<W1, W2>,
 ) -> Result<(ObjectId, Option<index::traverse::Outcome>)>
 where
@@ -128,6 +142,9 @@ where
     })?;
     let res = match ext {
         "pack" => {
+            if retry_from.is_some() {
+                return Err(anyhow!("Retrying failed entries requires the pack index file"));
+            }
             let pack = odb::pack::data::File::at(path).with_context(|| "Could not open pack file")?;
             pack.verify_checksum(
                 progress::DoOrDiscard::from(progress).add_child("Sha1 of pack"),
@@ -164,14 +181,85 @@ where
                 }
             };
 
-            idx.verify_integrity(
-                pack.as_ref().map(|p| (p, mode, algorithm.into(), cache)),
-                thread_limit,
-                progress,
-                should_interrupt,
-            )
-            .map(|(a, b, _)| (a, b))
-            .with_context(|| "Verification failure")?
+            if let Some(report_path) = retry_from {
+                let pack = pack.ok_or_else(|| {
+                    anyhow!(
+                        "Cannot retry verification without the pack file at '{}'",
+                        packfile_path.display()
+                    )
+                })?;
+                let entries = read_failure_report(&report_path)?.failures;
+                let num_entries = entries.len();
+                let failures = idx.verify_entries(
+                    &pack,
+                    entries,
+                    mode,
+                    cache(),
+                    progress::DoOrDiscard::from(progress),
+                    &should_interrupt,
+                )?;
+                let failed_entries: Vec<_> = failures.iter().map(|(failure, _)| *failure).collect();
+                for (_, reason) in failures {
+                    writeln!(err, "{:#}", anyhow::Error::from(reason))?;
+                }
+                if let Some(report_path) = failure_report {
+                    write_failure_report(
+                        &report_path,
+                        &FailureReport {
+                            failures: failed_entries.clone(),
+                        },
+                    )?;
+                }
+                if !failed_entries.is_empty() {
+                    return Err(anyhow!(
+                        "{} of {} entries still fail verification",
+                        failed_entries.len(),
+                        num_entries
+                    ));
+                }
+                (idx.index_checksum(), None)
+            } else {
+                match idx.verify_integrity(
+                    pack.as_ref().map(|p| (p, mode, algorithm.into(), cache)),
+                    thread_limit,
+                    progress,
+                    should_interrupt,
+                ) {
+                    Ok((id, outcome, _)) => (id, outcome),
+                    Err(verify_err) => {
+                        if let Some(report_path) = failure_report {
+                            let failure = match (idx.failure_of(&verify_err), pack.as_ref()) {
+                                (Some(failure), _) => Some(failure),
+                                // The interrupt flag may have been set by the failed verification, hence we use our own.
+                                (None, Some(pack)) => idx
+                                    .locate_failure(
+                                        pack,
+                                        mode,
+                                        cache,
+                                        thread_limit,
+                                        None::<progress::Discard>,
+                                        Default::default(),
+                                    )
+                                    .unwrap_or(None),
+                                (None, None) => None,
+                            };
+                            match failure {
+                                Some(failure) => write_failure_report(
+                                    &report_path,
+                                    &FailureReport {
+                                        failures: vec![failure],
+                                    },
+                                )?,
+                                None => writeln!(
+                                    err,
+                                    "No failure report was written as the failure doesn't concern a single entry"
+                                )?,
+                            }
+                        }
+                        return Err(anyhow::Error::from(verify_err).context("Verification failure"));
+                    }
+                }
+            }
         }
         ext => return Err(anyhow!("Unknown extension {:?}, expecting 'idx' or 'pack'", ext)),
     };
@@ -181,12 +269,49 @@ where
             Some(OutputFormat::Human) => drop(print_statistics(&mut out, stats)),
             #[cfg(feature = "serde1")]
             Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, stats)?,
+            #[cfg(feature = "serde1")]
+            Some(OutputFormat::JsonLines) => crate::write_json_line(out, stats)?,
             _ => {}
         };
     }
     Ok(res)
 }
 
+fn read_failure_report(path: &Path) -> Result<FailureReport> {
+    #[cfg(feature = "serde1")]
+    {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Could not open failure report at '{}'", path.display()))?;
+        serde_json::from_reader(io::BufReader::new(file))
+            .with_context(|| format!("Could not read failure report at '{}'", path.display()))
+    }
+    #[cfg(not(feature = "serde1"))]
+    {
+        Err(anyhow!(
+            "Cannot read failure report at '{}' without the 'serde1' feature",
+            path.display()
+        ))
+    }
+}
+
+fn write_failure_report(path: &Path, report: &FailureReport) -> Result<()> {
+    #[cfg(feature = "serde1")]
+    {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Could not create failure report at '{}'", path.display()))?;
+        serde_json::to_writer_pretty(io::BufWriter::new(file), report)?;
+        Ok(())
+    }
+    #[cfg(not(feature = "serde1"))]
+    {
+        let _ = report;
+        Err(anyhow!(
+            "Cannot write failure report to '{}' without the 'serde1' feature",
+            path.display()
+        ))
+    }
+}
+
 fn print_statistics(out: &mut impl io::Write, stats: &index::traverse::Outcome) -> io::Result<()> {
     writeln!(out, "objects per delta chain length")?;
     let mut chain_length_to_object: Vec<_> = stats.objects_per_chain_length.iter().map(|(a, b)| (*a, *b)).collect();
@@ -223,10 +348,10 @@ fn print_statistics(out: &mut impl io::Write, stats: &index::traverse::Outcome)
     #[rustfmt::skip]
     writeln!(
         out, "\t{:<width$}: {}\n\t{:<width$}: {}\n\t{:<width$}: {}\n\t{:<width$}: {}",
-        "compressed entries size", ByteSize(stats.total_compressed_entries_size),
-        "decompressed entries size", ByteSize(stats.total_decompressed_entries_size),
-        "total object size", ByteSize(stats.total_object_size),
-        "pack size", ByteSize(stats.pack_size),
+        "compressed entries size", format::bytes(stats.total_compressed_entries_size),
+        "decompressed entries size", format::bytes(stats.total_decompressed_entries_size),
+        "total object size", format::bytes(stats.total_object_size),
+        "pack size", format::bytes(stats.pack_size),
         width = width
     )?;
     #[rustfmt::skip]
@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use git_repository::{hash::ObjectId, interrupt, odb, prelude::*, refs::file::log::expire};
+use std::{
+    collections::{BTreeSet, HashSet},
+    io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+/// The default amount of days after which reflog entries expire, matching `gc.reflogExpire`.
+pub const DEFAULT_EXPIRE_DAYS: u32 = 90;
+/// The default amount of days after which unreachable reflog entries expire, matching `gc.reflogExpireUnreachable`.
+pub const DEFAULT_EXPIRE_UNREACHABLE_DAYS: u32 = 30;
+
+pub struct Context<W> {
+    /// Remove entries older than this amount of days, or keep them if `None`.
+    pub expire_days: Option<u32>,
+    /// Remove entries older than this amount of days that aren't reachable from the reference tip, or keep them if `None`.
+    pub expire_unreachable_days: Option<u32>,
+    /// Where to write information about the expired entries
+    pub out: W,
+}
+
+/// Expire the reflogs of the references named `refs` in the repository at `repository`, or of all references if
+/// `refs` is empty.
+pub fn expire<W: io::Write>(
+    repository: impl AsRef<Path>,
+    refs: Vec<String>,
+    Context {
+        expire_days,
+        expire_unreachable_days,
+        mut out,
+    }: Context<W>,
+) -> Result<()> {
+    let repo = git_repository::discover(repository)?;
+    let refs = if refs.is_empty() { all_refs(&repo)? } else { refs };
+
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
+    let cutoff = |days: u32| {
+        now.checked_sub(Duration::from_secs(days as u64 * 24 * 60 * 60))
+            .map(|d| d.as_secs() as u32)
+    };
+    let policy = expire::Policy {
+        expire: expire_days.and_then(cutoff),
+        expire_unreachable: expire_unreachable_days.and_then(cutoff),
+    };
+
+    for name in refs {
+        let reachable = match policy.expire_unreachable {
+            Some(_) => reachable_commits(&repo, &name)?,
+            None => HashSet::new(),
+        };
+        match repo
+            .refs
+            .expire_reflog(name.as_str(), policy, Default::default(), |id| reachable.contains(id))?
+        {
+            Some(outcome) => writeln!(
+                out,
+                "{}: removed {} of {} entries",
+                name,
+                outcome.removed,
+                outcome.kept + outcome.removed
+            )?,
+            None => writeln!(out, "{}: no reflog", name)?,
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn all_refs(repo: &git_repository::Repository) -> Result<Vec<String>> {
+    let mut names = BTreeSet::new();
+    names.insert("HEAD".to_string());
+    for reference in repo.refs.loose_iter()? {
+        names.insert(reference?.relative_path().to_string_lossy().into_owned());
+    }
+    if let Some(packed) = repo.refs.packed()? {
+        for reference in packed.iter()? {
+            names.insert(String::from_utf8_lossy(reference?.full_name.as_ref()).into_owned());
+        }
+    }
+    Ok(names.into_iter().collect())
+}
+
+fn reachable_commits(repo: &git_repository::Repository, name: &str) -> Result<HashSet<ObjectId>> {
+    let tip = match repo.refs.find_one(name)? {
+        Some(mut reference) => reference.peel_to_id_in_place()?.to_owned(),
+        None => return Ok(HashSet::new()),
+    };
+    let mut pack_cache = odb::pack::cache::Never;
+    let mut reachable = HashSet::new();
+    for id in interrupt::Iter::new(
+        tip.ancestors_iter(|oid, buf| repo.odb.find_existing_commit_iter(oid, buf, &mut pack_cache).ok()),
+        || anyhow!("Cancelled by user"),
+    ) {
+        reachable.insert(id??);
+    }
+    Ok(reachable)
+}
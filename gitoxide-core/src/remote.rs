@@ -1,6 +1,10 @@
+pub mod object_info;
+pub mod ref_map;
+
 pub mod refs {
     use crate::OutputFormat;
     use git_repository::{
+        object::bstr::BString,
         protocol,
         protocol::{
             fetch::{Action, Arguments, Ref, Response},
@@ -13,8 +17,8 @@ pub mod refs {
     use std::io;
 
     #[derive(Default)]
-    struct LsRemotes {
-        refs: Vec<Ref>,
+    pub(super) struct LsRemotes {
+        pub(super) refs: Vec<Ref>,
     }
 
     impl protocol::fetch::DelegateBlocking for LsRemotes {
@@ -41,13 +45,15 @@ pub mod refs {
 
     #[cfg(feature = "async-client")]
     mod async_io {
+        #[cfg(feature = "serde1")]
+        use super::JsonRefList;
         use super::{Context, LsRemotes};
         use crate::{net, remote::refs::print, OutputFormat};
         use async_trait::async_trait;
         use futures_io::AsyncBufRead;
         use git_repository::{
             protocol,
-            protocol::fetch::{Ref, Response},
+            protocol::fetch::{Negotiated, Ref, Response},
             Progress,
         };
         use std::io;
@@ -60,6 +66,7 @@ pub mod refs {
                 progress: impl Progress,
                 refs: &[Ref],
                 previous: &Response,
+                _negotiated: &Negotiated,
             ) -> io::Result<()> {
                 unreachable!("not called for ls-refs")
             }
@@ -72,7 +79,8 @@ pub mod refs {
             ctx: Context<impl io::Write + Send + 'static>,
         ) -> anyhow::Result<()> {
             let url = url.to_owned();
-            let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into()).await?;
+            let transport =
+                net::connect(url.as_bytes(), protocol.unwrap_or_default().into(), Default::default()).await?;
             blocking::unblock(
                 // `blocking` really needs a way to unblock futures, which is what it does internally anyway.
                 // Both fetch() needs unblocking as it executes blocking code within the future, and the other
@@ -80,14 +88,26 @@ pub mod refs {
                 move || {
                     futures_lite::future::block_on(async move {
                         let mut delegate = LsRemotes::default();
-                        protocol::fetch(transport, &mut delegate, protocol::credentials::helper, progress).await?;
+                        protocol::fetch(
+                            transport,
+                            &mut delegate,
+                            protocol::credentials::helper,
+                            progress,
+                            protocol::fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+                        )
+                        .await?;
 
                         match ctx.format {
                             OutputFormat::Human => drop(print(ctx.out, &delegate.refs)),
                             #[cfg(feature = "serde1")]
                             OutputFormat::Json => serde_json::to_writer_pretty(
                                 ctx.out,
-                                &delegate.refs.into_iter().map(JsonRef::from).collect::<Vec<_>>(),
+                                &JsonRefList::from_url_and_refs(url.as_bytes(), delegate.refs)?,
+                            )?,
+                            #[cfg(feature = "serde1")]
+                            OutputFormat::JsonLines => crate::write_json_line(
+                                ctx.out,
+                                &JsonRefList::from_url_and_refs(url.as_bytes(), delegate.refs)?,
                             )?,
                         }
                         Ok(())
@@ -103,12 +123,12 @@ pub mod refs {
     #[cfg(feature = "blocking-client")]
     mod blocking_io {
         #[cfg(feature = "serde1")]
-        use super::JsonRef;
+        use super::JsonRefList;
         use super::{print, Context, LsRemotes};
         use crate::{net, OutputFormat};
         use git_repository::{
             protocol,
-            protocol::fetch::{Ref, Response},
+            protocol::fetch::{Negotiated, Ref, Response},
             Progress,
         };
         use std::io;
@@ -120,6 +140,7 @@ pub mod refs {
                 _progress: impl Progress,
                 _refs: &[Ref],
                 _previous: &Response,
+                _negotiated: &Negotiated,
             ) -> io::Result<()> {
                 unreachable!("not called for ls-refs")
             }
@@ -131,17 +152,27 @@ pub mod refs {
             progress: impl Progress,
             ctx: Context<impl io::Write>,
         ) -> anyhow::Result<()> {
-            let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into())?;
+            let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into(), Default::default())?;
             let mut delegate = LsRemotes::default();
-            protocol::fetch(transport, &mut delegate, protocol::credentials::helper, progress)?;
+            protocol::fetch(
+                transport,
+                &mut delegate,
+                protocol::credentials::helper,
+                progress,
+                protocol::fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+            )?;
 
             match ctx.format {
                 OutputFormat::Human => drop(print(ctx.out, &delegate.refs)),
                 #[cfg(feature = "serde1")]
                 OutputFormat::Json => serde_json::to_writer_pretty(
                     ctx.out,
-                    &delegate.refs.into_iter().map(JsonRef::from).collect::<Vec<_>>(),
+                    &JsonRefList::from_url_and_refs(url.as_bytes(), delegate.refs)?,
                 )?,
+                #[cfg(feature = "serde1")]
+                OutputFormat::JsonLines => {
+                    crate::write_json_line(ctx.out, &JsonRefList::from_url_and_refs(url.as_bytes(), delegate.refs)?)?
+                }
             };
             Ok(())
         }
@@ -157,6 +188,7 @@ pub mod refs {
 
     #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
     pub enum JsonRef {
+        /// A reference pointing to an annotated `tag` object, which is peeled to `object`.
         Peeled {
             path: String,
             tag: String,
@@ -168,30 +200,96 @@ pub mod refs {
         },
         Symbolic {
             path: String,
+            /// The reference the symbolic reference points to directly.
             target: String,
+            /// All references followed to resolve the symbolic reference, starting with `target` and ending with the
+            /// reference pointing to `object`. It's longer than one only if `target` is symbolic itself.
+            chain: Vec<String>,
             object: String,
+            /// The object `object` peels to, if the last reference of the `chain` points to an annotated tag.
+            peeled: Option<String>,
         },
     }
 
-    impl From<Ref> for JsonRef {
-        fn from(value: Ref) -> Self {
-            match value {
-                Ref::Direct { path, object } => JsonRef::Direct {
-                    path: path.to_string(),
-                    object: object.to_string(),
-                },
-                Ref::Symbolic { path, target, object } => JsonRef::Symbolic {
-                    path: path.to_string(),
-                    target: target.to_string(),
-                    object: object.to_string(),
-                },
-                Ref::Peeled { path, tag, object } => JsonRef::Peeled {
-                    path: path.to_string(),
-                    tag: tag.to_string(),
-                    object: object.to_string(),
-                },
+    impl JsonRef {
+        /// Convert all `refs`, using all of them to resolve the chains of symbolic references.
+        pub fn from_refs(refs: &[Ref]) -> Vec<Self> {
+            refs.iter()
+                .map(|r| match r {
+                    Ref::Direct { path, object } => JsonRef::Direct {
+                        path: path.to_string(),
+                        object: object.to_string(),
+                    },
+                    Ref::Symbolic { path, target, object } => {
+                        let chain = symref_chain(refs, target);
+                        let peeled = chain.last().and_then(|last| {
+                            refs.iter().find_map(|r| match r {
+                                Ref::Peeled { path, object, .. } if path == *last => Some(object.to_string()),
+                                _ => None,
+                            })
+                        });
+                        JsonRef::Symbolic {
+                            path: path.to_string(),
+                            target: target.to_string(),
+                            chain: chain.into_iter().map(ToString::to_string).collect(),
+                            object: object.to_string(),
+                            peeled,
+                        }
+                    }
+                    Ref::Peeled { path, tag, object } => JsonRef::Peeled {
+                        path: path.to_string(),
+                        tag: tag.to_string(),
+                        object: object.to_string(),
+                    },
+                })
+                .collect()
+        }
+    }
+
+    /// The JSON representation of all references advertised by a remote.
+    ///
+    /// Note that this is an object, whereas previously only the plain array of `refs` was serialized.
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+    pub struct JsonRefList {
+        /// The URL the references were obtained from, with its password redacted.
+        pub url: String,
+        /// The reference the remote `HEAD` resolves to, commonly its default branch, if it was advertised.
+        pub head: Option<String>,
+        pub refs: Vec<JsonRef>,
+    }
+
+    impl JsonRefList {
+        pub fn from_url_and_refs(url: &[u8], refs: Vec<Ref>) -> anyhow::Result<Self> {
+            Ok(JsonRefList {
+                url: git_repository::url::parse(url)?.to_string(),
+                head: refs.iter().find_map(|r| match r {
+                    Ref::Symbolic { path, target, .. } if path == "HEAD" => {
+                        symref_chain(&refs, target).last().map(ToString::to_string)
+                    }
+                    _ => None,
+                }),
+                refs: JsonRef::from_refs(&refs),
+            })
+        }
+    }
+
+    /// Follow `target` through the symbolic references in `refs`, returning all references on the way including `target`.
+    fn symref_chain<'a>(refs: &'a [Ref], mut target: &'a BString) -> Vec<&'a BString> {
+        let mut chain = vec![target];
+        // There can't be more steps than references, unless there is a cycle.
+        while chain.len() <= refs.len() {
+            match refs.iter().find_map(|r| match r {
+                Ref::Symbolic { path, target: next, .. } if path == target => Some(next),
+                _ => None,
+            }) {
+                Some(next) => {
+                    chain.push(next);
+                    target = next;
+                }
+                None => break,
             }
         }
+        chain
     }
 
     pub(crate) fn print(mut out: impl io::Write, refs: &[Ref]) -> io::Result<()> {
@@ -206,7 +304,11 @@ pub mod refs {
                     "{} {} symref-target:{}",
                     object.to_sha1_hex_string(),
                     path,
-                    target
+                    symref_chain(refs, target)
+                        .into_iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" -> ")
                 ),
             }?;
         }
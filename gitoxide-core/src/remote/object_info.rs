@@ -0,0 +1,167 @@
+use git_repository::{hash::ObjectId, protocol::fetch::object_info::Size};
+use std::io;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+fn parse_ids(ids: &[String]) -> anyhow::Result<Vec<ObjectId>> {
+    ids.iter()
+        .map(|id| {
+            ObjectId::from_hex(id.as_bytes())
+                .map_err(|err| anyhow::Error::new(err).context(format!("Invalid object id '{}'", id)))
+        })
+        .collect()
+}
+
+fn protocol_v2_required(actual: git_repository::protocol::transport::Protocol) -> anyhow::Error {
+    anyhow::anyhow!(
+        "The server responded with protocol {:?}, but the 'object-info' command needs protocol version 2",
+        actual
+    )
+}
+
+#[cfg(feature = "async-client")]
+mod async_io {
+    #[cfg(feature = "serde1")]
+    use super::JsonObjectInfo;
+    use super::{parse_ids, print, protocol_v2_required};
+    use crate::{net, remote::refs::Context, OutputFormat};
+    use git_repository::{protocol, Progress};
+    use std::io;
+
+    pub async fn query(
+        url: &str,
+        ids: Vec<String>,
+        mut progress: impl Progress,
+        ctx: Context<impl io::Write + Send + 'static>,
+    ) -> anyhow::Result<()> {
+        let ids = parse_ids(&ids)?;
+        let url = url.to_owned();
+        let mut transport = net::connect(url.as_bytes(), net::Protocol::V2.into(), Default::default()).await?;
+        blocking::unblock(move || {
+            futures_lite::future::block_on(async move {
+                let outcome = protocol::fetch::handshake(
+                    &mut transport,
+                    protocol::credentials::helper,
+                    Vec::new(),
+                    &mut progress,
+                )
+                .await?;
+                let sizes = if outcome.server_protocol_version == protocol::transport::Protocol::V2 {
+                    progress.step();
+                    progress.set_name("object info");
+                    protocol::fetch::object_info(&mut transport, &outcome.capabilities, ids)
+                        .await
+                        .map_err(Into::into)
+                } else {
+                    Err(protocol_v2_required(outcome.server_protocol_version))
+                };
+                // Let the server know we are done, even on error, as it would wait for more input otherwise.
+                protocol::fetch::indicate_end_of_interaction(&mut transport).await?;
+                let sizes = sizes?;
+
+                match ctx.format {
+                    OutputFormat::Human => drop(print(ctx.out, &sizes)),
+                    #[cfg(feature = "serde1")]
+                    OutputFormat::Json => serde_json::to_writer_pretty(
+                        ctx.out,
+                        &JsonObjectInfo::from_url_and_sizes(url.as_bytes(), sizes)?,
+                    )?,
+                    #[cfg(feature = "serde1")]
+                    OutputFormat::JsonLines => {
+                        crate::write_json_line(ctx.out, &JsonObjectInfo::from_url_and_sizes(url.as_bytes(), sizes)?)?
+                    }
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+}
+#[cfg(feature = "async-client")]
+pub use self::async_io::query;
+
+#[cfg(feature = "blocking-client")]
+mod blocking_io {
+    #[cfg(feature = "serde1")]
+    use super::JsonObjectInfo;
+    use super::{parse_ids, print, protocol_v2_required};
+    use crate::{net, remote::refs::Context, OutputFormat};
+    use git_repository::{protocol, Progress};
+    use std::io;
+
+    pub fn query(
+        url: &str,
+        ids: Vec<String>,
+        mut progress: impl Progress,
+        ctx: Context<impl io::Write>,
+    ) -> anyhow::Result<()> {
+        let ids = parse_ids(&ids)?;
+        let mut transport = net::connect(url.as_bytes(), net::Protocol::V2.into(), Default::default())?;
+        let outcome =
+            protocol::fetch::handshake(&mut transport, protocol::credentials::helper, Vec::new(), &mut progress)?;
+        let sizes = if outcome.server_protocol_version == protocol::transport::Protocol::V2 {
+            progress.step();
+            progress.set_name("object info");
+            protocol::fetch::object_info(&mut transport, &outcome.capabilities, ids).map_err(Into::into)
+        } else {
+            Err(protocol_v2_required(outcome.server_protocol_version))
+        };
+        // Let the server know we are done, even on error, as it would wait for more input otherwise.
+        protocol::fetch::indicate_end_of_interaction(&mut transport)?;
+        let sizes = sizes?;
+
+        match ctx.format {
+            OutputFormat::Human => drop(print(ctx.out, &sizes)),
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(ctx.out, &JsonObjectInfo::from_url_and_sizes(url.as_bytes(), sizes)?)?
+            }
+            #[cfg(feature = "serde1")]
+            OutputFormat::JsonLines => {
+                crate::write_json_line(ctx.out, &JsonObjectInfo::from_url_and_sizes(url.as_bytes(), sizes)?)?
+            }
+        };
+        Ok(())
+    }
+}
+#[cfg(feature = "blocking-client")]
+pub use blocking_io::query;
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct JsonObject {
+    pub id: String,
+    /// The size of the object in bytes, or `None` if the remote doesn't have it.
+    pub size: Option<u64>,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct JsonObjectInfo {
+    /// The URL the object information was obtained from, with its password redacted.
+    pub url: String,
+    pub objects: Vec<JsonObject>,
+}
+
+impl JsonObjectInfo {
+    pub fn from_url_and_sizes(url: &[u8], sizes: Vec<(ObjectId, Size)>) -> anyhow::Result<Self> {
+        Ok(JsonObjectInfo {
+            url: git_repository::url::parse(url)?.to_string(),
+            objects: sizes
+                .into_iter()
+                .map(|(id, size)| JsonObject {
+                    id: id.to_string(),
+                    size,
+                })
+                .collect(),
+        })
+    }
+}
+
+fn print(mut out: impl io::Write, sizes: &[(ObjectId, Size)]) -> io::Result<()> {
+    for (id, size) in sizes {
+        match size {
+            Some(size) => writeln!(&mut out, "{} {}", id, size)?,
+            None => writeln!(&mut out, "{} missing", id)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use git_repository::{
+    hash::ObjectId,
+    object::bstr::{BString, ByteSlice},
+    protocol::fetch::Ref,
+    refspec::{self, match_group, RefSpec},
+};
+use std::io;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+/// The refspec git configures for the `origin` remote when cloning.
+pub const DEFAULT_REFSPEC: &str = "+refs/heads/*:refs/remotes/origin/*";
+
+/// A remote reference along with the local reference it would be written to during a fetch.
+pub struct Mapping {
+    /// The full name of the reference on the remote.
+    pub remote: BString,
+    /// The object the remote reference points to, which is the tag object for annotated tags.
+    pub object: ObjectId,
+    /// The local reference to update, or `None` if the object is fetched without being stored in a reference.
+    pub local: Option<BString>,
+    /// If true, the local reference would be updated even if that isn't a fast-forward.
+    pub force: bool,
+}
+
+/// Map all `refs` with `specs`, producing one mapping per matching pair of reference and refspec, while skipping
+/// references excluded by negative refspecs.
+pub fn map_refs(refs: &[Ref], specs: &[RefSpec]) -> Vec<Mapping> {
+    let mut out = Vec::new();
+    for r in refs {
+        let (path, object) = match r {
+            Ref::Direct { path, object } | Ref::Symbolic { path, object, .. } => (path, object),
+            Ref::Peeled { path, tag, .. } => (path, tag),
+        };
+        out.extend(
+            match_group::source_to_destination(specs, path.as_bstr())
+                .into_iter()
+                .map(|mapping| Mapping {
+                    remote: path.to_owned(),
+                    object: *object,
+                    local: mapping.name,
+                    force: mapping.spec.mode() == refspec::Mode::Force,
+                }),
+        );
+    }
+    out
+}
+
+fn parse_refspecs(refspecs: &[String]) -> anyhow::Result<Vec<RefSpec>> {
+    let parse = |spec: &str| refspec::parse(spec.as_bytes().as_bstr(), refspec::Operation::Fetch);
+    if refspecs.is_empty() {
+        Ok(vec![parse(DEFAULT_REFSPEC)?])
+    } else {
+        refspecs
+            .iter()
+            .map(|spec| {
+                parse(spec).map_err(|err| anyhow::Error::new(err).context(format!("Invalid refspec '{}'", spec)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "async-client")]
+mod async_io {
+    #[cfg(feature = "serde1")]
+    use super::JsonRefMap;
+    use super::{map_refs, parse_refspecs, print};
+    use crate::{
+        net,
+        remote::refs::{Context, LsRemotes},
+        OutputFormat,
+    };
+    use git_repository::{protocol, Progress};
+    use std::io;
+
+    pub async fn map(
+        protocol: Option<net::Protocol>,
+        url: &str,
+        refspecs: Vec<String>,
+        progress: impl Progress,
+        ctx: Context<impl io::Write + Send + 'static>,
+    ) -> anyhow::Result<()> {
+        let specs = parse_refspecs(&refspecs)?;
+        let url = url.to_owned();
+        let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into(), Default::default()).await?;
+        blocking::unblock(move || {
+            futures_lite::future::block_on(async move {
+                let mut delegate = LsRemotes::default();
+                protocol::fetch(
+                    transport,
+                    &mut delegate,
+                    protocol::credentials::helper,
+                    progress,
+                    protocol::fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+                )
+                .await?;
+
+                let mappings = map_refs(&delegate.refs, &specs);
+                match ctx.format {
+                    OutputFormat::Human => drop(print(ctx.out, &mappings)),
+                    #[cfg(feature = "serde1")]
+                    OutputFormat::Json => serde_json::to_writer_pretty(
+                        ctx.out,
+                        &JsonRefMap::from_url_and_mappings(url.as_bytes(), mappings)?,
+                    )?,
+                    #[cfg(feature = "serde1")]
+                    OutputFormat::JsonLines => {
+                        crate::write_json_line(ctx.out, &JsonRefMap::from_url_and_mappings(url.as_bytes(), mappings)?)?
+                    }
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+}
+#[cfg(feature = "async-client")]
+pub use self::async_io::map;
+
+#[cfg(feature = "blocking-client")]
+mod blocking_io {
+    #[cfg(feature = "serde1")]
+    use super::JsonRefMap;
+    use super::{map_refs, parse_refspecs, print};
+    use crate::{
+        net,
+        remote::refs::{Context, LsRemotes},
+        OutputFormat,
+    };
+    use git_repository::{protocol, Progress};
+    use std::io;
+
+    pub fn map(
+        protocol: Option<net::Protocol>,
+        url: &str,
+        refspecs: Vec<String>,
+        progress: impl Progress,
+        ctx: Context<impl io::Write>,
+    ) -> anyhow::Result<()> {
+        let specs = parse_refspecs(&refspecs)?;
+        let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into(), Default::default())?;
+        let mut delegate = LsRemotes::default();
+        protocol::fetch(
+            transport,
+            &mut delegate,
+            protocol::credentials::helper,
+            progress,
+            protocol::fetch::FetchConnection::TerminateOnSuccessfulCompletion,
+        )?;
+
+        let mappings = map_refs(&delegate.refs, &specs);
+        match ctx.format {
+            OutputFormat::Human => drop(print(ctx.out, &mappings)),
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(ctx.out, &JsonRefMap::from_url_and_mappings(url.as_bytes(), mappings)?)?
+            }
+            #[cfg(feature = "serde1")]
+            OutputFormat::JsonLines => {
+                crate::write_json_line(ctx.out, &JsonRefMap::from_url_and_mappings(url.as_bytes(), mappings)?)?
+            }
+        };
+        Ok(())
+    }
+}
+#[cfg(feature = "blocking-client")]
+pub use blocking_io::map;
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct JsonMapping {
+    pub remote: String,
+    pub object: String,
+    pub local: Option<String>,
+    pub force: bool,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct JsonRefMap {
+    /// The URL the references were obtained from, with its password redacted.
+    pub url: String,
+    pub mappings: Vec<JsonMapping>,
+}
+
+impl JsonRefMap {
+    pub fn from_url_and_mappings(url: &[u8], mappings: Vec<Mapping>) -> anyhow::Result<Self> {
+        Ok(JsonRefMap {
+            url: git_repository::url::parse(url)?.to_string(),
+            mappings: mappings.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+impl From<Mapping> for JsonMapping {
+    fn from(value: Mapping) -> Self {
+        JsonMapping {
+            remote: value.remote.to_string(),
+            object: value.object.to_string(),
+            local: value.local.map(|local| local.to_string()),
+            force: value.force,
+        }
+    }
+}
+
+fn print(mut out: impl io::Write, mappings: &[Mapping]) -> io::Result<()> {
+    for mapping in mappings {
+        write!(&mut out, "{} {}", mapping.object.to_sha1_hex_string(), mapping.remote)?;
+        if let Some(local) = &mapping.local {
+            write!(&mut out, " -> {}", local)?;
+        }
+        if mapping.force {
+            write!(&mut out, " (forced)")?;
+        }
+        writeln!(&mut out)?;
+    }
+    Ok(())
+}
@@ -1,6 +1,15 @@
 use anyhow::{Context as AnyhowContext, Result};
-use std::path::PathBuf;
+use git_config::file::GitConfig;
+use git_repository::{actor, Repository};
+use std::{convert::TryFrom, path::PathBuf};
 
 pub fn init(directory: Option<PathBuf>) -> Result<()> {
     git_repository::init::repository(directory.unwrap_or_default()).with_context(|| "Repository initialization failed")
 }
+
+/// The identity to use in reflog entries, taken from the environment or the repository configuration like git does.
+pub(crate) fn committer(repo: &Repository) -> Result<actor::Signature, actor::identity::Error> {
+    let config = std::fs::read(repo.common_dir().join("config")).unwrap_or_default();
+    let config = GitConfig::try_from(&config).unwrap_or_default();
+    actor::Signature::from_env_or_config(actor::identity::Role::Committer, &config)
+}
@@ -0,0 +1,242 @@
+use crate::OutputFormat;
+use anyhow::{Context as AnyhowContext, Result};
+use git_repository::{hash::ObjectId, progress::format, Progress};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    io,
+    path::Path,
+};
+
+/// The amount of objects to list in [`Statistics::biggest_objects`] by default.
+pub const DEFAULT_BIGGEST_OBJECTS: usize = 10;
+
+pub struct Context<W> {
+    /// The amount of biggest objects to list
+    pub biggest_objects: usize,
+    pub format: OutputFormat,
+    /// Where to write the statistics
+    pub out: W,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct LooseObjects {
+    pub count: u64,
+    pub size_on_disk: u64,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pack {
+    /// The path to the pack data file
+    pub path: String,
+    pub num_objects: u32,
+    pub data_size: u64,
+    pub index_size: u64,
+    /// If true, the pack was received from a promisor remote in a partial clone.
+    pub is_promisor: bool,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Object {
+    pub id: String,
+    /// The size of the object as stored, which is compressed and possibly a delta for packed objects.
+    pub size_on_disk: u64,
+    pub is_packed: bool,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reflog {
+    pub name: String,
+    pub entries: usize,
+    pub size: u64,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommitGraph {
+    pub num_commits: u32,
+}
+
+/// A summary of the shape of a repository.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Statistics {
+    pub loose_objects: LooseObjects,
+    pub packs: Vec<Pack>,
+    /// The objects taking the most space on disk, biggest first.
+    pub biggest_objects: Vec<Object>,
+    /// The amount of references per namespace, like `refs/heads`, along with `HEAD`.
+    pub refs_by_namespace: BTreeMap<String, usize>,
+    /// All reflogs, sorted by reference name.
+    pub reflogs: Vec<Reflog>,
+    /// Information about the commit-graph, or `None` if there is none.
+    pub commit_graph: Option<CommitGraph>,
+}
+
+/// Gather [`Statistics`] about the repository at `repository` and write them to `out`.
+pub fn statistics<W: io::Write>(
+    repository: impl AsRef<Path>,
+    mut progress: impl Progress,
+    Context {
+        biggest_objects,
+        format,
+        out,
+    }: Context<W>,
+) -> Result<()> {
+    let repo = git_repository::discover(repository)?;
+    let mut biggest = BinaryHeap::new();
+    let mut track_size = |id: ObjectId, size_on_disk: u64, is_packed: bool| {
+        biggest.push(Reverse((size_on_disk, id, is_packed)));
+        if biggest.len() > biggest_objects {
+            biggest.pop();
+        }
+    };
+
+    let mut loose_objects = LooseObjects {
+        count: 0,
+        size_on_disk: 0,
+    };
+    let mut packs = Vec::new();
+    {
+        let start = std::time::Instant::now();
+        let mut progress = progress.add_child("objects");
+        progress.init(None, git_repository::progress::count("objects"));
+        for db in &repo.odb.dbs {
+            for id in db.loose.iter() {
+                let id = id?;
+                let hex = id.to_sha1_hex_string();
+                let size = std::fs::metadata(db.loose.path.join(&hex[..2]).join(&hex[2..]))?.len();
+                loose_objects.count += 1;
+                loose_objects.size_on_disk += size;
+                track_size(id, size, false);
+                progress.inc();
+            }
+            for bundle_index in 0..db.bundles.len() {
+                let bundle = db.bundle(bundle_index)?;
+                let mut entries: Vec<_> = bundle.index.iter().map(|e| (e.pack_offset, e.oid)).collect();
+                entries.sort_by_key(|(offset, _)| *offset);
+                let pack_end = bundle.pack.pack_end() as u64;
+                for (idx, (offset, id)) in entries.iter().enumerate() {
+                    let next_offset = entries.get(idx + 1).map_or(pack_end, |(offset, _)| *offset);
+                    track_size(*id, next_offset - offset, true);
+                }
+                progress.inc_by(entries.len());
+                packs.push(Pack {
+                    path: bundle.pack.path().display().to_string(),
+                    num_objects: bundle.pack.num_objects(),
+                    data_size: bundle.pack.data_len() as u64,
+                    index_size: db.bundles[bundle_index].index_size,
+                    is_promisor: db.bundles[bundle_index].is_promisor,
+                });
+            }
+        }
+        progress.show_throughput(start);
+    }
+    let biggest_objects = biggest
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((size_on_disk, id, is_packed))| Object {
+            id: id.to_string(),
+            size_on_disk,
+            is_packed,
+        })
+        .collect();
+
+    let mut refs_by_namespace = BTreeMap::new();
+    let mut reflogs = Vec::new();
+    let mut buf = Vec::new();
+    for name in crate::reflog::all_refs(&repo)? {
+        *refs_by_namespace.entry(namespace(&name).to_owned()).or_insert(0) += 1;
+        let entries = match repo.refs.reflog_iter(name.as_str(), &mut buf)? {
+            Some(lines) => lines.count(),
+            None => continue,
+        };
+        reflogs.push(Reflog {
+            name,
+            entries,
+            size: buf.len() as u64,
+        });
+    }
+
+    let info_dir = repo.objects_dir().join("info");
+    let commit_graph = if info_dir.join("commit-graph").is_file() || info_dir.join("commit-graphs").is_dir() {
+        let graph = git_commitgraph::Graph::from_info_dir(&info_dir).with_context(|| "Could not open commit graph")?;
+        Some(CommitGraph {
+            num_commits: graph.num_commits(),
+        })
+    } else {
+        None
+    };
+
+    let stats = Statistics {
+        loose_objects,
+        packs,
+        biggest_objects,
+        refs_by_namespace,
+        reflogs,
+        commit_graph,
+    };
+    match format {
+        OutputFormat::Human => drop(print(out, &stats)),
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &stats)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => crate::write_json_line(out, &stats)?,
+    }
+    Ok(())
+}
+
+/// Return the first two components of the reference `name`, like `refs/heads`, or `name` itself if it has fewer.
+fn namespace(name: &str) -> &str {
+    match name.match_indices('/').nth(1) {
+        Some((pos, _)) => &name[..pos],
+        None => name,
+    }
+}
+
+fn print(mut out: impl io::Write, stats: &Statistics) -> io::Result<()> {
+    writeln!(
+        out,
+        "loose objects: {} ({})",
+        stats.loose_objects.count,
+        format::bytes(stats.loose_objects.size_on_disk)
+    )?;
+    writeln!(out, "packs: {}", stats.packs.len())?;
+    for pack in &stats.packs {
+        writeln!(
+            out,
+            "\t{}: {} objects, {} data, {} index{}",
+            pack.path,
+            pack.num_objects,
+            format::bytes(pack.data_size),
+            format::bytes(pack.index_size),
+            if pack.is_promisor { " (promisor)" } else { "" }
+        )?;
+    }
+    writeln!(out, "biggest objects on disk:")?;
+    for object in &stats.biggest_objects {
+        writeln!(
+            out,
+            "\t{} {} ({})",
+            object.id,
+            format::bytes(object.size_on_disk),
+            if object.is_packed { "packed" } else { "loose" }
+        )?;
+    }
+    writeln!(out, "references: {}", stats.refs_by_namespace.values().sum::<usize>())?;
+    for (namespace, count) in &stats.refs_by_namespace {
+        writeln!(out, "\t{}: {}", namespace, count)?;
+    }
+    writeln!(out, "reflogs: {}", stats.reflogs.len())?;
+    for reflog in &stats.reflogs {
+        writeln!(
+            out,
+            "\t{}: {} entries ({})",
+            reflog.name,
+            reflog.entries,
+            format::bytes(reflog.size)
+        )?;
+    }
+    match &stats.commit_graph {
+        Some(graph) => writeln!(out, "commit-graph: {} commits", graph.num_commits),
+        None => writeln!(out, "commit-graph: none"),
+    }
+}
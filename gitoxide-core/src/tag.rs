@@ -0,0 +1,197 @@
+use crate::OutputFormat;
+use anyhow::{anyhow, Result};
+use git_repository::{
+    hash::ObjectId,
+    object::{bstr::ByteSlice, Kind},
+    odb::pack,
+    prelude::*,
+    Repository,
+};
+use std::{collections::BTreeMap, io, path::Path, str::FromStr};
+
+const TAG_PREFIX: &str = "refs/tags/";
+
+/// The order in which tags are listed.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Sort {
+    /// Sort tags by their name.
+    Name,
+    /// Sort tags by the time they were created, oldest first, which is the time of the tagger for annotated tags and
+    /// the time of the committer for tags pointing to commits directly.
+    ///
+    /// Tags without such time are listed first, tags created at the same time are sorted by name.
+    Date,
+}
+
+impl Sort {
+    pub fn variants() -> &'static [&'static str] {
+        &["name", "date"]
+    }
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s_lc = s.to_ascii_lowercase();
+        Ok(match s_lc.as_str() {
+            "name" => Sort::Name,
+            "date" => Sort::Date,
+            _ => return Err(format!("Invalid sort order: '{}'", s)),
+        })
+    }
+}
+
+pub struct Context<W> {
+    /// The way to output the tags.
+    pub format: OutputFormat,
+    /// The order in which to list the tags.
+    pub sort: Sort,
+    /// If true, follow annotated tags to the object they eventually point to and list it as well.
+    pub peel: bool,
+    /// Where to write information about the tags.
+    pub out: W,
+}
+
+/// A tag as listed by [`list()`].
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// The short name of the tag, like `v1.0`.
+    pub name: String,
+    /// The object the tag reference points to, hex encoded.
+    pub target: String,
+    /// The kind of object the tag reference points to, which is `tag` for annotated tags.
+    pub kind: String,
+    /// The object annotated tags eventually point to, hex encoded, which usually is a commit.
+    ///
+    /// Only set if peeling was requested.
+    pub peeled: Option<String>,
+    /// The time the tag was created at in seconds since epoch, if known.
+    pub date: Option<u32>,
+}
+
+/// List all tags of the repository at `repository`, loose and packed, whose name matches any of `patterns`, or all of them
+/// if there is no pattern.
+///
+/// Patterns are matched against the short name of the tag, with `*` matching any amount of characters and `?` matching one.
+pub fn list(
+    repository: impl AsRef<Path>,
+    patterns: Vec<String>,
+    Context {
+        format,
+        sort,
+        peel,
+        mut out,
+    }: Context<impl io::Write>,
+) -> Result<()> {
+    let repo = git_repository::discover(repository)?;
+
+    let mut targets = BTreeMap::new();
+    if let Some(packed) = repo.refs.packed()? {
+        for reference in packed.iter()? {
+            let reference = reference?;
+            if let Some(name) = reference.full_name.to_str()?.strip_prefix(TAG_PREFIX) {
+                targets.insert(name.to_owned(), reference.target());
+            }
+        }
+    }
+    let mut loose_names = Vec::new();
+    for reference in repo.refs.loose_iter_prefixed("refs/tags")? {
+        loose_names.push(reference?.name().borrow().as_bstr().to_str()?.to_owned());
+    }
+    for (full_name, target) in loose_names
+        .iter()
+        .zip(crate::branch::peel_many(&repo, loose_names.iter().map(String::as_str))?)
+    {
+        if let Some(target) = target {
+            targets.insert(full_name[TAG_PREFIX.len()..].to_owned(), target);
+        }
+    }
+
+    let mut buf = Vec::new();
+    let mut tags = Vec::new();
+    for (name, target) in targets {
+        if !patterns.is_empty()
+            && !patterns
+                .iter()
+                .any(|pattern| crate::glob_match(pattern.as_bytes(), name.as_bytes()))
+        {
+            continue;
+        }
+        let (kind, date) = kind_and_date(&repo, target, &mut buf)?;
+        tags.push(Tag {
+            peeled: if peel {
+                Some(peel_tag(&repo, target, &mut buf)?.to_string())
+            } else {
+                None
+            },
+            target: target.to_string(),
+            kind: kind.to_string(),
+            date,
+            name,
+        });
+    }
+    if sort == Sort::Date {
+        tags.sort_by_key(|tag| tag.date);
+    }
+
+    match format {
+        OutputFormat::Human => {
+            for tag in &tags {
+                write!(out, "{} {}", &tag.target[..7], tag.name)?;
+                if let Some(peeled) = tag.peeled.as_ref().filter(|peeled| **peeled != tag.target) {
+                    write!(out, " -> {}", &peeled[..7])?;
+                }
+                writeln!(out)?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &tags)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => {
+            for tag in &tags {
+                crate::write_json_line(&mut out, tag)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Return the kind of the object `id` and the time it was created at, if it is an annotated tag with tagger or a commit.
+fn kind_and_date(repo: &Repository, id: ObjectId, buf: &mut Vec<u8>) -> Result<(Kind, Option<u32>)> {
+    let object = repo
+        .odb
+        .find_existing(id, buf, &mut pack::cache::Never)
+        .map_err(|_| anyhow!("Object {} does not exist", id))?;
+    let object = object.decode()?;
+    let date = match object.kind() {
+        Kind::Tag => object
+            .as_tag()
+            .and_then(|tag| tag.tagger.as_ref())
+            .map(|tagger| tagger.time.time),
+        Kind::Commit => object.as_commit().map(|commit| commit.committer.time.time),
+        _ => None,
+    };
+    Ok((object.kind(), date))
+}
+
+/// Follow the annotated tag `id` to the first object that isn't a tag, or return `id` if it isn't a tag.
+fn peel_tag(repo: &Repository, mut id: ObjectId, buf: &mut Vec<u8>) -> Result<ObjectId> {
+    loop {
+        let object = repo
+            .odb
+            .find_existing(id, buf, &mut pack::cache::Never)
+            .map_err(|_| anyhow!("Object {} does not exist", id))?;
+        match object.kind {
+            Kind::Tag => {
+                id = object
+                    .decode()?
+                    .as_tag()
+                    .map(|tag| tag.target())
+                    .ok_or_else(|| anyhow!("Tag {} has no target", id))?;
+            }
+            _ => return Ok(id),
+        }
+    }
+}
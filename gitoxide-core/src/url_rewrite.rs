@@ -0,0 +1,144 @@
+//! Rewrite remote URLs with the `url.<base>.insteadOf` and `url.<base>.pushInsteadOf` rules found in git configuration files.
+use crate::defaults;
+use anyhow::{anyhow, Result};
+use git_config::{file::GitConfig, parser};
+use std::{borrow::Cow, convert::TryFrom};
+
+/// The direction of the connection a URL is used for, which determines the rules that apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The URL is used to fetch, only `url.<base>.insteadOf` applies.
+    Fetch,
+    /// The URL is used to push, and `url.<base>.pushInsteadOf` applies before `url.<base>.insteadOf`.
+    Push,
+}
+
+/// A single rule to replace the prefix `instead_of` of a URL with `base`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    /// The URL prefix to use instead, the subsection name of the `url` section.
+    pub base: String,
+    /// The URL prefix to replace.
+    pub instead_of: String,
+    /// If `true`, this rule comes from `pushInsteadOf` and only applies to pushes.
+    pub push_only: bool,
+}
+
+impl Rule {
+    /// The name of the configuration key this rule was read from, like `url.<base>.insteadOf`.
+    pub fn key(&self) -> String {
+        format!(
+            "url.{}.{}",
+            self.base,
+            if self.push_only { "pushInsteadOf" } else { "insteadOf" }
+        )
+    }
+}
+
+/// All URL rewrite rules as read from git configuration.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// The rules in the order they were read.
+    pub rules: Vec<Rule>,
+}
+
+impl Rules {
+    /// Read the rules from the same configuration files as [`Defaults::from_environment()`][defaults::Defaults::from_environment()].
+    ///
+    /// Missing configuration files are ignored, but invalid ones cause an error.
+    pub fn from_environment() -> Result<Self> {
+        let mut rules = Rules::default();
+        for path in defaults::config_paths() {
+            let config = match std::fs::read(&path) {
+                Ok(config) => config,
+                Err(_) => continue,
+            };
+            rules.rules.extend(
+                Self::from_config(&config)
+                    .map_err(|err| anyhow!("Invalid configuration in '{}': {}", path.display(), err))?
+                    .rules,
+            );
+        }
+        Ok(rules)
+    }
+
+    /// Read all rules from the git configuration file `config`.
+    pub fn from_config(config: &[u8]) -> Result<Self> {
+        let bases = {
+            let parsed = parser::parse_from_bytes(config).map_err(|err| anyhow!("{}", err))?;
+            let mut bases: Vec<String> = parsed
+                .sections()
+                .iter()
+                .filter(|section| section.section_header.name.0.eq_ignore_ascii_case("url"))
+                .filter_map(|section| {
+                    section
+                        .section_header
+                        .subsection_name
+                        .as_ref()
+                        .map(|name| name.to_string())
+                })
+                .collect();
+            bases.sort();
+            bases.dedup();
+            bases
+        };
+        let config = GitConfig::try_from(config).map_err(|err| anyhow!("{}", err))?;
+        let mut rules = Vec::new();
+        for base in bases {
+            for (key, push_only) in &[("insteadOf", false), ("pushInsteadOf", true)] {
+                let values = config
+                    .multi_value::<Cow<'_, [u8]>>("url", Some(&base), key)
+                    .unwrap_or_default();
+                for value in values {
+                    let instead_of = std::str::from_utf8(&git_config::values::normalize_cow(value))
+                        .map_err(|_| anyhow!("url.{}.{} is not valid UTF-8", base, key))?
+                        .trim()
+                        .to_owned();
+                    rules.push(Rule {
+                        base: base.clone(),
+                        instead_of,
+                        push_only: *push_only,
+                    });
+                }
+            }
+        }
+        Ok(Rules { rules })
+    }
+
+    /// Return the rewritten `url` along with the rule that was used, or `None` if no rule applies to `url` when used in `direction`.
+    ///
+    /// Like git, the rule with the longest matching prefix wins, and for pushes `pushInsteadOf` rules are considered first.
+    pub fn rewrite(&self, url: &str, direction: Direction) -> Option<(String, &Rule)> {
+        let longest_match = |push_only: bool| {
+            self.rules
+                .iter()
+                .filter(|rule| rule.push_only == push_only && url.starts_with(&rule.instead_of))
+                .fold(None::<&Rule>, |best, rule| match best {
+                    Some(best) if best.instead_of.len() >= rule.instead_of.len() => Some(best),
+                    _ => Some(rule),
+                })
+        };
+        let rule = match direction {
+            Direction::Fetch => longest_match(false),
+            Direction::Push => longest_match(true).or_else(|| longest_match(false)),
+        }?;
+        Some((format!("{}{}", rule.base, &url[rule.instead_of.len()..]), rule))
+    }
+}
+
+/// Rewrite `url` for use in `direction` with the rules read from the environment, calling `on_rewrite(message)`
+/// with a description of the rewrite if a rule applied.
+pub fn apply(url: String, direction: Direction, on_rewrite: impl FnOnce(String)) -> Result<String> {
+    Ok(match Rules::from_environment()?.rewrite(&url, direction) {
+        Some((rewritten, rule)) => {
+            on_rewrite(format!(
+                "Rewrote '{}' to '{}' according to {}",
+                url,
+                rewritten,
+                rule.key()
+            ));
+            rewritten
+        }
+        None => url,
+    })
+}
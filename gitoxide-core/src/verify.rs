@@ -0,0 +1,438 @@
+use crate::{commitgraph, OutputFormat};
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use git_repository::{
+    hash::ObjectId,
+    object::{self, immutable},
+    odb::{pack, pack::index},
+    parallel,
+    refs::file::{find_one, reference::peel},
+    Progress,
+};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc, Mutex},
+};
+
+pub struct Context<W> {
+    pub format: OutputFormat,
+    /// Where to write the report
+    pub out: W,
+    /// If set, don't use more than this amount of threads per pack.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    pub thread_limit: Option<usize>,
+    /// How thoroughly to verify objects in packs.
+    ///
+    /// If objects are decoded, trees with non-canonical entry modes are reported as [warnings][Severity::Warning].
+    pub mode: index::verify::Mode,
+    /// The least severe finding which causes a check to fail, making [`integrity()`] return an error.
+    pub fail_on: Severity,
+    pub should_interrupt: Arc<AtomicBool>,
+}
+
+/// The kind of data structure that was checked.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Kind {
+    /// A pack data file, which can only be verified against its own checksum without index.
+    Pack,
+    /// A pack index along with its pack data file.
+    PackIndex,
+    /// A single commit-graph file or a chain of them.
+    CommitGraph,
+    /// All references of a repository, which must resolve to existing objects.
+    References,
+}
+
+impl Kind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Pack => "pack",
+            Kind::PackIndex => "pack-index",
+            Kind::CommitGraph => "commit-graph",
+            Kind::References => "references",
+        }
+    }
+}
+
+/// How severe a [`Finding`] is, ordered from least to most severe.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    /// Something worth knowing which is perfectly valid, like `HEAD` pointing to a branch that doesn't exist yet.
+    Info,
+    /// Something git tolerates but wouldn't produce itself, like trees with non-canonical entry modes.
+    Warning,
+    /// Corrupt or missing data, like objects whose hash doesn't match.
+    Error,
+}
+
+impl Severity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "WARN",
+            Severity::Error => "FAILED",
+        }
+    }
+}
+
+/// Something noteworthy found while performing a [`Check`].
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// The outcome of a single integrity check.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Check {
+    pub kind: Kind,
+    /// The path to the checked data, relative to the repository if one was verified.
+    pub path: String,
+    /// Everything found during the check, which is empty if everything is in order.
+    ///
+    /// A check which couldn't be completed has a single finding with [`Severity::Error`] describing why.
+    pub findings: Vec<Finding>,
+}
+
+impl Check {
+    /// Return the severity of the most severe finding, or `None` if there is none.
+    pub fn severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|finding| finding.severity).max()
+    }
+}
+
+/// All integrity checks performed on a path, in the order they were planned.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+/// Verify the integrity of whatever is found at `path`, which is a pack, a pack index, a commit-graph file or directory,
+/// or a repository whose packs, commit-graph and references are checked.
+///
+/// Pack checks run in parallel to all other checks, and the aggregated [`Report`] is written to `out`.
+/// With [`OutputFormat::JsonLines`], each [`Check`] is written to `out` as soon as it completes instead.
+/// Checks with findings at least as severe as [`Context::fail_on`] fail, which doesn't stop the remaining ones but causes
+/// an error to be returned after the report was written.
+pub fn integrity<P, W>(
+    path: impl AsRef<Path>,
+    mut progress: P,
+    Context {
+        format,
+        out,
+        thread_limit,
+        mode,
+        fail_on,
+        should_interrupt,
+    }: Context<W>,
+) -> Result<Report>
+where
+    P: Progress,
+    P::SubProgress: Send,
+    W: io::Write + Send,
+{
+    let path = path.as_ref();
+    let (repo, planned) = plan(path)?;
+    let relative_path = |p: &Path| match repo.as_ref().and_then(|repo| p.strip_prefix(repo.git_dir()).ok()) {
+        Some(relative) if relative.as_os_str().is_empty() => ".".into(),
+        Some(relative) => relative.display().to_string(),
+        None => p.display().to_string(),
+    };
+
+    let out = Mutex::new(out);
+    let to_check = |kind: Kind, path: PathBuf, res: Result<Vec<Finding>>| -> io::Result<Check> {
+        let check = Check {
+            kind,
+            path: relative_path(&path),
+            findings: res.unwrap_or_else(|err| {
+                vec![Finding {
+                    severity: Severity::Error,
+                    message: format!("{:#}", err),
+                }]
+            }),
+        };
+        #[cfg(feature = "serde1")]
+        if format == OutputFormat::JsonLines {
+            crate::write_json_line(&mut *out.lock().expect("no panic while writing"), &check)?;
+        }
+        Ok(check)
+    };
+
+    let (pack_checks, other_checks): (Vec<_>, Vec<_>) = planned
+        .into_iter()
+        .map(|(kind, path)| {
+            let progress = progress.add_child(format!("{} {}", kind.as_str(), relative_path(&path)));
+            (kind, path, progress)
+        })
+        .partition(|(kind, _, _)| matches!(kind, Kind::Pack | Kind::PackIndex));
+    let (pack_results, other_results) = parallel::join(
+        || {
+            pack_checks
+                .into_iter()
+                .map(|(kind, path, progress)| {
+                    let res = match kind {
+                        Kind::PackIndex => verify_pack_index(&path, progress, thread_limit, mode, &should_interrupt),
+                        Kind::Pack => crate::pack::verify::pack_or_pack_index(
+                            &path,
+                            Some(progress),
+                            crate::pack::verify::Context {
+                                output_statistics: None,
+                                out: io::sink(),
+                                err: io::sink(),
+                                thread_limit,
+                                mode,
+                                algorithm: crate::pack::verify::Algorithm::LessTime,
+                                should_interrupt: Arc::clone(&should_interrupt),
+                                failure_report: None,
+                                retry_from: None,
+                            },
+                        )
+                        .map(|_| Vec::new()),
+                        Kind::CommitGraph | Kind::References => unreachable!("only pack checks were partitioned in"),
+                    };
+                    to_check(kind, path, res)
+                })
+                .collect::<io::Result<Vec<_>>>()
+        },
+        || {
+            other_checks
+                .into_iter()
+                .map(|(kind, path, mut progress)| {
+                    let res = match kind {
+                        Kind::CommitGraph => commitgraph::verify::graph_or_file(
+                            &path,
+                            commitgraph::verify::Context {
+                                err: io::sink(),
+                                out: io::sink(),
+                                output_statistics: None,
+                            },
+                        )
+                        .map(|_| Vec::new()),
+                        Kind::References => verify_references(
+                            repo.as_ref().expect("references are only checked in repositories"),
+                            &mut progress,
+                        ),
+                        Kind::Pack | Kind::PackIndex => unreachable!("pack checks were partitioned out"),
+                    };
+                    to_check(kind, path, res)
+                })
+                .collect::<io::Result<Vec<_>>>()
+        },
+    );
+
+    let checks = pack_results?.into_iter().chain(other_results?).collect();
+    let report = Report { checks };
+
+    let mut out = out.into_inner().expect("no panic while writing");
+    match format {
+        OutputFormat::Human => print(&mut out, &report)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &report)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::JsonLines => {}
+    }
+    let num_failed = report
+        .checks
+        .iter()
+        .filter(|check| matches!(check.severity(), Some(severity) if severity >= fail_on))
+        .count();
+    if num_failed != 0 {
+        bail!("{} of {} checks failed", num_failed, report.checks.len());
+    }
+    Ok(report)
+}
+
+/// Determine which checks to perform for `path`, along with the repository it is in if it is one.
+fn plan(path: &Path) -> Result<(Option<git_repository::Repository>, Vec<(Kind, PathBuf)>)> {
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    if path.is_file() {
+        let kind = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("idx") => Kind::PackIndex,
+            Some("pack") => Kind::Pack,
+            Some("graph") => Kind::CommitGraph,
+            _ if file_name == "commit-graph" => Kind::CommitGraph,
+            _ => bail!(
+                "Don't know how to verify '{}', expecting a pack, pack index, commit-graph or repository",
+                path.display()
+            ),
+        };
+        return Ok((None, vec![(kind, path.to_owned())]));
+    }
+    if path.join("commit-graph-chain").is_file() {
+        return Ok((None, vec![(Kind::CommitGraph, path.to_owned())]));
+    }
+
+    let repo = git_repository::discover(path)
+        .map_err(|err| anyhow!("Could not find a repository at '{}': {}", path.display(), err))?;
+    let mut checks = Vec::new();
+    for db in &repo.odb.dbs {
+        checks.extend(
+            db.bundles
+                .iter()
+                .map(|bundle| (Kind::PackIndex, bundle.index_path.clone())),
+        );
+    }
+    let info_dir = repo.objects_dir().join("info");
+    if info_dir.join("commit-graph").is_file() || info_dir.join("commit-graphs").is_dir() {
+        checks.push((Kind::CommitGraph, info_dir));
+    }
+    checks.push((Kind::References, repo.refs.base.clone()));
+    Ok((Some(repo), checks))
+}
+
+/// Verify the pack index at `path` along with its pack, decoding objects depending on `mode` to find trees with
+/// non-canonical entry modes.
+///
+/// If the pack can't be opened, only the checksum of the index is verified.
+fn verify_pack_index(
+    path: &Path,
+    mut progress: impl Progress,
+    thread_limit: Option<usize>,
+    mode: index::verify::Mode,
+    should_interrupt: &Arc<AtomicBool>,
+) -> Result<Vec<Finding>> {
+    let idx = index::File::at(path).context("Could not open pack index file")?;
+    let pack = match pack::data::File::at(path.with_extension("pack")) {
+        Ok(pack) => pack,
+        Err(err) => {
+            idx.verify_checksum(progress.add_child("Sha1 of index"), should_interrupt)?;
+            return Ok(vec![Finding {
+                severity: Severity::Warning,
+                message: format!("Only the index was verified as its pack couldn't be opened: {}", err),
+            }]);
+        }
+    };
+    let decode = !matches!(mode, index::verify::Mode::Sha1Crc32);
+    let non_canonical_trees = Mutex::new(Vec::new());
+    idx.traverse(
+        &pack,
+        Some(progress),
+        || {
+            let non_canonical_trees = &non_canonical_trees;
+            move |kind, data: &[u8], entry: &index::Entry, _progress: &mut _| {
+                if decode {
+                    decode_object(kind, data, entry.oid, non_canonical_trees)
+                } else {
+                    Ok(())
+                }
+            }
+        },
+        || pack::cache::Never,
+        index::traverse::Options {
+            algorithm: index::traverse::Algorithm::DeltaTreeLookup,
+            thread_limit,
+            check: index::traverse::SafetyCheck::All,
+            should_interrupt: Arc::clone(should_interrupt),
+            prefetch: true,
+        },
+    )
+    .context("Verification failure")?;
+
+    let mut non_canonical_trees: Vec<ObjectId> = non_canonical_trees.into_inner().expect("no panic while holding lock");
+    non_canonical_trees.sort();
+    Ok(non_canonical_trees
+        .first()
+        .map(|first| Finding {
+            severity: Severity::Warning,
+            message: format!(
+                "{} tree(s) have entries with non-canonical modes, the first being {}",
+                non_canonical_trees.len(),
+                first
+            ),
+        })
+        .into_iter()
+        .collect())
+}
+
+/// Decode the object with `id` of the given `kind` from `data`, remembering trees with non-canonical entry modes
+/// in `non_canonical_trees`.
+fn decode_object(
+    kind: object::Kind,
+    data: &[u8],
+    id: ObjectId,
+    non_canonical_trees: &Mutex<Vec<ObjectId>>,
+) -> Result<(), immutable::object::decode::Error> {
+    match kind {
+        object::Kind::Tree => {
+            let tree = immutable::Tree::from_bytes(data)?;
+            if !tree.entries.iter().all(|entry| entry.has_canonical_mode()) {
+                non_canonical_trees
+                    .lock()
+                    .expect("no panic while holding lock")
+                    .push(id);
+            }
+        }
+        object::Kind::Commit | object::Kind::Tag => drop(immutable::Object::from_bytes(kind, data)?),
+        object::Kind::Blob => {}
+    }
+    Ok(())
+}
+
+/// Assure all references can be parsed and ultimately point to an object in the object database.
+///
+/// Each broken reference is reported as [error][Severity::Error] finding.
+fn verify_references(repo: &git_repository::Repository, progress: &mut impl Progress) -> Result<Vec<Finding>> {
+    let names = crate::reflog::all_refs(repo)?;
+    progress.init(Some(names.len()), git_repository::progress::count("references"));
+    let mut findings = Vec::new();
+    let broken = |message: String| Finding {
+        severity: Severity::Error,
+        message,
+    };
+    for name in names {
+        progress.inc();
+        let mut reference = match repo.refs.find_one(name.as_str()) {
+            Ok(Some(reference)) => reference,
+            Ok(None) => continue,
+            Err(err) => {
+                findings.push(broken(format!("Reference '{}' could not be read: {}", name, err)));
+                continue;
+            }
+        };
+        let id = match reference.peel_to_id_in_place() {
+            Ok(id) => id.to_owned(),
+            // HEAD is allowed to point to a branch that doesn't exist yet.
+            Err(peel::to_id::Error::PeelOne(peel::Error::FindExisting(find_one::existing::Error::NotFound(
+                branch,
+            )))) if name == "HEAD" => {
+                findings.push(Finding {
+                    severity: Severity::Info,
+                    message: format!("HEAD points to branch '{}' which doesn't exist yet", branch.display()),
+                });
+                continue;
+            }
+            Err(err) => {
+                findings.push(broken(format!("Reference '{}' could not be resolved: {}", name, err)));
+                continue;
+            }
+        };
+        if !repo.odb.contains(&id) {
+            findings.push(broken(format!(
+                "Reference '{}' points to object {} which doesn't exist",
+                name, id
+            )));
+        }
+    }
+    Ok(findings)
+}
+
+fn print(out: &mut impl io::Write, report: &Report) -> io::Result<()> {
+    for check in &report.checks {
+        if check.findings.is_empty() {
+            writeln!(out, "ok     {:<13} {}", check.kind.as_str(), check.path)?;
+        }
+        for finding in &check.findings {
+            writeln!(
+                out,
+                "{:<6} {:<13} {}: {}",
+                finding.severity.as_str(),
+                check.kind.as_str(),
+                check.path,
+                finding.message
+            )?;
+        }
+    }
+    Ok(())
+}
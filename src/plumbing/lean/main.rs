@@ -23,7 +23,8 @@ pub fn main() -> Result<()> {
         let should_interrupt = Arc::clone(&should_interrupt);
         move || should_interrupt.store(true, Ordering::SeqCst)
     })?;
-    let thread_limit = cli.threads;
+    let defaults = core::defaults::Defaults::from_environment()?;
+    let thread_limit = cli.threads.or(defaults.threads);
     let verbose = cli.verbose;
     match cli.subcommand {
         SubCommands::PackCreate(options::PackCreate {
@@ -31,10 +32,12 @@ pub fn main() -> Result<()> {
             expansion,
             nondeterministic_count,
             statistics,
+            not,
             tips,
             output_directory,
         }) => {
             let (_handle, progress) = prepare(verbose, "pack-create", Some(core::pack::create::PROGRESS_RANGE));
+            let tips = core::pack::create::tips_with_exclusions(tips, not);
             let has_tips = !tips.is_empty();
             let input = if has_tips {
                 None
@@ -68,10 +71,15 @@ pub fn main() -> Result<()> {
             )
         }
         #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
-        SubCommands::RemoteRefList(options::RemoteRefList { protocol, url }) => {
+        SubCommands::RemoteRefList(options::RemoteRefList {
+            protocol,
+            no_rewrite,
+            url,
+        }) => {
+            let url = crate::shared::rewrite_url(url, no_rewrite, verbose)?;
             let (_handle, progress) = prepare(verbose, "remote-ref-list", Some(core::remote::refs::PROGRESS_RANGE));
             let res = core::remote::refs::list(
-                protocol,
+                protocol.or(defaults.protocol),
                 &url,
                 DoOrDiscard::from(progress),
                 core::remote::refs::Context {
@@ -86,18 +94,53 @@ pub fn main() -> Result<()> {
             return futures_lite::future::block_on(res);
         }
         #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+        SubCommands::RemoteRefMap(options::RemoteRefMap {
+            protocol,
+            no_rewrite,
+            refspecs,
+            url,
+        }) => {
+            let url = crate::shared::rewrite_url(url, no_rewrite, verbose)?;
+            let (_handle, progress) = prepare(verbose, "remote-ref-map", Some(core::remote::ref_map::PROGRESS_RANGE));
+            let res = core::remote::ref_map::map(
+                protocol.or(defaults.protocol),
+                &url,
+                refspecs,
+                DoOrDiscard::from(progress),
+                core::remote::refs::Context {
+                    thread_limit,
+                    format: OutputFormat::Human,
+                    out: io::stdout(),
+                },
+            );
+            #[cfg(feature = "gitoxide-core-blocking-client")]
+            return res;
+            #[cfg(feature = "gitoxide-core-async-client")]
+            return futures_lite::future::block_on(res);
+        }
+        #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
         SubCommands::PackReceive(options::PackReceive {
             protocol,
+            no_rewrite,
             url,
             directory,
             refs_directory,
+            into,
+            remote,
+            refs,
         }) => {
+            let url = crate::shared::rewrite_url(url, no_rewrite, verbose)?;
             let (_handle, progress) = prepare(verbose, "pack-receive", core::pack::receive::PROGRESS_RANGE);
             let res = core::pack::receive(
-                protocol,
+                protocol.or(defaults.protocol),
                 &url,
                 directory,
                 refs_directory,
+                into.map(|path| core::pack::receive::IntoRepository {
+                    path,
+                    remote_name: remote.unwrap_or_else(|| "origin".into()),
+                }),
+                refs,
                 DoOrDiscard::from(progress),
                 core::pack::receive::Context {
                     thread_limit,
@@ -111,9 +154,28 @@ pub fn main() -> Result<()> {
             #[cfg(feature = "gitoxide-core-async-client")]
             return futures_lite::future::block_on(res);
         }
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        SubCommands::Daemon(options::Daemon {
+            export_path,
+            export_all,
+            listen,
+        }) => {
+            let (_handle, progress) = prepare(verbose, "daemon", None);
+            core::daemon::serve(
+                listen,
+                DoOrDiscard::from(progress),
+                core::daemon::Context {
+                    export_path,
+                    export_all,
+                    thread_limit,
+                    out: stdout(),
+                },
+            )
+        }
         SubCommands::IndexFromPack(options::IndexFromPack {
             iteration_mode,
             pack_path,
+            memory_map,
             directory,
         }) => {
             use gitoxide_core::pack::index::PathOrRead;
@@ -134,6 +196,7 @@ pub fn main() -> Result<()> {
                 core::pack::index::Context {
                     thread_limit,
                     iteration_mode: iteration_mode.unwrap_or_default(),
+                    memory_map,
                     format: OutputFormat::Human,
                     out: io::stdout(),
                     should_interrupt: &git_repository::interrupt::IS_INTERRUPTED,
@@ -169,6 +232,8 @@ pub fn main() -> Result<()> {
             algorithm,
             decode,
             re_encode,
+            failure_report,
+            retry_from,
         }) => {
             use self::core::pack::verify;
             let (_handle, progress) = prepare(verbose, "pack-verify", None);
@@ -191,10 +256,21 @@ pub fn main() -> Result<()> {
                     out: stdout(),
                     err: stderr(),
                     should_interrupt,
+                    failure_report,
+                    retry_from,
                 },
             )
             .map(|_| ())
         }
+        SubCommands::PackDiff(options::PackDiff { a, b }) => core::pack::diff::packs(
+            a,
+            b,
+            core::pack::diff::Context {
+                format: core::OutputFormat::Human,
+                out: stdout(),
+            },
+        )
+        .map(|_| ()),
         SubCommands::CommitGraphVerify(options::CommitGraphVerify { path, statistics }) => {
             use self::core::commitgraph::verify;
 
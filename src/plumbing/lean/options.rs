@@ -18,7 +18,8 @@ pub struct Args {
     #[argh(option, short = 't')]
     /// the amount of threads to use for some operations.
     ///
-    /// If unset, or the value is 0, there is no limit and all logical cores can be used.
+    /// If unset, `gitoxide.threads` in the git configuration or `GITOXIDE_THREADS` is used. If these aren't set either,
+    /// or the value is 0, there is no limit and all logical cores can be used.
     pub threads: Option<usize>,
 
     #[argh(subcommand)]
@@ -35,7 +36,12 @@ pub enum SubCommands {
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     RemoteRefList(RemoteRefList),
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+    RemoteRefMap(RemoteRefMap),
+    #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     PackReceive(PackReceive),
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    Daemon(Daemon),
+    PackDiff(PackDiff),
     CommitGraphVerify(CommitGraphVerify),
 }
 
@@ -61,6 +67,12 @@ pub struct IndexFromPack {
     #[argh(option, short = 'p')]
     pub pack_path: Option<PathBuf>,
 
+    /// memory-map the pack file and index it in place, without streaming it through a temporary file.
+    ///
+    /// This requires a pack path and can't be used with the 'restore' iteration mode.
+    #[argh(switch, short = 'm')]
+    pub memory_map: bool,
+
     /// the folder into which to place the pack and the generated index file
     ///
     /// If unset, only informational output will be provided to standard output.
@@ -76,10 +88,43 @@ pub struct IndexFromPack {
 #[argh(subcommand, name = "remote-ref-list")]
 #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
 pub struct RemoteRefList {
-    /// the protocol version to use. Valid values are 1 and 2
+    /// the protocol version to use. Valid values are 1 and 2, defaulting to `protocol.version` in the git configuration.
+    #[argh(option, short = 'p')]
+    pub protocol: Option<core::net::Protocol>,
+
+    /// don't rewrite the url with the 'url.<base>.insteadOf' rules in the git configuration.
+    #[argh(switch)]
+    pub no_rewrite: bool,
+
+    /// the URLs or path from which to receive references
+    ///
+    /// See here for a list of supported URLs: https://www.git-scm.com/docs/git-clone#_git_urls
+    #[argh(positional)]
+    pub url: String,
+}
+
+/// Show how references on a remote would map to local references when fetching with the given refspecs.
+///
+/// Use this to debug fetch configuration without actually fetching anything.
+/// Supported URLs are documented here: https://www.git-scm.com/docs/git-clone#_git_urls
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "remote-ref-map")]
+#[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+pub struct RemoteRefMap {
+    /// the protocol version to use. Valid values are 1 and 2, defaulting to `protocol.version` in the git configuration.
     #[argh(option, short = 'p')]
     pub protocol: Option<core::net::Protocol>,
 
+    /// don't rewrite the url with the 'url.<base>.insteadOf' rules in the git configuration.
+    #[argh(switch)]
+    pub no_rewrite: bool,
+
+    /// a fetch refspec like '+refs/heads/*:refs/remotes/origin/*' to map remote references with. Can be repeated.
+    ///
+    /// Defaults to '+refs/heads/*:refs/remotes/origin/*'.
+    #[argh(option, short = 's', long = "refspec")]
+    pub refspecs: Vec<String>,
+
     /// the URLs or path from which to receive references
     ///
     /// See here for a list of supported URLs: https://www.git-scm.com/docs/git-clone#_git_urls
@@ -87,6 +132,26 @@ pub struct RemoteRefList {
     pub url: String,
 }
 
+/// Serve repositories read-only via the git protocol, like `git daemon`, until interrupted.
+///
+/// Only upload-pack is supported, allowing to clone and fetch using protocol V1 and V2.
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "daemon")]
+#[cfg(feature = "gitoxide-core-blocking-client")]
+pub struct Daemon {
+    /// the directory containing the repositories to serve, which clients request relative to it.
+    #[argh(option)]
+    pub export_path: PathBuf,
+
+    /// serve all repositories in the export path, not only those containing a 'git-daemon-export-ok' file.
+    #[argh(switch)]
+    pub export_all: bool,
+
+    /// the address to listen on, like 'localhost:9418' or '0.0.0.0:9418'.
+    #[argh(option, default = "core::daemon::DEFAULT_ADDRESS.into()")]
+    pub listen: String,
+}
+
 /// Receive a pack from a remote identified by a url.
 ///
 /// This is the plumbing equivalent of `git clone` and `git-fetch`.
@@ -95,16 +160,37 @@ pub struct RemoteRefList {
 #[argh(subcommand, name = "pack-receive")]
 #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
 pub struct PackReceive {
-    /// the protocol version to use. Valid values are 1 and 2
+    /// the protocol version to use. Valid values are 1 and 2, defaulting to `protocol.version` in the git configuration.
     #[argh(option, short = 'p')]
     pub protocol: Option<core::net::Protocol>,
 
+    /// don't rewrite the url with the 'url.<base>.insteadOf' rules in the git configuration.
+    #[argh(switch)]
+    pub no_rewrite: bool,
+
     /// the directory into which to write references. Existing files will be overwritten.
     ///
     /// Note that the directory will be created if needed.
     #[argh(option, short = 'r')]
     pub refs_directory: Option<PathBuf>,
 
+    /// an existing repository to receive the pack into, along with remote-tracking references and tags.
+    ///
+    /// Branches are stored as 'refs/remotes/<remote>/<branch>' and tags as they are, with reflog entries as needed.
+    #[argh(option)]
+    pub into: Option<PathBuf>,
+
+    /// the name of the remote whose remote-tracking references to update when receiving into a repository, defaults to 'origin'.
+    #[argh(option)]
+    pub remote: Option<String>,
+
+    /// a pattern of the references to receive, like 'refs/heads/main' or 'refs/heads/feature-*'. Can be repeated.
+    ///
+    /// Patterns without '*' or '?' match all references they are a prefix of, otherwise the whole reference name
+    /// must match. Defaults to 'HEAD', 'refs/heads' and 'refs/tags'.
+    #[argh(option, long = "ref")]
+    pub refs: Vec<String>,
+
     /// the URLs or path from which to receive the pack.
     ///
     /// See here for a list of supported URLs: https://www.git-scm.com/docs/git-clone#_git_urls
@@ -188,14 +274,22 @@ pub struct PackCreate {
     /// It's a form of instrumentation for developers to help improve pack generation.
     pub statistics: bool,
 
-    /// the directory into which to write the pack file.
+    /// the directory into which to write the pack file along with its index.
     #[argh(option, short = 'o')]
     pub output_directory: Option<PathBuf>,
 
+    /// a commit to exclude along with all of its ancestors, equivalent to a tip prefixed with '^'. Can be repeated.
+    #[argh(option)]
+    pub not: Vec<OsString>,
+
     /// the tips from which to start the commit graph iteration.
     ///
     /// If empty, we expect to read objects on stdin and default to 'none' as expansion mode.
     /// Otherwise the expansion mode is 'tree-traversal' by default.
+    ///
+    /// Tips prefixed with '^' are excluded along with all of their ancestors, and 'a..b' is the same as '^a b'.
+    /// 'a...b' includes the commits reachable from either 'a' or 'b' but not both, and 'a^!' only includes 'a'.
+    /// Objects reachable from excluded commits at the boundary of the range are never added to the pack.
     #[argh(positional)]
     pub tips: Vec<OsString>,
 }
@@ -229,11 +323,35 @@ pub struct PackVerify {
     /// output statistical information about the pack
     #[argh(switch, short = 's')]
     pub statistics: bool,
+
+    #[argh(option)]
+    /// write the entry which failed verification to the given file, to verify only this entry with `--retry-from` later.
+    pub failure_report: Option<PathBuf>,
+
+    #[argh(option)]
+    /// verify only the entries listed in the failure report at the given path, for instance after a repair attempt.
+    ///
+    /// Only possible if a '.idx' file is given.
+    pub retry_from: Option<PathBuf>,
+
     /// the '.pack' or '.idx' file whose checksum to validate.
     #[argh(positional)]
     pub path: PathBuf,
 }
 
+/// Compare two packs and list objects only present in either of them, or stored differently in both.
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "pack-diff")]
+pub struct PackDiff {
+    /// the first '.pack' or '.idx' file to compare
+    #[argh(positional)]
+    pub a: PathBuf,
+
+    /// the second '.pack' or '.idx' file to compare
+    #[argh(positional)]
+    pub b: PathBuf,
+}
+
 /// Verify a commit graph
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "commit-graph-verify")]
@@ -4,7 +4,7 @@ use gitoxide_core as core;
 
 use crate::{
     plumbing::pretty::options::{Args, Subcommands},
-    shared::pretty::prepare_and_run,
+    shared::pretty::{prepare_and_run, ProgressMode},
 };
 use gitoxide_core::pack::verify;
 use std::{
@@ -25,6 +25,14 @@ pub fn main() -> Result<()> {
         format,
         cmd,
     } = Args::parse();
+    let defaults = core::defaults::Defaults::from_environment()?;
+    let thread_limit = thread_limit.or(defaults.threads);
+    let format = format.or(defaults.format).unwrap_or(core::OutputFormat::Human);
+    let progress = if progress {
+        ProgressMode::Tui
+    } else {
+        ProgressMode::Auto
+    };
     let should_interrupt = Arc::new(AtomicBool::new(false));
     git_repository::interrupt::init_handler({
         let should_interrupt = Arc::clone(&should_interrupt);
@@ -37,9 +45,11 @@ pub fn main() -> Result<()> {
             expansion,
             statistics,
             nondeterministic_count,
+            not,
             tips,
             output_directory,
         } => {
+            let tips = core::pack::create::tips_with_exclusions(tips, not);
             let has_tips = !tips.is_empty();
             prepare_and_run(
                 "pack-create",
@@ -80,46 +90,118 @@ pub fn main() -> Result<()> {
         #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
         Subcommands::PackReceive {
             protocol,
+            no_rewrite,
             url,
             directory,
             refs_directory,
-        } => prepare_and_run(
-            "pack-receive",
-            verbose,
-            progress,
-            progress_keep_open,
-            core::pack::receive::PROGRESS_RANGE,
-            move |progress, out, _err| {
-                core::pack::receive(
-                    protocol,
-                    &url,
-                    directory,
-                    refs_directory,
-                    git_features::progress::DoOrDiscard::from(progress),
-                    core::pack::receive::Context {
-                        thread_limit,
-                        format,
-                        should_interrupt,
-                        out,
-                    },
-                )
-            },
-        ),
+            into,
+            remote,
+            refs,
+        } => {
+            let url = crate::shared::rewrite_url(url, no_rewrite, verbose)?;
+            prepare_and_run(
+                "pack-receive",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::pack::receive::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::pack::receive(
+                        protocol.or(defaults.protocol),
+                        &url,
+                        directory,
+                        refs_directory,
+                        into.map(|path| core::pack::receive::IntoRepository {
+                            path,
+                            remote_name: remote.unwrap_or_else(|| "origin".into()),
+                        }),
+                        refs,
+                        git_features::progress::DoOrDiscard::from(progress),
+                        core::pack::receive::Context {
+                            thread_limit,
+                            format,
+                            should_interrupt,
+                            out,
+                        },
+                    )
+                },
+            )
+        }
         #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
-        Subcommands::RemoteRefList { protocol, url } => prepare_and_run(
-            "remote-ref-list",
+        Subcommands::RemoteRefList {
+            protocol,
+            no_rewrite,
+            url,
+        } => {
+            let url = crate::shared::rewrite_url(url, no_rewrite, verbose)?;
+            prepare_and_run(
+                "remote-ref-list",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::remote::refs::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::remote::refs::list(
+                        protocol.or(defaults.protocol),
+                        &url,
+                        git_features::progress::DoOrDiscard::from(progress),
+                        core::remote::refs::Context {
+                            thread_limit,
+                            format,
+                            out,
+                        },
+                    )
+                },
+            )
+        }
+        #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+        Subcommands::RemoteRefMap {
+            protocol,
+            no_rewrite,
+            refspecs,
+            url,
+        } => {
+            let url = crate::shared::rewrite_url(url, no_rewrite, verbose)?;
+            prepare_and_run(
+                "remote-ref-map",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::remote::ref_map::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::remote::ref_map::map(
+                        protocol.or(defaults.protocol),
+                        &url,
+                        refspecs,
+                        git_features::progress::DoOrDiscard::from(progress),
+                        core::remote::refs::Context {
+                            thread_limit,
+                            format,
+                            out,
+                        },
+                    )
+                },
+            )
+        }
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::Daemon {
+            export_path,
+            export_all,
+            listen,
+        } => prepare_and_run(
+            "daemon",
             verbose,
             progress,
             progress_keep_open,
-            core::remote::refs::PROGRESS_RANGE,
+            None,
             move |progress, out, _err| {
-                core::remote::refs::list(
-                    protocol,
-                    &url,
+                core::daemon::serve(
+                    listen,
                     git_features::progress::DoOrDiscard::from(progress),
-                    core::remote::refs::Context {
+                    core::daemon::Context {
+                        export_path,
+                        export_all,
                         thread_limit,
-                        format,
                         out,
                     },
                 )
@@ -128,6 +210,7 @@ pub fn main() -> Result<()> {
         Subcommands::PackIndexFromData {
             iteration_mode,
             pack_path,
+            memory_map,
             directory,
         } => prepare_and_run(
             "pack-index-from-data",
@@ -152,6 +235,7 @@ pub fn main() -> Result<()> {
                     core::pack::index::Context {
                         thread_limit,
                         iteration_mode,
+                        memory_map,
                         format,
                         out,
                         should_interrupt: &git_repository::interrupt::IS_INTERRUPTED,
@@ -194,6 +278,8 @@ pub fn main() -> Result<()> {
             decode,
             re_encode,
             statistics,
+            failure_report,
+            retry_from,
         } => prepare_and_run(
             "pack-verify",
             verbose,
@@ -218,11 +304,22 @@ pub fn main() -> Result<()> {
                         mode,
                         algorithm,
                         should_interrupt,
+                        failure_report,
+                        retry_from,
                     },
                 )
             },
         )
         .map(|_| ()),
+        Subcommands::PackDiff { a, b } => prepare_and_run(
+            "pack-diff",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| core::pack::diff::packs(a, b, core::pack::diff::Context { format, out }),
+        )
+        .map(|_| ()),
         Subcommands::CommitGraphVerify { path, statistics } => prepare_and_run(
             "commit-graph-verify",
             verbose,
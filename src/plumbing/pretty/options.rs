@@ -11,7 +11,8 @@ pub struct Args {
     #[clap(long, short = 't')]
     /// The amount of threads to use for some operations.
     ///
-    /// If unset, or the value is 0, there is no limit and all logical cores can be used.
+    /// If unset, `gitoxide.threads` in the git configuration or `GITOXIDE_THREADS` is used. If these aren't set either,
+    /// or the value is 0, there is no limit and all logical cores can be used.
     pub threads: Option<usize>,
 
     /// Display verbose messages and progress information
@@ -29,13 +30,10 @@ pub struct Args {
     pub progress_keep_open: bool,
 
     /// Determine the format to use when outputting statistics.
-    #[clap(
-        long,
-        short = 'f',
-        default_value = "human",
-        possible_values(core::OutputFormat::variants())
-    )]
-    pub format: core::OutputFormat,
+    ///
+    /// Defaults to the value of `gitoxide.format` in the git configuration or `GITOXIDE_FORMAT` if set, or 'human'.
+    #[clap(long, short = 'f', possible_values(core::OutputFormat::variants()))]
+    pub format: Option<core::OutputFormat>,
 
     #[clap(subcommand)]
     pub cmd: Subcommands,
@@ -68,30 +66,63 @@ pub enum Subcommands {
         /// It's a form of instrumentation for developers to help improve pack generation.
         statistics: bool,
 
-        /// The directory into which to write the pack file.
+        /// The directory into which to write the pack file along with its index.
         #[clap(long, short = 'o')]
         output_directory: Option<PathBuf>,
 
+        /// A commit to exclude along with all of its ancestors, equivalent to a tip prefixed with '^'. Can be repeated.
+        #[clap(long, multiple_occurrences = true, number_of_values = 1)]
+        not: Vec<OsString>,
+
         /// the tips from which to start the commit graph iteration.
         ///
         /// If empty, we expect to read objects on stdin and default to 'none' as expansion mode.
         /// Otherwise the expansion mode is 'tree-traversal' by default.
+        ///
+        /// Tips prefixed with '^' are excluded along with all of their ancestors, and 'a..b' is the same as '^a b'.
+        /// 'a...b' includes the commits reachable from either 'a' or 'b' but not both, and 'a^!' only includes 'a'.
+        /// Objects reachable from excluded commits at the boundary of the range are never added to the pack.
         tips: Vec<OsString>,
     },
     #[clap(setting = AppSettings::ColoredHelp)]
     #[clap(setting = AppSettings::DisableVersion)]
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     PackReceive {
-        /// The protocol version to use. Valid values are 1 and 2
+        /// The protocol version to use. Valid values are 1 and 2, defaulting to `protocol.version` in the git configuration.
         #[clap(long, short = 'p')]
         protocol: Option<core::net::Protocol>,
 
+        /// Don't rewrite the url with the 'url.<base>.insteadOf' rules in the git configuration.
+        ///
+        /// If not set, the rewritten url is printed in verbose mode.
+        #[clap(long)]
+        no_rewrite: bool,
+
         /// the directory into which to write references. Existing files will be overwritten.
         ///
         /// Note that the directory will be created if needed.
         #[clap(long, short = 'r')]
         refs_directory: Option<PathBuf>,
 
+        /// An existing repository to receive the pack into, along with remote-tracking references and tags.
+        ///
+        /// Branches are stored as 'refs/remotes/<remote>/<branch>' and tags as they are, with reflog entries as needed.
+        #[clap(long, conflicts_with_all(&["refs-directory", "directory"]))]
+        into: Option<PathBuf>,
+
+        /// The name of the remote whose remote-tracking references to update when receiving into a repository.
+        ///
+        /// Defaults to 'origin'.
+        #[clap(long, requires("into"))]
+        remote: Option<String>,
+
+        /// A pattern of the references to receive, like 'refs/heads/main' or 'refs/heads/feature-*'. Can be repeated.
+        ///
+        /// Patterns without '*' or '?' match all references they are a prefix of, otherwise the whole reference name
+        /// must match. Defaults to 'HEAD', 'refs/heads' and 'refs/tags'.
+        #[clap(long = "ref", multiple_occurrences = true, number_of_values = 1)]
+        refs: Vec<String>,
+
         /// The URLs or path from which to receive the pack.
         ///
         /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
@@ -110,15 +141,68 @@ pub enum Subcommands {
     #[clap(setting = AppSettings::DisableVersion)]
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     RemoteRefList {
-        /// The protocol version to use. Valid values are 1 and 2
+        /// The protocol version to use. Valid values are 1 and 2, defaulting to `protocol.version` in the git configuration.
         #[clap(long, short = 'p')]
         protocol: Option<core::net::Protocol>,
 
+        /// Don't rewrite the url with the 'url.<base>.insteadOf' rules in the git configuration.
+        ///
+        /// If not set, the rewritten url is printed in verbose mode.
+        #[clap(long)]
+        no_rewrite: bool,
+
         /// the URLs or path from which to receive references
         ///
         /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
         url: String,
     },
+    /// Show how references on a remote would map to local references when fetching with the given refspecs.
+    ///
+    /// Use this to debug fetch configuration without actually fetching anything.
+    #[clap(setting = AppSettings::ColoredHelp)]
+    #[clap(setting = AppSettings::DisableVersion)]
+    #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+    RemoteRefMap {
+        /// The protocol version to use. Valid values are 1 and 2, defaulting to `protocol.version` in the git configuration.
+        #[clap(long, short = 'p')]
+        protocol: Option<core::net::Protocol>,
+
+        /// Don't rewrite the url with the 'url.<base>.insteadOf' rules in the git configuration.
+        ///
+        /// If not set, the rewritten url is printed in verbose mode.
+        #[clap(long)]
+        no_rewrite: bool,
+
+        /// A fetch refspec like '+refs/heads/*:refs/remotes/origin/*' to map remote references with. Can be repeated.
+        ///
+        /// Defaults to '+refs/heads/*:refs/remotes/origin/*'.
+        #[clap(long = "refspec", short = 's', multiple_occurrences = true, number_of_values = 1)]
+        refspecs: Vec<String>,
+
+        /// the URLs or path from which to receive references
+        ///
+        /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
+        url: String,
+    },
+    /// Serve repositories read-only via the git protocol, like `git daemon`, until interrupted.
+    ///
+    /// Only upload-pack is supported, allowing to clone and fetch using protocol V1 and V2.
+    #[clap(setting = AppSettings::ColoredHelp)]
+    #[clap(setting = AppSettings::DisableVersion)]
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    Daemon {
+        /// The directory containing the repositories to serve, which clients request relative to it.
+        #[clap(long)]
+        export_path: PathBuf,
+
+        /// Serve all repositories in the export path, not only those containing a 'git-daemon-export-ok' file.
+        #[clap(long)]
+        export_all: bool,
+
+        /// The address to listen on, like 'localhost:9418' or '0.0.0.0:9418'.
+        #[clap(long, default_value = core::daemon::DEFAULT_ADDRESS)]
+        listen: String,
+    },
     #[clap(setting = AppSettings::ColoredHelp)]
     #[clap(setting = AppSettings::DisableVersion)]
     PackIndexFromData {
@@ -143,6 +227,12 @@ pub enum Subcommands {
         #[clap(long, short = 'p')]
         pack_path: Option<PathBuf>,
 
+        /// Memory-map the pack file and index it in place, without streaming it through a temporary file.
+        ///
+        /// This requires '--pack-path' and is much faster on large packs, but can't be used with the 'restore' iteration mode.
+        #[clap(long, short = 'm')]
+        memory_map: bool,
+
         /// The folder into which to place the pack and the generated index file
         ///
         /// If unset, only informational output will be provided to standard output.
@@ -188,6 +278,18 @@ pub enum Subcommands {
         #[clap(parse(from_os_str))]
         object_path: Option<PathBuf>,
     },
+    /// Compare two packs and list objects only present in either of them, or stored differently in both.
+    #[clap(setting = AppSettings::ColoredHelp)]
+    #[clap(setting = AppSettings::DisableVersion)]
+    PackDiff {
+        /// The first '.pack' or '.idx' file to compare
+        #[clap(parse(from_os_str))]
+        a: PathBuf,
+
+        /// The second '.pack' or '.idx' file to compare
+        #[clap(parse(from_os_str))]
+        b: PathBuf,
+    },
     /// Verify the integrity of a pack or index file
     #[clap(setting = AppSettings::ColoredHelp)]
     #[clap(setting = AppSettings::DisableVersion)]
@@ -220,6 +322,16 @@ pub enum Subcommands {
         /// owned objects, causing plenty of allocation to occour.
         re_encode: bool,
 
+        /// Write the entry which failed verification to the given file, to verify only this entry with `--retry-from` later.
+        #[clap(long, parse(from_os_str))]
+        failure_report: Option<PathBuf>,
+
+        /// Verify only the entries listed in the failure report at the given path, for instance after a repair attempt.
+        ///
+        /// Only possible if a '.idx' file is given.
+        #[clap(long, parse(from_os_str))]
+        retry_from: Option<PathBuf>,
+
         /// The '.pack' or '.idx' file whose checksum to validate.
         #[clap(parse(from_os_str))]
         path: PathBuf,
@@ -1,5 +1,10 @@
+#[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+use crate::porcelain::options::RemoteCommands;
 use crate::{
-    porcelain::options::{Args, EstimateHours, Subcommands, ToolCommands},
+    porcelain::options::{
+        AnalyzeCommands, Args, BranchCommands, EstimateHours, FreeCommands, LockCommands, ReflogCommands, Subcommands,
+        TagCommands, ToolCommands,
+    },
     shared::pretty::prepare_and_run,
 };
 use anyhow::Result;
@@ -21,6 +26,8 @@ pub fn main() -> Result<()> {
     let verbose = !args.quiet;
     let progress = args.progress;
     let progress_keep_open = args.progress_keep_open;
+    let defaults = core::defaults::Defaults::from_environment()?;
+    let default_format = defaults.format.unwrap_or(core::OutputFormat::Human);
 
     match args.cmd {
         #[cfg(debug_assertions)]
@@ -33,6 +40,240 @@ pub fn main() -> Result<()> {
             move |_progress, _out, _err| panic!("something went very wrong"),
         ),
         Subcommands::Init { directory } => core::repository::init(directory),
+        Subcommands::Stats {
+            format,
+            biggest_objects,
+            repository,
+        } => prepare_and_run(
+            "stats",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |progress, out, _err| {
+                core::stats::statistics(
+                    repository.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                    DoOrDiscard::from(progress),
+                    core::stats::Context {
+                        biggest_objects,
+                        format: format.unwrap_or(default_format),
+                        out,
+                    },
+                )
+            },
+        ),
+        Subcommands::Verify {
+            format,
+            decode,
+            strict,
+            path,
+        } => prepare_and_run(
+            "verify",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |progress, out, _err| {
+                core::verify::integrity(
+                    path.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                    DoOrDiscard::from(progress),
+                    core::verify::Context {
+                        format: format.unwrap_or(default_format),
+                        out,
+                        thread_limit: defaults.threads,
+                        mode: if decode {
+                            core::pack::verify::Mode::Sha1Crc32Decode
+                        } else {
+                            core::pack::verify::Mode::Sha1Crc32
+                        },
+                        fail_on: if strict {
+                            core::verify::Severity::Warning
+                        } else {
+                            core::verify::Severity::Error
+                        },
+                        should_interrupt,
+                    },
+                )
+            },
+        )
+        .map(|_| ()),
+        Subcommands::Analyze(AnalyzeCommands::Largest {
+            format,
+            count,
+            repository,
+        }) => prepare_and_run(
+            "analyze-largest",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |progress, out, _err| {
+                core::analyze::largest(
+                    repository.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                    DoOrDiscard::from(progress),
+                    core::analyze::Context {
+                        count,
+                        format: format.unwrap_or(default_format),
+                        out,
+                    },
+                )
+            },
+        ),
+        Subcommands::Branch(BranchCommands::List { format, repository }) => prepare_and_run(
+            "branch-list",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::branch::list(
+                    repository.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                    core::branch::Context {
+                        format: format.unwrap_or(default_format),
+                        out,
+                    },
+                )
+            },
+        ),
+        Subcommands::Branch(BranchCommands::Create {
+            force,
+            repository,
+            name,
+            start_point,
+        }) => prepare_and_run(
+            "branch-create",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::branch::create(
+                    repository.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                    &name,
+                    start_point.as_deref(),
+                    force,
+                    out,
+                )
+            },
+        ),
+        Subcommands::Branch(BranchCommands::Delete {
+            force,
+            repository,
+            names,
+        }) => prepare_and_run(
+            "branch-delete",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::branch::delete(
+                    repository.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                    names,
+                    force,
+                    out,
+                )
+            },
+        ),
+        Subcommands::Tag(TagCommands::List {
+            format,
+            sort,
+            peel,
+            repository,
+            patterns,
+        }) => prepare_and_run(
+            "tag-list",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::tag::list(
+                    repository.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                    patterns,
+                    core::tag::Context {
+                        format: format.unwrap_or(default_format),
+                        sort,
+                        peel,
+                        out,
+                    },
+                )
+            },
+        ),
+        #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+        Subcommands::Remote(RemoteCommands::ObjectInfo {
+            format,
+            no_rewrite,
+            url,
+            ids,
+        }) => {
+            let url = crate::shared::rewrite_url(url, no_rewrite, verbose)?;
+            prepare_and_run(
+                "remote-object-info",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::remote::object_info::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::remote::object_info::query(
+                        &url,
+                        ids,
+                        DoOrDiscard::from(progress),
+                        core::remote::refs::Context {
+                            thread_limit: defaults.threads,
+                            format: format.unwrap_or(default_format),
+                            out,
+                        },
+                    )
+                },
+            )
+        }
+        Subcommands::Reflog(ReflogCommands::Expire {
+            expire,
+            expire_unreachable,
+            repository,
+            refs,
+        }) => prepare_and_run(
+            "reflog-expire",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::reflog::expire(
+                    repository.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                    refs,
+                    core::reflog::Context {
+                        expire_days: Some(expire.unwrap_or(core::reflog::DEFAULT_EXPIRE_DAYS)),
+                        expire_unreachable_days: Some(
+                            expire_unreachable.unwrap_or(core::reflog::DEFAULT_EXPIRE_UNREACHABLE_DAYS),
+                        ),
+                        out,
+                    },
+                )
+            },
+        ),
+        Subcommands::Free(FreeCommands::Lock(cmd)) => {
+            let (mode, directory) = match cmd {
+                LockCommands::List { directory } => (core::lock::Mode::List, directory),
+                LockCommands::Clean { directory } => (core::lock::Mode::Clean, directory),
+            };
+            prepare_and_run(
+                "free-lock",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, err| {
+                    core::lock::run(
+                        mode,
+                        directory.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                        out,
+                        err,
+                    )
+                },
+            )
+        }
         Subcommands::Tools(tool) => match tool {
             ToolCommands::EstimateHours(EstimateHours {
                 working_dir,
@@ -81,7 +322,9 @@ pub fn main() -> Result<()> {
             ToolCommands::Organize {
                 destination_directory,
                 execute,
+                mode,
                 repository_source,
+                format,
             } => {
                 use gitoxide_core::organize;
                 prepare_and_run(
@@ -90,15 +333,18 @@ pub fn main() -> Result<()> {
                     progress,
                     progress_keep_open,
                     crate::shared::STANDARD_RANGE,
-                    move |progress, _out, _err| {
+                    move |progress, out, _err| {
                         organize::run(
                             if execute {
                                 organize::Mode::Execute
                             } else {
                                 organize::Mode::Simulate
                             },
+                            mode,
                             repository_source.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
                             destination_directory.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
+                            format.unwrap_or(default_format),
+                            out,
                             DoOrDiscard::from(progress),
                         )
                     },
@@ -1,4 +1,6 @@
+use crate::shared::pretty::ProgressMode;
 use clap::{AppSettings, Clap};
+use gitoxide_core as core;
 use std::ffi::OsString;
 use std::path::PathBuf;
 
@@ -11,14 +13,18 @@ pub struct Args {
     #[clap(long, short = 'q')]
     pub quiet: bool,
 
-    /// Bring up a terminal user interface displaying progress visually
-    #[clap(long, conflicts_with("quiet"))]
-    pub progress: bool,
+    /// Determine how to display progress.
+    ///
+    /// 'auto' shows progress lines if stderr is a terminal and otherwise prints timestamped progress summaries
+    /// suitable for logs, 'line' forces these summaries, 'tui' brings up a terminal user interface displaying
+    /// progress visually, and 'off' disables progress entirely.
+    #[clap(long, default_value = "auto", possible_values(ProgressMode::variants()))]
+    pub progress: ProgressMode,
 
     /// The progress TUI will stay up even though the work is already completed.
     ///
     /// Use this to be able to read progress messages or additional information visible in the TUI log pane.
-    #[clap(long, conflicts_with("quiet"), requires("progress"))]
+    #[clap(long, conflicts_with("quiet"))]
     pub progress_keep_open: bool,
 
     #[clap(subcommand)]
@@ -40,10 +46,287 @@ pub enum Subcommands {
     #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion, setting = AppSettings::SubcommandRequired)]
     #[clap(visible_alias = "t")]
     Tools(ToolCommands),
+    /// Summarize the shape of a repository, like the amount and size of its objects, packs, references and reflogs.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    Stats {
+        /// Determine the format to use when outputting statistics.
+        ///
+        /// Defaults to the value of `gitoxide.format` in the git configuration or `GITOXIDE_FORMAT` if set, or 'human'.
+        #[clap(long, short = 'f', possible_values(core::OutputFormat::variants()))]
+        format: Option<core::OutputFormat>,
+
+        /// The amount of objects taking the most space on disk to list.
+        #[clap(long, short = 'b', default_value = "10")]
+        biggest_objects: usize,
+
+        /// The directory of the repository to summarize.
+        ///
+        /// Defaults to the current working directory.
+        #[clap(long, short = 'r')]
+        repository: Option<PathBuf>,
+    },
+    /// Verify the integrity of a repository, or of a single pack, pack index or commit-graph.
+    ///
+    /// What is checked depends on what is found at the given path. In repositories, all packs, the commit-graph
+    /// and all references are verified.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    Verify {
+        /// Determine the format to use when outputting the report.
+        ///
+        /// Defaults to the value of `gitoxide.format` in the git configuration or `GITOXIDE_FORMAT` if set, or 'human'.
+        #[clap(long, short = 'f', possible_values(core::OutputFormat::variants()))]
+        format: Option<core::OutputFormat>,
+
+        /// Decode and parse tags, commits and trees in packs to validate their correctness beyond hashing correctly.
+        ///
+        /// This will reduce overall performance.
+        #[clap(long)]
+        decode: bool,
+
+        /// Fail if there are warnings, not only if there are errors.
+        ///
+        /// Warnings are reported for data git tolerates but wouldn't produce itself, like trees with non-canonical
+        /// entry modes found with '--decode'.
+        #[clap(long)]
+        strict: bool,
+
+        /// The repository, '.pack', '.idx' or commit-graph file or directory to verify.
+        ///
+        /// Defaults to the current working directory.
+        path: Option<PathBuf>,
+    },
+    /// Find out what takes up space in a repository
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion, setting = AppSettings::SubcommandRequired)]
+    Analyze(AnalyzeCommands),
+    /// List, create and delete local branches
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion, setting = AppSettings::SubcommandRequired)]
+    Branch(BranchCommands),
+    /// List tags
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion, setting = AppSettings::SubcommandRequired)]
+    Tag(TagCommands),
+    /// Query remotes without fetching from them
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion, setting = AppSettings::SubcommandRequired)]
+    #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+    Remote(RemoteCommands),
+    /// Interact with reference logs
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion, setting = AppSettings::SubcommandRequired)]
+    Reflog(ReflogCommands),
+    /// Commands that operate on plain directories and don't require a repository
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion, setting = AppSettings::SubcommandRequired)]
+    Free(FreeCommands),
     #[cfg(debug_assertions)]
     Panic,
 }
 
+#[derive(Debug, Clap)]
+pub enum AnalyzeCommands {
+    /// List the largest blobs in all loose objects and packs along with a path at which each can be found.
+    ///
+    /// Paths are found in the trees of commits reachable from references, newer commits first.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    Largest {
+        /// Determine the format to use when outputting the blobs.
+        ///
+        /// Defaults to the value of `gitoxide.format` in the git configuration or `GITOXIDE_FORMAT` if set, or 'human'.
+        #[clap(long, short = 'f', possible_values(core::OutputFormat::variants()))]
+        format: Option<core::OutputFormat>,
+
+        /// The amount of blobs to list.
+        #[clap(long, short = 'n', default_value = "10")]
+        count: usize,
+
+        /// The directory of the repository to analyze.
+        ///
+        /// Defaults to the current working directory.
+        #[clap(long, short = 'r')]
+        repository: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clap)]
+pub enum BranchCommands {
+    /// List all local branches, marking the one HEAD points to and showing the branch each one tracks, if any.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    List {
+        /// Determine the format to use when outputting the branches.
+        ///
+        /// Defaults to the value of `gitoxide.format` in the git configuration or `GITOXIDE_FORMAT` if set, or 'human'.
+        #[clap(long, short = 'f', possible_values(core::OutputFormat::variants()))]
+        format: Option<core::OutputFormat>,
+
+        /// The directory of the repository whose branches to list.
+        ///
+        /// Defaults to the current working directory.
+        #[clap(long, short = 'r')]
+        repository: Option<PathBuf>,
+    },
+    /// Create a new branch pointing to the given start point, and record its creation in its reflog.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    Create {
+        /// Overwrite the branch if it exists already, unless HEAD points to it.
+        #[clap(long)]
+        force: bool,
+
+        /// The directory of the repository in which to create the branch.
+        ///
+        /// Defaults to the current working directory.
+        #[clap(long, short = 'r')]
+        repository: Option<PathBuf>,
+
+        /// The name of the branch to create, like 'feature'.
+        name: String,
+
+        /// The reference, like 'main' or 'v1.0', or full object id of the commit the branch should point to.
+        ///
+        /// Defaults to HEAD.
+        start_point: Option<String>,
+    },
+    /// Delete branches, which must be merged into their upstream branch, or HEAD if they have none.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    Delete {
+        /// Delete the branches even if they are not merged.
+        #[clap(long)]
+        force: bool,
+
+        /// The directory of the repository in which to delete branches.
+        ///
+        /// Defaults to the current working directory.
+        #[clap(long, short = 'r')]
+        repository: Option<PathBuf>,
+
+        /// The names of the branches to delete, like 'feature'.
+        #[clap(required = true)]
+        names: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clap)]
+pub enum TagCommands {
+    /// List all tags, loose and packed, whose name matches any of the given patterns.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    List {
+        /// Determine the format to use when outputting the tags.
+        ///
+        /// Defaults to the value of `gitoxide.format` in the git configuration or `GITOXIDE_FORMAT` if set, or 'human'.
+        #[clap(long, short = 'f', possible_values(core::OutputFormat::variants()))]
+        format: Option<core::OutputFormat>,
+
+        /// The order in which to list the tags, by name or by the date they were created, oldest first.
+        #[clap(
+            long,
+            short = 's',
+            default_value = "name",
+            possible_values(core::tag::Sort::variants())
+        )]
+        sort: core::tag::Sort,
+
+        /// Follow annotated tags to the object they point to, usually a commit, and show it as well.
+        #[clap(long)]
+        peel: bool,
+
+        /// The directory of the repository whose tags to list.
+        ///
+        /// Defaults to the current working directory.
+        #[clap(long, short = 'r')]
+        repository: Option<PathBuf>,
+
+        /// Patterns like 'v1.*' to match the tag names against, where '*' matches any amount of characters and '?' matches one.
+        ///
+        /// All tags are listed if unset.
+        patterns: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clap)]
+#[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+pub enum RemoteCommands {
+    /// Show the size of objects on a remote identified by a url without fetching them, or 'missing' if it doesn't have them.
+    ///
+    /// This uses the 'object-info' command of protocol version 2, which servers may not support or advertise.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    ObjectInfo {
+        /// Determine the format to use when outputting the object sizes.
+        ///
+        /// Defaults to the value of `gitoxide.format` in the git configuration or `GITOXIDE_FORMAT` if set, or 'human'.
+        #[clap(long, short = 'f', possible_values(core::OutputFormat::variants()))]
+        format: Option<core::OutputFormat>,
+
+        /// Don't rewrite the url with the 'url.<base>.insteadOf' rules in the git configuration.
+        ///
+        /// If not set, the rewritten url is printed in verbose mode.
+        #[clap(long)]
+        no_rewrite: bool,
+
+        /// The URL or path of the remote to query.
+        ///
+        /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
+        url: String,
+
+        /// The full hexadecimal ids of the objects whose size to show.
+        #[clap(required = true)]
+        ids: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clap)]
+pub enum ReflogCommands {
+    /// Remove old reflog entries, or entries that are not reachable from the tip of their reference anymore.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    Expire {
+        /// Remove all entries older than the given amount of days.
+        ///
+        /// Defaults to 90 days, like `gc.reflogExpire`.
+        #[clap(long)]
+        expire: Option<u32>,
+
+        /// Remove entries older than the given amount of days if they are not reachable from the tip of their reference.
+        ///
+        /// Defaults to 30 days, like `gc.reflogExpireUnreachable`.
+        #[clap(long)]
+        expire_unreachable: Option<u32>,
+
+        /// The directory of the repository whose reflogs to expire.
+        ///
+        /// Defaults to the current working directory.
+        #[clap(long, short = 'r')]
+        repository: Option<PathBuf>,
+
+        /// The references whose reflogs to expire, like 'HEAD' or 'refs/heads/main'.
+        ///
+        /// If unset, the reflogs of all references are expired.
+        refs: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clap)]
+pub enum FreeCommands {
+    /// Inspect and remove lock files, like the ones left behind by crashed processes
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion, setting = AppSettings::SubcommandRequired)]
+    Lock(LockCommands),
+}
+
+#[derive(Debug, Clap)]
+pub enum LockCommands {
+    /// List all lock files in a directory along with whether their owning process is alive, dead or unknown.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    List {
+        /// The directory in which to find lock files, like a '.git' directory.
+        ///
+        /// Defaults to the current working directory.
+        directory: Option<PathBuf>,
+    },
+    /// Remove all lock files in a directory whose owning process is known to be dead.
+    ///
+    /// Locks which don't record their owner, like the ones created by git, are never removed.
+    #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
+    Clean {
+        /// The directory in which to find lock files, like a '.git' directory.
+        ///
+        /// Defaults to the current working directory.
+        directory: Option<PathBuf>,
+    },
+}
+
 #[derive(Debug, Clap)]
 pub enum ToolCommands {
     /// Find all repositories in a given directory.
@@ -54,13 +337,17 @@ pub enum ToolCommands {
         /// Defaults to the current working directory.
         root: Option<PathBuf>,
     },
-    /// Move all repositories found in a directory into a structure matching their clone URLs.
+    /// Move or symlink all repositories found in a directory into a structure matching their clone URLs.
     #[clap(setting = AppSettings::ColoredHelp, setting = AppSettings::DisableVersion)]
     Organize {
         #[clap(long)]
         /// The operation will be in dry-run mode unless this flag is set.
         execute: bool,
 
+        /// Determine whether repositories are moved into place, or symlinked to leave them where they are.
+        #[clap(long, default_value = "move", possible_values(core::organize::Placement::variants()))]
+        mode: core::organize::Placement,
+
         #[clap(long, short = 'f')]
         /// The directory to use when finding input repositories to move into position.
         ///
@@ -72,6 +359,12 @@ pub enum ToolCommands {
         ///
         /// Defaults to the current working directory.
         destination_directory: Option<PathBuf>,
+
+        /// Determine the format to use when reporting on each repository, in addition to the progress messages.
+        ///
+        /// Defaults to the value of `gitoxide.format` in the git configuration or `GITOXIDE_FORMAT` if set, or 'human'.
+        #[clap(long, possible_values(core::OutputFormat::variants()))]
+        format: Option<core::OutputFormat>,
     },
     EstimateHours(EstimateHours),
 }
@@ -20,6 +20,21 @@ pub fn init_env_logger(verbose: bool) {
     }
 }
 
+/// Rewrite `url` for fetching according to the `url.<base>.insteadOf` configuration, unless `no_rewrite` is set.
+/// If `verbose` is true, rewrites are reported on stderr.
+#[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+pub fn rewrite_url(url: String, no_rewrite: bool, verbose: bool) -> anyhow::Result<String> {
+    use gitoxide_core::url_rewrite;
+    if no_rewrite {
+        return Ok(url);
+    }
+    url_rewrite::apply(url, url_rewrite::Direction::Fetch, |message| {
+        if verbose {
+            eprintln!("{}", message)
+        }
+    })
+}
+
 #[cfg(any(feature = "prodash-render-line-crossterm", feature = "prodash-render-line-termion"))]
 fn progress_tree() -> prodash::Tree {
     prodash::TreeOptions {
@@ -71,15 +86,54 @@ pub mod pretty {
     use std::{
         io::{stderr, stdout, Write},
         panic::UnwindSafe,
+        str::FromStr,
     };
 
+    /// The way progress is displayed while running a command.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    pub enum ProgressMode {
+        /// Use the line renderer if stderr is a terminal, or print progress summaries to it otherwise.
+        Auto,
+        /// Bring up a terminal user interface displaying progress visually.
+        Tui,
+        /// Print throttled and timestamped progress summaries line by line, suitable for logs.
+        Line,
+        /// Do not display any progress.
+        Off,
+    }
+
+    impl ProgressMode {
+        #[allow(unused)] // Only used by the porcelain CLI
+        pub fn variants() -> &'static [&'static str] {
+            &["auto", "tui", "line", "off"]
+        }
+    }
+
+    impl FromStr for ProgressMode {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(match s.to_ascii_lowercase().as_str() {
+                "auto" => ProgressMode::Auto,
+                "tui" => ProgressMode::Tui,
+                "line" => ProgressMode::Line,
+                "off" => ProgressMode::Off,
+                _ => return Err(format!("Invalid progress mode: '{}'", s)),
+            })
+        }
+    }
+
     pub fn prepare_and_run<T: Send + 'static>(
         name: &str,
         verbose: bool,
-        progress: bool,
+        progress: ProgressMode,
         progress_keep_open: bool,
         range: impl Into<Option<ProgressRange>>,
-        run: impl FnOnce(Option<prodash::tree::Item>, &mut dyn std::io::Write, &mut dyn std::io::Write) -> Result<T>
+        run: impl FnOnce(
+                Option<prodash::tree::Item>,
+                &mut (dyn std::io::Write + Send),
+                &mut (dyn std::io::Write + Send),
+            ) -> Result<T>
             + Send
             + UnwindSafe
             + 'static,
@@ -88,18 +142,28 @@ pub mod pretty {
         crate::shared::init_env_logger(false);
         use git_repository::interrupt;
 
-        match (verbose, progress) {
-            (false, false) => run(None, &mut stdout(), &mut stderr()),
-            (true, false) => {
+        let progress = match progress {
+            ProgressMode::Auto if !verbose => ProgressMode::Off,
+            ProgressMode::Auto if !atty::is(atty::Stream::Stderr) => ProgressMode::Line,
+            mode => mode,
+        };
+        match progress {
+            ProgressMode::Off => run(None, &mut stdout(), &mut stderr()),
+            ProgressMode::Auto | ProgressMode::Line => {
                 enum Event<T> {
                     UiDone,
                     ComputationFailed,
                     ComputationDone(Result<T>),
                 }
-                let progress = crate::shared::progress_tree();
-                let sub_progress = progress.add_child(name);
+                let progress_tree = crate::shared::progress_tree();
+                let sub_progress = progress_tree.add_child(name);
                 let (tx, rx) = std::sync::mpsc::sync_channel::<Event<T>>(1);
-                let ui_handle = shared::setup_line_renderer_range(progress, range.into().unwrap_or(STANDARD_RANGE));
+                let range = range.into().unwrap_or(STANDARD_RANGE);
+                let ui_handle = if progress == ProgressMode::Line {
+                    shared::log::setup_renderer(progress_tree, range, shared::log::DEFAULT_INTERVAL)
+                } else {
+                    shared::setup_line_renderer_range(progress_tree, range).into()
+                };
                 std::thread::spawn({
                     let tx = tx.clone();
                     move || loop {
@@ -141,7 +205,7 @@ pub mod pretty {
                     },
                 }
             }
-            (true, true) | (false, true) => {
+            ProgressMode::Tui => {
                 enum Event<T> {
                     UiDone,
                     ComputationDone(Result<T>, Vec<u8>),
@@ -195,6 +259,132 @@ pub mod pretty {
     }
 }
 
+/// A progress renderer for logs, printing throttled and timestamped summaries of the progress tree line by line.
+#[cfg(feature = "pretty-cli")]
+pub mod log {
+    use prodash::messages::MessageLevel;
+    use std::{
+        io::Write,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    /// The time between two progress summaries.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// A handle to either the log renderer or the prodash line renderer, both of which are shut down the same way.
+    pub enum JoinHandle {
+        Log {
+            should_stop: Arc<AtomicBool>,
+            thread: std::thread::JoinHandle<()>,
+        },
+        Line(prodash::render::line::JoinHandle),
+    }
+
+    impl From<prodash::render::line::JoinHandle> for JoinHandle {
+        fn from(handle: prodash::render::line::JoinHandle) -> Self {
+            JoinHandle::Line(handle)
+        }
+    }
+
+    impl JoinHandle {
+        /// Stop rendering after printing a final summary and wait for the renderer to finish.
+        pub fn shutdown_and_wait(self) {
+            match self {
+                JoinHandle::Log { should_stop, thread } => {
+                    should_stop.store(true, Ordering::SeqCst);
+                    thread.thread().unpark();
+                    thread.join().ok();
+                }
+                JoinHandle::Line(handle) => handle.shutdown_and_wait(),
+            }
+        }
+    }
+
+    /// Print a summary of all tasks within `levels` of the `progress` tree to stderr once per `interval` if it changed,
+    /// along with all messages as they arrive.
+    pub fn setup_renderer(
+        progress: prodash::Tree,
+        levels: std::ops::RangeInclusive<prodash::progress::key::Level>,
+        interval: Duration,
+    ) -> JoinHandle {
+        let should_stop = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::spawn({
+            let should_stop = Arc::clone(&should_stop);
+            move || {
+                let mut tasks = Vec::new();
+                let mut messages = Vec::new();
+                let mut message_state = None;
+                let mut last_summary = Vec::new();
+                let mut next_summary = Instant::now() + interval;
+                loop {
+                    let stop = should_stop.load(Ordering::SeqCst);
+                    let mut out = std::io::stderr();
+                    message_state = Some(progress.copy_new_messages(&mut messages, message_state));
+                    for message in &messages {
+                        writeln!(
+                            out,
+                            "[{}] {:>7} {}: {}",
+                            prodash::time::format_time_for_messages(message.time),
+                            match message.level {
+                                MessageLevel::Info => "info",
+                                MessageLevel::Failure => "failure",
+                                MessageLevel::Success => "done",
+                            },
+                            message.origin,
+                            message.message
+                        )
+                        .ok();
+                    }
+
+                    if stop || Instant::now() >= next_summary {
+                        progress.sorted_snapshot(&mut tasks);
+                        let summary = summarize(&tasks, &levels);
+                        if summary != last_summary {
+                            let now = prodash::time::format_time_for_messages(std::time::SystemTime::now());
+                            for line in &summary {
+                                writeln!(out, "[{}] {}", now, line).ok();
+                            }
+                            last_summary = summary;
+                        }
+                        next_summary = Instant::now() + interval;
+                    }
+                    if stop {
+                        break;
+                    }
+                    std::thread::park_timeout(Duration::from_millis(250));
+                }
+            }
+        });
+        JoinHandle::Log { should_stop, thread }
+    }
+
+    fn summarize(
+        tasks: &[(prodash::progress::Key, prodash::progress::Task)],
+        levels: &std::ops::RangeInclusive<prodash::progress::key::Level>,
+    ) -> Vec<String> {
+        tasks
+            .iter()
+            .filter(|(key, _)| levels.contains(&key.level()))
+            .filter_map(|(_, task)| {
+                task.progress.as_ref().map(|value| {
+                    let step = value.step;
+                    match value.unit.as_ref() {
+                        Some(unit) => format!("{}: {}", task.name, unit.display(step, value.done_at, None)),
+                        None => match value.done_at {
+                            Some(done_at) => format!("{}: {}/{}", task.name, step, done_at),
+                            None => format!("{}: {}", task.name, step),
+                        },
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
 #[allow(unused)]
 #[cfg(feature = "prodash-render-line")]
 pub fn setup_line_renderer_range(
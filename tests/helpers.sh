@@ -65,3 +65,12 @@ function launch-git-daemon() {
     trap 'kill $daemon_pid' EXIT
 }
 
+function launch-gixp-daemon() {
+    "$exe_plumbing" daemon --export-path . --listen localhost:9419 "$@" &>/dev/null &
+    daemon_pid=$!
+    while ! nc -z localhost 9419; do
+      sleep 0.1
+    done
+    trap 'kill $daemon_pid && wait $daemon_pid' EXIT
+}
+
@@ -23,7 +23,7 @@ title "Porcelain ${kind}"
       (with "progress option set"
         it "fails as expected" && {
           WITH_SNAPSHOT="$snapshot/expected-failure-in-thread-with-progress" \
-          expect_run_sh $WITH_FAILURE "$exe --progress panic"
+          expect_run_sh $WITH_FAILURE "$exe --progress tui panic"
         }
       )
     )
@@ -98,6 +98,13 @@ title "Porcelain ${kind}"
               }
             )
 
+            (with "--format json-lines"
+              it "succeeds and prints a record for each repository on its own line" && {
+                WITH_SNAPSHOT="$snapshot/no-args-json-lines-success" \
+                expect_run_sh $SUCCESSFULLY "$exe tools organize --format json-lines 2>/dev/null | sed -e 's|$(pwd -P)|\$PWD|g'"
+              }
+            )
+
             (with "--execute"
               it "succeeds" && {
                 WITH_SNAPSHOT="$snapshot/execute-success" \
@@ -130,6 +137,354 @@ title "Porcelain ${kind}"
           )
         )
       )
+      (with "repositories sharing the same remote URL"
+        (sandbox
+          repo-with-remotes first origin https://example.com/shared
+          repo-with-remotes second origin https://example.com/shared
+          snapshot="$snapshot/tools/organize"
+
+          (with "--format json-lines"
+            it "succeeds and suffixes the destination of the repository handled last" && {
+              WITH_SNAPSHOT="$snapshot/shared-url-json-lines-success" \
+              expect_run_sh $SUCCESSFULLY "$exe tools organize --format json-lines 2>/dev/null | sed -e 's|$(pwd -P)|\$PWD|g'"
+            }
+          )
+
+          (with "--mode symlink --execute"
+            it "succeeds" && {
+              WITH_SNAPSHOT="$snapshot/shared-url-symlink-success" \
+              expect_run_sh $SUCCESSFULLY "$exe tools organize --mode symlink --execute --format json-lines 2>/dev/null | sed -e 's|$(pwd -P)|\$PWD|g'"
+            }
+
+            it "leaves the repositories in place and symlinks each of them" && {
+              WITH_SNAPSHOT="$snapshot/shared-url-symlinks" \
+              expect_run_sh $SUCCESSFULLY "for link in example.com/*; do echo \"\$link -> \$(basename \"\$(readlink \"\$link\")\")\"; done"
+            }
+          )
+
+          (with "--mode symlink --execute again"
+            it "succeeds and skips the repositories as their symlinks are already in place" && {
+              WITH_SNAPSHOT="$snapshot/shared-url-symlink-again-success" \
+              expect_run_sh $SUCCESSFULLY "$exe tools organize --mode symlink --execute --format json-lines 2>/dev/null | sed -e 's|$(pwd -P)|\$PWD|g'"
+            }
+          )
+        )
+      )
+    )
+  )
+
+  title "gix stats"
+  (when "running 'stats'"
+    snapshot="$snapshot/stats"
+    (with "a repo with a tiny commit history"
+      (small-repo-in-sandbox
+        it "succeeds and summarizes objects, references and reflogs" && {
+          WITH_SNAPSHOT="$snapshot/success" \
+          expect_run $SUCCESSFULLY "$exe" --progress off stats --biggest-objects 3
+        }
+        if test "$kind" = "max"; then
+        (with "--format json"
+          it "succeeds and prints the statistics as JSON" && {
+            WITH_SNAPSHOT="$snapshot/success-json" \
+            expect_run $SUCCESSFULLY "$exe" --progress off stats --format json --biggest-objects 1
+          }
+        )
+        fi
+      )
+    )
+  )
+
+  title "gix verify"
+  (when "running 'verify'"
+    snapshot="$snapshot/verify"
+    (with "a repo with a tiny commit history"
+      (small-repo-in-sandbox
+        it "succeeds and reports all checks" && {
+          WITH_SNAPSHOT="$snapshot/success" \
+          expect_run $SUCCESSFULLY "$exe" --progress off verify
+        }
+        if test "$kind" = "max"; then
+        (with "--format json"
+          it "succeeds and prints the report as JSON" && {
+            WITH_SNAPSHOT="$snapshot/success-json" \
+            expect_run $SUCCESSFULLY "$exe" --progress off verify --format json
+          }
+        )
+        (with "--format json-lines"
+          it "succeeds and prints each check as JSON on its own line" && {
+            WITH_SNAPSHOT="$snapshot/success-json-lines" \
+            expect_run $SUCCESSFULLY "$exe" --progress off verify --format json-lines
+          }
+        )
+        (with "gitoxide.format set to json in the repository configuration"
+          git config gitoxide.format json
+          it "succeeds and prints the report as JSON" && {
+            WITH_SNAPSHOT="$snapshot/success-json" \
+            expect_run $SUCCESSFULLY "$exe" --progress off verify
+          }
+          git config --unset gitoxide.format
+        )
+        fi
+        (with "an invalid GITOXIDE_FORMAT environment variable"
+          it "fails with an error naming the offending value" && {
+            WITH_SNAPSHOT="$snapshot/failure-invalid-format-env" \
+            expect_run $WITH_FAILURE env GITOXIDE_FORMAT=yaml "$exe" --progress off verify
+          }
+        )
+        (with "HEAD pointing to a branch that doesn't exist yet"
+          git symbolic-ref HEAD refs/heads/unborn
+          it "succeeds and reports it as info, even with --strict" && {
+            WITH_SNAPSHOT="$snapshot/success-unborn-head-info" \
+            expect_run $SUCCESSFULLY "$exe" --progress off verify --strict
+          }
+          git symbolic-ref HEAD refs/heads/main
+        )
+        (with "references pointing to missing objects"
+          echo 0000000000000000000000000000000000000000 > .git/refs/heads/broken
+          echo 1111111111111111111111111111111111111111 > .git/refs/tags/broken
+          it "fails and reports each broken reference" && {
+            WITH_SNAPSHOT="$snapshot/failure-missing-object" \
+            expect_run $WITH_FAILURE "$exe" --progress off verify
+          }
+        )
+      )
+    )
+    (with "a packed tree with non-canonical entry modes"
+      (small-repo-in-sandbox
+        {
+          tree=$(printf "100664 blob %s\tfile\n" "$(git rev-parse HEAD:a)" | git mktree)
+          git branch non-canonical "$(git commit-tree -m "non-canonical" "$tree")"
+          git repack -adq && git prune-packed
+          (cd .git/objects/pack && for f in pack-*; do mv "$f" "pack-non-canonical.${f##*.}"; done)
+        } &>/dev/null
+        (with "--decode"
+          it "succeeds and reports a warning" && {
+            WITH_SNAPSHOT="$snapshot/success-non-canonical-tree-warning" \
+            expect_run $SUCCESSFULLY "$exe" --progress off verify --decode
+          }
+        )
+        (with "--decode --strict"
+          it "fails due to the warning" && {
+            WITH_SNAPSHOT="$snapshot/failure-non-canonical-tree-strict" \
+            expect_run $WITH_FAILURE "$exe" --progress off verify --decode --strict
+          }
+        )
+      )
+    )
+  )
+
+  title "gix analyze largest"
+  (when "running 'analyze largest'"
+    snapshot="$snapshot/analyze-largest"
+    (with "a repo with a tiny commit history"
+      (small-repo-in-sandbox
+        it "succeeds and lists the largest blobs with a path" && {
+          WITH_SNAPSHOT="$snapshot/success" \
+          expect_run $SUCCESSFULLY "$exe" --progress off analyze largest -n 3
+        }
+        if test "$kind" = "max"; then
+        (with "--format json"
+          it "succeeds and prints the blobs as JSON" && {
+            WITH_SNAPSHOT="$snapshot/success-json" \
+            expect_run $SUCCESSFULLY "$exe" --progress off analyze largest --format json -n 1
+          }
+        )
+        fi
+      )
+    )
+  )
+
+  title "gix branch"
+  (when "running 'branch'"
+    snapshot="$snapshot/branch"
+    (with "a repo with a tiny commit history"
+      (small-repo-in-sandbox
+        it "lists all local branches, marking the one HEAD points to" && {
+          WITH_SNAPSHOT="$snapshot/list-success" \
+          expect_run $SUCCESSFULLY "$exe" --progress off branch list
+        }
+        (with "a new branch created from an annotated tag"
+          it "succeeds and points it to the commit of the tag" && {
+            WITH_SNAPSHOT="$snapshot/create-success" \
+            expect_run $SUCCESSFULLY "$exe" --progress off branch create feature annotated
+          }
+          it "records its creation in the reflog" && {
+            WITH_SNAPSHOT="$snapshot/create-reflog" \
+            expect_run $SUCCESSFULLY git reflog show feature
+          }
+          it "fails to create it again without --force" && {
+            WITH_SNAPSHOT="$snapshot/create-existing-failure" \
+            expect_run $WITH_FAILURE "$exe" --progress off branch create feature
+          }
+          (with "--force"
+            it "succeeds and moves it to HEAD" && {
+              WITH_SNAPSHOT="$snapshot/create-force-success" \
+              expect_run $SUCCESSFULLY "$exe" --progress off branch create --force feature
+            }
+            it "refuses to move the branch HEAD points to" && {
+              WITH_SNAPSHOT="$snapshot/create-force-head-failure" \
+              expect_run $WITH_FAILURE "$exe" --progress off branch create --force main dev
+            }
+          )
+          (with "an upstream branch configured"
+            git config branch.feature.remote .
+            git config branch.feature.merge refs/heads/dev
+            it "lists the upstream along with the branch" && {
+              WITH_SNAPSHOT="$snapshot/list-with-upstream-success" \
+              expect_run $SUCCESSFULLY "$exe" --progress off branch list
+            }
+            it "refuses to delete it as it isn't merged into its upstream" && {
+              WITH_SNAPSHOT="$snapshot/delete-unmerged-upstream-failure" \
+              expect_run $WITH_FAILURE "$exe" --progress off branch delete feature
+            }
+          )
+        )
+        (with "a branch that is merged into HEAD"
+          it "deletes it" && {
+            WITH_SNAPSHOT="$snapshot/delete-success" \
+            expect_run $SUCCESSFULLY "$exe" --progress off branch delete dev
+          }
+        )
+        (with "a branch that isn't merged into HEAD"
+          {
+            git checkout -b side
+            git commit --allow-empty -m "side"
+            git checkout main
+          } &>/dev/null
+          it "refuses to delete it" && {
+            WITH_SNAPSHOT="$snapshot/delete-unmerged-failure" \
+            expect_run $WITH_FAILURE "$exe" --progress off branch delete side
+          }
+          (with "--force"
+            it "deletes it anyway" && {
+              WITH_SNAPSHOT="$snapshot/delete-force-success" \
+              expect_run $SUCCESSFULLY "$exe" --progress off branch delete --force side
+            }
+          )
+        )
+        (with "the branch HEAD points to"
+          it "refuses to delete it" && {
+            WITH_SNAPSHOT="$snapshot/delete-head-failure" \
+            expect_run $WITH_FAILURE "$exe" --progress off branch delete --force main
+          }
+        )
+        if test "$kind" = "max"; then
+        (with "--format json"
+          it "succeeds and prints the branches as JSON" && {
+            WITH_SNAPSHOT="$snapshot/list-success-json" \
+            expect_run $SUCCESSFULLY "$exe" --progress off branch list --format json
+          }
+        )
+        fi
+        (with "a branch that is packed and loose at the same time"
+          {
+            git branch packed-and-loose
+            git pack-refs --all
+            git update-ref refs/heads/packed-and-loose HEAD~1
+          } &>/dev/null
+          it "refuses to delete it as the packed branch would remain" && {
+            WITH_SNAPSHOT="$snapshot/delete-packed-and-loose-failure" \
+            expect_run $WITH_FAILURE "$exe" --progress off branch delete --force packed-and-loose
+          }
+          it "lists the loose branch, which takes precedence" && {
+            WITH_SNAPSHOT="$snapshot/list-packed-and-loose-success" \
+            expect_run $SUCCESSFULLY "$exe" --progress off branch list
+          }
+        )
+      )
+    )
+  )
+
+  title "gix tag"
+  (when "running 'tag list'"
+    snapshot="$snapshot/tag"
+    (with "a repo with an annotated and a lightweight tag"
+      (small-repo-in-sandbox
+        {
+          git pack-refs --all
+          git tag loose-copy annotated
+          GIT_COMMITTER_DATE="2030-01-01 00:00:00 +0000" git tag a-newest -m "newest tag"
+        } &>/dev/null
+        it "lists loose and packed tags sorted by name" && {
+          WITH_SNAPSHOT="$snapshot/list-success" \
+          expect_run $SUCCESSFULLY "$exe" --progress off tag list
+        }
+        (with "--sort date"
+          it "lists tags oldest first" && {
+            WITH_SNAPSHOT="$snapshot/list-sort-date-success" \
+            expect_run $SUCCESSFULLY "$exe" --progress off tag list --sort date
+          }
+        )
+        (with "--peel"
+          it "shows the objects annotated tags point to" && {
+            WITH_SNAPSHOT="$snapshot/list-peel-success" \
+            expect_run $SUCCESSFULLY "$exe" --progress off tag list --peel
+          }
+        )
+        (with "patterns"
+          it "lists only tags matching any of them" && {
+            WITH_SNAPSHOT="$snapshot/list-patterns-success" \
+            expect_run $SUCCESSFULLY "$exe" --progress off tag list 'un*' 'loose-?opy'
+          }
+        )
+        if test "$kind" = "max"; then
+        (with "--format json"
+          it "succeeds and prints the tags as JSON" && {
+            WITH_SNAPSHOT="$snapshot/list-success-json" \
+            expect_run $SUCCESSFULLY "$exe" --progress off tag list --format json --peel --sort date
+          }
+        )
+        fi
+      )
+    )
+  )
+
+  if [[ "$kind" != "small" ]]; then
+  title "gix remote object-info"
+  (when "running 'remote object-info'"
+    snapshot="$snapshot/remote/object-info"
+    (with "a repo with a tiny commit history served by git daemon"
+      (small-repo-in-sandbox
+        git config transfer.advertiseObjectInfo true
+        launch-git-daemon
+        it "shows the size of existing objects and marks missing ones" && {
+          WITH_SNAPSHOT="$snapshot/success" \
+          expect_run $SUCCESSFULLY "$exe" --progress off remote object-info git://localhost/ 45b983be36b73c0788dc9cbcb76cbb80fc7bb057 0000000000000000000000000000000000000001
+        }
+        (with "an invalid object id"
+          it "fails with an error" && {
+            WITH_SNAPSHOT="$snapshot/fail-invalid-id" \
+            expect_run $WITH_FAILURE "$exe" --progress off remote object-info git://localhost/ 45b983
+          }
+        )
+        if test "$kind" = "max"; then
+        (with "--format json"
+          it "succeeds and prints the sizes as JSON" && {
+            WITH_SNAPSHOT="$snapshot/success-json" \
+            expect_run $SUCCESSFULLY "$exe" --progress off remote object-info --format json git://localhost/ 45b983be36b73c0788dc9cbcb76cbb80fc7bb057 0000000000000000000000000000000000000001
+          }
+        )
+        fi
+      )
+    )
+  )
+  fi
+
+  title "gix free lock"
+  (when "running 'free lock'"
+    snapshot="$snapshot/free-lock"
+    (with "a directory with lock files which don't record their owner"
+      (sandbox
+        mkdir -p refs/heads && echo content > refs/heads/main.lock && touch index.lock
+        it "lists them with unknown status" && {
+          WITH_SNAPSHOT="$snapshot/list-success" \
+          expect_run $SUCCESSFULLY "$exe" free lock list
+        }
+        it "doesn't remove them when cleaning" && {
+          WITH_SNAPSHOT="$snapshot/clean-success" \
+          expect_run $SUCCESSFULLY "$exe" free lock clean
+        }
+      )
     )
   )
 
@@ -54,6 +54,12 @@ title "gixp pack-receive"
             expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 1 .git
           }
         )
+        (with "--ref patterns"
+          it "receives only the matching references" && {
+            WITH_SNAPSHOT="$snapshot/file-v-any-no-output-with-ref-patterns" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 1 --ref 'refs/heads/d*' --ref refs/tags/annotated .git
+          }
+        )
         (with "output directory"
           mkdir out
           it "generates the correct output" && {
@@ -75,6 +81,39 @@ title "gixp pack-receive"
           )
           rm -Rf out
         )
+        (with "--into an existing repository"
+          git init -q into
+          it "generates the correct output" && {
+            WITH_SNAPSHOT="$snapshot/file-v-any-into-repository" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 1 --into into --remote upstream .git
+          }
+          it "writes remote-tracking references and tags" && {
+            expect_snapshot "$snapshot/into-repository-refs" into/.git/refs
+          }
+          (with "a new commit and a moved tag on the remote"
+            {
+              echo more >> b
+              git commit -qam "fourth"
+              git tag -f unannotated
+            } &>/dev/null
+            it "generates the correct output and rejects the moved tag" && {
+              WITH_SNAPSHOT="$snapshot/file-v-any-into-repository-again" \
+              expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 1 --into into --remote upstream .git
+            }
+            it "receives only the objects the repository doesn't have yet" && {
+              WITH_SNAPSHOT="$snapshot/into-repository-objects-per-pack" \
+              expect_run_sh $SUCCESSFULLY 'for idx in into/.git/objects/pack/*.idx; do git show-index < "$idx" | wc -l | tr -d " "; done'
+            }
+            it "updates remote-tracking references but keeps the existing tag" && {
+              expect_snapshot "$snapshot/into-repository-refs-again" into/.git/refs
+            }
+            {
+              git reset --hard HEAD~1
+              git tag -f unannotated HEAD~2
+            } &>/dev/null
+          )
+          rm -Rf into
+        )
         if test "$kind" = "max"; then
         (with "--format json"
           it "generates the correct output in JSON format" && {
@@ -91,6 +130,12 @@ title "gixp pack-receive"
             expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 2 .git
           }
         )
+        (with "--ref patterns"
+          it "receives only the matching references" && {
+            WITH_SNAPSHOT="$snapshot/file-v-any-no-output-with-ref-patterns" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 2 --ref 'refs/heads/d*' --ref refs/tags/annotated .git
+          }
+        )
         (with "output directory"
           mkdir out/
           it "generates the correct output" && {
@@ -110,6 +155,12 @@ title "gixp pack-receive"
             expect_run $SUCCESSFULLY "$exe_plumbing" --format json pack-receive --protocol 2 .git
           }
         )
+        (with "--format json-lines"
+          it "generates a line of JSON for each reference and the pack" && {
+            WITH_SNAPSHOT="$snapshot/file-v-any-no-output-json-lines" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" --format json-lines pack-receive --protocol 2 .git
+          }
+        )
         fi
       )
     )
@@ -191,6 +242,14 @@ title "gixp remote-ref-list"
           expect_run $SUCCESSFULLY "$exe_plumbing" remote-ref-list --protocol 2 "$PWD/.git"
         }
       )
+      (with "a url rewritten by url.<base>.insteadOf"
+        git config url."$PWD/.git".insteadOf "alias:"
+        it "generates the correct output" && {
+          WITH_SNAPSHOT="$snapshot/file-v-any" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" remote-ref-list --protocol 2 alias:
+        }
+        git config --unset url."$PWD/.git".insteadOf
+      )
       if test "$kind" = "max"; then
       (with "--format json"
         it "generates the correct output in JSON format" && {
@@ -250,6 +309,118 @@ title "gixp remote-ref-list"
   )
 )
 
+title "gixp remote-ref-map"
+(when "running 'remote-ref-map'"
+  snapshot="$snapshot/remote-ref-map"
+  (small-repo-in-sandbox
+    if [[ "$kind" != "small" ]]; then
+
+    if [[ "$kind" != "async" ]]; then
+    (with "file:// protocol"
+      (with "the default refspec"
+        (with "version 1"
+          it "maps all branches to remote tracking branches" && {
+            WITH_SNAPSHOT="$snapshot/file-v-any" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" remote-ref-map -p 1 .git
+          }
+        )
+        (with "version 2"
+          it "maps all branches to remote tracking branches" && {
+            WITH_SNAPSHOT="$snapshot/file-v-any" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" remote-ref-map --protocol 2 "$PWD/.git"
+          }
+        )
+      )
+      (with "multiple refspecs"
+        it "maps matching references according to each refspec" && {
+          WITH_SNAPSHOT="$snapshot/file-v-any-with-refspecs" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" remote-ref-map -s 'refs/tags/*:refs/tags/*' --refspec refs/heads/main .git
+        }
+      )
+      if test "$kind" = "max"; then
+      (with "--format json"
+        it "generates the correct output in JSON format" && {
+          WITH_SNAPSHOT="$snapshot/file-v-any-json" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --format json remote-ref-map .git
+        }
+      )
+      fi
+    )
+    fi
+    fi
+  )
+)
+
+title "gixp daemon"
+(when "running 'daemon'"
+  snapshot="$snapshot/daemon"
+  (small-repo-in-sandbox
+    if [[ "$kind" = "max" ]]; then
+    (with "--export-all"
+      launch-gixp-daemon --export-all
+      (with "version 1"
+        it "lists all references" && {
+          WITH_SNAPSHOT="$snapshot/remote-ref-list" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" remote-ref-list -p 1 git://localhost:9419/
+        }
+        it "sends a pack with all objects" && {
+          WITH_SNAPSHOT="$snapshot/pack-receive" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 1 git://localhost:9419/
+        }
+      )
+      (with "version 2"
+        it "lists all references" && {
+          WITH_SNAPSHOT="$snapshot/remote-ref-list" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" remote-ref-list -p 2 git://localhost:9419/
+        }
+        it "sends a pack with all objects" && {
+          WITH_SNAPSHOT="$snapshot/pack-receive" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 2 git://localhost:9419/
+        }
+      )
+      (with "native git as client"
+        for version in 1 2; do
+          it "can clone with protocol version $version and the result passes fsck" && {
+            expect_run $SUCCESSFULLY git -c protocol.version=$version clone -q git://localhost:9419/ "native-clone-v$version" \
+              && expect_run $SUCCESSFULLY git -C "native-clone-v$version" fsck --strict --no-dangling
+          }
+        done
+      )
+    )
+    (with "a repository without 'git-daemon-export-ok' file"
+      launch-gixp-daemon
+      it "denies access" && {
+        WITH_SNAPSHOT="$snapshot/remote-ref-list-not-exported-failure" \
+        expect_run $WITH_FAILURE "$exe_plumbing" remote-ref-list -p 2 git://localhost:9419/
+      }
+    )
+    fi
+  )
+)
+
+title "gixp pack-create"
+(when "running 'pack-create'"
+  snapshot="$snapshot/pack-create"
+  (small-repo-in-sandbox
+    for expansion in tree-traversal tree-diff; do
+      (with "'$expansion' expansion"
+        (with "a range of commits"
+          it "only adds objects not reachable from the excluded commit" && {
+            WITH_SNAPSHOT="$snapshot/$expansion-range-statistics" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" pack-create -r . -e $expansion --statistics "$(git rev-parse HEAD~1)..$(git rev-parse HEAD)"
+          }
+        )
+        (with "--not"
+          it "only adds objects not reachable from the excluded commit" && {
+            WITH_SNAPSHOT="$snapshot/$expansion-range-statistics" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" pack-create -r . -e $expansion --statistics --not "$(git rev-parse HEAD~1)" "$(git rev-parse HEAD)"
+          }
+        )
+      )
+    done
+  )
+)
+
 title "gixp pack-index-from-data"
 (when "running 'pack-index-from-data"
   snapshot="$snapshot/pack-index-from-data"
@@ -407,6 +578,25 @@ title "gixp pack-explode"
   )
 )
 
+title "gixp pack-diff"
+(when "running 'pack-diff"
+  snapshot="$snapshot/pack-diff"
+  PACK_FILE="$fixtures/packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2"
+  (with "the same pack given by its index and data file"
+    it "finds all objects unchanged" && {
+      WITH_SNAPSHOT="$snapshot/same-pack-success" \
+      expect_run $SUCCESSFULLY "$exe_plumbing" pack-diff "${PACK_FILE}.idx" "${PACK_FILE}.pack"
+    }
+  )
+  (with "two different packs"
+    OTHER_PACK_FILE="$fixtures/packs/pack-c0438c19fb16422b6bbcce24387b3264416d485b"
+    it "lists the objects only present in either pack" && {
+      WITH_SNAPSHOT="$snapshot/different-packs-success" \
+      expect_run $SUCCESSFULLY "$exe_plumbing" pack-diff "${PACK_FILE}.idx" "${OTHER_PACK_FILE}.idx"
+    }
+  )
+)
+
 title "gixp pack-verify"
 (when "running 'pack-verify"
   snapshot="$snapshot/pack-verify"